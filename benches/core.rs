@@ -0,0 +1,118 @@
+//! Benchmarks for the core hot paths: the placement density, sampling a
+//! target from it, resolving guesses and bitboard operations.
+//!
+//! Run with `cargo bench`; add `--features perf` to also print how much
+//! work one density takes, as counted by `battleship::perf`.
+
+use std::hint::black_box;
+
+use battleship::{
+    calc_pdf, calc_pdf_fixed, perf, sample_pdf, Board, BoardBits, Coord, Orientation, PlacementStrategy, UniformRandom,
+    BOARD_SIZE,
+};
+use criterion::{criterion_group, BatchSize, Criterion, Throughput};
+use rand::rngs::SmallRng;
+use rand::{Rng, SeedableRng};
+
+type BB = BoardBits;
+const N: usize = BOARD_SIZE as usize;
+const FLEET: [usize; 5] = [5, 4, 3, 3, 2];
+
+/// A board with a seeded fleet on it.
+fn fleet_board(seed: u64) -> Board {
+    let mut board = Board::new();
+    UniformRandom.place_fleet(&mut SmallRng::seed_from_u64(seed), &mut board).unwrap();
+    board
+}
+
+/// Hits and misses after 30 seeded random shots at a seeded fleet, with a
+/// hit or two left to chase.
+fn mid_game() -> (BB, BB) {
+    let mut board = fleet_board(7);
+    let mut rng = SmallRng::seed_from_u64(7);
+    let mut fired = 0;
+    while fired < 30 {
+        let target = Coord::new(rng.random_range(0..N), rng.random_range(0..N));
+        if board.guess(target).is_ok() {
+            fired += 1;
+        }
+    }
+    (board.hits(), board.misses())
+}
+
+fn bench_density(c: &mut Criterion) {
+    let (hits, misses) = mid_game();
+    let mut group = c.benchmark_group("density");
+    group.bench_function("calc_pdf/empty", |b| b.iter(|| calc_pdf(black_box(&BB::new()), &BB::new(), &FLEET)));
+    group.bench_function("calc_pdf/mid_game", |b| b.iter(|| calc_pdf(black_box(&hits), &misses, &FLEET)));
+    group.bench_function("calc_pdf_fixed/empty", |b| {
+        b.iter(|| calc_pdf_fixed(black_box(&BB::new()), &BB::new(), &FLEET))
+    });
+    group.bench_function("calc_pdf_fixed/mid_game", |b| b.iter(|| calc_pdf_fixed(black_box(&hits), &misses, &FLEET)));
+    group.finish();
+}
+
+fn bench_sampling(c: &mut Criterion) {
+    let pdf = calc_pdf(&BB::new(), &BB::new(), &FLEET);
+    let mut rng = SmallRng::seed_from_u64(1);
+    c.bench_function("sample_pdf", |b| b.iter(|| sample_pdf(black_box(&pdf), 0.5, &mut rng)));
+}
+
+fn bench_guess(c: &mut Criterion) {
+    let cells: Vec<Coord> = (0..N).flat_map(|r| (0..N).map(move |c| Coord::new(r, c))).collect();
+    let mut group = c.benchmark_group("board");
+    group.throughput(Throughput::Elements(cells.len() as u64));
+    group.bench_function("guess/every_cell", |b| {
+        b.iter_batched(
+            || fleet_board(3),
+            |mut board| {
+                for &cell in &cells {
+                    black_box(board.guess(cell).unwrap());
+                }
+                board
+            },
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn bench_bitboard(c: &mut Criterion) {
+    let (hits, misses) = mid_game();
+    let shots = hits | misses;
+    let mut group = c.benchmark_group("bitboard");
+    group.bench_function("count_ones", |b| b.iter(|| black_box(shots).count_ones()));
+    group.bench_function("iter_set_bits", |b| b.iter(|| black_box(shots).iter_set_bits().count()));
+    group.bench_function("shift_left", |b| b.iter(|| black_box(shots).shift_left(1)));
+    group.bench_function("shift_up", |b| b.iter(|| black_box(shots).shift_up(1)));
+    group.bench_function("dilate", |b| b.iter(|| black_box(hits).dilate(true)));
+    group.bench_function("run_starts", |b| {
+        b.iter(|| (!black_box(misses)).run_starts(5, Orientation::Horizontal))
+    });
+    group.bench_function("clusters", |b| b.iter(|| black_box(hits).clusters().count()));
+    group.finish();
+}
+
+criterion_group!(benches, bench_density, bench_sampling, bench_guess, bench_bitboard);
+
+/// Work one mid-game density takes, when counted.
+fn report_work() {
+    if !cfg!(feature = "perf") {
+        return;
+    }
+    let (hits, misses) = mid_game();
+    let before = perf::snapshot();
+    black_box(calc_pdf(&hits, &misses, &FLEET));
+    let work = perf::snapshot().since(&before);
+    println!(
+        "calc_pdf/mid_game: {} density, {} placements",
+        work.get(perf::Counter::Densities),
+        work.get(perf::Counter::Placements)
+    );
+}
+
+fn main() {
+    report_work();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
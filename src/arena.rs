@@ -0,0 +1,153 @@
+//! Self-play between two players, for weighing strategies against each
+//! other without transports.
+//!
+//! Each game of [`run_match`] lays out both fleets and lets the players
+//! fire in turn, on the engines directly, until each has sunk the other's
+//! fleet (or resigned). Playing both fleets out gives every game a shot
+//! count for each side; the side needing fewer shots wins, and the one that
+//! moved first wins a tie since its last shot would have landed first. The
+//! first move alternates between games.
+
+#![cfg(feature = "std")]
+
+use crate::core::{coord::Coord, game::GameEngine, game::GameStatus};
+use crate::player::Player;
+use anyhow::{anyhow, Context};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+/// Standard normal quantile for the 95% intervals of [`MatchReport`].
+const Z_95: f64 = 1.959_963_984_540_054;
+
+/// One game of a match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameRecord {
+    /// Shots each side fired, `[a, b]`.
+    pub shots: [usize; 2],
+    /// Whether each side sank the other's fleet, rather than resigning.
+    pub sank: [bool; 2],
+    /// The side that fired first: 0 for `a`, 1 for `b`.
+    pub first: usize,
+    /// The side that won.
+    pub winner: usize,
+}
+
+/// Outcome of [`run_match`], side 0 being `a` and side 1 `b`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MatchReport {
+    /// Every game, in the order played.
+    pub games: Vec<GameRecord>,
+}
+
+impl MatchReport {
+    /// Games `side` won.
+    pub fn wins(&self, side: usize) -> usize {
+        self.games.iter().filter(|g| g.winner == side).count()
+    }
+
+    /// Share of the games `side` won; zero for an empty match.
+    pub fn win_rate(&self, side: usize) -> f64 {
+        if self.games.is_empty() {
+            return 0.0;
+        }
+        self.wins(side) as f64 / self.games.len() as f64
+    }
+
+    /// 95% Wilson score interval for the win rate of `side`, which stays
+    /// within `0..=1` and is sound for few games or lopsided results.
+    pub fn win_rate_interval(&self, side: usize) -> (f64, f64) {
+        let n = self.games.len() as f64;
+        if n == 0.0 {
+            return (0.0, 1.0);
+        }
+        let p = self.win_rate(side);
+        let z2 = Z_95 * Z_95;
+        let centre = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let spread = Z_95 / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+        ((centre - spread).max(0.0), (centre + spread).min(1.0))
+    }
+
+    /// Mean shots `side` needed to sink the other fleet, over the games it
+    /// did; `None` if it never did.
+    pub fn average_shots(&self, side: usize) -> Option<f64> {
+        let sunk: Vec<usize> = self.games.iter().filter(|g| g.sank[side]).map(|g| g.shots[side]).collect();
+        (!sunk.is_empty()).then(|| sunk.iter().sum::<usize>() as f64 / sunk.len() as f64)
+    }
+}
+
+/// Play `games` games of `a` against `b`, reusing both players so that
+/// anything they learn carries over, as in a series.
+///
+/// Game `g` seeds each side's generator from `seed` and `g` alone, so a
+/// match is reproducible and its games independent of one another. Fails if
+/// a player cannot lay out its fleet or fires at a cell it already shot.
+pub fn run_match(a: &mut dyn Player, b: &mut dyn Player, games: u32, seed: u64) -> anyhow::Result<MatchReport> {
+    let mut players: [&mut dyn Player; 2] = [a, b];
+    let mut report = MatchReport::default();
+    for game in 0..games {
+        let record = play(&mut players, game, seed).with_context(|| format!("Game {}", game + 1))?;
+        report.games.push(record);
+    }
+    Ok(report)
+}
+
+/// Play one game of a match.
+fn play(players: &mut [&mut dyn Player; 2], game: u32, seed: u64) -> anyhow::Result<GameRecord> {
+    let base = seed.wrapping_add(2 * game as u64);
+    let mut rngs = [SmallRng::seed_from_u64(base), SmallRng::seed_from_u64(base.wrapping_add(1))];
+    // Each side's fleet, and its record of the shots it fired; kept apart so
+    // a side can play on once its own fleet is sunk
+    let mut fleets = [GameEngine::new(), GameEngine::new()];
+    let mut trackers = [GameEngine::new(), GameEngine::new()];
+    for side in 0..2 {
+        players[side]
+            .place_ships(&mut rngs[side], fleets[side].board_mut())
+            .map_err(|e| anyhow!("Side {} placement: {}", side, e))?;
+    }
+
+    let first = game as usize % 2;
+    let mut shots = [0; 2];
+    let mut sank = [false; 2];
+    let mut resigned = Vec::new();
+    let mut shooter = first;
+    // Every shot lands on a new cell, so both sides finish
+    while (0..2).any(|side| !sank[side] && !resigned.contains(&side)) {
+        let defender = 1 - shooter;
+        if !sank[shooter] && !resigned.contains(&shooter) {
+            let tracker = &trackers[shooter];
+            let target = players[shooter].select_target(
+                &mut rngs[shooter],
+                &tracker.guess_hits(),
+                &tracker.blocked_cells(),
+                &tracker.enemy_ship_lengths_remaining(),
+            );
+            if players[shooter].resigned() {
+                resigned.push(shooter);
+            } else {
+                let Coord { row, col } = target;
+                let result = fleets[defender]
+                    .opponent_guess(row, col)
+                    .map_err(|e| anyhow!("Side {} fired at {}: {}", shooter, target, e))?;
+                trackers[shooter]
+                    .record_guess(row, col, result)
+                    .map_err(|e| anyhow!("Side {} fired at {}: {}", shooter, target, e))?;
+                shots[shooter] += 1;
+                players[shooter].handle_guess_result(target, result);
+                players[defender].handle_opponent_guess(target, result);
+                sank[shooter] = fleets[defender].status() == GameStatus::Lost;
+            }
+        }
+        shooter = defender;
+    }
+
+    let winner = match sank {
+        [true, false] => 0,
+        [false, true] => 1,
+        // Fewer shots wins, the first mover on a tie
+        [true, true] if shots[0] != shots[1] => usize::from(shots[1] < shots[0]),
+        [true, true] => first,
+        // Both resigned: the first to do so lost
+        [false, false] => 1 - resigned[0],
+    };
+    Ok(GameRecord { shots, sank, first, winner })
+}
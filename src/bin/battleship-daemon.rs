@@ -0,0 +1,125 @@
+//! Headless matchmaking server: pairs players in arrival order (or through
+//! the lobby with `--lobby`), relays their games and records the results.
+
+#[cfg(not(feature = "std"))]
+fn main() {}
+
+#[cfg(feature = "std")]
+use battleship::{
+    daemon::{self, DaemonConfig, MatchRecord},
+    metrics::{self, Metrics},
+    protocol::{LobbyAuth, RateLimit},
+    LogConfig,
+};
+#[cfg(feature = "std")]
+use clap::Parser;
+#[cfg(feature = "std")]
+use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::io::Write;
+#[cfg(feature = "std")]
+use std::sync::Arc;
+#[cfg(feature = "std")]
+use tokio::net::TcpListener;
+#[cfg(feature = "std")]
+use tokio::sync::mpsc;
+#[cfg(feature = "std")]
+use tokio::time::Duration;
+
+#[derive(Parser)]
+#[command(author, version, about = "Pair incoming battleship players and relay their games", long_about = None)]
+#[cfg(feature = "std")]
+struct Args {
+    #[arg(long, default_value = "0.0.0.0:9090")]
+    bind: String,
+    #[arg(long, help = "Append each finished match to this file as a JSON line")]
+    results: Option<std::path::PathBuf>,
+    #[arg(long, help = "Let players browse, host and join games instead of pairing them in arrival order")]
+    lobby: bool,
+    #[arg(long, default_value_t = 300, help = "Seconds a player may stay silent before being dropped")]
+    idle_timeout: u64,
+    #[arg(long = "auth-token", value_name = "IDENTITY=TOKEN", help = "Require lobby clients to present one of these tokens (repeatable)")]
+    auth_tokens: Vec<String>,
+    #[arg(long, conflicts_with = "auth_tokens", help = "Require lobby clients to present a ticket signed with this secret")]
+    ticket_secret: Option<String>,
+    #[arg(long, default_value_t = 20, help = "Lobby requests allowed in a burst per identity (0 disables the limit)")]
+    rate_burst: u32,
+    #[arg(long, default_value_t = 2.0, help = "Lobby requests regained per second per identity")]
+    rate_per_sec: f64,
+    #[arg(long, value_name = "ADDR", help = "Serve Prometheus metrics at http://ADDR/metrics")]
+    metrics: Option<String>,
+    #[arg(long, default_value = "info", help = "Log level (error, warn, info, debug, trace)")]
+    log_level: String,
+    #[arg(long, help = "Emit logs as JSON lines")]
+    log_json: bool,
+}
+
+#[cfg(feature = "std")]
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    let _log_guard = battleship::init_logging(LogConfig {
+        level: args.log_level,
+        json: args.log_json,
+        ..LogConfig::default()
+    })?;
+
+    let listener = TcpListener::bind(&args.bind).await?;
+    tracing::info!(bind = %listener.local_addr()?, "matchmaking daemon listening");
+
+    let (results_tx, mut results_rx) = mpsc::unbounded_channel::<MatchRecord>();
+    let results_path = args.results;
+    tokio::spawn(async move {
+        while let Some(record) = results_rx.recv().await {
+            let Some(path) = &results_path else { continue };
+            if let Err(e) = append_record(path, &record) {
+                tracing::error!(path = %path.display(), error = %e, "failed to record match result");
+            }
+        }
+    });
+
+    let auth = if let Some(secret) = args.ticket_secret {
+        LobbyAuth::Tickets {
+            secret: secret.into_bytes(),
+        }
+    } else if !args.auth_tokens.is_empty() {
+        let mut tokens = HashMap::new();
+        for entry in &args.auth_tokens {
+            let (identity, token) = entry
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("--auth-token expects IDENTITY=TOKEN, got {:?}", entry))?;
+            tokens.insert(token.to_string(), identity.to_string());
+        }
+        LobbyAuth::Tokens(tokens)
+    } else {
+        LobbyAuth::Open
+    };
+    let rate_limit = (args.rate_burst > 0).then_some(RateLimit {
+        burst: args.rate_burst,
+        per_second: args.rate_per_sec,
+    });
+    let config = DaemonConfig {
+        idle_timeout: Duration::from_secs(args.idle_timeout),
+        auth,
+        rate_limit,
+        metrics: Arc::new(Metrics::new()),
+    };
+    if let Some(bind) = &args.metrics {
+        let metrics_listener = TcpListener::bind(bind).await?;
+        tracing::info!(bind = %metrics_listener.local_addr()?, "serving metrics");
+        tokio::spawn(metrics::serve(metrics_listener, config.metrics.clone()));
+    }
+    if args.lobby {
+        daemon::serve_lobby(listener, config, results_tx).await
+    } else {
+        daemon::serve(listener, config, results_tx).await
+    }
+}
+
+#[cfg(feature = "std")]
+fn append_record(path: &std::path::Path, record: &MatchRecord) -> anyhow::Result<()> {
+    let line = serde_json::to_string(record)?;
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)?;
+    Ok(())
+}
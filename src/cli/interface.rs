@@ -4,7 +4,7 @@
 //! This module is not fully integrated and may be removed.
 //! It is compiled only when the `std` feature is enabled.
 
-use crate::core::{Board, BoardError, GuessResult};
+use crate::core::Board;
 
 #[allow(dead_code)]
 pub struct InterfaceCli<'a> {
@@ -15,7 +15,7 @@ pub use interface::*;
 use crate::protocol::GameApi;
 use std::io::{self, Write};
 
-pub async fn run_cli(api: Box<dyn GameApi>) -> anyhow::Result<()> {
+pub async fn run_cli(_api: Box<dyn GameApi>) -> anyhow::Result<()> {
     loop {
         print!("> ");
         io::stdout().flush()?;
@@ -2,117 +2,644 @@
 // Uses no_std and avoids heap allocations.
 
 use super::{
-    bitboard::BitBoard,
-    config::{BOARD_SIZE, NUM_SHIPS},
+    board::halo,
+    config::{BoardBits, FleetConfig, PlacementRules, MAX_BOARD_SIZE, MAX_SHIPS},
+    endgame::ENDGAME_MAX_CANDIDATES,
+    fixed::calc_pdf_fixed_with_sunk,
+    perf::{self, Counter},
     ship::Orientation,
 };
 use libm::pow;
-use num_traits::float::FloatCore;
 use rand::Rng;
+#[cfg(feature = "parallel")]
+use rand::{rngs::SmallRng, SeedableRng};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+#[cfg(feature = "parallel")]
+use std::vec::Vec;
 
 /// Bitboard type alias for convenience.
-type BB = BitBoard<u128, { BOARD_SIZE as usize }>;
+type BB = BoardBits;
 
-const GRID_SIZE: usize = BOARD_SIZE as usize;
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+// Placements covering more observed hits should receive dramatically more
+// weight so that squares adjacent to confirmed hits stand out. The previous
+// implementation used a base of 2 which diluted the impact when many other
+// placements were possible. Using a larger bias concentrates the probability
+// mass around known hits. Empirically a larger multiplier greatly improves
+// target selection around partial ship discoveries. A base of 50 heavily
+// favors placements that include multiple confirmed hits while still allowing
+// some exploration elsewhere.
+const DEFAULT_HIT_BIAS: f64 = 50.0;
+
+// Lower temperature biases the sampling towards higher probability cells so
+// suggestions hone in on likely ship locations.
+const DEFAULT_TEMPERATURE: f64 = 0.5;
+
+/// Most unknown cells for which [`calc_exact_pdf`] enumerates fleets.
+pub const EXACT_MAX_UNKNOWN: usize = 30;
+
+/// Search nodes [`calc_exact_pdf`] may visit before giving up.
+const EXACT_NODE_BUDGET: usize = 2_000_000;
+
+/// Relative gap below the peak within which [`argmax_pdf`] treats cells as tied.
+pub const ARGMAX_TIE_TOLERANCE: f64 = 1e-9;
+
+/// Fleets the Monte Carlo sampler may draw per requested sample before it
+/// stops; most draws are rejected once several hits are on the board.
+const MC_ATTEMPTS_PER_SAMPLE: usize = 20;
+
+/// Independently seeded batches the Monte Carlo draws are split into under
+/// the `parallel` feature. Fixed rather than one per thread, so a seed gives
+/// the same density on any machine.
+#[cfg(feature = "parallel")]
+const MC_CHUNKS: usize = 16;
+
+/// Tunables for the targeting heuristics.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AiConfig {
+    /// Weight multiplier per observed hit covered by a candidate placement.
+    pub hit_bias: f64,
+    /// Sampling temperature; lower values pick high-probability cells more greedily.
+    pub temperature: f64,
+    /// Always shoot the most probable cell, as [`argmax_pdf`] breaks ties,
+    /// instead of sampling, so the same shots always get the same answer;
+    /// `temperature` is then unused.
+    pub deterministic: bool,
+    /// While hunting, only consider cells on a lattice spaced by the shortest
+    /// remaining ship.
+    pub parity: bool,
+    /// Use the exact posterior from [`calc_exact_pdf`] when it is feasible,
+    /// falling back to the heuristic density otherwise.
+    pub use_exact_solver: bool,
+    /// Number of Monte Carlo fleet samples [`calc_pdf_monte_carlo_with_rules`]
+    /// draws; `0` uses the heuristic density.
+    pub mc_samples: usize,
+    /// Once one enemy ship is left and it has at most this many placements,
+    /// [`AiPlayer`](crate::AiPlayer) shoots where [`plan_endgame`](crate::plan_endgame)
+    /// expects to sink it soonest instead of following the density; `0`
+    /// never does.
+    pub endgame_candidates: usize,
+    /// Weigh placements in integer arithmetic, as [`calc_pdf_fixed`](crate::calc_pdf_fixed)
+    /// does, with `hit_bias` rounded to a whole number. The density ranks
+    /// the cells the same, without a power per placement.
+    pub fixed_point: bool,
+}
+
+impl Default for AiConfig {
+    fn default() -> Self {
+        Self {
+            hit_bias: DEFAULT_HIT_BIAS,
+            temperature: DEFAULT_TEMPERATURE,
+            deterministic: false,
+            parity: false,
+            use_exact_solver: false,
+            mc_samples: 0,
+            endgame_candidates: ENDGAME_MAX_CANDIDATES,
+            fixed_point: false,
+        }
+    }
+}
 
 /// Compute a probability density over all unguessed squares given the sets of
 /// known hits and misses and the lengths of remaining enemy ships. The result
 /// is a matrix where each entry sums the relative likelihood of a ship segment
 /// occupying that coordinate.
+///
+/// `remaining_lengths` may list any number of ships; zero entries, such as
+/// sunk ships in [`GameEngine::enemy_ship_lengths_remaining`](crate::GameEngine::enemy_ship_lengths_remaining),
+/// are skipped.
 pub fn calc_pdf(
     hits: &BB,
     misses: &BB,
-    remaining_lengths: &[usize; NUM_SHIPS as usize],
+    remaining_lengths: &[usize],
+) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    calc_pdf_with_config(hits, misses, remaining_lengths, &AiConfig::default())
+}
+
+/// [`calc_pdf`] with explicit tunables: `hit_bias` weights placements over
+/// hits and `parity` masks the density to a hunting lattice once every hit
+/// belongs to a sunk ship.
+pub fn calc_pdf_with_config(
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    config: &AiConfig,
+) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    calc_pdf_for_fleet(hits, misses, remaining_lengths, &FleetConfig::STANDARD, config)
+}
+
+/// [`calc_pdf_with_config`] for a game played with `fleet` rather than the
+/// standard fleet; parity needs the fleet to tell which hits are resolved.
+pub fn calc_pdf_for_fleet(
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    fleet: &FleetConfig,
+    config: &AiConfig,
+) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    calc_pdf_with_rules(hits, misses, remaining_lengths, fleet, &PlacementRules::STANDARD, config)
+}
+
+/// [`calc_pdf_for_fleet`] for a game whose ships were placed under
+/// `placement`. With no touching allowed, a placement next to a hit it does
+/// not cover is impossible, since that hit belongs to another ship.
+pub fn calc_pdf_with_rules(
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    fleet: &FleetConfig,
+    placement: &PlacementRules,
+    config: &AiConfig,
 ) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    calc_pdf_with_sunk(hits, &BB::new(), misses, remaining_lengths, fleet, placement, config)
+}
+
+/// [`calc_pdf_with_rules`] knowing that the `sunk` hits belong to sunk
+/// ships, as [`OpponentModel::sunk_cells`](crate::OpponentModel::sunk_cells)
+/// reports them. No remaining ship can lie on those cells, or beside them
+/// when ships may not touch, and placements are only rewarded for covering
+/// the hits left unresolved. Passing the sunk cells among the misses, as
+/// [`GameEngine::blocked_cells`](crate::GameEngine::blocked_cells) does,
+/// gives the same density.
+pub fn calc_pdf_with_sunk(
+    hits: &BB,
+    sunk: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    fleet: &FleetConfig,
+    placement: &PlacementRules,
+    config: &AiConfig,
+) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    perf::count(Counter::Densities, 1);
+    if config.use_exact_solver {
+        if let Some(pdf) = calc_exact_pdf(hits, misses, remaining_lengths, fleet, placement) {
+            return pdf;
+        }
+    }
+    if config.fixed_point {
+        let scores = calc_pdf_fixed_with_sunk(hits, sunk, misses, remaining_lengths, fleet, placement, &config.into());
+        return normalize(scores.map(|row| row.map(f64::from)));
+    }
+    let unresolved = *hits & !*sunk;
+    let wrecks = if placement.no_touching { sunk.dilate(true) } else { *sunk };
+    let blocked = *misses | wrecks;
+    let density = |&len: &usize| length_density(len, &unresolved, &blocked, placement, config.hit_bias);
+
+    // Each ship is weighed on its own, in parallel if enabled, and the sums
+    // added in fleet order so both ways round alike
+    #[cfg(feature = "parallel")]
+    let densities: Vec<_> = remaining_lengths.par_iter().map(density).collect();
+    #[cfg(not(feature = "parallel"))]
+    let densities = remaining_lengths.iter().map(density);
+
     let mut matrix = [[0.0f64; GRID_SIZE]; GRID_SIZE];
+    for sums in densities {
+        add_matrix(&mut matrix, &sums);
+    }
+
+    if config.parity {
+        apply_parity(&mut matrix, hits, &blocked, remaining_lengths, fleet);
+    }
+
+    normalize(matrix)
+}
 
-    for &len in remaining_lengths.iter() {
-        if len == 0 {
+/// Each cell's total weight over the placements of a ship of length `len`,
+/// as [`calc_pdf_with_sunk`] weighs them.
+fn length_density(
+    len: usize,
+    unresolved: &BB,
+    blocked: &BB,
+    placement: &PlacementRules,
+    hit_bias: f64,
+) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    let mut matrix = [[0.0f64; GRID_SIZE]; GRID_SIZE];
+    for p in enumerate_placements(len, unresolved, blocked) {
+        if placement.no_touching && !(halo(&p.mask) & !p.mask & *unresolved).is_empty() {
             continue;
         }
+        let weight = if p.hits == 0 { 1.0 } else { pow(hit_bias, p.hits as f64) };
+        for (r, c) in (p.mask & !*unresolved).iter_set_bits() {
+            matrix[r][c] += weight;
+        }
+    }
+    matrix
+}
 
-        for orient in [Orientation::Horizontal, Orientation::Vertical] {
-            let max_row = if matches!(orient, Orientation::Vertical) {
-                GRID_SIZE - len + 1
-            } else {
-                GRID_SIZE
-            };
-            let max_col = if matches!(orient, Orientation::Horizontal) {
-                GRID_SIZE - len + 1
-            } else {
-                GRID_SIZE
-            };
-            for r in 0..max_row {
-                for c in 0..max_col {
-                    // check placement validity and count hits
-                    let mut valid = true;
-                    let mut n_hits = 0usize;
-                    for k in 0..len {
-                        let rr = r + if matches!(orient, Orientation::Vertical) {
-                            k
-                        } else {
-                            0
-                        };
-                        let cc = c + if matches!(orient, Orientation::Horizontal) {
-                            k
-                        } else {
-                            0
-                        };
-                        if misses.get(rr, cc).unwrap_or(false) {
-                            valid = false;
-                            break;
-                        }
-                        if hits.get(rr, cc).unwrap_or(false) {
-                            n_hits += 1;
-                        }
-                    }
-                    if !valid {
-                        continue;
-                    }
-
-                    // Placements covering more observed hits should receive
-                    // dramatically more weight so that squares adjacent to
-                    // confirmed hits stand out. The previous implementation
-                    // used a base of 2 which diluted the impact when many
-                    // other placements were possible. Using a larger bias
-                    // concentrates the probability mass around known hits.
-                    // Empirically a larger multiplier greatly improves target
-                    // selection around partial ship discoveries.
-                    // A base of 50 heavily favors placements that include
-                    // multiple confirmed hits while still allowing some
-                    // exploration elsewhere.
-                    const HIT_BIAS: f64 = 50.0;
-                    let weight = if n_hits == 0 {
-                        1.0
-                    } else {
-                        HIT_BIAS.powi(n_hits as i32)
-                    };
-                    for k in 0..len {
-                        let rr = r + if matches!(orient, Orientation::Vertical) {
-                            k
-                        } else {
-                            0
-                        };
-                        let cc = c + if matches!(orient, Orientation::Horizontal) {
-                            k
-                        } else {
-                            0
-                        };
-                        if !hits.get(rr, cc).unwrap_or(false)
-                            && !misses.get(rr, cc).unwrap_or(false)
-                        {
-                            matrix[rr][cc] += weight;
-                        }
-                    }
+/// Add `other` to `matrix` cell by cell.
+pub(crate) fn add_matrix(matrix: &mut [[f64; GRID_SIZE]; GRID_SIZE], other: &[[f64; GRID_SIZE]; GRID_SIZE]) {
+    for (row, other) in matrix.iter_mut().zip(other.iter()) {
+        for (v, &o) in row.iter_mut().zip(other.iter()) {
+            *v += o;
+        }
+    }
+}
+
+/// Number of valid placements of the remaining ships covering each cell.
+///
+/// A placement is valid when it fits on the board and avoids every miss.
+/// Counts are raw: hits add no bias, hit cells themselves are counted, and
+/// nothing is normalised, so the result is an exact combinatorial quantity.
+pub fn calc_placement_counts(
+    misses: &BB,
+    remaining_lengths: &[usize],
+) -> [[u32; GRID_SIZE]; GRID_SIZE] {
+    let mut counts = [[0u32; GRID_SIZE]; GRID_SIZE];
+    for &len in remaining_lengths {
+        for p in enumerate_placements(len, &BB::new(), misses) {
+            for (r, c) in p.mask.iter_set_bits() {
+                counts[r][c] += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// One way a ship could lie on the opponent's board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub row: usize,
+    pub col: usize,
+    pub orientation: Orientation,
+    pub length: usize,
+    /// Cells the ship covers.
+    pub mask: BB,
+    /// Number of known hits among those cells.
+    pub hits: usize,
+}
+
+/// Every in-bounds placement of a ship of length `len` that covers no miss,
+/// horizontal ones first, each in row-major order of its origin. Nothing is
+/// weighted or filtered by `hits`; each placement just counts the hits it
+/// covers. A zero-length ship or one longer than the board has none.
+///
+/// The origins come from [`BitBoard::run_starts`] over the cells not
+/// missed, so only placements that fit are ever built.
+pub fn enumerate_placements(len: usize, hits: &BB, misses: &BB) -> impl Iterator<Item = Placement> {
+    let (hits, open) = (*hits, !*misses);
+    [Orientation::Horizontal, Orientation::Vertical]
+        .into_iter()
+        .flat_map(move |orientation| {
+            // The clear cells of the complement own their board, unlike
+            // `iter_set_bits`, so the origins outlive this closure
+            (!open.run_starts(len, orientation))
+                .iter_clear_bits()
+                .map(move |(row, col)| (row, col, orientation))
+        })
+        .filter_map(move |(row, col, orientation)| {
+            let mask = BB::line(row, col, len, orientation).ok()?;
+            Some(Placement {
+                row,
+                col,
+                orientation,
+                length: len,
+                mask,
+                hits: (mask & hits).count_ones(),
+            })
+        })
+        .inspect(|_| perf::count(Counter::Placements, 1))
+}
+
+/// Exact density over the unknown cells, normalised like [`calc_pdf`]: each
+/// cell's weight is the number of fleet layouts consistent with the shots
+/// that put a ship still afloat on it, every such layout being equally
+/// likely.
+///
+/// A layout places every ship of `fleet` on the board without overlaps (or
+/// touching, when `placement` forbids it), covers every hit and no miss,
+/// lays each sunk ship wholly on hits and leaves each ship still afloat, as
+/// listed in `remaining_lengths`, with at least one cell not hit. Equal
+/// ships are placed in order so each layout is counted once. Hits also
+/// listed among the misses, as the wrecks in
+/// [`GameEngine::blocked_cells`](crate::GameEngine::blocked_cells) are,
+/// count as hits.
+///
+/// Returns `None` when more than [`EXACT_MAX_UNKNOWN`] cells are unknown,
+/// the search outgrows its budget, `remaining_lengths` does not fit the
+/// fleet, or no layout fits the shots.
+pub fn calc_exact_pdf(
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    fleet: &FleetConfig,
+    placement: &PlacementRules,
+) -> Option<[[f64; GRID_SIZE]; GRID_SIZE]> {
+    let misses = *misses & !*hits;
+    let unknown = BB::unknown(hits, &misses);
+    if unknown.count_ones() > EXACT_MAX_UNKNOWN {
+        return None;
+    }
+    let ships = fleet_status(fleet, remaining_lengths)?;
+    let mut search = ExactSearch {
+        hits: *hits,
+        misses,
+        ships,
+        count: fleet.len(),
+        no_touching: placement.no_touching,
+        nodes: 0,
+        layouts: 0.0,
+        cells: [[0.0; GRID_SIZE]; GRID_SIZE],
+    };
+    search.place(0, BB::new(), BB::new(), 0)?;
+    if search.layouts == 0.0 {
+        return None;
+    }
+    Some(normalize(search.cells))
+}
+
+/// Fleet ships as `(length, afloat)`, longest first, matching each afloat
+/// length in `remaining_lengths` to a fleet ship; the unmatched ships are
+/// the sunk ones. `None` if some remaining length is not in the fleet.
+fn fleet_status(fleet: &FleetConfig, remaining_lengths: &[usize]) -> Option<[(usize, bool); MAX_SHIPS]> {
+    let mut ships = [(0, false); MAX_SHIPS];
+    for (slot, def) in ships.iter_mut().zip(fleet.ships()) {
+        *slot = (def.length(), false);
+    }
+    let count = fleet.len();
+    ships[..count].sort_unstable_by_key(|s| core::cmp::Reverse(s.0));
+    for &len in remaining_lengths.iter().filter(|&&l| l > 0) {
+        let slot = ships[..count].iter_mut().find(|(l, afloat)| *l == len && !afloat)?;
+        slot.1 = true;
+    }
+    Some(ships)
+}
+
+/// Depth-first enumeration of fleet layouts for [`calc_exact_pdf`].
+struct ExactSearch {
+    hits: BB,
+    misses: BB,
+    ships: [(usize, bool); MAX_SHIPS],
+    count: usize,
+    no_touching: bool,
+    nodes: usize,
+    /// Layouts found.
+    layouts: f64,
+    /// For each cell, the layouts with an afloat ship on it.
+    cells: [[f64; GRID_SIZE]; GRID_SIZE],
+}
+
+impl ExactSearch {
+    /// Place ship `i` onwards given the cells `occupied` by earlier ships,
+    /// of which `afloat` belong to ships still afloat. `first` is the
+    /// lowest placement index ship `i` may take, keeping equal ships in
+    /// order. `None` once the node budget is spent.
+    fn place(&mut self, i: usize, occupied: BB, afloat: BB, first: usize) -> Option<()> {
+        self.nodes += 1;
+        if self.nodes > EXACT_NODE_BUDGET {
+            return None;
+        }
+        if i == self.count {
+            if (self.hits & !occupied).is_empty() {
+                self.layouts += 1.0;
+                for (r, c) in (afloat & !self.hits).iter_set_bits() {
+                    self.cells[r][c] += 1.0;
                 }
             }
+            return Some(());
+        }
+        // Every hit still uncovered needs a cell of a later ship
+        let room: usize = self.ships[i..self.count].iter().map(|&(len, _)| len).sum();
+        if (self.hits & !occupied).count_ones() > room {
+            return Some(());
+        }
+        let (len, is_afloat) = self.ships[i];
+        let blocked = if self.no_touching { occupied.dilate(true) } else { occupied };
+        for (index, p) in enumerate_placements(len, &self.hits, &self.misses).enumerate().skip(first) {
+            if !(p.mask & blocked).is_empty() {
+                continue;
+            }
+            // A sunk ship lies wholly on hits; one afloat has a cell left
+            if is_afloat == (p.hits == len) {
+                continue;
+            }
+            let next_first = match self.ships.get(i + 1) {
+                Some(&next) if i + 1 < self.count && next == (len, is_afloat) => index + 1,
+                _ => 0,
+            };
+            let next_afloat = if is_afloat { afloat | p.mask } else { afloat };
+            self.place(i + 1, occupied | p.mask, next_afloat, next_first)?;
+        }
+        Some(())
+    }
+}
+
+/// Estimate the density by sampling `samples` whole fleets of the standard
+/// game that fit the shots and counting how often each cell holds a ship.
+///
+/// Unlike [`calc_pdf`], which weighs each ship on its own, sampled ships
+/// never overlap, so cells crowded out by other ships lose weight. Falls
+/// back to [`calc_pdf`] when no sampled fleet fits the shots.
+pub fn calc_pdf_monte_carlo<R: Rng + ?Sized>(
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    samples: usize,
+    rng: &mut R,
+) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    let config = AiConfig { mc_samples: samples, ..AiConfig::default() };
+    calc_pdf_monte_carlo_with_rules(
+        hits,
+        misses,
+        remaining_lengths,
+        &FleetConfig::STANDARD,
+        &PlacementRules::STANDARD,
+        &config,
+        rng,
+    )
+}
+
+/// [`calc_pdf_monte_carlo`] for a game played with `fleet` under
+/// `placement`, drawing `config.mc_samples` fleets.
+///
+/// The remaining ships are laid one at a time, each uniformly among the
+/// placements that avoid the misses and the ships already laid and do not
+/// lie wholly on hits. A draw is kept when its ships cover every hit not
+/// accounted for by sunk ships. The exact posterior is used instead when
+/// `config.use_exact_solver` is set and it is feasible, and
+/// [`calc_pdf_with_rules`] when no draw is kept.
+///
+/// Under the `parallel` feature the draws are split into batches, each with
+/// its own generator seeded from `rng`, and sampled across threads. The
+/// density for a given seed then differs from the serial one but not
+/// between runs.
+pub fn calc_pdf_monte_carlo_with_rules<R: Rng + ?Sized>(
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    fleet: &FleetConfig,
+    placement: &PlacementRules,
+    config: &AiConfig,
+    rng: &mut R,
+) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    if config.use_exact_solver {
+        if let Some(pdf) = calc_exact_pdf(hits, misses, remaining_lengths, fleet, placement) {
+            return pdf;
         }
     }
+    let sunk_cells = fleet.total_cells().saturating_sub(remaining_lengths.iter().sum::<usize>());
+    let open_hits = hits.count_ones().saturating_sub(sunk_cells);
+    let no_touching = placement.no_touching;
+
+    #[cfg(not(feature = "parallel"))]
+    let (mut matrix, kept) =
+        draw_fleets(hits, misses, remaining_lengths, no_touching, open_hits, config.mc_samples, rng);
+    #[cfg(feature = "parallel")]
+    let (mut matrix, kept) = {
+        let seeds: [u64; MC_CHUNKS] = core::array::from_fn(|_| rng.random());
+        let batches: Vec<_> = seeds
+            .par_iter()
+            .enumerate()
+            .map(|(i, &seed)| {
+                let share = config.mc_samples / MC_CHUNKS + usize::from(i < config.mc_samples % MC_CHUNKS);
+                let mut rng = SmallRng::seed_from_u64(seed);
+                draw_fleets(hits, misses, remaining_lengths, no_touching, open_hits, share, &mut rng)
+            })
+            .collect();
+        batches.iter().fold(([[0.0f64; GRID_SIZE]; GRID_SIZE], 0), |(mut matrix, kept), (counts, n)| {
+            add_matrix(&mut matrix, counts);
+            (matrix, kept + n)
+        })
+    };
+    if kept == 0 {
+        return calc_pdf_with_rules(hits, misses, remaining_lengths, fleet, placement, config);
+    }
+
+    if config.parity {
+        apply_parity(&mut matrix, hits, misses, remaining_lengths, fleet);
+    }
 
     normalize(matrix)
 }
 
-fn normalize(mut matrix: [[f64; GRID_SIZE]; GRID_SIZE]) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+/// Draw up to `samples` fleets whose ships cover exactly `open_hits` hits,
+/// counting how many cover each unshot cell. Also returns the number drawn,
+/// fewer than `samples` when too many draws are rejected.
+fn draw_fleets<R: Rng + ?Sized>(
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    no_touching: bool,
+    open_hits: usize,
+    samples: usize,
+    rng: &mut R,
+) -> ([[f64; GRID_SIZE]; GRID_SIZE], usize) {
+    let mut matrix = [[0.0f64; GRID_SIZE]; GRID_SIZE];
+    let mut kept = 0;
+    for _ in 0..samples.saturating_mul(MC_ATTEMPTS_PER_SAMPLE) {
+        if kept == samples {
+            break;
+        }
+        perf::count(Counter::Fleets, 1);
+        let Some(occupied) = sample_fleet(hits, misses, remaining_lengths, no_touching, rng) else {
+            continue;
+        };
+        if (occupied & *hits).count_ones() != open_hits {
+            continue;
+        }
+        kept += 1;
+        for (r, c) in (occupied & !*hits).iter_set_bits() {
+            matrix[r][c] += 1.0;
+        }
+    }
+    (matrix, kept)
+}
+
+/// Lay the ships of `remaining_lengths` one after another, each uniformly
+/// among its free placements, returning the cells they cover. `None` when
+/// some ship has nowhere left to go.
+fn sample_fleet<R: Rng + ?Sized>(
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    no_touching: bool,
+    rng: &mut R,
+) -> Option<BB> {
+    let mut occupied = BB::new();
+    for &len in remaining_lengths.iter().filter(|&&l| l > 0) {
+        let blocked = if no_touching { occupied.dilate(true) } else { occupied };
+        let free = |p: &Placement| (p.mask & blocked).is_empty() && p.hits < len;
+        let count = enumerate_placements(len, hits, misses).filter(free).count();
+        if count == 0 {
+            return None;
+        }
+        let pick = enumerate_placements(len, hits, misses).filter(free).nth(rng.random_range(0..count))?;
+        occupied |= pick.mask;
+    }
+    Some(occupied)
+}
+
+/// The lattice worth hunting on, or `None` while some hit is unresolved.
+///
+/// Every ship of length `k` or more covers a cell of each lattice
+/// [`BitBoard::lattice`]`(k, offset)`, so with no partial hits to chase
+/// only one of them needs to be searched, `k` being the shortest ship
+/// still afloat. Of the `k` offsets this picks the lattice with the fewest
+/// unknown cells left, the cheapest to sweep; since shooting it only makes
+/// it cheaper, the choice holds until a sinking changes `k`.
+pub fn hunt_lattice(hits: &BB, misses: &BB, remaining_lengths: &[usize], fleet: &FleetConfig) -> Option<BB> {
+    let sunk_cells = fleet.total_cells().saturating_sub(remaining_lengths.iter().sum::<usize>());
+    if hits.count_ones() != sunk_cells {
+        return None;
+    }
+    let spacing = remaining_lengths.iter().copied().filter(|&l| l > 0).min()?;
+    let unknown = BB::unknown(hits, misses);
+    (0..spacing)
+        .map(|offset| BB::lattice(spacing, offset))
+        .min_by_key(|lattice| (*lattice & unknown).count_ones())
+}
+
+/// Cells worth shooting next to finish off a wounded ship.
+///
+/// `wounded` holds the hits not yet put down to a sunk ship. Its clusters
+/// are tried in turn: two or more hits in a line are followed along that
+/// line at either end, and a lone hit, or a line boxed in at both ends, by
+/// its unknown orthogonal neighbours. Empty when nothing is wounded or
+/// every cluster is boxed in by earlier shots.
+pub fn follow_up_targets(wounded: &BB, hits: &BB, misses: &BB) -> BB {
+    let unknown = BB::unknown(hits, misses);
+    for cluster in wounded.clusters() {
+        let around = cluster.neighbors(false) & unknown;
+        if cluster.count_ones() > 1 && cluster.is_line() {
+            let Some((row, col)) = cluster.iter_set_bits().next() else {
+                continue;
+            };
+            let axis = if cluster.count_row(row) > 1 { BB::row_mask(row) } else { BB::col_mask(col) };
+            let ends = around & axis;
+            if !ends.is_empty() {
+                return ends;
+            }
+        }
+        if !around.is_empty() {
+            return around;
+        }
+    }
+    BB::new()
+}
+
+/// Zero cells off the [`hunt_lattice`], unless that would leave no weight.
+pub(crate) fn apply_parity(
+    matrix: &mut [[f64; GRID_SIZE]; GRID_SIZE],
+    hits: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    fleet: &FleetConfig,
+) {
+    let Some(lattice) = hunt_lattice(hits, misses, remaining_lengths, fleet) else {
+        return;
+    };
+    let lattice_mass: f64 = lattice.iter_set_bits().map(|(r, c)| matrix[r][c]).sum();
+    if lattice_mass == 0.0 {
+        return;
+    }
+    for (r, c) in (!lattice).iter_set_bits() {
+        matrix[r][c] = 0.0;
+    }
+}
+
+pub(crate) fn normalize(mut matrix: [[f64; GRID_SIZE]; GRID_SIZE]) -> [[f64; GRID_SIZE]; GRID_SIZE] {
     let mut total = 0.0;
     for row in matrix.iter() {
         for &v in row.iter() {
@@ -121,16 +648,16 @@ fn normalize(mut matrix: [[f64; GRID_SIZE]; GRID_SIZE]) -> [[f64; GRID_SIZE]; GR
     }
     if total == 0.0 {
         let uniform = 1.0 / (GRID_SIZE * GRID_SIZE) as f64;
-        for r in 0..GRID_SIZE {
-            for c in 0..GRID_SIZE {
-                matrix[r][c] = uniform;
+        for row in matrix.iter_mut() {
+            for v in row.iter_mut() {
+                *v = uniform;
             }
         }
         return matrix;
     }
-    for r in 0..GRID_SIZE {
-        for c in 0..GRID_SIZE {
-            matrix[r][c] /= total;
+    for row in matrix.iter_mut() {
+        for v in row.iter_mut() {
+            *v /= total;
         }
     }
     matrix
@@ -142,12 +669,13 @@ pub fn sample_pdf<R: Rng + ?Sized>(
     temperature: f64,
     rng: &mut R,
 ) -> (usize, usize) {
+    perf::count(Counter::Samples, 1);
     let mut adjusted = [[0.0f64; GRID_SIZE]; GRID_SIZE];
     let mut total = 0.0;
-    for r in 0..GRID_SIZE {
-        for c in 0..GRID_SIZE {
-            let v = pow(pdf[r][c], 1.0 / temperature);
-            adjusted[r][c] = v;
+    for (adj_row, pdf_row) in adjusted.iter_mut().zip(pdf.iter()) {
+        for (adj, &p) in adj_row.iter_mut().zip(pdf_row.iter()) {
+            let v = pow(p, 1.0 / temperature);
+            *adj = v;
             total += v;
         }
     }
@@ -159,9 +687,9 @@ pub fn sample_pdf<R: Rng + ?Sized>(
     }
     let mut cumulative = 0.0;
     let threshold: f64 = rng.random_range(0.0..total);
-    for r in 0..GRID_SIZE {
-        for c in 0..GRID_SIZE {
-            cumulative += adjusted[r][c];
+    for (r, row) in adjusted.iter().enumerate() {
+        for (c, &v) in row.iter().enumerate() {
+            cumulative += v;
             if threshold < cumulative {
                 return (r, c);
             }
@@ -170,16 +698,65 @@ pub fn sample_pdf<R: Rng + ?Sized>(
     (GRID_SIZE - 1, GRID_SIZE - 1)
 }
 
+/// Choose a target from `pdf` as `config` asks: the most probable cell when
+/// `deterministic`, else a sample at its temperature.
+pub fn pick_target<R: Rng + ?Sized>(
+    pdf: &[[f64; GRID_SIZE]; GRID_SIZE],
+    config: &AiConfig,
+    rng: &mut R,
+) -> (usize, usize) {
+    if config.deterministic {
+        return argmax_pdf(pdf);
+    }
+    sample_pdf(pdf, config.temperature, rng)
+}
+
+/// The most probable cell of `pdf`.
+///
+/// Cells within [`ARGMAX_TIE_TOLERANCE`] of the peak, relative to it, tie,
+/// and the first of them in row-major order wins. Mirror-image cells whose
+/// sums were rounded differently thus still resolve the same way on every
+/// run and platform.
+pub fn argmax_pdf(pdf: &[[f64; GRID_SIZE]; GRID_SIZE]) -> (usize, usize) {
+    let peak = pdf.iter().flatten().fold(0.0f64, |m, &p| m.max(p));
+    let floor = peak - peak * ARGMAX_TIE_TOLERANCE;
+    (0..GRID_SIZE)
+        .flat_map(|r| (0..GRID_SIZE).map(move |c| (r, c)))
+        .find(|&(r, c)| pdf[r][c] >= floor)
+        .unwrap_or((0, 0))
+}
+
 /// Convenience wrapper that calculates the PDF and immediately selects a guess
 /// using the provided random number generator.
 pub fn calc_pdf_and_guess<R: Rng + ?Sized>(
     hits: &BB,
     misses: &BB,
-    lengths: &[usize; NUM_SHIPS as usize],
+    lengths: &[usize],
+    rng: &mut R,
+) -> (usize, usize) {
+    calc_pdf_and_guess_with_config(hits, misses, lengths, &AiConfig::default(), rng)
+}
+
+/// [`calc_pdf_and_guess`] with explicit tunables.
+pub fn calc_pdf_and_guess_with_config<R: Rng + ?Sized>(
+    hits: &BB,
+    misses: &BB,
+    lengths: &[usize],
+    config: &AiConfig,
     rng: &mut R,
 ) -> (usize, usize) {
-    let pdf = calc_pdf(hits, misses, lengths);
-    // Lower temperature biases the sampling towards higher probability cells
-    // so suggestions hone in on likely ship locations.
-    sample_pdf(&pdf, 0.5, rng)
+    let pdf = if config.mc_samples > 0 {
+        calc_pdf_monte_carlo_with_rules(
+            hits,
+            misses,
+            lengths,
+            &FleetConfig::STANDARD,
+            &PlacementRules::STANDARD,
+            config,
+            rng,
+        )
+    } else {
+        calc_pdf_with_config(hits, misses, lengths, config)
+    };
+    pick_target(&pdf, config, rng)
 }
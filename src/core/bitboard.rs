@@ -3,10 +3,17 @@
 //! The type is `no_std` friendly and avoids heap allocations. Boards are
 //! represented as an `N×N` grid packed into an unsigned integer `T`.
 //! Basic constructors and bitwise operations are provided.
+//!
+//! `new` is a `const fn` for every storage type. For the primitive unsigned
+//! integers, `from_raw`, `from_rows` and the builder-style [`BitBoard::with`]
+//! are `const` too, so masks and lattices can be computed at compile time,
+//! or drawn as a grid with the [`bitboard!`](crate::bitboard) macro.
 
 use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
 use core::{any, fmt, mem};
-use num_traits::{PrimInt, Unsigned, Zero};
+use num_traits::{ConstZero, PrimInt, Unsigned};
+
+use super::ship::Orientation;
 
 /// Errors returned by bitboard operations.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -40,17 +47,17 @@ impl std::error::Error for BitBoardError {}
 
 /// A fixed-size N×N bitboard stored in the unsigned integer `T`.
 #[derive(Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BitBoard<T, const N: usize>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     bits: T,
 }
 
 impl<T, const N: usize> BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     /// Number of usable bits in the board (`N * N`).
     const BOARD_BITS: usize = N * N;
@@ -66,8 +73,8 @@ where
 
     /// Create a new empty bitboard (all bits cleared) without size check.
     #[inline]
-    pub fn new() -> Self {
-        BitBoard { bits: T::zero() }
+    pub const fn new() -> Self {
+        BitBoard { bits: T::ZERO }
     }
 
     /// Fallible constructor: returns `Err(SizeTooLarge)` if N*N > T::BITS.
@@ -118,7 +125,7 @@ where
         self.check_bounds(row, col)?;
         let idx = row * N + col;
         self.bits = self.bits ^ (T::one() << idx);
-        Ok(())  
+        Ok(())
     }
 
     /// Sets all board bits to `1`.
@@ -148,17 +155,18 @@ where
         self.bits
     }
 
-    /// Creates a bitboard from the raw integer, masking out upper bits.
+    /// Wraps a raw integer, masking out bits beyond the board.
     #[inline]
-    pub fn from_raw(raw: T) -> Self {
+    fn masked(raw: T) -> Self {
         BitBoard {
             bits: raw & Self::mask(),
         }
     }
 
-    /// Creates a bitboard from an iterator over `(row, col)` positions.
+    /// Creates a bitboard from an iterator over `(row, col)` positions,
+    /// failing on the first position off the board.
     #[inline]
-    pub fn from_iter<I>(iter: I) -> Result<Self, BitBoardError>
+    pub fn try_from_cells<I>(iter: I) -> Result<Self, BitBoardError>
     where
         I: IntoIterator<Item = (usize, usize)>,
     {
@@ -169,6 +177,40 @@ where
         Ok(board)
     }
 
+    /// Creates a bitboard from an iterator over `(row, col)` positions.
+    #[deprecated(note = "use `try_from_cells`; `collect()` skips off-board cells instead")]
+    #[inline]
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_iter<I>(iter: I) -> Result<Self, BitBoardError>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        Self::try_from_cells(iter)
+    }
+
+    /// Creates a bitboard with the cells set that are `true` in `grid`,
+    /// indexed `grid[row][col]`.
+    pub fn from_grid(grid: &[[bool; N]; N]) -> Self {
+        let mut board = Self::new();
+        for (r, row) in grid.iter().enumerate() {
+            for (c, &cell) in row.iter().enumerate() {
+                if cell {
+                    let _ = board.set(r, c);
+                }
+            }
+        }
+        board
+    }
+
+    /// The board as a matrix of cells, indexed `[row][col]`.
+    pub fn to_grid(&self) -> [[bool; N]; N] {
+        let mut grid = [[false; N]; N];
+        for (r, c) in self.iter_set_bits() {
+            grid[r][c] = true;
+        }
+        grid
+    }
+
     /// Iterator over the set bits of the board.
     #[inline]
     pub fn iter_set_bits(&self) -> SetBits<'_, T, N> {
@@ -177,11 +219,298 @@ where
             idx: 0,
         }
     }
+
+    /// Iterator over the clear cells of the board, in the same row-major
+    /// order as [`BitBoard::iter_set_bits`].
+    #[inline]
+    pub fn iter_clear_bits(&self) -> ClearBits<T, N> {
+        ClearBits {
+            clear: !*self,
+            idx: 0,
+        }
+    }
+
+    /// Cells not yet fired at: neither in `hits` nor in `misses`.
+    #[inline]
+    pub fn unknown(hits: &Self, misses: &Self) -> Self {
+        !(*hits | *misses)
+    }
+
+    /// Rotates the board a quarter turn clockwise: `(r, c)` moves to `(c, N-1-r)`.
+    pub fn rotate90(&self) -> Self {
+        self.map_cells(|r, c| (c, N - 1 - r))
+    }
+
+    /// Rotates the board a half turn: `(r, c)` moves to `(N-1-r, N-1-c)`.
+    pub fn rotate180(&self) -> Self {
+        self.map_cells(|r, c| (N - 1 - r, N - 1 - c))
+    }
+
+    /// Rotates the board a quarter turn counter-clockwise: `(r, c)` moves to `(N-1-c, r)`.
+    pub fn rotate270(&self) -> Self {
+        self.map_cells(|r, c| (N - 1 - c, r))
+    }
+
+    /// Mirrors the board left to right: `(r, c)` moves to `(r, N-1-c)`.
+    pub fn mirror_h(&self) -> Self {
+        self.map_cells(|r, c| (r, N - 1 - c))
+    }
+
+    /// Mirrors the board top to bottom: `(r, c)` moves to `(N-1-r, c)`.
+    pub fn mirror_v(&self) -> Self {
+        self.map_cells(|r, c| (N - 1 - r, c))
+    }
+
+    /// The board under each of the eight rotations and reflections of the
+    /// square: the board itself, its three rotations, then the same for its
+    /// left-right mirror image.
+    pub fn symmetries(&self) -> [Self; 8] {
+        let h = self.mirror_h();
+        [
+            *self,
+            self.rotate90(),
+            self.rotate180(),
+            self.rotate270(),
+            h,
+            h.rotate90(),
+            h.rotate180(),
+            h.rotate270(),
+        ]
+    }
+
+    /// The symmetry of the board with the smallest raw value. Boards that
+    /// are rotations or reflections of each other share it, so it can key
+    /// a deduplicating set or a statistics table.
+    pub fn canonical(&self) -> Self {
+        self.symmetries().into_iter().min_by_key(|b| b.bits).unwrap_or(*self)
+    }
+
+    /// Moves every set bit to `f(row, col)`, which must stay on the board.
+    fn map_cells<F: Fn(usize, usize) -> (usize, usize)>(&self, f: F) -> Self {
+        let mut out = T::zero();
+        for (r, c) in self.iter_set_bits() {
+            let (nr, nc) = f(r, c);
+            out = out | (T::one() << (nr * N + nc));
+        }
+        BitBoard { bits: out }
+    }
+
+    /// Iterator over the orthogonally connected groups of set bits, each
+    /// yielded as its own board, in order of their lowest set bit.
+    #[inline]
+    pub fn clusters(&self) -> Clusters<T, N> {
+        Clusters { remaining: *self }
+    }
+
+    /// The orthogonally connected group of set bits containing (row, col),
+    /// as one of [`BitBoard::clusters`] would yield it; empty if that cell
+    /// is clear or off the board.
+    pub fn flood_fill(&self, row: usize, col: usize) -> Self {
+        if !self.get(row, col).unwrap_or(false) {
+            return Self::new();
+        }
+        let mut seed = Self::new();
+        let _ = seed.set(row, col);
+        self.fill_from(seed)
+    }
+
+    /// `seed` grown orthogonally through the set bits until it stops.
+    fn fill_from(&self, seed: Self) -> Self {
+        let mut filled = seed;
+        loop {
+            let grown = filled.dilate(false) & *self;
+            if grown == filled {
+                return filled;
+            }
+            filled = grown;
+        }
+    }
+
+    /// Whether the set bits form one unbroken horizontal or vertical run,
+    /// as a single ship does. An empty board is not a line.
+    pub fn is_line(&self) -> bool {
+        let mut cells = self.iter_set_bits();
+        let Some((r0, c0)) = cells.next() else {
+            return false;
+        };
+        let Some((r1, c1)) = cells.next() else {
+            return true;
+        };
+        // Row-major order lists a run's cells from its first
+        let (dr, dc) = if (r1, c1) == (r0, c0 + 1) { (0, 1) } else { (1, 0) };
+        [(r0, c0), (r1, c1)]
+            .into_iter()
+            .chain(cells)
+            .enumerate()
+            .all(|(k, cell)| cell == (r0 + k * dr, c0 + k * dc))
+    }
+
+    /// Mask of every cell in column `col`.
+    fn column_mask(col: usize) -> T {
+        (0..N).fold(T::zero(), |acc, r| acc | (T::one() << (r * N + col)))
+    }
+
+    /// Board with every cell of row `row` set; empty if `row` is off the
+    /// board.
+    pub fn row_mask(row: usize) -> Self {
+        if row >= N {
+            return Self::new();
+        }
+        let full_row = (T::one() << N) - T::one();
+        BitBoard { bits: full_row << (row * N) }
+    }
+
+    /// Board with every cell of column `col` set; empty if `col` is off the
+    /// board.
+    pub fn col_mask(col: usize) -> Self {
+        if col >= N {
+            return Self::new();
+        }
+        BitBoard {
+            bits: Self::column_mask(col),
+        }
+    }
+
+    /// Board with every cell whose `row + col` leaves `offset` over
+    /// `spacing` set: a diagonal lattice no line of `spacing` or more cells
+    /// can avoid. Empty if `spacing` is zero.
+    pub fn lattice(spacing: usize, offset: usize) -> Self {
+        if spacing == 0 {
+            return Self::new();
+        }
+        let bits = (0..N * N)
+            .filter(|i| (i / N + i % N) % spacing == offset % spacing)
+            .fold(T::zero(), |acc, i| acc | (T::one() << i));
+        BitBoard { bits }
+    }
+
+    /// Board with every cell from (`r0`, `c0`) to (`r1`, `c1`) set, corners
+    /// included and given in either order. Fails with `IndexOutOfBounds`
+    /// if a corner is off the board.
+    pub fn rect(r0: usize, c0: usize, r1: usize, c1: usize) -> Result<Self, BitBoardError> {
+        let board = Self::new();
+        board.check_bounds(r0, c0)?;
+        board.check_bounds(r1, c1)?;
+        let (top, bottom) = (r0.min(r1), r0.max(r1));
+        let (left, right) = (c0.min(c1), c0.max(c1));
+        let run = ((T::one() << (right - left + 1)) - T::one()) << left;
+        let bits = (top..=bottom).fold(T::zero(), |acc, r| acc | (run << (r * N)));
+        Ok(BitBoard { bits })
+    }
+
+    /// Board with the `len` cells from (`row`, `col`) rightwards or
+    /// downwards set: the cells a ship of that length placed there covers.
+    /// Fails with `IndexOutOfBounds` if the line leaves the board; a line
+    /// of length zero is empty.
+    pub fn line(row: usize, col: usize, len: usize, orientation: Orientation) -> Result<Self, BitBoardError> {
+        if len == 0 {
+            return Ok(Self::new());
+        }
+        match orientation {
+            Orientation::Horizontal => Self::rect(row, col, row, col + len - 1),
+            Orientation::Vertical => Self::rect(row, col, row + len - 1, col),
+        }
+    }
+
+    /// Cells where a run of `len` set bits starts, running rightwards or
+    /// downwards: the origins at which a ship of that length lies wholly on
+    /// set cells. Found with `len` whole-board shifts rather than a scan of
+    /// every origin; empty for a length of zero or past the board.
+    #[must_use]
+    pub fn run_starts(&self, len: usize, orientation: Orientation) -> Self {
+        if len == 0 {
+            return Self::new();
+        }
+        (1..len).fold(*self, |starts, k| {
+            starts
+                & match orientation {
+                    Orientation::Horizontal => self.shift_left(k),
+                    Orientation::Vertical => self.shift_up(k),
+                }
+        })
+    }
+
+    /// Number of set bits in row `row`.
+    pub fn count_row(&self, row: usize) -> usize {
+        (*self & Self::row_mask(row)).count_ones()
+    }
+
+    /// Number of set bits in column `col`.
+    pub fn count_col(&self, col: usize) -> usize {
+        (*self & Self::col_mask(col)).count_ones()
+    }
+
+    /// Mask of every cell in the columns `cols`.
+    fn columns_mask(cols: core::ops::Range<usize>) -> T {
+        cols.fold(T::zero(), |acc, c| acc | Self::column_mask(c))
+    }
+
+    /// Moves every set bit `n` rows up; bits in the top `n` rows fall off.
+    #[must_use]
+    pub fn shift_up(&self, n: usize) -> Self {
+        if n >= N {
+            return Self::new();
+        }
+        BitBoard { bits: self.bits >> (n * N) }
+    }
+
+    /// Moves every set bit `n` rows down; bits in the bottom `n` rows fall
+    /// off.
+    #[must_use]
+    pub fn shift_down(&self, n: usize) -> Self {
+        if n >= N {
+            return Self::new();
+        }
+        Self::masked(self.bits << (n * N))
+    }
+
+    /// Moves every set bit `n` columns left; bits in the first `n` columns
+    /// fall off rather than wrapping onto the row above.
+    #[must_use]
+    pub fn shift_left(&self, n: usize) -> Self {
+        if n >= N {
+            return Self::new();
+        }
+        BitBoard {
+            bits: (self.bits & !Self::columns_mask(0..n)) >> n,
+        }
+    }
+
+    /// Moves every set bit `n` columns right; bits in the last `n` columns
+    /// fall off rather than wrapping onto the row below.
+    #[must_use]
+    pub fn shift_right(&self, n: usize) -> Self {
+        if n >= N {
+            return Self::new();
+        }
+        BitBoard {
+            bits: (self.bits & !Self::columns_mask(N - n..N)) << n,
+        }
+    }
+
+    /// The board plus every cell orthogonally adjacent to a set bit, and
+    /// with `diagonal` the diagonally adjacent cells too.
+    #[must_use]
+    pub fn dilate(&self, diagonal: bool) -> Self {
+        let rows = *self | self.shift_up(1) | self.shift_down(1);
+        if diagonal {
+            rows | rows.shift_left(1) | rows.shift_right(1)
+        } else {
+            rows | self.shift_left(1) | self.shift_right(1)
+        }
+    }
+
+    /// Cells adjacent to a set bit but not set themselves; see
+    /// [`BitBoard::dilate`].
+    #[must_use]
+    pub fn neighbors(&self, diagonal: bool) -> Self {
+        self.dilate(diagonal) & !*self
+    }
 }
 
 impl<T, const N: usize> Default for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     #[inline]
     fn default() -> Self {
@@ -189,32 +518,37 @@ where
     }
 }
 
-impl<T, const N: usize> fmt::Debug for BitBoard<T, N>
+/// Collects `(row, col)` positions into a board. Positions off the board
+/// are skipped; [`BitBoard::try_from_cells`] rejects them instead.
+impl<T, const N: usize> FromIterator<(usize, usize)> for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero + fmt::Binary,
+    T: PrimInt + Unsigned + ConstZero,
 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "BitBoard<{}, {}>:", any::type_name::<T>(), N)?;
-        for r in 0..N {
-            for c in 0..N {
-                let bit = if ((self.bits >> (r * N + c)) & T::one()) != T::zero() {
-                    '■'
-                } else {
-                    '□'
-                };
-                write!(f, "{} ", bit)?;
-            }
-            writeln!(f)?;
+    fn from_iter<I: IntoIterator<Item = (usize, usize)>>(iter: I) -> Self {
+        let mut board = Self::new();
+        board.extend(iter);
+        board
+    }
+}
+
+/// Sets each `(row, col)` position, skipping any off the board.
+impl<T, const N: usize> Extend<(usize, usize)> for BitBoard<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    fn extend<I: IntoIterator<Item = (usize, usize)>>(&mut self, iter: I) {
+        for (r, c) in iter {
+            let _ = self.set(r, c);
         }
-        Ok(())
     }
 }
 
-impl<T, const N: usize> fmt::Display for BitBoard<T, N>
+impl<T, const N: usize> BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero + fmt::Binary,
+    T: PrimInt + Unsigned + ConstZero,
 {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    /// Writes the grid as rows of `■`/`□`, without a trailing newline.
+    fn write_grid(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         for r in 0..N {
             for c in 0..N {
                 let bit = if ((self.bits >> (r * N + c)) & T::one()) != T::zero() {
@@ -232,11 +566,80 @@ where
     }
 }
 
+impl<T, const N: usize> fmt::Debug for BitBoard<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "BitBoard<{}, {}>:", any::type_name::<T>(), N)?;
+        self.write_grid(f)?;
+        writeln!(f)
+    }
+}
+
+impl<T, const N: usize> fmt::Display for BitBoard<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_grid(f)
+    }
+}
+
+/// Compact single-line form for logs: the raw bits as hex, zero-padded to
+/// the board size (`{:#x}` adds a `0x` prefix).
+impl<T, const N: usize> fmt::LowerHex for BitBoard<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if f.alternate() {
+            f.write_str("0x")?;
+        }
+        let digits = Self::BOARD_BITS.div_ceil(4).max(1);
+        let nibble = T::from(0xf).unwrap();
+        for i in (0..digits).rev() {
+            let d = ((self.bits >> (i * 4)) & nibble).to_u32().unwrap_or(0);
+            let ch = char::from_digit(d, 16).unwrap_or('0');
+            write!(f, "{}", ch)?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the connected components of a bitboard; see [`BitBoard::clusters`].
+#[derive(Clone, Copy)]
+pub struct Clusters<T, const N: usize>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    remaining: BitBoard<T, N>,
+}
+
+impl<T, const N: usize> Iterator for Clusters<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    type Item = BitBoard<T, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining.is_empty() {
+            return None;
+        }
+        let seed = self.remaining.bits & (!self.remaining.bits + T::one());
+        let cluster = self.remaining.fill_from(BitBoard { bits: seed });
+        self.remaining = BitBoard {
+            bits: self.remaining.bits & !cluster.bits,
+        };
+        Some(cluster)
+    }
+}
+
 /// Iterator over the set bits of a bitboard.
 #[derive(Clone, Copy)]
 pub struct SetBits<'a, T, const N: usize>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     board: &'a BitBoard<T, N>,
     idx: usize,
@@ -244,7 +647,7 @@ where
 
 impl<'a, T, const N: usize> Iterator for SetBits<'a, T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     type Item = (usize, usize);
     #[inline]
@@ -260,64 +663,179 @@ where
     }
 }
 
-/// Macro for compile-time assertion of size and creation.
+/// Iterator over the clear cells of a bitboard; see [`BitBoard::iter_clear_bits`].
+#[derive(Clone, Copy)]
+pub struct ClearBits<T, const N: usize>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    clear: BitBoard<T, N>,
+    idx: usize,
+}
+
+impl<T, const N: usize> Iterator for ClearBits<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    type Item = (usize, usize);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.idx < N * N {
+            let idx = self.idx;
+            self.idx += 1;
+            if ((self.clear.bits >> idx) & T::one()) != T::zero() {
+                return Some((idx / N, idx % N));
+            }
+        }
+        None
+    }
+}
+
+/// Builds a bitboard with its size checked at compile time: empty, or from
+/// a picture of `N` rows of `#` (set) and `.` (clear), spaces ignored. The
+/// picture is parsed in a `const`, so a malformed grid fails the build.
+///
+/// ```
+/// use battleship::{bitboard, BitBoard};
+///
+/// let empty = bitboard!(u16, 4);
+/// const L_SHAPE: BitBoard<u16, 4> = bitboard!(u16, 4;
+///     "# . . .",
+///     "# . . .",
+///     "# # . .",
+///     ". . . .",
+/// );
+/// assert!(empty.is_empty());
+/// assert_eq!(L_SHAPE.count_ones(), 4);
+/// assert!(L_SHAPE.get(2, 1).unwrap());
+/// ```
 #[macro_export]
 macro_rules! bitboard {
     ($T:ty, $N:expr) => {{
         const _ASSERT: [(); 1] = [(); ($N * $N <= core::mem::size_of::<$T>() * 8) as usize];
         let _ = _ASSERT;
-        $crate::BitBoard::<$T, $N>::new()
+        $crate::BitBoard::<$T, { $N }>::new()
     }};
+    ($T:ty, $N:expr; $($row:expr),+ $(,)?) => {{
+        const _ASSERT: [(); 1] = [(); ($N * $N <= core::mem::size_of::<$T>() * 8) as usize];
+        const BOARD: $crate::BitBoard<$T, { $N }> = $crate::BitBoard::<$T, { $N }>::from_rows([$($row),+]);
+        let _ = _ASSERT;
+        BOARD
+    }};
+}
+
+/// `const` constructors for boards stored in a primitive unsigned integer.
+macro_rules! impl_const_ctors {
+    ($($t:ty),*) => {$(
+        impl<const N: usize> BitBoard<$t, N> {
+            /// Creates a bitboard from the raw integer, masking out upper bits.
+            #[inline]
+            pub const fn from_raw(raw: $t) -> Self {
+                let mask = if N * N == <$t>::BITS as usize {
+                    <$t>::MAX
+                } else {
+                    (1 << (N * N)) - 1
+                };
+                BitBoard { bits: raw & mask }
+            }
+
+            /// Returns a copy with the bit at (row, col) set.
+            ///
+            /// Builder-style counterpart of [`BitBoard::set`] usable in
+            /// `const` items; panics (a compile error in const context) if
+            /// the position is out of bounds.
+            #[inline]
+            #[must_use]
+            pub const fn with(self, row: usize, col: usize) -> Self {
+                assert!(row < N && col < N, "BitBoard::with: position out of bounds");
+                BitBoard {
+                    bits: self.bits | (1 << (row * N + col)),
+                }
+            }
+
+            /// Creates a bitboard from `N` rows of `#` (set) and `.` (clear),
+            /// ignoring spaces; see the [`bitboard!`](crate::bitboard) macro.
+            /// Panics (a compile error in const context) if a row does not
+            /// hold exactly `N` cells or uses another character.
+            pub const fn from_rows(rows: [&str; N]) -> Self {
+                let mut board = Self::from_raw(0);
+                let mut r = 0;
+                while r < N {
+                    let bytes = rows[r].as_bytes();
+                    let mut c = 0;
+                    let mut i = 0;
+                    while i < bytes.len() {
+                        match bytes[i] {
+                            b' ' => {}
+                            b'.' => c += 1,
+                            b'#' => {
+                                assert!(c < N, "BitBoard::from_rows: row too long");
+                                board = board.with(r, c);
+                                c += 1;
+                            }
+                            _ => panic!("BitBoard::from_rows: cells must be '#' or '.'"),
+                        }
+                        i += 1;
+                    }
+                    assert!(c == N, "BitBoard::from_rows: row must hold N cells");
+                    r += 1;
+                }
+                board
+            }
+        }
+    )*};
 }
 
+impl_const_ctors!(u8, u16, u32, u64, u128, usize);
+
 /// Bitwise AND for combining two bitboards.
 impl<T, const N: usize> BitAnd for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     type Output = Self;
     fn bitand(self, rhs: Self) -> Self {
-        BitBoard::from_raw(self.into_raw() & rhs.into_raw())
+        BitBoard::masked(self.into_raw() & rhs.into_raw())
     }
 }
 
 /// Bitwise OR for combining two bitboards.
 impl<T, const N: usize> BitOr for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     type Output = Self;
     fn bitor(self, rhs: Self) -> Self {
-        BitBoard::from_raw(self.into_raw() | rhs.into_raw())
+        BitBoard::masked(self.into_raw() | rhs.into_raw())
     }
 }
 
 /// Bitwise XOR for combining two bitboards.
 impl<T, const N: usize> BitXor for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     type Output = Self;
     fn bitxor(self, rhs: Self) -> Self {
-        BitBoard::from_raw(self.into_raw() ^ rhs.into_raw())
+        BitBoard::masked(self.into_raw() ^ rhs.into_raw())
     }
 }
 
 /// Bitwise NOT for inverting a bitboard (within board bounds).
 impl<T, const N: usize> Not for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     type Output = Self;
     #[inline]
     fn not(self) -> Self {
-        Self::from_raw(!self.bits)
+        Self::masked(!self.bits)
     }
 }
 
 impl<T, const N: usize> BitAndAssign for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     #[inline]
     fn bitand_assign(&mut self, rhs: Self) {
@@ -327,7 +845,7 @@ where
 
 impl<T, const N: usize> BitOrAssign for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     #[inline]
     fn bitor_assign(&mut self, rhs: Self) {
@@ -337,7 +855,7 @@ where
 
 impl<T, const N: usize> BitXorAssign for BitBoard<T, N>
 where
-    T: PrimInt + Unsigned + Zero,
+    T: PrimInt + Unsigned + ConstZero,
 {
     #[inline]
     fn bitxor_assign(&mut self, rhs: Self) {
@@ -348,6 +866,7 @@ where
 /// Convenience aliases for common board sizes.
 #[allow(dead_code)]
 pub mod aliases {
+    use super::super::bitboard_wide::BitBoardWide;
     use super::BitBoard;
     /// 8×8 board in `u64`.
     pub type BB8x8 = BitBoard<u64, 8>;
@@ -363,4 +882,8 @@ pub mod aliases {
     pub type BB64<const N: usize> = BitBoard<u64, N>;
     /// N×N board in `u128`.
     pub type BB128<const N: usize> = BitBoard<u128, N>;
+    /// 12×12 board in three `u64` words.
+    pub type BB12x12 = BitBoardWide<3, 12>;
+    /// 16×16 board in four `u64` words.
+    pub type BB16x16 = BitBoardWide<4, 16>;
 }
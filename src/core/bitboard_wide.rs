@@ -0,0 +1,750 @@
+//! A fixed-size bitboard spread over several `u64` words.
+//!
+//! [`BitBoard`] keeps the whole grid in one primitive integer, which caps
+//! it at 11×11 in a `u128`. `BitBoardWide` stores cell `row * N + col` in
+//! bit `idx % 64` of word `idx / 64`, so a `WORDS`-word board holds any `N`
+//! with `N * N <= 64 * WORDS`, and offers the same cell, iteration, shift
+//! and bitwise API. Unlike the byte-backed [`BitGrid`](super::bitgrid::BitGrid)
+//! it works a word at a time and serializes. Like `BitBoard` it is `no_std`,
+//! never allocates, converts to and from `BitBoard`, and implements
+//! [`CellGrid`].
+
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::{fmt, ops::Range};
+use num_traits::{ConstZero, PrimInt, Unsigned};
+
+use super::bitboard::{BitBoard, BitBoardError};
+use super::bitgrid::CellGrid;
+use super::ship::Orientation;
+
+/// An N×N bitboard stored in `WORDS` 64-bit words.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitBoardWide<const WORDS: usize, const N: usize> {
+    words: [u64; WORDS],
+}
+
+impl<const WORDS: usize, const N: usize> BitBoardWide<WORDS, N> {
+    /// Number of usable bits in the board (`N * N`).
+    const BOARD_BITS: usize = N * N;
+
+    /// Bits of word `w` that lie on the board.
+    #[inline]
+    fn word_mask(w: usize) -> u64 {
+        let start = w * 64;
+        if Self::BOARD_BITS >= start + 64 {
+            !0
+        } else if Self::BOARD_BITS <= start {
+            0
+        } else {
+            (1 << (Self::BOARD_BITS - start)) - 1
+        }
+    }
+
+    /// Wraps raw words, masking out bits beyond the board.
+    #[inline]
+    fn masked(mut words: [u64; WORDS]) -> Self {
+        for (w, word) in words.iter_mut().enumerate() {
+            *word &= Self::word_mask(w);
+        }
+        BitBoardWide { words }
+    }
+
+    /// Create a new empty bitboard (all bits cleared) without size check.
+    #[inline]
+    pub const fn new() -> Self {
+        BitBoardWide { words: [0; WORDS] }
+    }
+
+    /// Fallible constructor: returns `Err(SizeTooLarge)` if N*N exceeds
+    /// `64 * WORDS`.
+    pub fn try_new() -> Result<Self, BitBoardError> {
+        let capacity = WORDS * 64;
+        if Self::BOARD_BITS > capacity {
+            Err(BitBoardError::SizeTooLarge { n: N, capacity })
+        } else {
+            Ok(Self::new())
+        }
+    }
+
+    /// Creates a bitboard from raw words, masking out bits beyond the board.
+    #[inline]
+    pub fn from_raw(words: [u64; WORDS]) -> Self {
+        Self::masked(words)
+    }
+
+    /// Consumes the board and returns the raw words.
+    #[inline]
+    pub fn into_raw(self) -> [u64; WORDS] {
+        self.words
+    }
+
+    /// Returns the number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns true if no bits are set.
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    #[inline]
+    fn check_bounds(&self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        if row >= N || col >= N {
+            Err(BitBoardError::IndexOutOfBounds { row, col })
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    fn bit(&self, idx: usize) -> bool {
+        (self.words[idx / 64] >> (idx % 64)) & 1 != 0
+    }
+
+    /// Gets the bit at (row, col).
+    pub fn get(&self, row: usize, col: usize) -> Result<bool, BitBoardError> {
+        self.check_bounds(row, col)?;
+        Ok(self.bit(row * N + col))
+    }
+
+    /// Sets the bit at (row, col) to 1.
+    pub fn set(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        self.check_bounds(row, col)?;
+        let idx = row * N + col;
+        self.words[idx / 64] |= 1 << (idx % 64);
+        Ok(())
+    }
+
+    /// Clears the bit at (row, col) to 0.
+    pub fn clear(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        self.check_bounds(row, col)?;
+        let idx = row * N + col;
+        self.words[idx / 64] &= !(1 << (idx % 64));
+        Ok(())
+    }
+
+    /// Toggles the bit at (row, col).
+    pub fn toggle(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        self.check_bounds(row, col)?;
+        let idx = row * N + col;
+        self.words[idx / 64] ^= 1 << (idx % 64);
+        Ok(())
+    }
+
+    /// Sets all board bits to `1`.
+    #[inline]
+    pub fn fill(&mut self) {
+        *self = Self::masked([!0; WORDS]);
+    }
+
+    /// Clears all bits to `0`.
+    #[inline]
+    pub fn clear_all(&mut self) {
+        self.words = [0; WORDS];
+    }
+
+    /// Creates a bitboard from an iterator over `(row, col)` positions.
+    #[inline]
+    pub fn try_from_cells<I>(iter: I) -> Result<Self, BitBoardError>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut board = Self::new();
+        for (r, c) in iter {
+            board.set(r, c)?;
+        }
+        Ok(board)
+    }
+
+    /// Returns a copy with the bit at (row, col) set; usable in `const`
+    /// items, where an out-of-bounds position fails the build.
+    #[inline]
+    #[must_use]
+    pub const fn with(mut self, row: usize, col: usize) -> Self {
+        assert!(row < N && col < N, "BitBoardWide::with: position out of bounds");
+        let idx = row * N + col;
+        self.words[idx / 64] |= 1 << (idx % 64);
+        self
+    }
+
+    /// Creates a bitboard with the cells set that are `true` in `grid`,
+    /// indexed `grid[row][col]`.
+    pub fn from_grid(grid: &[[bool; N]; N]) -> Self {
+        let mut board = Self::new();
+        for (r, row) in grid.iter().enumerate() {
+            for (c, &cell) in row.iter().enumerate() {
+                if cell {
+                    let _ = board.set(r, c);
+                }
+            }
+        }
+        board
+    }
+
+    /// The board as a matrix of cells, indexed `[row][col]`.
+    pub fn to_grid(&self) -> [[bool; N]; N] {
+        let mut grid = [[false; N]; N];
+        for (r, c) in self.iter_set_bits() {
+            grid[r][c] = true;
+        }
+        grid
+    }
+
+    /// Iterator over the set bits of the board.
+    #[inline]
+    pub fn iter_set_bits(&self) -> SetBitsWide<'_, WORDS, N> {
+        SetBitsWide { board: self, idx: 0 }
+    }
+
+    /// Iterator over the clear cells of the board, in row-major order.
+    #[inline]
+    pub fn iter_clear_bits(&self) -> ClearBitsWide<WORDS, N> {
+        ClearBitsWide {
+            clear: !*self,
+            idx: 0,
+        }
+    }
+
+    /// Cells not yet fired at: neither in `hits` nor in `misses`.
+    #[inline]
+    pub fn unknown(hits: &Self, misses: &Self) -> Self {
+        !(*hits | *misses)
+    }
+
+    /// Iterator over the orthogonally connected groups of set bits, each
+    /// yielded as its own board, in order of their lowest set bit.
+    #[inline]
+    pub fn clusters(&self) -> ClustersWide<WORDS, N> {
+        ClustersWide { remaining: *self }
+    }
+
+    /// The orthogonally connected group of set bits containing (row, col);
+    /// empty if that cell is clear or off the board.
+    pub fn flood_fill(&self, row: usize, col: usize) -> Self {
+        if !self.get(row, col).unwrap_or(false) {
+            return Self::new();
+        }
+        let mut seed = Self::new();
+        let _ = seed.set(row, col);
+        self.fill_from(seed)
+    }
+
+    /// `seed` grown orthogonally through the set bits until it stops.
+    fn fill_from(&self, seed: Self) -> Self {
+        let mut filled = seed;
+        loop {
+            let grown = filled.dilate(false) & *self;
+            if grown == filled {
+                return filled;
+            }
+            filled = grown;
+        }
+    }
+
+    /// Whether the set bits form one unbroken horizontal or vertical run;
+    /// see [`BitBoard::is_line`].
+    pub fn is_line(&self) -> bool {
+        let mut cells = self.iter_set_bits();
+        let Some((r0, c0)) = cells.next() else {
+            return false;
+        };
+        let Some((r1, c1)) = cells.next() else {
+            return true;
+        };
+        let (dr, dc) = if (r1, c1) == (r0, c0 + 1) { (0, 1) } else { (1, 0) };
+        [(r0, c0), (r1, c1)]
+            .into_iter()
+            .chain(cells)
+            .enumerate()
+            .all(|(k, cell)| cell == (r0 + k * dr, c0 + k * dc))
+    }
+
+    /// Board with every cell whose `row + col` leaves `offset` over
+    /// `spacing` set; see [`BitBoard::lattice`]. Empty if `spacing` is zero.
+    pub fn lattice(spacing: usize, offset: usize) -> Self {
+        let mut board = Self::new();
+        if spacing == 0 {
+            return board;
+        }
+        for i in (0..Self::BOARD_BITS).filter(|i| (i / N + i % N) % spacing == offset % spacing) {
+            board.words[i / 64] |= 1 << (i % 64);
+        }
+        board
+    }
+
+    /// Board with every cell from (`r0`, `c0`) to (`r1`, `c1`) set, corners
+    /// included and given in either order. Fails with `IndexOutOfBounds`
+    /// if a corner is off the board.
+    pub fn rect(r0: usize, c0: usize, r1: usize, c1: usize) -> Result<Self, BitBoardError> {
+        let mut board = Self::new();
+        board.check_bounds(r0, c0)?;
+        board.check_bounds(r1, c1)?;
+        for r in r0.min(r1)..=r0.max(r1) {
+            for c in c0.min(c1)..=c0.max(c1) {
+                board.set(r, c)?;
+            }
+        }
+        Ok(board)
+    }
+
+    /// Board with the `len` cells from (`row`, `col`) rightwards or
+    /// downwards set; see [`BitBoard::line`].
+    pub fn line(row: usize, col: usize, len: usize, orientation: Orientation) -> Result<Self, BitBoardError> {
+        if len == 0 {
+            return Ok(Self::new());
+        }
+        match orientation {
+            Orientation::Horizontal => Self::rect(row, col, row, col + len - 1),
+            Orientation::Vertical => Self::rect(row, col, row + len - 1, col),
+        }
+    }
+
+    /// Cells where a run of `len` set bits starts, running rightwards or
+    /// downwards; see [`BitBoard::run_starts`].
+    #[must_use]
+    pub fn run_starts(&self, len: usize, orientation: Orientation) -> Self {
+        if len == 0 {
+            return Self::new();
+        }
+        (1..len).fold(*self, |starts, k| {
+            starts
+                & match orientation {
+                    Orientation::Horizontal => self.shift_left(k),
+                    Orientation::Vertical => self.shift_up(k),
+                }
+        })
+    }
+
+    /// Moves every bit `k` places towards the high end of the storage.
+    fn shl_bits(&self, k: usize) -> Self {
+        let (skip, shift) = (k / 64, k % 64);
+        Self::masked(core::array::from_fn(|i| {
+            let Some(src) = i.checked_sub(skip) else {
+                return 0;
+            };
+            let carry = if shift > 0 && src > 0 { self.words[src - 1] >> (64 - shift) } else { 0 };
+            (self.words[src] << shift) | carry
+        }))
+    }
+
+    /// Moves every bit `k` places towards the low end of the storage.
+    fn shr_bits(&self, k: usize) -> Self {
+        let (skip, shift) = (k / 64, k % 64);
+        let words = core::array::from_fn(|i| {
+            let src = i + skip;
+            if src >= WORDS {
+                return 0;
+            }
+            let carry = if shift > 0 && src + 1 < WORDS { self.words[src + 1] << (64 - shift) } else { 0 };
+            (self.words[src] >> shift) | carry
+        });
+        BitBoardWide { words }
+    }
+
+    /// Board with every cell in the columns `cols` set.
+    fn columns_mask(cols: Range<usize>) -> Self {
+        let mut mask = Self::new();
+        for r in 0..N {
+            for c in cols.clone() {
+                let _ = mask.set(r, c);
+            }
+        }
+        mask
+    }
+
+    /// Moves every set bit `n` rows up; bits in the top `n` rows fall off.
+    #[must_use]
+    pub fn shift_up(&self, n: usize) -> Self {
+        if n >= N {
+            return Self::new();
+        }
+        self.shr_bits(n * N)
+    }
+
+    /// Moves every set bit `n` rows down; bits in the bottom `n` rows fall
+    /// off.
+    #[must_use]
+    pub fn shift_down(&self, n: usize) -> Self {
+        if n >= N {
+            return Self::new();
+        }
+        self.shl_bits(n * N)
+    }
+
+    /// Moves every set bit `n` columns left; bits in the first `n` columns
+    /// fall off rather than wrapping onto the row above.
+    #[must_use]
+    pub fn shift_left(&self, n: usize) -> Self {
+        if n >= N {
+            return Self::new();
+        }
+        (*self & !Self::columns_mask(0..n)).shr_bits(n)
+    }
+
+    /// Moves every set bit `n` columns right; bits in the last `n` columns
+    /// fall off rather than wrapping onto the row below.
+    #[must_use]
+    pub fn shift_right(&self, n: usize) -> Self {
+        if n >= N {
+            return Self::new();
+        }
+        (*self & !Self::columns_mask(N - n..N)).shl_bits(n)
+    }
+
+    /// The board plus every cell orthogonally adjacent to a set bit, and
+    /// with `diagonal` the diagonally adjacent cells too.
+    #[must_use]
+    pub fn dilate(&self, diagonal: bool) -> Self {
+        let rows = *self | self.shift_up(1) | self.shift_down(1);
+        if diagonal {
+            rows | rows.shift_left(1) | rows.shift_right(1)
+        } else {
+            rows | self.shift_left(1) | self.shift_right(1)
+        }
+    }
+
+    /// Cells adjacent to a set bit but not set themselves; see
+    /// [`BitBoardWide::dilate`].
+    #[must_use]
+    pub fn neighbors(&self, diagonal: bool) -> Self {
+        self.dilate(diagonal) & !*self
+    }
+
+    /// Board with every cell of row `row` set; empty if `row` is off the
+    /// board.
+    pub fn row_mask(row: usize) -> Self {
+        let mut mask = Self::new();
+        if row < N {
+            for c in 0..N {
+                let _ = mask.set(row, c);
+            }
+        }
+        mask
+    }
+
+    /// Board with every cell of column `col` set; empty if `col` is off the
+    /// board.
+    pub fn col_mask(col: usize) -> Self {
+        if col >= N {
+            return Self::new();
+        }
+        Self::columns_mask(col..col + 1)
+    }
+
+    /// Number of set bits in row `row`.
+    pub fn count_row(&self, row: usize) -> usize {
+        (*self & Self::row_mask(row)).count_ones()
+    }
+
+    /// Number of set bits in column `col`.
+    pub fn count_col(&self, col: usize) -> usize {
+        (*self & Self::col_mask(col)).count_ones()
+    }
+
+    /// Rotates the board a quarter turn clockwise: `(r, c)` moves to `(c, N-1-r)`.
+    pub fn rotate90(&self) -> Self {
+        self.map_cells(|r, c| (c, N - 1 - r))
+    }
+
+    /// Rotates the board a half turn: `(r, c)` moves to `(N-1-r, N-1-c)`.
+    pub fn rotate180(&self) -> Self {
+        self.map_cells(|r, c| (N - 1 - r, N - 1 - c))
+    }
+
+    /// Rotates the board a quarter turn counter-clockwise: `(r, c)` moves to `(N-1-c, r)`.
+    pub fn rotate270(&self) -> Self {
+        self.map_cells(|r, c| (N - 1 - c, r))
+    }
+
+    /// Mirrors the board left to right: `(r, c)` moves to `(r, N-1-c)`.
+    pub fn mirror_h(&self) -> Self {
+        self.map_cells(|r, c| (r, N - 1 - c))
+    }
+
+    /// Mirrors the board top to bottom: `(r, c)` moves to `(N-1-r, c)`.
+    pub fn mirror_v(&self) -> Self {
+        self.map_cells(|r, c| (N - 1 - r, c))
+    }
+
+    /// The board under each of the eight rotations and reflections of the
+    /// square, as [`BitBoard::symmetries`](super::bitboard::BitBoard::symmetries).
+    pub fn symmetries(&self) -> [Self; 8] {
+        let h = self.mirror_h();
+        [
+            *self,
+            self.rotate90(),
+            self.rotate180(),
+            self.rotate270(),
+            h,
+            h.rotate90(),
+            h.rotate180(),
+            h.rotate270(),
+        ]
+    }
+
+    /// The symmetry of the board with the smallest raw words, compared from
+    /// the highest word down; see [`BitBoard::canonical`](super::bitboard::BitBoard::canonical).
+    pub fn canonical(&self) -> Self {
+        self.symmetries()
+            .into_iter()
+            .min_by(|a, b| a.words.iter().rev().cmp(b.words.iter().rev()))
+            .unwrap_or(*self)
+    }
+
+    /// Moves every set bit to `f(row, col)`, which must stay on the board.
+    fn map_cells<F: Fn(usize, usize) -> (usize, usize)>(&self, f: F) -> Self {
+        let mut out = Self::new();
+        for (r, c) in self.iter_set_bits() {
+            let (nr, nc) = f(r, c);
+            let _ = out.set(nr, nc);
+        }
+        out
+    }
+
+    /// Writes the grid as rows of `■`/`□`, without a trailing newline.
+    fn write_grid(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in 0..N {
+            for c in 0..N {
+                let bit = if self.bit(r * N + c) { '■' } else { '□' };
+                write!(f, "{} ", bit)?;
+            }
+            if r + 1 < N {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const WORDS: usize, const N: usize> Default for BitBoardWide<WORDS, N> {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const WORDS: usize, const N: usize> fmt::Debug for BitBoardWide<WORDS, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "BitBoardWide<{}, {}>:", WORDS, N)?;
+        self.write_grid(f)?;
+        writeln!(f)
+    }
+}
+
+impl<const WORDS: usize, const N: usize> fmt::Display for BitBoardWide<WORDS, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write_grid(f)
+    }
+}
+
+impl<T, const WORDS: usize, const N: usize> From<BitBoard<T, N>> for BitBoardWide<WORDS, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    fn from(board: BitBoard<T, N>) -> Self {
+        let mut wide = Self::new();
+        for (r, c) in board.iter_set_bits() {
+            let idx = r * N + c;
+            wide.words[idx / 64] |= 1 << (idx % 64);
+        }
+        wide
+    }
+}
+
+impl<T, const WORDS: usize, const N: usize> From<BitBoardWide<WORDS, N>> for BitBoard<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    fn from(wide: BitBoardWide<WORDS, N>) -> Self {
+        let mut board = BitBoard::new();
+        for (r, c) in wide.iter_set_bits() {
+            board.set(r, c).expect("cell within N×N");
+        }
+        board
+    }
+}
+
+impl<const WORDS: usize, const N: usize> CellGrid for BitBoardWide<WORDS, N> {
+    const SIDE: usize = N;
+
+    fn empty() -> Self {
+        Self::new()
+    }
+    fn get(&self, row: usize, col: usize) -> Result<bool, BitBoardError> {
+        BitBoardWide::get(self, row, col)
+    }
+    fn set(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        BitBoardWide::set(self, row, col)
+    }
+    fn clear(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        BitBoardWide::clear(self, row, col)
+    }
+    fn count_ones(&self) -> usize {
+        BitBoardWide::count_ones(self)
+    }
+    fn is_empty(&self) -> bool {
+        BitBoardWide::is_empty(self)
+    }
+}
+
+/// Iterator over the set bits of a wide bitboard.
+#[derive(Clone, Copy)]
+pub struct SetBitsWide<'a, const WORDS: usize, const N: usize> {
+    board: &'a BitBoardWide<WORDS, N>,
+    idx: usize,
+}
+
+impl<const WORDS: usize, const N: usize> Iterator for SetBitsWide<'_, WORDS, N> {
+    type Item = (usize, usize);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.idx < N * N {
+            let idx = self.idx;
+            self.idx += 1;
+            if self.board.bit(idx) {
+                return Some((idx / N, idx % N));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the clear cells of a wide bitboard; see
+/// [`BitBoardWide::iter_clear_bits`].
+#[derive(Clone, Copy)]
+pub struct ClearBitsWide<const WORDS: usize, const N: usize> {
+    clear: BitBoardWide<WORDS, N>,
+    idx: usize,
+}
+
+impl<const WORDS: usize, const N: usize> Iterator for ClearBitsWide<WORDS, N> {
+    type Item = (usize, usize);
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.idx < N * N {
+            let idx = self.idx;
+            self.idx += 1;
+            if self.clear.bit(idx) {
+                return Some((idx / N, idx % N));
+            }
+        }
+        None
+    }
+}
+
+/// Iterator over the connected components of a wide bitboard; see
+/// [`BitBoardWide::clusters`].
+#[derive(Clone, Copy)]
+pub struct ClustersWide<const WORDS: usize, const N: usize> {
+    remaining: BitBoardWide<WORDS, N>,
+}
+
+impl<const WORDS: usize, const N: usize> Iterator for ClustersWide<WORDS, N> {
+    type Item = BitBoardWide<WORDS, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (row, col) = self.remaining.iter_set_bits().next()?;
+        let cluster = self.remaining.flood_fill(row, col);
+        self.remaining &= !cluster;
+        Some(cluster)
+    }
+}
+
+/// Applies a word-wise operator to two boards.
+fn zip_words<const WORDS: usize>(a: [u64; WORDS], b: [u64; WORDS], op: impl Fn(u64, u64) -> u64) -> [u64; WORDS] {
+    core::array::from_fn(|i| op(a[i], b[i]))
+}
+
+/// Bitwise AND for combining two bitboards.
+impl<const WORDS: usize, const N: usize> BitAnd for BitBoardWide<WORDS, N> {
+    type Output = Self;
+    fn bitand(self, rhs: Self) -> Self {
+        Self::masked(zip_words(self.words, rhs.words, |a, b| a & b))
+    }
+}
+
+/// Bitwise OR for combining two bitboards.
+impl<const WORDS: usize, const N: usize> BitOr for BitBoardWide<WORDS, N> {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self::masked(zip_words(self.words, rhs.words, |a, b| a | b))
+    }
+}
+
+/// Bitwise XOR for combining two bitboards.
+impl<const WORDS: usize, const N: usize> BitXor for BitBoardWide<WORDS, N> {
+    type Output = Self;
+    fn bitxor(self, rhs: Self) -> Self {
+        Self::masked(zip_words(self.words, rhs.words, |a, b| a ^ b))
+    }
+}
+
+/// Bitwise NOT for inverting a bitboard (within board bounds).
+impl<const WORDS: usize, const N: usize> Not for BitBoardWide<WORDS, N> {
+    type Output = Self;
+    #[inline]
+    fn not(self) -> Self {
+        Self::masked(self.words.map(|w| !w))
+    }
+}
+
+impl<const WORDS: usize, const N: usize> BitAndAssign for BitBoardWide<WORDS, N> {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = *self & rhs;
+    }
+}
+
+impl<const WORDS: usize, const N: usize> BitOrAssign for BitBoardWide<WORDS, N> {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl<const WORDS: usize, const N: usize> BitXorAssign for BitBoardWide<WORDS, N> {
+    #[inline]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        *self = *self ^ rhs;
+    }
+}
+
+/// Serialized as a tuple of `WORDS` words, lowest first: serde's derives
+/// only cover arrays of up to 32 elements.
+#[cfg(feature = "serde")]
+impl<const WORDS: usize, const N: usize> serde::Serialize for BitBoardWide<WORDS, N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeTuple;
+        let mut tuple = serializer.serialize_tuple(WORDS)?;
+        for word in &self.words {
+            tuple.serialize_element(word)?;
+        }
+        tuple.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, const WORDS: usize, const N: usize> serde::Deserialize<'de> for BitBoardWide<WORDS, N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Words<const WORDS: usize>;
+
+        impl<'de, const WORDS: usize> serde::de::Visitor<'de> for Words<WORDS> {
+            type Value = [u64; WORDS];
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "a tuple of {} words", WORDS)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut words = [0u64; WORDS];
+                for (i, word) in words.iter_mut().enumerate() {
+                    *word = seq.next_element()?.ok_or_else(|| serde::de::Error::invalid_length(i, &self))?;
+                }
+                Ok(words)
+            }
+        }
+
+        deserializer.deserialize_tuple(WORDS, Words::<WORDS>).map(Self::masked)
+    }
+}
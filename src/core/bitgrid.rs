@@ -0,0 +1,350 @@
+//! Byte-array backed bitboard for grids too large for a primitive integer.
+//!
+//! [`BitGrid<N, B>`] stores an `N×N` grid in `[u8; B]`, where `B` must be at
+//! least `ceil(N*N / 8)` (checked at compile time). It mirrors the
+//! [`BitBoard`] API and converts to and from it, and both types implement
+//! [`CellGrid`] so code can stay generic over the representation.
+
+use super::bitboard::{BitBoard, BitBoardError};
+use core::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+use core::fmt;
+use num_traits::{ConstZero, PrimInt, Unsigned};
+
+/// Cell-level operations shared by [`BitBoard`] and [`BitGrid`].
+pub trait CellGrid: Copy {
+    /// Side length of the square grid.
+    const SIDE: usize;
+
+    /// Empty grid.
+    fn empty() -> Self;
+    /// Gets the bit at (row, col).
+    fn get(&self, row: usize, col: usize) -> Result<bool, BitBoardError>;
+    /// Sets the bit at (row, col) to 1.
+    fn set(&mut self, row: usize, col: usize) -> Result<(), BitBoardError>;
+    /// Clears the bit at (row, col) to 0.
+    fn clear(&mut self, row: usize, col: usize) -> Result<(), BitBoardError>;
+    /// Number of set bits.
+    fn count_ones(&self) -> usize;
+    /// Returns true if no bits are set.
+    fn is_empty(&self) -> bool;
+}
+
+/// A fixed-size N×N bitboard stored in `B` bytes, row-major, least
+/// significant bit first (the same layout as [`BitBoard`]).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitGrid<const N: usize, const B: usize> {
+    bytes: [u8; B],
+}
+
+/// Number of bytes needed for an `n×n` [`BitGrid`].
+pub const fn bitgrid_bytes(n: usize) -> usize {
+    (n * n).div_ceil(8)
+}
+
+impl<const N: usize, const B: usize> BitGrid<N, B> {
+    /// Number of usable bits in the board (`N * N`).
+    const BOARD_BITS: usize = N * N;
+
+    /// Compile-time check that `B` bytes can hold the board.
+    const FITS: () = assert!(B * 8 >= N * N, "BitGrid: B bytes too small for N*N bits");
+
+    /// Create a new empty grid (all bits cleared).
+    #[inline]
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let _ = Self::FITS;
+        BitGrid { bytes: [0; B] }
+    }
+
+    /// Returns the number of set bits
+    pub fn count_ones(&self) -> usize {
+        self.bytes.iter().map(|b| b.count_ones() as usize).sum()
+    }
+
+    /// Returns true if no bits are set.
+    pub fn is_empty(&self) -> bool {
+        self.bytes.iter().all(|&b| b == 0)
+    }
+
+    /// Gets the bit at (row, col).
+    pub fn get(&self, row: usize, col: usize) -> Result<bool, BitBoardError> {
+        let idx = Self::index(row, col)?;
+        Ok(self.bit(idx))
+    }
+
+    /// Sets the bit at (row, col) to 1.
+    pub fn set(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        let idx = Self::index(row, col)?;
+        self.bytes[idx / 8] |= 1 << (idx % 8);
+        Ok(())
+    }
+
+    /// Clears the bit at (row, col) to 0.
+    pub fn clear(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        let idx = Self::index(row, col)?;
+        self.bytes[idx / 8] &= !(1 << (idx % 8));
+        Ok(())
+    }
+
+    /// Toggles the bit at (row, col).
+    pub fn toggle(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        let idx = Self::index(row, col)?;
+        self.bytes[idx / 8] ^= 1 << (idx % 8);
+        Ok(())
+    }
+
+    /// Sets all board bits to `1`.
+    pub fn fill(&mut self) {
+        self.bytes = [0xff; B];
+        self.mask_tail();
+    }
+
+    /// Clears all bits to `0`.
+    #[inline]
+    pub fn clear_all(&mut self) {
+        self.bytes = [0; B];
+    }
+
+    /// The underlying bytes.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8; B] {
+        &self.bytes
+    }
+
+    /// Wraps raw bytes, masking out bits beyond the board.
+    pub fn from_bytes(bytes: [u8; B]) -> Self {
+        let mut grid = Self::new();
+        grid.bytes = bytes;
+        grid.mask_tail();
+        grid
+    }
+
+    /// Creates a grid from an iterator over `(row, col)` positions.
+    pub fn try_from_cells<I>(iter: I) -> Result<Self, BitBoardError>
+    where
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let mut grid = Self::new();
+        for (r, c) in iter {
+            grid.set(r, c)?;
+        }
+        Ok(grid)
+    }
+
+    /// Iterator over the set bits of the grid.
+    #[inline]
+    pub fn iter_set_bits(&self) -> GridSetBits<'_, N, B> {
+        GridSetBits { grid: self, idx: 0 }
+    }
+
+    #[inline]
+    fn index(row: usize, col: usize) -> Result<usize, BitBoardError> {
+        if row >= N || col >= N {
+            Err(BitBoardError::IndexOutOfBounds { row, col })
+        } else {
+            Ok(row * N + col)
+        }
+    }
+
+    #[inline]
+    fn bit(&self, idx: usize) -> bool {
+        (self.bytes[idx / 8] >> (idx % 8)) & 1 != 0
+    }
+
+    /// Clears every bit at or beyond `N * N`.
+    fn mask_tail(&mut self) {
+        for idx in Self::BOARD_BITS..B * 8 {
+            self.bytes[idx / 8] &= !(1 << (idx % 8));
+        }
+    }
+
+    fn zip_with(self, rhs: Self, f: impl Fn(u8, u8) -> u8) -> Self {
+        let mut out = self;
+        for (a, b) in out.bytes.iter_mut().zip(rhs.bytes.iter()) {
+            *a = f(*a, *b);
+        }
+        out
+    }
+}
+
+impl<const N: usize, const B: usize> Default for BitGrid<N, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize, const B: usize> BitAnd for BitGrid<N, B> {
+    type Output = Self;
+    #[inline]
+    fn bitand(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a & b)
+    }
+}
+
+impl<const N: usize, const B: usize> BitOr for BitGrid<N, B> {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a | b)
+    }
+}
+
+impl<const N: usize, const B: usize> BitXor for BitGrid<N, B> {
+    type Output = Self;
+    #[inline]
+    fn bitxor(self, rhs: Self) -> Self {
+        self.zip_with(rhs, |a, b| a ^ b)
+    }
+}
+
+impl<const N: usize, const B: usize> Not for BitGrid<N, B> {
+    type Output = Self;
+    fn not(self) -> Self {
+        let mut out = self;
+        for b in out.bytes.iter_mut() {
+            *b = !*b;
+        }
+        out.mask_tail();
+        out
+    }
+}
+
+impl<const N: usize, const B: usize> BitAndAssign for BitGrid<N, B> {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Self) {
+        *self = *self & rhs;
+    }
+}
+
+impl<const N: usize, const B: usize> BitOrAssign for BitGrid<N, B> {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        *self = *self | rhs;
+    }
+}
+
+impl<const N: usize, const B: usize> BitXorAssign for BitGrid<N, B> {
+    #[inline]
+    fn bitxor_assign(&mut self, rhs: Self) {
+        *self = *self ^ rhs;
+    }
+}
+
+impl<const N: usize, const B: usize> fmt::Debug for BitGrid<N, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "BitGrid<{}, {}>:", N, B)?;
+        writeln!(f, "{}", self)
+    }
+}
+
+impl<const N: usize, const B: usize> fmt::Display for BitGrid<N, B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for r in 0..N {
+            for c in 0..N {
+                let bit = if self.bit(r * N + c) { '■' } else { '□' };
+                write!(f, "{} ", bit)?;
+            }
+            if r + 1 < N {
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T, const N: usize, const B: usize> From<BitBoard<T, N>> for BitGrid<N, B>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    fn from(board: BitBoard<T, N>) -> Self {
+        let mut grid = Self::new();
+        for (r, c) in board.iter_set_bits() {
+            let idx = r * N + c;
+            grid.bytes[idx / 8] |= 1 << (idx % 8);
+        }
+        grid
+    }
+}
+
+impl<T, const N: usize, const B: usize> From<BitGrid<N, B>> for BitBoard<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    fn from(grid: BitGrid<N, B>) -> Self {
+        let mut board = BitBoard::new();
+        for (r, c) in grid.iter_set_bits() {
+            board.set(r, c).expect("cell within N×N");
+        }
+        board
+    }
+}
+
+impl<const N: usize, const B: usize> CellGrid for BitGrid<N, B> {
+    const SIDE: usize = N;
+
+    fn empty() -> Self {
+        Self::new()
+    }
+    fn get(&self, row: usize, col: usize) -> Result<bool, BitBoardError> {
+        BitGrid::get(self, row, col)
+    }
+    fn set(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        BitGrid::set(self, row, col)
+    }
+    fn clear(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        BitGrid::clear(self, row, col)
+    }
+    fn count_ones(&self) -> usize {
+        BitGrid::count_ones(self)
+    }
+    fn is_empty(&self) -> bool {
+        BitGrid::is_empty(self)
+    }
+}
+
+impl<T, const N: usize> CellGrid for BitBoard<T, N>
+where
+    T: PrimInt + Unsigned + ConstZero,
+{
+    const SIDE: usize = N;
+
+    fn empty() -> Self {
+        Self::new()
+    }
+    fn get(&self, row: usize, col: usize) -> Result<bool, BitBoardError> {
+        BitBoard::get(self, row, col)
+    }
+    fn set(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        BitBoard::set(self, row, col)
+    }
+    fn clear(&mut self, row: usize, col: usize) -> Result<(), BitBoardError> {
+        BitBoard::clear(self, row, col)
+    }
+    fn count_ones(&self) -> usize {
+        BitBoard::count_ones(self)
+    }
+    fn is_empty(&self) -> bool {
+        BitBoard::is_empty(self)
+    }
+}
+
+/// Iterator over the set bits of a [`BitGrid`].
+#[derive(Clone, Copy)]
+pub struct GridSetBits<'a, const N: usize, const B: usize> {
+    grid: &'a BitGrid<N, B>,
+    idx: usize,
+}
+
+impl<'a, const N: usize, const B: usize> Iterator for GridSetBits<'a, N, B> {
+    type Item = (usize, usize);
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.idx < N * N {
+            let idx = self.idx;
+            self.idx += 1;
+            if self.grid.bit(idx) {
+                return Some((idx / N, idx % N));
+            }
+        }
+        None
+    }
+}
@@ -1,68 +1,213 @@
 //! Game board state, using updated `BitBoard` and `Ship` types.
 
-use super::bitboard::BitBoard;
+use super::bitboard::BitBoardError;
 use super::common::{BoardError, GuessResult};
-use super::config::{BOARD_SIZE, NUM_SHIPS, SHIPS};
-use super::ship::{Orientation, Ship, ShipState};
+use super::config::{BoardBits, FleetConfig, RuleSet, MAX_BOARD_SIZE, MAX_SHIPS};
+use super::coord::Coord;
+use super::perf::{self, Counter};
+use super::ship::{Orientation, Ship, ShipDef, ShipState};
 use core::fmt;
+use rand::seq::SliceRandom;
 use rand::Rng;
 
-type BB = BitBoard<u128, { BOARD_SIZE as usize }>;
+type BB = BoardBits;
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
 
 /// Serializable board state for syncing or saving games.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BoardState {
-    pub ship_states: [ShipState; NUM_SHIPS as usize],
+    /// Side of the board the fleet is placed on.
+    pub size: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::fleet_slots"))]
+    pub ship_states: [ShipState; MAX_SHIPS],
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
     pub ship_map: BB,
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
     pub hits: BB,
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
     pub misses: BB,
 }
 
-/// Main board state: ship placements, hits, misses.
+/// What one cell of a board holds, see [`Board::cell`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CellState {
+    /// Open water nobody has fired at.
+    Empty,
+    /// Part of the ship with this fleet index, not yet hit.
+    Ship(usize),
+    /// Part of the ship with this fleet index, hit.
+    Hit(usize),
+    /// Open water that has been fired at.
+    Miss,
+}
+
+/// Damage summary for one ship of our own fleet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShipReport {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub name: &'static str,
+    pub length: usize,
+    pub placed: bool,
+    /// Cells of this ship hit so far.
+    pub hits: usize,
+    /// Cells of this ship not yet hit.
+    pub remaining: usize,
+    pub sunk: bool,
+}
+
+/// Damage summary for our whole fleet, see [`Board::fleet_report`]. Slots
+/// past the last ship of a smaller fleet have length zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FleetReport {
+    pub ships: [ShipReport; MAX_SHIPS],
+}
+
+impl FleetReport {
+    /// Total ship cells in the fleet.
+    pub fn total_cells(&self) -> usize {
+        self.ships.iter().map(|s| s.length).sum()
+    }
+
+    /// Ship cells hit so far.
+    pub fn hit_cells(&self) -> usize {
+        self.ships.iter().map(|s| s.hits).sum()
+    }
+
+    /// Ships not yet sunk.
+    pub fn ships_afloat(&self) -> usize {
+        self.ships.iter().filter(|s| s.length > 0 && !s.sunk).count()
+    }
+
+    /// Share of fleet cells not yet hit, rounded to the nearest percent.
+    pub fn health_percent(&self) -> u8 {
+        let total = self.total_cells();
+        if total == 0 {
+            return 0;
+        }
+        let intact = total - self.hit_cells();
+        ((intact * 100 + total / 2) / total) as u8
+    }
+}
 
+/// Main board state: ship placements, hits, misses.
 pub struct Board {
-    ships: [Option<Ship<u128, { BOARD_SIZE as usize }>>; NUM_SHIPS as usize],
+    ships: [Option<Ship<BB>>; MAX_SHIPS],
     ship_map: BB,
     hits: BB,
     misses: BB,
+    rules: RuleSet,
 }
 
 impl Board {
-    /// Create an empty board state (no ships placed).
+    /// Create an empty standard-size board (no ships placed).
     pub fn new() -> Self {
+        Self::with_rules(&RuleSet::STANDARD)
+    }
+
+    /// Create an empty board sized for `rules`, to hold their fleet.
+    pub fn with_rules(rules: &RuleSet) -> Self {
         let empty = BB::new();
         Board {
-            ships: [None; NUM_SHIPS as usize],
+            rules: *rules,
+            ships: [None; MAX_SHIPS],
             ship_map: empty,
             hits: empty,
             misses: empty,
         }
     }
 
-    /// Returns the public state of each ship.
-    pub fn ship_states(&self) -> [ShipState; NUM_SHIPS as usize] {
+    /// Side of the board; cells outside it cannot hold ships or be guessed.
+    pub fn size(&self) -> u8 {
+        self.rules.board_size()
+    }
+
+    /// Ships this board holds.
+    pub fn fleet(&self) -> &FleetConfig {
+        self.rules.fleet()
+    }
+
+    /// Rules this board was created for.
+    pub fn rules(&self) -> &RuleSet {
+        &self.rules
+    }
+
+    /// Returns the public state of each ship. Slots past the end of the
+    /// fleet hold an unnamed, unplaced ship.
+    pub fn ship_states(&self) -> [ShipState; MAX_SHIPS] {
         core::array::from_fn(|i| match &self.ships[i] {
-            Some(s) => ShipState {
-                name: s.ship_type().name(),
-                sunk: s.is_sunk(),
-                position: Some((s.origin().0, s.origin().1, s.orientation())),
-            },
-            None => ShipState::new(SHIPS[i].name()),
+            Some(s) => ShipState::from(s),
+            None => ShipState::new(self.fleet().get(i).map_or("", |def| def.name())),
         })
     }
 
+    /// Per-ship hit counts and overall health of this board's fleet.
+    pub fn fleet_report(&self) -> FleetReport {
+        FleetReport {
+            ships: core::array::from_fn(|i| {
+                let def = self.fleet().get(i).unwrap_or(ShipDef::new("", 0));
+                let hits = match &self.ships[i] {
+                    Some(ship) => (self.hits & ship.mask()).count_ones(),
+                    None => 0,
+                };
+                ShipReport {
+                    name: def.name(),
+                    length: def.length(),
+                    placed: self.ships[i].is_some(),
+                    hits,
+                    remaining: def.length() - hits,
+                    sunk: self.ships[i].as_ref().is_some_and(|s| s.is_sunk()),
+                }
+            }),
+        }
+    }
+
+    /// Index of the placed ship covering (row, col), if any.
+    pub fn ship_index_at(&self, row: usize, col: usize) -> Option<usize> {
+        self.ships
+            .iter()
+            .position(|s| s.as_ref().is_some_and(|ship| ship.mask().get(row, col).unwrap_or(false)))
+    }
+
     /// Returns `true` when all ships are sunk.
     pub fn all_sunk(&self) -> bool {
-        self.ships.iter().enumerate().all(|(i, s)| match s {
+        self.ships[..self.fleet().len()].iter().all(|s| match s {
             Some(ship) => ship.is_sunk(),
-            None => {
-                let _ = SHIPS[i];
-                false
-            }
+            None => false,
         })
     }
 
+    /// Returns `true` once every ship in the fleet has been placed.
+    pub fn all_placed(&self) -> bool {
+        self.ships[..self.fleet().len()].iter().all(Option::is_some)
+    }
+
+    /// State of the cell at (row, col), or an out-of-bounds error for a
+    /// cell off the board.
+    pub fn cell(&self, row: usize, col: usize) -> Result<CellState, BoardError> {
+        if !self.rules.contains(row, col) {
+            return Err(BitBoardError::IndexOutOfBounds { row, col }.into());
+        }
+        Ok(match self.ship_index_at(row, col) {
+            Some(i) if self.hits.get(row, col)? => CellState::Hit(i),
+            Some(i) => CellState::Ship(i),
+            None if self.misses.get(row, col)? => CellState::Miss,
+            None => CellState::Empty,
+        })
+    }
+
+    /// Every cell of the board with its state, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), CellState)> + '_ {
+        let size = self.size() as usize;
+        (0..size)
+            .flat_map(move |r| (0..size).map(move |c| (r, c)))
+            .filter_map(|(r, c)| self.cell(r, c).ok().map(|state| ((r, c), state)))
+    }
+
     /// Board occupancy mask of all ships.
     pub fn ship_map(&self) -> BB {
         self.ship_map
@@ -78,43 +223,93 @@ impl Board {
         self.misses
     }
 
-    /// Place a single ship by index at (row, col) and orientation.
-    pub fn place(
-        &mut self,
+    /// Checks whether ship `ship_index` could be placed at (row, col) with
+    /// `orientation`, without modifying the board.
+    ///
+    /// Returns the same error [`Board::place`] would.
+    pub fn can_place(
+        &self,
         ship_index: usize,
         row: usize,
         col: usize,
         orientation: Orientation,
     ) -> Result<(), BoardError> {
-        if ship_index >= NUM_SHIPS as usize {
+        let Some(def) = self.fleet().get(ship_index) else {
             return Err(BoardError::InvalidIndex);
-        }
+        };
         if self.ships[ship_index].is_some() {
             return Err(BoardError::ShipAlreadyPlaced);
         }
-        let def = SHIPS[ship_index];
-        let ship = Ship::<u128, { BOARD_SIZE as usize }>::new(def, orientation, row, col)?;
-        let mask = ship.mask();
-        // ensure no overlap
+        let mask = ship_mask(def.length(), row, col, orientation)?;
+        if !(mask & self.off_board()).is_empty() {
+            return Err(BoardError::ShipOutOfBounds);
+        }
         if !(self.ship_map & mask).is_empty() {
             return Err(BoardError::ShipOverlaps);
         }
+        if !(self.blocked_by(self.ship_map) & mask).is_empty() {
+            return Err(BoardError::ShipsTouch);
+        }
+        Ok(())
+    }
+
+    /// Place a single ship by index at (row, col) and orientation.
+    pub fn place(
+        &mut self,
+        ship_index: usize,
+        row: usize,
+        col: usize,
+        orientation: Orientation,
+    ) -> Result<(), BoardError> {
+        self.can_place(ship_index, row, col, orientation)?;
+        let def = self.fleet().ships()[ship_index];
+        let ship = Ship::<BB>::new(def, orientation, row, col)?;
         // record placement
-        self.ship_map = self.ship_map | mask;
+        self.ship_map |= ship.mask();
         self.ships[ship_index] = Some(ship);
         Ok(())
     }
 
+    /// Pick ship `ship_index` back up during setup, returning where it
+    /// was. Fails with `PlacementLocked` once the board has taken a shot.
+    pub fn remove(&mut self, ship_index: usize) -> Result<(usize, usize, Orientation), BoardError> {
+        if ship_index >= self.fleet().len() {
+            return Err(BoardError::InvalidIndex);
+        }
+        if !(self.hits | self.misses).is_empty() {
+            return Err(BoardError::PlacementLocked);
+        }
+        let ship = self.ships[ship_index].take().ok_or(BoardError::ShipNotPlaced)?;
+        self.ship_map = self.ships.iter().flatten().fold(BB::new(), |map, s| map | s.mask());
+        let (row, col) = ship.origin();
+        Ok((row, col, ship.orientation()))
+    }
+
+    /// Move the placed ship `ship_index` to (row, col) and `orientation`.
+    /// If the new position is illegal the ship stays where it was and the
+    /// error [`Board::place`] gives is returned.
+    pub fn move_ship(
+        &mut self,
+        ship_index: usize,
+        row: usize,
+        col: usize,
+        orientation: Orientation,
+    ) -> Result<(), BoardError> {
+        let (old_row, old_col, old_orientation) = self.remove(ship_index)?;
+        if let Err(e) = self.place(ship_index, row, col, orientation) {
+            self.place(ship_index, old_row, old_col, old_orientation)?;
+            return Err(e);
+        }
+        Ok(())
+    }
+
     /// Returns a random non‐overlapping (row, col, Orientation) for `ship_index`.
-    pub fn random_placement<R: Rng>(
+    pub fn random_placement<R: Rng + ?Sized>(
         &self,
         rng: &mut R,
         ship_index: usize,
     ) -> Result<(usize, usize, Orientation), BoardError> {
-        if ship_index >= NUM_SHIPS as usize {
-            return Err(BoardError::InvalidIndex);
-        }
-        let def = SHIPS[ship_index];
+        let def = self.fleet().get(ship_index).ok_or(BoardError::InvalidIndex)?;
         let mut attempts = 0;
         while attempts < 100 {
             attempts += 1;
@@ -123,29 +318,101 @@ impl Board {
             } else {
                 Orientation::Vertical
             };
+            let size = self.size() as usize;
             let max_r = if orient == Orientation::Vertical {
-                BOARD_SIZE as usize - def.length()
+                size - def.length()
             } else {
-                BOARD_SIZE as usize - 1
+                size - 1
             };
             let max_c = if orient == Orientation::Horizontal {
-                BOARD_SIZE as usize - def.length()
+                size - def.length()
             } else {
-                BOARD_SIZE as usize - 1
+                size - 1
             };
             let r = rng.random_range(0..=max_r);
             let c = rng.random_range(0..=max_c);
-            // build a temp ship and check overlap
-            let ship = Ship::<u128, { BOARD_SIZE as usize }>::new(def, orient, r, c)?;
-            if (self.ship_map & ship.mask()).is_empty() {
-                return Ok((r, c, orient));
+            match self.can_place(ship_index, r, c, orient) {
+                Ok(()) => return Ok((r, c, orient)),
+                Err(BoardError::ShipOverlaps | BoardError::ShipsTouch) => {}
+                Err(e) => return Err(e),
             }
         }
         Err(BoardError::UnableToPlaceShip)
     }
 
-    /// Process a guess at (row, col), marking hits/misses and reporting result.
-    pub fn guess(&mut self, row: usize, col: usize) -> Result<GuessResult, BoardError> {
+    /// Places every remaining ship at random, backtracking over earlier
+    /// choices when a ship no longer fits.
+    ///
+    /// Already placed ships are kept. Unlike repeated [`Board::random_placement`]
+    /// calls this always succeeds when any legal layout of the remaining ships
+    /// exists; otherwise it returns `UnableToPlaceShip` and leaves the board
+    /// unchanged.
+    pub fn place_all_random<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Result<(), BoardError> {
+        let mut layout = [None; MAX_SHIPS];
+        if !self.search_layout(rng, 0, self.blocked_by(self.ship_map), &mut layout) {
+            return Err(BoardError::UnableToPlaceShip);
+        }
+        for (i, pos) in layout.iter().enumerate() {
+            if let Some((r, c, o)) = *pos {
+                self.place(i, r, c, o)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Depth-first search for positions of the unplaced ships from `index`
+    /// on, trying each ship's legal positions in random order. `occupied`
+    /// holds the cells no further ship may cover.
+    fn search_layout<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        index: usize,
+        occupied: BB,
+        layout: &mut [Option<(usize, usize, Orientation)>; MAX_SHIPS],
+    ) -> bool {
+        let Some(i) = (index..self.fleet().len()).find(|&i| self.ships[i].is_none()) else {
+            return true;
+        };
+        let len = self.fleet().ships()[i].length();
+        let size = self.size() as usize;
+        let off_board = self.off_board();
+        let mut candidates = [(0u8, 0u8, Orientation::Horizontal); 2 * GRID_SIZE * GRID_SIZE];
+        let mut count = 0;
+        for o in [Orientation::Horizontal, Orientation::Vertical] {
+            for r in 0..size {
+                for c in 0..size {
+                    if let Ok(mask) = ship_mask(len, r, c, o) {
+                        if ((occupied | off_board) & mask).is_empty() {
+                            candidates[count] = (r as u8, c as u8, o);
+                            count += 1;
+                        }
+                    }
+                }
+            }
+        }
+        candidates[..count].shuffle(rng);
+
+        for &(r, c, o) in &candidates[..count] {
+            let (r, c) = (r as usize, c as usize);
+            let Ok(mask) = ship_mask(len, r, c, o) else {
+                continue;
+            };
+            layout[i] = Some((r, c, o));
+            if self.search_layout(rng, i + 1, occupied | self.blocked_by(mask), layout) {
+                return true;
+            }
+        }
+        layout[i] = None;
+        false
+    }
+
+    /// Process a guess at `target`, marking hits/misses and reporting result.
+    pub fn guess(&mut self, target: Coord) -> Result<GuessResult, BoardError> {
+        perf::count(Counter::Guesses, 1);
+        let Coord { row, col } = target;
+        if !self.rules.contains(row, col) {
+            return Err(BitBoardError::IndexOutOfBounds { row, col }.into());
+        }
         // prevent duplicates
         if self.hits.get(row, col)? || self.misses.get(row, col)? {
             return Err(BoardError::AlreadyGuessed);
@@ -155,16 +422,14 @@ impl Board {
             self.hits.set(row, col)?;
 
             // determine which ship was hit
-            for ship_opt in self.ships.iter_mut() {
-                if let Some(ship) = ship_opt {
-                    if ship.mask().get(row, col).unwrap_or(false) {
-                        let was_sunk = ship.is_sunk();
-                        ship.guess(row, col);
-                        if ship.is_sunk() && !was_sunk {
-                            return Ok(GuessResult::Sink(ship.ship_type().name()));
-                        }
-                        return Ok(GuessResult::Hit);
+            for ship in self.ships.iter_mut().flatten() {
+                if ship.mask().get(row, col).unwrap_or(false) {
+                    let was_sunk = ship.is_sunk();
+                    ship.guess(row, col);
+                    if ship.is_sunk() && !was_sunk {
+                        return Ok(GuessResult::Sink(ship.ship_type().name()));
                     }
+                    return Ok(GuessResult::Hit);
                 }
             }
             // should have found a ship; fallback
@@ -174,6 +439,46 @@ impl Board {
             Ok(GuessResult::Miss)
         }
     }
+
+    /// Cells of the bitboard storage outside this board.
+    fn off_board(&self) -> BB {
+        self.rules.off_board()
+    }
+
+    /// Cells other ships may not cover next to `ships`: the ships
+    /// themselves, plus every cell touching them under the no-touching rule.
+    fn blocked_by(&self, ships: BB) -> BB {
+        if self.rules.placement().no_touching {
+            halo(&ships)
+        } else {
+            ships
+        }
+    }
+}
+
+/// `mask` grown by one cell in every direction, diagonals included.
+pub(crate) fn halo(mask: &BB) -> BB {
+    mask.dilate(true)
+}
+
+/// Occupancy mask of a ship of length `len` at (row, col), or
+/// `ShipOutOfBounds` if it would leave the board.
+pub(crate) fn ship_mask(
+    len: usize,
+    row: usize,
+    col: usize,
+    orientation: Orientation,
+) -> Result<BB, BoardError> {
+    if len == 0 {
+        return Err(BoardError::ShipOutOfBounds);
+    }
+    BB::line(row, col, len, orientation).map_err(|_| BoardError::ShipOutOfBounds)
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl fmt::Debug for Board {
@@ -189,6 +494,7 @@ impl fmt::Debug for Board {
 impl From<&Board> for BoardState {
     fn from(b: &Board) -> Self {
         BoardState {
+            size: b.size(),
             ship_states: b.ship_states(),
             ship_map: b.ship_map,
             hits: b.hits,
@@ -197,21 +503,117 @@ impl From<&Board> for BoardState {
     }
 }
 
-impl From<BoardState> for Board {
-    fn from(state: BoardState) -> Self {
-        let mut board = Board::new();
+impl Board {
+    /// Rebuild a board saved from a game played by `rules`; the saved state
+    /// records the board size but not the fleet.
+    pub fn from_state(state: BoardState, rules: &RuleSet) -> Self {
+        let mut board = Board::with_rules(rules);
         board.ship_map = state.ship_map;
         board.hits = state.hits;
         board.misses = state.misses;
-        for i in 0..NUM_SHIPS as usize {
-            let def = SHIPS[i];
+        for (i, def) in rules.fleet().ships().iter().enumerate() {
             if let Some(ship) =
-                Ship::<u128, { BOARD_SIZE as usize }>::from_state(&state.ship_states[i], def)
+                Ship::<BB>::from_state(&state.ship_states[i], *def)
                     .unwrap()
             {
+                let mut ship = ship;
+                for (r, c) in (state.hits & ship.mask()).iter_set_bits() {
+                    ship.guess(r, c);
+                }
                 board.ships[i] = Some(ship);
             }
         }
         board
     }
 }
+
+impl From<BoardState> for Board {
+    fn from(state: BoardState) -> Self {
+        let rules = RuleSet::with_board_size(state.size).unwrap_or_default();
+        Board::from_state(state, &rules)
+    }
+}
+
+/// Why a [`BoardState`] is not a legal layout of a fleet, see
+/// [`BoardState::validate`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The board is empty, larger than the bitboard storage or too small
+    /// for the fleet's longest ship.
+    UnsupportedSize(u8),
+    /// The ship with this fleet index has no position.
+    MissingShip { ship: usize },
+    /// A ship is placed in this slot past the end of the fleet.
+    ExtraShip { slot: usize },
+    /// The ship with this fleet index runs off the board.
+    OutOfBounds { ship: usize },
+    /// The ship with this fleet index lies across an earlier ship.
+    Overlap { ship: usize },
+    /// The ship map holds a different number of cells than the fleet's
+    /// ships together.
+    CellCount { expected: usize, found: usize },
+    /// The ship map is not the union of the ships' straight runs: some
+    /// ship is bent, split or in the wrong place.
+    ShipMapMismatch,
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LayoutError::UnsupportedSize(size) => write!(f, "unsupported board size {}x{}", size, size),
+            LayoutError::MissingShip { ship } => write!(f, "ship {} is not placed", ship),
+            LayoutError::ExtraShip { slot } => write!(f, "ship placed in slot {} past the end of the fleet", slot),
+            LayoutError::OutOfBounds { ship } => write!(f, "ship {} runs off the board", ship),
+            LayoutError::Overlap { ship } => write!(f, "ship {} overlaps another ship", ship),
+            LayoutError::CellCount { expected, found } => {
+                write!(f, "ship map holds {} cells where the fleet has {}", found, expected)
+            }
+            LayoutError::ShipMapMismatch => write!(f, "ship map does not match the ship positions"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LayoutError {}
+
+impl BoardState {
+    /// Check that the state places exactly `fleet`: one straight ship of
+    /// the right length per fleet entry, each on the board, none
+    /// overlapping, and a ship map covering just those ships. A peer's
+    /// board from a sync or a reveal should pass this before it is trusted.
+    pub fn validate(&self, fleet: &FleetConfig) -> Result<(), LayoutError> {
+        let size = self.size as usize;
+        if size == 0 || self.size > MAX_BOARD_SIZE || size < fleet.longest() {
+            return Err(LayoutError::UnsupportedSize(self.size));
+        }
+        if let Some(slot) = (fleet.len()..MAX_SHIPS).find(|&i| self.ship_states[i].position.is_some()) {
+            return Err(LayoutError::ExtraShip { slot });
+        }
+        let on_board = BB::rect(0, 0, size - 1, size - 1).map_err(|_| LayoutError::UnsupportedSize(self.size))?;
+        let mut occupied = BB::new();
+        for (ship, def) in fleet.ships().iter().enumerate() {
+            let (row, col, orientation) = self.ship_states[ship]
+                .position
+                .ok_or(LayoutError::MissingShip { ship })?;
+            let mask = ship_mask(def.length(), row, col, orientation).map_err(|_| LayoutError::OutOfBounds { ship })?;
+            if mask & on_board != mask {
+                return Err(LayoutError::OutOfBounds { ship });
+            }
+            if !(mask & occupied).is_empty() {
+                return Err(LayoutError::Overlap { ship });
+            }
+            occupied |= mask;
+        }
+        let found = self.ship_map.count_ones();
+        if found != fleet.total_cells() {
+            return Err(LayoutError::CellCount {
+                expected: fleet.total_cells(),
+                found,
+            });
+        }
+        if self.ship_map != occupied {
+            return Err(LayoutError::ShipMapMismatch);
+        }
+        Ok(())
+    }
+}
@@ -26,6 +26,8 @@ pub enum BoardError {
     ShipAlreadyPlaced,
     /// Ship placement overlaps another ship.
     ShipOverlaps,
+    /// Ship placement touches another ship where the placement rules forbid it.
+    ShipsTouch,
     /// Guess was already made at this position.
     AlreadyGuessed,
     /// Unable to place ship (random or manual placement failed).
@@ -33,7 +35,19 @@ pub enum BoardError {
     // Ship out of bounds
     ShipOutOfBounds,
     /// Unknown ship hit (should not happen if all ships are placed correctly).
-    UnknownShipHit,}
+    UnknownShipHit,
+    /// Board side outside the range the bitboard storage supports.
+    UnsupportedBoardSize(u8),
+    /// Fleet with no ships, too many ships, a zero-length ship or more cells
+    /// than the board.
+    InvalidFleet,
+    /// Attempted to pick up a ship that is not on the board.
+    ShipNotPlaced,
+    /// Attempted to rearrange ships after shots have been fired.
+    PlacementLocked,
+    /// Placement was called off before the fleet was complete.
+    PlacementCancelled,
+}
 
 impl From<BitBoardError> for BoardError {
     fn from(err: BitBoardError) -> Self {
@@ -48,10 +62,16 @@ impl core::fmt::Display for BoardError {
             BoardError::InvalidIndex => write!(f, "Index is out of range"),
             BoardError::ShipAlreadyPlaced => write!(f, "Ship is already placed on the board"),
             BoardError::ShipOverlaps => write!(f, "Ship placement overlaps with another ship"),
+            BoardError::ShipsTouch => write!(f, "Ship placement touches another ship"),
             BoardError::AlreadyGuessed => write!(f, "Guess was already made at this position"),
             BoardError::UnableToPlaceShip => write!(f, "Unable to place ship"),
             BoardError::ShipOutOfBounds => write!(f, "Ship placement is out of bounds"),
             BoardError::UnknownShipHit => write!(f, "Hit on an unknown ship segment"),
+            BoardError::UnsupportedBoardSize(size) => write!(f, "Unsupported board size {}x{}", size, size),
+            BoardError::InvalidFleet => write!(f, "Invalid fleet definition"),
+            BoardError::ShipNotPlaced => write!(f, "Ship is not on the board"),
+            BoardError::PlacementLocked => write!(f, "Ships cannot be moved once shots have been fired"),
+            BoardError::PlacementCancelled => write!(f, "Ship placement was cancelled"),
         }
     }
 }
@@ -1,7 +1,35 @@
+#[cfg(not(feature = "large-boards"))]
+use super::bitboard::BitBoard;
+#[cfg(feature = "large-boards")]
+use super::bitboard_wide::BitBoardWide;
+use super::common::BoardError;
+use super::game::{TurnClock, TurnPolicy};
 use super::ship::ShipDef;
 
+/// Side of the standard board.
 pub const BOARD_SIZE: u8 = 10;
+
+/// Largest board side this build plays, and the side of [`BoardBits`]:
+/// [`BOARD_SIZE`], or 12 with the `large-boards` feature.
+pub const MAX_BOARD_SIZE: u8 = if cfg!(feature = "large-boards") { 12 } else { BOARD_SIZE };
+
+#[cfg(not(feature = "large-boards"))]
+type Storage = BitBoard<u128, { MAX_BOARD_SIZE as usize }>;
+#[cfg(feature = "large-boards")]
+type Storage = BitBoardWide<3, { MAX_BOARD_SIZE as usize }>;
+
+/// The bitboard every board is stored in, [`MAX_BOARD_SIZE`] cells a side:
+/// one `u128` for 10×10, three words for 12×12. A game on a smaller board
+/// leaves the cells past its edge unused, see [`RuleSet::off_board`].
+pub type BoardBits = Storage;
+
+/// Ships in the standard fleet.
 pub const NUM_SHIPS: usize = 5;
+
+/// Most ships a fleet may hold in this build, and the length of every
+/// per-ship array: [`NUM_SHIPS`], or 10 with the `large-fleets` feature.
+pub const MAX_SHIPS: usize = if cfg!(feature = "large-fleets") { 10 } else { NUM_SHIPS };
+
 pub const SHIPS: [ShipDef; NUM_SHIPS] = [
     ShipDef::new("Carrier", 5),
     ShipDef::new("Battleship", 4),
@@ -10,9 +38,331 @@ pub const SHIPS: [ShipDef; NUM_SHIPS] = [
     ShipDef::new("Destroyer", 2),
 ];
 
+/// Ship names a fleet agreed with a peer may use: the standard fleet's,
+/// then other common classes. Ship definitions hold `'static` names, so a
+/// name received over the network must be one of these.
+pub const SHIP_CLASSES: [&str; 11] = [
+    "Carrier",
+    "Battleship",
+    "Cruiser",
+    "Submarine",
+    "Destroyer",
+    "Aircraft Carrier",
+    "Frigate",
+    "Corvette",
+    "Minesweeper",
+    "Gunboat",
+    "Patrol Boat",
+];
+
 /// Total number of ship segments used in the standard configuration.
 pub const TOTAL_SHIP_CELLS: usize = 5 + 4 + 3 + 3 + 2;
 
+/// Smallest board side the standard fleet fits: its longest ship must fit
+/// in a row.
+pub const MIN_BOARD_SIZE: u8 = {
+    let mut longest = 0;
+    let mut i = 0;
+    while i < NUM_SHIPS {
+        if SHIPS[i].length() > longest {
+            longest = SHIPS[i].length();
+        }
+        i += 1;
+    }
+    longest as u8
+};
+
+/// The ships each side places, in fleet order. A fleet holds at most
+/// [`MAX_SHIPS`] ships, as per-ship state is kept in arrays of that size;
+/// ship names need not be unique.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FleetConfig {
+    ships: [ShipDef; MAX_SHIPS],
+    len: usize,
+}
+
+impl FleetConfig {
+    /// The standard fleet, [`SHIPS`].
+    pub const STANDARD: FleetConfig = {
+        let mut fleet = FleetConfig::empty();
+        while fleet.len < NUM_SHIPS {
+            fleet.ships[fleet.len] = SHIPS[fleet.len];
+            fleet.len += 1;
+        }
+        fleet
+    };
+
+    /// A fleet with no ships, to be filled with [`FleetConfig::with_ships`].
+    pub const fn empty() -> Self {
+        FleetConfig {
+            ships: [ShipDef::new("", 0); MAX_SHIPS],
+            len: 0,
+        }
+    }
+
+    /// Add `count` ships called `name` of `length` cells. Fails with
+    /// `InvalidFleet` for zero-length ships or once the fleet would exceed
+    /// [`MAX_SHIPS`].
+    ///
+    /// Any name plays locally, but a peer rebuilds the fleet from a
+    /// `GameConfig` and a hosted game from its saved one, and both only
+    /// accept names from [`SHIP_CLASSES`]; other fleets cannot be agreed
+    /// over the network or hosted.
+    pub fn with_ships(mut self, name: &'static str, length: usize, count: usize) -> Result<Self, BoardError> {
+        let Some(len) = self.len.checked_add(count).filter(|n| *n <= MAX_SHIPS) else {
+            return Err(BoardError::InvalidFleet);
+        };
+        if length == 0 {
+            return Err(BoardError::InvalidFleet);
+        }
+        while self.len < len {
+            self.ships[self.len] = ShipDef::new(name, length);
+            self.len += 1;
+        }
+        Ok(self)
+    }
+
+    /// A fleet of `ships` in order, such as a fixed list of a downstream
+    /// crate's own. Fails with `InvalidFleet` for zero-length ships or more
+    /// than [`MAX_SHIPS`] ships.
+    ///
+    /// As with [`FleetConfig::with_ships`], only fleets named from
+    /// [`SHIP_CLASSES`] can be sent to a peer or hosted.
+    pub fn from_ships(ships: &[ShipDef]) -> Result<Self, BoardError> {
+        if ships.len() > MAX_SHIPS || ships.iter().any(|def| def.length() == 0) {
+            return Err(BoardError::InvalidFleet);
+        }
+        let mut fleet = Self::empty();
+        fleet.ships[..ships.len()].copy_from_slice(ships);
+        fleet.len = ships.len();
+        Ok(fleet)
+    }
+
+    /// The ships in fleet order.
+    pub fn ships(&self) -> &[ShipDef] {
+        &self.ships[..self.len]
+    }
+
+    /// Number of ships in the fleet.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the fleet has no ships.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Ship `index` of the fleet.
+    pub fn get(&self, index: usize) -> Option<ShipDef> {
+        self.ships().get(index).copied()
+    }
+
+    /// Index of the first ship called `name`.
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.ships().iter().position(|def| def.name() == name)
+    }
+
+    /// Total number of ship cells in the fleet.
+    pub fn total_cells(&self) -> usize {
+        self.ships().iter().map(|def| def.length()).sum()
+    }
+
+    /// Length of the longest ship.
+    pub fn longest(&self) -> usize {
+        self.ships().iter().map(|def| def.length()).max().unwrap_or(0)
+    }
+
+    /// Ship lengths by index, zero past the end of the fleet. The targeting
+    /// functions take any slice of lengths; use `&fleet.lengths()[..fleet.len()]`
+    /// for just the fleet's own ships.
+    pub fn lengths(&self) -> [usize; MAX_SHIPS] {
+        core::array::from_fn(|i| self.get(i).map_or(0, |def| def.length()))
+    }
+}
+
+impl Default for FleetConfig {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
+/// Constraints on where ships may go beyond staying on the board and not
+/// overlapping.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct PlacementRules {
+    /// Ships may not touch, orthogonally or diagonally.
+    pub no_touching: bool,
+}
+
+impl PlacementRules {
+    /// Ships may touch but not overlap.
+    pub const STANDARD: PlacementRules = PlacementRules { no_touching: false };
+
+    /// Ships keep at least one empty cell between them, diagonals included.
+    pub const NO_TOUCHING: PlacementRules = PlacementRules { no_touching: true };
+}
+
+/// Rules fixed when a game is created, which both peers must agree on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RuleSet {
+    board_size: u8,
+    fleet: FleetConfig,
+    salvo: bool,
+    placement: PlacementRules,
+    turn_policy: TurnPolicy,
+    turn_clock: Option<TurnClock>,
+    move_limit: Option<u16>,
+}
+
+impl RuleSet {
+    /// The standard game: the standard fleet on a `BOARD_SIZE`×`BOARD_SIZE`
+    /// board.
+    pub const STANDARD: RuleSet = RuleSet {
+        board_size: BOARD_SIZE,
+        fleet: FleetConfig::STANDARD,
+        salvo: false,
+        placement: PlacementRules::STANDARD,
+        turn_policy: TurnPolicy::Alternate,
+        turn_clock: None,
+        move_limit: None,
+    };
+
+    /// Rules for the standard fleet on a `board_size`×`board_size` board.
+    /// Sizes from [`MIN_BOARD_SIZE`] up to [`MAX_BOARD_SIZE`] fit the
+    /// bitboard storage; anything else is `UnsupportedBoardSize`.
+    pub fn with_board_size(board_size: u8) -> Result<Self, BoardError> {
+        Self::new(board_size, FleetConfig::STANDARD)
+    }
+
+    /// Rules for `fleet` on a `board_size`×`board_size` board. The board
+    /// must fit the bitboard storage and the fleet's longest ship, and the
+    /// fleet must have at least one ship and fit in the board's cells.
+    pub fn new(board_size: u8, fleet: FleetConfig) -> Result<Self, BoardError> {
+        if board_size == 0 || board_size > MAX_BOARD_SIZE || (board_size as usize) < fleet.longest() {
+            return Err(BoardError::UnsupportedBoardSize(board_size));
+        }
+        if fleet.is_empty() || fleet.total_cells() > board_size as usize * board_size as usize {
+            return Err(BoardError::InvalidFleet);
+        }
+        Ok(RuleSet {
+            board_size,
+            fleet,
+            salvo: false,
+            placement: PlacementRules::STANDARD,
+            turn_policy: TurnPolicy::Alternate,
+            turn_clock: None,
+            move_limit: None,
+        })
+    }
+
+    /// These rules played with `fleet` instead.
+    pub fn with_fleet(self, fleet: FleetConfig) -> Result<Self, BoardError> {
+        Self::new(self.board_size, fleet).map(|rules| {
+            rules
+                .with_salvo(self.salvo)
+                .with_placement(self.placement)
+                .with_turn_policy(self.turn_policy)
+                .with_turn_clock(self.turn_clock)
+                .with_move_limit(self.move_limit)
+        })
+    }
+
+    /// These rules with salvo turns switched on or off. In a salvo game
+    /// each turn fires one shot per ship the shooter still has afloat.
+    pub const fn with_salvo(mut self, salvo: bool) -> Self {
+        self.salvo = salvo;
+        self
+    }
+
+    /// Whether each turn is a salvo rather than a single shot.
+    pub const fn salvo(&self) -> bool {
+        self.salvo
+    }
+
+    /// These rules with `placement` constraining where ships may go.
+    pub const fn with_placement(mut self, placement: PlacementRules) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Constraints on ship placement.
+    pub const fn placement(&self) -> PlacementRules {
+        self.placement
+    }
+
+    /// These rules with `policy` deciding who fires after each shot.
+    pub const fn with_turn_policy(mut self, policy: TurnPolicy) -> Self {
+        self.turn_policy = policy;
+        self
+    }
+
+    /// Who fires after each shot.
+    pub const fn turn_policy(&self) -> TurnPolicy {
+        self.turn_policy
+    }
+
+    /// These rules with each turn limited by `clock`, or untimed for `None`.
+    pub const fn with_turn_clock(mut self, clock: Option<TurnClock>) -> Self {
+        self.turn_clock = clock;
+        self
+    }
+
+    /// Deadline for each turn, if turns are timed.
+    pub const fn turn_clock(&self) -> Option<TurnClock> {
+        self.turn_clock
+    }
+
+    /// These rules with an undecided game drawn once each side has fired
+    /// `limit` shots, or played out for `None`.
+    pub const fn with_move_limit(mut self, limit: Option<u16>) -> Self {
+        self.move_limit = limit;
+        self
+    }
+
+    /// Shots each side may fire before an undecided game is drawn, if any.
+    pub const fn move_limit(&self) -> Option<u16> {
+        self.move_limit
+    }
+
+    /// Side of the square board.
+    pub const fn board_size(&self) -> u8 {
+        self.board_size
+    }
+
+    /// Ships each side places.
+    pub const fn fleet(&self) -> &FleetConfig {
+        &self.fleet
+    }
+
+    /// Whether (row, col) lies on the board.
+    pub const fn contains(&self, row: usize, col: usize) -> bool {
+        row < self.board_size as usize && col < self.board_size as usize
+    }
+
+    /// Cells of the bitboard storage that lie outside the board.
+    pub fn off_board(&self) -> BoardBits {
+        let mut mask = BoardBits::new();
+        if self.board_size == MAX_BOARD_SIZE {
+            return mask;
+        }
+        for r in 0..MAX_BOARD_SIZE as usize {
+            for c in 0..MAX_BOARD_SIZE as usize {
+                if !self.contains(r, c) {
+                    let _ = mask.set(r, c);
+                }
+            }
+        }
+        mask
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::STANDARD
+    }
+}
+
 /// Convert a ship name string to the canonical static name used in the
 /// configuration. Returns `None` if the name does not match any defined ship.
 pub fn ship_name_static(name: &str) -> Option<&'static str> {
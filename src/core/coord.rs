@@ -0,0 +1,119 @@
+//! Board coordinates and the `B7` notation players type and read.
+//!
+//! Notation puts the column letter first and the 1-based row after it, so
+//! `B7` is row 6, column 1. Parsing checks the coordinate against
+//! [`MAX_BOARD_SIZE`]; the engine still checks it against its own rules.
+
+use core::{fmt, str::FromStr};
+
+use super::config::MAX_BOARD_SIZE;
+
+/// A cell on the board, by zero-based row and column.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Coord {
+    pub row: usize,
+    pub col: usize,
+}
+
+impl Coord {
+    /// The cell at (row, col).
+    pub const fn new(row: usize, col: usize) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<(usize, usize)> for Coord {
+    fn from((row, col): (usize, usize)) -> Self {
+        Self { row, col }
+    }
+}
+
+impl From<Coord> for (usize, usize) {
+    fn from(coord: Coord) -> Self {
+        (coord.row, coord.col)
+    }
+}
+
+impl fmt::Display for Coord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", (b'A' + self.col as u8) as char, self.row + 1)
+    }
+}
+
+/// Why text could not be read as a [`Coord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoordError {
+    /// Nothing was entered.
+    Empty,
+    /// A column letter with no row number after it.
+    TooShort,
+    /// The first character is not a letter.
+    InvalidColumn(char),
+    /// The column letter lies past the edge of the board.
+    ColumnOutOfBounds(char),
+    /// What follows the column letter is not a number.
+    InvalidRow,
+    /// The row number is zero or lies past the edge of the board.
+    RowOutOfBounds(usize),
+}
+
+impl fmt::Display for CoordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let last_col = (b'A' + MAX_BOARD_SIZE - 1) as char;
+        match self {
+            CoordError::Empty => write!(f, "Empty input"),
+            CoordError::TooShort => write!(f, "Too short - need column letter and row number (e.g., A5)"),
+            CoordError::InvalidColumn(ch) => write!(f, "Invalid column '{}' - must be a letter A-{}", ch, last_col),
+            CoordError::ColumnOutOfBounds(ch) => write!(f, "Column '{}' out of bounds - must be A-{}", ch, last_col),
+            CoordError::InvalidRow => write!(f, "Invalid row - must be a number 1-{}", MAX_BOARD_SIZE),
+            CoordError::RowOutOfBounds(row) => write!(f, "Row {} out of bounds - must be 1-{}", row, MAX_BOARD_SIZE),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CoordError {}
+
+impl FromStr for Coord {
+    type Err = CoordError;
+
+    /// Parse notation such as `B7` or `j10`, rejecting cells off the board.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut chars = text.chars();
+        let col_ch = chars.next().ok_or(CoordError::Empty)?.to_ascii_uppercase();
+        if chars.as_str().is_empty() {
+            return Err(CoordError::TooShort);
+        }
+        if !col_ch.is_ascii_alphabetic() {
+            return Err(CoordError::InvalidColumn(col_ch));
+        }
+        let col = (col_ch as u8 - b'A') as usize;
+        if col >= MAX_BOARD_SIZE as usize {
+            return Err(CoordError::ColumnOutOfBounds(col_ch));
+        }
+        let row: usize = chars.as_str().parse().map_err(|_| CoordError::InvalidRow)?;
+        if row == 0 || row > MAX_BOARD_SIZE as usize {
+            return Err(CoordError::RowOutOfBounds(row));
+        }
+        Ok(Self { row: row - 1, col })
+    }
+}
+
+/// Serialized as a `(u8, u8)` tuple of row and column, the same bytes as
+/// the bare pair the wire protocol carried before.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Coord {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let row = u8::try_from(self.row).map_err(|_| serde::ser::Error::custom("row does not fit in a byte"))?;
+        let col = u8::try_from(self.col).map_err(|_| serde::ser::Error::custom("column does not fit in a byte"))?;
+        (row, col).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Coord {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (row, col) = <(u8, u8)>::deserialize(deserializer)?;
+        Ok(Self::new(row as usize, col as usize))
+    }
+}
@@ -0,0 +1,98 @@
+//! Compact differences between two [`GameState`]s of the same game.
+//!
+//! Shots are never taken back, so a later state is an earlier one plus the
+//! cells fired at since. A [`StateDelta`] carries just those cells, and is
+//! applied with [`GameEngine::apply_delta`](super::game::GameEngine::apply_delta)
+//! by a peer that still holds the earlier state.
+
+use super::{
+    config::{BoardBits, MAX_SHIPS},
+    game::GameState,
+};
+
+type BB = BoardBits;
+
+/// The shots that take one game state to a later one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateDelta {
+    /// [`GameState::digest`] of the state the delta applies to.
+    pub base: u64,
+    /// Hits newly landed on our board.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
+    pub hits: BB,
+    /// Misses newly landed on our board.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
+    pub misses: BB,
+    /// Our new hits on the opponent's board.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
+    pub guess_hits: BB,
+    /// Our new misses on the opponent's board.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
+    pub guess_misses: BB,
+    /// Which enemy ships remain afloat afterwards.
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::fleet_slots"))]
+    pub enemy_ships_remaining: [bool; MAX_SHIPS],
+}
+
+impl StateDelta {
+    /// Whether the delta adds no shots.
+    pub fn is_empty(&self) -> bool {
+        self.hits.is_empty() && self.misses.is_empty() && self.guess_hits.is_empty() && self.guess_misses.is_empty()
+    }
+
+    /// Number of shots the delta adds, both ways.
+    pub fn shots(&self) -> usize {
+        self.hits.count_ones() + self.misses.count_ones() + self.guess_hits.count_ones() + self.guess_misses.count_ones()
+    }
+}
+
+impl GameState {
+    /// The shots recorded in this state but not in `base`, an earlier
+    /// state of the same game. Shots `base` holds that this state lacks
+    /// cannot be expressed; applying the delta to `base` then fails.
+    pub fn diff(&self, base: &GameState) -> StateDelta {
+        StateDelta {
+            base: base.digest(),
+            hits: self.my_board.hits & !base.my_board.hits,
+            misses: self.my_board.misses & !base.my_board.misses,
+            guess_hits: self.my_guesses.hits & !base.my_guesses.hits,
+            guess_misses: self.my_guesses.misses & !base.my_guesses.misses,
+            enemy_ships_remaining: self.enemy_ships_remaining,
+        }
+    }
+}
+
+/// Why a [`StateDelta`] could not be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeltaError {
+    /// The delta was taken against a state other than the engine's.
+    BaseMismatch { expected: u64, actual: u64 },
+    /// The delta fires at a cell already fired at, off the board, or as
+    /// both a hit and a miss.
+    ConflictingShots,
+    /// A new shot on our board disagrees with our fleet: a hit on open
+    /// water or a miss on a ship.
+    WrongResult,
+    /// The enemy fleet summary raises a ship that was already sunk, or
+    /// lists more ships than the fleet has.
+    InvalidSinks,
+}
+
+impl core::fmt::Display for DeltaError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DeltaError::BaseMismatch { expected, actual } => write!(
+                f,
+                "delta applies to state {:016x} but the engine holds {:016x}",
+                expected, actual
+            ),
+            DeltaError::ConflictingShots => write!(f, "delta repeats a shot or fires off the board"),
+            DeltaError::WrongResult => write!(f, "delta reports a shot on our board that disagrees with our fleet"),
+            DeltaError::InvalidSinks => write!(f, "delta refloats a sunk ship or lists too many ships"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DeltaError {}
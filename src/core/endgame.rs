@@ -0,0 +1,259 @@
+// Exhaustive search for the last ship afloat.
+// Uses no_std and avoids heap allocations.
+
+use super::{
+    ai::enumerate_placements,
+    config::{BoardBits, MAX_BOARD_SIZE},
+    targeting::TargetingView,
+};
+
+/// Bitboard type alias for convenience.
+type BB = BoardBits;
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+/// Most placements of the last ship for which [`AiConfig`](crate::AiConfig)
+/// has [`plan_endgame`] search them all by default. The search grows
+/// exponentially with the candidates; eight stay well within a turn.
+pub const ENDGAME_MAX_CANDIDATES: usize = 8;
+
+/// Most placements the search can tell apart, one bit each.
+const CANDIDATE_CAPACITY: usize = 32;
+
+/// Positions [`plan_endgame`] may evaluate before giving up.
+const ENDGAME_NODE_BUDGET: usize = 20_000;
+
+/// Positions remembered by the search; later ones may evict earlier ones.
+const MEMO_SLOTS: usize = 1024;
+
+/// What [`plan_endgame`] found for the last ship afloat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EndgamePlan {
+    /// Placements the ship could still take, all equally likely.
+    pub candidates: usize,
+    /// The shot to take, when the candidates were few enough to search.
+    pub best: Option<EndgameShot>,
+}
+
+/// The shot expected to sink the last ship soonest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EndgameShot {
+    pub row: usize,
+    pub col: usize,
+    /// Shots expected to sink the ship, this one included, when every
+    /// later shot is chosen the same way.
+    pub expected_shots: f64,
+}
+
+/// Plan the hunt for the last ship afloat, or `None` unless exactly one is.
+///
+/// The ship's candidates are its placements that cover every hit not put
+/// down to a sunk ship and no other shot cell (nor, when ships may not
+/// touch, a cell next to a wreck). When there are at most `max_candidates`
+/// of them, every sequence of shots over their cells is weighed, each shot
+/// splitting the candidates into a miss, a hit or a sinking, and the first
+/// shot of a sequence with the fewest shots expected is returned.
+///
+/// `view.sunk` must hold every sunk ship's cells; without them the stray
+/// hits could not be told from the last ship's and no plan is made. No shot
+/// is suggested when the search outgrows its budget.
+pub fn plan_endgame(view: &TargetingView<'_>, max_candidates: usize) -> Option<EndgamePlan> {
+    let mut afloat = view.remaining.iter().copied().filter(|&l| l > 0);
+    let len = afloat.next()?;
+    if afloat.next().is_some() {
+        return None;
+    }
+    let sunk_cells = view.fleet.total_cells().saturating_sub(len);
+    if (*view.hits & *view.sunk).count_ones() != sunk_cells {
+        return None;
+    }
+    let unresolved = *view.hits & !*view.sunk;
+    let wrecks = if view.placement.no_touching { view.sunk.dilate(true) } else { *view.sunk };
+    let blocked = (*view.misses | wrecks) & !unresolved;
+
+    let mut masks = [BB::new(); CANDIDATE_CAPACITY];
+    let mut candidates = 0;
+    let wounds = unresolved.count_ones();
+    for p in enumerate_placements(len, &unresolved, &blocked).filter(|p| p.hits == wounds && p.hits < len) {
+        if candidates < CANDIDATE_CAPACITY {
+            masks[candidates] = p.mask;
+        }
+        candidates += 1;
+    }
+
+    let searchable = candidates > 0 && candidates <= max_candidates.min(CANDIDATE_CAPACITY);
+    let best = searchable
+        .then(|| {
+            EndgameSearch::new(&masks[..candidates]).best(u32::MAX >> (32 - candidates), unresolved)
+        })
+        .flatten()
+        .map(|(expected_shots, (row, col))| EndgameShot { row, col, expected_shots });
+    Some(EndgamePlan { candidates, best })
+}
+
+/// A solved position: the candidates still possible and the hits among
+/// their cells.
+#[derive(Clone, Copy)]
+struct Memo {
+    alive: u32,
+    hits: BB,
+    expected: f64,
+    target: (u8, u8),
+}
+
+impl Memo {
+    /// No candidate set is empty, so this matches no position.
+    const EMPTY: Memo = Memo {
+        alive: 0,
+        hits: BB::new(),
+        expected: 0.0,
+        target: (0, 0),
+    };
+}
+
+/// A shot worth considering: a cell and the candidates covering it.
+#[derive(Clone, Copy, Default)]
+struct Shot {
+    row: usize,
+    col: usize,
+    cover: u32,
+}
+
+/// Expectimax over the last ship's candidates for [`plan_endgame`].
+struct EndgameSearch<'a> {
+    masks: &'a [BB],
+    /// Every cell some candidate covers.
+    shots: [Shot; GRID_SIZE * GRID_SIZE],
+    shot_count: usize,
+    nodes: usize,
+    memo: [Memo; MEMO_SLOTS],
+}
+
+impl<'a> EndgameSearch<'a> {
+    fn new(masks: &'a [BB]) -> Self {
+        let mut cells = BB::new();
+        for mask in masks {
+            cells |= *mask;
+        }
+        let mut shots = [Shot::default(); GRID_SIZE * GRID_SIZE];
+        let mut shot_count = 0;
+        for (row, col) in cells.iter_set_bits() {
+            let cover = (0..masks.len())
+                .filter(|&i| masks[i].get(row, col).unwrap_or(false))
+                .fold(0, |cover, i| cover | 1 << i);
+            shots[shot_count] = Shot { row, col, cover };
+            shot_count += 1;
+        }
+        Self {
+            masks,
+            shots,
+            shot_count,
+            nodes: 0,
+            memo: [Memo::EMPTY; MEMO_SLOTS],
+        }
+    }
+
+    /// Fewest shots expected to sink the ship when it lies, equally likely,
+    /// on one of the candidates in `alive`, `hits` having been shot, and
+    /// the cell to shoot first. `None` once the node budget is spent.
+    fn best(&mut self, alive: u32, hits: BB) -> Option<(f64, (usize, usize))> {
+        let mut cells = BB::new();
+        for i in members(alive) {
+            cells |= self.masks[i];
+        }
+        let hits = hits & cells;
+        let slot = slot(alive, &hits);
+        let memo = self.memo[slot];
+        if memo.alive == alive && memo.hits == hits {
+            return Some((memo.expected, (memo.target.0 as usize, memo.target.1 as usize)));
+        }
+        self.nodes += 1;
+        if self.nodes > ENDGAME_NODE_BUDGET {
+            return None;
+        }
+
+        // Cells covered by the same candidates are interchangeable; those
+        // splitting the candidates most evenly usually do best, so they go
+        // first to cut the rest short sooner
+        let n = alive.count_ones();
+        let mut moves = [Shot::default(); GRID_SIZE * GRID_SIZE];
+        let mut count = 0;
+        for shot in &self.shots[..self.shot_count] {
+            let cover = shot.cover & alive;
+            if cover == 0 || hits.get(shot.row, shot.col).unwrap_or(true) {
+                continue;
+            }
+            if moves[..count].iter().any(|m| m.cover == cover) {
+                continue;
+            }
+            moves[count] = Shot { cover, ..*shot };
+            count += 1;
+        }
+        moves[..count].sort_by_key(|m| (2 * m.cover.count_ones()).abs_diff(n));
+
+        let share = |set: u32| set.count_ones() as f64 / n as f64;
+        let mut best: Option<(f64, (usize, usize))> = None;
+        let beaten = |expected: f64, best: Option<(f64, (usize, usize))>| best.is_some_and(|(b, _)| expected >= b);
+        for &Shot { row, col, cover } in &moves[..count] {
+            let mut after = hits;
+            after.set(row, col).ok()?;
+            let sinks = self.subset(cover, |mask| (*mask & !after).is_empty());
+            let (missed, wounded) = (alive & !cover, cover & !sinks);
+            // Every candidate left afloat still has its unhit cells to shoot
+            let (missed_bound, wounded_bound) = (self.bound(missed, &hits), self.bound(wounded, &after));
+            let mut expected = 1.0 + share(missed) * missed_bound + share(wounded) * wounded_bound;
+            if beaten(expected, best) {
+                continue;
+            }
+            if missed != 0 {
+                expected += share(missed) * (self.best(missed, hits)?.0 - missed_bound);
+                if beaten(expected, best) {
+                    continue;
+                }
+            }
+            if wounded != 0 {
+                expected += share(wounded) * (self.best(wounded, after)?.0 - wounded_bound);
+            }
+            if !beaten(expected, best) {
+                best = Some((expected, (row, col)));
+            }
+        }
+
+        let (expected, (row, col)) = best?;
+        self.memo[slot] = Memo {
+            alive,
+            hits,
+            expected,
+            target: (row as u8, col as u8),
+        };
+        best
+    }
+
+    /// Mean cells the candidates in `set` have not had hit: no way of
+    /// sinking the ship takes fewer shots on average. Zero for no candidates.
+    fn bound(&self, set: u32, hits: &BB) -> f64 {
+        if set == 0 {
+            return 0.0;
+        }
+        let unhit: u32 = members(set).map(|i| (self.masks[i] & !*hits).count_ones() as u32).sum();
+        unhit as f64 / set.count_ones() as f64
+    }
+
+    /// The candidates in `set` whose cells satisfy `keep`.
+    fn subset(&self, set: u32, keep: impl Fn(&BB) -> bool) -> u32 {
+        members(set).filter(|&i| keep(&self.masks[i])).fold(0, |subset, i| subset | 1 << i)
+    }
+}
+
+/// Indices of the candidates in `set`.
+fn members(set: u32) -> impl Iterator<Item = usize> {
+    (0..32).filter(move |&i| set >> i & 1 == 1)
+}
+
+/// Memo slot for a position.
+fn slot(alive: u32, hits: &BB) -> usize {
+    let mixed = hits
+        .iter_set_bits()
+        .fold(alive as u64, |h, (r, c)| (h ^ (r * GRID_SIZE + c) as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    (mixed.wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 54) as usize % MEMO_SLOTS
+}
@@ -0,0 +1,59 @@
+// Typed game events for observers (UI, metrics, loggers, spectators).
+// Uses no_std and avoids heap allocations.
+
+use super::{common::GuessResult, game::GameStatus};
+
+/// Something that happened during a game, as seen by one player.
+///
+/// Coordinates are `(row, col)` on the board the shot landed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    /// Protocol handshake with the peer succeeded.
+    HandshakeComplete,
+    /// Coin flip and setup finished; `first_move` is whether we shoot first.
+    GameStarted { first_move: bool },
+    /// We fired at the opponent.
+    GuessSent { row: usize, col: usize },
+    /// The opponent answered our shot.
+    ResultReceived {
+        row: usize,
+        col: usize,
+        result: GuessResult,
+    },
+    /// The opponent fired at our board and we answered.
+    OpponentGuess {
+        row: usize,
+        col: usize,
+        result: GuessResult,
+    },
+    /// A ship went down; `ours` is true when it was one of our ships.
+    Sink { ship: &'static str, ours: bool },
+    /// A full game state sync was applied.
+    StateSynced,
+    /// A turn clock ran out; `ours` is true when it was our turn.
+    TurnTimedOut { ours: bool },
+    /// The game reached a final status.
+    GameOver { status: GameStatus },
+}
+
+/// Receives [`GameEvent`]s as they happen.
+///
+/// Implemented for any `FnMut(&GameEvent)` closure, which is the way to
+/// observe a game without `std`.
+pub trait EventListener {
+    fn on_event(&mut self, event: &GameEvent);
+}
+
+impl<F: FnMut(&GameEvent)> EventListener for F {
+    fn on_event(&mut self, event: &GameEvent) {
+        self(event)
+    }
+}
+
+#[cfg(feature = "std")]
+impl EventListener for tokio::sync::broadcast::Sender<GameEvent> {
+    /// Publishes to every current subscriber; events are dropped when there are none.
+    fn on_event(&mut self, event: &GameEvent) {
+        let _ = self.send(*event);
+    }
+}
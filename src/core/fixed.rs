@@ -0,0 +1,144 @@
+// Integer-only placement density for targets without floating point.
+// Uses no_std and avoids heap allocations.
+
+use super::{
+    ai::{enumerate_placements, hunt_lattice, AiConfig},
+    board::halo,
+    config::{BoardBits, FleetConfig, PlacementRules, MAX_BOARD_SIZE},
+    perf::{self, Counter},
+};
+use rand::Rng;
+
+/// Bitboard type alias for convenience.
+type BB = BoardBits;
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+/// Hit bias of [`FixedConfig::default`], the same as [`AiConfig`]'s.
+pub const DEFAULT_FIXED_HIT_BIAS: u32 = 50;
+
+/// Tunables for [`calc_pdf_fixed_with_sunk`]: the integer counterparts of
+/// [`AiConfig::hit_bias`] and [`AiConfig::parity`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FixedConfig {
+    /// Weight multiplier per observed hit covered by a candidate placement.
+    pub hit_bias: u32,
+    /// While hunting, only score cells on a lattice spaced by the shortest
+    /// remaining ship.
+    pub parity: bool,
+}
+
+impl Default for FixedConfig {
+    fn default() -> Self {
+        Self {
+            hit_bias: DEFAULT_FIXED_HIT_BIAS,
+            parity: false,
+        }
+    }
+}
+
+impl From<&AiConfig> for FixedConfig {
+    /// Rounds `hit_bias` to the nearest whole number, negative ones to zero.
+    fn from(config: &AiConfig) -> Self {
+        Self {
+            hit_bias: (config.hit_bias + 0.5) as u32,
+            parity: config.parity,
+        }
+    }
+}
+
+/// [`calc_pdf`](crate::calc_pdf) in integer arithmetic: each cell scores
+/// the summed weights of the placements covering it, unnormalised.
+///
+/// A placement over `k` unresolved hits weighs `hit_bias^k`, exactly the
+/// weight the floating-point density gives it before normalising, so the
+/// cells rank the same; only a bias large enough to saturate `u32` can set
+/// them apart. Pick from the scores with [`argmax_fixed`] or
+/// [`sample_fixed`] to target without floating point at all.
+pub fn calc_pdf_fixed(hits: &BB, misses: &BB, remaining_lengths: &[usize]) -> [[u32; GRID_SIZE]; GRID_SIZE] {
+    calc_pdf_fixed_with_sunk(
+        hits,
+        &BB::new(),
+        misses,
+        remaining_lengths,
+        &FleetConfig::STANDARD,
+        &PlacementRules::STANDARD,
+        &FixedConfig::default(),
+    )
+}
+
+/// [`calc_pdf_fixed`] knowing which hits belong to sunk ships and the rules
+/// the fleet was placed under, as [`calc_pdf_with_sunk`](crate::calc_pdf_with_sunk)
+/// takes them.
+pub fn calc_pdf_fixed_with_sunk(
+    hits: &BB,
+    sunk: &BB,
+    misses: &BB,
+    remaining_lengths: &[usize],
+    fleet: &FleetConfig,
+    placement: &PlacementRules,
+    config: &FixedConfig,
+) -> [[u32; GRID_SIZE]; GRID_SIZE] {
+    perf::count(Counter::Densities, 1);
+    let unresolved = *hits & !*sunk;
+    let wrecks = if placement.no_touching { sunk.dilate(true) } else { *sunk };
+    let blocked = *misses | wrecks;
+
+    let mut matrix = [[0u32; GRID_SIZE]; GRID_SIZE];
+    for &len in remaining_lengths {
+        for p in enumerate_placements(len, &unresolved, &blocked) {
+            if placement.no_touching && !(halo(&p.mask) & !p.mask & unresolved).is_empty() {
+                continue;
+            }
+            let weight = config.hit_bias.saturating_pow(p.hits as u32);
+            for (r, c) in (p.mask & !unresolved).iter_set_bits() {
+                matrix[r][c] = matrix[r][c].saturating_add(weight);
+            }
+        }
+    }
+
+    // Off the lattice nothing scores, unless that would leave no score
+    if config.parity {
+        if let Some(lattice) = hunt_lattice(hits, &blocked, remaining_lengths, fleet) {
+            if lattice.iter_set_bits().any(|(r, c)| matrix[r][c] > 0) {
+                for (r, c) in (!lattice).iter_set_bits() {
+                    matrix[r][c] = 0;
+                }
+            }
+        }
+    }
+    matrix
+}
+
+/// The highest-scoring cell, the first in row-major order on a tie, as
+/// [`argmax_pdf`](crate::argmax_pdf) picks from a density.
+pub fn argmax_fixed(scores: &[[u32; GRID_SIZE]; GRID_SIZE]) -> (usize, usize) {
+    let peak = scores.iter().flatten().copied().max().unwrap_or(0);
+    (0..GRID_SIZE)
+        .flat_map(|r| (0..GRID_SIZE).map(move |c| (r, c)))
+        .find(|&(r, c)| scores[r][c] == peak)
+        .unwrap_or((0, 0))
+}
+
+/// Sample a cell in proportion to its score, or uniformly when none
+/// scores. Without a temperature this samples as
+/// [`sample_pdf`](crate::sample_pdf) does at temperature one.
+pub fn sample_fixed<R: Rng + ?Sized>(scores: &[[u32; GRID_SIZE]; GRID_SIZE], rng: &mut R) -> (usize, usize) {
+    perf::count(Counter::Samples, 1);
+    let total: u64 = scores.iter().flatten().map(|&s| s as u64).sum();
+    if total == 0 {
+        return (rng.random_range(0..GRID_SIZE), rng.random_range(0..GRID_SIZE));
+    }
+    let threshold = rng.random_range(0..total);
+    let mut cumulative = 0u64;
+    for (r, row) in scores.iter().enumerate() {
+        for (c, &s) in row.iter().enumerate() {
+            cumulative += s as u64;
+            if threshold < cumulative {
+                return (r, c);
+            }
+        }
+    }
+    (GRID_SIZE - 1, GRID_SIZE - 1)
+}
@@ -1,62 +1,516 @@
 use super::{
-    bitboard::BitBoard,
+    bitboard::BitBoardError,
     board::{Board, BoardState},
     common::{BoardError, GuessResult},
-    config::{BOARD_SIZE, NUM_SHIPS, SHIPS, TOTAL_SHIP_CELLS},
+    config::{BoardBits, FleetConfig, PlacementRules, RuleSet, BOARD_SIZE, MAX_SHIPS},
+    coord::Coord,
+    delta::{DeltaError, StateDelta},
+    history::{Move, MoveLog, ReplayError, Shooter},
+    opponent_model::OpponentModel,
 };
 
 /// Bitboard type used for game state tracking.
-type BB = BitBoard<u128, { BOARD_SIZE as usize }>;
+type BB = BoardBits;
 
 /// Public state of the player's guesses against the opponent.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GuessBoardState {
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
     pub hits: BB,
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::board_bits"))]
     pub misses: BB,
 }
 
 /// Serializable overall game state.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameState {
     pub my_board: BoardState,
     pub my_guesses: GuessBoardState,
-    /// Which enemy ships remain afloat (by index)
-    pub enemy_ships_remaining: [bool; NUM_SHIPS as usize],
+    /// Which enemy ships remain afloat (by index); `false` past the end of
+    /// the fleet
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::fleet_slots"))]
+    pub enemy_ships_remaining: [bool; MAX_SHIPS],
     /// Number of enemy ship cells remaining
     pub enemy_remaining: usize,
 }
 
+impl GameState {
+    /// Stable hash of the shots on both boards: the hits and misses on our
+    /// board, then our hits and misses on the opponent's. Ship positions are
+    /// left out, so two honest peers can compare digests without revealing
+    /// their fleets; see [`GameState::mirrored_digest`].
+    pub fn digest(&self) -> u64 {
+        shots_digest(
+            [self.my_board.hits, self.my_board.misses],
+            [self.my_guesses.hits, self.my_guesses.misses],
+        )
+    }
+
+    /// The [`GameState::digest`] the opponent's state should have: our
+    /// guesses are the shots on its board and the shots on ours are its
+    /// guesses.
+    pub fn mirrored_digest(&self) -> u64 {
+        shots_digest(
+            [self.my_guesses.hits, self.my_guesses.misses],
+            [self.my_board.hits, self.my_board.misses],
+        )
+    }
+}
+
+/// FNV-1a over the raw bits of the shots received, then the shots fired.
+fn shots_digest(received: [BB; 2], fired: [BB; 2]) -> u64 {
+    const OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    received
+        .into_iter()
+        .chain(fired)
+        .flat_map(raw_bytes)
+        .fold(OFFSET, |hash, byte| (hash ^ u64::from(byte)).wrapping_mul(PRIME))
+}
+
+/// The storage bits of `bb`, least significant byte first.
+#[cfg(not(feature = "large-boards"))]
+fn raw_bytes(bb: BB) -> impl Iterator<Item = u8> {
+    bb.into_raw().to_le_bytes().into_iter()
+}
+
+/// The storage bits of `bb`, least significant byte first. Shots that all
+/// fit a standard board are hashed in the standard `u128` layout, so a game
+/// digests the same whether or not either peer was built with large boards.
+#[cfg(feature = "large-boards")]
+fn raw_bytes(bb: BB) -> impl Iterator<Item = u8> {
+    const N: usize = BOARD_SIZE as usize;
+    let mut bytes = [0u8; 24];
+    let mut standard = 0u128;
+    let mut fits = true;
+    for (r, c) in bb.iter_set_bits() {
+        if r < N && c < N {
+            standard |= 1 << (r * N + c);
+        } else {
+            fits = false;
+        }
+    }
+    let len = if fits {
+        bytes[..16].copy_from_slice(&standard.to_le_bytes());
+        16
+    } else {
+        for (chunk, word) in bytes.chunks_exact_mut(8).zip(bb.into_raw()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        24
+    };
+    bytes.into_iter().take(len)
+}
+
 /// Current status of a game.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum GameStatus {
     InProgress,
     Won,
     Lost,
+    /// The opponent's reported sinks and the count of enemy cells hit
+    /// disagree, so the outcome cannot be trusted.
+    Desync,
+    /// The game was called off before either fleet was sunk.
+    Aborted,
+    /// We conceded the game; the opponent is recorded as the winner.
+    Forfeited,
+    /// Neither side won: the move limit was reached, or both sides resigned
+    /// at once.
+    Draw,
+}
+
+/// How a guess at an already-targeted cell is treated, on both our board
+/// and our record of guesses against the opponent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DuplicateGuessPolicy {
+    /// Reject the guess with `BoardError::AlreadyGuessed`.
+    #[default]
+    Error,
+    /// Answer with the result the cell gave the first time, leaving the
+    /// game unchanged. Suits peers that retransmit a guess whose answer was
+    /// lost.
+    IdempotentReturnPrior,
+    /// Answer `Miss`, leaving the game unchanged: the shooter loses the turn.
+    WastedTurn,
+}
+
+/// Who fires next once a shot has been answered. Both peers must agree on
+/// it, so it is part of the [`RuleSet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TurnPolicy {
+    /// Players take strictly alternate turns.
+    #[default]
+    Alternate,
+    /// A player keeps firing after every hit and passes the turn on a miss.
+    /// Under salvo rules a volley with any hit keeps the turn.
+    ContinueOnHit,
+}
+
+impl TurnPolicy {
+    /// Whether the shooter fires again after a new shot answered `result`.
+    /// Repeated shots never keep the turn.
+    pub fn keeps_turn(self, result: GuessResult) -> bool {
+        match self {
+            TurnPolicy::Alternate => false,
+            TurnPolicy::ContinueOnHit => result != GuessResult::Miss,
+        }
+    }
+}
+
+/// What the waiting side does when the opponent's turn clock runs out.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TimeoutAction {
+    /// The late player forfeits the game.
+    #[default]
+    Forfeit,
+    /// The turn is played on the late player's behalf, at random cells it
+    /// has not fired at yet.
+    RandomMove,
+}
+
+/// Deadline for each turn. Both peers enforce it on each other, so it is
+/// part of the [`RuleSet`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TurnClock {
+    /// Milliseconds a player has to fire once its turn starts.
+    pub limit_ms: u32,
+    pub on_timeout: TimeoutAction,
+}
+
+impl TurnClock {
+    /// A clock allowing `limit_ms` milliseconds per turn.
+    pub const fn new(limit_ms: u32, on_timeout: TimeoutAction) -> Self {
+        TurnClock { limit_ms, on_timeout }
+    }
+
+    /// Time allowed per turn.
+    pub const fn limit(&self) -> core::time::Duration {
+        core::time::Duration::from_millis(self.limit_ms as u64)
+    }
+}
+
+/// How one of our ships fared under the opponent's fire, see
+/// [`GameEngine::defense_report`]. Turns count the shots the opponent has
+/// fired at us, starting from 1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ShipDefense {
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub name: &'static str,
+    pub length: usize,
+    /// Shots this ship has absorbed.
+    pub hits: usize,
+    /// Turn of the first hit on this ship, if known.
+    pub first_hit: Option<usize>,
+    /// Turn this ship was sunk, if known.
+    pub sunk_at: Option<usize>,
+    /// Turns the ship stayed afloat: until it sank, or every turn so far.
+    pub turns_survived: usize,
+}
+
+/// Incoming-fire summary for our whole fleet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DefenseReport {
+    /// One entry per fleet slot; slots past the end of the fleet are
+    /// unnamed and never hit.
+    pub ships: [ShipDefense; MAX_SHIPS],
+    /// Shots the opponent has fired at us.
+    pub shots_received: usize,
+    /// Those shots that hit a ship.
+    pub hits_received: usize,
+}
+
+impl DefenseReport {
+    /// Index of the ship that sank first, if any sank with a known turn.
+    pub fn first_ship_lost(&self) -> Option<usize> {
+        self.ships
+            .iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.sunk_at.map(|turn| (turn, i)))
+            .min()
+            .map(|(_, i)| i)
+    }
+
+    /// Share of the opponent's shots that hit, rounded to the nearest percent.
+    pub fn opponent_accuracy_percent(&self) -> u8 {
+        if self.shots_received == 0 {
+            return 0;
+        }
+        ((self.hits_received * 100 + self.shots_received / 2) / self.shots_received) as u8
+    }
+}
+
+/// Running totals of our own fire and how long our fleet has held out,
+/// maintained by the engine as shots are recorded; see
+/// [`GameEngine::stats`]. Repeated shots are not counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    /// Our shots that hit a ship, sinking shots included.
+    pub hits: usize,
+    /// Our shots that found open water.
+    pub misses: usize,
+    /// Hits in a row since our last miss.
+    pub hit_streak: usize,
+    /// Longest run of hits without a miss between them.
+    pub longest_hit_streak: usize,
+    /// Enemy ships we have sunk.
+    pub ships_sunk: usize,
+    /// Opponent shots our fleet has come through with a ship still afloat.
+    pub turns_survived: usize,
+}
+
+impl Stats {
+    /// Shots we have fired.
+    pub fn shots(&self) -> usize {
+        self.hits + self.misses
+    }
+
+    /// Share of our shots that hit, rounded to the nearest percent.
+    pub fn accuracy_percent(&self) -> u8 {
+        if self.shots() == 0 {
+            return 0;
+        }
+        ((self.hits * 100 + self.shots() / 2) / self.shots()) as u8
+    }
+
+    /// Shots fired for each ship sunk, or `None` before the first sink.
+    pub fn shots_per_sink(&self) -> Option<f32> {
+        (self.ships_sunk > 0).then(|| self.shots() as f32 / self.ships_sunk as f32)
+    }
+
+    fn record_shot(&mut self, result: GuessResult) {
+        match result {
+            GuessResult::Miss => {
+                self.misses += 1;
+                self.hit_streak = 0;
+            }
+            GuessResult::Hit | GuessResult::Sink(_) => {
+                self.hits += 1;
+                self.hit_streak += 1;
+                self.longest_hit_streak = self.longest_hit_streak.max(self.hit_streak);
+                self.ships_sunk += matches!(result, GuessResult::Sink(_)) as usize;
+            }
+        }
+    }
 }
 
+/// Cross-field inconsistency in a [`GameEngine`], reported by
+/// [`GameEngine::check_invariants`]. None of these can arise from any
+/// sequence of moves, honest or not; each points at a bug in the engine or
+/// in code that rebuilt it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvariantViolation {
+    /// Cells hit on our board that hold no ship.
+    HitOffShip { cells: usize },
+    /// Cells recorded as misses on our board that hold a ship.
+    MissOnShip { cells: usize },
+    /// The ship map and the placed ships cover different numbers of cells.
+    ShipMapMismatch { map_cells: usize, fleet_cells: usize },
+    /// A ship's sunk flag disagrees with the hits on its cells.
+    SunkFlagMismatch { ship: &'static str, hits: usize, length: usize, sunk: bool },
+    /// Cells recorded as both a hit and a miss on the opponent's board.
+    OverlappingGuesses { cells: usize },
+    /// The enemy cell counter does not match our recorded hits.
+    EnemyRemainingMismatch { recorded: usize, expected: usize },
+    /// Fewer shots counted against us than marked on our board, or more
+    /// when repeats are not counted.
+    ShotCountMismatch { counted: usize, marked: usize },
+    /// Sink bookkeeping for one of our ships disagrees with the board.
+    SinkRecordMismatch { ship: &'static str },
+}
+
+impl core::fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            InvariantViolation::HitOffShip { cells } => write!(f, "{} hit cell(s) hold no ship", cells),
+            InvariantViolation::MissOnShip { cells } => write!(f, "{} missed cell(s) hold a ship", cells),
+            InvariantViolation::ShipMapMismatch { map_cells, fleet_cells } => write!(
+                f,
+                "ship map covers {} cells but the placed ships cover {}",
+                map_cells, fleet_cells
+            ),
+            InvariantViolation::SunkFlagMismatch { ship, hits, length, sunk } => write!(
+                f,
+                "{} has {}/{} cells hit but sunk = {}",
+                ship, hits, length, sunk
+            ),
+            InvariantViolation::OverlappingGuesses { cells } => {
+                write!(f, "{} guessed cell(s) recorded as both hit and miss", cells)
+            }
+            InvariantViolation::EnemyRemainingMismatch { recorded, expected } => write!(
+                f,
+                "{} enemy cells recorded as remaining, guess hits imply {}",
+                recorded, expected
+            ),
+            InvariantViolation::ShotCountMismatch { counted, marked } => write!(
+                f,
+                "{} incoming shots counted but {} marked on the board",
+                counted, marked
+            ),
+            InvariantViolation::SinkRecordMismatch { ship } => {
+                write!(f, "sink record for {} disagrees with the board", ship)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvariantViolation {}
+
 /// Core game logic holding the player's board and guess history.
 pub struct GameEngine {
+    rules: RuleSet,
     board: Board,
     guess_hits: BB,
     guess_misses: BB,
     enemy_remaining: usize,
-    enemy_ships_remaining: [bool; NUM_SHIPS as usize],
+    enemy_ships_remaining: [bool; MAX_SHIPS],
+    duplicate_policy: DuplicateGuessPolicy,
+    /// Cell whose hit sank each of our ships, so a repeated shot there can
+    /// be answered `Sink` again under `IdempotentReturnPrior`.
+    sinking_shots: [Option<(usize, usize)>; MAX_SHIPS],
+    /// Shots the opponent has fired at us.
+    shots_received: usize,
+    /// Turn each of our ships was first hit.
+    first_hits: [Option<usize>; MAX_SHIPS],
+    /// Turn each of our ships was sunk.
+    sunk_turns: [Option<usize>; MAX_SHIPS],
+    aborted: bool,
+    /// Who conceded, if the game ended by resignation: `Some(true)` when we
+    /// did, `Some(false)` when the opponent did.
+    resigned: Option<bool>,
+    /// The opponent's resignation crossed ours.
+    drawn: bool,
+    /// Every shot recorded so far, both ways.
+    history: MoveLog,
+    stats: Stats,
+    /// Which of our hits belong to which sunk enemy ship.
+    model: OpponentModel,
 }
 
 impl GameEngine {
-    /// Create a new engine with an empty board and no guesses recorded.
+    /// Create a new engine for the standard rules with an empty board and
+    /// no guesses recorded.
     pub fn new() -> Self {
+        Self::new_with_config(RuleSet::STANDARD)
+    }
+
+    /// Start configuring an engine; see [`GameEngineBuilder`].
+    pub const fn builder() -> GameEngineBuilder {
+        GameEngineBuilder::new()
+    }
+
+    /// Create a new engine playing by `rules`, with an empty board and no
+    /// guesses recorded.
+    pub fn new_with_config(rules: RuleSet) -> Self {
         Self {
-            board: Board::new(),
+            rules,
+            board: Board::with_rules(&rules),
             guess_hits: BB::new(),
             guess_misses: BB::new(),
-            enemy_remaining: TOTAL_SHIP_CELLS,
-            enemy_ships_remaining: [true; NUM_SHIPS as usize],
+            enemy_remaining: rules.fleet().total_cells(),
+            enemy_ships_remaining: core::array::from_fn(|i| i < rules.fleet().len()),
+            duplicate_policy: DuplicateGuessPolicy::Error,
+            sinking_shots: [None; MAX_SHIPS],
+            shots_received: 0,
+            first_hits: [None; MAX_SHIPS],
+            sunk_turns: [None; MAX_SHIPS],
+            aborted: false,
+            resigned: None,
+            drawn: false,
+            history: MoveLog::new(),
+            stats: Stats::default(),
+            model: OpponentModel::new(),
         }
     }
 
+    /// Treat repeated guesses according to `policy` instead of rejecting
+    /// them.
+    pub fn with_duplicate_policy(mut self, policy: DuplicateGuessPolicy) -> Self {
+        self.duplicate_policy = policy;
+        self
+    }
+
+    /// How repeated guesses are treated.
+    pub fn duplicate_policy(&self) -> DuplicateGuessPolicy {
+        self.duplicate_policy
+    }
+
+    /// Rules this game is played by.
+    pub fn rules(&self) -> RuleSet {
+        self.rules
+    }
+
+    /// Who fires next after a shot.
+    pub fn turn_policy(&self) -> TurnPolicy {
+        self.rules.turn_policy()
+    }
+
+    /// Deadline for each turn, if turns are timed.
+    pub fn turn_clock(&self) -> Option<TurnClock> {
+        self.rules.turn_clock()
+    }
+
+    /// Shots each side may fire before an undecided game is drawn, if any.
+    pub fn move_limit(&self) -> Option<u16> {
+        self.rules.move_limit()
+    }
+
+    /// Cells the opponent's floating ships cannot occupy: our misses, the
+    /// cells of sunk ships placed by the [`OpponentModel`] (with their
+    /// surroundings when ships may not touch), and any storage cells outside
+    /// the board. Pass this as the misses of a targeting strategy so it
+    /// never aims off a reduced board or builds a live ship through a wreck.
+    pub fn blocked_cells(&self) -> BB {
+        let mut wrecks = self.model.sunk_cells();
+        if self.rules.placement().no_touching {
+            wrecks = wrecks.dilate(true);
+        }
+        self.guess_misses | wrecks | self.rules.off_board()
+    }
+
+    /// Which of our hits belong to which sunk enemy ship, as far as the
+    /// sink reports tell.
+    pub fn opponent_model(&self) -> &OpponentModel {
+        &self.model
+    }
+
+    /// Shots we fire this turn: one, or under salvo rules one per ship we
+    /// still have afloat, but never more than the enemy cells not yet
+    /// guessed.
+    pub fn shots_per_turn(&self) -> usize {
+        if !self.rules.salvo() {
+            return 1;
+        }
+        let open = self.board_cells() - (self.guess_hits | self.guess_misses).count_ones();
+        self.board.fleet_report().ships_afloat().min(open)
+    }
+
+    /// Shots the opponent fires this turn, counted like
+    /// [`GameEngine::shots_per_turn`] from the sink reports we received.
+    pub fn opponent_shots_per_turn(&self) -> usize {
+        if !self.rules.salvo() {
+            return 1;
+        }
+        let open = self.board_cells() - (self.board.hits() | self.board.misses()).count_ones();
+        let afloat = self.enemy_ships_remaining.iter().filter(|&&afloat| afloat).count();
+        afloat.min(open)
+    }
+
+    fn board_cells(&self) -> usize {
+        let size = self.rules.board_size() as usize;
+        size * size
+    }
+
     /// Mutable reference to the player's board for ship placement.
     pub fn board_mut(&mut self) -> &mut Board {
         &mut self.board
@@ -77,31 +531,159 @@ impl GameEngine {
         self.guess_misses
     }
 
-    /// Handle an opponent guess on the player's board.
+    /// Which enemy ships have not been reported sunk (by index); `false`
+    /// past the end of the fleet.
+    pub fn enemy_ships_remaining(&self) -> [bool; MAX_SHIPS] {
+        self.enemy_ships_remaining
+    }
+
+    /// Number of enemy ship cells not yet hit.
+    pub fn enemy_cells_remaining(&self) -> usize {
+        self.enemy_remaining
+    }
+
+    /// Whether the opponent has already fired at (row, col) on our board.
+    pub fn opponent_guessed(&self, row: usize, col: usize) -> bool {
+        self.board.hits().get(row, col).unwrap_or(false) || self.board.misses().get(row, col).unwrap_or(false)
+    }
+
+    /// Whether we have already fired at (row, col) on the opponent's board.
+    pub fn already_guessed(&self, row: usize, col: usize) -> bool {
+        self.guess_hits.get(row, col).unwrap_or(false) || self.guess_misses.get(row, col).unwrap_or(false)
+    }
+
+    /// Handle an opponent guess on the player's board. A repeated guess is
+    /// handled according to the [`DuplicateGuessPolicy`].
     pub fn opponent_guess(&mut self, row: usize, col: usize) -> Result<GuessResult, BoardError> {
-        self.board.guess(row, col)
+        if self.opponent_guessed(row, col) {
+            return match self.duplicate_policy {
+                DuplicateGuessPolicy::Error => Err(BoardError::AlreadyGuessed),
+                DuplicateGuessPolicy::IdempotentReturnPrior => Ok(self.prior_result(row, col)),
+                DuplicateGuessPolicy::WastedTurn => {
+                    self.shots_received += 1;
+                    self.stats.turns_survived += !self.board.all_sunk() as usize;
+                    self.debug_check();
+                    Ok(GuessResult::Miss)
+                }
+            };
+        }
+        let result = self.board.guess(Coord::new(row, col))?;
+        self.shots_received += 1;
+        self.stats.turns_survived += !self.board.all_sunk() as usize;
+        let turn = self.shots_received;
+        if let Some(idx) = self.board.ship_index_at(row, col) {
+            self.first_hits[idx].get_or_insert(turn);
+        }
+        if let (GuessResult::Sink(_), Some(idx)) = (result, self.board.ship_index_at(row, col)) {
+            self.sinking_shots[idx] = Some((row, col));
+            self.sunk_turns[idx] = Some(turn);
+        }
+        self.history.push(Move {
+            shooter: Shooter::Opponent,
+            row,
+            col,
+            result,
+        });
+        self.debug_check();
+        Ok(result)
+    }
+
+    /// Fire one shot at our own board on the opponent's behalf, at a random
+    /// on-board cell it has not fired at yet; used when its turn clock runs
+    /// out. Returns the cell and its result, or `AlreadyGuessed` when every
+    /// cell has been fired at.
+    pub fn play_for_opponent<R: rand::Rng + ?Sized>(
+        &mut self,
+        rng: &mut R,
+    ) -> Result<(usize, usize, GuessResult), BoardError> {
+        let size = self.rules.board_size() as usize;
+        let open = |r: usize, c: usize| !self.opponent_guessed(r, c);
+        let count = (0..size).flat_map(|r| (0..size).map(move |c| (r, c))).filter(|&(r, c)| open(r, c)).count();
+        if count == 0 {
+            return Err(BoardError::AlreadyGuessed);
+        }
+        let pick = rng.random_range(0..count);
+        let (row, col) = (0..size)
+            .flat_map(|r| (0..size).map(move |c| (r, c)))
+            .filter(|&(r, c)| open(r, c))
+            .nth(pick)
+            .ok_or(BoardError::AlreadyGuessed)?;
+        let result = self.opponent_guess(row, col)?;
+        Ok((row, col, result))
+    }
+
+    /// Which of our ships have absorbed the opponent's shots and when, and
+    /// how accurate the opponent has been. An engine restored from a saved
+    /// state counts earlier shots but does not know their turns.
+    pub fn defense_report(&self) -> DefenseReport {
+        let fleet = self.board.fleet_report();
+        DefenseReport {
+            ships: core::array::from_fn(|i| {
+                let ship = fleet.ships[i];
+                ShipDefense {
+                    name: ship.name,
+                    length: ship.length,
+                    hits: ship.hits,
+                    first_hit: self.first_hits[i],
+                    sunk_at: self.sunk_turns[i],
+                    turns_survived: self.sunk_turns[i].unwrap_or(self.shots_received),
+                }
+            }),
+            shots_received: self.shots_received,
+            hits_received: fleet.hit_cells(),
+        }
     }
 
-    /// Record the result of a guess made against the opponent.
+    /// Our hits, misses and streaks, and how long our fleet has held out.
+    /// An engine restored from a saved state counts earlier shots but not
+    /// the streaks they formed.
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    /// What the opponent's first guess at (row, col) was answered with. An
+    /// engine restored from a saved state does not know which shot sank a
+    /// ship, and reports such shots as `Hit`.
+    fn prior_result(&self, row: usize, col: usize) -> GuessResult {
+        if let Some(idx) = self.sinking_shots.iter().position(|&cell| cell == Some((row, col))) {
+            GuessResult::Sink(self.rules.fleet().ships()[idx].name())
+        } else if self.board.hits().get(row, col).unwrap_or(false) {
+            GuessResult::Hit
+        } else {
+            GuessResult::Miss
+        }
+    }
+
+    /// Record the result of a guess made against the opponent. Unless the
+    /// [`DuplicateGuessPolicy`] is `Error`, the answer to a repeated guess
+    /// changes nothing.
     pub fn record_guess(
         &mut self,
         row: usize,
         col: usize,
         result: GuessResult,
     ) -> Result<(), BoardError> {
+        if !self.rules.contains(row, col) {
+            return Err(BitBoardError::IndexOutOfBounds { row, col }.into());
+        }
         if self.guess_hits.get(row, col)? || self.guess_misses.get(row, col)? {
-            return Err(BoardError::AlreadyGuessed);
+            return match self.duplicate_policy {
+                DuplicateGuessPolicy::Error => Err(BoardError::AlreadyGuessed),
+                DuplicateGuessPolicy::IdempotentReturnPrior | DuplicateGuessPolicy::WastedTurn => Ok(()),
+            };
         }
         match result {
             GuessResult::Hit => {
                 self.guess_hits.set(row, col)?;
                 self.enemy_remaining = self.enemy_remaining.saturating_sub(1);
+                self.model.record_hit(row, col);
             }
             GuessResult::Sink(name) => {
                 self.guess_hits.set(row, col)?;
                 self.enemy_remaining = self.enemy_remaining.saturating_sub(1);
-                if let Some(idx) = SHIPS.iter().position(|s| s.name() == name) {
+                if let Some(idx) = self.enemy_ship_index(name) {
                     self.enemy_ships_remaining[idx] = false;
+                    self.model.record_sink(idx, self.rules.fleet().ships()[idx].length(), row, col);
                 } else {
                     return Err(BoardError::NameNotFound);
                 }
@@ -110,9 +692,53 @@ impl GameEngine {
                 self.guess_misses.set(row, col)?;
             }
         }
+        self.stats.record_shot(result);
+        self.history.push(Move {
+            shooter: Shooter::Us,
+            row,
+            col,
+            result,
+        });
+        self.debug_check();
         Ok(())
     }
 
+    /// Every shot recorded so far in either direction, oldest first.
+    /// Repeated shots are not logged, and an engine restored from a saved
+    /// state starts with an empty history.
+    pub fn history(&self) -> &MoveLog {
+        &self.history
+    }
+
+    /// Rebuild an engine by playing `moves` in order, starting from
+    /// `fleet`, our board before the first shot. The opponent's shots are
+    /// answered by `fleet` and must match the results logged for them, so
+    /// a log from a desynced game shows where the two sides diverged.
+    pub fn replay(fleet: Board, moves: &[Move]) -> Result<Self, ReplayError> {
+        let mut engine = Self::new_with_config(*fleet.rules());
+        engine.board = fleet;
+        for (index, mv) in moves.iter().enumerate() {
+            match mv.shooter {
+                Shooter::Us => engine
+                    .record_guess(mv.row, mv.col, mv.result)
+                    .map_err(|error| ReplayError::Rejected { index, error })?,
+                Shooter::Opponent => {
+                    let actual = engine
+                        .opponent_guess(mv.row, mv.col)
+                        .map_err(|error| ReplayError::Rejected { index, error })?;
+                    if actual != mv.result {
+                        return Err(ReplayError::ResultMismatch {
+                            index,
+                            logged: mv.result,
+                            actual,
+                        });
+                    }
+                }
+            }
+        }
+        Ok(engine)
+    }
+
     /// Generate a serializable snapshot of the current state.
     pub fn state(&self) -> GameState {
         GameState {
@@ -126,34 +752,304 @@ impl GameEngine {
         }
     }
 
-    /// Restore an engine from a previously saved state.
+    /// Which enemy ship a sink report for `name` refers to: the first
+    /// afloat ship of that name, else the first of that name at all.
+    fn enemy_ship_index(&self, name: &str) -> Option<usize> {
+        let fleet = self.rules.fleet();
+        (0..fleet.len())
+            .find(|&i| self.enemy_ships_remaining[i] && fleet.ships()[i].name() == name)
+            .or_else(|| fleet.index_of(name))
+    }
+
+    /// Restore an engine for the standard fleet from a previously saved
+    /// state. With the `debug-invariants` feature an inconsistent state
+    /// panics here.
     pub fn from_state(state: GameState) -> Self {
-        Self {
-            board: Board::from(state.my_board),
+        let rules = RuleSet::with_board_size(state.my_board.size).unwrap_or_default();
+        Self::from_state_with_config(state, rules)
+    }
+
+    /// Restore an engine playing by `rules`, such as a custom fleet, from a
+    /// state it saved.
+    pub fn from_state_with_config(state: GameState, rules: RuleSet) -> Self {
+        let board = Board::from_state(state.my_board, &rules);
+        let shots_received = state.my_board.hits.count_ones() + state.my_board.misses.count_ones();
+        let lost = shots_received > 0 && board.all_sunk();
+        let engine = Self {
+            rules,
+            board,
             guess_hits: state.my_guesses.hits,
             guess_misses: state.my_guesses.misses,
             enemy_remaining: state.enemy_remaining,
             enemy_ships_remaining: state.enemy_ships_remaining,
+            duplicate_policy: DuplicateGuessPolicy::Error,
+            sinking_shots: [None; MAX_SHIPS],
+            shots_received,
+            first_hits: [None; MAX_SHIPS],
+            sunk_turns: [None; MAX_SHIPS],
+            aborted: false,
+            resigned: None,
+            drawn: false,
+            history: MoveLog::new(),
+            model: OpponentModel::with_hits(state.my_guesses.hits),
+            stats: Stats {
+                hits: state.my_guesses.hits.count_ones(),
+                misses: state.my_guesses.misses.count_ones(),
+                ships_sunk: state.enemy_ships_remaining[..rules.fleet().len()].iter().filter(|afloat| !**afloat).count(),
+                turns_survived: shots_received.saturating_sub(lost as usize),
+                ..Stats::default()
+            },
+        };
+        engine.debug_check();
+        engine
+    }
+
+    /// Bring this engine up to the later state `delta` was taken from, see
+    /// [`GameState::diff`]. The new shots are applied in place; history,
+    /// streaks, turn records and the resign and abort flags are kept. A
+    /// delta does not order its shots, so they are not logged and the turns
+    /// of first hits and sinks among them stay unknown, as for a restored
+    /// engine. A delta that does not fit the engine's state leaves the
+    /// engine unchanged.
+    pub fn apply_delta(&mut self, delta: &StateDelta) -> Result<(), DeltaError> {
+        let state = self.state();
+        let actual = state.digest();
+        if delta.base != actual {
+            return Err(DeltaError::BaseMismatch {
+                expected: delta.base,
+                actual,
+            });
+        }
+        let outside = self.rules.off_board();
+        let new_shots = |hits: BB, misses: BB, taken: BB| {
+            let cells = hits | misses;
+            (hits & misses).is_empty()
+                && (cells & taken).is_empty()
+                && (cells & outside).is_empty()
+        };
+        let board = state.my_board;
+        let guesses = state.my_guesses;
+        if !new_shots(delta.hits, delta.misses, board.hits | board.misses)
+            || !new_shots(delta.guess_hits, delta.guess_misses, guesses.hits | guesses.misses)
+        {
+            return Err(DeltaError::ConflictingShots);
+        }
+        if !(delta.hits & !board.ship_map).is_empty() || !(delta.misses & board.ship_map).is_empty() {
+            return Err(DeltaError::WrongResult);
+        }
+        let fleet = self.rules.fleet().len();
+        let refloated = delta
+            .enemy_ships_remaining
+            .iter()
+            .zip(state.enemy_ships_remaining)
+            .any(|(&after, before)| after && !before);
+        if refloated || delta.enemy_ships_remaining[fleet..].iter().any(|&afloat| afloat) {
+            return Err(DeltaError::InvalidSinks);
+        }
+
+        let received = delta.hits | delta.misses;
+        let was_lost = self.board.all_sunk();
+        for (row, col) in received.iter_set_bits() {
+            // Checked above: on the board and not fired at before
+            let _ = self.board.guess(Coord::new(row, col));
+        }
+        let shots = received.count_ones();
+        self.shots_received += shots;
+        if !was_lost {
+            self.stats.turns_survived += shots - self.board.all_sunk() as usize;
+        }
+
+        self.guess_hits |= delta.guess_hits;
+        self.guess_misses |= delta.guess_misses;
+        for (row, col) in delta.guess_hits.iter_set_bits() {
+            self.model.record_hit(row, col);
+        }
+        self.enemy_remaining = self.enemy_remaining.saturating_sub(delta.guess_hits.count_ones());
+        self.stats.hits += delta.guess_hits.count_ones();
+        self.stats.misses += delta.guess_misses.count_ones();
+        self.stats.ships_sunk += state
+            .enemy_ships_remaining
+            .iter()
+            .zip(delta.enemy_ships_remaining)
+            .filter(|&(&before, after)| before && !after)
+            .count();
+        self.enemy_ships_remaining = delta.enemy_ships_remaining;
+        self.debug_check();
+        Ok(())
+    }
+
+    /// Check the engine's fields against each other, returning the first
+    /// inconsistency found.
+    pub fn check_invariants(&self) -> Result<(), InvariantViolation> {
+        let (hits, misses, ship_map) = (self.board.hits(), self.board.misses(), self.board.ship_map());
+        let cells = (hits & !ship_map).count_ones();
+        if cells > 0 {
+            return Err(InvariantViolation::HitOffShip { cells });
+        }
+        let cells = (misses & ship_map).count_ones();
+        if cells > 0 {
+            return Err(InvariantViolation::MissOnShip { cells });
+        }
+        let fleet = self.board.fleet_report();
+        let fleet_cells: usize = fleet.ships.iter().filter(|s| s.placed).map(|s| s.length).sum();
+        if ship_map.count_ones() != fleet_cells {
+            return Err(InvariantViolation::ShipMapMismatch {
+                map_cells: ship_map.count_ones(),
+                fleet_cells,
+            });
+        }
+        for ship in fleet.ships.iter().filter(|s| s.placed) {
+            if ship.sunk != (ship.remaining == 0) {
+                return Err(InvariantViolation::SunkFlagMismatch {
+                    ship: ship.name,
+                    hits: ship.hits,
+                    length: ship.length,
+                    sunk: ship.sunk,
+                });
+            }
+        }
+        for (i, ship) in fleet.ships.iter().enumerate() {
+            let sinking_shot_hit = self.sinking_shots[i].is_none_or(|(r, c)| hits.get(r, c).unwrap_or(false));
+            let sunk_after_first_hit = match (self.first_hits[i], self.sunk_turns[i]) {
+                (Some(first), Some(sunk)) => first <= sunk,
+                (None, Some(_)) => false,
+                _ => true,
+            };
+            if !sinking_shot_hit || !sunk_after_first_hit || (self.sunk_turns[i].is_some() && !ship.sunk) {
+                return Err(InvariantViolation::SinkRecordMismatch { ship: ship.name });
+            }
+        }
+
+        let cells = (self.guess_hits & self.guess_misses).count_ones();
+        if cells > 0 {
+            return Err(InvariantViolation::OverlappingGuesses { cells });
+        }
+        let expected = self.rules.fleet().total_cells().saturating_sub(self.guess_hits.count_ones());
+        if self.enemy_remaining != expected {
+            return Err(InvariantViolation::EnemyRemainingMismatch {
+                recorded: self.enemy_remaining,
+                expected,
+            });
+        }
+
+        // Wasted repeats are counted as shots without marking a cell
+        let marked = hits.count_ones() + misses.count_ones();
+        let repeats_counted = self.duplicate_policy == DuplicateGuessPolicy::WastedTurn;
+        if self.shots_received < marked || (!repeats_counted && self.shots_received != marked) {
+            return Err(InvariantViolation::ShotCountMismatch {
+                counted: self.shots_received,
+                marked,
+            });
+        }
+        Ok(())
+    }
+
+    /// With the `debug-invariants` feature, panic with a report as soon as
+    /// a mutation leaves the engine inconsistent. Compiles to nothing
+    /// otherwise.
+    #[inline]
+    fn debug_check(&self) {
+        #[cfg(feature = "debug-invariants")]
+        if let Err(violation) = self.check_invariants() {
+            panic!(
+                "GameEngine invariant violated: {}\nstatus: {:?}, policy: {:?}, shots received: {}\nstate: {:#?}",
+                violation,
+                self.status(),
+                self.duplicate_policy,
+                self.shots_received,
+                self.state()
+            );
+        }
+    }
+
+    /// Start the next game of a series: clear both boards, our fleet
+    /// included, along with the history, statistics and outcome, keeping
+    /// the rules and duplicate policy.
+    pub fn reset_for_rematch(&mut self) {
+        *self = Self::new_with_config(self.rules).with_duplicate_policy(self.duplicate_policy);
+    }
+
+    /// Call the game off. The status stays `Aborted` from then on, whatever
+    /// else is recorded.
+    pub fn abort(&mut self) {
+        self.aborted = true;
+    }
+
+    /// Concede the game: the status becomes `Forfeited`. Has no effect once
+    /// the game is decided.
+    pub fn resign(&mut self) {
+        if self.status() == GameStatus::InProgress {
+            self.resigned = Some(true);
+        }
+    }
+
+    /// Record that the opponent conceded: the status becomes `Won`. Has no
+    /// effect once the game is decided.
+    pub fn record_resignation(&mut self) {
+        if self.status() == GameStatus::InProgress {
+            self.resigned = Some(false);
+        }
+    }
+
+    /// Record that the opponent resigned while our own resignation was on
+    /// its way: the status becomes `Draw`. Has no effect unless we resigned
+    /// and nothing else decided the game first.
+    pub fn record_crossed_resignation(&mut self) {
+        if self.resigned == Some(true) {
+            self.drawn = true;
         }
     }
 
     /// Evaluate the current game status.
+    ///
+    /// Victory requires every enemy ship to have been reported sunk; the
+    /// remaining-cell counter is only a cross-check, and `Desync` is
+    /// returned when exactly one of the two says the enemy fleet is gone.
+    /// A game still undecided once both sides have fired the rules' move
+    /// limit is a `Draw`.
     pub fn status(&self) -> GameStatus {
-        if self.board.all_sunk() {
+        let all_ships_sunk = self.enemy_ships_remaining.iter().all(|afloat| !afloat);
+        let all_cells_hit = self.enemy_remaining == 0;
+        if self.aborted {
+            GameStatus::Aborted
+        } else if self.drawn {
+            GameStatus::Draw
+        } else if let Some(ours) = self.resigned {
+            if ours {
+                GameStatus::Forfeited
+            } else {
+                GameStatus::Won
+            }
+        } else if self.board.all_sunk() {
             GameStatus::Lost
-        } else if self.enemy_remaining == 0 {
+        } else if all_ships_sunk && all_cells_hit {
             GameStatus::Won
+        } else if all_ships_sunk || all_cells_hit {
+            GameStatus::Desync
+        } else if self.move_limit_reached() {
+            GameStatus::Draw
         } else {
             GameStatus::InProgress
         }
     }
 
+    /// Whether both sides have fired at least the rules' move limit of
+    /// distinct cells.
+    fn move_limit_reached(&self) -> bool {
+        self.rules.move_limit().is_some_and(|limit| {
+            let fired = self.guess_hits.count_ones() + self.guess_misses.count_ones();
+            let received = self.board.hits().count_ones() + self.board.misses().count_ones();
+            fired.min(received) >= limit as usize
+        })
+    }
+
     /// Lengths of enemy ships that have not yet been sunk. Entries are zero
-    /// for ships already sunk, maintaining fixed-size output for `no_std`
-    /// callers.
-pub fn enemy_ship_lengths_remaining(&self) -> [usize; NUM_SHIPS as usize] {
-        let mut lens = [0usize; NUM_SHIPS as usize];
-        for (i, def) in SHIPS.iter().enumerate() {
+    /// for ships already sunk and past the end of the fleet, maintaining
+    /// fixed-size output for `no_std` callers. Pair it with [`GameEngine::blocked_cells`], which holds the
+    /// sunk ships' cells once the [`OpponentModel`] has placed them.
+    pub fn enemy_ship_lengths_remaining(&self) -> [usize; MAX_SHIPS] {
+        let mut lens = [0usize; MAX_SHIPS];
+        for (i, def) in self.rules.fleet().ships().iter().enumerate() {
             if self.enemy_ships_remaining[i] {
                 lens[i] = def.length();
             }
@@ -162,16 +1058,162 @@ pub fn enemy_ship_lengths_remaining(&self) -> [usize; NUM_SHIPS as usize] {
     }
 }
 
+impl Default for GameEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Step-by-step setup of a [`GameEngine`]: each rule starts at its
+/// standard value, and the combination is only checked by
+/// [`GameEngineBuilder::build`].
+///
+/// ```
+/// use battleship::{GameEngine, PlacementRules, TurnPolicy};
+///
+/// let engine = GameEngine::builder()
+///     .board_size(8)
+///     .placement(PlacementRules::NO_TOUCHING)
+///     .turn_policy(TurnPolicy::ContinueOnHit)
+///     .build()
+///     .unwrap();
+/// assert_eq!(engine.rules().board_size(), 8);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameEngineBuilder {
+    board_size: u8,
+    fleet: FleetConfig,
+    salvo: bool,
+    placement: PlacementRules,
+    turn_policy: TurnPolicy,
+    turn_clock: Option<TurnClock>,
+    move_limit: Option<u16>,
+    duplicate_policy: DuplicateGuessPolicy,
+}
+
+impl GameEngineBuilder {
+    /// The standard rules, with repeated guesses rejected.
+    pub const fn new() -> Self {
+        Self {
+            board_size: BOARD_SIZE,
+            fleet: FleetConfig::STANDARD,
+            salvo: false,
+            placement: PlacementRules::STANDARD,
+            turn_policy: TurnPolicy::Alternate,
+            turn_clock: None,
+            move_limit: None,
+            duplicate_policy: DuplicateGuessPolicy::Error,
+        }
+    }
+
+    /// Play on a `size`×`size` board.
+    pub const fn board_size(mut self, size: u8) -> Self {
+        self.board_size = size;
+        self
+    }
+
+    /// Place `fleet` instead of the standard one.
+    pub const fn fleet(mut self, fleet: FleetConfig) -> Self {
+        self.fleet = fleet;
+        self
+    }
+
+    /// Fire one shot per ship afloat each turn.
+    pub const fn salvo(mut self, salvo: bool) -> Self {
+        self.salvo = salvo;
+        self
+    }
+
+    /// Constrain where ships may go, e.g. keep them from touching.
+    pub const fn placement(mut self, placement: PlacementRules) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Decide who fires after each shot.
+    pub const fn turn_policy(mut self, policy: TurnPolicy) -> Self {
+        self.turn_policy = policy;
+        self
+    }
+
+    /// Limit each turn by `clock`, or leave turns untimed with `None`.
+    pub const fn turn_clock(mut self, clock: Option<TurnClock>) -> Self {
+        self.turn_clock = clock;
+        self
+    }
+
+    /// Draw the game once each side has fired `limit` shots without a
+    /// winner, or play until a fleet is sunk with `None`.
+    pub const fn move_limit(mut self, limit: Option<u16>) -> Self {
+        self.move_limit = limit;
+        self
+    }
+
+    /// Treat repeated guesses according to `policy`. Peers agree on it
+    /// along with the rules, since it decides what answer a repeat gets.
+    pub const fn duplicate_policy(mut self, policy: DuplicateGuessPolicy) -> Self {
+        self.duplicate_policy = policy;
+        self
+    }
+
+    /// The rules chosen so far, or why they cannot be played together.
+    pub fn rules(&self) -> Result<RuleSet, BoardError> {
+        Ok(RuleSet::new(self.board_size, self.fleet)?
+            .with_salvo(self.salvo)
+            .with_placement(self.placement)
+            .with_turn_policy(self.turn_policy)
+            .with_turn_clock(self.turn_clock)
+            .with_move_limit(self.move_limit))
+    }
+
+    /// An engine playing by the chosen rules, with an empty board.
+    pub fn build(self) -> Result<GameEngine, BoardError> {
+        Ok(GameEngine::new_with_config(self.rules()?).with_duplicate_policy(self.duplicate_policy))
+    }
+}
+
+impl Default for GameEngineBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl From<RuleSet> for GameEngineBuilder {
+    fn from(rules: RuleSet) -> Self {
+        Self::new()
+            .board_size(rules.board_size())
+            .fleet(*rules.fleet())
+            .salvo(rules.salvo())
+            .placement(rules.placement())
+            .turn_policy(rules.turn_policy())
+            .turn_clock(rules.turn_clock())
+            .move_limit(rules.move_limit())
+    }
+}
+
 #[cfg(feature = "std")]
 #[async_trait::async_trait]
 impl crate::protocol::GameApi for GameEngine {
-    async fn make_guess(&mut self, x: u8, y: u8) -> anyhow::Result<crate::protocol::domain::GuessResult> {
-        let res = self
-            .opponent_guess(x as usize, y as usize)
-            .map_err(|e| anyhow::anyhow!(e))?;
+    async fn make_guess(&mut self, target: Coord) -> anyhow::Result<crate::protocol::domain::GuessResult> {
+        let Coord { row, col } = crate::protocol::validation::validate_guess(self, target)?;
+        let res = self.opponent_guess(row, col).map_err(|e| anyhow::anyhow!(e))?;
         Ok(crate::protocol::domain::GuessResult::from(res))
     }
 
+    async fn make_salvo(&mut self, shots: Vec<(u8, u8)>) -> anyhow::Result<Vec<crate::protocol::domain::GuessResult>> {
+        let cells = crate::protocol::validation::validate_salvo(self, &shots)?;
+        let mut results = Vec::with_capacity(cells.len());
+        // Shots after the one that decides the game are not resolved
+        for (row, col) in cells {
+            if GameEngine::status(self) != GameStatus::InProgress {
+                break;
+            }
+            let res = self.opponent_guess(row, col).map_err(|e| anyhow::anyhow!(e))?;
+            results.push(crate::protocol::domain::GuessResult::from(res));
+        }
+        Ok(results)
+    }
+
     async fn get_ship_status(&self, ship_id: usize) -> anyhow::Result<crate::protocol::domain::Ship> {
         let states = self.board.ship_states();
         if ship_id >= states.len() {
@@ -181,21 +1223,53 @@ impl crate::protocol::GameApi for GameEngine {
     }
 
     async fn sync_state(&mut self, payload: crate::protocol::domain::SyncPayload) -> anyhow::Result<()> {
-        // Restore engine state from the sync payload
-        let restored = Self::from_state(payload.game_state);
-        self.board = restored.board;
-        self.guess_hits = restored.guess_hits;
-        self.guess_misses = restored.guess_misses;
-        self.enemy_remaining = restored.enemy_remaining;
-        self.enemy_ships_remaining = restored.enemy_ships_remaining;
+        // Never rebuild from a state that could not occur in a real game
+        crate::protocol::validation::validate_sync(&self.rules, &payload).map_err(|v| anyhow::anyhow!(v))?;
+        *self = Self::from_state_with_config(payload.game_state, self.rules).with_duplicate_policy(self.duplicate_policy);
+        Ok(())
+    }
+
+    async fn sync_delta(&mut self, delta: StateDelta) -> anyhow::Result<()> {
+        self.apply_delta(&delta).map_err(|e| anyhow::anyhow!(e))
+    }
+
+    async fn abort_game(&mut self, reason: String) -> anyhow::Result<()> {
+        tracing::info!(%reason, "game aborted");
+        self.abort();
         Ok(())
     }
 
+    async fn concede(&mut self) -> anyhow::Result<()> {
+        tracing::info!("opponent resigned");
+        self.record_resignation();
+        Ok(())
+    }
+
+    fn state_digest(&self) -> Option<[u8; 32]> {
+        Some(crate::protocol::domain::state_digest(&self.state()))
+    }
+
+    async fn place_fleet(
+        &mut self,
+        placements: Vec<crate::protocol::domain::Placement>,
+    ) -> anyhow::Result<Result<(), Vec<String>>> {
+        if !self.board.ship_map().is_empty() {
+            return Ok(Err(vec!["fleet is already placed".to_string()]));
+        }
+        let placed = crate::protocol::validation::validate_fleet(&self.rules, &placements).map(|board| self.board = board);
+        self.debug_check();
+        Ok(placed)
+    }
+
     fn status(&self) -> crate::protocol::domain::GameStatus {
         match GameEngine::status(self) {
             GameStatus::InProgress => crate::protocol::domain::GameStatus::InProgress,
             GameStatus::Won => crate::protocol::domain::GameStatus::Won,
             GameStatus::Lost => crate::protocol::domain::GameStatus::Lost,
+            GameStatus::Desync => crate::protocol::domain::GameStatus::Desync,
+            GameStatus::Aborted => crate::protocol::domain::GameStatus::Aborted,
+            GameStatus::Forfeited => crate::protocol::domain::GameStatus::Forfeited,
+            GameStatus::Draw => crate::protocol::domain::GameStatus::Draw,
         }
     }
 }
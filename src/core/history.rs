@@ -0,0 +1,150 @@
+//! Ordered record of the shots a [`GameEngine`](super::game::GameEngine)
+//! has seen, in both directions.
+//!
+//! The log has a fixed capacity so it stays usable without an allocator:
+//! every cell can be fired at once by each side, and repeated shots change
+//! nothing, so they are not logged.
+
+use super::{
+    common::{BoardError, GuessResult},
+    config::MAX_BOARD_SIZE,
+};
+
+/// Most moves a game can log: one per cell and side.
+pub const MAX_MOVES: usize = 2 * MAX_BOARD_SIZE as usize * MAX_BOARD_SIZE as usize;
+
+/// Which side fired a logged shot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Shooter {
+    /// We fired at the opponent's board.
+    Us,
+    /// The opponent fired at our board.
+    Opponent,
+}
+
+/// One shot and the answer it got.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub shooter: Shooter,
+    pub row: usize,
+    pub col: usize,
+    pub result: GuessResult,
+}
+
+/// Shots in the order the engine recorded them.
+#[derive(Clone)]
+pub struct MoveLog {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl MoveLog {
+    /// An empty log.
+    pub fn new() -> Self {
+        let blank = Move {
+            shooter: Shooter::Us,
+            row: 0,
+            col: 0,
+            result: GuessResult::Miss,
+        };
+        Self {
+            moves: [blank; MAX_MOVES],
+            len: 0,
+        }
+    }
+
+    /// Append `mv`. A full log means the engine recorded more shots than
+    /// the board has cells, so the move is dropped.
+    pub(crate) fn push(&mut self, mv: Move) {
+        debug_assert!(self.len < MAX_MOVES, "move log overflow");
+        if let Some(slot) = self.moves.get_mut(self.len) {
+            *slot = mv;
+            self.len += 1;
+        }
+    }
+
+    /// Moves logged so far, oldest first.
+    pub fn as_slice(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+
+    /// Iterate over the moves, oldest first.
+    pub fn iter(&self) -> core::slice::Iter<'_, Move> {
+        self.as_slice().iter()
+    }
+
+    /// Number of moves logged.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether no move has been logged.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The most recent move, if any.
+    pub fn last(&self) -> Option<&Move> {
+        self.as_slice().last()
+    }
+}
+
+impl Default for MoveLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::fmt::Debug for MoveLog {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl PartialEq for MoveLog {
+    fn eq(&self, other: &Self) -> bool {
+        self.as_slice() == other.as_slice()
+    }
+}
+
+impl Eq for MoveLog {}
+
+impl<'a> IntoIterator for &'a MoveLog {
+    type Item = &'a Move;
+    type IntoIter = core::slice::Iter<'a, Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Why a move list could not be replayed, see
+/// [`GameEngine::replay`](super::game::GameEngine::replay).
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The engine refused move `index`.
+    Rejected { index: usize, error: BoardError },
+    /// Our board answers the opponent's move `index` differently from the
+    /// result logged for it.
+    ResultMismatch {
+        index: usize,
+        logged: GuessResult,
+        actual: GuessResult,
+    },
+}
+
+impl core::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ReplayError::Rejected { index, error } => write!(f, "move {} rejected: {}", index, error),
+            ReplayError::ResultMismatch { index, logged, actual } => write!(
+                f,
+                "move {} was logged as {:?} but the board answers {:?}",
+                index, logged, actual
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ReplayError {}
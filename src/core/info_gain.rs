@@ -0,0 +1,54 @@
+// Information-gain scoring of candidate shots.
+// Uses no_std and avoids heap allocations.
+
+use super::config::MAX_BOARD_SIZE;
+use libm::log2;
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+/// Information, in bits, a shot is expected to give about the fleet layout
+/// when it hits with probability `p`.
+///
+/// A shot splits the layouts still possible into those it hits and those it
+/// misses, so the expected drop in their entropy is the entropy of that
+/// outcome. A sure hit or a sure miss tells nothing; an even chance tells a
+/// whole bit.
+pub fn shot_entropy(p: f64) -> f64 {
+    if p <= 0.0 || p >= 1.0 {
+        return 0.0;
+    }
+    -p * log2(p) - (1.0 - p) * log2(1.0 - p)
+}
+
+/// Chance that each cell holds a ship, from a density normalised over the
+/// unknown cells, as [`calc_exact_pdf`](crate::calc_exact_pdf) and
+/// [`calc_pdf_monte_carlo`](crate::calc_pdf_monte_carlo) give it.
+///
+/// Every layout puts a ship on the same number of unknown cells,
+/// `unseen_cells`: the fleet's cells less the hits. So scaling the density
+/// by it gives each cell's share of the layouts, capped at one for
+/// densities that only approximate the layouts.
+pub fn hit_probabilities(pdf: &[[f64; GRID_SIZE]; GRID_SIZE], unseen_cells: usize) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    let mut probs = *pdf;
+    for p in probs.iter_mut().flatten() {
+        *p = (*p * unseen_cells as f64).min(1.0);
+    }
+    probs
+}
+
+/// The [`shot_entropy`] of each cell under [`hit_probabilities`].
+///
+/// Sinkings reveal more than a hit, but are not scored. When no cell left
+/// is uncertain the hit probabilities are returned instead, so the cells
+/// known to hold ships are still shot.
+pub fn info_gain_scores(pdf: &[[f64; GRID_SIZE]; GRID_SIZE], unseen_cells: usize) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    let probs = hit_probabilities(pdf, unseen_cells);
+    let mut scores = probs;
+    for s in scores.iter_mut().flatten() {
+        *s = shot_entropy(*s);
+    }
+    if scores.iter().flatten().all(|&s| s == 0.0) {
+        return probs;
+    }
+    scores
+}
@@ -0,0 +1,239 @@
+//! JSON form of [`GameState`] for web clients.
+//!
+//! Bitboards are spelled out as lists of `[row, col]` cells rather than the
+//! packed `u128` used by bincode, so the schema is readable from JavaScript
+//! without big-integer support. Schema (version 1):
+//!
+//! ```text
+//! {
+//!   "schema_version": 1,
+//!   "board_size": 10,
+//!   "my_board": {
+//!     "ships": [ { "name": "Carrier", "sunk": false,
+//!                  "position": { "row": 0, "col": 0, "orientation": "Horizontal" } | null }, ... ],
+//!     "hits":   [[row, col], ...],
+//!     "misses": [[row, col], ...]
+//!   },
+//!   "my_guesses": { "hits": [[row, col], ...], "misses": [[row, col], ...] },
+//!   "enemy_ships_remaining": [true, ...],
+//!   "enemy_remaining": 17
+//! }
+//! ```
+//!
+//! Ships appear in fleet order, one entry per ship of the game's fleet.
+//! `sunk` is informational; on import it is recomputed from the hits.
+//! `board_size` may be omitted for the standard board. Salvo games add
+//! `"salvo": true`, games where ships may not touch `"no_touching": true`,
+//! games where a hit keeps the turn `"turn_policy": "ContinueOnHit"`, and
+//! timed games `"turn_clock": {"limit_ms": 30000, "on_timeout": "Forfeit"}`,
+//! and games drawn after a number of shots each `"move_limit": 60`.
+
+#![cfg(feature = "std")]
+
+use super::{
+    board::{Board, BoardState},
+    config::{BoardBits, FleetConfig, PlacementRules, RuleSet, BOARD_SIZE, MAX_SHIPS},
+    game::{GameEngine, GameState, GuessBoardState, TurnClock, TurnPolicy},
+    ship::Orientation,
+};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+type BB = BoardBits;
+
+/// Current version of the JSON schema.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct JsonGameState {
+    schema_version: u32,
+    #[serde(default = "standard_board_size")]
+    board_size: u8,
+    my_board: JsonBoard,
+    my_guesses: JsonGuesses,
+    enemy_ships_remaining: Vec<bool>,
+    enemy_remaining: usize,
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    salvo: bool,
+    #[serde(default, skip_serializing_if = "core::ops::Not::not")]
+    no_touching: bool,
+    #[serde(default, skip_serializing_if = "is_alternate")]
+    turn_policy: TurnPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    turn_clock: Option<TurnClock>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    move_limit: Option<u16>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonBoard {
+    ships: Vec<JsonShip>,
+    hits: Vec<[usize; 2]>,
+    misses: Vec<[usize; 2]>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonShip {
+    name: String,
+    sunk: bool,
+    position: Option<JsonPosition>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonPosition {
+    row: usize,
+    col: usize,
+    orientation: Orientation,
+}
+
+#[derive(Serialize, Deserialize)]
+struct JsonGuesses {
+    hits: Vec<[usize; 2]>,
+    misses: Vec<[usize; 2]>,
+}
+
+fn standard_board_size() -> u8 {
+    BOARD_SIZE
+}
+
+fn is_alternate(policy: &TurnPolicy) -> bool {
+    *policy == TurnPolicy::Alternate
+}
+
+fn cells(bb: &BB) -> Vec<[usize; 2]> {
+    bb.iter_set_bits().map(|(r, c)| [r, c]).collect()
+}
+
+fn board_from_cells(cells: &[[usize; 2]], what: &str) -> anyhow::Result<BB> {
+    BB::try_from_cells(cells.iter().map(|&[r, c]| (r, c))).map_err(|e| anyhow!("{}: {}", what, e))
+}
+
+impl JsonGameState {
+    fn new(state: &GameState, rules: &RuleSet) -> Self {
+        let fleet = rules.fleet();
+        JsonGameState {
+            schema_version: JSON_SCHEMA_VERSION,
+            board_size: state.my_board.size,
+            my_board: JsonBoard {
+                ships: state
+                    .my_board
+                    .ship_states[..fleet.len()]
+                    .iter()
+                    .map(|s| JsonShip {
+                        name: s.name.to_string(),
+                        sunk: s.sunk,
+                        position: s.position.map(|(row, col, orientation)| JsonPosition {
+                            row,
+                            col,
+                            orientation,
+                        }),
+                    })
+                    .collect(),
+                hits: cells(&state.my_board.hits),
+                misses: cells(&state.my_board.misses),
+            },
+            my_guesses: JsonGuesses {
+                hits: cells(&state.my_guesses.hits),
+                misses: cells(&state.my_guesses.misses),
+            },
+            enemy_ships_remaining: state.enemy_ships_remaining[..fleet.len()].to_vec(),
+            enemy_remaining: state.enemy_remaining,
+            salvo: rules.salvo(),
+            no_touching: rules.placement().no_touching,
+            turn_policy: rules.turn_policy(),
+            turn_clock: rules.turn_clock(),
+            move_limit: rules.move_limit(),
+        }
+    }
+
+    /// The rules and state described, for a game played with `fleet`.
+    fn into_state(self, fleet: &FleetConfig) -> anyhow::Result<(RuleSet, GameState)> {
+        if self.schema_version != JSON_SCHEMA_VERSION {
+            return Err(anyhow!(
+                "Unsupported game state schema version {} (expected {})",
+                self.schema_version,
+                JSON_SCHEMA_VERSION
+            ));
+        }
+        if self.my_board.ships.len() != fleet.len() || self.enemy_ships_remaining.len() != fleet.len() {
+            return Err(anyhow!("Game state must list exactly {} ships", fleet.len()));
+        }
+
+        let rules = RuleSet::new(self.board_size, *fleet)
+            .map_err(|e| anyhow!("{}", e))?
+            .with_salvo(self.salvo)
+            .with_placement(PlacementRules {
+                no_touching: self.no_touching,
+            })
+            .with_turn_policy(self.turn_policy)
+            .with_turn_clock(self.turn_clock)
+            .with_move_limit(self.move_limit);
+        let mut board = Board::with_rules(&rules);
+        for (i, (ship, def)) in self.my_board.ships.iter().zip(fleet.ships()).enumerate() {
+            if ship.name != def.name() {
+                return Err(anyhow!("Ship {} should be {}, got {:?}", i, def.name(), ship.name));
+            }
+            if let Some(p) = &ship.position {
+                board
+                    .place(i, p.row, p.col, p.orientation)
+                    .map_err(|e| anyhow!("Invalid position for {}: {}", def.name(), e))?;
+            }
+        }
+        let mut placed = BoardState::from(&board);
+        placed.hits = board_from_cells(&self.my_board.hits, "my_board.hits")?;
+        placed.misses = board_from_cells(&self.my_board.misses, "my_board.misses")?;
+        if placed.hits != (placed.hits & placed.ship_map) || !(placed.misses & placed.ship_map).is_empty() {
+            return Err(anyhow!("my_board hits and misses disagree with the ship positions"));
+        }
+        // Round-trip through Board so sunk flags follow the hits
+        let my_board = BoardState::from(&Board::from_state(placed, &rules));
+
+        let mut enemy_ships_remaining = [false; MAX_SHIPS];
+        enemy_ships_remaining[..fleet.len()].copy_from_slice(&self.enemy_ships_remaining);
+
+        let my_guesses = GuessBoardState {
+            hits: board_from_cells(&self.my_guesses.hits, "my_guesses.hits")?,
+            misses: board_from_cells(&self.my_guesses.misses, "my_guesses.misses")?,
+        };
+        let off_board = rules.off_board();
+        if ![placed.misses, my_guesses.hits, my_guesses.misses].iter().all(|bb| (*bb & off_board).is_empty()) {
+            return Err(anyhow!("Game state marks cells outside the {}x{} board", self.board_size, self.board_size));
+        }
+        if !(my_guesses.hits & my_guesses.misses).is_empty() {
+            return Err(anyhow!("my_guesses marks a cell as both hit and miss"));
+        }
+
+        let state = GameState {
+            my_board,
+            my_guesses,
+            enemy_ships_remaining,
+            enemy_remaining: self.enemy_remaining,
+        };
+        Ok((rules, state))
+    }
+}
+
+impl GameEngine {
+    /// Serialize the engine state as JSON using the schema described in
+    /// [`crate::core::json`].
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string(&JsonGameState::new(&self.state(), &self.rules()))
+            .context("Failed to encode game state as JSON")
+    }
+
+    /// Restore an engine from JSON produced by [`GameEngine::to_json`].
+    ///
+    /// Ship positions are validated by placing them on a fresh board, so
+    /// overlapping or out-of-bounds fleets are rejected.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        Self::from_json_with_fleet(json, &FleetConfig::STANDARD)
+    }
+
+    /// [`GameEngine::from_json`] for a game played with `fleet`, which the
+    /// JSON does not record.
+    pub fn from_json_with_fleet(json: &str, fleet: &FleetConfig) -> anyhow::Result<Self> {
+        let parsed: JsonGameState = serde_json::from_str(json).context("Failed to parse game state JSON")?;
+        let (rules, state) = parsed.into_state(fleet)?;
+        Ok(Self::from_state_with_config(state, rules))
+    }
+}
@@ -6,17 +6,58 @@
 
 pub mod ai;
 pub mod bitboard;
+pub mod bitboard_wide;
+pub mod bitgrid;
 pub mod board;
 pub mod common;
 pub mod config;
+pub mod coord;
+pub mod delta;
+pub mod endgame;
+pub mod events;
+pub mod fixed;
 pub mod game;
+pub mod history;
+pub mod info_gain;
+pub mod json;
+pub mod opponent;
+pub mod opponent_model;
+pub mod pdf_cache;
+pub mod perf;
+pub mod placement;
+pub mod replay;
 pub mod ship;
+pub mod targeting;
+#[cfg(feature = "serde")]
+pub mod wire;
 
 // Re-export commonly used types
-pub use ai::{calc_pdf, calc_pdf_and_guess, sample_pdf};
-pub use bitboard::{BitBoard, BitBoardError};
-pub use board::{Board, BoardState};
+pub use ai::{
+    argmax_pdf, calc_exact_pdf, calc_pdf, calc_pdf_and_guess, calc_pdf_and_guess_with_config, calc_pdf_for_fleet, calc_pdf_monte_carlo,
+    calc_pdf_monte_carlo_with_rules, calc_pdf_with_config, calc_pdf_with_rules, calc_pdf_with_sunk, calc_placement_counts,
+    enumerate_placements, follow_up_targets, hunt_lattice, pick_target, sample_pdf, AiConfig, Placement, ARGMAX_TIE_TOLERANCE,
+    EXACT_MAX_UNKNOWN,
+};
+pub use bitboard::{BitBoard, BitBoardError, ClearBits, Clusters};
+pub use bitboard_wide::{BitBoardWide, ClearBitsWide, ClustersWide};
+pub use bitgrid::{bitgrid_bytes, BitGrid, CellGrid};
+pub use board::{Board, BoardState, CellState, FleetReport, LayoutError, ShipReport};
 pub use common::{BoardError, GuessResult};
 pub use config::*;
-pub use game::{GameEngine, GameState, GameStatus, GuessBoardState};
+pub use coord::{Coord, CoordError};
+pub use delta::{DeltaError, StateDelta};
+pub use endgame::{plan_endgame, EndgamePlan, EndgameShot, ENDGAME_MAX_CANDIDATES};
+pub use events::{EventListener, GameEvent};
+pub use fixed::{argmax_fixed, calc_pdf_fixed, calc_pdf_fixed_with_sunk, sample_fixed, FixedConfig, DEFAULT_FIXED_HIT_BIAS};
+pub use game::{
+    DefenseReport, DuplicateGuessPolicy, GameEngine, GameEngineBuilder, GameState, GameStatus, GuessBoardState,
+    InvariantViolation, ShipDefense, Stats, TimeoutAction, TurnClock, TurnPolicy,
+};
+pub use history::{Move, MoveLog, ReplayError, Shooter, MAX_MOVES};
+pub use info_gain::{hit_probabilities, info_gain_scores, shot_entropy};
+pub use opponent::OpponentProfile;
+pub use opponent_model::OpponentModel;
+pub use pdf_cache::PdfCache;
+pub use placement::{PlacementStrategy, PlacementStyle, WeightedRandom};
 pub use ship::{Orientation, Ship, ShipDef, ShipState};
+pub use targeting::{TargetingStrategy, TargetingStyle, TargetingView};
@@ -0,0 +1,134 @@
+// Observed opponent tendencies carried across games of a series.
+// Uses no_std and avoids heap allocations.
+
+use super::config::MAX_BOARD_SIZE;
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+/// Number of opening shots per game counted as the opponent's search pattern.
+pub const EARLY_SHOT_WINDOW: u32 = 20;
+
+/// Completed games observed before the profile is trusted to steer play.
+pub const MIN_GAMES_FOR_ADAPTATION: u32 = 3;
+
+/// How strongly past enemy ship cells raise the hunting prior.
+const SHIP_PRIOR_WEIGHT: f64 = 2.0;
+
+/// Opponent behaviour accumulated over a series of games.
+///
+/// Records where the opponent shoots at our board (with extra weight on the
+/// opening shots of each game) and where its ships turned out to be, so later
+/// games can place away from its favourite targets and hunt where it tends to
+/// hide ships.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpponentProfile {
+    games: u32,
+    shots: [[u32; GRID_SIZE]; GRID_SIZE],
+    early_shots: [[u32; GRID_SIZE]; GRID_SIZE],
+    ship_cells: [[u32; GRID_SIZE]; GRID_SIZE],
+    shots_this_game: u32,
+}
+
+impl OpponentProfile {
+    /// Empty profile with no games observed.
+    pub const fn new() -> Self {
+        Self {
+            games: 0,
+            shots: [[0; GRID_SIZE]; GRID_SIZE],
+            early_shots: [[0; GRID_SIZE]; GRID_SIZE],
+            ship_cells: [[0; GRID_SIZE]; GRID_SIZE],
+            shots_this_game: 0,
+        }
+    }
+
+    /// Number of games observed so far (including the current one).
+    pub fn games(&self) -> u32 {
+        self.games
+    }
+
+    /// Whether enough earlier games have been seen to adapt to this opponent.
+    pub fn is_mature(&self) -> bool {
+        self.games > MIN_GAMES_FOR_ADAPTATION
+    }
+
+    /// Start observing a new game.
+    pub fn begin_game(&mut self) {
+        self.games = self.games.saturating_add(1);
+        self.shots_this_game = 0;
+    }
+
+    /// Record an opponent shot at our board.
+    pub fn record_opponent_shot(&mut self, row: usize, col: usize) {
+        if row >= GRID_SIZE || col >= GRID_SIZE {
+            return;
+        }
+        self.shots[row][col] = self.shots[row][col].saturating_add(1);
+        if self.shots_this_game < EARLY_SHOT_WINDOW {
+            self.early_shots[row][col] = self.early_shots[row][col].saturating_add(1);
+        }
+        self.shots_this_game = self.shots_this_game.saturating_add(1);
+    }
+
+    /// Record a cell where one of the opponent's ships was hit.
+    pub fn record_enemy_ship_cell(&mut self, row: usize, col: usize) {
+        if row < GRID_SIZE && col < GRID_SIZE {
+            self.ship_cells[row][col] = self.ship_cells[row][col].saturating_add(1);
+        }
+    }
+
+    /// How much the opponent favours each cell of our board, early shots
+    /// counted double. Suitable for [`AvoidHeat`](super::placement::AvoidHeat).
+    pub fn shot_heat(&self) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+        core::array::from_fn(|r| {
+            core::array::from_fn(|c| (self.shots[r][c] + 2 * self.early_shots[r][c]) as f64)
+        })
+    }
+
+    /// Multiply a targeting PDF by how often opponent ships occupied each
+    /// cell in past games, then renormalise.
+    pub fn apply_hunting_prior(&self, pdf: &mut [[f64; GRID_SIZE]; GRID_SIZE]) {
+        if self.games == 0 {
+            return;
+        }
+        let games = self.games as f64;
+        let mut total = 0.0;
+        for (pdf_row, ship_row) in pdf.iter_mut().zip(self.ship_cells.iter()) {
+            for (p, &seen) in pdf_row.iter_mut().zip(ship_row.iter()) {
+                *p *= 1.0 + SHIP_PRIOR_WEIGHT * seen as f64 / games;
+                total += *p;
+            }
+        }
+        if total > 0.0 {
+            for p in pdf.iter_mut().flatten() {
+                *p /= total;
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl OpponentProfile {
+    /// Read a profile written by [`OpponentProfile::save`], to keep adapting
+    /// to the same opponent in a later session.
+    pub fn load(path: impl AsRef<std::path::Path>) -> anyhow::Result<Self> {
+        use anyhow::Context;
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        serde_json::from_str(&json).with_context(|| format!("Invalid opponent profile {}", path.display()))
+    }
+
+    /// Write the profile to `path` as JSON.
+    pub fn save(&self, path: impl AsRef<std::path::Path>) -> anyhow::Result<()> {
+        use anyhow::Context;
+        let path = path.as_ref();
+        let json = serde_json::to_string(self).context("Failed to encode opponent profile as JSON")?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+impl Default for OpponentProfile {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,151 @@
+//! Which of our hits on the opponent's board belong to which sunk ship.
+//!
+//! A sink report names the ship and so its length, and the shot that sank it
+//! is one of its cells; the rest of the ship is a straight run of earlier
+//! hits through that cell. When only one such run is left the ship is
+//! placed. Otherwise the sink waits until other ships have claimed enough of
+//! the hits around it.
+
+use super::{
+    board::ship_mask,
+    config::{BoardBits, MAX_SHIPS},
+    ship::Orientation,
+};
+
+type BB = BoardBits;
+
+/// A sunk ship not yet tied to its cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PendingSink {
+    length: usize,
+    cell: (usize, usize),
+}
+
+/// Our picture of the opponent's fleet, built from the hits and sinks we
+/// recorded. Kept by [`GameEngine`](super::game::GameEngine); see
+/// [`GameEngine::opponent_model`](super::game::GameEngine::opponent_model).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OpponentModel {
+    /// Hits not assigned to a sunk ship.
+    unresolved: BB,
+    /// Cells of each sunk ship, by fleet index, once known.
+    sunk: [Option<BB>; MAX_SHIPS],
+    /// Sunk ships whose cells are still ambiguous, by fleet index.
+    pending: [Option<PendingSink>; MAX_SHIPS],
+}
+
+impl OpponentModel {
+    /// A model with no hits recorded.
+    pub const fn new() -> Self {
+        Self {
+            unresolved: BB::new(),
+            sunk: [None; MAX_SHIPS],
+            pending: [None; MAX_SHIPS],
+        }
+    }
+
+    /// A model for `hits` whose sinking shots are unknown, as for an engine
+    /// restored from a saved state: every hit stays unresolved.
+    pub const fn with_hits(hits: BB) -> Self {
+        Self {
+            unresolved: hits,
+            sunk: [None; MAX_SHIPS],
+            pending: [None; MAX_SHIPS],
+        }
+    }
+
+    /// Record a hit at (row, col) that did not sink anything.
+    pub fn record_hit(&mut self, row: usize, col: usize) {
+        let _ = self.unresolved.set(row, col);
+        self.resolve();
+    }
+
+    /// Record that the shot at (row, col) sank ship `ship` of `length`
+    /// cells, and place every sunk ship whose cells are now certain.
+    pub fn record_sink(&mut self, ship: usize, length: usize, row: usize, col: usize) {
+        let _ = self.unresolved.set(row, col);
+        if let Some(slot) = self.pending.get_mut(ship) {
+            *slot = Some(PendingSink {
+                length,
+                cell: (row, col),
+            });
+        }
+        self.resolve();
+    }
+
+    /// Cells of ship `ship`, once it is sunk and its position certain.
+    pub fn ship_cells(&self, ship: usize) -> Option<BB> {
+        self.sunk.get(ship).copied().flatten()
+    }
+
+    /// Every cell known to belong to a sunk ship.
+    pub fn sunk_cells(&self) -> BB {
+        self.sunk.iter().flatten().fold(BB::new(), |acc, &cells| acc | cells)
+    }
+
+    /// Hits not known to belong to a sunk ship: the wounded ships still to
+    /// be finished off, plus sunk ships whose cells are still ambiguous.
+    pub fn unresolved_hits(&self) -> BB {
+        self.unresolved
+    }
+
+    /// Whether some sunk ship's cells are still ambiguous.
+    pub fn has_pending(&self) -> bool {
+        self.pending.iter().any(Option::is_some)
+    }
+
+    /// Place pending sinks with exactly one possible position among the
+    /// unresolved hits, repeating while placing one narrows down another.
+    fn resolve(&mut self) {
+        let mut progress = true;
+        while progress {
+            progress = false;
+            for ship in 0..MAX_SHIPS {
+                let Some(sink) = self.pending[ship] else {
+                    continue;
+                };
+                if let Some(cells) = self.only_position(sink) {
+                    self.unresolved &= !cells;
+                    self.sunk[ship] = Some(cells);
+                    self.pending[ship] = None;
+                    progress = true;
+                }
+            }
+        }
+    }
+
+    /// The one run of unresolved hits `sink` could occupy, or `None` when
+    /// there are several or none.
+    fn only_position(&self, sink: PendingSink) -> Option<BB> {
+        let (row, col) = sink.cell;
+        let mut found = None;
+        for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+            for k in 0..sink.length {
+                let start = match orientation {
+                    Orientation::Horizontal => col.checked_sub(k).map(|c| (row, c)),
+                    Orientation::Vertical => row.checked_sub(k).map(|r| (r, col)),
+                };
+                let Some((r, c)) = start else {
+                    continue;
+                };
+                let Ok(mask) = ship_mask(sink.length, r, c, orientation) else {
+                    continue;
+                };
+                if (mask & self.unresolved) != mask {
+                    continue;
+                }
+                if found.is_some() {
+                    return None;
+                }
+                found = Some(mask);
+            }
+        }
+        found
+    }
+}
+
+impl Default for OpponentModel {
+    fn default() -> Self {
+        Self::new()
+    }
+}
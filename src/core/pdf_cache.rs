@@ -0,0 +1,170 @@
+// Incremental placement density between turns.
+// Uses no_std and avoids heap allocations.
+
+use super::{
+    ai::{add_matrix, apply_parity, calc_exact_pdf, calc_pdf_with_sunk, enumerate_placements, normalize, AiConfig},
+    board::halo,
+    config::{BoardBits, FleetConfig, PlacementRules, MAX_BOARD_SIZE, MAX_SHIPS},
+    targeting::TargetingView,
+};
+use libm::pow;
+
+/// Bitboard type alias for convenience.
+type BB = BoardBits;
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+/// The density of [`calc_pdf_with_sunk`], kept up to date between turns.
+///
+/// The cache holds, for each ship length in the fleet, every cell's total
+/// placement weight. A new shot only changes the placements covering it (or
+/// next to it, when ships may not touch), so each turn re-scores just those
+/// instead of enumerating every placement again. Shots that disappear, as
+/// when a new game starts, or a different fleet, rules or hit bias, start
+/// the sums over.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfCache {
+    fleet: FleetConfig,
+    placement: PlacementRules,
+    hit_bias: f64,
+    /// Unresolved hits and blocked cells the sums reflect; `None` before
+    /// the first density.
+    shots: Option<(BB, BB)>,
+    /// Distinct ship lengths of the fleet, zero past the last.
+    lengths: [usize; MAX_SHIPS],
+    /// For each length, every cell's total placement weight.
+    sums: [[[f64; GRID_SIZE]; GRID_SIZE]; MAX_SHIPS],
+}
+
+impl PdfCache {
+    /// An empty cache; the first density fills it.
+    pub fn new() -> Self {
+        Self {
+            fleet: FleetConfig::STANDARD,
+            placement: PlacementRules::STANDARD,
+            hit_bias: AiConfig::default().hit_bias,
+            shots: None,
+            lengths: [0; MAX_SHIPS],
+            sums: [[[0.0; GRID_SIZE]; GRID_SIZE]; MAX_SHIPS],
+        }
+    }
+
+    /// The same density [`calc_pdf_with_sunk`] gives for `view`, up to
+    /// rounding, updated from the shots seen last time.
+    pub fn density(&mut self, view: &TargetingView<'_>, config: &AiConfig) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+        if config.use_exact_solver {
+            if let Some(pdf) = calc_exact_pdf(view.hits, view.misses, view.remaining, view.fleet, view.placement) {
+                return pdf;
+            }
+        }
+        let unresolved = *view.hits & !*view.sunk;
+        let wrecks = if view.placement.no_touching { view.sunk.dilate(true) } else { *view.sunk };
+        let blocked = *view.misses | wrecks;
+        self.update(view.fleet, view.placement, config.hit_bias, unresolved, blocked);
+
+        let mut matrix = [[0.0f64; GRID_SIZE]; GRID_SIZE];
+        for &len in view.remaining.iter().filter(|&&l| l > 0) {
+            let Some(slot) = self.lengths.iter().position(|&l| l == len) else {
+                // Not a ship of this fleet: nothing cached to go on
+                return calc_pdf_with_sunk(
+                    view.hits,
+                    view.sunk,
+                    view.misses,
+                    view.remaining,
+                    view.fleet,
+                    view.placement,
+                    config,
+                );
+            };
+            add_matrix(&mut matrix, &self.sums[slot]);
+        }
+        // Shot cells carry no weight, whatever rounding left behind
+        for (r, c) in (unresolved | blocked).iter_set_bits() {
+            matrix[r][c] = 0.0;
+        }
+
+        if config.parity {
+            apply_parity(&mut matrix, view.hits, &blocked, view.remaining, view.fleet);
+        }
+
+        normalize(matrix)
+    }
+
+    /// Bring the sums in line with `unresolved` and `blocked`, re-scoring
+    /// only the placements the cells that changed can affect.
+    fn update(&mut self, fleet: &FleetConfig, placement: &PlacementRules, hit_bias: f64, unresolved: BB, blocked: BB) {
+        let same_game = self.fleet == *fleet && self.placement == *placement && self.hit_bias == hit_bias;
+        let previous = self
+            .shots
+            .filter(|&(old_unresolved, old_blocked)| {
+                same_game && ((old_unresolved | old_blocked) & !(unresolved | blocked)).is_empty()
+            });
+        let reach = match previous {
+            Some((old_unresolved, old_blocked)) => {
+                let changed = (old_unresolved ^ unresolved) | (old_blocked ^ blocked);
+                if placement.no_touching { changed.dilate(true) } else { changed }
+            }
+            None => {
+                *self = Self {
+                    fleet: *fleet,
+                    placement: *placement,
+                    hit_bias,
+                    lengths: distinct_lengths(fleet),
+                    ..Self::new()
+                };
+                !BB::new()
+            }
+        };
+        if reach.is_empty() {
+            return;
+        }
+        for slot in 0..MAX_SHIPS {
+            let len = self.lengths[slot];
+            for p in enumerate_placements(len, &BB::new(), &BB::new()) {
+                if (p.mask & reach).is_empty() {
+                    continue;
+                }
+                if let Some((old_unresolved, old_blocked)) = previous {
+                    self.add(slot, &p.mask, &old_unresolved, &old_blocked, -1.0);
+                }
+                self.add(slot, &p.mask, &unresolved, &blocked, 1.0);
+            }
+        }
+        self.shots = Some((unresolved, blocked));
+    }
+
+    /// Add `sign` times the weight of a ship on `mask` to its cells, if it
+    /// can lie there, as [`calc_pdf_with_sunk`] weighs it.
+    fn add(&mut self, slot: usize, mask: &BB, unresolved: &BB, blocked: &BB, sign: f64) {
+        if !(*mask & *blocked).is_empty() {
+            return;
+        }
+        if self.placement.no_touching && !(halo(mask) & !*mask & *unresolved).is_empty() {
+            return;
+        }
+        let hits = (*mask & *unresolved).count_ones();
+        let weight = if hits == 0 { 1.0 } else { pow(self.hit_bias, hits as f64) };
+        for (r, c) in (*mask & !*unresolved).iter_set_bits() {
+            self.sums[slot][r][c] += sign * weight;
+        }
+    }
+}
+
+impl Default for PdfCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Each ship length in `fleet` once, in fleet order, zero past the last.
+fn distinct_lengths(fleet: &FleetConfig) -> [usize; MAX_SHIPS] {
+    let mut lengths = [0; MAX_SHIPS];
+    let mut count = 0;
+    for def in fleet.ships() {
+        if !lengths[..count].contains(&def.length()) {
+            lengths[count] = def.length();
+            count += 1;
+        }
+    }
+    lengths
+}
@@ -0,0 +1,80 @@
+// Counters of hot-path work, for measuring changes to the AI and bitboards.
+// Uses no_std and avoids heap allocations.
+
+#[cfg(feature = "perf")]
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// A kind of work [`snapshot`] reports.
+///
+/// With the `perf` feature each is counted in a process-wide atomic;
+/// without it counting compiles to nothing and every counter reads zero.
+/// The counters are shared by every thread, so take a [`snapshot`] before
+/// and after the work to measure and compare them with [`Counters::since`]
+/// rather than resetting in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    /// Placement densities computed, in floating point or fixed point.
+    Densities,
+    /// Ship placements enumerated.
+    Placements,
+    /// Targets sampled from a density.
+    Samples,
+    /// Fleets the Monte Carlo sampler drew, kept or not.
+    Fleets,
+    /// Guesses a [`Board`](crate::Board) resolved.
+    Guesses,
+}
+
+const COUNTERS: usize = 5;
+
+#[cfg(feature = "perf")]
+static COUNTS: [AtomicU32; COUNTERS] = [const { AtomicU32::new(0) }; COUNTERS];
+
+/// Values of every [`Counter`] at one moment.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Counters {
+    counts: [u32; COUNTERS],
+}
+
+impl Counters {
+    /// Value of `counter`.
+    pub fn get(&self, counter: Counter) -> u32 {
+        self.counts[counter as usize]
+    }
+
+    /// Work done since `earlier` was taken; counters wrap around.
+    pub fn since(&self, earlier: &Counters) -> Counters {
+        let mut counts = self.counts;
+        for (count, before) in counts.iter_mut().zip(earlier.counts) {
+            *count = count.wrapping_sub(before);
+        }
+        Counters { counts }
+    }
+}
+
+/// Add `n` to `counter`.
+#[inline(always)]
+pub(crate) fn count(counter: Counter, n: u32) {
+    #[cfg(feature = "perf")]
+    COUNTS[counter as usize].fetch_add(n, Ordering::Relaxed);
+    #[cfg(not(feature = "perf"))]
+    let _ = (counter, n);
+}
+
+/// Every counter as it stands.
+pub fn snapshot() -> Counters {
+    #[cfg(feature = "perf")]
+    let counts = core::array::from_fn(|i| COUNTS[i].load(Ordering::Relaxed));
+    #[cfg(not(feature = "perf"))]
+    let counts = [0; COUNTERS];
+    Counters { counts }
+}
+
+/// Set every counter back to zero. Work on other threads in the meantime
+/// may be lost or kept; see [`Counters::since`].
+pub fn reset() {
+    #[cfg(feature = "perf")]
+    for count in &COUNTS {
+        count.store(0, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,311 @@
+// Fleet placement strategies for computer players.
+// Uses no_std and avoids heap allocations.
+
+use super::{
+    ai::{calc_pdf_with_rules, AiConfig},
+    board::{ship_mask, Board},
+    common::BoardError,
+    config::{BoardBits, MAX_BOARD_SIZE},
+    ship::Orientation,
+};
+use libm::{log, pow};
+use rand::Rng;
+
+/// Bitboard type alias for convenience.
+type BB = BoardBits;
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+/// Extra weight per ship cell lying on the outer ring of the board.
+const EDGE_WEIGHT: u32 = 4;
+
+/// Extra weight per ship cell orthogonally touching an already placed ship.
+const CLUSTER_WEIGHT: u32 = 4;
+
+/// Weight given to a position covering only the coldest cells of a heat map.
+const AVOID_HEAT_SCALE: f64 = 1000.0;
+
+/// How sharply heat-avoiding placement steers away from hot cells.
+const AVOID_HEAT_EXPONENT: f64 = 3.0;
+
+/// A way of laying out a fleet on an empty or partially filled board.
+///
+/// Implementations place every ship that is not yet on `board`, leaving
+/// already placed ships untouched.
+pub trait PlacementStrategy {
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError>;
+}
+
+/// Every legal position equally likely (per ship, in fleet order).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UniformRandom;
+
+/// Prefers positions along the outer ring of the board.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EdgeHugging;
+
+/// Prefers positions far away from ships already placed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Dispersed;
+
+/// Prefers positions touching ships already placed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Clustered;
+
+/// Avoids the cells the built-in targeting PDF rates highest on an empty
+/// board (the centre), so the crate's own AI needs more shots to find it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CounterMeta;
+
+/// Avoids cells in proportion to an arbitrary heat map, such as where an
+/// opponent has been observed to shoot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AvoidHeat {
+    heat: [[f64; GRID_SIZE]; GRID_SIZE],
+}
+
+impl AvoidHeat {
+    /// Strategy steering ships away from the hottest cells of `heat`.
+    pub fn new(heat: [[f64; GRID_SIZE]; GRID_SIZE]) -> Self {
+        Self { heat }
+    }
+}
+
+/// Draws each ship's position with probability proportional to the product
+/// of the weights of the cells it covers, so a zero-weight cell is never
+/// occupied and uniform weights reproduce uniform placement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightedRandom {
+    weights: [[f64; GRID_SIZE]; GRID_SIZE],
+}
+
+impl WeightedRandom {
+    /// Sampler over the per-cell `weights`; negative or NaN weights count as zero.
+    pub fn new(weights: [[f64; GRID_SIZE]; GRID_SIZE]) -> Self {
+        Self { weights }
+    }
+}
+
+/// Selectable placement strategy, e.g. for an AI personality setting.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlacementStyle {
+    #[default]
+    UniformRandom,
+    EdgeHugging,
+    Dispersed,
+    Clustered,
+    CounterMeta,
+}
+
+impl PlacementStrategy for UniformRandom {
+    /// Backtracks over the whole fleet, so a dense fleet that still has a
+    /// legal layout is always placed.
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError> {
+        board.place_all_random(rng)
+    }
+}
+
+impl PlacementStrategy for EdgeHugging {
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError> {
+        let ring = edge_ring(board.rules().board_size() as usize);
+        place_weighted(rng, board, |_, mask| {
+            (1 + EDGE_WEIGHT * (*mask & ring).count_ones() as u32) as f64
+        })
+    }
+}
+
+impl PlacementStrategy for Dispersed {
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError> {
+        place_weighted(rng, board, |occupied, mask| {
+            if occupied.is_empty() {
+                return 1.0;
+            }
+            let dist = mask
+                .iter_set_bits()
+                .flat_map(|(r, c)| {
+                    occupied
+                        .iter_set_bits()
+                        .map(move |(or, oc)| r.abs_diff(or).max(c.abs_diff(oc)))
+                })
+                .min()
+                .unwrap_or(GRID_SIZE) as u32;
+            (dist * dist) as f64
+        })
+    }
+}
+
+impl PlacementStrategy for Clustered {
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError> {
+        place_weighted(rng, board, |occupied, mask| {
+            let touching = mask
+                .iter_set_bits()
+                .filter(|&(r, c)| {
+                    orthogonal_neighbours(r, c).any(|(nr, nc)| occupied.get(nr, nc).unwrap_or(false))
+                })
+                .count() as u32;
+            (1 + CLUSTER_WEIGHT * touching) as f64
+        })
+    }
+}
+
+impl PlacementStrategy for CounterMeta {
+    /// Avoids the cells an opening shooter favours under this game's rules:
+    /// cells off a smaller board count as misses and the density follows
+    /// the game's fleet and placement rules.
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError> {
+        let rules = board.rules();
+        let lengths = rules.fleet().lengths();
+        let heat = calc_pdf_with_rules(
+            &BB::new(),
+            &rules.off_board(),
+            &lengths,
+            rules.fleet(),
+            &rules.placement(),
+            &AiConfig::default(),
+        );
+        AvoidHeat::new(heat).place_fleet(rng, board)
+    }
+}
+
+impl PlacementStrategy for AvoidHeat {
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError> {
+        let max = self.heat.iter().flatten().fold(0.0f64, |m, &p| m.max(p));
+        place_weighted(rng, board, |_, mask| {
+            let cells = mask.count_ones() as f64;
+            let heat = mask.iter_set_bits().map(|(r, c)| self.heat[r][c]).sum::<f64>() / cells;
+            let coolness = if max > 0.0 { 1.0 - heat / max } else { 1.0 };
+            1.0 + AVOID_HEAT_SCALE * pow(coolness, AVOID_HEAT_EXPONENT)
+        })
+    }
+}
+
+impl PlacementStrategy for WeightedRandom {
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError> {
+        place_weighted(rng, board, |_, mask| {
+            mask.iter_set_bits()
+                .map(|(r, c)| self.weights[r][c])
+                .map(|w| if w > 0.0 { w } else { 0.0 })
+                .product()
+        })
+    }
+}
+
+impl PlacementStrategy for PlacementStyle {
+    fn place_fleet<R: Rng + ?Sized>(&self, rng: &mut R, board: &mut Board) -> Result<(), BoardError> {
+        match self {
+            PlacementStyle::UniformRandom => UniformRandom.place_fleet(rng, board),
+            PlacementStyle::EdgeHugging => EdgeHugging.place_fleet(rng, board),
+            PlacementStyle::Dispersed => Dispersed.place_fleet(rng, board),
+            PlacementStyle::Clustered => Clustered.place_fleet(rng, board),
+            PlacementStyle::CounterMeta => CounterMeta.place_fleet(rng, board),
+        }
+    }
+}
+
+/// Indices of ships not yet placed on `board`.
+fn unplaced(board: &Board) -> impl Iterator<Item = usize> {
+    let states = board.ship_states();
+    (0..board.fleet().len()).filter(move |&i| states[i].position.is_none())
+}
+
+/// Place each unplaced ship by sampling legal positions in proportion to
+/// `weight(occupied, candidate_mask)`.
+///
+/// A ship left with no position of positive weight sends the search back to
+/// the earlier ships' next draws, so like [`Board::place_all_random`] this
+/// places a dense fleet whenever some layout of positive weight exists.
+/// Otherwise it returns `UnableToPlaceShip` and leaves the board unchanged.
+fn place_weighted<R, F>(rng: &mut R, board: &mut Board, weight: F) -> Result<(), BoardError>
+where
+    R: Rng + ?Sized,
+    F: Fn(&BB, &BB) -> f64,
+{
+    if search_weighted(rng, board, &weight)? {
+        Ok(())
+    } else {
+        Err(BoardError::UnableToPlaceShip)
+    }
+}
+
+/// Depth-first search for [`place_weighted`]: tries the first unplaced
+/// ship's positions in a weighted random order, placing the rest after each.
+fn search_weighted<R, F>(rng: &mut R, board: &mut Board, weight: &F) -> Result<bool, BoardError>
+where
+    R: Rng + ?Sized,
+    F: Fn(&BB, &BB) -> f64,
+{
+    let Some(i) = unplaced(board).next() else {
+        return Ok(true);
+    };
+    let occupied = board.ship_map();
+    let mut order = [((0u8, 0u8, Orientation::Horizontal), 0.0f64); 2 * GRID_SIZE * GRID_SIZE];
+    let mut count = 0;
+    for_each_candidate(board, i, |(r, c, o), mask| {
+        let w = weight(&occupied, &mask);
+        if w > 0.0 {
+            // Sorting on exponential arrival times with rate `w` draws the
+            // positions in proportion to their weights, without replacement
+            let u = rng.random::<f64>();
+            order[count] = ((r as u8, c as u8, o), -log(1.0 - u) / w);
+            count += 1;
+        }
+    })?;
+    order[..count].sort_unstable_by(|a, b| a.1.total_cmp(&b.1));
+
+    for &((r, c, o), _) in &order[..count] {
+        board.place(i, r as usize, c as usize, o)?;
+        if search_weighted(rng, board, weight)? {
+            return Ok(true);
+        }
+        board.remove(i)?;
+    }
+    Ok(false)
+}
+
+/// Call `f` with every position where ship `index` can legally go on `board`.
+fn for_each_candidate<F>(board: &Board, index: usize, mut f: F) -> Result<(), BoardError>
+where
+    F: FnMut((usize, usize, Orientation), BB),
+{
+    let len = board.fleet().get(index).ok_or(BoardError::InvalidIndex)?.length();
+    let n = board.size() as usize;
+    for orient in [Orientation::Horizontal, Orientation::Vertical] {
+        let (max_row, max_col) = match orient {
+            Orientation::Horizontal => (n, n - len + 1),
+            Orientation::Vertical => (n - len + 1, n),
+        };
+        for r in 0..max_row {
+            for c in 0..max_col {
+                match board.can_place(index, r, c, orient) {
+                    Ok(()) => f((r, c, orient), ship_mask(len, r, c, orient)?),
+                    Err(BoardError::ShipOverlaps | BoardError::ShipsTouch) => {}
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Mask of all cells on the outer ring of a `size`×`size` board.
+fn edge_ring(size: usize) -> BB {
+    let mut ring = BB::new();
+    for i in 0..size {
+        for (r, c) in [(0, i), (size - 1, i), (i, 0), (i, size - 1)] {
+            let _ = ring.set(r, c);
+        }
+    }
+    ring
+}
+
+fn orthogonal_neighbours(r: usize, c: usize) -> impl Iterator<Item = (usize, usize)> {
+    [
+        (r.wrapping_sub(1), c),
+        (r + 1, c),
+        (r, c.wrapping_sub(1)),
+        (r, c + 1),
+    ]
+    .into_iter()
+    .filter(|&(nr, nc)| nr < GRID_SIZE && nc < GRID_SIZE)
+}
@@ -0,0 +1,243 @@
+//! Recorded games that can be stepped through move by move.
+//!
+//! A replay stores both fleets and every shot in the order it was fired, as
+//! JSON with coordinates in the same `B5` notation the CLI accepts
+//! (column letter, then row number). Schema (version 1):
+//!
+//! ```text
+//! {
+//!   "version": 1,
+//!   "fleets": [
+//!     [ { "ship": "Carrier", "row": 0, "col": 0, "orientation": "Horizontal" }, ... ],
+//!     [ ... ]
+//!   ],
+//!   "moves": [ { "player": 0, "target": "E5" }, { "player": 1, "target": "A1" }, ... ]
+//! }
+//! ```
+//!
+//! Results are not stored; [`Replay::frame`] recomputes them by replaying
+//! the shots against the fleets, so a replay can never disagree with itself.
+
+#![cfg(feature = "std")]
+
+use super::{
+    board::Board,
+    common::GuessResult,
+    coord::Coord,
+    config::{NUM_SHIPS, SHIPS},
+    events::GameEvent,
+    game::GameEngine,
+    ship::Orientation,
+};
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use tokio::sync::broadcast;
+
+/// Current version of the replay format.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// Where one ship of a fleet was placed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ShipPlacement {
+    pub ship: String,
+    pub row: usize,
+    pub col: usize,
+    pub orientation: Orientation,
+}
+
+/// One shot: `player` (0 or 1) fired at `target`, e.g. `"B5"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplayMove {
+    pub player: usize,
+    pub target: String,
+}
+
+/// A complete recorded game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub fleets: [Vec<ShipPlacement>; 2],
+    pub moves: Vec<ReplayMove>,
+}
+
+/// The game as it stood after the first `index` moves of a replay.
+pub struct ReplayFrame {
+    pub index: usize,
+    /// Each player's engine: own board with damage taken, and guesses made.
+    pub engines: [GameEngine; 2],
+    /// The move that led here and its result.
+    pub last: Option<(usize, (usize, usize), GuessResult)>,
+    /// The move played from here, if any: shooter and target.
+    pub next: Option<(usize, (usize, usize))>,
+}
+
+/// Coordinate in CLI notation: column letter then 1-based row.
+pub fn notation(row: usize, col: usize) -> String {
+    Coord::new(row, col).to_string()
+}
+
+/// Parse CLI notation such as `B5` into `(row, col)`.
+pub fn parse_notation(text: &str) -> anyhow::Result<(usize, usize)> {
+    let coord: Coord = text
+        .parse()
+        .map_err(|e| anyhow!("Invalid coordinate {:?}: {}", text, e))?;
+    Ok(coord.into())
+}
+
+impl Replay {
+    /// Start a replay of a game between these two fleets, with no moves yet.
+    pub fn new(fleets: [&Board; 2]) -> Self {
+        let placements = |board: &Board| {
+            board
+                .ship_states()
+                .iter()
+                .filter_map(|s| {
+                    s.position.map(|(row, col, orientation)| ShipPlacement {
+                        ship: s.name.to_string(),
+                        row,
+                        col,
+                        orientation,
+                    })
+                })
+                .collect()
+        };
+        Self {
+            version: REPLAY_VERSION,
+            fleets: [placements(fleets[0]), placements(fleets[1])],
+            moves: Vec::new(),
+        }
+    }
+
+    /// Append the shots each player fired, given in their own order.
+    /// Turns alternate, starting with `first`.
+    pub fn with_shots(mut self, first: usize, shots: [Vec<(usize, usize)>; 2]) -> Self {
+        let mut fired = [0usize; 2];
+        let mut player = first;
+        while let Some(&(row, col)) = shots[player].get(fired[player]) {
+            self.push(player, row, col);
+            fired[player] += 1;
+            player = 1 - player;
+        }
+        self
+    }
+
+    /// Append a shot by `player` at `(row, col)`.
+    pub fn push(&mut self, player: usize, row: usize, col: usize) {
+        self.moves.push(ReplayMove {
+            player,
+            target: notation(row, col),
+        });
+    }
+
+    /// Number of moves recorded.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// The game after the first `index` moves (`0` is before any shot).
+    pub fn frame(&self, index: usize) -> anyhow::Result<ReplayFrame> {
+        if index > self.moves.len() {
+            return Err(anyhow!("Move {} is past the end of the replay ({} moves)", index, self.moves.len()));
+        }
+        let mut engines = [GameEngine::new(), GameEngine::new()];
+        for (player, fleet) in self.fleets.iter().enumerate() {
+            *engines[player].board_mut() = fleet_board(fleet).with_context(|| format!("Invalid fleet for player {}", player))?;
+        }
+        let mut last = None;
+        for (i, mv) in self.moves[..index].iter().enumerate() {
+            let (player, target) = self.parse_move(i, mv)?;
+            let result = engines[1 - player]
+                .opponent_guess(target.0, target.1)
+                .map_err(|e| anyhow!("Move {} ({}): {}", i + 1, mv.target, e))?;
+            engines[player]
+                .record_guess(target.0, target.1, result)
+                .map_err(|e| anyhow!("Move {} ({}): {}", i + 1, mv.target, e))?;
+            last = Some((player, target, result));
+        }
+        let next = match self.moves.get(index) {
+            Some(mv) => Some(self.parse_move(index, mv)?),
+            None => None,
+        };
+        Ok(ReplayFrame {
+            index,
+            engines,
+            last,
+            next,
+        })
+    }
+
+    fn parse_move(&self, i: usize, mv: &ReplayMove) -> anyhow::Result<(usize, (usize, usize))> {
+        if mv.player > 1 {
+            return Err(anyhow!("Move {}: player must be 0 or 1, got {}", i + 1, mv.player));
+        }
+        Ok((mv.player, parse_notation(&mv.target).with_context(|| format!("Move {}", i + 1))?))
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        serde_json::to_string_pretty(self).context("Failed to encode replay as JSON")
+    }
+
+    /// Parse and check a replay: every fleet must be legal and every move
+    /// must land on a cell its shooter has not tried before.
+    pub fn from_json(json: &str) -> anyhow::Result<Self> {
+        let replay: Replay = serde_json::from_str(json).context("Failed to parse replay JSON")?;
+        if replay.version != REPLAY_VERSION {
+            return Err(anyhow!(
+                "Unsupported replay version {} (expected {})",
+                replay.version,
+                REPLAY_VERSION
+            ));
+        }
+        replay.frame(replay.len())?;
+        Ok(replay)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let json = std::fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+        Self::from_json(&json).with_context(|| format!("Invalid replay {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, self.to_json()?).with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+fn fleet_board(fleet: &[ShipPlacement]) -> anyhow::Result<Board> {
+    if fleet.len() != NUM_SHIPS {
+        return Err(anyhow!("Fleet must have {} ships, got {}", NUM_SHIPS, fleet.len()));
+    }
+    let mut board = Board::new();
+    for p in fleet {
+        let index = SHIPS
+            .iter()
+            .position(|def| def.name() == p.ship)
+            .ok_or_else(|| anyhow!("Unknown ship {:?}", p.ship))?;
+        board
+            .place(index, p.row, p.col, p.orientation)
+            .map_err(|e| anyhow!("Cannot place {}: {}", p.ship, e))?;
+    }
+    Ok(board)
+}
+
+/// Collect the shots a player fired from its event stream, in order, until
+/// the publishing node goes away.
+pub async fn shots_fired(mut events: broadcast::Receiver<GameEvent>) -> anyhow::Result<Vec<(usize, usize)>> {
+    let mut shots = Vec::new();
+    loop {
+        match events.recv().await {
+            Ok(GameEvent::GuessSent { row, col }) => shots.push((row, col)),
+            Ok(_) => {}
+            Err(broadcast::error::RecvError::Closed) => return Ok(shots),
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                return Err(anyhow!("Missed {} game events; the replay would be incomplete", missed))
+            }
+        }
+    }
+}
@@ -1,14 +1,13 @@
-//! Ship definitions and placement logic using our `BitBoard`.
+//! Ship definitions and placement logic over any [`CellGrid`] board.
 
 use core::fmt;
-use num_traits::{PrimInt, Unsigned, Zero};
 
-use super::bitboard::BitBoard;
+use super::bitgrid::CellGrid;
 use super::common::BoardError;
 
 /// Orientation of a ship on the board.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Orientation {
     Horizontal,
     Vertical,
@@ -16,12 +15,15 @@ pub enum Orientation {
 
 /// Public state of a ship on the board used for serialization or UI.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShipState {
-    #[cfg_attr(feature = "std", serde(skip))]
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub name: &'static str,
     pub sunk: bool,
     pub position: Option<(usize, usize, Orientation)>,
+    /// Segments hit: bit `k` is set once the `k`-th cell from the ship's
+    /// origin has been hit.
+    pub damage: u16,
 }
 
 impl ShipState {
@@ -31,27 +33,28 @@ impl ShipState {
             name,
             sunk: false,
             position: None,
+            damage: 0,
         }
     }
+
+    /// Whether segment `k`, counted from the ship's origin, has been hit.
+    pub const fn segment_hit(&self, k: usize) -> bool {
+        k < u16::BITS as usize && self.damage & (1 << k) != 0
+    }
 }
 
-impl<T, const N: usize> From<&Ship<T, N>> for ShipState
-where
-    T: PrimInt + Unsigned + Zero,
-{
-    fn from(ship: &Ship<T, N>) -> Self {
+impl<G: CellGrid> From<&Ship<G>> for ShipState {
+    fn from(ship: &Ship<G>) -> Self {
         ShipState {
             name: ship.ship_type().name(),
             sunk: ship.is_sunk(),
             position: Some((ship.row, ship.col, ship.orientation)),
+            damage: ship.damage(),
         }
     }
 }
 
-impl<T, const N: usize> Ship<T, N>
-where
-    T: PrimInt + Unsigned + Zero,
-{
+impl<G: CellGrid> Ship<G> {
     /// Construct a ship from a [`ShipState`] if placement information is present.
     pub fn from_state(state: &ShipState, def: ShipDef) -> Result<Option<Self>, BoardError> {
         if let Some((row, col, orient)) = state.position {
@@ -63,7 +66,7 @@ where
 }
 
 /// Definition of a ship: name and length.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct ShipDef {
     name: &'static str,
     length: usize,
@@ -86,24 +89,19 @@ impl ShipDef {
     }
 }
 
-/// A ship placed on an N×N board, with hits tracked in a `BitBoard`.
+/// A ship placed on a board stored as `G`, e.g. a `BitBoard`, with its
+/// cells and hits tracked in that same storage.
 #[derive(Clone, Copy, PartialEq, Eq)]
-pub struct Ship<T, const N: usize>
-where
-    T: PrimInt + Unsigned + Zero,
-{
+pub struct Ship<G: CellGrid> {
     ship_type: ShipDef,
     orientation: Orientation,
     row: usize,
     col: usize,
-    mask: BitBoard<T, N>,
-    hits: BitBoard<T, N>,
+    mask: G,
+    hits: G,
 }
 
-impl<T, const N: usize> Ship<T, N>
-where
-    T: PrimInt + Unsigned + Zero,
-{
+impl<G: CellGrid> Ship<G> {
     /// Place a ship at (`row`, `col`) with `orientation`.
     /// Returns the newly constructed ship.
     pub fn new(
@@ -113,35 +111,28 @@ where
         col: usize,
     ) -> Result<Self, BoardError> {
         let len = ship_type.length();
-        // Ensure placement fits within N×N
+        // Ensure placement fits within the grid
         if orientation == Orientation::Horizontal {
-            if col + len > N {
+            if col + len > G::SIDE {
                 return Err(BoardError::ShipOutOfBounds);
             }
-        } else if row + len > N {
+        } else if row + len > G::SIDE {
             return Err(BoardError::ShipOutOfBounds);
         }
 
-        // Build occupancy mask
-        let mut mask = BitBoard::<T, N>::new();
-        for i in 0..len {
-            let (r, c) = match orientation {
-                Orientation::Horizontal => (row, col + i),
-                Orientation::Vertical => (row + i, col),
-            };
-            mask.set(r, c)?;
-        }
-
-        // Initialize empty hits board
-        let hits = BitBoard::<T, N>::new();
-        Ok(Ship {
+        let mut ship = Ship {
             ship_type,
             orientation,
             row,
             col,
-            mask,
-            hits,
-        })
+            mask: G::empty(),
+            hits: G::empty(),
+        };
+        for k in 0..len {
+            let (r, c) = ship.segment(k);
+            ship.mask.set(r, c)?;
+        }
+        Ok(ship)
     }
 
     /// Register a hit at (`row`, `col`) using an occupancy mask.
@@ -176,15 +167,36 @@ where
     }
 
     /// Occupancy mask of the ship on the board.
-    pub fn mask(&self) -> BitBoard<T, N> {
+    pub fn mask(&self) -> G {
         self.mask
     }
+
+    /// Cells of the ship that have been hit.
+    pub fn hit_cells(&self) -> G {
+        self.hits
+    }
+
+    /// Cell of segment `k`, counted from the origin.
+    fn segment(&self, k: usize) -> (usize, usize) {
+        match self.orientation {
+            Orientation::Horizontal => (self.row, self.col + k),
+            Orientation::Vertical => (self.row + k, self.col),
+        }
+    }
+
+    /// Segments hit as a bit mask, bit `k` for the `k`-th cell from the
+    /// origin; see [`ShipState::damage`].
+    pub fn damage(&self) -> u16 {
+        (0..self.ship_type.length().min(u16::BITS as usize))
+            .filter(|&k| {
+                let (r, c) = self.segment(k);
+                self.hits.get(r, c).unwrap_or(false)
+            })
+            .fold(0, |damage, k| damage | (1 << k))
+    }
 }
 
-impl<T, const N: usize> fmt::Debug for Ship<T, N>
-where
-    T: PrimInt + Unsigned + Zero + fmt::Binary,
-{
+impl<G: CellGrid + fmt::Debug> fmt::Debug for Ship<G> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
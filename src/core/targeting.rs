@@ -0,0 +1,187 @@
+// Targeting strategies for computer players.
+// Uses no_std and avoids heap allocations.
+
+use super::{
+    ai::{calc_pdf_monte_carlo_with_rules, calc_pdf_with_sunk, hunt_lattice, AiConfig},
+    config::{BoardBits, FleetConfig, PlacementRules, MAX_BOARD_SIZE},
+    info_gain::info_gain_scores,
+};
+use rand::Rng;
+
+/// Bitboard type alias for convenience.
+type BB = BoardBits;
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+/// Fleets [`MonteCarlo`] samples when [`AiConfig::mc_samples`] is zero.
+const DEFAULT_MC_SAMPLES: usize = 1000;
+
+/// What a targeting strategy knows about the opponent's board.
+#[derive(Debug, Clone, Copy)]
+pub struct TargetingView<'a> {
+    /// Every hit so far.
+    pub hits: &'a BB,
+    /// The hits known to belong to sunk ships.
+    pub sunk: &'a BB,
+    /// Misses, and any other cells no ship can occupy.
+    pub misses: &'a BB,
+    /// Lengths of the ships still afloat, zero for sunk ones.
+    pub remaining: &'a [usize],
+    /// The opponent's fleet.
+    pub fleet: &'a FleetConfig,
+    /// Rules the opponent's ships were placed under.
+    pub placement: &'a PlacementRules,
+}
+
+impl TargetingView<'_> {
+    /// Cells not yet shot.
+    fn unknown(&self) -> BB {
+        BB::unknown(self.hits, self.misses)
+    }
+}
+
+/// A way of weighing the cells worth shooting next.
+///
+/// Implementations return a weight per cell, zero for cells already shot;
+/// the weights need not sum to one. Callers sample a target from them, as
+/// [`AiPlayer`](crate::AiPlayer) does with its temperature.
+pub trait TargetingStrategy {
+    fn density<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        view: &TargetingView<'_>,
+        config: &AiConfig,
+    ) -> [[f64; GRID_SIZE]; GRID_SIZE];
+}
+
+/// Every unknown cell equally likely.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RandomTargeting;
+
+/// Every unknown cell on the [`hunt_lattice`] equally likely, or every
+/// unknown cell while a hit is unresolved.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParityHunt;
+
+/// The placement density of [`calc_pdf_with_sunk`], or Monte Carlo
+/// sampling when [`AiConfig::mc_samples`] is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DensityTargeting;
+
+/// Sampled whole fleets, as [`calc_pdf_monte_carlo_with_rules`] draws them,
+/// even when [`AiConfig::mc_samples`] is zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MonteCarlo;
+
+/// The shots expected to tell the most about the fleet layout, scored by
+/// [`info_gain_scores`] over the layouts [`MonteCarlo`] samples (or counts
+/// exactly, with [`AiConfig::use_exact_solver`]) rather than by how likely
+/// each cell is to hold a ship.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InfoGain;
+
+/// Selectable targeting strategy, e.g. to pit strategies against each other.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TargetingStyle {
+    Random,
+    ParityHunt,
+    #[default]
+    Density,
+    MonteCarlo,
+    InfoGain,
+}
+
+impl TargetingStrategy for RandomTargeting {
+    fn density<R: Rng + ?Sized>(
+        &self,
+        _rng: &mut R,
+        view: &TargetingView<'_>,
+        _config: &AiConfig,
+    ) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+        uniform_over(&view.unknown())
+    }
+}
+
+impl TargetingStrategy for ParityHunt {
+    fn density<R: Rng + ?Sized>(
+        &self,
+        _rng: &mut R,
+        view: &TargetingView<'_>,
+        _config: &AiConfig,
+    ) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+        let unknown = view.unknown();
+        let lattice = hunt_lattice(view.hits, view.misses, view.remaining, view.fleet)
+            .map(|lattice| lattice & unknown)
+            .filter(|cells| !cells.is_empty());
+        uniform_over(&lattice.unwrap_or(unknown))
+    }
+}
+
+impl TargetingStrategy for DensityTargeting {
+    fn density<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        view: &TargetingView<'_>,
+        config: &AiConfig,
+    ) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+        if config.mc_samples > 0 {
+            return MonteCarlo.density(rng, view, config);
+        }
+        calc_pdf_with_sunk(view.hits, view.sunk, view.misses, view.remaining, view.fleet, view.placement, config)
+    }
+}
+
+impl TargetingStrategy for MonteCarlo {
+    fn density<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        view: &TargetingView<'_>,
+        config: &AiConfig,
+    ) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+        let samples = if config.mc_samples > 0 { config.mc_samples } else { DEFAULT_MC_SAMPLES };
+        let config = AiConfig { mc_samples: samples, ..*config };
+        let blocked = *view.misses | *view.sunk;
+        calc_pdf_monte_carlo_with_rules(view.hits, &blocked, view.remaining, view.fleet, view.placement, &config, rng)
+    }
+}
+
+impl TargetingStrategy for InfoGain {
+    fn density<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        view: &TargetingView<'_>,
+        config: &AiConfig,
+    ) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+        // Parity would skew the layouts' share of each cell
+        let layouts = MonteCarlo.density(rng, view, &AiConfig { parity: false, ..*config });
+        let unseen = view.fleet.total_cells().saturating_sub(view.hits.count_ones());
+        info_gain_scores(&layouts, unseen)
+    }
+}
+
+impl TargetingStrategy for TargetingStyle {
+    fn density<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        view: &TargetingView<'_>,
+        config: &AiConfig,
+    ) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+        match self {
+            TargetingStyle::Random => RandomTargeting.density(rng, view, config),
+            TargetingStyle::ParityHunt => ParityHunt.density(rng, view, config),
+            TargetingStyle::Density => DensityTargeting.density(rng, view, config),
+            TargetingStyle::MonteCarlo => MonteCarlo.density(rng, view, config),
+            TargetingStyle::InfoGain => InfoGain.density(rng, view, config),
+        }
+    }
+}
+
+/// Weight one on each of `cells`, zero elsewhere.
+fn uniform_over(cells: &BB) -> [[f64; GRID_SIZE]; GRID_SIZE] {
+    let mut matrix = [[0.0f64; GRID_SIZE]; GRID_SIZE];
+    for (r, c) in cells.iter_set_bits() {
+        matrix[r][c] = 1.0;
+    }
+    matrix
+}
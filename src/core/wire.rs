@@ -0,0 +1,157 @@
+//! Build-independent serde encodings for the board-sized fields of the core
+//! state types.
+//!
+//! [`BoardBits`] is laid out for this build's [`MAX_BOARD_SIZE`], so its own
+//! encoding differs between builds with and without `large-boards`. Fields
+//! that cross the wire or feed a digest use [`board_bits`] instead: the rows
+//! of the board as bitmasks, column 0 in the lowest bit, with trailing empty
+//! rows left off. Equal cells encode to equal bytes in every build, and a
+//! build refuses cells past the edge of its largest board.
+//!
+//! Per-ship arrays are sized by [`MAX_SHIPS`] and go through [`fleet_slots`]
+//! the same way: the slots up to the last one in use, with a length prefix.
+
+use super::config::{BoardBits, MAX_BOARD_SIZE, MAX_SHIPS};
+use super::ship::ShipState;
+
+const SIZE: usize = MAX_BOARD_SIZE as usize;
+
+// Each row travels as one `u16`
+const _: () = assert!(SIZE <= u16::BITS as usize);
+
+/// `#[serde(with = "crate::core::wire::board_bits")]` for a [`BoardBits`]
+/// field.
+pub mod board_bits {
+    use super::{BoardBits, SIZE};
+    use core::fmt;
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::ser::SerializeSeq;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(bits: &BoardBits, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut rows = [0u16; SIZE];
+        for (r, c) in bits.iter_set_bits() {
+            rows[r] |= 1 << c;
+        }
+        let len = rows.iter().rposition(|&row| row != 0).map_or(0, |last| last + 1);
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        for row in &rows[..len] {
+            seq.serialize_element(row)?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BoardBits, D::Error> {
+        struct Rows;
+
+        impl<'de> Visitor<'de> for Rows {
+            type Value = BoardBits;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "at most {} rows of {} cells", SIZE, SIZE)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut bits = BoardBits::new();
+                let mut r = 0;
+                while let Some(row) = seq.next_element::<u16>()? {
+                    if r == SIZE || u32::from(row) >> SIZE != 0 {
+                        return Err(A::Error::custom(format_args!(
+                            "cell in row {} is off a {}x{} board",
+                            r, SIZE, SIZE
+                        )));
+                    }
+                    for c in (0..SIZE).filter(|c| row & (1 << c) != 0) {
+                        bits.set(r, c).map_err(A::Error::custom)?;
+                    }
+                    r += 1;
+                }
+                Ok(bits)
+            }
+        }
+
+        deserializer.deserialize_seq(Rows)
+    }
+}
+
+/// A per-ship slot that [`fleet_slots`] may leave off the end of the fleet.
+pub trait FleetSlot: Copy {
+    /// The value of a slot past the end of the fleet.
+    const EMPTY: Self;
+
+    /// Whether the slot carries nothing worth sending.
+    fn is_empty(&self) -> bool;
+}
+
+impl FleetSlot for bool {
+    const EMPTY: Self = false;
+
+    fn is_empty(&self) -> bool {
+        !*self
+    }
+}
+
+impl FleetSlot for ShipState {
+    const EMPTY: Self = ShipState::new("");
+
+    fn is_empty(&self) -> bool {
+        !self.sunk && self.position.is_none() && self.damage == 0
+    }
+}
+
+/// `#[serde(with = "crate::core::wire::fleet_slots")]` for a
+/// `[T; MAX_SHIPS]` field.
+pub mod fleet_slots {
+    use super::{FleetSlot, MAX_SHIPS};
+    use core::{fmt, marker::PhantomData};
+    use serde::de::{Error, SeqAccess, Visitor};
+    use serde::ser::SerializeSeq;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S, T>(slots: &[T; MAX_SHIPS], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: FleetSlot + Serialize,
+    {
+        let len = slots.iter().rposition(|slot| !slot.is_empty()).map_or(0, |last| last + 1);
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        for slot in &slots[..len] {
+            seq.serialize_element(slot)?;
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<[T; MAX_SHIPS], D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FleetSlot + Deserialize<'de>,
+    {
+        struct Slots<T>(PhantomData<T>);
+
+        impl<'de, T: FleetSlot + Deserialize<'de>> Visitor<'de> for Slots<T> {
+            type Value = [T; MAX_SHIPS];
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "at most {} ship slots", MAX_SHIPS)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut slots = [T::EMPTY; MAX_SHIPS];
+                let mut i = 0;
+                while let Some(slot) = seq.next_element::<T>()? {
+                    if i == MAX_SHIPS {
+                        return Err(A::Error::custom(format_args!(
+                            "more than {} ship slots",
+                            MAX_SHIPS
+                        )));
+                    }
+                    slots[i] = slot;
+                    i += 1;
+                }
+                Ok(slots)
+            }
+        }
+
+        deserializer.deserialize_seq(Slots(PhantomData))
+    }
+}
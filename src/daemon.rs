@@ -0,0 +1,636 @@
+//! Headless matchmaking: pair incoming players and relay their games.
+//!
+//! The daemon keeps no game state of its own. Each connection waits in a
+//! FIFO queue until an opponent arrives; both players are then told their
+//! side with [`Message::Paired`] (the earlier arrival is the initiator) and
+//! every message from one is relayed unchanged to the other. The relay
+//! watches guesses, sink reports and resignations so it can record who won
+//! or that the game was drawn.
+//!
+//! Heartbeats stop at the daemon. They keep each player's own link alive but
+//! are never forwarded: `HeartbeatTransport` echoes what it receives, so a
+//! relayed heartbeat would bounce between the players forever.
+//!
+//! [`serve_lobby`] is the alternative to the FIFO queue: clients browse and
+//! host games over the lobby channel ([`crate::protocol::lobby`]) and pick
+//! their opponent; the game is then relayed the same way.
+//!
+//! Both servers report what they relay to the [`Metrics`] in their
+//! [`DaemonConfig`], which `metrics::serve` can expose to Prometheus.
+
+#![cfg(feature = "std")]
+
+use crate::core::coord::Coord;
+use crate::metrics::Metrics;
+use crate::protocol::{
+    auth::{LobbyAuth, LobbyGate, RateLimit},
+    domain::GuessResult,
+    lobby::{GameId, Lobby, LobbyEvent, LobbyMessage, LobbyReply},
+    handshake::SUPPORTED_VERSIONS,
+    Message, Role,
+};
+use crate::transport::tcp::{self, TcpTransport};
+use crate::transport::Transport;
+use anyhow::anyhow;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{broadcast, mpsc, oneshot};
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+
+/// Lobby events buffered per connection before slow readers miss some.
+const LOBBY_EVENT_CAPACITY: usize = 64;
+
+/// Settings for [`serve`] and [`serve_lobby`].
+#[derive(Debug, Clone)]
+pub struct DaemonConfig {
+    /// How long a player may stay silent before the daemon drops them.
+    /// Clients heartbeat only while waiting on the network, so this also
+    /// bounds how long a player may think about a move.
+    pub idle_timeout: Duration,
+    /// Credentials required before lobby commands are accepted.
+    pub auth: LobbyAuth,
+    /// Per-identity limit on lobby requests; `None` disables it.
+    pub rate_limit: Option<RateLimit>,
+    /// Where matches, moves and connection failures are counted.
+    pub metrics: Arc<Metrics>,
+}
+
+impl Default for DaemonConfig {
+    fn default() -> Self {
+        Self {
+            idle_timeout: Duration::from_secs(300),
+            auth: LobbyAuth::Open,
+            rate_limit: Some(RateLimit::default()),
+            metrics: Arc::new(Metrics::new()),
+        }
+    }
+}
+
+/// How a relayed game ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum MatchOutcome {
+    /// Every ship of one player was reported sunk, or that player resigned
+    /// or let its turn clock run out. `winner` indexes
+    /// [`MatchRecord::players`].
+    Finished { winner: usize },
+    /// Both players resigned at once, or both fired the rules' move limit
+    /// without either fleet going down.
+    Drawn,
+    /// `player` disconnected or sent something unrelayable before the game
+    /// was decided.
+    Abandoned { player: usize, reason: String },
+}
+
+/// Result of one relayed game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatchRecord {
+    /// Sequential match number, starting at 0 for each daemon.
+    pub id: u64,
+    /// Peer labels, usually socket addresses; index 0 is the initiator.
+    pub players: [String; 2],
+    /// Guesses sent by each player, counting every shot of a salvo.
+    pub shots: [u32; 2],
+    #[serde(flatten)]
+    pub outcome: MatchOutcome,
+}
+
+/// A queued player, held by a task that drains its heartbeats until paired.
+struct Waiter<T> {
+    peer: String,
+    pair: oneshot::Sender<()>,
+    task: JoinHandle<anyhow::Result<T>>,
+}
+
+impl<T: Transport + 'static> Waiter<T> {
+    fn spawn(peer: String, transport: T) -> Self {
+        let (pair, paired) = oneshot::channel();
+        Self {
+            peer,
+            pair,
+            task: tokio::spawn(hold(transport, paired)),
+        }
+    }
+
+    /// Take the transport back, or `None` if the player left while queued.
+    async fn take(self) -> Option<(String, T)> {
+        let _ = self.pair.send(());
+        match self.task.await {
+            Ok(Ok(transport)) => Some((self.peer, transport)),
+            Ok(Err(e)) => {
+                tracing::info!(peer = %self.peer, error = %e, "player left the queue");
+                None
+            }
+            Err(e) => {
+                tracing::warn!(peer = %self.peer, error = %e, "queue task failed");
+                None
+            }
+        }
+    }
+}
+
+async fn hold<T: Transport>(mut transport: T, mut paired: oneshot::Receiver<()>) -> anyhow::Result<T> {
+    loop {
+        tokio::select! {
+            biased;
+            res = &mut paired => {
+                res.map_err(|_| anyhow!("Matchmaker shut down"))?;
+                return Ok(transport);
+            }
+            msg = transport.recv() => match msg? {
+                Message::Heartbeat { .. } => {}
+                other => {
+                    return Err(anyhow!("Unexpected message while waiting for an opponent: {:?}", other))
+                }
+            },
+        }
+    }
+}
+
+/// FIFO queue of players waiting for an opponent.
+///
+/// Generic over the transport so the pairing logic can run over anything;
+/// [`serve`] feeds it TCP connections.
+pub struct Matchmaker<T> {
+    queue: VecDeque<Waiter<T>>,
+    next_id: u64,
+    results: mpsc::UnboundedSender<MatchRecord>,
+    metrics: Arc<Metrics>,
+}
+
+impl<T: Transport + 'static> Matchmaker<T> {
+    /// Create an empty matchmaker; every finished or abandoned match is sent
+    /// to `results`.
+    pub fn new(results: mpsc::UnboundedSender<MatchRecord>) -> Self {
+        Self {
+            queue: VecDeque::new(),
+            next_id: 0,
+            results,
+            metrics: Arc::new(Metrics::new()),
+        }
+    }
+
+    /// Count matches in `metrics` instead of a private set.
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Number of queued players that are still connected.
+    pub fn waiting(&self) -> usize {
+        self.queue.iter().filter(|w| !w.task.is_finished()).count()
+    }
+
+    /// Queue a newly connected player, starting a match as soon as two
+    /// players are waiting.
+    pub async fn enqueue(&mut self, peer: impl Into<String>, transport: T) {
+        // A task that finished before being paired means the player left
+        self.queue.retain(|w| !w.task.is_finished());
+        self.queue.push_back(Waiter::spawn(peer.into(), transport));
+
+        while self.queue.len() >= 2 {
+            let (first, second) = match (self.queue.pop_front(), self.queue.pop_front()) {
+                (Some(first), Some(second)) => (first, second),
+                _ => break,
+            };
+            match (first.take().await, second.take().await) {
+                (Some(a), Some(b)) => self.start_match(a, b),
+                // A survivor keeps its place at the head of the queue
+                (Some((peer, t)), None) | (None, Some((peer, t))) => {
+                    self.metrics.record_transport_error();
+                    self.queue.push_front(Waiter::spawn(peer, t));
+                }
+                (None, None) => {
+                    self.metrics.record_transport_error();
+                    self.metrics.record_transport_error();
+                }
+            }
+        }
+    }
+
+    fn start_match(&mut self, a: (String, T), b: (String, T)) {
+        let id = self.next_id;
+        self.next_id += 1;
+        spawn_match(id, a, b, self.results.clone(), self.metrics.clone());
+    }
+}
+
+/// Announce roles to a paired couple (`a` initiates) and relay their game
+/// in the background, sending the result to `results`.
+fn spawn_match<T: Transport + 'static>(
+    id: u64,
+    (peer_a, mut a): (String, T),
+    (peer_b, mut b): (String, T),
+    results: mpsc::UnboundedSender<MatchRecord>,
+    metrics: Arc<Metrics>,
+) {
+    tokio::spawn(async move {
+        tracing::info!(id, initiator = %peer_a, responder = %peer_b, "match started");
+        metrics.game_started(id);
+        let (outcome, shots) = match announce(&mut a, &mut b).await {
+            Ok(()) => relay_observed(&mut a, &mut b, || metrics.record_move(id)).await,
+            Err((player, e)) => (
+                MatchOutcome::Abandoned {
+                    player,
+                    reason: e.to_string(),
+                },
+                [0, 0],
+            ),
+        };
+        tracing::info!(id, outcome = ?outcome, shots = ?shots, "match finished");
+        let finished = !matches!(outcome, MatchOutcome::Abandoned { .. });
+        if !finished {
+            // Abandoned games always end on a failed send or receive
+            metrics.record_disconnect();
+            metrics.record_transport_error();
+        }
+        metrics.game_ended(id, finished);
+        let _ = results.send(MatchRecord {
+            id,
+            players: [peer_a, peer_b],
+            shots,
+            outcome,
+        });
+    });
+}
+
+/// Tell both players their side. `Paired` reads the same in every supported
+/// version, so it goes out at the oldest one, which every client accepts.
+async fn announce<A: Transport, B: Transport>(a: &mut A, b: &mut B) -> Result<(), (usize, anyhow::Error)> {
+    let version = *SUPPORTED_VERSIONS.start();
+    a.send(Message::Paired {
+        version,
+        role: Role::Initiator,
+    })
+    .await
+    .map_err(|e| (0, e))?;
+    b.send(Message::Paired {
+        version,
+        role: Role::Responder,
+    })
+    .await
+    .map_err(|e| (1, e))
+}
+
+/// Relay messages between two paired players until the game is decided or
+/// a player drops. Returns the outcome and the guesses sent by each side.
+///
+/// A player's ship is counted as sunk when that player answers a guess, or
+/// one shot of a salvo, with a `Sink` result; the relay does not check the
+/// game rules itself, and takes the fleet size and move limit from the
+/// initiator's `StartGame`. A `Resign`, or a `TurnTimeout` forfeiting the
+/// turn, hands the other player the win; crossed resignations draw. Once
+/// the game is decided the relay carries on until both players have sent
+/// `GameOver`, if the version agreed in their handshake has it, and a
+/// player dropping then no longer changes the outcome.
+pub async fn relay<A: Transport, B: Transport>(a: &mut A, b: &mut B) -> (MatchOutcome, [u32; 2]) {
+    relay_observed(a, b, || {}).await
+}
+
+/// [`relay`], calling `on_guess` for every guess passed on.
+async fn relay_observed<A: Transport, B: Transport>(
+    a: &mut A,
+    b: &mut B,
+    mut on_guess: impl FnMut(),
+) -> (MatchOutcome, [u32; 2]) {
+    let mut shots = [0u32; 2];
+    // Distinct cells each player fired at, for the move limit
+    let mut targets = [HashSet::new(), HashSet::new()];
+    let mut sunk = [0usize; 2];
+    let mut resigned = [false; 2];
+    let mut reports = 0;
+    // Ships per fleet and move limit, once the initiator's `StartGame` has
+    // set the rules
+    let mut fleet_size = None;
+    let mut move_limit = None;
+    let mut decided = None;
+    let outcome = loop {
+        let (from, msg) = tokio::select! {
+            msg = a.recv() => (0, msg),
+            msg = b.recv() => (1, msg),
+        };
+        let to = 1 - from;
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(e) => match decided {
+                Some(outcome) => break outcome,
+                None => {
+                    let outcome = MatchOutcome::Abandoned {
+                        player: from,
+                        reason: e.to_string(),
+                    };
+                    return (outcome, shots);
+                }
+            },
+        };
+        let mut answered = false;
+        match &msg {
+            Message::Heartbeat { .. } => continue,
+            Message::Guess { target, .. } => {
+                shots[from] += 1;
+                targets[from].insert(*target);
+                on_guess();
+            }
+            Message::GuessSalvo { shots: salvo, .. } => {
+                for &(row, col) in salvo {
+                    shots[from] += 1;
+                    targets[from].insert(Coord::new(row as usize, col as usize));
+                    on_guess();
+                }
+            }
+            Message::StatusResp { res, .. } => {
+                sunk[from] += matches!(res, GuessResult::Sink(_)) as usize;
+                answered = true;
+            }
+            Message::SalvoResp { results, .. } => {
+                sunk[from] += results.iter().filter(|res| matches!(res, GuessResult::Sink(_))).count();
+                answered = true;
+            }
+            // Sent in place of an answer when `to` ran out of time: either
+            // it forfeits or shots were fired for it
+            Message::TurnTimeout { shots: fired, results, .. } => {
+                if fired.is_empty() {
+                    resigned[to] = true;
+                }
+                for &(row, col) in fired {
+                    shots[to] += 1;
+                    targets[to].insert(Coord::new(row as usize, col as usize));
+                }
+                sunk[from] += results.iter().filter(|res| matches!(res, GuessResult::Sink(_))).count();
+                answered = true;
+            }
+            Message::Resign { .. } => resigned[from] = true,
+            Message::Rules { config, .. } | Message::StartGame { config, .. } => {
+                fleet_size = Some(config.ships.len());
+                move_limit = config.move_limit;
+            }
+            Message::GameOver { .. } => reports += 1,
+            _ => {}
+        }
+
+        let sent = if from == 0 { b.send(msg).await } else { a.send(msg).await };
+        if let Err(e) = sent {
+            if let Some(outcome) = decided {
+                break outcome;
+            }
+            let outcome = MatchOutcome::Abandoned {
+                player: to,
+                reason: e.to_string(),
+            };
+            return (outcome, shots);
+        }
+        if resigned == [true; 2] {
+            // Resignations that crossed on the wire
+            decided = Some(MatchOutcome::Drawn);
+        } else if decided.is_none() {
+            if let Some(player) = resigned.iter().position(|&r| r) {
+                decided = Some(MatchOutcome::Finished { winner: 1 - player });
+            } else if fleet_size.is_some_and(|size| sunk[from] >= size) {
+                decided = Some(MatchOutcome::Finished { winner: to });
+            } else if answered && move_limit.is_some_and(|limit| targets[0].len().min(targets[1].len()) >= limit as usize)
+            {
+                decided = Some(MatchOutcome::Drawn);
+            }
+        }
+        match decided {
+            Some(outcome) if reports == 2 => break outcome,
+            _ => {}
+        }
+    };
+    (outcome, shots)
+}
+
+/// Accept players on `listener` forever, pairing them in arrival order and
+/// sending each match result to `results`.
+pub async fn serve(
+    listener: TcpListener,
+    config: DaemonConfig,
+    results: mpsc::UnboundedSender<MatchRecord>,
+) -> anyhow::Result<()> {
+    let mut matchmaker = Matchmaker::new(results).with_metrics(config.metrics.clone());
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to accept connection");
+                continue;
+            }
+        };
+        tracing::info!(peer = %addr, waiting = matchmaker.waiting(), "player connected");
+        matchmaker.enqueue(addr.to_string(), player_transport(stream, &config)).await;
+    }
+}
+
+fn player_transport(stream: TcpStream, config: &DaemonConfig) -> TcpTransport {
+    TcpTransport::with_config(
+        stream,
+        config.idle_timeout,
+        tcp::MAX_MESSAGE_SIZE,
+        tcp::DEFAULT_HEARTBEAT_INTERVAL,
+        config.idle_timeout,
+    )
+}
+
+/// Lobby state shared by every connection of [`serve_lobby`].
+struct Hub<T> {
+    lobby: Lobby,
+    gate: LobbyGate,
+    /// Hand-off to the task hosting each open game: the joining connection
+    /// sends its transport here and the host starts the match.
+    seats: HashMap<GameId, oneshot::Sender<(String, T)>>,
+    events: broadcast::Sender<LobbyEvent>,
+}
+
+impl<T> Hub<T> {
+    fn publish(&self, events: Vec<LobbyEvent>) {
+        for event in events {
+            // No subscribers only means nobody is connected
+            let _ = self.events.send(event);
+        }
+    }
+}
+
+/// Accept players on `listener` and serve the lobby. Clients list, create
+/// and join games; once a game has its second player both are sent
+/// `Paired` (the host initiates) and the game is relayed like a FIFO match,
+/// recorded under the lobby game id. Requests pass the configured
+/// authentication and rate limit first.
+pub async fn serve_lobby(
+    listener: TcpListener,
+    config: DaemonConfig,
+    results: mpsc::UnboundedSender<MatchRecord>,
+) -> anyhow::Result<()> {
+    let (events, _) = broadcast::channel(LOBBY_EVENT_CAPACITY);
+    let hub = Arc::new(Mutex::new(Hub {
+        lobby: Lobby::new(),
+        gate: LobbyGate::new(config.auth.clone(), config.rate_limit),
+        seats: HashMap::new(),
+        events,
+    }));
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to accept connection");
+                continue;
+            }
+        };
+        tracing::info!(peer = %addr, "lobby client connected");
+        let transport = player_transport(stream, &config);
+        tokio::spawn(lobby_session(
+            addr.to_string(),
+            transport,
+            hub.clone(),
+            results.clone(),
+            config.metrics.clone(),
+        ));
+    }
+}
+
+/// Serve one lobby client until it disconnects or its game starts.
+async fn lobby_session<T: Transport + 'static>(
+    peer: String,
+    mut transport: T,
+    hub: Arc<Mutex<Hub<T>>>,
+    results: mpsc::UnboundedSender<MatchRecord>,
+    metrics: Arc<Metrics>,
+) {
+    let Ok(mut events) = hub.lock().map(|h| h.events.subscribe()) else { return };
+    // Game we host and the receiver a joiner's transport arrives on
+    let mut seat: Option<(GameId, oneshot::Receiver<(String, T)>)> = None;
+    let mut identity: Option<String> = None;
+    // Version of the client's last request; until then the oldest, which
+    // every client can read
+    let mut version = *SUPPORTED_VERSIONS.start();
+
+    loop {
+        tokio::select! {
+            joiner = async {
+                match &mut seat {
+                    Some((_, rx)) => rx.await.ok(),
+                    None => std::future::pending().await,
+                }
+            } => {
+                match (joiner, seat.take()) {
+                    (Some(joiner), Some((id, _))) => {
+                        spawn_match(id, (peer, transport), joiner, results, metrics);
+                        return;
+                    }
+                    // Seat withdrawn without a joiner
+                    _ => continue,
+                }
+            }
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if send_lobby(&mut transport, version, LobbyMessage::Event(event)).await.is_err() {
+                        break;
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(missed)) => {
+                    tracing::warn!(peer = %peer, missed, "lobby client missed events");
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            msg = transport.recv() => {
+                let msg = match msg {
+                    Ok(Message::Heartbeat { .. }) => continue,
+                    Ok(Message::Lobby { version: sent, msg }) if SUPPORTED_VERSIONS.contains(&sent) => {
+                        version = sent;
+                        msg
+                    }
+                    Ok(other) => {
+                        tracing::warn!(peer = %peer, message = ?other, "unexpected message in lobby");
+                        break;
+                    }
+                    Err(e) => {
+                        tracing::info!(peer = %peer, error = %e, "lobby client disconnected");
+                        metrics.record_transport_error();
+                        break;
+                    }
+                };
+
+                let (reply, host_seat) = {
+                    let Ok(mut hub) = hub.lock() else { break };
+                    if let Some(reply) = hub.gate.check(&peer, &mut identity, &msg, std::time::Instant::now()) {
+                        (reply, None)
+                    } else {
+                        let Some(outcome) = hub.lobby.handle(&peer, msg) else { continue };
+                        let mut host_seat = None;
+                        match &outcome.reply {
+                            LobbyMessage::Reply { result: Ok(LobbyReply::Created(id)), .. } => {
+                                let (tx, rx) = oneshot::channel();
+                                hub.seats.insert(*id, tx);
+                                seat = Some((*id, rx));
+                            }
+                            LobbyMessage::Reply { result: Ok(LobbyReply::Left(id)), .. } => {
+                                hub.seats.remove(id);
+                                seat = None;
+                            }
+                            _ => {}
+                        }
+                        if let Some((id, _)) = &outcome.matched {
+                            host_seat = hub.seats.remove(id).map(|tx| (*id, tx));
+                        }
+                        hub.publish(outcome.events);
+                        (outcome.reply, host_seat)
+                    }
+                };
+                if send_lobby(&mut transport, version, reply).await.is_err() {
+                    break;
+                }
+                if let Some((id, tx)) = host_seat {
+                    match tx.send((peer.clone(), transport)) {
+                        Ok(()) => return,
+                        Err((_, returned)) => {
+                            // Host vanished after the join was accepted
+                            transport = returned;
+                            let msg = LobbyMessage::Event(LobbyEvent::GameClosed(id));
+                            if send_lobby(&mut transport, version, msg).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    if let Ok(mut hub) = hub.lock() {
+        if let Some(id) = hub.lobby.hosted_by(&peer) {
+            hub.seats.remove(&id);
+        }
+        let events = hub.lobby.disconnect(&peer);
+        hub.publish(events);
+    }
+}
+
+/// Send `msg` to a client on protocol `version`.
+async fn send_lobby<T: Transport>(transport: &mut T, version: u8, msg: LobbyMessage) -> anyhow::Result<()> {
+    transport.send(Message::Lobby { version, msg }).await
+}
+
+/// Client side of matchmaking: wait in the daemon's queue and return the
+/// role assigned once an opponent is found.
+///
+/// Use a heartbeat-enabled transport; the daemon drops silent players.
+pub async fn wait_for_opponent(transport: &mut dyn Transport) -> anyhow::Result<Role> {
+    loop {
+        match transport.recv().await? {
+            Message::Paired { version, role } if SUPPORTED_VERSIONS.contains(&version) => return Ok(role),
+            Message::Paired { version, .. } => {
+                return Err(anyhow!(
+                    "Protocol version mismatch: expected {}..={}, got {}",
+                    SUPPORTED_VERSIONS.start(),
+                    SUPPORTED_VERSIONS.end(),
+                    version
+                ))
+            }
+            // Lobby events keep coming until the game starts
+            Message::Heartbeat { .. } | Message::Lobby { .. } => {}
+            other => return Err(anyhow!("Expected Paired from matchmaking daemon, got {:?}", other)),
+        }
+    }
+}
@@ -1,4 +1,4 @@
-#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(not(any(feature = "std", feature = "wasm")), no_std)]
 
 #[cfg(not(feature = "std"))]
 extern crate alloc;
@@ -11,20 +11,25 @@ extern crate std;
 pub mod core;
 
 // Convenience re-exports of core types
+#[cfg(feature = "std")]
+pub use core::{json::JSON_SCHEMA_VERSION, replay::Replay};
+pub use core::perf;
 pub use core::{
-    ai::*, bitboard::*, board::*, common::*, config::*, game::*, ship::*,
+    ai::*, bitboard::*, bitboard_wide::*, bitgrid::*, board::*, common::*, config::*, coord::*, delta::*, endgame::*, events::*, fixed::*, game::*, history::*, info_gain::*, opponent::*, opponent_model::*, pdf_cache::*, placement::*, ship::*, targeting::*,
 };
 
 // ========================================
 // Layer 2: Player & Transport
 // ========================================
 pub mod player;
-pub use player::{AiPlayer, Player};
+pub use player::{AiDebugState, AiPlayer, Candidate, Explanation, MaskConstraints, Player, TargetPhase, EXPLAIN_TOP};
+#[cfg(feature = "debug-tools")]
+pub use player::CheatingAiPlayer;
 
 #[cfg(feature = "std")]
-pub use player::{CliPlayer, PlayerNode};
+pub use player::{CliPlayer, PlayerNode, SeriesResult};
 
-#[cfg(feature = "std")]
+#[cfg(any(feature = "std", feature = "wasm"))]
 pub mod transport;
 
 #[cfg(feature = "std")]
@@ -32,17 +37,44 @@ pub use transport::tcp::TcpTransport;
 #[cfg(feature = "std")]
 pub use transport::heartbeat::HeartbeatTransport;
 
+#[cfg(feature = "std")]
+pub mod arena;
+
+#[cfg(feature = "std")]
+pub use arena::{run_match, GameRecord, MatchReport};
+
 // ========================================
 // Layer 3: Protocol & RPC
 // ========================================
-#[cfg(feature = "std")]
+#[cfg(any(feature = "std", feature = "wasm"))]
 pub mod protocol;
 
 #[cfg(feature = "std")]
-pub use protocol::{domain, GameApi, Message, Skeleton, Stub, PROTOCOL_VERSION};
+pub use protocol::{domain, GameApi, Message, ProtocolViolation, Role, Skeleton, Stub, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
 
 // ========================================
 // Layer 4: CLI
 // ========================================
 #[cfg(feature = "std")]
 pub mod cli;
+
+// ========================================
+// Diagnostics
+// ========================================
+#[cfg(feature = "std")]
+pub mod logging;
+
+#[cfg(feature = "std")]
+pub use logging::{init_logging, LogConfig, LogGuard};
+
+#[cfg(feature = "std")]
+pub mod metrics;
+
+#[cfg(feature = "std")]
+pub mod notify;
+
+// ========================================
+// Matchmaking
+// ========================================
+#[cfg(feature = "std")]
+pub mod daemon;
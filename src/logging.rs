@@ -0,0 +1,153 @@
+//! Process-wide diagnostic logging built on `tracing`.
+//!
+//! Binaries call [`init_logging`] once at startup with a [`LogConfig`] and
+//! keep the returned [`LogGuard`] alive until exit; dropping it flushes any
+//! buffered file output.
+
+#![cfg(feature = "std")]
+
+use anyhow::anyhow;
+use std::path::PathBuf;
+use std::str::FromStr;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer, Registry};
+
+type BoxedLayer = Box<dyn Layer<Registry> + Send + Sync>;
+
+/// How often a log file is rolled over.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LogRotation {
+    #[default]
+    Never,
+    Hourly,
+    Daily,
+}
+
+/// Log file destination: `directory/prefix`, plus a date suffix when rotating.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogFile {
+    pub directory: PathBuf,
+    pub prefix: String,
+    pub rotation: LogRotation,
+}
+
+/// Logging setup chosen by the binary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogConfig {
+    /// Default level for every target (`error`, `warn`, `info`, `debug`, `trace`).
+    pub level: String,
+    /// Per-module overrides, e.g. `("battleship::transport", "debug")`.
+    pub module_levels: Vec<(String, String)>,
+    /// Emit one JSON object per event instead of human-readable lines.
+    pub json: bool,
+    /// Also write events to a (possibly rotating) file.
+    pub file: Option<LogFile>,
+    /// Keep stderr clear for an interactive session: only errors reach it.
+    /// File output, if configured, still uses the configured levels.
+    pub quiet: bool,
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        Self {
+            level: "info".to_string(),
+            module_levels: Vec::new(),
+            json: false,
+            file: None,
+            quiet: false,
+        }
+    }
+}
+
+impl LogConfig {
+    /// Config for interactive terminal play: errors only on stderr.
+    pub fn quiet() -> Self {
+        Self {
+            quiet: true,
+            ..Self::default()
+        }
+    }
+
+    /// Adds a level override for one module path.
+    pub fn with_module_level(mut self, module: impl Into<String>, level: impl Into<String>) -> Self {
+        self.module_levels.push((module.into(), level.into()));
+        self
+    }
+
+    /// `EnvFilter` directive string for this config, e.g. `info,battleship::ai=debug`.
+    pub fn filter_directives(&self) -> String {
+        let mut directives = self.level.clone();
+        for (module, level) in &self.module_levels {
+            directives.push(',');
+            directives.push_str(module);
+            directives.push('=');
+            directives.push_str(level);
+        }
+        directives
+    }
+
+    fn filter(&self) -> anyhow::Result<EnvFilter> {
+        let levels = core::iter::once(&self.level).chain(self.module_levels.iter().map(|(_, l)| l));
+        for level in levels {
+            LevelFilter::from_str(level).map_err(|_| anyhow!("Invalid log level {:?}", level))?;
+        }
+        EnvFilter::try_new(self.filter_directives())
+            .map_err(|e| anyhow!("Invalid log filter {:?}: {}", self.filter_directives(), e))
+    }
+}
+
+/// Keeps background log writers alive; drop it last to flush file output.
+#[must_use = "dropping the guard stops file logging"]
+pub struct LogGuard {
+    _file: Option<WorkerGuard>,
+}
+
+/// Installs the global `tracing` subscriber described by `config`.
+///
+/// Fails if the filter does not parse or a global subscriber is already set.
+pub fn init_logging(config: LogConfig) -> anyhow::Result<LogGuard> {
+    let filter = config.filter()?;
+    let stderr_filter = if config.quiet { EnvFilter::new("error") } else { filter };
+    let stderr_layer: BoxedLayer = if config.json {
+        tracing_subscriber::fmt::layer()
+            .json()
+            .with_writer(std::io::stderr)
+            .with_filter(stderr_filter)
+            .boxed()
+    } else {
+        tracing_subscriber::fmt::layer()
+            .with_writer(std::io::stderr)
+            .with_filter(stderr_filter)
+            .boxed()
+    };
+
+    let mut layers = vec![stderr_layer];
+    let mut file_guard = None;
+    if let Some(file) = &config.file {
+        let rotation = match file.rotation {
+            LogRotation::Never => Rotation::NEVER,
+            LogRotation::Hourly => Rotation::HOURLY,
+            LogRotation::Daily => Rotation::DAILY,
+        };
+        let appender = RollingFileAppender::new(rotation, &file.directory, &file.prefix);
+        let (writer, guard) = tracing_appender::non_blocking(appender);
+        file_guard = Some(guard);
+        let layer = tracing_subscriber::fmt::layer().with_writer(writer).with_ansi(false);
+        let filter = config.filter()?;
+        layers.push(if config.json {
+            layer.json().with_filter(filter).boxed()
+        } else {
+            layer.with_filter(filter).boxed()
+        });
+    }
+
+    tracing_subscriber::registry()
+        .with(layers)
+        .try_init()
+        .map_err(|e| anyhow!("Logging already initialised: {}", e))?;
+    Ok(LogGuard { _file: file_guard })
+}
@@ -3,19 +3,26 @@ fn main() {}
 
 #[cfg(feature = "std")]
 use battleship::{
-    calc_pdf, ship_name_static,
-    player::cli::{print_player_view, print_probability_board},
+    calc_pdf, daemon, protocol::{coin_flip, commitment::{self, LayoutCommitment}, handshake, setup::{self, SetupConfig}, validation},
+    core::replay,
+    player::cli::{
+        print_defense_report, print_fleet_board, print_fleets_side_by_side, print_opponent_fleet, print_player_view,
+        print_probability_board, print_stats,
+    },
     transport::in_memory::InMemoryTransport, transport::tcp::TcpTransport,
-    HeartbeatTransport, AiPlayer, CliPlayer, GameEngine, GameStatus, Player, PlayerNode,
-    PROTOCOL_VERSION,
+    logging::{LogFile, LogRotation},
+    notify::{self, Notifications, TerminalBell},
+    protocol::{domain::GameConfig, GameId},
+    run_match, Coord, EventListener, GameEvent, HeartbeatTransport, AiConfig, AiPlayer, Stub, CliPlayer, GameEngine, GameStatus, LogConfig, Player, PlayerNode, Replay, Role,
+    PlacementStyle, TargetingStyle,
 };
 
 #[cfg(feature = "std")]
-use clap::{Parser, ValueEnum};
+use clap::{Parser, Subcommand, ValueEnum};
 #[cfg(feature = "std")]
 use rand::rngs::SmallRng;
 #[cfg(feature = "std")]
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
 #[cfg(feature = "std")]
 use tokio::net::TcpListener;
 #[cfg(feature = "std")]
@@ -27,6 +34,12 @@ use tokio::time::Duration;
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+    #[arg(long, global = true, default_value = "info", help = "Log level, optionally with module overrides (e.g. info,battleship::transport=debug)")]
+    log_level: String,
+    #[arg(long, global = true, help = "Emit logs as JSON lines")]
+    log_json: bool,
+    #[arg(long, global = true, help = "Also write logs to daily-rotated files in this directory")]
+    log_dir: Option<std::path::PathBuf>,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -36,6 +49,29 @@ enum PlayerType {
     Ai,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug)]
+#[cfg(feature = "std")]
+enum Targeting {
+    Random,
+    ParityHunt,
+    Density,
+    MonteCarlo,
+    InfoGain,
+}
+
+#[cfg(feature = "std")]
+impl From<Targeting> for TargetingStyle {
+    fn from(targeting: Targeting) -> Self {
+        match targeting {
+            Targeting::Random => TargetingStyle::Random,
+            Targeting::ParityHunt => TargetingStyle::ParityHunt,
+            Targeting::Density => TargetingStyle::Density,
+            Targeting::MonteCarlo => TargetingStyle::MonteCarlo,
+            Targeting::InfoGain => TargetingStyle::InfoGain,
+        }
+    }
+}
+
 #[derive(Parser)]
 #[cfg(feature = "std")]
 enum Commands {
@@ -43,6 +79,38 @@ enum Commands {
     Local {
         #[arg(long, help = "Fix RNG seed for reproducible games (e.g., --seed 12345)")]
         seed: Option<u64>,
+        #[arg(long, help = "Save the game as a replay file for `battleship replay`")]
+        record: Option<std::path::PathBuf>,
+        #[arg(long, help = "AIs always shoot their most probable cell instead of sampling")]
+        deterministic: bool,
+    },
+    /// Watch two AIs play each other on animated boards.
+    Demo {
+        #[arg(long, help = "Fix RNG seed for reproducible games (e.g., --seed 12345)")]
+        seed: Option<u64>,
+        #[arg(long, default_value_t = 300, help = "Milliseconds to pause after each move")]
+        delay_ms: u64,
+        #[arg(long, help = "Print each move below the last instead of redrawing the screen")]
+        no_clear: bool,
+        #[arg(long, help = "AIs always shoot their most probable cell instead of sampling")]
+        deterministic: bool,
+    },
+    /// Step through a recorded game move by move.
+    Replay {
+        /// Replay file written by `local --record`.
+        file: std::path::PathBuf,
+    },
+    /// Pit two AI targeting strategies against each other and report how
+    /// they fared.
+    Arena {
+        #[arg(long, value_enum, default_value_t = Targeting::Density)]
+        a: Targeting,
+        #[arg(long, value_enum, default_value_t = Targeting::Random)]
+        b: Targeting,
+        #[arg(long, default_value_t = 100, help = "Games to play, alternating who fires first")]
+        games: u32,
+        #[arg(long, help = "Fix RNG seed for reproducible matches (e.g., --seed 12345)")]
+        seed: Option<u64>,
     },
     /// Host a networked game and wait for a client to connect.
     TcpServer {
@@ -52,6 +120,10 @@ enum Commands {
         player: PlayerType,
         #[arg(long, help = "Fix RNG seed for reproducible games (e.g., --seed 12345)")]
         seed: Option<u64>,
+        #[arg(long, default_value_t = 300, help = "Seconds allowed for both fleets to be placed")]
+        setup_timeout: u64,
+        #[arg(long, help = "Ring the terminal bell on your turn, sunk ships and game over")]
+        bell: bool,
     },
     /// Connect to a networked game hosted by a server.
     TcpClient {
@@ -61,16 +133,76 @@ enum Commands {
         player: PlayerType,
         #[arg(long, help = "Fix RNG seed for reproducible games (e.g., --seed 12345)")]
         seed: Option<u64>,
+        #[arg(long, default_value_t = 300, help = "Seconds allowed for both fleets to be placed")]
+        setup_timeout: u64,
+        #[arg(long, help = "Ring the terminal bell on your turn, sunk ships and game over")]
+        bell: bool,
+        #[arg(long, help = "Server is a battleship-daemon: wait in its queue for an opponent")]
+        matchmaking: bool,
     },
+    /// Browse, host or join games on a battleship-daemon started with --lobby.
+    Lobby {
+        #[arg(long, default_value = "127.0.0.1:9090")]
+        connect: String,
+        #[arg(long, value_enum, default_value_t = PlayerType::Human)]
+        player: PlayerType,
+        #[arg(long, help = "Fix RNG seed for reproducible games (e.g., --seed 12345)")]
+        seed: Option<u64>,
+        #[arg(long, default_value_t = 300, help = "Seconds allowed for both fleets to be placed")]
+        setup_timeout: u64,
+        #[arg(long, help = "Ring the terminal bell on your turn, sunk ships and game over")]
+        bell: bool,
+        #[arg(long, help = "Token or signed ticket for lobbies that require authentication")]
+        auth: Option<String>,
+        #[command(subcommand)]
+        action: LobbyAction,
+    },
+}
+
+#[derive(Subcommand)]
+#[cfg(feature = "std")]
+enum LobbyAction {
+    /// List games waiting for an opponent.
+    List,
+    /// Host a game and wait for someone to join.
+    Create,
+    /// Join an open game by id.
+    Join { id: GameId },
 }
 
 #[cfg(feature = "std")]
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
+    // Human players get a quiet stderr so logs don't interleave with the board
+    let interactive = matches!(
+        cli.command,
+        Commands::TcpServer { player: PlayerType::Human, .. }
+            | Commands::TcpClient { player: PlayerType::Human, .. }
+            | Commands::Lobby { player: PlayerType::Human, .. }
+    );
+    let mut directives = cli.log_level.split(',');
+    let level = directives.next().unwrap_or("info").to_string();
+    let module_levels = directives
+        .map(|d| match d.split_once('=') {
+            Some((module, level)) => Ok((module.to_string(), level.to_string())),
+            None => Err(anyhow::anyhow!("Invalid --log-level override {:?}: expected module=level", d)),
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let _log_guard = battleship::init_logging(LogConfig {
+        level,
+        module_levels,
+        json: cli.log_json,
+        file: cli.log_dir.clone().map(|directory| LogFile {
+            directory,
+            prefix: "battleship.log".to_string(),
+            rotation: LogRotation::Daily,
+        }),
+        quiet: interactive,
+    })?;
 
     match cli.command {
-        Commands::Local { seed } => {
+        Commands::Local { seed, record, deterministic } => {
             println!("Starting local AI vs AI game...");
             if let Some(s) = seed {
                 println!("Using fixed seed: {} (game will be reproducible)", s);
@@ -88,33 +220,75 @@ async fn main() -> anyhow::Result<()> {
                 SmallRng::from_rng(&mut seed_rng)
             };
 
-            let mut ai1 = AiPlayer::new();
-            let mut ai2 = AiPlayer::new();
-            let mut engine1 = GameEngine::new();
-            let mut engine2 = GameEngine::new();
-
-            ai1.place_ships(&mut rng1, engine1.board_mut())
-                .map_err(|e| anyhow::anyhow!(e))?;
-            ai2.place_ships(&mut rng2, engine2.board_mut())
-                .map_err(|e| anyhow::anyhow!(e))?;
+            let ai_config = AiConfig { deterministic, ..AiConfig::default() };
+            let ai1 = AiPlayer::with_config(ai_config);
+            let ai2 = AiPlayer::with_config(ai_config);
+            let engine1 = GameEngine::new();
+            let engine2 = GameEngine::new();
 
             let (t1, t2) = InMemoryTransport::pair();
             let transport1 = Box::new(HeartbeatTransport::disabled(t1));
             let transport2 = Box::new(HeartbeatTransport::disabled(t2));
 
-            let ai1_future = async move {
-                let mut node = PlayerNode::new(Box::new(ai1), engine1, transport1);
-                node.run(&mut rng1, true).await
-            };
+            let mut node1 = PlayerNode::new(Box::new(ai1), engine1, transport1);
+            let mut node2 = PlayerNode::new(Box::new(ai2), engine2, transport2);
+            let shots = record.is_some().then(|| {
+                (
+                    tokio::spawn(replay::shots_fired(node1.subscribe())),
+                    tokio::spawn(replay::shots_fired(node2.subscribe())),
+                )
+            });
 
-            let ai2_future = async move {
-                let mut node = PlayerNode::new(Box::new(ai2), engine2, transport2);
-                node.run(&mut rng2, false).await
-            };
+            tokio::try_join!(
+                node1.run(&mut rng1, Role::Initiator),
+                node2.run(&mut rng2, Role::Responder)
+            )?;
 
-            tokio::try_join!(ai1_future, ai2_future)?;
+            if let (Some(path), Some((shots1, shots2))) = (record, shots) {
+                let first = if node1.first_move() == Some(true) { 0 } else { 1 };
+                let recording = Replay::new([node1.engine().board(), node2.engine().board()]);
+                // The shot collectors finish once the nodes stop publishing
+                drop((node1, node2));
+                let recording = recording.with_shots(first, [shots1.await??, shots2.await??]);
+                recording.save(&path)?;
+                println!("Replay saved to {} ({} moves)", path.display(), recording.len());
+            }
+        }
+        Commands::Demo { seed, delay_ms, no_clear, deterministic } => {
+            let ai_config = AiConfig { deterministic, ..AiConfig::default() };
+            run_demo(seed, Duration::from_millis(delay_ms), !no_clear, ai_config).await?;
         }
-        Commands::TcpServer { bind, player, seed } => {
+        Commands::Replay { file } => {
+            let recording = Replay::load(&file)?;
+            view_replay(&recording)?;
+        }
+        Commands::Arena { a, b, games, seed } => {
+            let seed = seed.unwrap_or_else(|| rand::rng().random());
+            let mut player_a = AiPlayer::with_strategies(a.into(), PlacementStyle::default());
+            let mut player_b = AiPlayer::with_strategies(b.into(), PlacementStyle::default());
+            let report = run_match(&mut player_a, &mut player_b, games, seed)?;
+            println!("{} games, seed {}", report.games.len(), seed);
+            for (side, targeting) in [(0, a), (1, b)] {
+                let (low, high) = report.win_rate_interval(side);
+                let shots = report.average_shots(side).map_or("-".to_string(), |s| format!("{:.2}", s));
+                println!(
+                    "{:?}: {} wins ({:.1}%, 95% CI {:.1}-{:.1}%), {} shots on average",
+                    targeting,
+                    report.wins(side),
+                    100.0 * report.win_rate(side),
+                    100.0 * low,
+                    100.0 * high,
+                    shots,
+                );
+            }
+        }
+        Commands::TcpServer {
+            bind,
+            player,
+            seed,
+            setup_timeout,
+            bell,
+        } => {
             println!("Starting TCP server at {}...", bind);
             if let Some(s) = seed {
                 println!("Using fixed seed: {} (game will be reproducible)", s);
@@ -129,142 +303,173 @@ async fn main() -> anyhow::Result<()> {
                 Duration::from_secs(10),
                 Duration::from_secs(45),
             ));
-            let mut rng = if let Some(s) = seed {
-                SmallRng::seed_from_u64(s)
-            } else {
-                let mut seed_rng = rand::rng();
-                SmallRng::from_rng(&mut seed_rng)
-            };
-            let mut engine = GameEngine::new();
-
-            match player {
-                PlayerType::Human => {
-                    let mut cli_player = CliPlayer::new();
-                    cli_player
-                        .place_ships(&mut rng, engine.board_mut())
-                        .map_err(|e| anyhow::anyhow!(e))?;
-                    let game_future = run_cli(cli_player, engine, transport, rng, true);
-                    if let Err(e) = game_future.await {
-                        eprintln!("Game ended with an error: {}", e);
-                    }
-                }
-                PlayerType::Ai => {
-                    println!("AI player selected.");
-                    let mut ai_player = AiPlayer::new();
-                    ai_player
-                        .place_ships(&mut rng, engine.board_mut())
-                        .map_err(|e| anyhow::anyhow!(e))?;
-                    let mut node = PlayerNode::new(Box::new(ai_player), engine, transport);
-                    let game_future = node.run(&mut rng, true);
-                    if let Err(e) = game_future.await {
-                        eprintln!("Game ended with an error: {}", e);
-                    }
-                }
-            }
+            play_networked(transport, player, seed, setup_timeout, bell, Role::Initiator).await;
         }
-        Commands::TcpClient { connect, player, seed } => {
+        Commands::TcpClient {
+            connect,
+            player,
+            seed,
+            setup_timeout,
+            bell,
+            matchmaking,
+        } => {
             println!("Connecting to TCP server at {}...", connect);
             if let Some(s) = seed {
                 println!("Using fixed seed: {} (game will be reproducible)", s);
             }
             let tcp = TcpTransport::connect(&connect).await?;
-            let transport = Box::new(HeartbeatTransport::new(
+            let mut transport = Box::new(HeartbeatTransport::new(
                 tcp,
                 Duration::from_secs(10),
                 Duration::from_secs(45),
             ));
             println!("Connected successfully!");
-
-            let mut rng = if let Some(s) = seed {
-                SmallRng::seed_from_u64(s)
+            let role = if matchmaking {
+                println!("Waiting for an opponent...");
+                let role = daemon::wait_for_opponent(transport.as_mut()).await?;
+                println!("Opponent found.");
+                role
             } else {
-                let mut seed_rng = rand::rng();
-                SmallRng::from_rng(&mut seed_rng)
+                Role::Responder
             };
-            let mut engine = GameEngine::new();
 
-            match player {
-                PlayerType::Human => {
-                    let mut cli_player = CliPlayer::new();
-                    cli_player
-                        .place_ships(&mut rng, engine.board_mut())
-                        .map_err(|e| anyhow::anyhow!(e))?;
-                    let game_future = run_cli(cli_player, engine, transport, rng, false);
-                    if let Err(e) = game_future.await {
-                        eprintln!("Game ended with an error: {}", e);
+            play_networked(transport, player, seed, setup_timeout, bell, role).await;
+        }
+        Commands::Lobby {
+            connect,
+            player,
+            seed,
+            setup_timeout,
+            bell,
+            auth,
+            action,
+        } => {
+            let tcp = TcpTransport::connect(&connect).await?;
+            let stub = Stub::new(HeartbeatTransport::new(
+                tcp,
+                Duration::from_secs(10),
+                Duration::from_secs(45),
+            ));
+            if let Some(credential) = auth {
+                let identity = stub.authenticate(&credential).await?;
+                println!("Authenticated as {}", identity);
+            }
+            match action {
+                LobbyAction::List => {
+                    let games = stub.list_games().await?;
+                    if games.is_empty() {
+                        println!("No open games.");
                     }
-                }
-                PlayerType::Ai => {
-                    println!("AI player selected.");
-                    let mut ai_player = AiPlayer::new();
-                    ai_player
-                        .place_ships(&mut rng, engine.board_mut())
-                        .map_err(|e| anyhow::anyhow!(e))?;
-                    let mut node = PlayerNode::new(Box::new(ai_player), engine, transport);
-                    let game_future = node.run(&mut rng, false);
-                    if let Err(e) = game_future.await {
-                        eprintln!("Game ended with an error: {}", e);
+                    for game in games {
+                        println!("Game {} hosted by {}", game.id, game.host);
                     }
+                    return Ok(());
+                }
+                LobbyAction::Create => {
+                    let id = stub.create_game(GameConfig::local()).await?;
+                    println!("Hosting game {}. Waiting for an opponent...", id);
+                }
+                LobbyAction::Join { id } => {
+                    stub.join_game(id).await?;
+                    println!("Joined game {}.", id);
                 }
             }
+            let mut transport = Box::new(stub.into_transport());
+            let role = daemon::wait_for_opponent(transport.as_mut()).await?;
+            println!("Opponent found.");
+            play_networked(transport, player, seed, setup_timeout, bell, role).await;
         }
     }
     Ok(())
 }
 
+/// Play one networked game as a human or AI over an established transport.
+#[cfg(feature = "std")]
+async fn play_networked(
+    transport: Box<dyn battleship::transport::Transport>,
+    player: PlayerType,
+    seed: Option<u64>,
+    setup_timeout: u64,
+    bell: bool,
+    role: Role,
+) {
+    let mut rng = if let Some(s) = seed {
+        SmallRng::seed_from_u64(s)
+    } else {
+        let mut seed_rng = rand::rng();
+        SmallRng::from_rng(&mut seed_rng)
+    };
+    let engine = GameEngine::new();
+    let setup_config = SetupConfig {
+        timeout: Duration::from_secs(setup_timeout),
+        ..SetupConfig::default()
+    };
+
+    match player {
+        PlayerType::Human => {
+            let cli_player = CliPlayer::new();
+            let mut notifications = Notifications::new(TerminalBell);
+            let mut silent = |_: &GameEvent| {};
+            let notify: &mut dyn EventListener = if bell { &mut notifications } else { &mut silent };
+            let game_future = run_cli(cli_player, engine, transport, rng, role, setup_config, notify);
+            if let Err(e) = game_future.await {
+                eprintln!("Game ended with an error: {}", e);
+            }
+        }
+        PlayerType::Ai => {
+            println!("AI player selected.");
+            let ai_player = AiPlayer::new();
+            let mut node = PlayerNode::new(Box::new(ai_player), engine, transport)
+                .with_setup_config(setup_config);
+            if bell {
+                tokio::spawn(notify::forward_events(node.subscribe(), Notifications::new(TerminalBell)));
+            }
+            let game_future = node.run(&mut rng, role);
+            if let Err(e) = game_future.await {
+                eprintln!("Game ended with an error: {}", e);
+            }
+        }
+    }
+}
+
 #[cfg(feature = "std")]
 async fn run_cli(
     mut player: CliPlayer,
     mut engine: GameEngine,
     mut transport: Box<dyn battleship::transport::Transport>,
     mut rng: SmallRng,
-    first_move: bool,
+    role: Role,
+    setup_config: SetupConfig,
+    notify: &mut dyn EventListener,
 ) -> anyhow::Result<()> {
-    // Perform handshake
+    // Settle on the newest protocol version both sides speak
+    let protocol_version = handshake::exchange_handshake(transport.as_mut(), role, &handshake::SUPPORTED_VERSIONS).await?;
+    let config = setup::agree_config(transport.as_mut(), protocol_version, &GameConfig::from(&engine)).await?;
+
+    let first_move = coin_flip::negotiate_first_move(transport.as_mut(), protocol_version, &mut rng, role).await?;
     if first_move {
-        // Send handshake
-        transport
-            .send(battleship::Message::Handshake {
-                version: PROTOCOL_VERSION,
-            })
-            .await?;
-        
-        // Wait for ack
-        let reply = transport.recv().await?;
-        match reply {
-            battleship::Message::HandshakeAck { version } if version == PROTOCOL_VERSION => {}
-            battleship::Message::HandshakeAck { version } => {
-                return Err(anyhow::anyhow!(
-                    "Protocol version mismatch: expected {}, got {}",
-                    PROTOCOL_VERSION,
-                    version
-                ));
-            }
-            _ => return Err(anyhow::anyhow!("Expected HandshakeAck")),
-        }
+        println!("Coin flip: you move first.");
     } else {
-        // Wait for handshake
-        let msg = transport.recv().await?;
-        match msg {
-            battleship::Message::Handshake { version } if version == PROTOCOL_VERSION => {
-                // Send ack
-                transport
-                    .send(battleship::Message::HandshakeAck {
-                        version: PROTOCOL_VERSION,
-                    })
-                    .await?;
-            }
-            battleship::Message::Handshake { version } => {
-                return Err(anyhow::anyhow!(
-                    "Protocol version mismatch: expected {}, got {}",
-                    PROTOCOL_VERSION,
-                    version
-                ));
-            }
-            _ => return Err(anyhow::anyhow!("Expected Handshake")),
-        }
+        println!("Coin flip: your opponent moves first.");
     }
-    
+
+    let deadline = tokio::time::Instant::now() + setup_config.timeout;
+    let mut board = std::mem::take(engine.board_mut());
+    let (mut player, board, mut rng, res) =
+        setup::run_placement(transport.as_mut(), protocol_version, &setup_config, deadline, move |cancel| {
+            let res = player.place_ships_cancellable(&mut rng, &mut board, &cancel);
+            (player, board, rng, res)
+        })
+        .await?;
+    res.map_err(|e| anyhow::anyhow!(e))?;
+    *engine.board_mut() = board;
+    println!("Fleet placed. Waiting for your opponent to finish placing...");
+    setup::exchange_ready(transport.as_mut(), protocol_version, &config, role, first_move, deadline)
+        .await?;
+    let layout = LayoutCommitment::new(&mut rng, engine.board());
+    let peer_digest = commitment::exchange_commitments(transport.as_mut(), protocol_version, role, layout.digest()).await?;
+    notify.on_event(&GameEvent::GameStarted { first_move });
+
     let mut my_turn = first_move;
     let mut my_seq: u64 = 0;
     let mut expected_recv_seq: u64 = 0;
@@ -276,41 +481,56 @@ async fn run_cli(
             print_player_view(&engine);
             let pdf = calc_pdf(
                 &engine.guess_hits(),
-                &engine.guess_misses(),
+                &engine.blocked_cells(),
                 &engine.enemy_ship_lengths_remaining(),
             );
-            print_probability_board(&pdf);
+            print_probability_board(&pdf, engine.rules().board_size());
 
-            let (r, c) = player.select_target(
+            let target = player.select_target(
                 &mut rng,
                 &engine.guess_hits(),
-                &engine.guess_misses(),
+                &engine.blocked_cells(),
                 &engine.enemy_ship_lengths_remaining(),
             );
+            if player.resigned() {
+                engine.resign();
+                transport
+                    .send(battleship::Message::Resign {
+                        version: protocol_version,
+                        seq: my_seq,
+                    })
+                    .await?;
+                match transport.recv().await? {
+                    battleship::Message::Ack { seq, .. } if seq == my_seq => {}
+                    _ => return Err(anyhow::anyhow!("unexpected reply to resignation")),
+                }
+                break;
+            }
             transport
                 .send(battleship::Message::Guess {
-                    version: PROTOCOL_VERSION,
+                    version: protocol_version,
                     seq: my_seq,
-                    x: r as u8,
-                    y: c as u8,
+                    target,
                 })
                 .await?;
+            let Coord { row: r, col: c } = target;
             let reply = transport.recv().await?;
-            let res_domain = match reply {
+            let (res_domain, digest) = match reply {
                 battleship::Message::StatusResp {
                     version,
                     seq: resp_seq,
                     res,
+                    digest,
                 } if resp_seq == my_seq => {
                     // Validate version
-                    if version != PROTOCOL_VERSION {
+                    if version != protocol_version {
                         return Err(anyhow::anyhow!(
                             "Protocol version mismatch in response: expected {}, got {}",
-                            PROTOCOL_VERSION,
+                            protocol_version,
                             version
                         ));
                     }
-                    res
+                    (res, digest)
                 }
                 battleship::Message::StatusResp {
                     seq: resp_seq, ..
@@ -324,20 +544,24 @@ async fn run_cli(
                 _ => return Err(anyhow::anyhow!("unexpected reply")),
             };
             my_seq += 1;
-            let res_common = match res_domain {
-                battleship::domain::GuessResult::Hit => battleship::GuessResult::Hit,
-                battleship::domain::GuessResult::Miss => battleship::GuessResult::Miss,
-                battleship::domain::GuessResult::Sink(name) => {
-                    let static_name =
-                        ship_name_static(&name).ok_or_else(|| anyhow::anyhow!("unknown ship"))?;
-                    battleship::GuessResult::Sink(static_name)
-                }
-            };
+            let res_common = validation::validate_result(&engine, &res_domain)
+                .map_err(|v| anyhow::anyhow!("Protocol violation: {} (closing session)", v))?;
             engine
                 .record_guess(r, c, res_common)
                 .map_err(|e| anyhow::anyhow!(e))?;
-            player.handle_guess_result((r, c), res_common);
-            my_turn = false;
+            if digest.is_some_and(|d| d != engine.state().mirrored_digest()) {
+                return Err(anyhow::anyhow!(
+                    "Game state desync: the opponent's board no longer matches ours (closing session)"
+                ));
+            }
+            player.handle_guess_result(target, res_common);
+            if let battleship::GuessResult::Sink(ship) = res_common {
+                notify.on_event(&GameEvent::Sink { ship, ours: false });
+            }
+            my_turn = engine.turn_policy().keeps_turn(res_common);
+            if my_turn && engine.status() == GameStatus::InProgress {
+                println!("Hit! You fire again.");
+            }
         } else {
             println!("Waiting for opponent's move...");
             let msg = transport.recv().await?;
@@ -345,14 +569,13 @@ async fn run_cli(
                 battleship::Message::Guess {
                     version,
                     seq: msg_seq,
-                    x,
-                    y,
+                    target,
                 } => {
                     // Validate version
-                    if version != PROTOCOL_VERSION {
+                    if version != protocol_version {
                         return Err(anyhow::anyhow!(
                             "Protocol version mismatch in guess: expected {}, got {}",
-                            PROTOCOL_VERSION,
+                            protocol_version,
                             version
                         ));
                     }
@@ -366,33 +589,76 @@ async fn run_cli(
                         ));
                     }
                     
+                    let Coord { row: gr, col: gc } = validation::validate_guess(&engine, target)
+                        .map_err(|v| anyhow::anyhow!("Protocol violation: {} (closing session)", v))?;
                     let res_common = engine
-                        .opponent_guess(x as usize, y as usize)
+                        .opponent_guess(gr, gc)
                         .map_err(|e| anyhow::anyhow!(e))?;
-                    player.handle_opponent_guess((x as usize, y as usize), res_common);
+                    player.handle_opponent_guess(target, res_common);
+                    notify.on_event(&GameEvent::OpponentGuess { row: gr, col: gc, result: res_common });
+                    if let battleship::GuessResult::Sink(ship) = res_common {
+                        notify.on_event(&GameEvent::Sink { ship, ours: true });
+                    }
                     let res_domain = battleship::domain::GuessResult::from(res_common);
                     transport
                         .send(battleship::Message::StatusResp {
-                            version: PROTOCOL_VERSION,
+                            version: protocol_version,
                             seq: msg_seq,
                             res: res_domain,
+                            digest: Some(engine.state().digest()),
                         })
                         .await?;
                     expected_recv_seq += 1;
+                    my_turn = !engine.turn_policy().keeps_turn(res_common);
+                }
+                battleship::Message::Resign { seq, .. } if seq == expected_recv_seq => {
+                    engine.record_resignation();
+                    println!("Opponent resigned the game.");
+                    transport
+                        .send(battleship::Message::Ack {
+                            version: protocol_version,
+                            seq,
+                        })
+                        .await?;
+                }
+                battleship::Message::Abort { seq, reason, .. } => {
+                    engine.abort();
+                    println!("Opponent aborted the game: {}", reason);
+                    transport
+                        .send(battleship::Message::Ack {
+                            version: protocol_version,
+                            seq,
+                        })
+                        .await?;
                 }
                 _ => return Err(anyhow::anyhow!("unexpected message received")),
             }
-            my_turn = true;
         }
 
         if !matches!(engine.status(), GameStatus::InProgress) {
             break;
         }
     }
+    let mut opponent = None;
+    if commitment::reveal_due(&engine) {
+        let (salt, peer_layout) = commitment::exchange_reveals(transport.as_mut(), protocol_version, role, &layout).await?;
+        let fleet = commitment::verify_reveal(&peer_digest, &salt, &peer_layout, &engine.rules(), engine.history())?;
+        let (peer_board, peer_stats) =
+            commitment::exchange_game_over(transport.as_mut(), protocol_version, role, engine.board().into(), engine.stats()).await?;
+        commitment::verify_game_over(&fleet, &peer_board, &peer_stats, &engine)?;
+        println!("Your opponent's fleet and report match the layout they committed to.");
+        opponent = Some((fleet, peer_stats));
+    }
+    notify.on_event(&GameEvent::GameOver { status: engine.status() });
     std::println!("\n╔══════════════════════════════════════════════════════════╗");
     std::println!("║                   GAME OVER                              ║");
     std::println!("╚══════════════════════════════════════════════════════════╝\n");
     print_player_view(&engine);
+    print_stats(&engine);
+    print_defense_report(&engine);
+    if let Some((fleet, stats)) = &opponent {
+        print_opponent_fleet(fleet, stats);
+    }
     match engine.status() {
         GameStatus::Won => {
             std::println!("\n🎉🎉🎉 VICTORY! 🎉🎉🎉");
@@ -402,7 +668,151 @@ async fn run_cli(
             std::println!("\n💀 DEFEAT 💀");
             std::println!("All your ships have been destroyed.");
         }
-        _ => {}
+        GameStatus::Desync => {
+            std::println!("\nGame ended with inconsistent state: the opponent's sink reports do not match the hits recorded.");
+        }
+        GameStatus::Aborted => {
+            std::println!("\nThe game was aborted before either fleet was sunk.");
+        }
+        GameStatus::Forfeited => {
+            std::println!("\nYou resigned. Your opponent takes the win.");
+        }
+        GameStatus::Draw => {
+            std::println!("\nDRAW");
+            std::println!("Neither side could claim the win.");
+        }
+        GameStatus::InProgress => {}
     }
     Ok(())
 }
+
+/// Interactive replay viewer: show both fleets after each move and the
+/// probability map the next shooter's AI computed before firing.
+#[cfg(feature = "std")]
+fn view_replay(recording: &Replay) -> anyhow::Result<()> {
+    use std::io::{self, BufRead, Write};
+
+    let total = recording.len();
+    let mut index = 0;
+    let mut stdin = io::stdin().lock();
+    loop {
+        let frame = recording.frame(index)?;
+        println!("\n════════════════════ Move {}/{} ════════════════════", index, total);
+        match frame.last {
+            Some((player, (r, c), result)) => {
+                println!("Player {} fired at {}: {:?}", player + 1, Coord::new(r, c), result)
+            }
+            None => println!("Start of game"),
+        }
+        for (player, engine) in frame.engines.iter().enumerate() {
+            println!("\nPlayer {} fleet:", player + 1);
+            print_fleet_board(engine.board());
+        }
+        match frame.next {
+            Some((player, (r, c))) => {
+                let engine = &frame.engines[player];
+                let pdf = calc_pdf(
+                    &engine.guess_hits(),
+                    &engine.blocked_cells(),
+                    &engine.enemy_ship_lengths_remaining(),
+                );
+                println!("\nPlayer {} to move; it fires at {}", player + 1, Coord::new(r, c));
+                print_probability_board(&pdf, engine.rules().board_size());
+            }
+            None => {
+                let winner = frame.engines.iter().position(|e| e.status() == GameStatus::Won);
+                match winner {
+                    Some(player) => println!("\nEnd of game: Player {} won", player + 1),
+                    None => println!("\nEnd of recording"),
+                }
+            }
+        }
+
+        print!("\n[n]ext, [p]rev, [j]ump <move>, [q]uit > ");
+        io::stdout().flush()?;
+        let mut line = String::new();
+        if stdin.read_line(&mut line)? == 0 {
+            return Ok(());
+        }
+        let mut words = line.split_whitespace();
+        match words.next().unwrap_or("n") {
+            "n" | "next" => index = (index + 1).min(total),
+            "p" | "prev" => index = index.saturating_sub(1),
+            "j" | "jump" => match words.next().and_then(|w| w.parse::<usize>().ok()) {
+                Some(target) if target <= total => index = target,
+                _ => println!("Jump to a move between 0 and {}", total),
+            },
+            "q" | "quit" => return Ok(()),
+            other => println!("Unknown command {:?}", other),
+        }
+    }
+}
+
+/// AI vs AI on one screen: both players share a process and no transport,
+/// and the boards are redrawn after every shot with `delay` between moves.
+#[cfg(feature = "std")]
+async fn run_demo(seed: Option<u64>, delay: Duration, clear: bool, ai_config: AiConfig) -> anyhow::Result<()> {
+    let mut rngs = match seed {
+        Some(s) => [SmallRng::seed_from_u64(s), SmallRng::seed_from_u64(s.wrapping_add(1))],
+        None => {
+            let mut seed_rng = rand::rng();
+            [SmallRng::from_rng(&mut seed_rng), SmallRng::from_rng(&mut seed_rng)]
+        }
+    };
+    let mut players = [AiPlayer::with_config(ai_config), AiPlayer::with_config(ai_config)];
+    let mut engines = [GameEngine::new(), GameEngine::new()];
+    for i in 0..2 {
+        players[i]
+            .place_ships(&mut rngs[i], engines[i].board_mut())
+            .map_err(|e| anyhow::anyhow!(e))?;
+    }
+
+    let mut shooter = if rngs[0].random_bool(0.5) { 0 } else { 1 };
+    let mut moves = 0;
+    loop {
+        let defender = 1 - shooter;
+        let target = players[shooter].select_target(
+            &mut rngs[shooter],
+            &engines[shooter].guess_hits(),
+            &engines[shooter].blocked_cells(),
+            &engines[shooter].enemy_ship_lengths_remaining(),
+        );
+        let Coord { row: r, col: c } = target;
+        let result = engines[defender].opponent_guess(r, c).map_err(|e| anyhow::anyhow!(e))?;
+        engines[shooter]
+            .record_guess(r, c, result)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        players[shooter].handle_guess_result(target, result);
+        players[defender].handle_opponent_guess(target, result);
+        moves += 1;
+
+        if clear {
+            // Clear the screen and move the cursor home
+            print!("\x1b[2J\x1b[H");
+        }
+        println!("Battleship demo: AI 1 vs AI 2 (move {})\n", moves);
+        print_fleets_side_by_side(
+            [engines[0].board(), engines[1].board()],
+            ["AI 1 fleet", "AI 2 fleet"],
+            Some((defender, (r, c))),
+        );
+        println!(
+            "\nAI {} fired at {}{}: {:?}",
+            shooter + 1,
+            (b'A' + c as u8) as char,
+            r + 1,
+            result
+        );
+
+        if engines[shooter].status() == GameStatus::Won {
+            println!("\nAI {} wins after {} moves!", shooter + 1, moves);
+            for (i, engine) in engines.iter().enumerate() {
+                println!("\nAI {}:", i + 1);
+                print_stats(engine);
+            }
+            return Ok(());
+        }
+        tokio::time::sleep(delay).await;
+        shooter = defender;
+    }
+}
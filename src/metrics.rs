@@ -0,0 +1,218 @@
+//! Server metrics in the Prometheus text format.
+//!
+//! The daemon feeds a shared [`Metrics`] as matches start, moves are
+//! relayed and games end; [`serve`] answers `GET /metrics` with a snapshot.
+//! Server-wide counters cover every match since startup, while per-game
+//! series (labelled `game="<id>"`) exist only while that game is running.
+//!
+//! Moves per second across the server is `rate(battleship_moves_total[1m])`;
+//! the per-game gauge averages over the game's lifetime so far.
+
+#![cfg(feature = "std")]
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest request head [`serve`] reads before giving up on a client.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// How long a scraper gets to send its request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+struct LiveGame {
+    started: Instant,
+    moves: u64,
+}
+
+/// Counters and gauges for one daemon, shared between its tasks.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    games_finished: AtomicU64,
+    games_abandoned: AtomicU64,
+    moves: AtomicU64,
+    disconnects: AtomicU64,
+    transport_errors: AtomicU64,
+    /// Total length of ended games, in microseconds.
+    duration_micros: AtomicU64,
+    live: Mutex<BTreeMap<u64, LiveGame>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A match with this id started.
+    pub fn game_started(&self, id: u64) {
+        if let Ok(mut live) = self.live.lock() {
+            live.insert(
+                id,
+                LiveGame {
+                    started: Instant::now(),
+                    moves: 0,
+                },
+            );
+        }
+    }
+
+    /// A guess was relayed in match `id`.
+    pub fn record_move(&self, id: u64) {
+        self.moves.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut live) = self.live.lock() {
+            if let Some(game) = live.get_mut(&id) {
+                game.moves += 1;
+            }
+        }
+    }
+
+    /// Match `id` ended, with a winner or because a player left.
+    pub fn game_ended(&self, id: u64, finished: bool) {
+        let counter = if finished { &self.games_finished } else { &self.games_abandoned };
+        counter.fetch_add(1, Ordering::Relaxed);
+        let game = self.live.lock().ok().and_then(|mut live| live.remove(&id));
+        if let Some(game) = game {
+            let micros = game.started.elapsed().as_micros().min(u64::MAX as u128) as u64;
+            self.duration_micros.fetch_add(micros, Ordering::Relaxed);
+        }
+    }
+
+    /// A player dropped before their game was decided.
+    pub fn record_disconnect(&self) {
+        self.disconnects.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// A send or receive on a player connection failed.
+    pub fn record_transport_error(&self) {
+        self.transport_errors.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Games currently being played.
+    pub fn active_games(&self) -> usize {
+        self.live.lock().map_or(0, |live| live.len())
+    }
+
+    /// Snapshot of every metric in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let finished = self.games_finished.load(Ordering::Relaxed);
+        let abandoned = self.games_abandoned.load(Ordering::Relaxed);
+        let live: Vec<(u64, LiveGame)> = self
+            .live
+            .lock()
+            .map(|live| live.iter().map(|(id, game)| (*id, *game)).collect())
+            .unwrap_or_default();
+
+        let mut out = String::new();
+        gauge(&mut out, "battleship_games_active", "Games currently being played.", live.len() as f64);
+        let _ = writeln!(out, "# HELP battleship_games_total Games that have ended, by outcome.");
+        let _ = writeln!(out, "# TYPE battleship_games_total counter");
+        let _ = writeln!(out, "battleship_games_total{{outcome=\"finished\"}} {}", finished);
+        let _ = writeln!(out, "battleship_games_total{{outcome=\"abandoned\"}} {}", abandoned);
+        counter(
+            &mut out,
+            "battleship_moves_total",
+            "Guesses relayed between players.",
+            self.moves.load(Ordering::Relaxed),
+        );
+        counter(
+            &mut out,
+            "battleship_disconnects_total",
+            "Players who dropped before their game was decided.",
+            self.disconnects.load(Ordering::Relaxed),
+        );
+        counter(
+            &mut out,
+            "battleship_transport_errors_total",
+            "Failed sends and receives on player connections.",
+            self.transport_errors.load(Ordering::Relaxed),
+        );
+        let _ = writeln!(out, "# HELP battleship_game_duration_seconds Length of ended games.");
+        let _ = writeln!(out, "# TYPE battleship_game_duration_seconds summary");
+        let _ = writeln!(
+            out,
+            "battleship_game_duration_seconds_sum {}",
+            self.duration_micros.load(Ordering::Relaxed) as f64 / 1e6
+        );
+        let _ = writeln!(out, "battleship_game_duration_seconds_count {}", finished + abandoned);
+
+        let _ = writeln!(out, "# HELP battleship_game_moves Guesses relayed so far in a running game.");
+        let _ = writeln!(out, "# TYPE battleship_game_moves gauge");
+        for (id, game) in &live {
+            let _ = writeln!(out, "battleship_game_moves{{game=\"{}\"}} {}", id, game.moves);
+        }
+        let _ = writeln!(out, "# HELP battleship_game_moves_per_second Average move rate of a running game.");
+        let _ = writeln!(out, "# TYPE battleship_game_moves_per_second gauge");
+        for (id, game) in &live {
+            let elapsed = game.started.elapsed().as_secs_f64();
+            let rate = if elapsed > 0.0 { game.moves as f64 / elapsed } else { 0.0 };
+            let _ = writeln!(out, "battleship_game_moves_per_second{{game=\"{}\"}} {}", id, rate);
+        }
+        out
+    }
+}
+
+fn counter(out: &mut String, name: &str, help: &str, value: u64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} counter", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+fn gauge(out: &mut String, name: &str, help: &str, value: f64) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} gauge", name);
+    let _ = writeln!(out, "{} {}", name, value);
+}
+
+/// Answer HTTP scrapes on `listener` forever: `GET /metrics` gets
+/// [`Metrics::render`], anything else a 404.
+pub async fn serve(listener: TcpListener, metrics: Arc<Metrics>) -> anyhow::Result<()> {
+    loop {
+        let (stream, addr) = match listener.accept().await {
+            Ok(conn) => conn,
+            Err(e) => {
+                tracing::warn!(error = %e, "failed to accept metrics connection");
+                continue;
+            }
+        };
+        let metrics = metrics.clone();
+        tokio::spawn(async move {
+            if let Err(e) = scrape(stream, &metrics).await {
+                tracing::debug!(peer = %addr, error = %e, "metrics request failed");
+            }
+        });
+    }
+}
+
+async fn scrape(mut stream: TcpStream, metrics: &Metrics) -> anyhow::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 1024];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = tokio::time::timeout(REQUEST_TIMEOUT, stream.read(&mut buf)).await??;
+        if n == 0 || head.len() + n > MAX_REQUEST_HEAD {
+            return Err(anyhow::anyhow!("Incomplete HTTP request"));
+        }
+        head.extend_from_slice(&buf[..n]);
+    }
+
+    let request = String::from_utf8_lossy(&head);
+    let mut parts = request.split_whitespace();
+    let (status, content_type, body) = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => ("200 OK", "text/plain; version=0.0.4", metrics.render()),
+        _ => ("404 Not Found", "text/plain", "not found\n".to_string()),
+    };
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
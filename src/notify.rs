@@ -0,0 +1,140 @@
+//! Hooks for telling a player that something worth their attention happened.
+//!
+//! A [`Notifier`] reacts to the three moments a player who looked away cares
+//! about: their turn coming round, a ship going down, and the end of the
+//! game. Binaries implement it for whatever channel suits them (terminal
+//! bell, desktop notification, webhook); [`TerminalBell`] is the built-in
+//! one. [`Notifications`] adapts a notifier to the [`GameEvent`] bus and
+//! drops turn and sink notifications that arrive faster than
+//! `min_interval`, so a quick opponent cannot turn it into a stream of
+//! alerts. Game over is always delivered.
+
+#![cfg(feature = "std")]
+
+use crate::core::{
+    events::{EventListener, GameEvent},
+    game::GameStatus,
+};
+use std::io::Write;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast;
+
+/// Default minimum gap between two notifications of the same kind.
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Receives significant game moments. Every method defaults to doing
+/// nothing, so implementors pick the ones they care about.
+pub trait Notifier: Send {
+    /// The opponent has moved and it is our turn to shoot.
+    fn on_your_turn(&mut self) {}
+
+    /// `ship` was sunk; `ours` is true when it was one of our ships.
+    fn on_ship_sunk(&mut self, ship: &'static str, ours: bool) {
+        let _ = (ship, ours);
+    }
+
+    /// The game reached a final status.
+    fn on_game_over(&mut self, status: GameStatus) {
+        let _ = status;
+    }
+}
+
+/// Rings the terminal bell (BEL) on every notification.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TerminalBell;
+
+impl TerminalBell {
+    fn ring(&self) {
+        let mut out = std::io::stdout();
+        let _ = out.write_all(b"\x07");
+        let _ = out.flush();
+    }
+}
+
+impl Notifier for TerminalBell {
+    fn on_your_turn(&mut self) {
+        self.ring();
+    }
+
+    fn on_ship_sunk(&mut self, _ship: &'static str, _ours: bool) {
+        self.ring();
+    }
+
+    fn on_game_over(&mut self, _status: GameStatus) {
+        self.ring();
+    }
+}
+
+/// Forwards [`GameEvent`]s to a [`Notifier`], rate limited.
+pub struct Notifications<N> {
+    notifier: N,
+    min_interval: Duration,
+    last_turn: Option<Instant>,
+    last_sink: Option<Instant>,
+    game_over: bool,
+}
+
+impl<N: Notifier> Notifications<N> {
+    pub fn new(notifier: N) -> Self {
+        Self::with_min_interval(notifier, DEFAULT_MIN_INTERVAL)
+    }
+
+    pub fn with_min_interval(notifier: N, min_interval: Duration) -> Self {
+        Self {
+            notifier,
+            min_interval,
+            last_turn: None,
+            last_sink: None,
+            game_over: false,
+        }
+    }
+
+    pub fn notifier(&self) -> &N {
+        &self.notifier
+    }
+
+    /// Whether a notification last sent at `last` may go out again now.
+    fn due(last: &mut Option<Instant>, min_interval: Duration) -> bool {
+        let now = Instant::now();
+        if last.is_some_and(|t| now.duration_since(t) < min_interval) {
+            return false;
+        }
+        *last = Some(now);
+        true
+    }
+}
+
+impl<N: Notifier> EventListener for Notifications<N> {
+    fn on_event(&mut self, event: &GameEvent) {
+        if self.game_over {
+            return;
+        }
+        match *event {
+            GameEvent::GameStarted { first_move: true } | GameEvent::OpponentGuess { .. }
+                if Self::due(&mut self.last_turn, self.min_interval) =>
+            {
+                self.notifier.on_your_turn();
+            }
+            GameEvent::Sink { ship, ours } if Self::due(&mut self.last_sink, self.min_interval) => {
+                self.notifier.on_ship_sunk(ship, ours);
+            }
+            GameEvent::GameOver { status } => {
+                self.game_over = true;
+                self.notifier.on_game_over(status);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Feed every event from `events` to `listener` until the publishing node
+/// goes away. Events missed by a lagging receiver are skipped.
+pub async fn forward_events(mut events: broadcast::Receiver<GameEvent>, mut listener: impl EventListener) {
+    loop {
+        match events.recv().await {
+            Ok(event) => listener.on_event(&event),
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => return,
+        }
+    }
+}
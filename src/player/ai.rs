@@ -1,33 +1,181 @@
 use crate::core::{
-    ai,
-    bitboard::BitBoard,
+    ai::{self, AiConfig},
     board::Board,
     common::GuessResult,
-    config::{BOARD_SIZE, NUM_SHIPS},
+    config::{BoardBits, FleetConfig, PlacementRules, RuleSet, MAX_BOARD_SIZE, MAX_SHIPS},
+    coord::Coord,
+    endgame,
+    opponent::OpponentProfile,
+    opponent_model::OpponentModel,
+    pdf_cache::PdfCache,
+    placement::{AvoidHeat, PlacementStrategy, PlacementStyle},
+    targeting::{TargetingStrategy, TargetingStyle, TargetingView},
     BoardError,
 };
 use rand::rngs::SmallRng;
 
+use super::explain::{Explanation, MaskConstraints, TargetPhase};
 use super::Player;
 
 /// Simple AI player that uses probability based guessing.
-pub struct AiPlayer;
+///
+/// Across a series of games against the same opponent it builds an
+/// [`OpponentProfile`]; once that is mature, placement avoids the opponent's
+/// favourite targets and hunting favours cells where its ships used to be.
+///
+/// After a hit it switches to target mode, probing around the hits not yet
+/// put down to a sunk ship and following their line once two are aligned,
+/// until sinkings account for all of them. Once a single ship is left with
+/// few enough placements, it searches them all for the quickest way to
+/// sink it, as [`AiConfig::endgame_candidates`] allows.
+pub struct AiPlayer {
+    config: AiConfig,
+    targeting: TargetingStyle,
+    placement: PlacementStyle,
+    profile: OpponentProfile,
+    fleet: FleetConfig,
+    placement_rules: PlacementRules,
+    /// Storage cells outside the board, never worth a shot.
+    off_board: BoardBits,
+    /// Which of our hits belong to which sunk ship, for target mode.
+    model: OpponentModel,
+    /// Enemy ships reported sunk this game, by fleet index.
+    sunk: [bool; MAX_SHIPS],
+    /// Placement density carried over from the last turn.
+    cache: PdfCache,
+    debug: AiDebugState,
+    explanation: Option<Explanation>,
+}
+
+/// What an [`AiPlayer`] worked out on its last turn, for tests and tools.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AiDebugState {
+    /// Placements the last enemy ship afloat could take, once only one is.
+    pub endgame_candidates: Option<usize>,
+    /// Shots the endgame search expected to need, when it picked the shot.
+    pub endgame_expected_shots: Option<f64>,
+}
 
 impl AiPlayer {
     pub fn new() -> Self {
-        Self
+        Self::with_placement(PlacementStyle::default())
+    }
+
+    /// AI player that lays out its fleet using `placement`.
+    pub fn with_placement(placement: PlacementStyle) -> Self {
+        Self::with_strategies(TargetingStyle::default(), placement)
+    }
+
+    /// AI player that weighs its shots with `targeting` and lays out its
+    /// fleet using `placement`, e.g. to pit strategies against each other.
+    pub fn with_strategies(targeting: TargetingStyle, placement: PlacementStyle) -> Self {
+        Self {
+            config: AiConfig::default(),
+            targeting,
+            placement,
+            profile: OpponentProfile::new(),
+            fleet: FleetConfig::STANDARD,
+            placement_rules: PlacementRules::STANDARD,
+            off_board: RuleSet::STANDARD.off_board(),
+            model: OpponentModel::new(),
+            sunk: [false; MAX_SHIPS],
+            cache: PdfCache::new(),
+            debug: AiDebugState::default(),
+            explanation: None,
+        }
+    }
+
+    /// AI player using the given targeting tunables.
+    pub fn with_config(config: AiConfig) -> Self {
+        Self {
+            config,
+            ..Self::new()
+        }
+    }
+
+    /// Targeting tunables used by this player.
+    pub fn config(&self) -> &AiConfig {
+        &self.config
+    }
+
+    /// Resume adapting to an opponent from a previously saved profile.
+    pub fn with_profile(mut self, profile: OpponentProfile) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Target an opponent fleet other than the standard one. Placing ships
+    /// adopts the fleet of the board's rules instead.
+    pub fn with_fleet(mut self, fleet: FleetConfig) -> Self {
+        self.fleet = fleet;
+        self
+    }
+
+    /// Target an opponent whose ships were placed under `rules`, ruling out
+    /// positions they forbid. Placing ships adopts the board's placement
+    /// rules instead.
+    pub fn with_placement_rules(mut self, rules: PlacementRules) -> Self {
+        self.placement_rules = rules;
+        self
+    }
+
+    /// What this player has learned about its opponent so far.
+    pub fn profile(&self) -> &OpponentProfile {
+        &self.profile
+    }
+
+    /// Targeting strategy used by this player.
+    pub fn targeting(&self) -> TargetingStyle {
+        self.targeting
+    }
+
+    /// Placement strategy used by this player.
+    pub fn placement(&self) -> PlacementStyle {
+        self.placement
+    }
+
+    /// The opponent fleet this player targets.
+    pub fn fleet(&self) -> &FleetConfig {
+        &self.fleet
+    }
+
+    /// Which of this game's hits belong to which sunk ship; target mode
+    /// chases the hits left unresolved.
+    pub fn opponent_model(&self) -> &OpponentModel {
+        &self.model
+    }
+
+    /// What this player worked out when it last chose a target.
+    pub fn debug_state(&self) -> AiDebugState {
+        self.debug
+    }
+
+    /// Why this player chose its last target; `None` before its first turn.
+    pub fn last_explanation(&self) -> Option<Explanation> {
+        self.explanation
+    }
+}
+
+impl Default for AiPlayer {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-type BB = BitBoard<u128, { BOARD_SIZE as usize }>;
+type BB = BoardBits;
 
 impl Player for AiPlayer {
     fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError> {
-        for i in 0..NUM_SHIPS as usize {
-            let (r, c, o) = board.random_placement(rng, i)?;
-            board.place(i, r, c, o)?;
+        // Both fleets are placed under the same rules
+        self.fleet = *board.rules().fleet();
+        self.placement_rules = board.rules().placement();
+        self.off_board = board.rules().off_board();
+        self.profile.begin_game();
+        if self.profile.is_mature() {
+            AvoidHeat::new(self.profile.shot_heat()).place_fleet(rng, board)
+        } else {
+            self.placement.place_fleet(rng, board)
         }
-        Ok(())
     }
 
     fn select_target(
@@ -35,11 +183,102 @@ impl Player for AiPlayer {
         rng: &mut SmallRng,
         hits: &BB,
         misses: &BB,
-        remaining: &[usize; NUM_SHIPS as usize],
-    ) -> (usize, usize) {
-        ai::calc_pdf_and_guess(hits, misses, remaining, rng)
+        remaining: &[usize],
+    ) -> Coord {
+        let ruled_out = (*hits | *misses).count_ones();
+        let misses = &(*misses | self.off_board);
+        // Hits from an earlier game are not on this board
+        if !((self.model.unresolved_hits() | self.model.sunk_cells()) & !*hits).is_empty() {
+            self.model = OpponentModel::new();
+            self.sunk = [false; MAX_SHIPS];
+        }
+        let sunk = self.model.sunk_cells();
+        let view = TargetingView {
+            hits,
+            sunk: &sunk,
+            misses,
+            remaining,
+            fleet: &self.fleet,
+            placement: &self.placement_rules,
+        };
+        let plan = endgame::plan_endgame(&view, self.config.endgame_candidates);
+        let shot = plan.and_then(|plan| plan.best);
+        self.debug = AiDebugState {
+            endgame_candidates: plan.map(|plan| plan.candidates),
+            endgame_expected_shots: shot.map(|shot| shot.expected_shots),
+        };
+        let constraints = MaskConstraints {
+            ruled_out,
+            no_touching: self.placement_rules.no_touching && !sunk.is_empty(),
+            ..MaskConstraints::default()
+        };
+        if let Some(shot) = shot {
+            let target = Coord::new(shot.row, shot.col);
+            let explanation = Explanation::new(TargetPhase::Endgame, constraints, target);
+            self.explanation = Some(explanation.expecting(shot.expected_shots));
+            return target;
+        }
+        let cached = self.config.mc_samples == 0 && !self.config.fixed_point;
+        let mut pdf = if self.targeting == TargetingStyle::Density && cached {
+            self.cache.density(&view, &self.config)
+        } else {
+            self.targeting.density(rng, &view, &self.config)
+        };
+        if self.profile.is_mature() {
+            self.profile.apply_hunting_prior(&mut pdf);
+        }
+        // Where no placement fits, as once a salvo has blocked the cells it
+        // already picked, the density is spread over every cell, shot or
+        // not; keep it to the open ones
+        focus(&mut pdf, &BB::unknown(hits, misses));
+        let follow_up = ai::follow_up_targets(&self.model.unresolved_hits(), hits, misses);
+        let phase = if follow_up.is_empty() { TargetPhase::Hunt } else { TargetPhase::Target };
+        if phase == TargetPhase::Target {
+            focus(&mut pdf, &follow_up);
+        }
+        let target = Coord::from(ai::pick_target(&pdf, &self.config, rng));
+        let constraints = MaskConstraints {
+            parity: self.config.parity && phase == TargetPhase::Hunt && self.targeting != TargetingStyle::InfoGain,
+            follow_up: follow_up.count_ones(),
+            hunting_prior: self.profile.is_mature(),
+            ..constraints
+        };
+        self.explanation = Some(Explanation::new(phase, constraints, target).ranking(&pdf));
+        target
+    }
+
+    fn handle_guess_result(&mut self, Coord { row, col }: Coord, result: GuessResult) {
+        if !matches!(result, GuessResult::Miss) {
+            self.profile.record_enemy_ship_cell(row, col);
+        }
+        match result {
+            GuessResult::Hit => self.model.record_hit(row, col),
+            GuessResult::Sink(name) => {
+                // The first ship of that name still afloat, as the engine counts it
+                let ships = self.fleet.ships();
+                if let Some(idx) = (0..ships.len()).find(|&i| !self.sunk[i] && ships[i].name() == name) {
+                    self.sunk[idx] = true;
+                    self.model.record_sink(idx, ships[idx].length(), row, col);
+                }
+            }
+            GuessResult::Miss => {}
+        }
     }
 
-    fn handle_guess_result(&mut self, _coord: (usize, usize), _result: GuessResult) {}
-    fn handle_opponent_guess(&mut self, _coord: (usize, usize), _result: GuessResult) {}
+    fn handle_opponent_guess(&mut self, Coord { row, col }: Coord, _result: GuessResult) {
+        self.profile.record_opponent_shot(row, col);
+    }
+}
+
+/// Keep only the weight on `cells`, spreading it evenly there if none is.
+fn focus(pdf: &mut [[f64; MAX_BOARD_SIZE as usize]; MAX_BOARD_SIZE as usize], cells: &BB) {
+    let mass: f64 = cells.iter_set_bits().map(|(r, c)| pdf[r][c]).sum();
+    for (r, c) in (!*cells).iter_set_bits() {
+        pdf[r][c] = 0.0;
+    }
+    if mass == 0.0 {
+        for (r, c) in cells.iter_set_bits() {
+            pdf[r][c] = 1.0;
+        }
+    }
 }
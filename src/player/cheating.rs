@@ -0,0 +1,57 @@
+#![cfg(feature = "debug-tools")]
+
+use crate::core::{
+    board::Board,
+    config::BoardBits,
+    coord::Coord,
+    placement::{PlacementStrategy, UniformRandom},
+    BoardError,
+};
+use rand::rngs::SmallRng;
+
+use super::Player;
+
+type BB = BoardBits;
+
+/// Omniscient AI that knows the opponent's ship layout and always fires at
+/// the next unguessed ship cell in row-major order.
+///
+/// Only for tests: it sinks a fleet in exactly as many shots as the fleet has
+/// cells, making end-to-end games fast and deterministic.
+pub struct CheatingAiPlayer {
+    targets: BB,
+}
+
+impl CheatingAiPlayer {
+    /// Cheat against a fleet occupying `targets`.
+    pub fn new(targets: BB) -> Self {
+        Self { targets }
+    }
+
+    /// Cheat against the ships already placed on the opponent's `board`.
+    pub fn from_board(board: &Board) -> Self {
+        Self::new(board.ship_map())
+    }
+}
+
+impl Player for CheatingAiPlayer {
+    fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError> {
+        UniformRandom.place_fleet(rng, board)
+    }
+
+    fn select_target(
+        &mut self,
+        _rng: &mut SmallRng,
+        hits: &BB,
+        misses: &BB,
+        _remaining: &[usize],
+    ) -> Coord {
+        let unknown = BB::unknown(hits, misses);
+        (self.targets & unknown)
+            .iter_set_bits()
+            .next()
+            .or_else(|| unknown.iter_set_bits().next())
+            .map(Coord::from)
+            .unwrap_or_default()
+    }
+}
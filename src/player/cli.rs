@@ -2,89 +2,107 @@
 
 use std::io::{self, Write};
 use std::string::String;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::core::{
-    ai,
-    bitboard::BitBoard,
-    board::Board,
+    ai::AiConfig,
+    board::{Board, CellState, ShipReport},
     common::GuessResult,
-    config::{BOARD_SIZE, NUM_SHIPS, SHIPS},
+    config::{BoardBits, BOARD_SIZE, MAX_BOARD_SIZE},
+    coord::Coord,
+    ship::ShipState,
+    game::Stats,
     GameEngine,
     BoardError,
 };
 use rand::rngs::SmallRng;
 
-use super::Player;
+use super::{AiPlayer, Player};
 
-type BB = BitBoard<u128, { BOARD_SIZE as usize }>;
+type BB = BoardBits;
 
-pub struct CliPlayer;
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+pub struct CliPlayer {
+    /// Pick targets with a cursor instead of typing coordinates; only
+    /// available with the `tui` feature on a capable terminal.
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    cursor: bool,
+    /// Set once the user types `resign` at the targeting prompt.
+    resigned: bool,
+    /// Side of the board being played, taken from it at placement.
+    #[cfg_attr(not(feature = "tui"), allow(dead_code))]
+    board_size: u8,
+    /// Suggests targets, following our shots as an AI player would, and
+    /// explains them when asked `why`.
+    adviser: AiPlayer,
+}
 
 impl CliPlayer {
     pub fn new() -> Self {
-        Self
+        Self {
+            cursor: cfg!(feature = "tui"),
+            resigned: false,
+            board_size: BOARD_SIZE,
+            adviser: AiPlayer::new(),
+        }
     }
-}
-
-fn coord_to_string(r: usize, c: usize) -> String {
-    let col = (b'A' + c as u8) as char;
-    std::format!("{}{}", col, r + 1)
-}
 
-fn parse_coord(input: &str) -> Result<(usize, usize), String> {
-    if input.is_empty() {
-        return Err("Empty input".to_string());
-    }
-    if input.len() < 2 {
-        return Err("Too short - need column letter and row number (e.g., A5)".to_string());
-    }
-    let mut chars = input.chars();
-    let col_ch = chars.next().ok_or("No column letter")?.to_ascii_uppercase();
-    if !col_ch.is_ascii_alphabetic() {
-        return Err(format!("Invalid column '{}' - must be a letter A-J", col_ch));
+    /// Always type target coordinates, even where cursor targeting works.
+    pub fn text_only() -> Self {
+        Self {
+            cursor: false,
+            ..Self::new()
+        }
     }
-    let col = (col_ch as u8).wrapping_sub(b'A') as usize;
-    if col >= BOARD_SIZE as usize {
-        return Err(format!("Column '{}' out of bounds - must be A-J", col_ch));
+
+    /// Suggest targets using `config` instead of the default tunables.
+    pub fn with_ai_config(mut self, config: AiConfig) -> Self {
+        self.adviser = AiPlayer::with_config(config);
+        self
     }
-    let row_str: String = chars.collect();
-    let row: usize = row_str.parse()
-        .map_err(|_| format!("Invalid row '{}' - must be a number 1-10", row_str))?;
-    if row == 0 {
-        return Err("Row cannot be 0 - must be 1-10".to_string());
+
+    /// Tunables used for the suggested target.
+    pub fn ai_config(&self) -> &AiConfig {
+        self.adviser.config()
     }
-    if row > BOARD_SIZE as usize {
-        return Err(format!("Row {} out of bounds - must be 1-10", row));
+}
+
+impl Default for CliPlayer {
+    fn default() -> Self {
+        Self::new()
     }
-    Ok((row - 1, col))
+}
+
+/// Horizontal rule of a board frame `size` cells wide.
+fn frame_rule(size: usize) -> String {
+    "═".repeat(2 * size + 4)
 }
 
 fn print_board(board: &Board, reveal: bool) {
-    std::println!("    ╔═══════════════════════╗");
-    std::print!("    ║  ");
-    for c in 0..BOARD_SIZE as usize {
+    let size = board.rules().board_size() as usize;
+    std::println!("    ╔{}╗", frame_rule(size));
+    std::print!("    ║   ");
+    for c in 0..size {
         let ch = (b'A' + c as u8) as char;
         std::print!(" {}", ch);
     }
     std::println!(" ║");
-    std::println!("    ╠═══════════════════════╣");
-    for r in 0..BOARD_SIZE as usize {
+    std::println!("    ╠{}╣", frame_rule(size));
+    for r in 0..size {
         std::print!("    ║ {:2}", r + 1);
-        for c in 0..BOARD_SIZE as usize {
-            let ch = if board.hits().get(r, c).unwrap_or(false) {
-                'X'
-            } else if board.misses().get(r, c).unwrap_or(false) {
-                'o'
-            } else if reveal && board.ship_map().get(r, c).unwrap_or(false) {
-                'S'
-            } else {
-                '.'
+        for c in 0..size {
+            let ch = match board.cell(r, c) {
+                Ok(CellState::Hit(_)) => 'X',
+                Ok(CellState::Miss) => 'o',
+                Ok(CellState::Ship(_)) if reveal => 'S',
+                _ => '.',
             };
             std::print!(" {}", ch);
         }
         std::println!(" ║");
     }
-    std::println!("    ╚═══════════════════════╝");
+    std::println!("    ╚{}╝", frame_rule(size));
     
     // Print legend
     if reveal {
@@ -92,102 +110,272 @@ fn print_board(board: &Board, reveal: bool) {
     } else {
         std::println!("    Legend: X=Hit  o=Miss  .=Unknown");
     }
-    
-    // Print ship status
-    if reveal {
-        std::println!("\n    Ships:");
-        let states = board.ship_states();
-        for (i, state) in states.iter().enumerate() {
-            let def = SHIPS[i];
-            let status = if state.sunk { "SUNK" } else { "Active" };
-            std::println!("      {} ({}): {}", def.name(), def.length(), status);
-        }
+}
+
+fn own_ship_status(hits: usize, length: usize, sunk: bool) -> String {
+    if sunk {
+        "SUNK".to_string()
+    } else if hits > 0 {
+        format!("{}/{} hit", hits, length)
+    } else {
+        "Active".to_string()
     }
 }
 
-fn print_guess_board(hits: &BB, misses: &BB) {
-    std::println!("    ╔═══════════════════════╗");
-    std::print!("    ║  ");
-    for c in 0..BOARD_SIZE as usize {
-        let ch = (b'A' + c as u8) as char;
-        std::print!(" {}", ch);
+/// Status of one of our ships, with a segment map such as `[X.X..]` once
+/// it is damaged but afloat.
+fn own_ship_damage(ship: &ShipReport, state: &ShipState) -> String {
+    let status = own_ship_status(ship.hits, ship.length, ship.sunk);
+    if ship.hits == 0 || ship.sunk {
+        return status;
     }
-    std::println!(" ║");
-    std::println!("    ╠═══════════════════════╣");
-    for r in 0..BOARD_SIZE as usize {
-        std::print!("    ║ {:2}", r + 1);
-        for c in 0..BOARD_SIZE as usize {
-            let ch = if hits.get(r, c).unwrap_or(false) {
-                'X'
-            } else if misses.get(r, c).unwrap_or(false) {
-                'o'
-            } else {
-                '.'
-            };
-            std::print!(" {}", ch);
-        }
-        std::println!(" ║");
+    let segments: String = (0..ship.length)
+        .map(|k| if state.segment_hit(k) { 'X' } else { '.' })
+        .collect();
+    format!("{} [{}]", status, segments)
+}
+
+fn print_fleet_status(board: &Board) {
+    std::println!("\n    Ships:");
+    let report = board.fleet_report();
+    let states = board.ship_states();
+    for (ship, state) in report.ships.iter().zip(&states).filter(|(s, _)| s.length > 0) {
+        std::println!("      {} ({}): {}", ship.name, ship.length, own_ship_damage(ship, state));
+    }
+    std::println!("    Fleet health: {}%", report.health_percent());
+}
+
+fn guess_board_lines(hits: &BB, misses: &BB, size: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    lines.push(format!("    ╔{}╗", frame_rule(size)));
+    let header: String = (0..size)
+        .map(|c| format!(" {}", (b'A' + c as u8) as char))
+        .collect();
+    lines.push(format!("    ║   {} ║", header));
+    lines.push(format!("    ╠{}╣", frame_rule(size)));
+    for r in 0..size {
+        let cells: String = (0..size)
+            .map(|c| {
+                let ch = if hits.get(r, c).unwrap_or(false) {
+                    'X'
+                } else if misses.get(r, c).unwrap_or(false) {
+                    'o'
+                } else {
+                    '.'
+                };
+                format!(" {}", ch)
+            })
+            .collect();
+        lines.push(format!("    ║ {:2}{} ║", r + 1, cells));
     }
-    std::println!("    ╚═══════════════════════╝");
-    std::println!("    Legend: X=Hit  o=Miss  .=Unknown");
+    lines.push(format!("    ╚{}╝", frame_rule(size)));
+    lines.push("    Legend: X=Hit  o=Miss  .=Unknown".to_string());
+    lines
 }
 
-/// Print a normalized probability distribution matrix.
-pub fn print_probability_board(pdf: &[[f64; BOARD_SIZE as usize]; BOARD_SIZE as usize]) {
+/// Sidebar text for the player view: every enemy ship with its length and
+/// whether it has been sunk, then the damage to our own fleet.
+pub fn fleet_status_lines(engine: &GameEngine) -> Vec<String> {
+    let rules = engine.rules();
+    let fleet = rules.fleet();
+    let remaining = engine.enemy_ships_remaining();
+    let afloat = remaining.iter().filter(|&&afloat| afloat).count();
+    let mut lines = std::vec![format!("Enemy fleet ({}/{} afloat)", afloat, fleet.len())];
+    for (def, afloat) in fleet.ships().iter().zip(remaining) {
+        let status = if afloat { "afloat" } else { "SUNK" };
+        lines.push(format!("  {:<10} {}  {}", def.name(), def.length(), status));
+    }
+
+    let report = engine.board().fleet_report();
+    let states = engine.board().ship_states();
+    lines.push(String::new());
+    lines.push(format!("Your fleet ({}% health)", report.health_percent()));
+    for (ship, state) in report.ships.iter().zip(&states).filter(|(s, _)| s.length > 0) {
+        let status = own_ship_damage(ship, state);
+        lines.push(format!("  {:<10} {}  {}", ship.name, ship.length, status));
+    }
+    lines
+}
+
+/// Print a normalized probability distribution matrix, cropped to a
+/// `board_size`×`board_size` board.
+pub fn print_probability_board(pdf: &[[f64; GRID_SIZE]; GRID_SIZE], board_size: u8) {
+    let size = board_size as usize;
     std::println!("\nProbability distribution:");
     std::print!("   ");
-    for c in 0..BOARD_SIZE as usize {
+    for c in 0..size {
         let ch = (b'A' + c as u8) as char;
         std::print!(" {:>4}", ch);
     }
     std::println!();
-    for r in 0..BOARD_SIZE as usize {
+    for (r, row) in pdf.iter().take(size).enumerate() {
         std::print!("{:2} ", r + 1);
-        for c in 0..BOARD_SIZE as usize {
-            std::print!(" {:4.2}", pdf[r][c]);
+        for v in row.iter().take(size) {
+            std::print!(" {:4.2}", v);
         }
         std::println!();
     }
 }
 
-/// Display the opponent board (top) and the player's board (bottom).
+/// Display two fleets next to each other with ships revealed. The cell in
+/// `highlight`, given as (board index, (row, col)), is marked with `*`.
+pub fn print_fleets_side_by_side(
+    boards: [&Board; 2],
+    labels: [&str; 2],
+    highlight: Option<(usize, (usize, usize))>,
+) {
+    let cell = |i: usize, r: usize, c: usize| {
+        let board = boards[i];
+        if highlight == Some((i, (r, c))) {
+            return '*';
+        }
+        match board.cell(r, c) {
+            Ok(CellState::Hit(_)) => 'X',
+            Ok(CellState::Miss) => 'o',
+            Ok(CellState::Ship(_)) => 'S',
+            _ => '.',
+        }
+    };
+    let size = boards[0].rules().board_size() as usize;
+    let header: String = (0..size)
+        .map(|c| format!(" {}", (b'A' + c as u8) as char))
+        .collect();
+    std::println!("    {:<width$}{}", labels[0], labels[1], width = 2 * size + 7);
+    std::println!("      {}       {}", header, header);
+    for r in 0..size {
+        let left: String = (0..size).map(|c| format!(" {}", cell(0, r, c))).collect();
+        let right: String = (0..size).map(|c| format!(" {}", cell(1, r, c))).collect();
+        std::println!("    {:2}{}     {:2}{}", r + 1, left, r + 1, right);
+    }
+    let afloat = |i: usize| boards[i].fleet_report().ships_afloat();
+    std::println!("    Ships afloat: {:<13}Ships afloat: {}", afloat(0), afloat(1));
+    std::println!("    Legend: S=Ship  X=Hit  o=Miss  *=Last shot  .=Water");
+}
+
+/// Display a board with its ships revealed, damage and fleet status.
+pub fn print_fleet_board(board: &Board) {
+    print_board(board, true);
+    print_fleet_status(board);
+}
+
+/// Display the opponent board (top) with the fleet status sidebar beside
+/// it, and the player's board (bottom).
 pub fn print_player_view(engine: &GameEngine) {
     std::println!("Opponent board:");
-    print_guess_board(&engine.guess_hits(), &engine.guess_misses());
+    let size = engine.rules().board_size() as usize;
+    let board = guess_board_lines(&engine.guess_hits(), &engine.guess_misses(), size);
+    let sidebar = fleet_status_lines(engine);
+    for i in 0..board.len().max(sidebar.len()) {
+        let left = board.get(i).map_or("", String::as_str);
+        let right = sidebar.get(i).map_or("", String::as_str);
+        let line = format!("{:<width$} {}", left, right, width = 2 * size + 12);
+        std::println!("{}", line.trim_end());
+    }
     std::println!("\nYour board:");
     print_board(engine.board(), true);
 }
 
+/// Print the end-of-game summary of the opponent's fire against our fleet.
+pub fn print_defense_report(engine: &GameEngine) {
+    let report = engine.defense_report();
+    std::println!("\n    Defense:");
+    for ship in report.ships.iter().filter(|s| s.length > 0) {
+        let fate = match (ship.sunk_at, ship.first_hit) {
+            (Some(turn), _) => format!("sunk on turn {}", turn),
+            (None, Some(turn)) => format!("{}/{} hit, first on turn {}", ship.hits, ship.length, turn),
+            (None, None) => own_ship_status(ship.hits, ship.length, false),
+        };
+        std::println!("      {} ({}): {}", ship.name, ship.length, fate);
+    }
+    if let Some(i) = report.first_ship_lost() {
+        std::println!("    First ship lost: {}", report.ships[i].name);
+    }
+    std::println!(
+        "    Opponent accuracy: {}% ({}/{} shots)",
+        report.opponent_accuracy_percent(),
+        report.hits_received,
+        report.shots_received
+    );
+}
+
+/// Print the opponent's fleet as revealed at the end of the game, with our
+/// shots on it, and the longest run of hits it managed against us.
+pub fn print_opponent_fleet(board: &Board, stats: &Stats) {
+    std::println!("\nOpponent's fleet:");
+    print_fleet_board(board);
+    std::println!("    Opponent's longest hit streak: {}", stats.longest_hit_streak);
+}
+
+/// Print the scoreboard of our own fire and how long our fleet held out.
+pub fn print_stats(engine: &GameEngine) {
+    let stats = engine.stats();
+    std::println!("\n    Offense:");
+    std::println!("      Accuracy: {}% ({}/{} shots)", stats.accuracy_percent(), stats.hits, stats.shots());
+    std::println!("      Longest hit streak: {}", stats.longest_hit_streak);
+    match stats.shots_per_sink() {
+        Some(shots) => std::println!("      Shots per ship sunk: {:.1} ({} sunk)", shots, stats.ships_sunk),
+        None => std::println!("      Shots per ship sunk: - (none sunk)"),
+    }
+    std::println!("      Turns survived: {}", stats.turns_survived);
+}
+
 impl Player for CliPlayer {
     fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError> {
+        self.place_ships_cancellable(rng, board, &AtomicBool::new(false))
+    }
+
+    /// Checked after each line read: a prompt already waiting on the
+    /// terminal gives up once the player presses ENTER.
+    fn place_ships_cancellable(
+        &mut self,
+        rng: &mut SmallRng,
+        board: &mut Board,
+        cancel: &AtomicBool,
+    ) -> Result<(), BoardError> {
         std::println!("\n════════════════════════════════════════════════════════════");
         std::println!("                    SHIP PLACEMENT PHASE");
         std::println!("════════════════════════════════════════════════════════════");
         std::println!("\nInstructions:");
         std::println!("  • Enter coordinates and orientation (e.g., A5 H or A5 V)");
         std::println!("  • H = Horizontal, V = Vertical");
-        std::println!("  • Valid columns: A-J, Valid rows: 1-10");
+        self.board_size = board.rules().board_size();
+        std::println!(
+            "  • Valid columns: A-{}, Valid rows: 1-{}",
+            (b'A' + self.board_size - 1) as char,
+            self.board_size
+        );
         std::println!("  • Press ENTER for random placement");
+        std::println!("  • Type 'undo' to pick up the last ship, 'redo' to put it back");
         std::println!("  • Type 'help' for more information\n");
         
-        for i in 0..NUM_SHIPS as usize {
-            let def = SHIPS[i];
+        let fleet = *board.fleet();
+        // Ships picked up with 'undo', most recent last
+        let mut undone: Vec<(usize, usize, crate::core::ship::Orientation)> = Vec::new();
+        let mut i = 0;
+        while i < fleet.len() {
+            let def = fleet.ships()[i];
             loop {
                 std::println!("\n═══════════════════════════════════════════════════════════=");
-                print_board(board, true);
+                print_fleet_board(board);
                 std::println!("\nShip {}/{}: {} (length {})", 
-                    i + 1, NUM_SHIPS, def.name(), def.length());
+                    i + 1, fleet.len(), def.name(), def.length());
                 std::print!("Enter placement (or ENTER for random, 'help' for help): ");
                 io::stdout().flush().unwrap();
                 let mut line = String::new();
                 io::stdin().read_line(&mut line).unwrap();
+                if cancel.load(Ordering::SeqCst) {
+                    std::println!("\n✗ Placement time is up.");
+                    return Err(BoardError::PlacementCancelled);
+                }
                 let line = line.trim();
                 
                 if line.is_empty() {
                     let (r, c, o) = board.random_placement(rng, i)?;
                     board.place(i, r, c, o)?;
                     std::println!("✓ {} randomly placed at {}", 
-                        def.name(), coord_to_string(r, c));
+                        def.name(), Coord::new(r, c));
+                    undone.clear();
+                    i += 1;
                     break;
                 }
                 
@@ -195,6 +383,28 @@ impl Player for CliPlayer {
                     print_placement_help();
                     continue;
                 }
+
+                if line.eq_ignore_ascii_case("undo") {
+                    if i == 0 {
+                        std::println!("Nothing to undo");
+                        continue;
+                    }
+                    i -= 1;
+                    undone.push(board.remove(i)?);
+                    std::println!("↶ {} picked up", fleet.ships()[i].name());
+                    break;
+                }
+
+                if line.eq_ignore_ascii_case("redo") {
+                    let Some((r, c, o)) = undone.pop() else {
+                        std::println!("Nothing to redo");
+                        continue;
+                    };
+                    board.place(i, r, c, o)?;
+                    std::println!("↷ {} placed again at {}", def.name(), Coord::new(r, c));
+                    i += 1;
+                    break;
+                }
                 
                 let mut parts = line.split_whitespace();
                 let coord_str = parts.next();
@@ -205,8 +415,8 @@ impl Player for CliPlayer {
                     continue;
                 }
                 
-                let coord_result = parse_coord(coord_str.unwrap());
-                let (r, c) = match coord_result {
+                let coord_result = coord_str.unwrap().parse::<Coord>();
+                let Coord { row: r, col: c } = match coord_result {
                     Ok(coord) => coord,
                     Err(e) => {
                         std::println!("✗ Error: {}", e);
@@ -227,17 +437,18 @@ impl Player for CliPlayer {
                     continue;
                 };
                 
-                match board.place(i, r, c, o) {
-                    Ok(()) => {
-                        std::println!("✓ {} placed successfully at {}", 
-                            def.name(), coord_to_string(r, c));
-                        break;
-                    }
-                    Err(e) => {
-                        std::println!("✗ Error: {:?}", e);
-                        std::println!("   Hint: Make sure the ship fits and doesn't overlap with others.");
-                    }
+                if let Err(e) = board.can_place(i, r, c, o) {
+                    std::println!("✗ Error: {:?}", e);
+                    std::println!("   Hint: Make sure the ship fits and doesn't overlap with others.");
+                    continue;
                 }
+
+                board.place(i, r, c, o)?;
+                std::println!("✓ {} placed successfully at {}", 
+                    def.name(), Coord::new(r, c));
+                undone.clear();
+                i += 1;
+                break;
             }
         }
         std::println!("\n✓ All ships placed! Ready to begin battle.\n");
@@ -249,30 +460,63 @@ impl Player for CliPlayer {
         rng: &mut SmallRng,
         hits: &BB,
         misses: &BB,
-        remaining: &[usize; NUM_SHIPS as usize],
-    ) -> (usize, usize) {
-        let (sr, sc) = ai::calc_pdf_and_guess(hits, misses, remaining, rng);
+        remaining: &[usize],
+    ) -> Coord {
+        let suggestion = self.adviser.select_target(rng, hits, misses, remaining);
+        #[cfg(feature = "tui")]
+        if self.cursor && super::cursor::supported() {
+            match super::cursor::select_target(hits, misses, suggestion, self.board_size as usize) {
+                Ok(Some(target)) => return target,
+                Ok(None) => {}
+                Err(e) => {
+                    std::println!("Cursor targeting unavailable ({}); falling back to text entry.", e);
+                    self.cursor = false;
+                }
+            }
+        }
         loop {
             // Show probability-based suggestion in brackets
-            std::print!("\nEnter target coordinates [AI suggests: {}] (or 'help'): ", 
-                coord_to_string(sr, sc));
+            std::print!("\nEnter target coordinates [AI suggests: {}] (or 'why', 'help'): ", 
+                suggestion);
             io::stdout().flush().unwrap();
             let mut line = String::new();
             io::stdin().read_line(&mut line).unwrap();
             let line = line.trim();
             
             if line.is_empty() {
-                std::println!("Using AI suggestion: {}", coord_to_string(sr, sc));
-                return (sr, sc);
+                std::println!("Using AI suggestion: {}", suggestion);
+                return suggestion;
             }
             
             if line.eq_ignore_ascii_case("help") {
                 print_targeting_help();
                 continue;
             }
+
+            if line.eq_ignore_ascii_case("why") {
+                if let Some(explanation) = self.adviser.last_explanation() {
+                    std::println!("\n{}", explanation);
+                }
+                continue;
+            }
+
+            if line.eq_ignore_ascii_case("resign") {
+                std::print!("Really concede the game? [y/N]: ");
+                let mut answer = String::new();
+                // EOF or a broken terminal counts as "no": never resign by accident
+                let confirmed = io::stdout().flush().is_ok()
+                    && matches!(io::stdin().read_line(&mut answer), Ok(n) if n > 0)
+                    && answer.trim().eq_ignore_ascii_case("y");
+                if confirmed {
+                    self.resigned = true;
+                    return suggestion;
+                }
+                continue;
+            }
             
-            match parse_coord(line) {
-                Ok((r, c)) => {
+            match line.parse::<Coord>() {
+                Ok(target) => {
+                    let Coord { row: r, col: c } = target;
                     // Check if already guessed
                     if hits.get(r, c).unwrap_or(false) {
                         std::println!("✗ You already hit that location! Choose another target.");
@@ -282,7 +526,7 @@ impl Player for CliPlayer {
                         std::println!("✗ You already missed that location! Choose another target.");
                         continue;
                     }
-                    return (r, c);
+                    return target;
                 }
                 Err(e) => {
                     std::println!("✗ Invalid coordinate: {}", e);
@@ -292,15 +536,16 @@ impl Player for CliPlayer {
         }
     }
 
-    fn handle_guess_result(&mut self, coord: (usize, usize), result: GuessResult) {
+    fn handle_guess_result(&mut self, coord: Coord, result: GuessResult) {
+        self.adviser.handle_guess_result(coord, result);
         match result {
             GuessResult::Hit => {
                 std::println!("\n🎯 HIT! Your shot at {} struck an enemy ship!", 
-                    coord_to_string(coord.0, coord.1));
+                    coord);
             }
             GuessResult::Miss => {
                 std::println!("\n💧 Miss. Your shot at {} hit only water.", 
-                    coord_to_string(coord.0, coord.1));
+                    coord);
             }
             GuessResult::Sink(name) => {
                 std::println!("\n💥 SUNK! You destroyed the enemy's {}!", name);
@@ -308,22 +553,26 @@ impl Player for CliPlayer {
         }
     }
 
-    fn handle_opponent_guess(&mut self, coord: (usize, usize), result: GuessResult) {
+    fn handle_opponent_guess(&mut self, coord: Coord, result: GuessResult) {
         match result {
             GuessResult::Hit => {
                 std::println!("\n⚠️  ENEMY HIT! They struck your ship at {}", 
-                    coord_to_string(coord.0, coord.1));
+                    coord);
             }
             GuessResult::Miss => {
                 std::println!("\n✓ Enemy missed at {}", 
-                    coord_to_string(coord.0, coord.1));
+                    coord);
             }
             GuessResult::Sink(name) => {
                 std::println!("\n💀 SHIP LOST! Enemy destroyed your {} at {}", 
-                    name, coord_to_string(coord.0, coord.1));
+                    name, coord);
             }
         }
     }
+
+    fn resigned(&self) -> bool {
+        self.resigned
+    }
 }
 
 fn print_placement_help() {
@@ -347,6 +596,9 @@ fn print_placement_help() {
     std::println!("║                                                        ║");
     std::println!("║ Press ENTER without input for automatic random        ║");
     std::println!("║ placement of the current ship.                        ║");
+    std::println!("║                                                        ║");
+    std::println!("║ Type 'undo' to pick up the last ship placed and       ║");
+    std::println!("║ 'redo' to put it back where it was.                   ║");
     std::println!("╚════════════════════════════════════════════════════════╝\n");
 }
 
@@ -370,6 +622,9 @@ fn print_targeting_help() {
     std::println!("║   . = Unknown (not yet targeted)                      ║");
     std::println!("║                                                        ║");
     std::println!("║ The AI suggestion is based on probability analysis    ║");
-    std::println!("║ of possible ship placements. Press ENTER to use it.   ║");
+    std::println!("║ of possible ship placements. Press ENTER to use it,   ║");
+    std::println!("║ or type 'why' to see how it was chosen.               ║");
+    std::println!("║                                                        ║");
+    std::println!("║ Type 'resign' to concede the game.                    ║");
     std::println!("╚════════════════════════════════════════════════════════╝\n");
 }
@@ -0,0 +1,143 @@
+//! Cursor targeting for [`CliPlayer`](super::CliPlayer) (`tui` feature).
+//!
+//! The opponent grid is drawn in place and redrawn as the player moves a
+//! cursor with the arrow keys (or `hjkl`); Enter fires at the highlighted
+//! cell. The terminal is in raw mode only while a target is being chosen.
+
+#![cfg(feature = "tui")]
+
+use crate::core::{config::BoardBits, coord::Coord};
+use crossterm::{
+    cursor::{Hide, MoveToPreviousLine, Show},
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    queue,
+    style::{Print, Stylize},
+    terminal::{self, Clear, ClearType},
+};
+use std::io::{self, IsTerminal, Write};
+
+type BB = BoardBits;
+
+/// Lines drawn per frame for a `size`-row board: help, column header, rows,
+/// status.
+fn frame_lines(size: usize) -> u16 {
+    size as u16 + 3
+}
+
+/// Whether the terminal can run cursor targeting. Piped input and `dumb`
+/// terminals get text entry instead.
+pub fn supported() -> bool {
+    let dumb = std::env::var("TERM").map_or(cfg!(unix), |term| term.is_empty() || term == "dumb");
+    io::stdin().is_terminal() && io::stdout().is_terminal() && !dumb
+}
+
+/// Leaves raw mode and shows the cursor again, even on early return.
+struct RawMode;
+
+impl RawMode {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let guard = RawMode;
+        queue!(io::stdout(), Hide)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let mut out = io::stdout();
+        let _ = queue!(out, Show);
+        let _ = out.flush();
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Let the player pick an unguessed cell of a `size`×`size` board with the
+/// cursor, starting on `start`. Returns `None` if they asked to type
+/// coordinates instead.
+pub fn select_target(hits: &BB, misses: &BB, start: Coord, size: usize) -> io::Result<Option<Coord>> {
+    let guessed = |r: usize, c: usize| hits.get(r, c).unwrap_or(false) || misses.get(r, c).unwrap_or(false);
+    let mut out = io::stdout();
+    let raw = RawMode::enter()?;
+    let Coord { mut row, mut col } = start;
+    let mut status = String::new();
+    let mut first = true;
+
+    loop {
+        draw(&mut out, hits, misses, (row, col), size, &status, first)?;
+        first = false;
+        status.clear();
+
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind == KeyEventKind::Release {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                drop(raw);
+                std::process::exit(130);
+            }
+            KeyCode::Up | KeyCode::Char('k') => row = row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => row = (row + 1).min(size - 1),
+            KeyCode::Left | KeyCode::Char('h') => col = col.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => col = (col + 1).min(size - 1),
+            KeyCode::Char('s') => Coord { row, col } = start,
+            KeyCode::Esc | KeyCode::Char('t') => return Ok(None),
+            KeyCode::Enter | KeyCode::Char(' ') => {
+                if guessed(row, col) {
+                    status = format!("{} was already targeted; choose another cell", Coord::new(row, col));
+                } else {
+                    return Ok(Some(Coord::new(row, col)));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn draw(
+    out: &mut io::Stdout,
+    hits: &BB,
+    misses: &BB,
+    cursor: (usize, usize),
+    size: usize,
+    status: &str,
+    first: bool,
+) -> io::Result<()> {
+    if !first {
+        queue!(out, MoveToPreviousLine(frame_lines(size)))?;
+    }
+    let mut lines = Vec::with_capacity(frame_lines(size) as usize);
+    lines.push("Arrows/hjkl move, Enter fires, s = AI suggestion, t = type coordinates".to_string());
+    let header: String = (0..size).map(|c| format!(" {}", (b'A' + c as u8) as char)).collect();
+    lines.push(format!("    {}", header));
+    for r in 0..size {
+        let mut line = format!(" {:2}", r + 1);
+        for c in 0..size {
+            let ch = if hits.get(r, c).unwrap_or(false) {
+                'X'
+            } else if misses.get(r, c).unwrap_or(false) {
+                'o'
+            } else {
+                '.'
+            };
+            line.push(' ');
+            if (r, c) == cursor {
+                line.push_str(&ch.to_string().reverse().to_string());
+            } else {
+                line.push(ch);
+            }
+        }
+        lines.push(line);
+    }
+    lines.push(if status.is_empty() {
+        format!("Target: {}", Coord::from(cursor))
+    } else {
+        status.to_string()
+    });
+
+    for line in lines {
+        queue!(out, Clear(ClearType::CurrentLine), Print(line), Print("\r\n"))?;
+    }
+    out.flush()
+}
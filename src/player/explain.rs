@@ -0,0 +1,152 @@
+//! Why an [`AiPlayer`](super::AiPlayer) chose its last target.
+
+use core::fmt;
+
+use crate::core::{config::MAX_BOARD_SIZE, coord::Coord};
+
+const GRID_SIZE: usize = MAX_BOARD_SIZE as usize;
+
+/// Most candidate cells an [`Explanation`] ranks.
+pub const EXPLAIN_TOP: usize = 5;
+
+/// How the AI went about choosing a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TargetPhase {
+    /// Every hit belongs to a sunk ship, so the whole board is searched.
+    Hunt,
+    /// Probing around hits not yet put down to a sunk ship.
+    Target,
+    /// Shooting where the endgame search expects to sink the last ship
+    /// soonest.
+    Endgame,
+}
+
+/// A cell the AI weighed, with its share of the final density.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Candidate {
+    pub coord: Coord,
+    pub probability: f64,
+}
+
+/// Masks that narrowed the density before a cell was picked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MaskConstraints {
+    /// Cells already shot or otherwise ruled out.
+    pub ruled_out: usize,
+    /// Whether cells beside sunk ships were ruled out, as the ships may not
+    /// touch.
+    pub no_touching: bool,
+    /// Whether hunting kept to a lattice spaced by the shortest ship left,
+    /// as [`AiConfig::parity`](crate::AiConfig::parity) asks.
+    pub parity: bool,
+    /// Cells target mode narrowed the shot to; zero while hunting.
+    pub follow_up: usize,
+    /// Whether the opponent profile reweighted the density towards where
+    /// its ships used to be.
+    pub hunting_prior: bool,
+}
+
+/// One turn of an [`AiPlayer`](super::AiPlayer)'s targeting, as
+/// [`last_explanation`](super::AiPlayer::last_explanation) returns it.
+///
+/// Displays as a few lines of text suitable for a "why" hint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Explanation {
+    pub phase: TargetPhase,
+    pub constraints: MaskConstraints,
+    /// The cell fired at; with sampling, not always the most probable one.
+    pub chosen: Coord,
+    /// Shots the endgame search expected to need, in the endgame phase.
+    pub expected_shots: Option<f64>,
+    top: [Candidate; EXPLAIN_TOP],
+    top_len: usize,
+}
+
+impl Explanation {
+    /// Explanation without ranked candidates, as for an endgame shot.
+    pub(crate) fn new(phase: TargetPhase, constraints: MaskConstraints, chosen: Coord) -> Self {
+        Self {
+            phase,
+            constraints,
+            chosen,
+            expected_shots: None,
+            top: [Candidate { coord: Coord::new(0, 0), probability: 0.0 }; EXPLAIN_TOP],
+            top_len: 0,
+        }
+    }
+
+    /// Rank the cells of the density the target was picked from, highest
+    /// first and ties in row-major order.
+    pub(crate) fn ranking(mut self, pdf: &[[f64; GRID_SIZE]; GRID_SIZE]) -> Self {
+        let total: f64 = pdf.iter().flatten().sum();
+        if total <= 0.0 {
+            return self;
+        }
+        for (row, col) in (0..GRID_SIZE).flat_map(|r| (0..GRID_SIZE).map(move |c| (r, c))) {
+            let probability = pdf[row][col] / total;
+            if probability <= 0.0 {
+                continue;
+            }
+            let at = self.top[..self.top_len].iter().position(|c| probability > c.probability);
+            let at = match at {
+                Some(at) => at,
+                None if self.top_len < EXPLAIN_TOP => self.top_len,
+                None => continue,
+            };
+            self.top_len = (self.top_len + 1).min(EXPLAIN_TOP);
+            self.top.copy_within(at..self.top_len - 1, at + 1);
+            self.top[at] = Candidate { coord: Coord::new(row, col), probability };
+        }
+        self
+    }
+
+    /// Shots expected to sink the last ship, for an endgame shot.
+    pub(crate) fn expecting(mut self, shots: f64) -> Self {
+        self.expected_shots = Some(shots);
+        self
+    }
+
+    /// The most probable cells, highest first; empty for an endgame shot.
+    pub fn top(&self) -> &[Candidate] {
+        &self.top[..self.top_len]
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = &self.constraints;
+        match self.phase {
+            TargetPhase::Hunt => writeln!(f, "Hunting: no hit is left unresolved, so any open cell may hold a ship.")?,
+            TargetPhase::Target => {
+                writeln!(f, "Target mode: probing {} cells beside hits not yet put down to a sunk ship.", c.follow_up)?
+            }
+            TargetPhase::Endgame => {
+                write!(f, "Endgame: every placement of the last ship searched")?;
+                if let Some(shots) = self.expected_shots {
+                    write!(f, ", {:.2} shots expected to sink it", shots)?;
+                }
+                writeln!(f, ".")?;
+            }
+        }
+        write!(f, "Ruled out {} cells already shot", c.ruled_out)?;
+        if c.no_touching {
+            write!(f, " and those beside sunk ships")?;
+        }
+        writeln!(f, ".")?;
+        if c.parity {
+            writeln!(f, "Kept to a lattice spaced by the shortest ship left.")?;
+        }
+        if c.hunting_prior {
+            writeln!(f, "Weighted towards where the opponent's ships used to be.")?;
+        }
+        if self.top_len > 0 {
+            write!(f, "Most likely:")?;
+            for (i, candidate) in self.top().iter().enumerate() {
+                let sep = if i == 0 { " " } else { ", " };
+                write!(f, "{}{} {:.1}%", sep, candidate.coord, 100.0 * candidate.probability)?;
+            }
+            writeln!(f)?;
+        }
+        write!(f, "Chose {}.", self.chosen)
+    }
+}
@@ -2,19 +2,22 @@
 //!
 //! This module defines the Player trait and provides concrete implementations:
 //! - AiPlayer: Probability-based AI using hunt/target mode
-//! - CliPlayer: Interactive command-line player
+//! - CliPlayer: Interactive command-line player, with cursor targeting
+//!   under the `tui` feature
+//! - CheatingAiPlayer: Omniscient test opponent (`debug-tools` feature)
 //! - PlayerNode: Orchestrator combining Player + GameEngine + Transport
 
 use crate::core::{
-    bitboard::BitBoard,
     board::Board,
     common::GuessResult,
-    config::{BOARD_SIZE, NUM_SHIPS},
+    config::BoardBits,
+    coord::Coord,
     BoardError,
 };
+use core::sync::atomic::AtomicBool;
 use rand::rngs::SmallRng;
 
-type BB = BitBoard<u128, { BOARD_SIZE as usize }>;
+type BB = BoardBits;
 
 /// Interface implemented by different player types.
 ///
@@ -26,32 +29,64 @@ pub trait Player: Send {
     /// Place all ships onto the provided board.
     fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError>;
 
-    /// Choose the next target coordinate given guess history and remaining enemy ships.
+    /// Place all ships as [`Player::place_ships`] does, giving up with
+    /// [`BoardError::PlacementCancelled`] once `cancel` is set. Players that
+    /// never wait on anyone can ignore the flag.
+    fn place_ships_cancellable(
+        &mut self,
+        rng: &mut SmallRng,
+        board: &mut Board,
+        cancel: &AtomicBool,
+    ) -> Result<(), BoardError> {
+        let _ = cancel;
+        self.place_ships(rng, board)
+    }
+
+    /// Choose the next target coordinate given guess history and the lengths
+    /// of the enemy ships still afloat, zero for sunk ships.
     fn select_target(
         &mut self,
         rng: &mut SmallRng,
         hits: &BB,
         misses: &BB,
-        remaining: &[usize; NUM_SHIPS as usize],
-    ) -> (usize, usize);
+        remaining: &[usize],
+    ) -> Coord;
 
     /// Inform the player of the result of its last guess.
-    fn handle_guess_result(&mut self, _coord: (usize, usize), _result: GuessResult) {}
+    fn handle_guess_result(&mut self, _coord: Coord, _result: GuessResult) {}
 
     /// Inform the player of an opponent guess against its board.
-    fn handle_opponent_guess(&mut self, _coord: (usize, usize), _result: GuessResult) {}
+    fn handle_opponent_guess(&mut self, _coord: Coord, _result: GuessResult) {}
+
+    /// Whether the player has conceded the game. Checked after each
+    /// `select_target`; the target returned alongside a resignation is
+    /// never fired.
+    fn resigned(&self) -> bool {
+        false
+    }
 }
 
 // Re-export implementations
 pub mod ai;
-pub use ai::AiPlayer;
+pub use ai::{AiDebugState, AiPlayer};
+
+pub mod explain;
+pub use explain::{Candidate, Explanation, MaskConstraints, TargetPhase, EXPLAIN_TOP};
+
+#[cfg(feature = "debug-tools")]
+pub mod cheating;
+#[cfg(feature = "debug-tools")]
+pub use cheating::CheatingAiPlayer;
 
 #[cfg(feature = "std")]
 pub mod cli;
 #[cfg(feature = "std")]
 pub use cli::CliPlayer;
 
+#[cfg(feature = "tui")]
+pub mod cursor;
+
 #[cfg(feature = "std")]
 pub mod node;
 #[cfg(feature = "std")]
-pub use node::PlayerNode;
+pub use node::{PlayerNode, SeriesResult};
@@ -3,137 +3,718 @@
 extern crate alloc;
 
 use alloc::boxed::Box;
+use core::ops::RangeInclusive;
 use rand::rngs::SmallRng;
+use tokio::sync::broadcast;
 
 use crate::{
     core::{
         common::GuessResult,
-        config::ship_name_static,
-        game::GameStatus,
+        coord::Coord,
+        events::{EventListener, GameEvent},
+        board::{Board, BoardState},
+        game::{DuplicateGuessPolicy, GameStatus, Stats, TimeoutAction},
         GameEngine,
     },
-    domain::GuessResult as DomainGuessResult,
-    protocol::{Message, PROTOCOL_VERSION},
+    domain::{ErrorCode, GameConfig, GuessResult as DomainGuessResult, RemoteError},
+    protocol::{
+        coin_flip,
+        commitment::{self, LayoutCommitment},
+        setup::{self, SetupConfig},
+        handshake, validation, CheatDetected, Message, Role, PROTOCOL_VERSION,
+    },
     transport::Transport,
 };
 
-use super::Player;
+use super::{AiPlayer, Player};
+
+/// Events buffered per subscriber before slow receivers start lagging.
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
 
 pub struct PlayerNode {
     player: Box<dyn Player>,
     engine: GameEngine,
     transport: Box<dyn Transport>,
+    first_move: Option<bool>,
+    setup_config: SetupConfig,
+    events: broadcast::Sender<GameEvent>,
+    /// Request the peer may still send for a turn that timed out.
+    stale_seq: Option<u64>,
+    /// The peer's fleet and stats as its `GameOver` reported them.
+    opponent: Option<(Board, Stats)>,
+    /// Protocol versions offered in the handshake.
+    versions: RangeInclusive<u8>,
+    /// Protocol version agreed in the handshake.
+    version: u8,
+    /// How the peer answers our repeated guesses, as agreed with it.
+    duplicate_policy: DuplicateGuessPolicy,
 }
 
 impl PlayerNode {
     pub fn new(player: Box<dyn Player>, engine: GameEngine, transport: Box<dyn Transport>) -> Self {
         Self {
             player,
+            duplicate_policy: engine.duplicate_policy(),
             engine,
             transport,
+            first_move: None,
+            setup_config: SetupConfig::default(),
+            events: broadcast::channel(EVENT_CHANNEL_CAPACITY).0,
+            stale_seq: None,
+            opponent: None,
+            versions: handshake::SUPPORTED_VERSIONS,
+            version: PROTOCOL_VERSION,
         }
     }
 
-    /// Perform handshake to establish protocol version.
-    async fn handshake(&mut self, initiator: bool) -> anyhow::Result<()> {
-        if initiator {
-            // Send handshake
-            self.transport
-                .send(Message::Handshake {
-                    version: PROTOCOL_VERSION,
-                })
-                .await?;
-            
-            // Wait for ack
-            let reply = self.transport.recv().await?;
-            match reply {
-                Message::HandshakeAck { version } if version == PROTOCOL_VERSION => Ok(()),
-                Message::HandshakeAck { version } => {
-                    eprintln!(
-                        "[PlayerNode] Handshake protocol version mismatch: expected {}, peer responded with {}",
-                        PROTOCOL_VERSION, version
-                    );
-                    Err(anyhow::anyhow!(
-                        "Protocol version mismatch in HandshakeAck: expected {}, got {}",
-                        PROTOCOL_VERSION,
-                        version
-                    ))
-                }
-                other => {
-                    eprintln!(
-                        "[PlayerNode] Expected HandshakeAck, got unexpected message: {:?}",
-                        other
-                    );
-                    Err(anyhow::anyhow!("Expected HandshakeAck, got unexpected message (closing session)"))
+    /// Receive every [`GameEvent`] this node publishes from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<GameEvent> {
+        self.events.subscribe()
+    }
+
+    fn emit(&mut self, event: GameEvent) {
+        self.events.on_event(&event);
+    }
+
+    /// Override the placement-phase progress interval and timeout.
+    pub fn with_setup_config(mut self, setup_config: SetupConfig) -> Self {
+        self.setup_config = setup_config;
+        self
+    }
+
+    /// Offer only protocol versions `min..=max` in the handshake, as a build
+    /// that predates the newer ones would. Versions outside
+    /// [`handshake::SUPPORTED_VERSIONS`] are dropped from the range.
+    pub fn with_protocol_versions(mut self, min: u8, max: u8) -> Self {
+        let supported = handshake::SUPPORTED_VERSIONS;
+        self.versions = min.max(*supported.start())..=max.min(*supported.end());
+        self
+    }
+
+    /// Tell the peer why we are about to close the session. Best effort: the
+    /// session is closing anyway, so a failed send is ignored.
+    async fn report(&mut self, seq: u64, code: ErrorCode, detail: String) {
+        let _ = self
+            .transport
+            .send(Message::Error {
+                version: self.version,
+                seq,
+                code,
+                detail,
+            })
+            .await;
+    }
+
+    /// The peer called the game off: mark our engine aborted and acknowledge
+    /// request `seq`. Abort is final, so its sequence number is not checked.
+    async fn accept_abort(&mut self, seq: u64, reason: String) -> anyhow::Result<()> {
+        tracing::info!(seq, %reason, "peer aborted the game");
+        self.engine.abort();
+        self.transport
+            .send(Message::Ack {
+                version: self.version,
+                seq,
+            })
+            .await?;
+        self.emit(GameEvent::GameOver {
+            status: GameStatus::Aborted,
+        });
+        Ok(())
+    }
+
+    /// Our player conceded: send `Resign` in place of request `seq`'s shot
+    /// and wait for the peer to acknowledge it. A resignation from the peer
+    /// that crosses ours draws the game.
+    async fn resign(&mut self, seq: u64) -> anyhow::Result<()> {
+        tracing::info!(seq, "resigning the game");
+        self.engine.resign();
+        self.transport
+            .send(Message::Resign {
+                version: self.version,
+                seq,
+            })
+            .await?;
+        let mut timed_out = false;
+        loop {
+            match self.transport.recv().await? {
+                Message::Ack { seq: ack_seq, .. } if ack_seq == seq => break,
+                // Our clock ran out first; a forfeit ends the game all the same
+                Message::TurnTimeout { seq: t, shots, .. } if t == seq && shots.is_empty() => break,
+                // The peer played our turn before it saw the resignation, and
+                // may fire its own before acknowledging it. The shots it
+                // fired for us still count; the reveal checks their results
+                Message::TurnTimeout {
+                    seq: t, shots, results, ..
+                } if t == seq => {
+                    for (&(x, y), res) in shots.iter().zip(&results) {
+                        let res = validation::validate_late_result(&self.engine, res).map_err(|v| anyhow::anyhow!(v))?;
+                        self.engine
+                            .record_guess(x as usize, y as usize, res)
+                            .map_err(|e| anyhow::anyhow!(e))?;
+                    }
+                    timed_out = true;
                 }
-            }
-        } else {
-            // Wait for handshake
-            let msg = self.transport.recv().await?;
-            match msg {
-                Message::Handshake { version } if version == PROTOCOL_VERSION => {
-                    // Send ack
+                Message::Guess { .. } | Message::GuessSalvo { .. } if timed_out => {}
+                // The peer conceded the turn it took after playing ours
+                Message::Resign { seq: r, .. } if timed_out || Some(r) == self.stale_seq => {
+                    tracing::info!(seq = r, "peer resigned at the same time");
+                    self.engine.record_crossed_resignation();
                     self.transport
-                        .send(Message::HandshakeAck {
-                            version: PROTOCOL_VERSION,
+                        .send(Message::Ack {
+                            version: self.version,
+                            seq: r,
                         })
                         .await?;
-                    Ok(())
                 }
-                Message::Handshake { version } => {
-                    eprintln!(
-                        "[PlayerNode] Handshake protocol version mismatch: expected {}, peer sent {}",
-                        PROTOCOL_VERSION, version
-                    );
-                    Err(anyhow::anyhow!(
-                        "Protocol version mismatch in Handshake: expected {}, got {}",
-                        PROTOCOL_VERSION,
-                        version
-                    ))
+                Message::Error { code, detail, .. } => {
+                    eprintln!("[PlayerNode] Peer reported {:?} for our resignation: {}", code, detail);
+                    self.emit(GameEvent::GameOver {
+                        status: GameStatus::Forfeited,
+                    });
+                    return Err(RemoteError { code, detail }.into());
                 }
                 other => {
-                    eprintln!(
-                        "[PlayerNode] Expected Handshake, got unexpected message: {:?}",
+                    self.emit(GameEvent::GameOver {
+                        status: GameStatus::Forfeited,
+                    });
+                    return Err(anyhow::anyhow!(
+                        "Expected Ack for resignation, got {:?} (closing session)",
                         other
-                    );
-                    Err(anyhow::anyhow!("Expected Handshake, got unexpected message (closing session)"))
+                    ));
                 }
             }
         }
+        self.emit(GameEvent::GameOver {
+            status: self.engine.status(),
+        });
+        Ok(())
+    }
+
+    /// The peer conceded in place of request `seq`'s shot: record the win
+    /// and acknowledge it.
+    async fn accept_resignation(&mut self, seq: u64) -> anyhow::Result<()> {
+        tracing::info!(seq, "peer resigned the game");
+        self.engine.record_resignation();
+        self.transport
+            .send(Message::Ack {
+                version: self.version,
+                seq,
+            })
+            .await?;
+        self.emit(GameEvent::GameOver {
+            status: GameStatus::Won,
+        });
+        Ok(())
     }
 
-    pub async fn run(&mut self, rng: &mut SmallRng, first_move: bool) -> anyhow::Result<()> {
+    /// Receive the peer's next message, dropping a shot it sent for a turn
+    /// we already played on its behalf.
+    async fn recv(&mut self) -> anyhow::Result<Message> {
+        loop {
+            match self.transport.recv().await? {
+                Message::Guess { seq, .. } | Message::GuessSalvo { seq, .. } if Some(seq) == self.stale_seq => {
+                    tracing::debug!(seq, "dropping shot sent after the turn timed out");
+                }
+                msg => return Ok(msg),
+            }
+        }
+    }
+
+    /// Receive the peer's next request, or `None` if the game has a turn
+    /// clock and it runs out first.
+    async fn recv_request(&mut self) -> anyhow::Result<Option<Message>> {
+        match self.engine.turn_clock() {
+            Some(clock) => match tokio::time::timeout(clock.limit(), self.recv()).await {
+                Ok(msg) => msg.map(Some),
+                Err(_) => Ok(None),
+            },
+            None => self.recv().await.map(Some),
+        }
+    }
+
+    /// The peer let its turn clock run out on request `seq`: forfeit it, or
+    /// fire its turn at random on its behalf, and tell it with
+    /// `TurnTimeout`. Returns whether the shots fired for it keep its turn,
+    /// or `None` if it forfeited.
+    async fn time_out_turn(&mut self, rng: &mut SmallRng, seq: u64) -> anyhow::Result<Option<bool>> {
+        let action = self.engine.turn_clock().map_or(TimeoutAction::Forfeit, |clock| clock.on_timeout);
+        tracing::info!(seq, ?action, "peer's turn clock ran out");
+        self.emit(GameEvent::TurnTimedOut { ours: false });
+        let mut shots = Vec::new();
+        let mut results = Vec::new();
+        let mut keep_turn = false;
+        match action {
+            TimeoutAction::Forfeit => self.engine.record_resignation(),
+            TimeoutAction::RandomMove => {
+                for _ in 0..self.engine.opponent_shots_per_turn() {
+                    if self.engine.status() != GameStatus::InProgress {
+                        break;
+                    }
+                    let (r, c, res) = self.engine.play_for_opponent(rng).map_err(|e| anyhow::anyhow!(e))?;
+                    self.announce_opponent_shot((r, c), res, false);
+                    keep_turn |= self.engine.turn_policy().keeps_turn(res);
+                    shots.push((r as u8, c as u8));
+                    results.push(DomainGuessResult::from(res));
+                }
+            }
+        }
+        self.stale_seq = Some(seq);
+        self.transport
+            .send(Message::TurnTimeout {
+                version: self.version,
+                seq,
+                shots,
+                results,
+            })
+            .await?;
+        if action == TimeoutAction::Forfeit {
+            self.emit(GameEvent::GameOver {
+                status: GameStatus::Won,
+            });
+            return Ok(None);
+        }
+        Ok(Some(keep_turn))
+    }
+
+    /// The peer answered our request `seq` with `TurnTimeout`: forfeit if
+    /// it sent no shots, otherwise record the shots it fired for us.
+    /// Returns whether they keep our turn, or `None` if we forfeited.
+    async fn accept_timeout(
+        &mut self,
+        seq: u64,
+        shots: &[(u8, u8)],
+        results: &[DomainGuessResult],
+    ) -> anyhow::Result<Option<bool>> {
+        tracing::info!(seq, shots = shots.len(), "our turn clock ran out");
+        self.emit(GameEvent::TurnTimedOut { ours: true });
+        if shots.is_empty() {
+            self.engine.resign();
+            self.emit(GameEvent::GameOver {
+                status: GameStatus::Forfeited,
+            });
+            return Ok(None);
+        }
+        if shots.len() != results.len() {
+            let v = validation::ProtocolViolation::SalvoSize {
+                expected: shots.len(),
+                got: results.len(),
+            };
+            eprintln!("[PlayerNode] Protocol violation in TurnTimeout (seq: {}): {}", seq, v);
+            self.report(seq, v.code(), v.to_string()).await;
+            return Err(anyhow::anyhow!("Protocol violation: {} (closing session)", v));
+        }
+        let mut keep_turn = false;
+        for (&(x, y), res) in shots.iter().zip(results) {
+            keep_turn |= self.apply_result(seq, (x as usize, y as usize), res).await?;
+        }
+        Ok(Some(keep_turn))
+    }
+
+    /// Check the version and sequence number of the peer's `kind` request,
+    /// reporting a mismatch to the peer before failing.
+    async fn check_request(&mut self, kind: &str, version: u8, seq: u64, expected_seq: u64) -> anyhow::Result<()> {
+        if version != self.version {
+            eprintln!(
+                "[PlayerNode] Protocol version mismatch in {}: expected {}, got {} (seq: {})",
+                kind, self.version, version, seq
+            );
+            self.report(
+                seq,
+                ErrorCode::VersionMismatch,
+                format!("expected protocol version {}, got {}", self.version, version),
+            )
+            .await;
+            return Err(anyhow::anyhow!(
+                "Protocol version mismatch in {}: expected {}, got {}",
+                kind,
+                self.version,
+                version
+            ));
+        }
+        if seq != expected_seq {
+            eprintln!("[PlayerNode] Out-of-order {}: expected seq {}, got {}", kind, expected_seq, seq);
+            self.report(seq, ErrorCode::BadSeq, format!("expected seq {}, got {}", expected_seq, seq))
+                .await;
+            return Err(anyhow::anyhow!(
+                "Out-of-order {}: expected seq {}, got {}",
+                kind,
+                expected_seq,
+                seq
+            ));
+        }
+        Ok(())
+    }
+
+    /// Check the peer's answer to our shot at (r, c) from request `seq` and
+    /// record it. Returns whether the shot keeps our turn.
+    async fn apply_result(&mut self, seq: u64, (r, c): (usize, usize), res_domain: &DomainGuessResult) -> anyhow::Result<bool> {
+        // A repeat is only sent when the duplicate guess policy allows it
+        let repeat = self.engine.already_guessed(r, c) && self.duplicate_policy != DuplicateGuessPolicy::Error;
+        let checked = if repeat {
+            validation::validate_repeat(&self.engine, self.duplicate_policy, r, c, res_domain)
+        } else {
+            validation::validate_result(&self.engine, res_domain)
+        };
+        let res_common = match checked {
+            Ok(res) => res,
+            Err(v) => {
+                eprintln!("[PlayerNode] Protocol violation in result for ({}, {}): {}", r, c, v);
+                self.report(seq, v.code(), v.to_string()).await;
+                return Err(anyhow::anyhow!("Protocol violation: {} (closing session)", v));
+            }
+        };
+        self.engine
+            .record_guess(r, c, res_common)
+            .map_err(|e| anyhow::anyhow!(e))?;
+        if !repeat {
+            self.player.handle_guess_result(Coord::new(r, c), res_common);
+        }
+        self.emit(GameEvent::ResultReceived {
+            row: r,
+            col: c,
+            result: res_common,
+        });
+        if let (GuessResult::Sink(ship), false) = (res_common, repeat) {
+            self.emit(GameEvent::Sink { ship, ours: false });
+        }
+        Ok(!repeat && self.engine.turn_policy().keeps_turn(res_common))
+    }
+
+    /// Resolve the peer's shot at `target` from request `seq` on our board.
+    /// Returns the result and whether the shot keeps the peer's turn.
+    async fn answer_shot(&mut self, seq: u64, target: Coord) -> anyhow::Result<(GuessResult, bool)> {
+        let Coord { row: gr, col: gc } = match validation::validate_guess(&self.engine, target) {
+            Ok(cell) => cell,
+            Err(v) => {
+                eprintln!("[PlayerNode] Protocol violation in Guess (seq: {}): {}", seq, v);
+                self.report(seq, v.code(), v.to_string()).await;
+                return Err(anyhow::anyhow!("Protocol violation: {} (closing session)", v));
+            }
+        };
+        let repeat = self.engine.opponent_guessed(gr, gc);
+        let res_common = match self.engine.opponent_guess(gr, gc) {
+            Ok(res) => res,
+            Err(e) => {
+                eprintln!("[PlayerNode] Rejected Guess (seq: {}) at ({}, {}): {}", seq, gr, gc, e);
+                self.report(seq, ErrorCode::RuleViolation, e.to_string()).await;
+                return Err(anyhow::anyhow!("Rejected guess at ({}, {}): {} (closing session)", gr, gc, e));
+            }
+        };
+        self.announce_opponent_shot((gr, gc), res_common, repeat);
+        Ok((res_common, !repeat && self.engine.turn_policy().keeps_turn(res_common)))
+    }
+
+    /// Tell our player and subscribers about a shot resolved on our board.
+    fn announce_opponent_shot(&mut self, (r, c): (usize, usize), res: GuessResult, repeat: bool) {
+        if !repeat {
+            self.player.handle_opponent_guess(Coord::new(r, c), res);
+        }
+        self.emit(GameEvent::OpponentGuess { row: r, col: c, result: res });
+        if let (GuessResult::Sink(ship), false) = (res, repeat) {
+            self.emit(GameEvent::Sink { ship, ours: true });
+        }
+    }
+
+    /// Compare the digest the peer sent with its answer to request `seq`
+    /// against the one our state implies, so a divergence surfaces on the
+    /// turn it happens.
+    async fn check_digest(&mut self, seq: u64, digest: Option<u64>) -> anyhow::Result<()> {
+        let Some(theirs) = digest else {
+            return Ok(());
+        };
+        let ours = self.engine.state().mirrored_digest();
+        if theirs == ours {
+            return Ok(());
+        }
+        eprintln!(
+            "[PlayerNode] State digest mismatch after seq {}: peer {:016x}, expected {:016x}",
+            seq, theirs, ours
+        );
+        self.report(
+            seq,
+            ErrorCode::InvalidState,
+            format!("state digest {:016x} does not match {:016x}", theirs, ours),
+        )
+        .await;
+        self.emit(GameEvent::GameOver {
+            status: GameStatus::Desync,
+        });
+        Err(anyhow::anyhow!(
+            "Game state desync: peer state digest differs after seq {} (closing session)",
+            seq
+        ))
+    }
+
+    /// Fire this turn's salvo as request `seq` and record the answers.
+    /// Returns whether the volley keeps our turn, or `None` if the game
+    /// ended instead: a side resigned, we forfeited on time or the peer
+    /// aborted.
+    async fn fire_salvo(&mut self, rng: &mut SmallRng, seq: u64) -> anyhow::Result<Option<bool>> {
+        let hits = self.engine.guess_hits();
+        let remaining = self.engine.enemy_ship_lengths_remaining();
+        // Cells already picked for this volley are blocked like misses
+        let mut blocked = self.engine.blocked_cells();
+        let count = self.engine.shots_per_turn();
+        let mut shots = Vec::with_capacity(count);
+        for _ in 0..count {
+            let Coord { row: r, col: c } = self.player.select_target(rng, &hits, &blocked, &remaining);
+            if self.player.resigned() {
+                self.resign(seq).await?;
+                return Ok(None);
+            }
+            let _ = blocked.set(r, c);
+            shots.push((r, c));
+        }
+        self.transport
+            .send(Message::GuessSalvo {
+                version: self.version,
+                seq,
+                shots: shots.iter().map(|&(r, c)| (r as u8, c as u8)).collect(),
+            })
+            .await?;
+        for &(row, col) in &shots {
+            self.emit(GameEvent::GuessSent { row, col });
+        }
+        let (results, digest) = match self.recv().await? {
+            Message::SalvoResp {
+                version,
+                seq: resp_seq,
+                results,
+                digest,
+            } if version == self.version && resp_seq == seq => (results, digest),
+            Message::TurnTimeout {
+                version,
+                seq: resp_seq,
+                shots: played,
+                results,
+            } if version == self.version && resp_seq == seq && self.engine.turn_clock().is_some() => {
+                return self.accept_timeout(seq, &played, &results).await;
+            }
+            Message::Resign {
+                version,
+                seq: resign_seq,
+            } if version == self.version && Some(resign_seq) == self.stale_seq => {
+                self.accept_resignation(resign_seq).await?;
+                return Ok(None);
+            }
+            Message::Error { code, detail, .. } => {
+                eprintln!("[PlayerNode] Peer reported {:?} for our salvo: {}", code, detail);
+                return Err(RemoteError { code, detail }.into());
+            }
+            Message::Abort {
+                version,
+                seq: abort_seq,
+                reason,
+            } if version == self.version => {
+                self.accept_abort(abort_seq, reason).await?;
+                return Ok(None);
+            }
+            other => {
+                eprintln!(
+                    "[PlayerNode] Unexpected message when expecting SalvoResp: {:?} (my_seq: {})",
+                    other, seq
+                );
+                return Err(anyhow::anyhow!(
+                    "Expected SalvoResp, got unexpected message type (closing session)"
+                ));
+            }
+        };
+        let mut keep_turn = false;
+        for (&cell, res) in shots.iter().zip(&results) {
+            keep_turn |= self.apply_result(seq, cell, res).await?;
+        }
+        // Only the shot that wins or draws the game may leave the rest
+        // unanswered
+        let complete = results.len() == shots.len()
+            || (results.len() < shots.len() && matches!(self.engine.status(), GameStatus::Won | GameStatus::Draw));
+        if !complete {
+            let v = validation::ProtocolViolation::SalvoSize {
+                expected: shots.len(),
+                got: results.len(),
+            };
+            eprintln!("[PlayerNode] Protocol violation in SalvoResp (seq: {}): {}", seq, v);
+            self.report(seq, v.code(), v.to_string()).await;
+            return Err(anyhow::anyhow!("Protocol violation: {} (closing session)", v));
+        }
+        self.check_digest(seq, digest).await?;
+        Ok(Some(keep_turn))
+    }
+
+    /// Answer the peer's salvo from request `seq`, resolving its shots in
+    /// order until the game is decided. Returns whether the volley keeps the
+    /// peer's turn.
+    async fn answer_salvo(&mut self, seq: u64, shots: &[(u8, u8)]) -> anyhow::Result<bool> {
+        if let Err(v) = validation::validate_salvo(&self.engine, shots) {
+            eprintln!("[PlayerNode] Protocol violation in GuessSalvo (seq: {}): {}", seq, v);
+            self.report(seq, v.code(), v.to_string()).await;
+            return Err(anyhow::anyhow!("Protocol violation: {} (closing session)", v));
+        }
+        let mut results = Vec::with_capacity(shots.len());
+        let mut keep_turn = false;
+        for &(x, y) in shots {
+            if self.engine.status() != GameStatus::InProgress {
+                break;
+            }
+            let (res, keeps) = self.answer_shot(seq, Coord::new(x as usize, y as usize)).await?;
+            keep_turn |= keeps;
+            results.push(DomainGuessResult::from(res));
+        }
+        self.transport
+            .send(Message::SalvoResp {
+                version: self.version,
+                seq,
+                results,
+                digest: Some(self.engine.state().digest()),
+            })
+            .await?;
+        Ok(keep_turn)
+    }
+
+    /// Reveal our layout and check the peer's against the digest it
+    /// committed to and the results it gave for our shots, then exchange
+    /// `GameOver` and check the peer's account of the game.
+    async fn finish_game(&mut self, role: Role, ours: &LayoutCommitment, committed: &[u8; commitment::SALT_LEN]) -> anyhow::Result<()> {
+        let (salt, layout) = commitment::exchange_reveals(self.transport.as_mut(), self.version, role, ours).await?;
+        let rules = self.engine.rules();
+        let checked = commitment::verify_reveal(committed, &salt, &layout, &rules, self.engine.history());
+        let fleet = self.check_honest(checked).await?;
+        let final_board = BoardState::from(self.engine.board());
+        let (peer_board, peer_stats) =
+            commitment::exchange_game_over(self.transport.as_mut(), self.version, role, final_board, self.engine.stats()).await?;
+        let checked = commitment::verify_game_over(&fleet, &peer_board, &peer_stats, &self.engine);
+        self.check_honest(checked).await?;
+        self.opponent = Some((fleet, peer_stats));
+        Ok(())
+    }
+
+    /// Pass on the outcome of a check of the peer's honesty, telling the
+    /// peer before failing.
+    async fn check_honest<T>(&mut self, checked: Result<T, CheatDetected>) -> anyhow::Result<T> {
+        match checked {
+            Ok(value) => Ok(value),
+            Err(cheat) => {
+                eprintln!("[PlayerNode] {}", cheat);
+                self.report(0, ErrorCode::RuleViolation, cheat.to_string()).await;
+                Err(cheat.into())
+            }
+        }
+    }
+
+    /// Play a full game over the transport.
+    ///
+    /// The handshake settles on the highest protocol version both peers
+    /// support, available from [`Self::protocol_version`]. Both peers then
+    /// exchange their game config and part unless it matches. Next they run
+    /// a commit-reveal coin flip to decide who moves first; the outcome is
+    /// available from [`Self::first_move`].
+    /// If the board is not yet placed the player places its fleet next, then
+    /// both sides exchange `Ready` and `StartGame` before the first guess.
+    /// The whole placement phase is bounded by the configured setup timeout.
+    ///
+    /// Each side then commits to its layout.
+    /// Once the game is over, unless it was aborted, both reveal their layouts and exchange `GameOver`
+    /// with their final boards and stats; a peer whose layout or report
+    /// does not bear out its commitment or its answers fails the game with
+    /// [`CheatDetected`]. The peer's fleet is then available from
+    /// [`Self::opponent_fleet`].
+    pub async fn run(&mut self, rng: &mut SmallRng, role: Role) -> anyhow::Result<()> {
+        self.opponent = None;
         // Perform handshake before starting game
-        self.handshake(first_move).await?;
-        
+        self.version = handshake::exchange_handshake(self.transport.as_mut(), role, &self.versions).await?;
+        tracing::debug!(version = self.version, "protocol version agreed");
+        self.emit(GameEvent::HandshakeComplete);
+        let config = setup::agree_config(self.transport.as_mut(), self.version, &GameConfig::from(&self.engine)).await?;
+        self.duplicate_policy = config.duplicate_policy;
+        let first_move =
+            coin_flip::negotiate_first_move(self.transport.as_mut(), self.version, rng, role).await?;
+        self.first_move = Some(first_move);
+
+        let deadline = tokio::time::Instant::now() + self.setup_config.timeout;
+        if !self.engine.board().all_placed() {
+            // Placement runs on the blocking pool, so it takes the player,
+            // board and RNG by value; a stand-in player fills the slot meanwhile.
+            let mut player = core::mem::replace(&mut self.player, Box::new(AiPlayer::new()));
+            let mut board = core::mem::take(self.engine.board_mut());
+            let mut placing_rng = rng.clone();
+            let (player, board, placing_rng, res) = setup::run_placement(
+                self.transport.as_mut(),
+                self.version,
+                &self.setup_config,
+                deadline,
+                move |cancel| {
+                    let res = player.place_ships_cancellable(&mut placing_rng, &mut board, &cancel);
+                    (player, board, placing_rng, res)
+                },
+            )
+            .await?;
+            self.player = player;
+            *self.engine.board_mut() = board;
+            *rng = placing_rng;
+            res.map_err(|e| anyhow::anyhow!(e))?;
+        }
+        setup::exchange_ready(self.transport.as_mut(), self.version, &config, role, first_move, deadline).await?;
+        let layout = LayoutCommitment::new(rng, self.engine.board());
+        let peer_digest = commitment::exchange_commitments(self.transport.as_mut(), self.version, role, layout.digest()).await?;
+        self.emit(GameEvent::GameStarted { first_move });
+
         let mut my_turn = first_move;
         let mut my_seq: u64 = 0;
         let mut expected_recv_seq: u64 = 0;
+        let salvo = self.engine.rules().salvo();
         loop {
-            if my_turn {
+            if my_turn && salvo {
+                let Some(keep_turn) = self.fire_salvo(rng, my_seq).await? else {
+                    break;
+                };
+                my_seq += 1;
+                my_turn = keep_turn;
+            } else if my_turn {
                 // Choose our guess and send to opponent
-                let (r, c) = self.player.select_target(
+                let target = self.player.select_target(
                     rng,
                     &self.engine.guess_hits(),
-                    &self.engine.guess_misses(),
+                    &self.engine.blocked_cells(),
                     &self.engine.enemy_ship_lengths_remaining(),
                 );
+                if self.player.resigned() {
+                    self.resign(my_seq).await?;
+                    break;
+                }
                 self.transport
                     .send(Message::Guess {
-                        version: PROTOCOL_VERSION,
+                        version: self.version,
                         seq: my_seq,
-                        x: r as u8,
-                        y: c as u8,
+                        target,
                     })
                     .await?;
-                let reply = self.transport.recv().await?;
-                let res_domain = match reply {
+                let Coord { row: r, col: c } = target;
+                self.emit(GameEvent::GuessSent { row: r, col: c });
+                let reply = self.recv().await?;
+                let keep_turn = match reply {
                     Message::StatusResp {
                         version,
                         seq: resp_seq,
                         res,
-                    } if resp_seq == my_seq && version == PROTOCOL_VERSION => {
-                        res
+                        digest,
+                    } if resp_seq == my_seq && version == self.version => {
+                        let keep_turn = self.apply_result(my_seq, (r, c), &res).await?;
+                        self.check_digest(my_seq, digest).await?;
+                        keep_turn
+                    }
+                    Message::TurnTimeout {
+                        version,
+                        seq: resp_seq,
+                        shots,
+                        results,
+                    } if resp_seq == my_seq && version == self.version && self.engine.turn_clock().is_some() => {
+                        match self.accept_timeout(my_seq, &shots, &results).await? {
+                            Some(keep_turn) => keep_turn,
+                            None => break,
+                        }
+                    }
+                    Message::Resign { version, seq } if version == self.version && Some(seq) == self.stale_seq => {
+                        self.accept_resignation(seq).await?;
+                        break;
                     }
                     Message::StatusResp {
                         version,
@@ -141,14 +722,14 @@ impl PlayerNode {
                         ..
                     } => {
                         // Log detailed mismatch information before closing session
-                        if version != PROTOCOL_VERSION {
+                        if version != self.version {
                             eprintln!(
                                 "[PlayerNode] Protocol version mismatch in StatusResp: expected {}, got {} (seq: {}, my_seq: {})",
-                                PROTOCOL_VERSION, version, resp_seq, my_seq
+                                self.version, version, resp_seq, my_seq
                             );
                             return Err(anyhow::anyhow!(
                                 "Protocol version mismatch in StatusResp: expected {}, got {}",
-                                PROTOCOL_VERSION,
+                                self.version,
                                 version
                             ));
                         }
@@ -166,6 +747,14 @@ impl PlayerNode {
                         // This should be unreachable due to the guard above, but handle it safely
                         return Err(anyhow::anyhow!("Unexpected StatusResp state"));
                     }
+                    Message::Error { code, detail, .. } => {
+                        eprintln!("[PlayerNode] Peer reported {:?} for our guess: {}", code, detail);
+                        return Err(RemoteError { code, detail }.into());
+                    }
+                    Message::Abort { version, seq, reason } if version == self.version => {
+                        self.accept_abort(seq, reason).await?;
+                        break;
+                    }
                     other => {
                         eprintln!(
                             "[PlayerNode] Unexpected message type when expecting StatusResp: {:?} (my_seq: {})",
@@ -177,73 +766,49 @@ impl PlayerNode {
                     }
                 };
                 my_seq += 1;
-                let res_common = match res_domain {
-                    DomainGuessResult::Hit => GuessResult::Hit,
-                    DomainGuessResult::Miss => GuessResult::Miss,
-                    DomainGuessResult::Sink(name) => {
-                        let static_name = ship_name_static(&name)
-                            .ok_or_else(|| anyhow::anyhow!("unknown ship"))?;
-                        GuessResult::Sink(static_name)
-                    }
-                };
-                self.engine
-                    .record_guess(r, c, res_common)
-                    .map_err(|e| anyhow::anyhow!(e))?;
-                self.player.handle_guess_result((r, c), res_common);
-                my_turn = false;
-            } else {
-                // Receive opponent guess and respond
-                let msg = self.transport.recv().await?;
+                my_turn = keep_turn;
+            } else if let Some(msg) = self.recv_request().await? {
+                // Respond to the opponent's guess
                 match msg {
                     Message::Guess {
                         version,
                         seq: msg_seq,
-                        x,
-                        y,
-                    } => {
-                        // Validate version
-                        if version != PROTOCOL_VERSION {
-                            eprintln!(
-                                "[PlayerNode] Protocol version mismatch in Guess: expected {}, got {} (seq: {})",
-                                PROTOCOL_VERSION, version, msg_seq
-                            );
-                            return Err(anyhow::anyhow!(
-                                "Protocol version mismatch in Guess: expected {}, got {}",
-                                PROTOCOL_VERSION,
-                                version
-                            ));
-                        }
-                        
-                        // Validate sequence number
-                        if msg_seq != expected_recv_seq {
-                            eprintln!(
-                                "[PlayerNode] Out-of-order Guess: expected seq {}, got {}",
-                                expected_recv_seq, msg_seq
-                            );
-                            return Err(anyhow::anyhow!(
-                                "Out-of-order Guess: expected seq {}, got {}",
-                                expected_recv_seq,
-                                msg_seq
-                            ));
-                        }
-                        
-                        let res_common = self
-                            .engine
-                            .opponent_guess(x as usize, y as usize)
-                            .map_err(|e| anyhow::anyhow!(e))?;
-                        self.player
-                            .handle_opponent_guess((x as usize, y as usize), res_common);
+                        target,
+                    } if !salvo => {
+                        self.check_request("Guess", version, msg_seq, expected_recv_seq).await?;
+                        let (res_common, keeps_turn) = self.answer_shot(msg_seq, target).await?;
                         let res_domain = DomainGuessResult::from(res_common);
                         self.transport
                             .send(Message::StatusResp {
-                                version: PROTOCOL_VERSION,
+                                version: self.version,
                                 seq: msg_seq,
                                 res: res_domain,
+                                digest: Some(self.engine.state().digest()),
                             })
                             .await?;
-                        my_turn = true;
+                        my_turn = !keeps_turn;
                         expected_recv_seq += 1;
                     }
+                    Message::GuessSalvo { version, seq: msg_seq, shots } if salvo => {
+                        self.check_request("GuessSalvo", version, msg_seq, expected_recv_seq).await?;
+                        my_turn = !self.answer_salvo(msg_seq, &shots).await?;
+                        expected_recv_seq += 1;
+                    }
+                    Message::Resign { version, seq: msg_seq } => {
+                        // A resignation may cross the move we played for a timed-out turn
+                        let expected = if self.stale_seq == Some(msg_seq) { msg_seq } else { expected_recv_seq };
+                        self.check_request("Resign", version, msg_seq, expected).await?;
+                        self.accept_resignation(msg_seq).await?;
+                        break;
+                    }
+                    Message::Error { code, detail, .. } => {
+                        eprintln!("[PlayerNode] Peer reported {:?}: {}", code, detail);
+                        return Err(RemoteError { code, detail }.into());
+                    }
+                    Message::Abort { version, seq, reason } if version == self.version => {
+                        self.accept_abort(seq, reason).await?;
+                        break;
+                    }
                     other => {
                         eprintln!(
                             "[PlayerNode] Unexpected message type when expecting Guess: {:?} (expected_seq: {})",
@@ -254,15 +819,152 @@ impl PlayerNode {
                         ))
                     }
                 }
+            } else {
+                // The opponent's turn clock ran out
+                let Some(keeps_turn) = self.time_out_turn(rng, expected_recv_seq).await? else {
+                    break;
+                };
+                my_turn = !keeps_turn;
+                expected_recv_seq += 1;
+            }
+
+            match self.engine.status() {
+                GameStatus::InProgress => {}
+                GameStatus::Desync => {
+                    self.emit(GameEvent::GameOver {
+                        status: GameStatus::Desync,
+                    });
+                    eprintln!(
+                        "[PlayerNode] Enemy fleet state desync: enemy ships remaining {:?}, cells remaining {}",
+                        self.engine.enemy_ships_remaining(),
+                        self.engine.enemy_cells_remaining()
+                    );
+                    return Err(anyhow::anyhow!(
+                        "Game state desync: sink reports disagree with hits recorded (closing session)"
+                    ));
+                }
+                status @ (GameStatus::Won
+                | GameStatus::Lost
+                | GameStatus::Aborted
+                | GameStatus::Forfeited
+                | GameStatus::Draw) => {
+                    self.emit(GameEvent::GameOver { status });
+                    break;
+                }
             }
+        }
+        if commitment::reveal_due(&self.engine) {
+            self.finish_game(role, &layout, &peer_digest).await?;
+        }
+        tracing::info!(
+            status = ?self.engine.status(),
+            guesses = self.guess_count(),
+            first_move,
+            "game finished"
+        );
+        Ok(())
+    }
 
-            if !matches!(self.engine.status(), GameStatus::InProgress) {
+    /// Play a best-of-`games` series against a peer running the same
+    /// series. Each game after the first is agreed with `RematchRequest` and
+    /// `RematchAccept` and played on a fresh engine with the same rules. The
+    /// series stops early once either side has won a majority, or when a
+    /// game is aborted.
+    pub async fn run_series(&mut self, rng: &mut SmallRng, role: Role, games: u32) -> anyhow::Result<SeriesResult> {
+        let majority = games as usize / 2 + 1;
+        let mut series = SeriesResult { games: Vec::new() };
+        for game in 1..=games {
+            if game > 1 {
+                self.agree_rematch(role, game).await?;
+                self.engine.reset_for_rematch();
+                self.stale_seq = None;
+            }
+            self.run(rng, role).await?;
+            let status = self.engine.status();
+            series.games.push(status);
+            tracing::info!(game, ?status, wins = series.wins(), losses = series.losses(), "series game finished");
+            if status == GameStatus::Aborted || series.wins() >= majority || series.losses() >= majority {
                 break;
             }
         }
+        tracing::info!(status = ?series.status(), games = series.games.len(), "series finished");
+        Ok(series)
+    }
+
+    /// Agree with the peer to play game `game` of a series: the initiator
+    /// asks, the responder accepts.
+    async fn agree_rematch(&mut self, role: Role, game: u32) -> anyhow::Result<()> {
+        if role == Role::Initiator {
+            self.transport
+                .send(Message::RematchRequest {
+                    version: self.version,
+                    game,
+                })
+                .await?;
+        }
+        loop {
+            match self.recv().await? {
+                Message::RematchRequest { version, game: g }
+                    if role == Role::Responder && version == self.version && g == game =>
+                {
+                    self.transport
+                        .send(Message::RematchAccept {
+                            version: self.version,
+                            game,
+                        })
+                        .await?;
+                    break;
+                }
+                Message::RematchAccept { version, game: g }
+                    if role == Role::Initiator && version == self.version && g == game =>
+                {
+                    break
+                }
+                // A resignation that crossed our win on time
+                Message::Resign { seq, .. } if Some(seq) == self.stale_seq => {}
+                Message::Error { code, detail, .. } => {
+                    eprintln!("[PlayerNode] Peer reported {:?} instead of a rematch: {}", code, detail);
+                    return Err(RemoteError { code, detail }.into());
+                }
+                other => {
+                    eprintln!(
+                        "[PlayerNode] Unexpected message when agreeing game {} of the series: {:?}",
+                        game, other
+                    );
+                    self.report(0, ErrorCode::Unexpected, format!("expected a rematch for game {}", game))
+                        .await;
+                    return Err(anyhow::anyhow!(
+                        "Expected rematch for game {}, got unexpected message type (closing session)",
+                        game
+                    ));
+                }
+            }
+        }
+        tracing::info!(game, "rematch agreed");
         Ok(())
     }
 
+    /// Whether this node moved first, once the coin flip has completed.
+    pub fn first_move(&self) -> Option<bool> {
+        self.first_move
+    }
+
+    /// Protocol version agreed in the last handshake.
+    pub fn protocol_version(&self) -> u8 {
+        self.version
+    }
+
+    /// The peer's fleet with our shots on it and the stats it reported,
+    /// once a game that was not aborted is over.
+    pub fn opponent_fleet(&self) -> Option<(&Board, &Stats)> {
+        self.opponent.as_ref().map(|(board, stats)| (board, stats))
+    }
+
+    /// The engine holding this player's board and guesses.
+    pub fn engine(&self) -> &GameEngine {
+        &self.engine
+    }
+
     /// Current status of the underlying game engine.
     pub fn status(&self) -> GameStatus {
         self.engine.status()
@@ -273,3 +975,45 @@ impl PlayerNode {
         self.engine.guess_hits().count_ones() + self.engine.guess_misses().count_ones()
     }
 }
+
+/// How a series played by [`PlayerNode::run_series`] went, from this
+/// node's side.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesResult {
+    /// How each game ended, in the order played.
+    pub games: Vec<GameStatus>,
+}
+
+impl SeriesResult {
+    /// Games we won, by sinking the enemy fleet or by the peer conceding.
+    pub fn wins(&self) -> usize {
+        self.count(|status| status == GameStatus::Won)
+    }
+
+    /// Games we lost, resignations and forfeits on time included.
+    pub fn losses(&self) -> usize {
+        self.count(|status| matches!(status, GameStatus::Lost | GameStatus::Forfeited))
+    }
+
+    /// Games drawn.
+    pub fn draws(&self) -> usize {
+        self.count(|status| status == GameStatus::Draw)
+    }
+
+    /// The series outcome: `Won` or `Lost` for the side with more wins,
+    /// `Draw` when level, or `Aborted` if the last game was called off.
+    pub fn status(&self) -> GameStatus {
+        if self.games.last() == Some(&GameStatus::Aborted) {
+            return GameStatus::Aborted;
+        }
+        match self.wins().cmp(&self.losses()) {
+            core::cmp::Ordering::Greater => GameStatus::Won,
+            core::cmp::Ordering::Less => GameStatus::Lost,
+            core::cmp::Ordering::Equal => GameStatus::Draw,
+        }
+    }
+
+    fn count(&self, pred: impl Fn(GameStatus) -> bool) -> usize {
+        self.games.iter().filter(|&&status| pred(status)).count()
+    }
+}
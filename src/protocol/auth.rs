@@ -0,0 +1,260 @@
+//! Authentication and rate limiting for lobby commands.
+//!
+//! A public lobby server puts a [`LobbyGate`] in front of its [`Lobby`]:
+//! clients must send `Authenticate` with a credential before other lobby
+//! commands are accepted (unless the server is [`LobbyAuth::Open`]), and
+//! every request counts against a per-identity [`RateLimit`].
+//!
+//! Credentials are either pre-shared tokens mapped to identities, or
+//! tickets signed with a secret shared between the server and whoever
+//! issues them (see [`issue_ticket`]).
+//!
+//! [`Lobby`]: super::lobby::Lobby
+
+use super::lobby::{LobbyError, LobbyMessage, LobbyReply};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// How lobby clients prove who they are.
+#[derive(Debug, Clone, Default)]
+pub enum LobbyAuth {
+    /// No credential needed; each connection is its own identity.
+    #[default]
+    Open,
+    /// Pre-shared tokens, each mapped to the identity it grants.
+    Tokens(HashMap<String, String>),
+    /// Tickets from [`issue_ticket`] signed with this secret.
+    Tickets { secret: Vec<u8> },
+}
+
+impl LobbyAuth {
+    /// Whether clients must authenticate before using the lobby.
+    pub fn required(&self) -> bool {
+        !matches!(self, LobbyAuth::Open)
+    }
+
+    /// Identity granted by `credential`, checking ticket expiry against
+    /// `now` (seconds since the Unix epoch).
+    pub fn verify(&self, credential: &str, now: u64) -> Result<String, LobbyError> {
+        match self {
+            LobbyAuth::Open => Err(LobbyError::Unauthorized),
+            LobbyAuth::Tokens(tokens) => tokens
+                .iter()
+                .find(|(token, _)| constant_time_eq(token.as_bytes(), credential.as_bytes()))
+                .map(|(_, identity)| identity.clone())
+                .ok_or(LobbyError::Unauthorized),
+            LobbyAuth::Tickets { secret } => {
+                let mut parts = credential.splitn(3, '.');
+                let (Some(expires), Some(mac), Some(identity)) = (parts.next(), parts.next(), parts.next()) else {
+                    return Err(LobbyError::Unauthorized);
+                };
+                let expected = ticket_mac(secret, identity, expires);
+                if !constant_time_eq(expected.as_bytes(), mac.as_bytes()) {
+                    return Err(LobbyError::Unauthorized);
+                }
+                match expires.parse::<u64>() {
+                    Ok(expires) if expires > now => Ok(identity.to_string()),
+                    _ => Err(LobbyError::Unauthorized),
+                }
+            }
+        }
+    }
+}
+
+/// Sign a ticket granting `identity` until `expires_at` (seconds since the
+/// Unix epoch). The ticket reads `expires.mac.identity`.
+pub fn issue_ticket(secret: &[u8], identity: &str, expires_at: u64) -> String {
+    let expires = expires_at.to_string();
+    let mac = ticket_mac(secret, identity, &expires);
+    format!("{}.{}.{}", expires, mac, identity)
+}
+
+fn ticket_mac(secret: &[u8], identity: &str, expires: &str) -> String {
+    let message = format!("{}.{}", expires, identity);
+    hmac_sha256(secret, message.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    const BLOCK: usize = 64;
+    let mut block = [0u8; BLOCK];
+    if key.len() > BLOCK {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+    let inner = Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x36))
+        .chain_update(message)
+        .finalize();
+    Sha256::new()
+        .chain_update(block.map(|b| b ^ 0x5c))
+        .chain_update(inner)
+        .finalize()
+        .into()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Token-bucket limit on lobby requests per identity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Requests allowed in a burst.
+    pub burst: u32,
+    /// Requests regained per second.
+    pub per_second: f64,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        Self {
+            burst: 20,
+            per_second: 2.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl Bucket {
+    /// Tokens held at `now`, counting the refill since the last request.
+    fn level(&self, limit: RateLimit, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        (self.tokens + elapsed * limit.per_second).min(limit.burst as f64)
+    }
+}
+
+/// Whose budget a request is charged to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum BucketKey {
+    Identity(String),
+    /// A connection that has not authenticated, by the host part of its
+    /// peer label: reconnecting from a new port does not buy a new burst.
+    Host(String),
+}
+
+/// Fewest buckets worth sweeping for full ones.
+const MIN_SWEEP: usize = 64;
+
+/// Admission control for lobby requests: authentication plus rate limits.
+///
+/// One gate is shared by every connection of a server, so connections with
+/// the same identity share a rate limit. Before authenticating, a
+/// connection is limited under the IP address of its peer label, or the
+/// whole label if it is not an `ip:port` address.
+///
+/// Buckets that have refilled to the burst are forgotten once the map has
+/// doubled since the last sweep, so a stream of one-off clients does not
+/// grow it without bound.
+#[derive(Debug)]
+pub struct LobbyGate {
+    auth: LobbyAuth,
+    limit: Option<RateLimit>,
+    buckets: HashMap<BucketKey, Bucket>,
+    /// Bucket count at which full buckets are next swept out.
+    sweep_at: usize,
+}
+
+impl LobbyGate {
+    pub fn new(auth: LobbyAuth, limit: Option<RateLimit>) -> Self {
+        Self {
+            auth,
+            limit,
+            buckets: HashMap::new(),
+            sweep_at: MIN_SWEEP,
+        }
+    }
+
+    /// Number of clients whose rate limit is being tracked.
+    pub fn tracked(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// Screen one lobby message from `peer`, whose authenticated identity
+    /// (if any) is tracked by the caller in `identity`.
+    ///
+    /// Returns `None` when the message may go on to the lobby, or the reply
+    /// to send instead: the answer to `Authenticate`, or a refusal with
+    /// `Unauthorized` or `RateLimited`.
+    pub fn check(
+        &mut self,
+        peer: &str,
+        identity: &mut Option<String>,
+        msg: &LobbyMessage,
+        now: Instant,
+    ) -> Option<LobbyMessage> {
+        let seq = match msg {
+            LobbyMessage::ListGames { seq }
+            | LobbyMessage::CreateGame { seq, .. }
+            | LobbyMessage::JoinGame { seq, .. }
+            | LobbyMessage::LeaveGame { seq }
+            | LobbyMessage::Authenticate { seq, .. } => *seq,
+            LobbyMessage::Reply { .. } | LobbyMessage::Event(_) => return None,
+        };
+        let key = match identity {
+            Some(identity) => BucketKey::Identity(identity.clone()),
+            None => BucketKey::Host(peer.parse::<SocketAddr>().map_or_else(|_| peer.to_string(), |addr| addr.ip().to_string())),
+        };
+        if !self.take_token(key, now) {
+            return Some(refusal(seq, LobbyError::RateLimited));
+        }
+
+        if let LobbyMessage::Authenticate { credential, .. } = msg {
+            let result = if self.auth.required() {
+                self.auth.verify(credential, unix_now())
+            } else {
+                Ok(peer.to_string())
+            };
+            if let Ok(granted) = &result {
+                *identity = Some(granted.clone());
+            }
+            return Some(LobbyMessage::Reply {
+                seq,
+                result: result.map(LobbyReply::Authenticated),
+            });
+        }
+        if self.auth.required() && identity.is_none() {
+            return Some(refusal(seq, LobbyError::Unauthorized));
+        }
+        None
+    }
+
+    fn take_token(&mut self, key: BucketKey, now: Instant) -> bool {
+        let Some(limit) = self.limit else { return true };
+        if self.buckets.len() >= self.sweep_at {
+            // A full bucket behaves exactly like a missing one
+            self.buckets.retain(|_, bucket| bucket.level(limit, now) < limit.burst as f64);
+            self.sweep_at = (self.buckets.len() * 2).max(MIN_SWEEP);
+        }
+        let bucket = self.buckets.entry(key).or_insert(Bucket {
+            tokens: limit.burst as f64,
+            updated: now,
+        });
+        bucket.tokens = bucket.level(limit, now);
+        bucket.updated = now;
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+fn refusal(seq: u64, error: LobbyError) -> LobbyMessage {
+    LobbyMessage::Reply { seq, result: Err(error) }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
@@ -0,0 +1,144 @@
+//! Commit-reveal coin flip deciding which peer moves first.
+//!
+//! After the handshake each side draws a random nonce and sends a SHA-256
+//! commitment to it. Once both commitments are exchanged the nonces are
+//! revealed and checked against them. The low bit of the XOR of both nonces
+//! picks the first mover, so neither peer can bias the outcome without
+//! breaking its own commitment.
+
+#![cfg(feature = "std")]
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use super::{Message, Role};
+use crate::transport::Transport;
+
+/// Size in bytes of coin-flip nonces and commitments.
+pub const NONCE_LEN: usize = 32;
+
+/// Commitment to a coin-flip nonce (SHA-256 of the nonce).
+pub fn commitment(nonce: &[u8; NONCE_LEN]) -> [u8; NONCE_LEN] {
+    Sha256::digest(nonce).into()
+}
+
+/// Decide whether the initiator moves first from both revealed nonces.
+pub fn initiator_moves_first(
+    initiator_nonce: &[u8; NONCE_LEN],
+    responder_nonce: &[u8; NONCE_LEN],
+) -> bool {
+    (initiator_nonce[NONCE_LEN - 1] ^ responder_nonce[NONCE_LEN - 1]) & 1 == 0
+}
+
+/// Run the coin flip over `transport` and return `true` if this side moves first.
+/// Messages carry `version`, the protocol version agreed in the handshake.
+///
+/// The initiator sends its commitment and reveal first; the responder answers
+/// each step. Any unexpected message, version mismatch, or reveal that does
+/// not match its commitment aborts the session.
+pub async fn negotiate_first_move<T, R>(
+    transport: &mut T,
+    version: u8,
+    rng: &mut R,
+    role: Role,
+) -> anyhow::Result<bool>
+where
+    T: Transport + ?Sized,
+    R: Rng + ?Sized,
+{
+    let mut nonce = [0u8; NONCE_LEN];
+    rng.fill(&mut nonce);
+    let own_commit = Message::CoinCommit {
+        version,
+        commitment: commitment(&nonce),
+    };
+    let own_reveal = Message::CoinReveal {
+        version,
+        nonce,
+    };
+
+    let peer_commitment = match role {
+        Role::Initiator => {
+            transport.send(own_commit).await?;
+            recv_commit(transport, version).await?
+        }
+        Role::Responder => {
+            let peer = recv_commit(transport, version).await?;
+            transport.send(own_commit).await?;
+            peer
+        }
+    };
+
+    let peer_nonce = match role {
+        Role::Initiator => {
+            transport.send(own_reveal).await?;
+            recv_reveal(transport, version).await?
+        }
+        Role::Responder => {
+            let peer = recv_reveal(transport, version).await?;
+            transport.send(own_reveal).await?;
+            peer
+        }
+    };
+
+    if commitment(&peer_nonce) != peer_commitment {
+        eprintln!("[CoinFlip] Peer revealed a nonce that does not match its commitment");
+        return Err(anyhow::anyhow!(
+            "Coin flip reveal does not match commitment (closing session)"
+        ));
+    }
+
+    Ok(match role {
+        Role::Initiator => initiator_moves_first(&nonce, &peer_nonce),
+        Role::Responder => !initiator_moves_first(&peer_nonce, &nonce),
+    })
+}
+
+async fn recv_commit<T: Transport + ?Sized>(transport: &mut T, expected: u8) -> anyhow::Result<[u8; NONCE_LEN]> {
+    match transport.recv().await? {
+        Message::CoinCommit {
+            version,
+            commitment,
+        } if version == expected => Ok(commitment),
+        Message::CoinCommit { version, .. } => {
+            eprintln!(
+                "[CoinFlip] Protocol version mismatch in CoinCommit: expected {}, got {}",
+                expected, version
+            );
+            Err(anyhow::anyhow!(
+                "Protocol version mismatch in CoinCommit: expected {}, got {}",
+                expected,
+                version
+            ))
+        }
+        other => {
+            eprintln!("[CoinFlip] Expected CoinCommit, got unexpected message: {:?}", other);
+            Err(anyhow::anyhow!(
+                "Expected CoinCommit, got unexpected message (closing session)"
+            ))
+        }
+    }
+}
+
+async fn recv_reveal<T: Transport + ?Sized>(transport: &mut T, expected: u8) -> anyhow::Result<[u8; NONCE_LEN]> {
+    match transport.recv().await? {
+        Message::CoinReveal { version, nonce } if version == expected => Ok(nonce),
+        Message::CoinReveal { version, .. } => {
+            eprintln!(
+                "[CoinFlip] Protocol version mismatch in CoinReveal: expected {}, got {}",
+                expected, version
+            );
+            Err(anyhow::anyhow!(
+                "Protocol version mismatch in CoinReveal: expected {}, got {}",
+                expected,
+                version
+            ))
+        }
+        other => {
+            eprintln!("[CoinFlip] Expected CoinReveal, got unexpected message: {:?}", other);
+            Err(anyhow::anyhow!(
+                "Expected CoinReveal, got unexpected message (closing session)"
+            ))
+        }
+    }
+}
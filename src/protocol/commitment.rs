@@ -0,0 +1,366 @@
+//! Commit-reveal of each peer's fleet layout, so neither can move a ship
+//! once the shooting starts, and the `GameOver` report that follows it.
+//!
+//! Once both peers are ready each sends `Commit` with the SHA-256 of a
+//! random salt followed by the bincode encoding of its placed
+//! [`BoardState`]. When the game is over (see [`reveal_due`]) each sends
+//! `Reveal` with the salt and layout, and the other checks them with
+//! [`verify_reveal`]: the digest must match, the layout must be a legal
+//! fleet under the agreed rules, and it must answer every shot we fired
+//! exactly as the peer did. Each then sends `GameOver` with its board as
+//! the game left it and its [`Stats`], which [`verify_game_over`] holds
+//! against the revealed layout and our own record. The initiator leads
+//! every exchange, as in the coin flip.
+
+#![cfg(feature = "std")]
+
+use core::fmt;
+
+use rand::Rng;
+use sha2::{Digest, Sha256};
+
+use super::{Message, Role};
+use crate::core::{
+    board::{Board, BoardState},
+    common::GuessResult,
+    config::RuleSet,
+    coord::Coord,
+    game::{GameEngine, GameStatus, Stats},
+    history::{MoveLog, Shooter},
+};
+use crate::transport::Transport;
+
+/// Size in bytes of layout salts and commitments.
+pub const SALT_LEN: usize = 32;
+
+/// Commitment to `layout` under `salt`: SHA-256 of the salt followed by the
+/// layout's bincode encoding.
+pub fn layout_digest(salt: &[u8; SALT_LEN], layout: &BoardState) -> [u8; SALT_LEN] {
+    let bytes = bincode::serialize(layout).expect("BoardState always serializes");
+    let mut hasher = Sha256::new();
+    hasher.update(salt);
+    hasher.update(bytes);
+    hasher.finalize().into()
+}
+
+/// Our side of the commitment: the layout we placed and the salt hiding it
+/// until the reveal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LayoutCommitment {
+    pub salt: [u8; SALT_LEN],
+    pub layout: BoardState,
+}
+
+impl LayoutCommitment {
+    /// Commit to `board` as placed, under a fresh salt from `rng`.
+    pub fn new<R: Rng + ?Sized>(rng: &mut R, board: &Board) -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        rng.fill(&mut salt);
+        Self {
+            salt,
+            layout: BoardState::from(board),
+        }
+    }
+
+    /// The digest sent in `Commit`.
+    pub fn digest(&self) -> [u8; SALT_LEN] {
+        layout_digest(&self.salt, &self.layout)
+    }
+}
+
+/// How a peer's revealed layout gave it away.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheatDetected {
+    /// The revealed salt and layout do not hash to the committed digest.
+    CommitmentMismatch,
+    /// The revealed layout is not a fleet the agreed rules allow.
+    IllegalLayout(String),
+    /// The revealed layout answers our shot at `target` with `actual`, but
+    /// the peer reported `reported`.
+    ResultMismatch {
+        target: Coord,
+        reported: GuessResult,
+        actual: GuessResult,
+    },
+    /// The peer's `GameOver` report disagrees with its revealed layout or
+    /// with the shots we saw.
+    GameOverMismatch(String),
+}
+
+impl fmt::Display for CheatDetected {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cheat detected: ")?;
+        match self {
+            CheatDetected::CommitmentMismatch => write!(f, "revealed layout does not match its commitment")?,
+            CheatDetected::IllegalLayout(reason) => write!(f, "revealed layout is illegal: {}", reason)?,
+            CheatDetected::ResultMismatch { target, reported, actual } => write!(
+                f,
+                "shot at {} was answered {:?} but the revealed layout gives {:?}",
+                target, reported, actual
+            )?,
+            CheatDetected::GameOverMismatch(reason) => write!(f, "game over report disagrees: {}", reason)?,
+        }
+        write!(f, " (closing session)")
+    }
+}
+
+impl std::error::Error for CheatDetected {}
+
+/// Whether `engine`'s game is over in a way both peers agree on, so that
+/// they reveal their layouts and report the game over: won, lost, drawn or
+/// forfeited. An aborted game ends without either, as the peer that called
+/// it off may already be gone.
+pub fn reveal_due(engine: &GameEngine) -> bool {
+    !matches!(
+        engine.status(),
+        GameStatus::InProgress | GameStatus::Aborted | GameStatus::Desync
+    )
+}
+
+/// Check the peer's revealed `salt` and `layout` against the digest it
+/// committed to, the `rules` of the game and the results it gave for our
+/// shots in `history`. Returns the peer's board with our shots on it.
+pub fn verify_reveal(
+    committed: &[u8; SALT_LEN],
+    salt: &[u8; SALT_LEN],
+    layout: &BoardState,
+    rules: &RuleSet,
+    history: &MoveLog,
+) -> Result<Board, CheatDetected> {
+    if layout_digest(salt, layout) != *committed {
+        return Err(CheatDetected::CommitmentMismatch);
+    }
+    if layout.size != rules.board_size() {
+        return Err(CheatDetected::IllegalLayout(format!(
+            "board is {}x{}, the game is played on {}x{}",
+            layout.size,
+            layout.size,
+            rules.board_size(),
+            rules.board_size()
+        )));
+    }
+    layout
+        .validate(rules.fleet())
+        .map_err(|e| CheatDetected::IllegalLayout(e.to_string()))?;
+
+    // Place the fleet afresh, so the placement rules apply and no shot is
+    // taken on trust from the revealed state
+    let mut board = Board::with_rules(rules);
+    for (ship, state) in layout.ship_states.iter().enumerate().take(rules.fleet().len()) {
+        let (row, col, orientation) = state.position.expect("validated layouts place every ship");
+        board
+            .place(ship, row, col, orientation)
+            .map_err(|e| CheatDetected::IllegalLayout(format!("ship {}: {}", ship, e)))?;
+    }
+    for mv in history.iter().filter(|mv| mv.shooter == Shooter::Us) {
+        let target = Coord::new(mv.row, mv.col);
+        let actual = board
+            .guess(target)
+            .map_err(|e| CheatDetected::IllegalLayout(format!("shot at {}: {}", target, e)))?;
+        if actual != mv.result {
+            return Err(CheatDetected::ResultMismatch {
+                target,
+                reported: mv.result,
+                actual,
+            });
+        }
+    }
+    Ok(board)
+}
+
+/// Check the peer's `GameOver` report against `revealed`, its layout as
+/// [`verify_reveal`] returned it, and against `engine`'s record of the
+/// game: the final board must hold the same fleet and exactly the shots we
+/// fired, and the stats must count the hits, misses and sinks the peer
+/// scored on our board.
+pub fn verify_game_over(
+    revealed: &Board,
+    final_board: &BoardState,
+    stats: &Stats,
+    engine: &GameEngine,
+) -> Result<(), CheatDetected> {
+    let mismatch = |what: &str| Err(CheatDetected::GameOverMismatch(what.to_string()));
+    let positions = |states: &[crate::core::ship::ShipState]| states.iter().map(|s| s.position).collect::<Vec<_>>();
+    if final_board.ship_map != revealed.ship_map()
+        || positions(&final_board.ship_states) != positions(&revealed.ship_states())
+    {
+        return mismatch("final board holds another fleet than the one revealed");
+    }
+    if final_board.hits != engine.guess_hits() || final_board.misses != engine.guess_misses() {
+        return mismatch("final board shows other shots than the ones we fired");
+    }
+    let ours = engine.board();
+    let sunk = ours.ship_states().iter().filter(|s| s.sunk).count();
+    if stats.hits != ours.hits().count_ones()
+        || stats.misses != ours.misses().count_ones()
+        || stats.ships_sunk != sunk
+    {
+        return mismatch("stats count other shots than the ones we received");
+    }
+    Ok(())
+}
+
+/// Send our layout `digest` and receive the peer's over `transport`.
+/// Messages carry `version`, the protocol version agreed in the handshake.
+///
+/// The initiator sends first and the responder answers. Any unexpected
+/// message or version mismatch aborts the session.
+pub async fn exchange_commitments<T>(
+    transport: &mut T,
+    version: u8,
+    role: Role,
+    digest: [u8; SALT_LEN],
+) -> anyhow::Result<[u8; SALT_LEN]>
+where
+    T: Transport + ?Sized,
+{
+    let own = Message::Commit {
+        version,
+        digest,
+    };
+    match role {
+        Role::Initiator => {
+            transport.send(own).await?;
+            recv_commit(transport, version).await
+        }
+        Role::Responder => {
+            let peer = recv_commit(transport, version).await?;
+            transport.send(own).await?;
+            Ok(peer)
+        }
+    }
+}
+
+/// Reveal our `commitment` and receive the peer's salt and layout over
+/// `transport`, in the same order as [`exchange_commitments`].
+///
+/// Shots or a resignation that crossed the end of the game are dropped;
+/// anything else unexpected aborts the session.
+pub async fn exchange_reveals<T>(
+    transport: &mut T,
+    version: u8,
+    role: Role,
+    commitment: &LayoutCommitment,
+) -> anyhow::Result<([u8; SALT_LEN], BoardState)>
+where
+    T: Transport + ?Sized,
+{
+    let own = Message::Reveal {
+        version,
+        salt: commitment.salt,
+        layout: commitment.layout,
+    };
+    match role {
+        Role::Initiator => {
+            transport.send(own).await?;
+            recv_reveal(transport, version).await
+        }
+        Role::Responder => {
+            let peer = recv_reveal(transport, version).await?;
+            transport.send(own).await?;
+            Ok(peer)
+        }
+    }
+}
+
+/// Send our `final_board` and `stats` in `GameOver` and receive the
+/// peer's, in the same order as [`exchange_commitments`].
+pub async fn exchange_game_over<T>(
+    transport: &mut T,
+    version: u8,
+    role: Role,
+    final_board: BoardState,
+    stats: Stats,
+) -> anyhow::Result<(BoardState, Stats)>
+where
+    T: Transport + ?Sized,
+{
+    let own = Message::GameOver {
+        version,
+        final_board,
+        stats,
+    };
+    match role {
+        Role::Initiator => {
+            transport.send(own).await?;
+            recv_game_over(transport, version).await
+        }
+        Role::Responder => {
+            let peer = recv_game_over(transport, version).await?;
+            transport.send(own).await?;
+            Ok(peer)
+        }
+    }
+}
+
+async fn recv_commit<T: Transport + ?Sized>(transport: &mut T, expected: u8) -> anyhow::Result<[u8; SALT_LEN]> {
+    match transport.recv().await? {
+        Message::Commit { version, digest } if version == expected => Ok(digest),
+        Message::Commit { version, .. } => {
+            eprintln!(
+                "[Commitment] Protocol version mismatch in Commit: expected {}, got {}",
+                expected, version
+            );
+            Err(anyhow::anyhow!(
+                "Protocol version mismatch in Commit: expected {}, got {}",
+                expected,
+                version
+            ))
+        }
+        other => {
+            eprintln!("[Commitment] Expected Commit, got unexpected message: {:?}", other);
+            Err(anyhow::anyhow!("Expected Commit, got unexpected message (closing session)"))
+        }
+    }
+}
+
+async fn recv_reveal<T: Transport + ?Sized>(transport: &mut T, expected: u8) -> anyhow::Result<([u8; SALT_LEN], BoardState)> {
+    loop {
+        match transport.recv().await? {
+            Message::Reveal { version, salt, layout } if version == expected => return Ok((salt, layout)),
+            Message::Reveal { version, .. } => {
+                eprintln!(
+                    "[Commitment] Protocol version mismatch in Reveal: expected {}, got {}",
+                    expected, version
+                );
+                return Err(anyhow::anyhow!(
+                    "Protocol version mismatch in Reveal: expected {}, got {}",
+                    expected,
+                    version
+                ));
+            }
+            Message::Guess { seq, .. } | Message::GuessSalvo { seq, .. } | Message::Resign { seq, .. } => {
+                tracing::debug!(seq, "dropping a move sent after the game ended");
+            }
+            other => {
+                eprintln!("[Commitment] Expected Reveal, got unexpected message: {:?}", other);
+                return Err(anyhow::anyhow!("Expected Reveal, got unexpected message (closing session)"));
+            }
+        }
+    }
+}
+
+async fn recv_game_over<T: Transport + ?Sized>(transport: &mut T, expected: u8) -> anyhow::Result<(BoardState, Stats)> {
+    match transport.recv().await? {
+        Message::GameOver {
+            version,
+            final_board,
+            stats,
+        } if version == expected => Ok((final_board, stats)),
+        Message::GameOver { version, .. } => {
+            eprintln!(
+                "[Commitment] Protocol version mismatch in GameOver: expected {}, got {}",
+                expected, version
+            );
+            Err(anyhow::anyhow!(
+                "Protocol version mismatch in GameOver: expected {}, got {}",
+                expected,
+                version
+            ))
+        }
+        other => {
+            eprintln!("[Commitment] Expected GameOver, got unexpected message: {:?}", other);
+            Err(anyhow::anyhow!("Expected GameOver, got unexpected message (closing session)"))
+        }
+    }
+}
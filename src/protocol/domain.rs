@@ -1,20 +1,27 @@
 #[cfg(not(feature = "std"))]
 use alloc::string::{String, ToString};
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
 #[cfg(feature = "std")]
 use std::string::{String, ToString};
+#[cfg(feature = "std")]
+use std::vec::Vec;
 
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Board {/* grid, ships, hits/misses */}
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Ship {
     pub name: String,
     pub sunk: bool,
     pub position: Option<(u8, u8, crate::core::ship::Orientation)>,
+    /// Segments hit, counted from the ship's origin, in increasing order.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub hit_segments: Vec<u8>,
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GuessResult {
     Hit,
     Miss,
@@ -22,20 +29,230 @@ pub enum GuessResult {
 }
 
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GameStatus {
     InProgress,
     Won,
     Lost,
+    Desync,
+    Aborted,
+    Forfeited,
+    Draw,
+}
+
+/// Machine-readable reason carried by `Message::Error`, so a peer can decide
+/// how to recover without parsing the detail text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ErrorCode {
+    /// The peer speaks another protocol version.
+    VersionMismatch,
+    /// A request carried a sequence number other than the one expected.
+    BadSeq,
+    /// Guess coordinates outside the board.
+    InvalidCoordinate,
+    /// A move or report no honest game could produce.
+    RuleViolation,
+    /// A sync payload or request that does not fit the current game state.
+    InvalidState,
+    /// Too many requests; try again later.
+    Throttled,
+    /// A message of a kind the receiver does not handle at this point.
+    Unexpected,
+    /// The receiver failed for reasons of its own.
+    Internal,
+}
+
+impl ErrorCode {
+    /// Whether the condition ends the session. A sequence gap can be
+    /// resynced and throttling waited out where the peer supports it;
+    /// everything else closes the session.
+    pub fn is_fatal(self) -> bool {
+        !matches!(self, ErrorCode::BadSeq | ErrorCode::Throttled)
+    }
+}
+
+/// A `Message::Error` received from the peer, as an error value. Callers
+/// can recover the code with `anyhow::Error::downcast_ref::<RemoteError>()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteError {
+    pub code: ErrorCode,
+    pub detail: String,
+}
+
+impl core::fmt::Display for RemoteError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "peer reported {:?}: {}", self.code, self.detail)?;
+        if self.code.is_fatal() {
+            write!(f, " (closing session)")?;
+        }
+        Ok(())
+    }
 }
 
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+impl std::error::Error for RemoteError {}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Clone)]
 pub struct SyncPayload {
     /// Complete game state for full synchronization
     pub game_state: crate::core::game::GameState,
-    /// Which enemy ships are still afloat (by index into SHIPS array)
-    pub enemy_ships_remaining: [bool; crate::core::config::NUM_SHIPS as usize],
+    /// Which enemy ships are still afloat (by fleet index)
+    #[cfg_attr(feature = "serde", serde(with = "crate::core::wire::fleet_slots"))]
+    pub enemy_ships_remaining: [bool; crate::core::config::MAX_SHIPS],
+}
+
+/// One ship of a fleet submitted to a remote authority with `PlaceFleet`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Placement {
+    pub ship: String,
+    pub row: u8,
+    pub col: u8,
+    pub orientation: crate::core::ship::Orientation,
+}
+
+impl Placement {
+    /// The placements of every ship on `board`, in fleet order.
+    pub fn from_board(board: &crate::core::board::Board) -> Vec<Placement> {
+        board
+            .ship_states()
+            .iter()
+            .filter_map(|s| {
+                s.position.map(|(row, col, orientation)| Placement {
+                    ship: s.name.to_string(),
+                    row: row as u8,
+                    col: col as u8,
+                    orientation,
+                })
+            })
+            .collect()
+    }
+}
+
+/// SHA-256 of a game state's bincode encoding, so two peers can confirm
+/// they hold the same state without sending it again.
+#[cfg(feature = "std")]
+pub fn state_digest(state: &crate::core::game::GameState) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+    let bytes = bincode::serialize(state).expect("GameState always serializes");
+    Sha256::digest(bytes).into()
+}
+
+/// Longest ship name a [`GameConfig`] may carry.
+pub const MAX_SHIP_NAME_LEN: usize = 32;
+
+/// `name` with the `'static` lifetime ship definitions need, if it is one
+/// of the [`SHIP_CLASSES`](crate::core::config::SHIP_CLASSES).
+fn static_ship_name(name: &str) -> Option<&'static str> {
+    crate::core::config::SHIP_CLASSES.iter().copied().find(|&class| class == name)
+}
+
+/// Game rules both peers agree on before the first guess.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GameConfig {
+    pub board_size: u8,
+    /// Name and length of each ship in fleet order.
+    pub ships: Vec<(String, u8)>,
+    /// Each turn fires one shot per ship still afloat.
+    pub salvo: bool,
+    /// Ships may not touch, diagonals included.
+    pub no_touching: bool,
+    /// Who fires after each shot.
+    pub turn_policy: crate::core::game::TurnPolicy,
+    /// Deadline for each turn, if turns are timed.
+    pub turn_clock: Option<crate::core::game::TurnClock>,
+    /// Shots each side may fire before an undecided game is drawn.
+    pub move_limit: Option<u16>,
+    /// How a guess at a cell already fired at is answered.
+    pub duplicate_policy: crate::core::game::DuplicateGuessPolicy,
+}
+
+impl GameConfig {
+    /// The standard configuration.
+    pub fn local() -> Self {
+        Self::from(&crate::core::config::RuleSet::STANDARD)
+    }
+
+    /// The rules this configuration describes, or `None` if this build
+    /// cannot play them: an unsupported board size, or a fleet that is
+    /// empty, too large, has a zero-length ship or a name longer than
+    /// [`MAX_SHIP_NAME_LEN`] bytes or missing from
+    /// [`SHIP_CLASSES`](crate::core::config::SHIP_CLASSES).
+    pub fn rules(&self) -> Option<crate::core::config::RuleSet> {
+        // Sizes first, so a peer's names are only read once the rest fits
+        if self.board_size > crate::core::config::MAX_BOARD_SIZE
+            || self.ships.is_empty()
+            || self.ships.len() > crate::core::config::MAX_SHIPS
+            || self.ships.iter().any(|(name, length)| *length == 0 || name.len() > MAX_SHIP_NAME_LEN)
+        {
+            return None;
+        }
+        let mut fleet = crate::core::config::FleetConfig::empty();
+        for (name, length) in &self.ships {
+            fleet = fleet.with_ships(static_ship_name(name)?, *length as usize, 1).ok()?;
+        }
+        let rules = crate::core::config::RuleSet::new(self.board_size, fleet).ok()?;
+        let placement = crate::core::config::PlacementRules {
+            no_touching: self.no_touching,
+        };
+        Some(
+            rules
+                .with_salvo(self.salvo)
+                .with_placement(placement)
+                .with_turn_policy(self.turn_policy)
+                .with_turn_clock(self.turn_clock)
+                .with_move_limit(self.move_limit),
+        )
+    }
+
+    /// A builder preset to these rules, for a peer that sets up its engine
+    /// from the configuration it was sent; `None` as for
+    /// [`GameConfig::rules`].
+    pub fn builder(&self) -> Option<crate::core::game::GameEngineBuilder> {
+        self.rules()
+            .map(|rules| crate::core::game::GameEngineBuilder::from(rules).duplicate_policy(self.duplicate_policy))
+    }
+}
+
+/// The rules a builder would play by, as sent in the handshake.
+impl TryFrom<&crate::core::game::GameEngineBuilder> for GameConfig {
+    type Error = crate::core::common::BoardError;
+
+    fn try_from(builder: &crate::core::game::GameEngineBuilder) -> Result<Self, Self::Error> {
+        builder.build().map(|engine| GameConfig::from(&engine))
+    }
+}
+
+/// The rules an engine plays by, duplicate policy included.
+impl From<&crate::core::game::GameEngine> for GameConfig {
+    fn from(engine: &crate::core::game::GameEngine) -> Self {
+        GameConfig {
+            duplicate_policy: engine.duplicate_policy(),
+            ..GameConfig::from(&engine.rules())
+        }
+    }
+}
+
+impl From<&crate::core::config::RuleSet> for GameConfig {
+    fn from(rules: &crate::core::config::RuleSet) -> Self {
+        GameConfig {
+            board_size: rules.board_size(),
+            ships: rules
+                .fleet()
+                .ships()
+                .iter()
+                .map(|def| (def.name().to_string(), def.length() as u8))
+                .collect(),
+            salvo: rules.salvo(),
+            no_touching: rules.placement().no_touching,
+            turn_policy: rules.turn_policy(),
+            turn_clock: rules.turn_clock(),
+            move_limit: rules.move_limit(),
+            duplicate_policy: crate::core::game::DuplicateGuessPolicy::default(),
+        }
+    }
 }
 
 impl From<crate::core::common::GuessResult> for GuessResult {
@@ -54,6 +271,7 @@ impl From<crate::core::ship::ShipState> for Ship {
             name: state.name.to_string(),
             sunk: state.sunk,
             position: state.position.map(|(r, c, o)| (r as u8, c as u8, o)),
+            hit_segments: (0..u16::BITS as u8).filter(|&k| state.segment_hit(k as usize)).collect(),
         }
     }
 }
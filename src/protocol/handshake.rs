@@ -0,0 +1,140 @@
+//! Protocol version negotiation.
+//!
+//! The initiator opens with `HandshakeRange`, advertising the oldest and
+//! newest protocol versions it speaks. The responder picks the highest
+//! version both sides support and confirms it with `HandshakeAck`, or
+//! reports `VersionMismatch` when the ranges do not overlap. Every later
+//! message of the session carries the agreed version.
+//!
+//! `Handshake { version }` is read as a range of that one version, and an
+//! initiator limited to a single version sends it. Version 1 peers, the
+//! first release, know nothing else: they get `Handshake` or `HandshakeAck`
+//! at the same variant indices as ever and report the mismatch themselves.
+
+#![cfg(feature = "std")]
+
+use core::ops::RangeInclusive;
+
+use super::domain::{ErrorCode, RemoteError};
+use super::{Message, Role, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use crate::transport::Transport;
+
+/// The first release, which has no `Error` message. A responder tells such
+/// a peer its own version in a `HandshakeAck` instead, which the peer
+/// rejects as a mismatch.
+pub const FIRST_VERSION: u8 = 1;
+
+/// Every protocol version this build can play.
+pub const SUPPORTED_VERSIONS: RangeInclusive<u8> = MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION;
+
+/// The highest version in both `ours` and the peer's `min..=max`, if any.
+pub fn negotiate(ours: &RangeInclusive<u8>, min: u8, max: u8) -> Option<u8> {
+    let version = max.min(*ours.end());
+    (version >= min.max(*ours.start())).then_some(version)
+}
+
+/// The opening message for an initiator speaking `ours`.
+fn handshake_message(ours: &RangeInclusive<u8>) -> Message {
+    if ours.start() == ours.end() {
+        Message::Handshake { version: *ours.start() }
+    } else {
+        Message::HandshakeRange {
+            min_version: *ours.start(),
+            max_version: *ours.end(),
+        }
+    }
+}
+
+/// The `min..=max` versions an opening handshake advertises.
+fn advertised_versions(msg: &Message) -> Option<(u8, u8)> {
+    match *msg {
+        Message::Handshake { version } => Some((version, version)),
+        Message::HandshakeRange {
+            min_version,
+            max_version,
+        } => Some((min_version, max_version)),
+        _ => None,
+    }
+}
+
+/// Agree on a protocol version from `ours` with the peer over `transport`.
+///
+/// The initiator advertises its range and accepts any version in it; the
+/// responder picks one and tells the initiator. Disjoint ranges, or any
+/// unexpected message, abort the session.
+pub async fn exchange_handshake<T>(transport: &mut T, role: Role, ours: &RangeInclusive<u8>) -> anyhow::Result<u8>
+where
+    T: Transport + ?Sized,
+{
+    match role {
+        Role::Initiator => {
+            transport.send(handshake_message(ours)).await?;
+            match transport.recv().await? {
+                Message::HandshakeAck { version } if ours.contains(&version) => Ok(version),
+                Message::HandshakeAck { version } => {
+                    eprintln!(
+                        "[Handshake] Peer picked protocol version {}, outside our range {}..={}",
+                        version,
+                        ours.start(),
+                        ours.end()
+                    );
+                    Err(anyhow::anyhow!(
+                        "Protocol version mismatch in HandshakeAck: expected {}..={}, got {}",
+                        ours.start(),
+                        ours.end(),
+                        version
+                    ))
+                }
+                Message::Error { code, detail, .. } => Err(RemoteError { code, detail }.into()),
+                other => {
+                    eprintln!("[Handshake] Expected HandshakeAck, got unexpected message: {:?}", other);
+                    Err(anyhow::anyhow!("Expected HandshakeAck, got unexpected message (closing session)"))
+                }
+            }
+        }
+        Role::Responder => {
+            let msg = transport.recv().await?;
+            match advertised_versions(&msg) {
+                Some((min_version, max_version)) => match negotiate(ours, min_version, max_version) {
+                    Some(version) => {
+                        transport.send(Message::HandshakeAck { version }).await?;
+                        Ok(version)
+                    }
+                    None => {
+                        eprintln!(
+                            "[Handshake] No common protocol version: we speak {}..={}, peer speaks {}..={}",
+                            ours.start(),
+                            ours.end(),
+                            min_version,
+                            max_version
+                        );
+                        let detail = format!(
+                            "expected a protocol version in {}..={}, got {}..={}",
+                            ours.start(),
+                            ours.end(),
+                            min_version,
+                            max_version
+                        );
+                        let refusal = if max_version <= FIRST_VERSION {
+                            Message::HandshakeAck { version: *ours.end() }
+                        } else {
+                            Message::Error {
+                                version: *ours.end(),
+                                seq: 0,
+                                code: ErrorCode::VersionMismatch,
+                                detail: detail.clone(),
+                            }
+                        };
+                        // Best effort: the session is closing anyway
+                        let _ = transport.send(refusal).await;
+                        Err(anyhow::anyhow!("Protocol version mismatch in Handshake: {}", detail))
+                    }
+                },
+                None => {
+                    eprintln!("[Handshake] Expected Handshake, got unexpected message: {:?}", msg);
+                    Err(anyhow::anyhow!("Expected Handshake, got unexpected message (closing session)"))
+                }
+            }
+        }
+    }
+}
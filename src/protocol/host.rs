@@ -0,0 +1,135 @@
+//! Server-hosted games that survive dropped connections.
+//!
+//! [`GameHost`] serves a server-side [`GameEngine`] to a remote [`Stub`]
+//! through a [`Skeleton`] restored from a [`GameStore`], which checkpoints
+//! the session there after every accepted request. Clients connect with `JoinGame { id, resume_token }` on
+//! the lobby channel, both the first time and after losing the connection:
+//! the host restores the Skeleton from the store, replays the reply to the
+//! last accepted request (the client may never have received it), and the
+//! game continues where it left off. With a persistent store this also
+//! works across a server restart.
+//!
+//! [`Stub`]: super::Stub
+
+use super::{
+    domain::GameConfig,
+    lobby::{GameId, LobbyError, LobbyMessage, LobbyReply, ResumeToken},
+    store::{GameStore, SavedGame},
+    handshake::SUPPORTED_VERSIONS,
+    Message, Skeleton,
+};
+use crate::core::game::GameEngine;
+use crate::transport::Transport;
+use anyhow::anyhow;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+/// Hosts rejoinable games backed by a [`GameStore`].
+pub struct GameHost<S: GameStore> {
+    store: Arc<S>,
+    next_id: AtomicU64,
+}
+
+impl<S: GameStore + 'static> GameHost<S> {
+    /// Host games in `store`; new ids continue after any already stored.
+    pub fn new(store: S) -> anyhow::Result<Self> {
+        let next_id = store.ids()?.last().map_or(0, |id| id + 1);
+        Ok(Self {
+            store: Arc::new(store),
+            next_id: AtomicU64::new(next_id),
+        })
+    }
+
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Start hosting a game for `engine`. The id and token are what the
+    /// client needs to join, and to rejoin later.
+    ///
+    /// Fails if the engine's rules could not be restored from the store,
+    /// i.e. a ship is not named after one of the
+    /// [`SHIP_CLASSES`](crate::core::config::SHIP_CLASSES).
+    pub fn create(&self, engine: &GameEngine) -> anyhow::Result<(GameId, ResumeToken)> {
+        let config = GameConfig::from(engine);
+        if config.rules() != Some(engine.rules()) {
+            return Err(anyhow!("Cannot host a game whose rules do not survive a restore: {:?}", config));
+        }
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        let resume_token: ResumeToken = rand::random();
+        let saved = SavedGame {
+            state: engine.state(),
+            config,
+            next_seq: 0,
+            last_response: None,
+            resume_token,
+        };
+        self.store.save(id, &saved)?;
+        Ok((id, resume_token))
+    }
+
+    /// Serve one client connection until it closes.
+    ///
+    /// The client must open with `JoinGame` carrying the game's resume
+    /// token. The game stays in the store when the connection drops, ready
+    /// for the next rejoin; an aborted game is removed from it.
+    pub async fn serve<T: Transport>(&self, mut transport: T) -> anyhow::Result<()> {
+        let (version, seq, id, token) = loop {
+            match transport.recv().await? {
+                Message::Heartbeat { .. } => {}
+                Message::Lobby {
+                    version,
+                    msg: LobbyMessage::JoinGame { seq, id, resume_token },
+                } if SUPPORTED_VERSIONS.contains(&version) => break (version, seq, id, resume_token),
+                other => return Err(anyhow!("Expected JoinGame, got {:?} (closing session)", other)),
+            }
+        };
+
+        let saved = match self.store.load(id)? {
+            Some(saved) if token == Some(saved.resume_token) => saved,
+            found => {
+                let error = if found.is_some() {
+                    LobbyError::InvalidResumeToken(id)
+                } else {
+                    LobbyError::UnknownGame(id)
+                };
+                let msg = LobbyMessage::Reply {
+                    seq,
+                    result: Err(error.clone()),
+                };
+                transport.send(Message::Lobby { version, msg }).await?;
+                return Err(anyhow!("Rejoin refused: {} (closing session)", error));
+            }
+        };
+
+        let reply = LobbyReply::Rejoined {
+            id,
+            next_seq: saved.next_seq,
+            replayed: saved.last_response.is_some() as u32,
+        };
+        let msg = LobbyMessage::Reply { seq, result: Ok(reply) };
+        transport.send(Message::Lobby { version, msg }).await?;
+        if let Some(response) = &saved.last_response {
+            transport.send(response.clone()).await?;
+        }
+        tracing::info!(id, next_seq = saved.next_seq, "client joined hosted game");
+
+        let mut skeleton = Skeleton::restore(id, self.store.clone(), transport)?;
+        loop {
+            let msg = match skeleton.recv().await {
+                Ok(msg) => msg,
+                Err(e) => {
+                    tracing::info!(id, error = %e, "client left hosted game");
+                    return Ok(());
+                }
+            };
+            skeleton.handle(msg).await?;
+            if skeleton.aborted() {
+                tracing::info!(id, "hosted game aborted");
+                return Ok(());
+            }
+        }
+    }
+}
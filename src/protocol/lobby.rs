@@ -0,0 +1,244 @@
+//! Lobby protocol: browse, host and join games on a shared server.
+//!
+//! Lobby traffic has its own message enum, [`LobbyMessage`], and its own
+//! sequence numbers. On the wire it travels as [`Message::Lobby`], a channel
+//! separate from game messages, so a connection can take part in the lobby
+//! and then carry a game without the two getting mixed up.
+//!
+//! [`Lobby`] is the server-side handler. It keeps the list of open games and
+//! turns each request into a reply plus events for every other client; the
+//! caller owns the connections and screens requests through a
+//! [`LobbyGate`](super::auth::LobbyGate) first.
+//!
+//! [`Message::Lobby`]: super::Message::Lobby
+
+use super::domain::GameConfig;
+use core::fmt;
+use std::collections::BTreeMap;
+
+/// Server-assigned identifier of a hosted game.
+pub type GameId = u64;
+
+/// Secret handed to a player of a server-hosted game so it can rejoin after
+/// losing its connection.
+pub type ResumeToken = [u8; 16];
+
+/// Messages on the lobby channel.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LobbyMessage {
+    /// Request the games waiting for an opponent.
+    ListGames { seq: u64 },
+    /// Host a new game with the given rules and wait for an opponent.
+    CreateGame { seq: u64, config: GameConfig },
+    /// Join an open game; both players are sent `Paired` once it starts.
+    /// With a `resume_token`, rejoin an in-progress server-hosted game
+    /// instead (see `protocol::host`).
+    JoinGame {
+        seq: u64,
+        id: GameId,
+        resume_token: Option<ResumeToken>,
+    },
+    /// Withdraw the game this connection is hosting.
+    LeaveGame { seq: u64 },
+    /// Prove the client's identity with a token or signed ticket; see
+    /// `protocol::auth`.
+    Authenticate { seq: u64, credential: String },
+    /// Server answer to the request with the same `seq`.
+    Reply {
+        seq: u64,
+        result: Result<LobbyReply, LobbyError>,
+    },
+    /// Unsolicited notice of a lobby change.
+    Event(LobbyEvent),
+}
+
+/// Successful answer to a lobby request.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LobbyReply {
+    Games(Vec<GameInfo>),
+    Created(GameId),
+    Joined(GameId),
+    Left(GameId),
+    /// Rejoined a hosted game. The next request must carry `next_seq`; the
+    /// server then sends the `replayed` responses the client may have missed.
+    Rejoined { id: GameId, next_seq: u64, replayed: u32 },
+    /// Credential accepted for this identity.
+    Authenticated(String),
+}
+
+/// Change in the lobby pushed to every connected client.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LobbyEvent {
+    /// A new game is waiting for an opponent.
+    GameOpened(GameInfo),
+    /// A game got its second player and left the lobby.
+    GameStarted(GameId),
+    /// A game was withdrawn or its host disconnected.
+    GameClosed(GameId),
+}
+
+/// A game waiting for an opponent.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct GameInfo {
+    pub id: GameId,
+    /// Label of the hosting player, usually its socket address.
+    pub host: String,
+    pub config: GameConfig,
+}
+
+/// Why a lobby request was refused.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum LobbyError {
+    /// No open game has this id.
+    UnknownGame(GameId),
+    /// The connection already hosts a game.
+    AlreadyHosting(GameId),
+    /// `LeaveGame` from a connection that hosts nothing.
+    NotHosting,
+    /// A host tried to join its own game.
+    OwnGame(GameId),
+    /// The server cannot play these rules.
+    UnsupportedConfig,
+    /// Rejoin refused: the game is not resumable or the token is wrong.
+    InvalidResumeToken(GameId),
+    /// Missing or rejected credential.
+    Unauthorized,
+    /// Too many requests from this identity; retry later.
+    RateLimited,
+}
+
+impl fmt::Display for LobbyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LobbyError::UnknownGame(id) => write!(f, "no open game with id {}", id),
+            LobbyError::AlreadyHosting(id) => write!(f, "already hosting game {}", id),
+            LobbyError::NotHosting => write!(f, "not hosting a game"),
+            LobbyError::OwnGame(id) => write!(f, "cannot join own game {}", id),
+            LobbyError::UnsupportedConfig => write!(f, "game configuration not supported by this server"),
+            LobbyError::InvalidResumeToken(id) => write!(f, "cannot resume game {} with this token", id),
+            LobbyError::Unauthorized => write!(f, "not authenticated"),
+            LobbyError::RateLimited => write!(f, "too many lobby requests"),
+        }
+    }
+}
+
+impl std::error::Error for LobbyError {}
+
+/// What the server should do after [`Lobby::handle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LobbyOutcome {
+    /// Reply for the requesting client.
+    pub reply: LobbyMessage,
+    /// Events for every connected client.
+    pub events: Vec<LobbyEvent>,
+    /// Set when a join filled a game: the game and its host, who plays as
+    /// initiator against the joining client.
+    pub matched: Option<(GameId, String)>,
+}
+
+/// Server-side lobby state: the open games and who hosts them.
+///
+/// Clients are identified by a label unique per connection, such as the
+/// peer address.
+#[derive(Debug, Default)]
+pub struct Lobby {
+    games: BTreeMap<GameId, GameInfo>,
+    next_id: GameId,
+}
+
+impl Lobby {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Games waiting for an opponent, oldest first.
+    pub fn open_games(&self) -> Vec<GameInfo> {
+        self.games.values().cloned().collect()
+    }
+
+    /// Game hosted by `peer`, if any.
+    pub fn hosted_by(&self, peer: &str) -> Option<GameId> {
+        self.games.values().find(|g| g.host == peer).map(|g| g.id)
+    }
+
+    /// Handle one message from `peer`. Returns `None` for messages the
+    /// lobby does not answer: `Reply` and `Event`, which only a server
+    /// sends, and `Authenticate`, which belongs to the server's
+    /// [`LobbyGate`](super::auth::LobbyGate).
+    pub fn handle(&mut self, peer: &str, msg: LobbyMessage) -> Option<LobbyOutcome> {
+        let (seq, result, events, matched) = match msg {
+            LobbyMessage::ListGames { seq } => (seq, Ok(LobbyReply::Games(self.open_games())), Vec::new(), None),
+            LobbyMessage::CreateGame { seq, config } => match self.create(peer, config) {
+                Ok(info) => (seq, Ok(LobbyReply::Created(info.id)), vec![LobbyEvent::GameOpened(info)], None),
+                Err(e) => (seq, Err(e), Vec::new(), None),
+            },
+            // Lobby games are peer to peer; only hosted games can be resumed
+            LobbyMessage::JoinGame {
+                seq,
+                id,
+                resume_token: Some(_),
+            } => (seq, Err(LobbyError::InvalidResumeToken(id)), Vec::new(), None),
+            LobbyMessage::JoinGame { seq, id, resume_token: None } => match self.join(peer, id) {
+                Ok(host) => (
+                    seq,
+                    Ok(LobbyReply::Joined(id)),
+                    vec![LobbyEvent::GameStarted(id)],
+                    Some((id, host)),
+                ),
+                Err(e) => (seq, Err(e), Vec::new(), None),
+            },
+            LobbyMessage::LeaveGame { seq } => match self.hosted_by(peer) {
+                Some(id) => {
+                    self.games.remove(&id);
+                    (seq, Ok(LobbyReply::Left(id)), vec![LobbyEvent::GameClosed(id)], None)
+                }
+                None => (seq, Err(LobbyError::NotHosting), Vec::new(), None),
+            },
+            LobbyMessage::Reply { .. } | LobbyMessage::Event(_) | LobbyMessage::Authenticate { .. } => return None,
+        };
+        Some(LobbyOutcome {
+            reply: LobbyMessage::Reply { seq, result },
+            events,
+            matched,
+        })
+    }
+
+    /// Forget a disconnected client, closing any game it hosted.
+    pub fn disconnect(&mut self, peer: &str) -> Vec<LobbyEvent> {
+        match self.hosted_by(peer) {
+            Some(id) => {
+                self.games.remove(&id);
+                vec![LobbyEvent::GameClosed(id)]
+            }
+            None => Vec::new(),
+        }
+    }
+
+    fn create(&mut self, peer: &str, config: GameConfig) -> Result<GameInfo, LobbyError> {
+        if let Some(id) = self.hosted_by(peer) {
+            return Err(LobbyError::AlreadyHosting(id));
+        }
+        if config.rules().is_none() {
+            return Err(LobbyError::UnsupportedConfig);
+        }
+        let info = GameInfo {
+            id: self.next_id,
+            host: peer.to_string(),
+            config,
+        };
+        self.next_id += 1;
+        self.games.insert(info.id, info.clone());
+        Ok(info)
+    }
+
+    fn join(&mut self, peer: &str, id: GameId) -> Result<String, LobbyError> {
+        let game = self.games.get(&id).ok_or(LobbyError::UnknownGame(id))?;
+        if game.host == peer {
+            return Err(LobbyError::OwnGame(id));
+        }
+        if let Some(hosted) = self.hosted_by(peer) {
+            return Err(LobbyError::AlreadyHosting(hosted));
+        }
+        Ok(self.games.remove(&id).map(|g| g.host).unwrap_or_default())
+    }
+}
@@ -6,42 +6,113 @@
 //! - Skeleton: Server-side RPC handler
 //! - Stub: Client-side RPC proxy
 //! - Domain types: Serializable versions of game types
+//! - Validation: Rejects impossible inbound values as `ProtocolViolation`
+//! - Lobby: Browse/host/join messages on their own channel, plus the server handler
+//! - Auth: Token/ticket authentication and per-identity rate limits for lobby commands
+//! - Host/Store: Server-hosted games persisted in a `GameStore` and rejoinable by id
+//! - Referee: A trusted node that owns both fleets and adjudicates every shot
+//! - Handshake: Negotiates the highest protocol version both peers support
+//! - Commitment: Salted layout commitments, revealed and checked with a `GameOver` report once the game ends
+//!
+//! The `wasm` feature builds only the wire types (`Message`, `domain`,
+//! `lobby`) and `GameApi`, enough for a browser client on a
+//! `transport::websocket::WebSocketTransport`.
 
-#![cfg(feature = "std")]
+#![cfg(any(feature = "std", feature = "wasm"))]
 
+#[cfg(feature = "std")]
+pub mod auth;
+#[cfg(feature = "std")]
+pub mod coin_flip;
+#[cfg(feature = "std")]
+pub mod commitment;
 pub mod domain;
+#[cfg(feature = "std")]
+pub mod handshake;
+#[cfg(feature = "std")]
+pub mod host;
+pub mod lobby;
+#[cfg(feature = "std")]
+pub mod referee;
+#[cfg(feature = "std")]
+pub mod setup;
+#[cfg(feature = "std")]
 pub mod skeleton;
+#[cfg(feature = "std")]
+pub mod store;
+#[cfg(feature = "std")]
 pub mod stub;
+#[cfg(feature = "std")]
+pub mod validation;
 
 use domain::*;
+use crate::core::coord::Coord;
 
-/// Current protocol version.
-pub const PROTOCOL_VERSION: u8 = 1;
+/// Current protocol version, the newest this build speaks.
+pub const PROTOCOL_VERSION: u8 = 2;
+
+/// Oldest protocol version this build still plays. Version 1 had no
+/// setup phase, and its `Guess` and `StatusResp` answered differently,
+/// so a version 1 peer is turned away at the handshake.
+pub const MIN_PROTOCOL_VERSION: u8 = 2;
+
+/// Side of a session. The initiator opens the handshake and leads each
+/// setup exchange; it does not imply moving first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Role {
+    Initiator,
+    Responder,
+}
 
 // Re-exports
+#[cfg(feature = "std")]
 pub use skeleton::Skeleton;
+#[cfg(feature = "std")]
 pub use stub::Stub;
-
 #[cfg(feature = "std")]
+pub use validation::ProtocolViolation;
+#[cfg(feature = "std")]
+pub use commitment::CheatDetected;
+#[cfg(feature = "std")]
+pub use auth::{LobbyAuth, LobbyGate, RateLimit};
+#[cfg(feature = "std")]
+pub use host::GameHost;
+#[cfg(feature = "std")]
+pub use referee::{RefereeNode, RefereeOutcome};
+pub use lobby::{GameId, Lobby, LobbyMessage, ResumeToken};
+#[cfg(feature = "std")]
+pub use store::{FileStore, GameStore, MemoryStore, SavedGame};
+
 pub use async_trait;
 
 /// Messages exchanged between the game engine and a remote client.
+/// New variants go at the end, so the handshake keeps the encoding a
+/// version 1 peer reads it in.
+#[allow(clippy::large_enum_variant)]
 #[derive(Debug, Clone)]
-#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Message {
-    /// Handshake message to establish connection and negotiate protocol version.
+    /// Handshake message to establish connection on exactly `version`.
+    /// Peers that speak a range of versions send `HandshakeRange` instead.
     Handshake { version: u8 },
-    /// Acknowledgement of handshake with agreed version.
+    /// Acknowledgement of handshake with the highest version both sides
+    /// support, used by every later message of the session.
     HandshakeAck { version: u8 },
-    /// Request to make a guess at the given coordinates.
-    Guess { version: u8, seq: u64, x: u8, y: u8 },
+    /// Request to make a guess at the given cell. The target travels as a
+    /// row byte then a column byte.
+    Guess { version: u8, seq: u64, target: Coord },
     /// Request the current game status.
     StatusReq { version: u8, seq: u64 },
-    /// Response carrying the result of a guess.
+    /// Response carrying the result of a guess. A peer that keeps both
+    /// boards adds the [`GameState::digest`](crate::core::game::GameState::digest)
+    /// of its state after the shot, which the shooter checks against its own
+    /// mirrored digest.
     StatusResp {
         version: u8,
         seq: u64,
         res: GuessResult,
+        digest: Option<u64>,
     },
     /// Synchronise state between peers.
     Sync {
@@ -65,12 +136,186 @@ pub enum Message {
     Ack { version: u8, seq: u64 },
     /// Heartbeat/keepalive to maintain connection.
     Heartbeat { version: u8 },
+    /// Commitment to a coin-flip nonce, exchanged right after the handshake.
+    CoinCommit { version: u8, commitment: [u8; 32] },
+    /// Reveal of the nonce behind an earlier `CoinCommit`.
+    CoinReveal { version: u8, nonce: [u8; 32] },
+    /// Periodic notice that the sender is still placing its fleet.
+    PlacementProgress { version: u8, elapsed_secs: u32 },
+    /// Sent once the local fleet is placed; no guesses may follow until both
+    /// peers are ready.
+    Ready { version: u8 },
+    /// Sent by the initiator after both peers are ready, confirming the
+    /// rules and who takes the first shot.
+    StartGame {
+        version: u8,
+        config: GameConfig,
+        first_mover: Role,
+    },
+    /// Commitment to the sender's placed fleet, exchanged after
+    /// `StartGame`; see [`commitment`].
+    Commit { version: u8, digest: [u8; 32] },
+    /// Salt and layout behind an earlier `Commit`, exchanged once the game
+    /// is over.
+    Reveal {
+        version: u8,
+        salt: [u8; 32],
+        layout: crate::core::board::BoardState,
+    },
+    /// The sender's board as the game left it and its own stats, exchanged
+    /// after `Reveal` so each side can check the other's account of the
+    /// game and show the opponent's fleet.
+    GameOver {
+        version: u8,
+        final_board: crate::core::board::BoardState,
+        stats: crate::core::game::Stats,
+    },
+    /// Sent by a matchmaking daemon once an opponent is found, telling the
+    /// client which side of the handshake to take.
+    Paired { version: u8, role: Role },
+    /// Lobby channel; see [`lobby`].
+    Lobby { version: u8, msg: LobbyMessage },
+    /// Realign a session whose sequence numbers drifted apart, e.g. after a
+    /// lost reply. Accepted with any `seq`; the server expects `seq + 1` next.
+    /// With a payload the server also replaces its game state.
+    Resync {
+        version: u8,
+        seq: u64,
+        payload: Option<SyncPayload>,
+    },
+    /// Answer to `Resync`, with the digest of the server's state afterwards
+    /// (see [`domain::state_digest`]) when its engine can compute one.
+    /// Anything sent before it used the old numbering.
+    ResyncAck {
+        version: u8,
+        seq: u64,
+        digest: Option<[u8; 32]>,
+    },
+    /// Submit our whole fleet to a remote authority that owns our board.
+    PlaceFleet {
+        version: u8,
+        seq: u64,
+        placements: Vec<Placement>,
+    },
+    /// The authority accepted a `PlaceFleet` layout.
+    PlacementAccepted { version: u8, seq: u64 },
+    /// The authority refused a `PlaceFleet` layout; `reasons` lists every
+    /// problem found, so the client can fix them in one go.
+    PlacementRejected {
+        version: u8,
+        seq: u64,
+        reasons: Vec<String>,
+    },
+    /// The request numbered `seq` (or, for messages without a number, the
+    /// last one received) could not be served. Fatal codes (see
+    /// [`ErrorCode::is_fatal`]) are followed by the sender closing the
+    /// session.
+    Error {
+        version: u8,
+        seq: u64,
+        code: ErrorCode,
+        detail: String,
+    },
+    /// Call the game off; answered with an `Ack` for `seq`, after which
+    /// both sides treat the game as aborted and release it.
+    Abort { version: u8, seq: u64, reason: String },
+    /// A whole turn's shots under salvo rules, as `(x, y)` pairs. The
+    /// volley must hold exactly as many distinct cells as the shooter is
+    /// allowed this turn (see [`crate::GameEngine::shots_per_turn`]).
+    GuessSalvo {
+        version: u8,
+        seq: u64,
+        shots: Vec<(u8, u8)>,
+    },
+    /// Results of a `GuessSalvo`, in the order the shots were fired. Shots
+    /// after the one that ended the game are left unanswered. `digest` is
+    /// as for `StatusResp`, taken after the last shot resolved.
+    SalvoResp {
+        version: u8,
+        seq: u64,
+        results: Vec<GuessResult>,
+        digest: Option<u64>,
+    },
+    /// Concede the game in place of request `seq`'s shot; answered with an
+    /// `Ack`. The sender's game ends `Forfeited` and the receiver's `Won`.
+    Resign { version: u8, seq: u64 },
+    /// Sent in place of the answer to request `seq` when the requester let
+    /// its turn clock run out. Empty `shots` means it forfeited; otherwise
+    /// `shots` were fired on its behalf and answered `results`. Not
+    /// acknowledged; a late request `seq` that crosses it is ignored.
+    TurnTimeout {
+        version: u8,
+        seq: u64,
+        shots: Vec<(u8, u8)>,
+        results: Vec<GuessResult>,
+    },
+    /// Once a game of a series is over, ask to play game number `game`
+    /// (counting from 1) with the same rules; answered with
+    /// `RematchAccept` for the same number.
+    RematchRequest { version: u8, game: u32 },
+    /// Agree to play game `game` of the series. Both sides then start it
+    /// afresh from the handshake.
+    RematchAccept { version: u8, game: u32 },
+    /// `Resync` that brings the server's state forward by `delta`, the
+    /// shots since the state it was taken against, instead of replacing it
+    /// whole. Answered with `ResyncAck`.
+    ResyncDelta {
+        version: u8,
+        seq: u64,
+        delta: crate::core::delta::StateDelta,
+    },
+    /// Handshake advertising the oldest and newest protocol versions the
+    /// sender supports. Answered with `HandshakeAck` like `Handshake`.
+    HandshakeRange { min_version: u8, max_version: u8 },
+    /// The rules the sender's engine plays by, exchanged by both peers
+    /// right after the handshake so that peers set up for different games
+    /// part before either places a ship.
+    Rules { version: u8, config: GameConfig },
 }
 
-#[cfg_attr(feature = "std", async_trait::async_trait)]
+#[async_trait::async_trait]
 pub trait GameApi: Send + Sync {
-    async fn make_guess(&mut self, x: u8, y: u8) -> anyhow::Result<GuessResult>;
+    async fn make_guess(&mut self, target: Coord) -> anyhow::Result<GuessResult>;
     async fn get_ship_status(&self, ship_id: usize) -> anyhow::Result<Ship>;
     async fn sync_state(&mut self, payload: SyncPayload) -> anyhow::Result<()>;
     fn status(&self) -> GameStatus;
+
+    /// Digest of the engine's current state, for engines that hold one.
+    fn state_digest(&self) -> Option<[u8; 32]> {
+        None
+    }
+
+    /// Set up our fleet from `placements`. The inner `Err` lists why the
+    /// layout was refused; the outer one is a transport or engine failure.
+    async fn place_fleet(&mut self, placements: Vec<Placement>) -> anyhow::Result<Result<(), Vec<String>>> {
+        let _ = placements;
+        Ok(Err(vec!["this engine does not accept remote placement".to_string()]))
+    }
+
+    /// Resolve a salvo, returning one result per shot resolved. Engines
+    /// that only play single shots refuse it.
+    async fn make_salvo(&mut self, shots: Vec<(u8, u8)>) -> anyhow::Result<Vec<GuessResult>> {
+        let _ = shots;
+        Err(anyhow::anyhow!("this engine does not play salvo rules"))
+    }
+
+    /// Bring the game state forward by `delta`. Engines that hold no
+    /// state to compare it against refuse it.
+    async fn sync_delta(&mut self, delta: crate::core::delta::StateDelta) -> anyhow::Result<()> {
+        let _ = delta;
+        Err(anyhow::anyhow!("this engine does not accept state deltas"))
+    }
+
+    /// The client concedes, leaving this engine's status `Won`. Engines
+    /// that keep no result accept this as is.
+    async fn concede(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Call the game off for `reason`, leaving the status `Aborted`.
+    /// Engines with nothing to tear down accept this as is.
+    async fn abort_game(&mut self, reason: String) -> anyhow::Result<()> {
+        let _ = reason;
+        Ok(())
+    }
 }
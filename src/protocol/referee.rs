@@ -0,0 +1,384 @@
+//! Refereed games: a trusted third process owns both fleets.
+//!
+//! In a peer-to-peer game each player answers the other's shots from its
+//! own board, so a dishonest client can misreport. A [`RefereeNode`] holds
+//! both players' engines instead. Each player talks to it like a [`Stub`]
+//! talks to a [`Skeleton`]: it submits its fleet with `PlaceFleet`, fires
+//! with `Guess` and may ask for its game and ship status at any time. The
+//! referee adjudicates every shot against the defender's board, so neither
+//! player ever reports a result.
+//!
+//! Turns are scheduled by the referee. A player may send its guess early;
+//! the guess waits, and its reply is held back, until both fleets are
+//! placed and it is that player's turn. Once the game is decided, any
+//! guess still waiting or sent later is answered with the player's final
+//! `GameStatusResp`.
+//!
+//! [`Stub`]: super::Stub
+//! [`Skeleton`]: super::Skeleton
+
+use super::{
+    domain::{ErrorCode, GuessResult as DomainGuessResult, RemoteError},
+    validation, GameApi, Message, Role, PROTOCOL_VERSION,
+};
+use crate::core::{
+    coord::Coord,
+    game::{DuplicateGuessPolicy, GameEngine},
+};
+use crate::transport::Transport;
+use anyhow::anyhow;
+
+/// How a refereed game ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefereeOutcome {
+    /// `winner` sank the whole enemy fleet; `shots` counts each player's
+    /// guesses, indexed like the players.
+    Finished { winner: Role, shots: [usize; 2] },
+    /// A player disconnected before the game was decided.
+    Abandoned { by: Role },
+}
+
+/// One player's connection and the engine the referee keeps for them.
+struct Seat {
+    transport: Box<dyn Transport>,
+    engine: GameEngine,
+    next_seq: u64,
+    placed: bool,
+    /// Guess waiting for this player's turn: seq and target.
+    pending: Option<(u64, Coord)>,
+    open: bool,
+    shots: usize,
+}
+
+impl Seat {
+    fn new(transport: Box<dyn Transport>) -> Self {
+        Self {
+            transport,
+            engine: GameEngine::new(),
+            next_seq: 0,
+            placed: false,
+            pending: None,
+            open: true,
+            shots: 0,
+        }
+    }
+}
+
+/// Runs one game between two players, owning both boards.
+///
+/// The first player is [`Role::Initiator`], the second [`Role::Responder`].
+pub struct RefereeNode {
+    seats: [Seat; 2],
+    first_mover: Role,
+    started: bool,
+    turn: usize,
+    winner: Option<usize>,
+}
+
+fn role(player: usize) -> Role {
+    if player == 0 {
+        Role::Initiator
+    } else {
+        Role::Responder
+    }
+}
+
+fn index(role: Role) -> usize {
+    match role {
+        Role::Initiator => 0,
+        Role::Responder => 1,
+    }
+}
+
+impl RefereeNode {
+    /// Referee a game between two connected players. Who shoots first is
+    /// decided at random.
+    pub fn new(first: Box<dyn Transport>, second: Box<dyn Transport>) -> Self {
+        Self {
+            seats: [Seat::new(first), Seat::new(second)],
+            first_mover: if rand::random() { Role::Initiator } else { Role::Responder },
+            started: false,
+            turn: 0,
+            winner: None,
+        }
+    }
+
+    /// Let `first_mover` take the first shot instead of a random player.
+    pub fn with_first_mover(mut self, first_mover: Role) -> Self {
+        self.first_mover = first_mover;
+        self
+    }
+
+    /// Apply `policy` to guesses at cells a player has already targeted.
+    pub fn with_duplicate_policy(mut self, policy: DuplicateGuessPolicy) -> Self {
+        for seat in &mut self.seats {
+            let engine = core::mem::take(&mut seat.engine);
+            seat.engine = engine.with_duplicate_policy(policy);
+        }
+        self
+    }
+
+    /// The engine the referee keeps for `player`: their own board and the
+    /// guesses they have made.
+    pub fn engine(&self, player: Role) -> &GameEngine {
+        &self.seats[index(player)].engine
+    }
+
+    /// Serve both players until the game is decided and both have
+    /// disconnected, or until one leaves early.
+    pub async fn run(&mut self) -> anyhow::Result<RefereeOutcome> {
+        while self.seats.iter().any(|s| s.open) {
+            let [first, second] = &mut self.seats;
+            let (player, received) = tokio::select! {
+                msg = first.transport.recv(), if first.open => (0, msg),
+                msg = second.transport.recv(), if second.open => (1, msg),
+            };
+            match received {
+                Ok(msg) => self.handle(player, msg).await?,
+                Err(e) => {
+                    self.seats[player].open = false;
+                    if self.winner.is_none() {
+                        tracing::info!(player = ?role(player), error = %e, "player left before the game was decided");
+                        return Ok(RefereeOutcome::Abandoned { by: role(player) });
+                    }
+                }
+            }
+        }
+        let winner = self.winner.ok_or_else(|| anyhow!("Referee stopped before the game was decided"))?;
+        Ok(RefereeOutcome::Finished {
+            winner: role(winner),
+            shots: [self.seats[0].shots, self.seats[1].shots],
+        })
+    }
+
+    async fn send(&mut self, player: usize, msg: Message) -> anyhow::Result<()> {
+        self.seats[player].transport.send(msg).await
+    }
+
+    /// Tell `player` why request `seq` failed before the session closes.
+    /// Best effort, like the Skeleton's report.
+    async fn report(&mut self, player: usize, seq: u64, code: ErrorCode, detail: String) {
+        let _ = self
+            .send(
+                player,
+                Message::Error {
+                    version: PROTOCOL_VERSION,
+                    seq,
+                    code,
+                    detail,
+                },
+            )
+            .await;
+    }
+
+    /// Accept request `seq` from `player` if it is the one expected next,
+    /// refusing it with an `Ack` otherwise (as a Skeleton does).
+    async fn in_sequence(&mut self, player: usize, version: u8, seq: u64) -> anyhow::Result<bool> {
+        if version != PROTOCOL_VERSION || seq != self.seats[player].next_seq {
+            self.send(player, Message::Ack { version: PROTOCOL_VERSION, seq }).await?;
+            return Ok(false);
+        }
+        self.seats[player].next_seq += 1;
+        Ok(true)
+    }
+
+    /// Handle one message from `player`.
+    async fn handle(&mut self, player: usize, msg: Message) -> anyhow::Result<()> {
+        match msg {
+            Message::Heartbeat { .. } | Message::Lobby { .. } => {}
+            Message::PlaceFleet {
+                version,
+                seq,
+                placements,
+            } => {
+                if !self.in_sequence(player, version, seq).await? {
+                    return Ok(());
+                }
+                let reply = if self.seats[player].placed {
+                    Message::PlacementRejected {
+                        version: PROTOCOL_VERSION,
+                        seq,
+                        reasons: vec!["fleet is already placed".to_string()],
+                    }
+                } else {
+                    match validation::validate_fleet(&self.seats[player].engine.rules(), &placements) {
+                        Ok(board) => {
+                            *self.seats[player].engine.board_mut() = board;
+                            self.seats[player].placed = true;
+                            Message::PlacementAccepted {
+                                version: PROTOCOL_VERSION,
+                                seq,
+                            }
+                        }
+                        Err(reasons) => Message::PlacementRejected {
+                            version: PROTOCOL_VERSION,
+                            seq,
+                            reasons,
+                        },
+                    }
+                };
+                self.send(player, reply).await?;
+                if !self.started && self.seats.iter().all(|s| s.placed) {
+                    self.started = true;
+                    self.turn = index(self.first_mover);
+                    tracing::info!(first = ?self.first_mover, "both fleets placed; refereed game started");
+                }
+                self.schedule().await?;
+            }
+            Message::Guess { version, seq, target } => {
+                if self.seats[player].pending.is_some() {
+                    // One guess at a time: the earlier one is still waiting
+                    self.send(player, Message::Ack { version: PROTOCOL_VERSION, seq }).await?;
+                    return Ok(());
+                }
+                if !self.in_sequence(player, version, seq).await? {
+                    return Ok(());
+                }
+                self.seats[player].pending = Some((seq, target));
+                self.schedule().await?;
+            }
+            Message::StatusReq { version, seq } | Message::GameStatusReq { version, seq } => {
+                if !self.in_sequence(player, version, seq).await? {
+                    return Ok(());
+                }
+                let status = GameApi::status(&self.seats[player].engine);
+                self.send(
+                    player,
+                    Message::GameStatusResp {
+                        version: PROTOCOL_VERSION,
+                        seq,
+                        status,
+                    },
+                )
+                .await?;
+            }
+            Message::ShipStatusReq { version, seq, id } => {
+                if !self.in_sequence(player, version, seq).await? {
+                    return Ok(());
+                }
+                let ship = match self.seats[player].engine.get_ship_status(id).await {
+                    Ok(ship) => ship,
+                    Err(e) => {
+                        self.report(player, seq, validation::error_code(&e), e.to_string()).await;
+                        return Err(e);
+                    }
+                };
+                self.send(
+                    player,
+                    Message::ShipStatusResp {
+                        version: PROTOCOL_VERSION,
+                        seq,
+                        ship,
+                    },
+                )
+                .await?;
+            }
+            Message::Resync {
+                version,
+                seq,
+                payload: None,
+            } if version == PROTOCOL_VERSION => {
+                self.seats[player].next_seq = seq.saturating_add(1);
+                self.send(
+                    player,
+                    Message::ResyncAck {
+                        version: PROTOCOL_VERSION,
+                        seq,
+                        digest: None,
+                    },
+                )
+                .await?;
+            }
+            Message::Error { code, detail, .. } => {
+                tracing::warn!(player = ?role(player), ?code, %detail, "player reported an error");
+                if code.is_fatal() {
+                    return Err(RemoteError { code, detail }.into());
+                }
+            }
+            Message::Resync { seq, .. } | Message::Sync { seq, .. } => {
+                self.report(player, seq, ErrorCode::InvalidState, "refereed state cannot be replaced".to_string())
+                    .await;
+                return Err(anyhow!(
+                    "Player {:?} tried to replace refereed game state (closing session)",
+                    role(player)
+                ));
+            }
+            _ => {
+                let seq = self.seats[player].next_seq;
+                self.send(player, Message::Ack { version: PROTOCOL_VERSION, seq }).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fire waiting guesses whose turn has come, and answer those that can
+    /// no longer be played.
+    async fn schedule(&mut self) -> anyhow::Result<()> {
+        loop {
+            if self.winner.is_some() {
+                for player in 0..2 {
+                    if let Some((seq, _)) = self.seats[player].pending.take() {
+                        let status = GameApi::status(&self.seats[player].engine);
+                        self.send(
+                            player,
+                            Message::GameStatusResp {
+                                version: PROTOCOL_VERSION,
+                                seq,
+                                status,
+                            },
+                        )
+                        .await?;
+                    }
+                }
+                return Ok(());
+            }
+            if !self.started {
+                return Ok(());
+            }
+            let shooter = self.turn;
+            let Some((seq, target)) = self.seats[shooter].pending.take() else {
+                return Ok(());
+            };
+            self.fire(shooter, seq, target).await?;
+        }
+    }
+
+    /// Adjudicate `shooter`'s guess against the other player's board.
+    async fn fire(&mut self, shooter: usize, seq: u64, target: Coord) -> anyhow::Result<()> {
+        let defender = 1 - shooter;
+        let Coord { row, col } = match validation::validate_guess(&self.seats[defender].engine, target) {
+            Ok(cell) => cell,
+            Err(v) => {
+                self.report(shooter, seq, v.code(), v.to_string()).await;
+                return Err(anyhow!("Player {:?} sent an illegal guess: {} (closing session)", role(shooter), v));
+            }
+        };
+        let result = self.seats[defender]
+            .engine
+            .opponent_guess(row, col)
+            .map_err(|e| anyhow!("Player {:?} guess at ({}, {}): {} (closing session)", role(shooter), row, col, e))?;
+        self.seats[shooter]
+            .engine
+            .record_guess(row, col, result)
+            .map_err(|e| anyhow!("Player {:?} guess at ({}, {}): {} (closing session)", role(shooter), row, col, e))?;
+        self.seats[shooter].shots += 1;
+        self.send(
+            shooter,
+            Message::StatusResp {
+                version: PROTOCOL_VERSION,
+                seq,
+                res: DomainGuessResult::from(result),
+                digest: None,
+            },
+        )
+        .await?;
+
+        if self.seats[defender].engine.board().all_sunk() {
+            self.winner = Some(shooter);
+            tracing::info!(winner = ?role(shooter), "refereed game decided");
+        } else {
+            self.turn = defender;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,312 @@
+//! Placement phase and the Ready/StartGame exchange that gates the first guess.
+//!
+//! Before any ship is placed both peers send `Rules` with the game config
+//! their engine was created with, and each refuses a peer whose config
+//! differs from its own, so peers set up for different board sizes, fleets
+//! or turn rules part before anyone spends time on placement.
+//!
+//! While a fleet is being placed the peer is sent a `PlacementProgress`
+//! notification every [`SetupConfig::progress_interval`], so it can tell a
+//! slow human from a dead connection. Each peer sends `Ready` once its fleet
+//! is placed and waits for the other side's `Ready`, giving up once the setup
+//! deadline passes. A side that gives up tells its peer with `Abort` first,
+//! and calls off its own placement through a shared cancel flag. The initiator then sends `StartGame` with the game config
+//! its engine was created with and the first mover from the coin flip; the
+//! responder checks both against its own view before play begins, so peers
+//! set up for different board sizes never start a game.
+
+#![cfg(feature = "std")]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use tokio::time::{Duration, Instant};
+
+use super::domain::GameConfig;
+use super::{Message, Role};
+use crate::transport::Transport;
+
+/// Default interval between `PlacementProgress` notifications (5 seconds).
+const DEFAULT_PROGRESS_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Default time allowed for both fleets to be placed (5 minutes).
+const DEFAULT_SETUP_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Timing of the placement phase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetupConfig {
+    /// How often to tell the peer that placement is still under way.
+    pub progress_interval: Duration,
+    /// Time allowed from the start of placement until the peer is ready.
+    pub timeout: Duration,
+}
+
+impl Default for SetupConfig {
+    fn default() -> Self {
+        Self {
+            progress_interval: DEFAULT_PROGRESS_INTERVAL,
+            timeout: DEFAULT_SETUP_TIMEOUT,
+        }
+    }
+}
+
+/// Exchange `Rules` over `transport` and check that the peer plays by the
+/// same `config`.
+///
+/// `version` is the protocol version agreed in the handshake. Returns the
+/// agreed config. An unexpected message, a version mismatch or a different
+/// config aborts the session.
+pub async fn agree_config<T>(transport: &mut T, version: u8, config: &GameConfig) -> anyhow::Result<GameConfig>
+where
+    T: Transport + ?Sized,
+{
+    transport
+        .send(Message::Rules {
+            version,
+            config: config.clone(),
+        })
+        .await?;
+    let peer_config = match transport.recv().await? {
+        Message::Rules { version: v, config } if v == version => config,
+        Message::Rules { version: v, .. } => {
+            eprintln!(
+                "[Setup] Protocol version mismatch in Rules: expected {}, got {}",
+                version, v
+            );
+            return Err(anyhow::anyhow!(
+                "Protocol version mismatch in Rules: expected {}, got {}",
+                version,
+                v
+            ));
+        }
+        other => {
+            eprintln!("[Setup] Expected Rules, got unexpected message: {:?}", other);
+            return Err(anyhow::anyhow!(
+                "Expected Rules, got unexpected message (closing session)"
+            ));
+        }
+    };
+    if peer_config != *config {
+        eprintln!(
+            "[Setup] Game config mismatch in Rules: expected {:?}, got {:?}",
+            config, peer_config
+        );
+        return Err(anyhow::anyhow!("Game config mismatch in Rules (closing session)"));
+    }
+    Ok(peer_config)
+}
+
+/// Run a blocking `place` closure while sending `PlacementProgress`, stamped
+/// with the agreed protocol `version`, to the peer.
+///
+/// Placement may wait on a human, so it runs on the blocking thread pool and
+/// owns whatever it needs; the closure hands those back through its result.
+/// If placement is still running at `deadline` the peer is sent `Abort`, the
+/// flag passed to `place` is set so that it can stop, and this fails.
+pub async fn run_placement<T, F, R>(
+    transport: &mut T,
+    version: u8,
+    config: &SetupConfig,
+    deadline: Instant,
+    place: F,
+) -> anyhow::Result<R>
+where
+    T: Transport + ?Sized,
+    F: FnOnce(Arc<AtomicBool>) -> R + Send + 'static,
+    R: Send + 'static,
+{
+    let started = Instant::now();
+    let cancel = Arc::new(AtomicBool::new(false));
+    let flag = cancel.clone();
+    let mut placing = tokio::task::spawn_blocking(move || place(flag));
+    let mut ticker = tokio::time::interval_at(
+        started + config.progress_interval,
+        config.progress_interval,
+    );
+
+    loop {
+        tokio::select! {
+            res = &mut placing => {
+                return res.map_err(|e| anyhow::anyhow!("Fleet placement task failed: {}", e));
+            }
+            _ = ticker.tick() => {
+                transport
+                    .send(Message::PlacementProgress {
+                        version,
+                        elapsed_secs: started.elapsed().as_secs() as u32,
+                    })
+                    .await?;
+            }
+            _ = tokio::time::sleep_until(deadline) => {
+                eprintln!(
+                    "[Setup] Fleet placement not finished within {}s",
+                    config.timeout.as_secs()
+                );
+                cancel.store(true, Ordering::SeqCst);
+                abort(
+                    transport,
+                    version,
+                    format!("fleet placement not finished within {}s", config.timeout.as_secs()),
+                )
+                .await;
+                return Err(anyhow::anyhow!(
+                    "Setup timed out after {}s: fleet placement not finished (closing session)",
+                    config.timeout.as_secs()
+                ));
+            }
+        }
+    }
+}
+
+/// Exchange `Ready` and `StartGame` over `transport`.
+///
+/// `version` is the protocol version agreed in the handshake, `config`
+/// describes the rules this side's engine plays by and `first_move` is its
+/// coin-flip outcome. Returns the agreed config. Any
+/// unexpected message, version mismatch, disagreement about the config or
+/// first mover, or a peer still placing at `deadline` aborts the session.
+pub async fn exchange_ready<T>(
+    transport: &mut T,
+    version: u8,
+    config: &GameConfig,
+    role: Role,
+    first_move: bool,
+    deadline: Instant,
+) -> anyhow::Result<GameConfig>
+where
+    T: Transport + ?Sized,
+{
+    let first_mover = match (role, first_move) {
+        (Role::Initiator, true) | (Role::Responder, false) => Role::Initiator,
+        (Role::Initiator, false) | (Role::Responder, true) => Role::Responder,
+    };
+    transport
+        .send(Message::Ready {
+            version,
+        })
+        .await?;
+    match tokio::time::timeout_at(deadline, recv_ready(transport, version)).await {
+        Ok(res) => res?,
+        Err(_) => {
+            eprintln!("[Setup] Peer did not finish placing ships before the setup deadline");
+            abort(transport, version, "peer did not finish placing ships in time".to_string()).await;
+            return Err(anyhow::anyhow!(
+                "Setup timed out waiting for peer to finish placing ships (closing session)"
+            ));
+        }
+    }
+
+    match role {
+        Role::Initiator => {
+            transport
+                .send(Message::StartGame {
+                    version,
+                    config: config.clone(),
+                    first_mover,
+                })
+                .await?;
+        }
+        Role::Responder => {
+            let (peer_config, peer_first_mover) = recv_start_game(transport, version).await?;
+            if peer_config != *config {
+                eprintln!(
+                    "[Setup] Game config mismatch in StartGame: expected {:?}, got {:?}",
+                    config, peer_config
+                );
+                return Err(anyhow::anyhow!(
+                    "Game config mismatch in StartGame (closing session)"
+                ));
+            }
+            if peer_first_mover != first_mover {
+                eprintln!(
+                    "[Setup] First mover mismatch in StartGame: expected {:?}, got {:?}",
+                    first_mover, peer_first_mover
+                );
+                return Err(anyhow::anyhow!(
+                    "First mover mismatch in StartGame (closing session)"
+                ));
+            }
+        }
+    }
+
+    Ok(config.clone())
+}
+
+/// Tell the peer that setup is called off for `reason`. The session is
+/// closing either way, so a failed send is only logged.
+async fn abort<T: Transport + ?Sized>(transport: &mut T, version: u8, reason: String) {
+    if let Err(e) = transport.send(Message::Abort { version, seq: 0, reason }).await {
+        eprintln!("[Setup] Could not send Abort to peer: {}", e);
+    }
+}
+
+async fn recv_ready<T: Transport + ?Sized>(transport: &mut T, expected: u8) -> anyhow::Result<()> {
+    loop {
+        match transport.recv().await? {
+            Message::PlacementProgress {
+                version,
+                elapsed_secs,
+            } if version == expected => {
+                eprintln!("[Setup] Peer still placing ships ({}s elapsed)", elapsed_secs);
+            }
+            other => return check_ready(other, expected),
+        }
+    }
+}
+
+fn check_ready(msg: Message, expected: u8) -> anyhow::Result<()> {
+    match msg {
+        Message::Ready { version } if version == expected => Ok(()),
+        Message::Abort { version, reason, .. } if version == expected => {
+            eprintln!("[Setup] Peer aborted setup: {}", reason);
+            Err(anyhow::anyhow!("Peer aborted setup: {} (closing session)", reason))
+        }
+        Message::Ready { version } => {
+            eprintln!(
+                "[Setup] Protocol version mismatch in Ready: expected {}, got {}",
+                expected, version
+            );
+            Err(anyhow::anyhow!(
+                "Protocol version mismatch in Ready: expected {}, got {}",
+                expected,
+                version
+            ))
+        }
+        other => {
+            eprintln!("[Setup] Expected Ready, got unexpected message: {:?}", other);
+            Err(anyhow::anyhow!(
+                "Expected Ready, got unexpected message (closing session)"
+            ))
+        }
+    }
+}
+
+async fn recv_start_game<T: Transport + ?Sized>(
+    transport: &mut T,
+    expected: u8,
+) -> anyhow::Result<(GameConfig, Role)> {
+    match transport.recv().await? {
+        Message::StartGame {
+            version,
+            config,
+            first_mover,
+        } if version == expected => Ok((config, first_mover)),
+        Message::StartGame { version, .. } => {
+            eprintln!(
+                "[Setup] Protocol version mismatch in StartGame: expected {}, got {}",
+                expected, version
+            );
+            Err(anyhow::anyhow!(
+                "Protocol version mismatch in StartGame: expected {}, got {}",
+                expected,
+                version
+            ))
+        }
+        other => {
+            eprintln!("[Setup] Expected StartGame, got unexpected message: {:?}", other);
+            Err(anyhow::anyhow!(
+                "Expected StartGame, got unexpected message (closing session)"
+            ))
+        }
+    }
+}
@@ -1,12 +1,72 @@
 #![cfg(feature = "std")]
 
-use super::{GameApi, Message, PROTOCOL_VERSION};
+use super::{
+    lobby::{GameId, ResumeToken},
+    domain::{ErrorCode, GameConfig, RemoteError, SyncPayload},
+    store::{GameStore, SavedGame},
+    handshake, validation, GameApi, Message, PROTOCOL_VERSION,
+};
+use crate::core::game::{GameEngine, GameState};
 use crate::transport::Transport;
+use anyhow::Context;
+use std::sync::Arc;
 
 pub struct Skeleton<E: GameApi, T: Transport> {
     engine: E,
     transport: T,
     next_seq: u64,
+    /// Reply to the most recent accepted request, kept so it can be replayed
+    /// to a client that reconnects without having seen it.
+    last_response: Option<Message>,
+    checkpoint: Option<Checkpoint<E>>,
+    aborted: bool,
+    /// Protocol version agreed with the client, or the current one if it
+    /// skipped the handshake.
+    version: u8,
+}
+
+/// Where a restored Skeleton saves its session after each accepted request.
+struct Checkpoint<E> {
+    id: GameId,
+    store: Arc<dyn GameStore>,
+    resume_token: ResumeToken,
+    snapshot: fn(&E) -> GameState,
+    /// Rules and duplicate policy the game was restored with; neither
+    /// changes once it has started.
+    config: GameConfig,
+}
+
+impl<T: Transport> Skeleton<GameEngine, T> {
+    /// Bring back game `game_id` from `store` and keep checkpointing it
+    /// there: engine state, next sequence number and last reply are saved
+    /// after every accepted request. The engine plays by the saved rules
+    /// and duplicate policy; a saved state those rules could not reach is
+    /// refused like a bad resync.
+    pub fn restore(game_id: GameId, store: Arc<dyn GameStore>, transport: T) -> anyhow::Result<Self> {
+        let saved = store
+            .load(game_id)?
+            .ok_or_else(|| anyhow::anyhow!("No saved game {}", game_id))?;
+        let rules = saved
+            .config
+            .rules()
+            .ok_or_else(|| anyhow::anyhow!("Saved game {} has unplayable rules {:?}", game_id, saved.config))?;
+        let payload = SyncPayload {
+            game_state: saved.state,
+            enemy_ships_remaining: saved.state.enemy_ships_remaining,
+        };
+        validation::validate_sync(&rules, &payload)
+            .with_context(|| format!("Saved game {} does not fit its rules", game_id))?;
+        let engine = GameEngine::from_state_with_config(saved.state, rules).with_duplicate_policy(saved.config.duplicate_policy);
+        let mut skeleton = Self::new(engine, transport).resumed(saved.next_seq, saved.last_response);
+        skeleton.checkpoint = Some(Checkpoint {
+            id: game_id,
+            store,
+            resume_token: saved.resume_token,
+            snapshot: GameEngine::state,
+            config: saved.config,
+        });
+        Ok(skeleton)
+    }
 }
 
 impl<E: GameApi, T: Transport> Skeleton<E, T> {
@@ -15,123 +75,415 @@ impl<E: GameApi, T: Transport> Skeleton<E, T> {
             engine,
             transport,
             next_seq: 0,
+            last_response: None,
+            checkpoint: None,
+            aborted: false,
+            version: PROTOCOL_VERSION,
         }
     }
+
+    /// Continue a session: expect request `next_seq` next and remember
+    /// `last_response` as the reply to the one before it.
+    pub fn resumed(mut self, next_seq: u64, last_response: Option<Message>) -> Self {
+        self.next_seq = next_seq;
+        self.last_response = last_response;
+        self
+    }
+
+    /// The engine being served.
+    pub fn engine(&self) -> &E {
+        &self.engine
+    }
+
+    /// Protocol version replies are stamped with and requests must carry.
+    pub fn protocol_version(&self) -> u8 {
+        self.version
+    }
+
+    /// Sequence number the next request must carry.
+    pub fn next_seq(&self) -> u64 {
+        self.next_seq
+    }
+
+    /// Reply to the most recent accepted request, if any.
+    pub fn last_response(&self) -> Option<&Message> {
+        self.last_response.as_ref()
+    }
+
+    /// Whether the client has aborted the game.
+    pub fn aborted(&self) -> bool {
+        self.aborted
+    }
+
+    /// Serve requests until the transport closes or the game is aborted.
     pub async fn run(&mut self) -> anyhow::Result<()> {
         while let Ok(msg) = self.transport.recv().await {
-            match msg {
-                Message::Handshake { version } => {
-                    // Respond to handshake with ack
+            self.handle(msg).await?;
+            if self.aborted {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    /// Receive the next message from the client.
+    pub async fn recv(&mut self) -> anyhow::Result<Message> {
+        self.transport.recv().await
+    }
+
+    /// Reply to an accepted request and remember the reply for replay.
+    ///
+    /// With a checkpoint the session is saved before the reply goes out, so
+    /// a crash can only lose a request the client has not had answered.
+    async fn respond(&mut self, msg: Message) -> anyhow::Result<()> {
+        self.last_response = Some(msg.clone());
+        if let Some(checkpoint) = &self.checkpoint {
+            let saved = SavedGame {
+                state: (checkpoint.snapshot)(&self.engine),
+                config: checkpoint.config.clone(),
+                next_seq: self.next_seq,
+                last_response: self.last_response.clone(),
+                resume_token: checkpoint.resume_token,
+            };
+            checkpoint
+                .store
+                .save(checkpoint.id, &saved)
+                .with_context(|| format!("Failed to checkpoint game {} (closing session)", checkpoint.id))?;
+        }
+        self.transport.send(msg).await
+    }
+
+    /// Tell the client why request `seq` failed and hand the error back, so
+    /// the caller can close the session with it. The report is best effort.
+    async fn fail(&mut self, seq: u64, error: anyhow::Error) -> anyhow::Error {
+        let _ = self
+            .transport
+            .send(Message::Error {
+                version: self.version,
+                seq,
+                code: validation::error_code(&error),
+                detail: error.to_string(),
+            })
+            .await;
+        error
+    }
+
+    /// Settle on the highest version both we and a client speaking
+    /// `min_version..=max_version` support, and confirm it.
+    async fn accept_handshake(&mut self, min_version: u8, max_version: u8) -> anyhow::Result<()> {
+        let Some(version) = handshake::negotiate(&handshake::SUPPORTED_VERSIONS, min_version, max_version) else {
+            let detail = format!(
+                "expected a protocol version in {}..={}, got {}..={}",
+                handshake::SUPPORTED_VERSIONS.start(),
+                handshake::SUPPORTED_VERSIONS.end(),
+                min_version,
+                max_version
+            );
+            let error = RemoteError {
+                code: ErrorCode::VersionMismatch,
+                detail,
+            };
+            if max_version <= handshake::FIRST_VERSION {
+                // Best effort, as in `fail`
+                let version = *handshake::SUPPORTED_VERSIONS.end();
+                let _ = self.transport.send(Message::HandshakeAck { version }).await;
+                return Err(error.into());
+            }
+            return Err(self.fail(0, error.into()).await);
+        };
+        self.version = version;
+        self.transport.send(Message::HandshakeAck { version }).await
+    }
+
+    /// Handle one message from the client.
+    pub async fn handle(&mut self, msg: Message) -> anyhow::Result<()> {
+        match msg {
+            Message::Handshake { version } => self.accept_handshake(version, version).await?,
+            Message::HandshakeRange {
+                min_version,
+                max_version,
+            } => self.accept_handshake(min_version, max_version).await?,
+            Message::HandshakeAck { .. } | Message::Lobby { .. } => {
+                // Handshake ack or lobby traffic, neither needs a reply here
+            }
+            Message::Guess { version, seq, target } => {
+                if version != self.version || seq != self.next_seq {
                     self.transport
-                        .send(Message::HandshakeAck { version })
+                        .send(Message::Ack {
+                            version: self.version,
+                            seq,
+                        })
                         .await?;
+                    return Ok(());
                 }
-                Message::HandshakeAck { .. } => {
-                    // Handshake ack received, continue
-                    continue;
-                }
-                Message::Guess { version, seq, x, y } => {
-                    if version != PROTOCOL_VERSION || seq != self.next_seq {
-                        self.transport
-                            .send(Message::Ack {
-                                version: PROTOCOL_VERSION,
-                                seq,
-                            })
-                            .await?;
-                        continue;
-                    }
-                    self.next_seq += 1;
-                    let res = self.engine.make_guess(x, y).await?;
+                self.next_seq += 1;
+                let res = match self.engine.make_guess(target).await {
+                    Ok(res) => res,
+                    Err(e) => return Err(self.fail(seq, e).await),
+                };
+                self.respond(Message::StatusResp {
+                    version: self.version,
+                    seq,
+                    res,
+                    digest: None,
+                })
+                .await?;
+            }
+            Message::GuessSalvo { version, seq, shots } => {
+                if version != self.version || seq != self.next_seq {
                     self.transport
-                        .send(Message::StatusResp {
-                            version: PROTOCOL_VERSION,
+                        .send(Message::Ack {
+                            version: self.version,
                             seq,
-                            res,
                         })
                         .await?;
+                    return Ok(());
                 }
-                Message::StatusReq { version, seq } | Message::GameStatusReq { version, seq } => {
-                    if version != PROTOCOL_VERSION || seq != self.next_seq {
-                        self.transport
-                            .send(Message::Ack {
-                                version: PROTOCOL_VERSION,
-                                seq,
-                            })
-                            .await?;
-                        continue;
-                    }
-                    self.next_seq += 1;
-                    let status = self.engine.status();
+                self.next_seq += 1;
+                let results = match self.engine.make_salvo(shots).await {
+                    Ok(results) => results,
+                    Err(e) => return Err(self.fail(seq, e).await),
+                };
+                self.respond(Message::SalvoResp {
+                    version: self.version,
+                    seq,
+                    results,
+                    digest: None,
+                })
+                .await?;
+            }
+            Message::StatusReq { version, seq } | Message::GameStatusReq { version, seq } => {
+                if version != self.version || seq != self.next_seq {
+                    self.transport
+                        .send(Message::Ack {
+                            version: self.version,
+                            seq,
+                        })
+                        .await?;
+                    return Ok(());
+                }
+                self.next_seq += 1;
+                let status = self.engine.status();
+                self.respond(Message::GameStatusResp {
+                    version: self.version,
+                    seq,
+                    status,
+                })
+                .await?;
+            }
+            Message::ShipStatusReq { version, seq, id } => {
+                if version != self.version || seq != self.next_seq {
                     self.transport
-                        .send(Message::GameStatusResp {
-                            version: PROTOCOL_VERSION,
+                        .send(Message::Ack {
+                            version: self.version,
                             seq,
-                            status,
                         })
                         .await?;
+                    return Ok(());
                 }
-                Message::ShipStatusReq { version, seq, id } => {
-                    if version != PROTOCOL_VERSION || seq != self.next_seq {
-                        self.transport
-                            .send(Message::Ack {
-                                version: PROTOCOL_VERSION,
-                                seq,
-                            })
-                            .await?;
-                        continue;
-                    }
-                    self.next_seq += 1;
-                    let ship = self.engine.get_ship_status(id).await?;
+                self.next_seq += 1;
+                let ship = match self.engine.get_ship_status(id).await {
+                    Ok(ship) => ship,
+                    Err(e) => return Err(self.fail(seq, e).await),
+                };
+                self.respond(Message::ShipStatusResp {
+                    version: self.version,
+                    seq,
+                    ship,
+                })
+                .await?;
+            }
+            Message::Sync {
+                version,
+                seq,
+                payload,
+            } => {
+                if version != self.version || seq != self.next_seq {
                     self.transport
-                        .send(Message::ShipStatusResp {
-                            version: PROTOCOL_VERSION,
+                        .send(Message::Ack {
+                            version: self.version,
                             seq,
-                            ship,
                         })
                         .await?;
+                    return Ok(());
                 }
-                Message::Sync {
-                    version,
+                self.next_seq += 1;
+                if let Err(e) = self.engine.sync_state(payload).await {
+                    return Err(self.fail(seq, e).await);
+                }
+                self.respond(Message::Ack {
+                    version: self.version,
                     seq,
-                    payload,
-                } => {
-                    if version != PROTOCOL_VERSION || seq != self.next_seq {
-                        self.transport
-                            .send(Message::Ack {
-                                version: PROTOCOL_VERSION,
-                                seq,
-                            })
-                            .await?;
-                        continue;
+                })
+                .await?;
+            }
+            Message::PlaceFleet {
+                version,
+                seq,
+                placements,
+            } => {
+                if version != self.version || seq != self.next_seq {
+                    self.transport
+                        .send(Message::Ack {
+                            version: self.version,
+                            seq,
+                        })
+                        .await?;
+                    return Ok(());
+                }
+                self.next_seq += 1;
+                let reply = match self.engine.place_fleet(placements).await? {
+                    Ok(()) => Message::PlacementAccepted {
+                        version: self.version,
+                        seq,
+                    },
+                    Err(reasons) => Message::PlacementRejected {
+                        version: self.version,
+                        seq,
+                        reasons,
+                    },
+                };
+                self.respond(reply).await?;
+            }
+            Message::Resync { version, seq, payload } => {
+                if version != self.version {
+                    self.transport
+                        .send(Message::Ack {
+                            version: self.version,
+                            seq,
+                        })
+                        .await?;
+                    return Ok(());
+                }
+                tracing::debug!(expected = self.next_seq, seq, with_state = payload.is_some(), "client resynced");
+                if let Some(payload) = payload {
+                    if let Err(e) = self.engine.sync_state(payload).await {
+                        let e = self.fail(seq, e).await;
+                        return Err(anyhow::anyhow!("Rejected resync: {} (closing session)", e));
                     }
-                    self.next_seq += 1;
-                    self.engine.sync_state(payload).await?;
+                }
+                self.next_seq = seq.saturating_add(1);
+                self.respond(Message::ResyncAck {
+                    version: self.version,
+                    seq,
+                    digest: self.engine.state_digest(),
+                })
+                .await?;
+            }
+            Message::ResyncDelta { version, seq, delta } => {
+                if version != self.version {
                     self.transport
                         .send(Message::Ack {
-                            version: PROTOCOL_VERSION,
+                            version: self.version,
                             seq,
                         })
                         .await?;
+                    return Ok(());
+                }
+                tracing::debug!(expected = self.next_seq, seq, shots = delta.shots(), "client resynced by delta");
+                if let Err(e) = self.engine.sync_delta(delta).await {
+                    let e = self.fail(seq, e).await;
+                    return Err(anyhow::anyhow!("Rejected resync delta: {} (closing session)", e));
                 }
-                Message::Heartbeat { .. } => {
-                    // Heartbeat received, respond with heartbeat to keep connection alive
+                self.next_seq = seq.saturating_add(1);
+                self.respond(Message::ResyncAck {
+                    version: self.version,
+                    seq,
+                    digest: self.engine.state_digest(),
+                })
+                .await?;
+            }
+            Message::Resign { version, seq } => {
+                if version != self.version || seq != self.next_seq {
                     self.transport
-                        .send(Message::Heartbeat {
-                            version: PROTOCOL_VERSION,
+                        .send(Message::Ack {
+                            version: self.version,
+                            seq,
                         })
                         .await?;
+                    return Ok(());
+                }
+                self.next_seq += 1;
+                if let Err(e) = self.engine.concede().await {
+                    return Err(self.fail(seq, e).await);
                 }
-                Message::ShipStatusResp { .. }
-                | Message::StatusResp { .. }
-                | Message::GameStatusResp { .. }
-                | Message::Ack { .. } => {
+                self.respond(Message::Ack {
+                    version: self.version,
+                    seq,
+                })
+                .await?;
+            }
+            Message::Abort { version, seq, reason } => {
+                if version != self.version || seq != self.next_seq {
                     self.transport
                         .send(Message::Ack {
-                            version: PROTOCOL_VERSION,
-                            seq: self.next_seq,
+                            version: self.version,
+                            seq,
                         })
                         .await?;
+                    return Ok(());
+                }
+                self.next_seq += 1;
+                if let Err(e) = self.engine.abort_game(reason).await {
+                    return Err(self.fail(seq, e).await);
                 }
+                self.aborted = true;
+                // An aborted game cannot be rejoined, so its checkpoint goes
+                if let Some(checkpoint) = self.checkpoint.take() {
+                    checkpoint
+                        .store
+                        .remove(checkpoint.id)
+                        .with_context(|| format!("Failed to release aborted game {}", checkpoint.id))?;
+                }
+                self.last_response = None;
+                self.transport
+                    .send(Message::Ack {
+                        version: self.version,
+                        seq,
+                    })
+                    .await?;
+            }
+            Message::Heartbeat { .. } => {
+                // Heartbeat received, respond with heartbeat to keep connection alive
+                self.transport
+                    .send(Message::Heartbeat {
+                        version: self.version,
+                    })
+                    .await?;
+            }
+            Message::Error { code, detail, .. } => {
+                tracing::warn!(?code, %detail, "client reported an error");
+                if code.is_fatal() {
+                    return Err(RemoteError { code, detail }.into());
+                }
+            }
+            Message::ShipStatusResp { .. }
+            | Message::StatusResp { .. }
+            | Message::SalvoResp { .. }
+            | Message::GameStatusResp { .. }
+            | Message::Ack { .. }
+            | Message::ResyncAck { .. }
+            | Message::PlacementAccepted { .. }
+            | Message::PlacementRejected { .. }
+            | Message::CoinCommit { .. }
+            | Message::CoinReveal { .. }
+            | Message::PlacementProgress { .. }
+            | Message::Ready { .. }
+            | Message::StartGame { .. }
+            | Message::Rules { .. }
+            | Message::Commit { .. }
+            | Message::Reveal { .. }
+            | Message::GameOver { .. }
+            | Message::Paired { .. }
+            | Message::TurnTimeout { .. }
+            | Message::RematchRequest { .. }
+            | Message::RematchAccept { .. } => {
+                self.transport
+                    .send(Message::Ack {
+                        version: self.version,
+                        seq: self.next_seq,
+                    })
+                    .await?;
             }
         }
         Ok(())
@@ -0,0 +1,164 @@
+//! Persistence for server-hosted games.
+//!
+//! A [`SavedGame`] holds everything needed to bring a hosted game back
+//! after a dropped connection or a server restart: the engine state and the
+//! rules it plays by, where the request sequence stands, the last reply
+//! sent, and the resume token.
+
+use super::{
+    domain::GameConfig,
+    lobby::{GameId, ResumeToken},
+    Message,
+};
+use crate::core::game::GameState;
+use anyhow::Context;
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Snapshot of one hosted game session.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SavedGame {
+    pub state: GameState,
+    /// Rules the engine plays by, custom fleets, salvo and duplicate
+    /// policy included.
+    pub config: GameConfig,
+    /// Sequence number the client's next request must carry.
+    pub next_seq: u64,
+    /// Reply to the last accepted request, replayed on rejoin.
+    pub last_response: Option<Message>,
+    pub resume_token: ResumeToken,
+}
+
+/// Storage for [`SavedGame`]s, keyed by game id.
+pub trait GameStore: Send + Sync {
+    fn save(&self, id: GameId, game: &SavedGame) -> anyhow::Result<()>;
+    fn load(&self, id: GameId) -> anyhow::Result<Option<SavedGame>>;
+    fn remove(&self, id: GameId) -> anyhow::Result<()>;
+    /// Ids of every stored game, in ascending order.
+    fn ids(&self) -> anyhow::Result<Vec<GameId>>;
+}
+
+/// Store that lives as long as the process; useful for tests and servers
+/// that only need to survive client reconnects.
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    games: Mutex<BTreeMap<GameId, SavedGame>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn games(&self) -> anyhow::Result<std::sync::MutexGuard<'_, BTreeMap<GameId, SavedGame>>> {
+        self.games.lock().map_err(|_| anyhow::anyhow!("Game store lock poisoned"))
+    }
+}
+
+impl GameStore for MemoryStore {
+    fn save(&self, id: GameId, game: &SavedGame) -> anyhow::Result<()> {
+        self.games()?.insert(id, game.clone());
+        Ok(())
+    }
+
+    fn load(&self, id: GameId) -> anyhow::Result<Option<SavedGame>> {
+        Ok(self.games()?.get(&id).cloned())
+    }
+
+    fn remove(&self, id: GameId) -> anyhow::Result<()> {
+        self.games()?.remove(&id);
+        Ok(())
+    }
+
+    fn ids(&self) -> anyhow::Result<Vec<GameId>> {
+        Ok(self.games()?.keys().copied().collect())
+    }
+}
+
+/// Store keeping one bincode file per game in a directory.
+///
+/// Each save writes and syncs a temporary file, renames it over the old
+/// one and then syncs the directory, so a crash mid-write leaves the
+/// previous snapshot intact and a save that returned survives power loss.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    dir: PathBuf,
+}
+
+impl FileStore {
+    /// Use `dir` for game files, creating it if needed.
+    pub fn new(dir: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let dir = dir.into();
+        std::fs::create_dir_all(&dir).with_context(|| format!("Failed to create game store {}", dir.display()))?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, id: GameId) -> PathBuf {
+        self.dir.join(format!("game-{}.bin", id))
+    }
+
+    /// Make a rename in the store directory durable.
+    #[cfg(unix)]
+    fn sync_dir(&self) -> anyhow::Result<()> {
+        std::fs::File::open(&self.dir)
+            .and_then(|dir| dir.sync_all())
+            .with_context(|| format!("Failed to sync game store {}", self.dir.display()))
+    }
+
+    /// Directories cannot be opened for syncing here; the rename is as
+    /// durable as the platform makes it.
+    #[cfg(not(unix))]
+    fn sync_dir(&self) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+impl GameStore for FileStore {
+    fn save(&self, id: GameId, game: &SavedGame) -> anyhow::Result<()> {
+        let data = bincode::serialize(game).map_err(|e| anyhow::anyhow!("Serialization error: {}", e))?;
+        let path = self.path(id);
+        let tmp = path.with_extension("bin.tmp");
+        let mut file = std::fs::File::create(&tmp).with_context(|| format!("Failed to create {}", tmp.display()))?;
+        file.write_all(&data)
+            .and_then(|()| file.sync_all())
+            .with_context(|| format!("Failed to write {}", tmp.display()))?;
+        drop(file);
+        std::fs::rename(&tmp, &path).with_context(|| format!("Failed to replace {}", path.display()))?;
+        self.sync_dir()
+    }
+
+    fn load(&self, id: GameId) -> anyhow::Result<Option<SavedGame>> {
+        let path = self.path(id);
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+        let game = bincode::deserialize(&data).map_err(|e| anyhow::anyhow!("Corrupt game file {}: {}", path.display(), e))?;
+        Ok(Some(game))
+    }
+
+    fn remove(&self, id: GameId) -> anyhow::Result<()> {
+        match std::fs::remove_file(self.path(id)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn ids(&self) -> anyhow::Result<Vec<GameId>> {
+        let mut ids = Vec::new();
+        for entry in std::fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            let id = name
+                .to_str()
+                .and_then(|n| n.strip_prefix("game-"))
+                .and_then(|n| n.strip_suffix(".bin"))
+                .and_then(|n| n.parse::<GameId>().ok());
+            ids.extend(id);
+        }
+        ids.sort_unstable();
+        Ok(ids)
+    }
+}
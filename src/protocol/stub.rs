@@ -1,16 +1,21 @@
 #![cfg(feature = "std")]
 
 use super::{
-    domain::{GameStatus, GuessResult, Ship, SyncPayload},
+    domain::{state_digest, GameConfig, GameStatus, GuessResult, Placement, RemoteError, Ship, SyncPayload},
+    lobby::{GameId, GameInfo, LobbyEvent, LobbyMessage, LobbyReply, ResumeToken},
     GameApi, Message, PROTOCOL_VERSION,
 };
+use crate::core::{coord::Coord, game::GameState};
 use crate::transport::Transport;
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::sync::Mutex;
 
 pub struct Stub<T: Transport> {
     transport: Mutex<T>,
     seq: AtomicU64,
+    /// Lobby events that arrived while waiting for a lobby reply.
+    lobby_events: std::sync::Mutex<VecDeque<LobbyEvent>>,
 }
 
 impl<T: Transport> Stub<T> {
@@ -18,88 +23,450 @@ impl<T: Transport> Stub<T> {
         Self {
             transport: Mutex::new(transport),
             seq: AtomicU64::new(0),
+            lobby_events: std::sync::Mutex::new(VecDeque::new()),
         }
     }
 
     fn next_seq(&self) -> u64 {
         self.seq.fetch_add(1, Ordering::SeqCst)
     }
-}
-#[async_trait::async_trait]
-impl<T: Transport> GameApi for Stub<T> {
-    async fn make_guess(&mut self, x: u8, y: u8) -> anyhow::Result<GuessResult> {
+
+    /// Give back the transport, e.g. to play the game a lobby request set up.
+    pub fn into_transport(self) -> T {
+        self.transport.into_inner()
+    }
+
+    /// Authenticate with a pre-shared token or signed ticket; returns the
+    /// identity the server granted.
+    pub async fn authenticate(&self, credential: impl Into<String>) -> anyhow::Result<String> {
+        let credential = credential.into();
+        match self.lobby_request(|seq| LobbyMessage::Authenticate { seq, credential }).await? {
+            LobbyReply::Authenticated(identity) => Ok(identity),
+            other => Err(anyhow::anyhow!("Unexpected lobby reply: {:?}", other)),
+        }
+    }
+
+    /// Games on the server waiting for an opponent.
+    pub async fn list_games(&self) -> anyhow::Result<Vec<GameInfo>> {
+        match self.lobby_request(|seq| LobbyMessage::ListGames { seq }).await? {
+            LobbyReply::Games(games) => Ok(games),
+            other => Err(anyhow::anyhow!("Unexpected lobby reply: {:?}", other)),
+        }
+    }
+
+    /// Host a game with the given rules; the server sends `Paired` once
+    /// someone joins.
+    pub async fn create_game(&self, config: GameConfig) -> anyhow::Result<GameId> {
+        match self.lobby_request(|seq| LobbyMessage::CreateGame { seq, config }).await? {
+            LobbyReply::Created(id) => Ok(id),
+            other => Err(anyhow::anyhow!("Unexpected lobby reply: {:?}", other)),
+        }
+    }
+
+    /// Join an open game; the server sends `Paired` when the game starts.
+    pub async fn join_game(&self, id: GameId) -> anyhow::Result<()> {
+        let request = |seq| LobbyMessage::JoinGame {
+            seq,
+            id,
+            resume_token: None,
+        };
+        match self.lobby_request(request).await? {
+            LobbyReply::Joined(joined) if joined == id => Ok(()),
+            other => Err(anyhow::anyhow!("Unexpected lobby reply: {:?}", other)),
+        }
+    }
+
+    /// Rejoin a server-hosted game after losing the connection.
+    ///
+    /// Continues this stub's sequence numbering where the server left off
+    /// and returns the responses the server replayed, which may include the
+    /// answer to a request whose reply was lost.
+    pub async fn rejoin_game(&self, id: GameId, resume_token: ResumeToken) -> anyhow::Result<Vec<Message>> {
+        let request = |seq| LobbyMessage::JoinGame {
+            seq,
+            id,
+            resume_token: Some(resume_token),
+        };
+        let (next_seq, replayed) = match self.lobby_request(request).await? {
+            LobbyReply::Rejoined {
+                id: joined,
+                next_seq,
+                replayed,
+            } if joined == id => (next_seq, replayed),
+            other => return Err(anyhow::anyhow!("Unexpected lobby reply: {:?}", other)),
+        };
+        self.seq.store(next_seq, Ordering::SeqCst);
+
+        let mut transport = self.transport.lock().await;
+        let mut responses = Vec::new();
+        while responses.len() < replayed as usize {
+            match transport.recv().await? {
+                Message::Heartbeat { .. } | Message::Lobby { .. } => {}
+                msg => responses.push(msg),
+            }
+        }
+        Ok(responses)
+    }
+
+    /// Withdraw the game this client is hosting.
+    pub async fn leave_game(&self) -> anyhow::Result<GameId> {
+        match self.lobby_request(|seq| LobbyMessage::LeaveGame { seq }).await? {
+            LobbyReply::Left(id) => Ok(id),
+            other => Err(anyhow::anyhow!("Unexpected lobby reply: {:?}", other)),
+        }
+    }
+
+    /// Next lobby event, waiting for one if none is buffered.
+    pub async fn next_lobby_event(&self) -> anyhow::Result<LobbyEvent> {
+        if let Some(event) = self.buffered_lobby_event() {
+            return Ok(event);
+        }
+        let mut transport = self.transport.lock().await;
+        loop {
+            match transport.recv().await? {
+                Message::Lobby {
+                    msg: LobbyMessage::Event(event),
+                    ..
+                } => return Ok(event),
+                Message::Heartbeat { .. } => {}
+                other => return Err(anyhow::anyhow!("Unexpected message: {:?}", other)),
+            }
+        }
+    }
+
+    /// Realign sequence numbers with the server after they drifted apart.
+    ///
+    /// Replies still in flight from before the resync are discarded. Game
+    /// requests do this on their own when a reply comes back out of
+    /// sequence.
+    pub async fn resync(&self) -> anyhow::Result<()> {
+        let mut transport = self.transport.lock().await;
+        self.realign(&mut *transport, resync(None), |_| {}).await.map(|_| ())
+    }
+
+    /// Resync and replace the server's game state with `payload`.
+    ///
+    /// Fails if the digest the server reports afterwards differs from the
+    /// digest of the state sent.
+    pub async fn resync_state(&self, payload: SyncPayload) -> anyhow::Result<()> {
+        let expected = state_digest(&payload.game_state);
+        let mut transport = self.transport.lock().await;
+        match self.realign(&mut *transport, resync(Some(payload)), |_| {}).await? {
+            Some(digest) if digest != expected => {
+                Err(anyhow::anyhow!("Server state differs from the state sent in resync (closing session)"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Resync and bring the server's game state from `base` forward to
+    /// `state` by sending only the shots between them.
+    ///
+    /// Fails if the server holds another state than `base`, or if the
+    /// digest it reports afterwards differs from the digest of `state`.
+    pub async fn resync_delta(&self, base: &GameState, state: &GameState) -> anyhow::Result<()> {
+        let delta = state.diff(base);
+        let expected = state_digest(state);
+        let mut transport = self.transport.lock().await;
+        let request = |seq| Message::ResyncDelta {
+            version: PROTOCOL_VERSION,
+            seq,
+            delta,
+        };
+        match self.realign(&mut *transport, request, |_| {}).await? {
+            Some(digest) if digest != expected => {
+                Err(anyhow::anyhow!("Server state differs from the state sent in resync (closing session)"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Send `request(seq)`, a `Resync` or `ResyncDelta`, and pass everything
+    /// received before its `ResyncAck` to `stale`. Returns the server's
+    /// state digest, if it sent one.
+    async fn realign(
+        &self,
+        transport: &mut T,
+        request: impl FnOnce(u64) -> Message,
+        mut stale: impl FnMut(Message),
+    ) -> anyhow::Result<Option<[u8; 32]>> {
+        let seq = self.next_seq();
+        transport.send(request(seq)).await?;
+        loop {
+            match self.recv_reply(transport).await? {
+                Message::ResyncAck {
+                    seq: resp_seq, digest, ..
+                } if resp_seq == seq => return Ok(digest),
+                msg => match remote_error(&msg, seq) {
+                    Some(error) => return Err(error.into()),
+                    None => stale(msg),
+                },
+            }
+        }
+    }
+
+    /// Send request `build(seq)` and pick the answer out of the reply with
+    /// `accept`.
+    ///
+    /// A reply numbered for another request, or a bare `Ack` where another
+    /// reply was due (the server refused the number), means the two sides'
+    /// sequence numbers drifted apart. The stub then resyncs; if the real
+    /// answer turns up among the stale replies it is used, otherwise the
+    /// request is sent once more under the new numbering. A fatal
+    /// `Message::Error` for the request is returned as a [`RemoteError`].
+    async fn call<R>(
+        &self,
+        build: impl Fn(u64) -> Message,
+        accept: impl Fn(&Message, u64) -> Option<R>,
+    ) -> anyhow::Result<R> {
+        let mut transport = self.transport.lock().await;
+        let seq = self.next_seq();
+        transport.send(build(seq)).await?;
+        let reply = self.recv_reply(&mut *transport).await?;
+        if let Some(answer) = accept(&reply, seq) {
+            return Ok(answer);
+        }
+        if let Some(error) = remote_error(&reply, seq).filter(|e| e.code.is_fatal()) {
+            return Err(error.into());
+        }
+        let Some(reply_seq) = response_seq(&reply) else {
+            return Err(anyhow::anyhow!("Unexpected message"));
+        };
+        tracing::warn!(expected = seq, got = reply_seq, "reply out of sequence; resyncing with server");
+
+        let mut answer = None;
+        self.realign(&mut *transport, resync(None), |msg| {
+            if answer.is_none() {
+                answer = match remote_error(&msg, seq).filter(|e| e.code.is_fatal()) {
+                    Some(error) => Some(Err(error)),
+                    None => accept(&msg, seq).map(Ok),
+                };
+            }
+        })
+        .await?;
+        if let Some(answer) = answer {
+            return answer.map_err(anyhow::Error::from);
+        }
+        let seq = self.next_seq();
+        transport.send(build(seq)).await?;
+        let reply = self.recv_reply(&mut *transport).await?;
+        if let Some(error) = remote_error(&reply, seq) {
+            return Err(error.into());
+        }
+        accept(&reply, seq).ok_or_else(|| anyhow::anyhow!("Unexpected message after resync: {:?}", reply))
+    }
+
+    /// Next message that is not a heartbeat, buffering lobby events.
+    async fn recv_reply(&self, transport: &mut T) -> anyhow::Result<Message> {
+        loop {
+            match transport.recv().await? {
+                Message::Heartbeat { .. } => {}
+                Message::Lobby {
+                    msg: LobbyMessage::Event(event),
+                    ..
+                } => {
+                    if let Ok(mut events) = self.lobby_events.lock() {
+                        events.push_back(event);
+                    }
+                }
+                msg => return Ok(msg),
+            }
+        }
+    }
+
+    fn buffered_lobby_event(&self) -> Option<LobbyEvent> {
+        self.lobby_events.lock().ok()?.pop_front()
+    }
+
+    async fn lobby_request(&self, request: impl FnOnce(u64) -> LobbyMessage) -> anyhow::Result<LobbyReply> {
         let mut transport = self.transport.lock().await;
         let seq = self.next_seq();
         transport
-            .send(Message::Guess {
+            .send(Message::Lobby {
                 version: PROTOCOL_VERSION,
-                seq,
-                x,
-                y,
+                msg: request(seq),
             })
             .await?;
-        match transport.recv().await? {
-            Message::StatusResp {
-                seq: resp_seq, res, ..
-            } if resp_seq == seq => Ok(res),
-            _ => Err(anyhow::anyhow!("Unexpected message")),
+        loop {
+            match transport.recv().await? {
+                Message::Lobby {
+                    msg: LobbyMessage::Reply { seq: resp_seq, result },
+                    ..
+                } if resp_seq == seq => return result.map_err(anyhow::Error::from),
+                Message::Lobby {
+                    msg: LobbyMessage::Event(event),
+                    ..
+                } => {
+                    if let Ok(mut events) = self.lobby_events.lock() {
+                        events.push_back(event);
+                    }
+                }
+                Message::Heartbeat { .. } => {}
+                _ => return Err(anyhow::anyhow!("Unexpected message")),
+            }
         }
     }
+}
+#[async_trait::async_trait]
+impl<T: Transport> GameApi for Stub<T> {
+    async fn make_guess(&mut self, target: Coord) -> anyhow::Result<GuessResult> {
+        self.call(
+            |seq| Message::Guess {
+                version: PROTOCOL_VERSION,
+                seq,
+                target,
+            },
+            |reply, seq| match reply {
+                Message::StatusResp { seq: resp_seq, res, .. } if *resp_seq == seq => Some(res.clone()),
+                _ => None,
+            },
+        )
+        .await
+    }
+    async fn make_salvo(&mut self, shots: Vec<(u8, u8)>) -> anyhow::Result<Vec<GuessResult>> {
+        self.call(
+            |seq| Message::GuessSalvo {
+                version: PROTOCOL_VERSION,
+                seq,
+                shots: shots.clone(),
+            },
+            |reply, seq| match reply {
+                Message::SalvoResp { seq: resp_seq, results, .. } if *resp_seq == seq => Some(results.clone()),
+                _ => None,
+            },
+        )
+        .await
+    }
     async fn get_ship_status(&self, ship_id: usize) -> anyhow::Result<Ship> {
-        let mut transport = self.transport.lock().await;
-        let seq = self.next_seq();
-        transport
-            .send(Message::ShipStatusReq {
+        self.call(
+            |seq| Message::ShipStatusReq {
                 version: PROTOCOL_VERSION,
                 seq,
                 id: ship_id,
-            })
-            .await?;
-        match transport.recv().await? {
-            Message::ShipStatusResp {
-                seq: resp_seq,
-                ship,
-                ..
-            } if resp_seq == seq => Ok(ship),
-            _ => Err(anyhow::anyhow!("Unexpected message")),
-        }
+            },
+            |reply, seq| match reply {
+                Message::ShipStatusResp { seq: resp_seq, ship, .. } if *resp_seq == seq => Some(ship.clone()),
+                _ => None,
+            },
+        )
+        .await
     }
     async fn sync_state(&mut self, payload: SyncPayload) -> anyhow::Result<()> {
-        let mut transport = self.transport.lock().await;
-        let seq = self.next_seq();
-        transport
-            .send(Message::Sync {
+        self.call(
+            |seq| Message::Sync {
                 version: PROTOCOL_VERSION,
                 seq,
-                payload,
-            })
-            .await?;
-        match transport.recv().await? {
-            Message::Ack { seq: resp_seq, .. } if resp_seq == seq => Ok(()),
-            _ => Err(anyhow::anyhow!("Unexpected message")),
-        }
+                payload: payload.clone(),
+            },
+            |reply, seq| match reply {
+                Message::Ack { seq: resp_seq, .. } if *resp_seq == seq => Some(()),
+                _ => None,
+            },
+        )
+        .await
+    }
+    async fn place_fleet(&mut self, placements: Vec<Placement>) -> anyhow::Result<Result<(), Vec<String>>> {
+        self.call(
+            |seq| Message::PlaceFleet {
+                version: PROTOCOL_VERSION,
+                seq,
+                placements: placements.clone(),
+            },
+            |reply, seq| match reply {
+                Message::PlacementAccepted { seq: resp_seq, .. } if *resp_seq == seq => Some(Ok(())),
+                Message::PlacementRejected {
+                    seq: resp_seq, reasons, ..
+                } if *resp_seq == seq => Some(Err(reasons.clone())),
+                _ => None,
+            },
+        )
+        .await
+    }
+    async fn concede(&mut self) -> anyhow::Result<()> {
+        self.call(
+            |seq| Message::Resign {
+                version: PROTOCOL_VERSION,
+                seq,
+            },
+            |reply, seq| match reply {
+                Message::Ack { seq: resp_seq, .. } if *resp_seq == seq => Some(()),
+                _ => None,
+            },
+        )
+        .await
+    }
+    async fn abort_game(&mut self, reason: String) -> anyhow::Result<()> {
+        self.call(
+            |seq| Message::Abort {
+                version: PROTOCOL_VERSION,
+                seq,
+                reason: reason.clone(),
+            },
+            |reply, seq| match reply {
+                Message::Ack { seq: resp_seq, .. } if *resp_seq == seq => Some(()),
+                _ => None,
+            },
+        )
+        .await
     }
     fn status(&self) -> GameStatus {
         tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
-                let mut transport = self.transport.lock().await;
-                let seq = self.next_seq();
-                transport
-                    .send(Message::GameStatusReq {
+                self.call(
+                    |seq| Message::GameStatusReq {
                         version: PROTOCOL_VERSION,
                         seq,
-                    })
-                    .await
-                    .unwrap();
-                match transport.recv().await.unwrap() {
-                    Message::GameStatusResp {
-                        seq: resp_seq,
-                        status,
-                        ..
-                    } if resp_seq == seq => status,
-                    _ => panic!("Unexpected message"),
-                }
+                    },
+                    |reply, seq| match reply {
+                        Message::GameStatusResp { seq: resp_seq, status, .. } if *resp_seq == seq => Some(status.clone()),
+                        _ => None,
+                    },
+                )
+                .await
+                .unwrap()
             })
         })
     }
 }
+
+/// Sequence number of a reply to a numbered request, if `msg` is one.
+fn response_seq(msg: &Message) -> Option<u64> {
+    match msg {
+        Message::StatusResp { seq, .. }
+        | Message::SalvoResp { seq, .. }
+        | Message::ShipStatusResp { seq, .. }
+        | Message::GameStatusResp { seq, .. }
+        | Message::Ack { seq, .. }
+        | Message::ResyncAck { seq, .. }
+        | Message::PlacementAccepted { seq, .. }
+        | Message::PlacementRejected { seq, .. }
+        | Message::Error { seq, .. } => Some(*seq),
+        _ => None,
+    }
+}
+
+/// `msg` as an error value if it is a `Message::Error` for request `seq`.
+fn remote_error(msg: &Message, seq: u64) -> Option<RemoteError> {
+    match msg {
+        Message::Error {
+            seq: resp_seq,
+            code,
+            detail,
+            ..
+        } if *resp_seq == seq => Some(RemoteError {
+            code: *code,
+            detail: detail.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Builder for a `Resync` numbered `seq`, replacing the server's state with
+/// `payload` if given.
+fn resync(payload: Option<SyncPayload>) -> impl FnOnce(u64) -> Message {
+    move |seq| Message::Resync {
+        version: PROTOCOL_VERSION,
+        seq,
+        payload,
+    }
+}
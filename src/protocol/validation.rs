@@ -0,0 +1,332 @@
+//! Validation of inbound protocol values before they reach the game engine.
+//!
+//! Coordinates and results arrive from an untrusted peer. Every value is
+//! checked here against the engine's view of the game, and anything that
+//! could not happen in an honest game is rejected as a [`ProtocolViolation`]
+//! instead of being converted with `as usize` and trusted.
+
+#![cfg(feature = "std")]
+
+use super::domain::{ErrorCode, GuessResult as DomainGuessResult, Placement, RemoteError, SyncPayload};
+use crate::core::{
+    board::{Board, LayoutError},
+    common::GuessResult,
+    config::{BoardBits, FleetConfig, RuleSet, MAX_SHIPS},
+    coord::Coord,
+    game::{DuplicateGuessPolicy, GameEngine, GameStatus},
+};
+use core::fmt;
+
+/// A message value that an honest peer could not have sent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolViolation {
+    /// Guess coordinates outside the `size`×`size` board.
+    CoordinateOutOfBounds { target: Coord, size: u8 },
+    /// A guess or result arrived after the game was decided.
+    GameFinished,
+    /// Sink reported for a ship name not in the fleet.
+    UnknownShip(String),
+    /// Sink reported twice for the same ship.
+    ShipAlreadySunk(&'static str),
+    /// Sink reported before enough hits to cover every sunk ship.
+    ImpossibleSink {
+        ship: &'static str,
+        hits: usize,
+        required: usize,
+    },
+    /// Hit reported that would leave every fleet cell hit with a ship afloat.
+    ImpossibleHit { hits: usize },
+    /// A sync payload describes a state no game could reach.
+    InvalidSync(&'static str),
+    /// A board received from the peer is not a legal layout of the fleet.
+    InvalidLayout(LayoutError),
+    /// A repeated guess was answered differently from what the duplicate
+    /// guess policy allows.
+    InconsistentRepeat { row: usize, col: usize },
+    /// A salvo, or its answer, held the wrong number of shots.
+    SalvoSize { expected: usize, got: usize },
+    /// A salvo fired twice at the same cell.
+    DuplicateShot { x: u8, y: u8 },
+}
+
+impl ProtocolViolation {
+    /// The `Message::Error` code reporting this violation to the peer.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ProtocolViolation::CoordinateOutOfBounds { .. } => ErrorCode::InvalidCoordinate,
+            ProtocolViolation::InvalidSync(_) | ProtocolViolation::InvalidLayout(_) => ErrorCode::InvalidState,
+            _ => ErrorCode::RuleViolation,
+        }
+    }
+}
+
+impl fmt::Display for ProtocolViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolViolation::CoordinateOutOfBounds { target, size } => {
+                write!(f, "guess ({}, {}) is outside the {}x{} board", target.row, target.col, size, size)
+            }
+            ProtocolViolation::GameFinished => write!(f, "game is already finished"),
+            ProtocolViolation::UnknownShip(name) => write!(f, "sink reported for unknown ship {:?}", name),
+            ProtocolViolation::ShipAlreadySunk(name) => write!(f, "{} was already sunk", name),
+            ProtocolViolation::ImpossibleSink { ship, hits, required } => write!(
+                f,
+                "sink of {} after {} hits, but the sunk ships need at least {}",
+                ship, hits, required
+            ),
+            ProtocolViolation::ImpossibleHit { hits } => write!(
+                f,
+                "hit number {} would cover every fleet cell without a final sink",
+                hits
+            ),
+            ProtocolViolation::InvalidSync(reason) => write!(f, "invalid sync payload: {}", reason),
+            ProtocolViolation::InvalidLayout(e) => write!(f, "invalid fleet layout: {}", e),
+            ProtocolViolation::InconsistentRepeat { row, col } => write!(
+                f,
+                "repeated guess ({}, {}) answered inconsistently with the duplicate guess policy",
+                row, col
+            ),
+            ProtocolViolation::SalvoSize { expected, got } => {
+                write!(f, "salvo of {} shots where {} were due", got, expected)
+            }
+            ProtocolViolation::DuplicateShot { x, y } => write!(f, "salvo fires at ({}, {}) twice", x, y),
+        }
+    }
+}
+
+impl std::error::Error for ProtocolViolation {}
+
+/// The `Message::Error` code for a failed request: the violation's own code
+/// when the error is a [`ProtocolViolation`], otherwise a rule violation.
+pub fn error_code(error: &anyhow::Error) -> ErrorCode {
+    if let Some(violation) = error.downcast_ref::<ProtocolViolation>() {
+        violation.code()
+    } else if let Some(remote) = error.downcast_ref::<RemoteError>() {
+        remote.code
+    } else {
+        ErrorCode::RuleViolation
+    }
+}
+
+/// Check an opponent's guess against our engine and return it once it is
+/// known to lie on the board.
+pub fn validate_guess(engine: &GameEngine, target: Coord) -> Result<Coord, ProtocolViolation> {
+    if engine.status() != GameStatus::InProgress {
+        return Err(ProtocolViolation::GameFinished);
+    }
+    let rules = engine.rules();
+    if !rules.contains(target.row, target.col) {
+        return Err(ProtocolViolation::CoordinateOutOfBounds { target, size: rules.board_size() });
+    }
+    Ok(target)
+}
+
+/// Check an opponent's salvo against our engine: one shot for each the
+/// opponent is due this turn, each on the board and none repeated. Returns
+/// the shots as board positions in firing order.
+pub fn validate_salvo(engine: &GameEngine, shots: &[(u8, u8)]) -> Result<Vec<(usize, usize)>, ProtocolViolation> {
+    let expected = engine.opponent_shots_per_turn();
+    if shots.len() != expected {
+        return Err(ProtocolViolation::SalvoSize { expected, got: shots.len() });
+    }
+    let mut cells = Vec::with_capacity(shots.len());
+    for &(x, y) in shots {
+        let cell = validate_guess(engine, Coord::new(x as usize, y as usize))?.into();
+        if cells.contains(&cell) {
+            return Err(ProtocolViolation::DuplicateShot { x, y });
+        }
+        cells.push(cell);
+    }
+    Ok(cells)
+}
+
+/// Check the opponent's answer to our guess and convert it for the engine.
+pub fn validate_result(
+    engine: &GameEngine,
+    res: &DomainGuessResult,
+) -> Result<GuessResult, ProtocolViolation> {
+    if engine.status() != GameStatus::InProgress {
+        return Err(ProtocolViolation::GameFinished);
+    }
+    let rules = engine.rules();
+    let fleet = rules.fleet();
+    let hits = engine.guess_hits().count_ones() + 1;
+    match res {
+        DomainGuessResult::Miss => Ok(GuessResult::Miss),
+        DomainGuessResult::Hit => {
+            if hits >= fleet.total_cells() {
+                return Err(ProtocolViolation::ImpossibleHit { hits });
+            }
+            Ok(GuessResult::Hit)
+        }
+        DomainGuessResult::Sink(name) => {
+            let ship = fleet_name(fleet, name)?;
+            let afloat = engine.enemy_ships_remaining();
+            let Some(index) = (0..fleet.len()).find(|&i| afloat[i] && fleet.ships()[i].name() == ship) else {
+                return Err(ProtocolViolation::ShipAlreadySunk(ship));
+            };
+            let required = fleet
+                .ships()
+                .iter()
+                .zip(afloat.iter())
+                .enumerate()
+                .filter(|&(i, (_, &up))| !up || i == index)
+                .map(|(_, (def, _))| def.length())
+                .sum();
+            if hits < required {
+                return Err(ProtocolViolation::ImpossibleSink { ship, hits, required });
+            }
+            Ok(GuessResult::Sink(ship))
+        }
+    }
+}
+
+/// Check the answer to a guess at a cell we had already fired at, under the
+/// [`DuplicateGuessPolicy`] agreed with the peer: the first answer again, or
+/// a miss for a wasted turn. A policy that rejects repeats accepts no answer.
+pub fn validate_repeat(
+    engine: &GameEngine,
+    policy: DuplicateGuessPolicy,
+    row: usize,
+    col: usize,
+    res: &DomainGuessResult,
+) -> Result<GuessResult, ProtocolViolation> {
+    if engine.status() != GameStatus::InProgress {
+        return Err(ProtocolViolation::GameFinished);
+    }
+    let was_hit = engine.guess_hits().get(row, col).unwrap_or(false);
+    let consistent = match (policy, res) {
+        (DuplicateGuessPolicy::Error, _) => false,
+        (DuplicateGuessPolicy::WastedTurn, DomainGuessResult::Miss) => true,
+        (DuplicateGuessPolicy::WastedTurn, _) => false,
+        (DuplicateGuessPolicy::IdempotentReturnPrior, DomainGuessResult::Miss) => !was_hit,
+        (DuplicateGuessPolicy::IdempotentReturnPrior, DomainGuessResult::Hit) => was_hit,
+        (DuplicateGuessPolicy::IdempotentReturnPrior, DomainGuessResult::Sink(name)) => {
+            let rules = engine.rules();
+            let fleet = rules.fleet();
+            let ship = fleet_name(fleet, name)?;
+            let afloat = engine.enemy_ships_remaining();
+            was_hit && (0..fleet.len()).any(|i| !afloat[i] && fleet.ships()[i].name() == ship)
+        }
+    };
+    if !consistent {
+        return Err(ProtocolViolation::InconsistentRepeat { row, col });
+    }
+    Ok(match res {
+        DomainGuessResult::Miss => GuessResult::Miss,
+        DomainGuessResult::Hit => GuessResult::Hit,
+        DomainGuessResult::Sink(name) => GuessResult::Sink(fleet_name(engine.rules().fleet(), name)?),
+    })
+}
+
+/// Convert the answer to a shot the peer fired on our behalf after we
+/// resigned. Only the ship name can be checked here; the layout reveal at
+/// the end of the game checks the result itself.
+pub fn validate_late_result(engine: &GameEngine, res: &DomainGuessResult) -> Result<GuessResult, ProtocolViolation> {
+    match res {
+        DomainGuessResult::Miss => Ok(GuessResult::Miss),
+        DomainGuessResult::Hit => Ok(GuessResult::Hit),
+        DomainGuessResult::Sink(name) => fleet_name(engine.rules().fleet(), name).map(GuessResult::Sink),
+    }
+}
+
+/// The fleet's own name for a ship named by the peer.
+fn fleet_name(fleet: &FleetConfig, name: &str) -> Result<&'static str, ProtocolViolation> {
+    fleet
+        .index_of(name)
+        .map(|i| fleet.ships()[i].name())
+        .ok_or_else(|| ProtocolViolation::UnknownShip(name.to_string()))
+}
+
+/// Check that a sync payload describes a state an honest game under `rules`
+/// could reach before an engine is rebuilt from it.
+pub fn validate_sync(rules: &RuleSet, payload: &SyncPayload) -> Result<(), ProtocolViolation> {
+    let state = &payload.game_state;
+    if payload.enemy_ships_remaining != state.enemy_ships_remaining {
+        return Err(ProtocolViolation::InvalidSync("enemy fleet summary disagrees with the game state"));
+    }
+
+    let board = &state.my_board;
+    let guesses = &state.my_guesses;
+    if board.size != rules.board_size() {
+        return Err(ProtocolViolation::InvalidSync("board size differs from the agreed rules"));
+    }
+    let outside = rules.off_board();
+    let off_board = |cells: BoardBits| {
+        cells != (cells & !BoardBits::new()) || !(cells & outside).is_empty()
+    };
+    if [board.ship_map, board.hits, board.misses, guesses.hits, guesses.misses]
+        .into_iter()
+        .any(off_board)
+    {
+        return Err(ProtocolViolation::InvalidSync("cells outside the board"));
+    }
+
+    let ships = rules.fleet();
+    if state.enemy_ships_remaining[ships.len()..].iter().any(|&afloat| afloat) {
+        return Err(ProtocolViolation::InvalidSync("more ships than the agreed fleet"));
+    }
+    // A board still being set up has no ships; once any is placed the
+    // whole fleet must be
+    let unplaced = board.ship_states.iter().all(|s| s.position.is_none()) && board.ship_map.is_empty();
+    if !unplaced {
+        board.validate(ships).map_err(ProtocolViolation::InvalidLayout)?;
+    }
+    let fleet = board.ship_map;
+    if !(board.hits & !fleet).is_empty() || !(board.misses & fleet).is_empty() {
+        return Err(ProtocolViolation::InvalidSync("shots on own board disagree with the fleet"));
+    }
+
+    if !(guesses.hits & guesses.misses).is_empty() {
+        return Err(ProtocolViolation::InvalidSync("a cell is both a hit and a miss"));
+    }
+    let hits = guesses.hits.count_ones();
+    let total = ships.total_cells();
+    if hits > total || state.enemy_remaining != total - hits {
+        return Err(ProtocolViolation::InvalidSync("enemy cells remaining disagrees with hits"));
+    }
+    let sunk_cells: usize = ships
+        .ships()
+        .iter()
+        .zip(state.enemy_ships_remaining.iter())
+        .filter(|(_, &afloat)| !afloat)
+        .map(|(def, _)| def.length())
+        .sum();
+    if sunk_cells > hits {
+        return Err(ProtocolViolation::InvalidSync("more enemy ships sunk than hits allow"));
+    }
+    Ok(())
+}
+
+/// Build a board from a submitted fleet, or list every reason it is illegal:
+/// unknown or repeated ships, missing ships, ships off the board and
+/// overlaps.
+pub fn validate_fleet(rules: &RuleSet, placements: &[Placement]) -> Result<Board, Vec<String>> {
+    let mut board = Board::with_rules(rules);
+    let mut reasons = Vec::new();
+    let fleet = rules.fleet();
+    let mut seen = [false; MAX_SHIPS];
+    for p in placements {
+        if fleet.index_of(&p.ship).is_none() {
+            reasons.push(format!("unknown ship {:?}", p.ship));
+            continue;
+        }
+        // Ships sharing a name fill the fleet's slots in order
+        let Some(index) = (0..fleet.len()).find(|&i| !seen[i] && fleet.ships()[i].name() == p.ship) else {
+            reasons.push(format!("{} placed more than once", p.ship));
+            continue;
+        };
+        seen[index] = true;
+        if let Err(e) = board.place(index, p.row as usize, p.col as usize, p.orientation) {
+            reasons.push(format!("{} at ({}, {}) {:?}: {}", p.ship, p.row, p.col, p.orientation, e));
+        }
+    }
+    for (def, _) in fleet.ships().iter().zip(seen).filter(|(_, placed)| !placed) {
+        reasons.push(format!("{} is missing", def.name()));
+    }
+    if reasons.is_empty() {
+        Ok(board)
+    } else {
+        Err(reasons)
+    }
+}
@@ -3,7 +3,7 @@
 use tokio::time::{interval, Duration, Instant};
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
-use crate::protocol::{Message, PROTOCOL_VERSION};
+use crate::protocol::{handshake::SUPPORTED_VERSIONS, Message, PROTOCOL_VERSION};
 use crate::transport::Transport;
 
 /// Transport wrapper that adds active heartbeat monitoring and idle connection detection.
@@ -115,25 +115,27 @@ impl<T: Transport> Transport for HeartbeatTransport<T> {
                 msg_result = self.inner.recv() => {
                     match msg_result {
                         Ok(Message::Heartbeat { version }) => {
-                            // Validate protocol version
-                            if version != PROTOCOL_VERSION {
+                            // Heartbeats run below the handshake, so any version
+                            // this build can play is accepted
+                            if !SUPPORTED_VERSIONS.contains(&version) {
                                 eprintln!(
-                                    "[HeartbeatTransport] Heartbeat version mismatch: expected {}, got {}",
-                                    PROTOCOL_VERSION, version
+                                    "[HeartbeatTransport] Heartbeat version mismatch: expected {}..={}, got {}",
+                                    SUPPORTED_VERSIONS.start(), SUPPORTED_VERSIONS.end(), version
                                 );
                                 return Err(anyhow::anyhow!(
-                                    "Heartbeat version mismatch: expected {}, got {}",
-                                    PROTOCOL_VERSION, version
+                                    "Heartbeat version mismatch: expected {}..={}, got {}",
+                                    SUPPORTED_VERSIONS.start(), SUPPORTED_VERSIONS.end(), version
                                 ));
                             }
 
-                            // Mark activity and echo heartbeat back
+                            // Mark activity and echo heartbeat back. A failed echo is
+                            // not fatal: messages the peer sent before closing may still
+                            // be queued, and the next read reports the closure.
                             self.mark_activity();
                             if let Err(e) = self.inner.send(Message::Heartbeat {
-                                version: PROTOCOL_VERSION
+                                version
                             }).await {
                                 eprintln!("[HeartbeatTransport] Failed to echo heartbeat: {}", e);
-                                return Err(e);
                             }
 
                             // Continue loop to get next message (filter out heartbeat)
@@ -1,4 +1,4 @@
-#![cfg(feature = "std")]
+#![cfg(any(feature = "std", feature = "wasm"))]
 
 use crate::protocol::Message;
 
@@ -14,3 +14,5 @@ pub mod tcp;
 pub mod in_memory;
 #[cfg(feature = "std")]
 pub mod heartbeat;
+#[cfg(all(any(feature = "std", feature = "wasm"), target_arch = "wasm32"))]
+pub mod websocket;
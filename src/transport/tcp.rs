@@ -4,6 +4,7 @@ use tokio::net::{TcpStream, ToSocketAddrs};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::time::{timeout, Duration, Instant};
 use std::sync::Arc;
+use bincode::Options;
 use std::sync::atomic::{AtomicBool, Ordering};
 
 use crate::transport::Transport;
@@ -13,10 +14,10 @@ use crate::protocol::{Message, PROTOCOL_VERSION};
 const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
 
 /// Maximum message size (10 MB) to prevent excessive memory allocation.
-const MAX_MESSAGE_SIZE: u32 = 10_000_000;
+pub const MAX_MESSAGE_SIZE: u32 = 10_000_000;
 
 /// Default heartbeat interval (10 seconds).
-const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
 
 /// Default idle timeout before considering connection dead (45 seconds).
 const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(45);
@@ -31,32 +32,25 @@ pub struct TcpTransport {
     idle_timeout: Duration,
     last_activity: Instant,
     shutdown: Arc<AtomicBool>,
+    /// Bytes of a partially received frame. Kept across calls so `recv` stays
+    /// cancel-safe when raced inside `tokio::select!`.
+    read_buf: Vec<u8>,
 }
 
 #[cfg(feature = "std")]
 impl TcpTransport {
     pub fn new(stream: TcpStream) -> Self {
-        Self { 
-            stream,
-            timeout_duration: DEFAULT_TIMEOUT,
-            max_message_size: MAX_MESSAGE_SIZE,
-            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
-            idle_timeout: DEFAULT_IDLE_TIMEOUT,
-            last_activity: Instant::now(),
-            shutdown: Arc::new(AtomicBool::new(false)),
-        }
+        Self::with_timeout(stream, DEFAULT_TIMEOUT)
     }
 
     pub fn with_timeout(stream: TcpStream, timeout_duration: Duration) -> Self {
-        Self {
+        Self::with_config(
             stream,
             timeout_duration,
-            max_message_size: MAX_MESSAGE_SIZE,
-            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
-            idle_timeout: DEFAULT_IDLE_TIMEOUT,
-            last_activity: Instant::now(),
-            shutdown: Arc::new(AtomicBool::new(false)),
-        }
+            MAX_MESSAGE_SIZE,
+            DEFAULT_HEARTBEAT_INTERVAL,
+            DEFAULT_IDLE_TIMEOUT,
+        )
     }
 
     pub fn with_config(
@@ -74,6 +68,7 @@ impl TcpTransport {
             idle_timeout,
             last_activity: Instant::now(),
             shutdown: Arc::new(AtomicBool::new(false)),
+            read_buf: Vec::new(),
         }
     }
 
@@ -183,49 +178,58 @@ impl Transport for TcpTransport {
         }
         
         let recv_op = async {
-            let mut len_buf = [0u8; 4];
-            self.stream.read_exact(&mut len_buf).await
-                .map_err(|e| {
-                    if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                        anyhow::anyhow!("Connection closed by peer")
-                    } else if e.kind() == std::io::ErrorKind::ConnectionReset {
-                        anyhow::anyhow!("Connection reset by peer")
-                    } else {
-                        anyhow::anyhow!("Read error: {}", e)
+            // Accumulate bytes until a whole frame is buffered. Only the
+            // cancel-safe `read` is awaited, so dropping this future never
+            // loses bytes that were already taken off the socket.
+            loop {
+                if self.read_buf.len() >= 4 {
+                    let len = u32::from_be_bytes([
+                        self.read_buf[0],
+                        self.read_buf[1],
+                        self.read_buf[2],
+                        self.read_buf[3],
+                    ]);
+
+                    // Bounded read length check to prevent excessive memory allocation
+                    if len > self.max_message_size {
+                        self.read_buf.clear();
+                        return Err(anyhow::anyhow!(
+                            "Message too large: {} bytes (max: {})",
+                            len,
+                            self.max_message_size
+                        ));
                     }
-                })?;
-            
-            let len = u32::from_be_bytes(len_buf);
-            
-            // Bounded read length check to prevent excessive memory allocation
-            if len > self.max_message_size {
-                return Err(anyhow::anyhow!(
-                    "Message too large: {} bytes (max: {})", 
-                    len, 
-                    self.max_message_size
-                ));
-            }
-            
-            if len == 0 {
-                return Err(anyhow::anyhow!("Invalid message length: 0"));
-            }
-            
-            let mut buf = vec![0u8; len as usize];
-            self.stream.read_exact(&mut buf).await
-                .map_err(|e| {
-                    if e.kind() == std::io::ErrorKind::UnexpectedEof {
-                        anyhow::anyhow!("Connection closed by peer")
-                    } else if e.kind() == std::io::ErrorKind::ConnectionReset {
+
+                    if len == 0 {
+                        self.read_buf.clear();
+                        return Err(anyhow::anyhow!("Invalid message length: 0"));
+                    }
+
+                    let frame_len = 4 + len as usize;
+                    if self.read_buf.len() >= frame_len {
+                        let frame: Vec<u8> = self.read_buf.drain(..frame_len).collect();
+                        let msg = bincode::DefaultOptions::new()
+                            .with_fixint_encoding()
+                            .reject_trailing_bytes()
+                            .deserialize(&frame[4..])
+                            .map_err(|e| anyhow::anyhow!("Deserialization error: {}", e))?;
+                        return anyhow::Ok(msg);
+                    }
+                }
+
+                let mut chunk = [0u8; 4096];
+                let n = self.stream.read(&mut chunk).await.map_err(|e| {
+                    if e.kind() == std::io::ErrorKind::ConnectionReset {
                         anyhow::anyhow!("Connection reset by peer")
                     } else {
                         anyhow::anyhow!("Read error: {}", e)
                     }
                 })?;
-            
-            let msg = bincode::deserialize(&buf)
-                .map_err(|e| anyhow::anyhow!("Deserialization error: {}", e))?;
-            
-            anyhow::Ok(msg)
+                if n == 0 {
+                    return Err(anyhow::anyhow!("Connection closed by peer"));
+                }
+                self.read_buf.extend_from_slice(&chunk[..n]);
+            }
         };
         
         let result = timeout(self.timeout_duration, recv_op)
@@ -0,0 +1,160 @@
+//! Browser WebSocket transport for WASM builds.
+//!
+//! Each protocol message travels as one binary WebSocket frame holding the
+//! same bincode encoding the TCP transport uses, minus its length prefix
+//! (WebSocket frames are already delimited). The socket's callbacks feed a
+//! channel that [`Transport::recv`] drains, so frames arriving while the
+//! engine is busy are queued rather than dropped.
+
+#![cfg(all(any(feature = "std", feature = "wasm"), target_arch = "wasm32"))]
+
+use bincode::Options;
+use tokio::sync::{mpsc, oneshot};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{BinaryType, CloseEvent, Event, MessageEvent, WebSocket};
+
+use crate::protocol::Message;
+use crate::transport::Transport;
+
+/// Largest frame accepted from the server; matches the TCP transport.
+pub const MAX_MESSAGE_SIZE: usize = 10_000_000;
+
+enum Incoming {
+    Frame(Vec<u8>),
+    Closed(String),
+}
+
+pub struct WebSocketTransport {
+    socket: WebSocket,
+    incoming: mpsc::UnboundedReceiver<Incoming>,
+    // Kept alive for as long as the socket may call them.
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+    _on_error: Closure<dyn FnMut(Event)>,
+}
+
+// SAFETY: wasm32 builds without the `atomics` target feature run on a single
+// thread, so the JS handles inside are never touched from another thread.
+// `Transport` requires `Send + Sync` for the native multi-threaded runtimes.
+unsafe impl Send for WebSocketTransport {}
+unsafe impl Sync for WebSocketTransport {}
+
+impl WebSocketTransport {
+    /// Open a connection to `url` (`ws://` or `wss://`) and wait until the
+    /// server accepts it.
+    pub async fn connect(url: &str) -> anyhow::Result<Self> {
+        let socket = WebSocket::new(url).map_err(|e| anyhow::anyhow!("Invalid WebSocket URL {}: {:?}", url, e))?;
+        socket.set_binary_type(BinaryType::Arraybuffer);
+
+        let (tx, incoming) = mpsc::unbounded_channel();
+        let (opened_tx, opened) = oneshot::channel::<Result<(), String>>();
+        let opened_tx = std::rc::Rc::new(std::cell::RefCell::new(Some(opened_tx)));
+
+        let on_open = {
+            let opened_tx = opened_tx.clone();
+            Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+                if let Some(tx) = opened_tx.borrow_mut().take() {
+                    let _ = tx.send(Ok(()));
+                }
+            })
+        };
+        let on_message = {
+            let tx = tx.clone();
+            Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+                let incoming = match event.data().dyn_into::<js_sys::ArrayBuffer>() {
+                    Ok(buffer) => Incoming::Frame(js_sys::Uint8Array::new(&buffer).to_vec()),
+                    Err(_) => Incoming::Closed("Received a text frame; expected binary".to_string()),
+                };
+                let _ = tx.send(incoming);
+            })
+        };
+        let on_close = {
+            let tx = tx.clone();
+            let opened_tx = opened_tx.clone();
+            Closure::<dyn FnMut(CloseEvent)>::new(move |event: CloseEvent| {
+                let reason = format!("Connection closed by peer (code {})", event.code());
+                if let Some(opened) = opened_tx.borrow_mut().take() {
+                    let _ = opened.send(Err(reason.clone()));
+                }
+                let _ = tx.send(Incoming::Closed(reason));
+            })
+        };
+        let on_error = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            if let Some(opened) = opened_tx.borrow_mut().take() {
+                let _ = opened.send(Err("WebSocket connection failed".to_string()));
+            }
+            let _ = tx.send(Incoming::Closed("WebSocket error".to_string()));
+        });
+
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        let result = opened
+            .await
+            .map_err(|_| anyhow::anyhow!("WebSocket closed before opening"))
+            .and_then(|r| r.map_err(|e| anyhow::anyhow!(e)));
+        socket.set_onopen(None);
+        drop(on_open);
+        result?;
+
+        Ok(Self {
+            socket,
+            incoming,
+            _on_message: on_message,
+            _on_close: on_close,
+            _on_error: on_error,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Transport for WebSocketTransport {
+    async fn send(&mut self, msg: Message) -> anyhow::Result<()> {
+        if self.socket.ready_state() != WebSocket::OPEN {
+            return Err(anyhow::anyhow!("Connection closed by peer"));
+        }
+        let data = bincode::serialize(&msg).map_err(|e| anyhow::anyhow!("Serialization error: {}", e))?;
+        if data.len() > MAX_MESSAGE_SIZE {
+            return Err(anyhow::anyhow!(
+                "Message too large: {} bytes (max: {})",
+                data.len(),
+                MAX_MESSAGE_SIZE
+            ));
+        }
+        self.socket
+            .send_with_u8_array(&data)
+            .map_err(|e| anyhow::anyhow!("Write error: {:?}", e))
+    }
+
+    async fn recv(&mut self) -> anyhow::Result<Message> {
+        match self.incoming.recv().await {
+            Some(Incoming::Frame(data)) => {
+                if data.len() > MAX_MESSAGE_SIZE {
+                    return Err(anyhow::anyhow!(
+                        "Message too large: {} bytes (max: {})",
+                        data.len(),
+                        MAX_MESSAGE_SIZE
+                    ));
+                }
+                bincode::DefaultOptions::new()
+                    .with_fixint_encoding()
+                    .reject_trailing_bytes()
+                    .deserialize(&data)
+                    .map_err(|e| anyhow::anyhow!("Deserialization error: {}", e))
+            }
+            Some(Incoming::Closed(reason)) => Err(anyhow::anyhow!(reason)),
+            None => Err(anyhow::anyhow!("Connection closed by peer")),
+        }
+    }
+}
+
+impl Drop for WebSocketTransport {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.socket.set_onclose(None);
+        self.socket.set_onerror(None);
+        let _ = self.socket.close();
+    }
+}
@@ -0,0 +1,54 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    domain::GameStatus,
+    protocol::{GameHost, GameStore, MemoryStore},
+    transport::in_memory::InMemoryTransport,
+    Coord, GameApi, Skeleton, Stub,
+};
+use std::sync::Arc;
+
+mod common;
+
+#[test]
+fn abort_overrides_every_other_status() {
+    let mut engine = common::stacked_engine();
+    engine.abort();
+    assert_eq!(engine.status(), battleship::GameStatus::Aborted);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn abort_game_stops_the_skeleton() -> anyhow::Result<()> {
+    let (client, server) = InMemoryTransport::pair();
+    let task = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(common::stacked_engine(), server);
+        skeleton.run().await.map(|_| (skeleton.aborted(), skeleton.engine().status()))
+    });
+
+    let mut stub = Stub::new(client);
+    stub.make_guess(Coord::new(0, 0)).await?;
+    stub.abort_game("match stuck".to_string()).await?;
+
+    let (aborted, status) = task.await??;
+    assert!(aborted);
+    assert_eq!(status, battleship::GameStatus::Aborted);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn aborted_hosted_game_is_released() -> anyhow::Result<()> {
+    let host = Arc::new(GameHost::new(MemoryStore::new())?);
+    let (id, token) = host.create(&common::stacked_engine())?;
+
+    let (client, server) = InMemoryTransport::pair();
+    let serving = host.clone();
+    let task = tokio::spawn(async move { serving.serve(server).await });
+    let mut stub = Stub::new(client);
+    stub.rejoin_game(id, token).await?;
+    assert!(matches!(stub.status(), GameStatus::InProgress));
+    stub.abort_game("operator teardown".to_string()).await?;
+    task.await??;
+
+    assert!(host.store().load(id)?.is_none());
+    Ok(())
+}
@@ -0,0 +1,170 @@
+use battleship::{
+    argmax_pdf, calc_pdf, calc_pdf_and_guess, calc_pdf_and_guess_with_config, calc_pdf_with_config, hunt_lattice,
+    pick_target,
+    AiConfig, AiPlayer, BoardBits, Coord, FleetConfig, Player, RuleSet, BOARD_SIZE, MAX_BOARD_SIZE,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+const N: usize = BOARD_SIZE as usize;
+const GRID: usize = MAX_BOARD_SIZE as usize;
+
+#[test]
+fn test_default_config_matches_plain_functions() {
+    let hits = common::cells(&[(4, 4)]);
+    let misses = common::cells(&[(4, 5)]);
+    let lengths = common::full_fleet();
+
+    let plain = calc_pdf(&hits, &misses, &lengths);
+    let configured = calc_pdf_with_config(&hits, &misses, &lengths, &AiConfig::default());
+    assert_eq!(plain, configured);
+
+    let mut rng1 = SmallRng::seed_from_u64(1);
+    let mut rng2 = SmallRng::seed_from_u64(1);
+    for _ in 0..20 {
+        assert_eq!(
+            calc_pdf_and_guess(&hits, &misses, &lengths, &mut rng1),
+            calc_pdf_and_guess_with_config(&hits, &misses, &lengths, &AiConfig::default(), &mut rng2)
+        );
+    }
+}
+
+#[test]
+fn test_hit_bias_controls_focus_around_hits() {
+    let hits = common::cells(&[(5, 5)]);
+    let misses = BoardBits::new();
+    let lengths = common::full_fleet();
+
+    let weak = AiConfig {
+        hit_bias: 1.0,
+        ..AiConfig::default()
+    };
+    let flat = calc_pdf_with_config(&hits, &misses, &lengths, &weak);
+    let focused = calc_pdf(&hits, &misses, &lengths);
+    assert!(focused[5][6] > flat[5][6]);
+    assert!(focused[0][0] < flat[0][0]);
+}
+
+#[test]
+fn test_parity_limits_hunting_to_lattice() {
+    let hits = BoardBits::new();
+    let misses = BoardBits::new();
+    let lengths = common::full_fleet();
+    let config = AiConfig {
+        parity: true,
+        ..AiConfig::default()
+    };
+
+    let pdf = calc_pdf_with_config(&hits, &misses, &lengths, &config);
+    for (r, row) in pdf.iter().enumerate() {
+        for (c, &p) in row.iter().enumerate() {
+            if (r + c) % 2 == 1 {
+                assert_eq!(p, 0.0, "off-lattice cell ({}, {}) kept weight", r, c);
+            }
+        }
+    }
+    let total: f64 = pdf.iter().flatten().sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_parity_hunts_the_cheapest_lattice() {
+    // Destroyer sunk; most of the third lattice has already been shot
+    let hits = common::cells(&[(0, 0), (0, 1)]);
+    let misses = BoardBits::lattice(3, 2) & BoardBits::rect(0, 0, 5, 9).unwrap() | RuleSet::STANDARD.off_board();
+    let lengths = [5, 4, 3, 3, 0];
+    assert_eq!(hunt_lattice(&hits, &misses, &lengths, &FleetConfig::STANDARD), Some(BoardBits::lattice(3, 2)));
+
+    let config = AiConfig {
+        parity: true,
+        ..AiConfig::default()
+    };
+    let pdf = calc_pdf_with_config(&hits, &misses, &lengths, &config);
+    for (r, row) in pdf.iter().enumerate() {
+        for (c, &p) in row.iter().enumerate() {
+            assert_eq!(p > 0.0, (r + c) % 3 == 2 && r > 5 && r < N && c < N, "({}, {})", r, c);
+        }
+    }
+
+    let mut wounded = hits;
+    wounded.set(7, 7).unwrap();
+    assert_eq!(hunt_lattice(&wounded, &misses, &lengths, &FleetConfig::STANDARD), None);
+}
+
+#[test]
+fn test_parity_off_while_a_hit_is_unresolved() {
+    let hits = common::cells(&[(3, 3)]);
+    let misses = BoardBits::new();
+    let lengths = common::full_fleet();
+    let config = AiConfig {
+        parity: true,
+        ..AiConfig::default()
+    };
+
+    let pdf = calc_pdf_with_config(&hits, &misses, &lengths, &config);
+    assert!(pdf[3][4] > 0.0);
+}
+
+#[test]
+fn test_ai_player_uses_config() {
+    let config = AiConfig {
+        parity: true,
+        temperature: 0.1,
+        ..AiConfig::default()
+    };
+    let mut player = AiPlayer::with_config(config);
+    assert_eq!(player.config(), &config);
+
+    let mut rng = SmallRng::seed_from_u64(8);
+    let hits = BoardBits::new();
+    let misses = BoardBits::new();
+    for _ in 0..30 {
+        let Coord { row: r, col: c } = player.select_target(&mut rng, &hits, &misses, &common::full_fleet());
+        assert!(r < N && c < N);
+        assert_eq!((r + c) % 2, 0);
+    }
+}
+
+#[test]
+fn test_deterministic_config_always_shoots_the_peak() {
+    let hits = common::cells(&[(4, 4)]);
+    let misses = common::cells(&[(4, 5)]);
+    let lengths = common::full_fleet();
+    let config = AiConfig {
+        deterministic: true,
+        ..AiConfig::default()
+    };
+
+    let pdf = calc_pdf_with_config(&hits, &misses, &lengths, &config);
+    let peak = pdf.iter().flatten().fold(0.0f64, |m, &p| m.max(p));
+    let target = pick_target(&pdf, &config, &mut SmallRng::seed_from_u64(0));
+    assert_eq!(pdf[target.0][target.1], peak);
+    for seed in 1..20 {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        assert_eq!(calc_pdf_and_guess_with_config(&hits, &misses, &lengths, &config, &mut rng), target);
+    }
+
+    let mut player = AiPlayer::with_config(config);
+    let first = player.select_target(&mut SmallRng::seed_from_u64(1), &hits, &misses, &lengths);
+    let second = player.select_target(&mut SmallRng::seed_from_u64(2), &hits, &misses, &lengths);
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_argmax_breaks_ties_in_reading_order() {
+    let mut pdf = [[0.0; GRID]; GRID];
+    pdf[7][2] = 0.3;
+    pdf[2][7] = 0.3 * (1.0 - 1e-12);
+    pdf[5][5] = 0.2;
+    assert_eq!(argmax_pdf(&pdf), (2, 7));
+    pdf[2][7] = 0.29;
+    assert_eq!(argmax_pdf(&pdf), (7, 2));
+
+    // The empty-board density is symmetric; the first central peak wins
+    let open = calc_pdf(&BoardBits::new(), &BoardBits::new(), &common::full_fleet());
+    let (r, c) = argmax_pdf(&open);
+    assert!((r, c) <= (N - 1 - r, c) && (r, c) <= (r, N - 1 - c) && (r, c) <= (c, r));
+    assert_eq!(argmax_pdf(&[[0.0; GRID]; GRID]), (0, 0));
+}
@@ -22,8 +22,8 @@ fn test_ai_vs_ai_game() {
             &e1.guess_misses(),
             &e1.enemy_ship_lengths_remaining(),
         );
-        let res = e2.opponent_guess(guess.0, guess.1).unwrap();
-        e1.record_guess(guess.0, guess.1, res).unwrap();
+        let res = e2.opponent_guess(guess.row, guess.col).unwrap();
+        e1.record_guess(guess.row, guess.col, res).unwrap();
         p1.handle_guess_result(guess, res);
         if e2.status() == GameStatus::Lost {
             break;
@@ -35,8 +35,8 @@ fn test_ai_vs_ai_game() {
             &e2.guess_misses(),
             &e2.enemy_ship_lengths_remaining(),
         );
-        let res = e1.opponent_guess(guess.0, guess.1).unwrap();
-        e2.record_guess(guess.0, guess.1, res).unwrap();
+        let res = e1.opponent_guess(guess.row, guess.col).unwrap();
+        e2.record_guess(guess.row, guess.col, res).unwrap();
         p2.handle_guess_result(guess, res);
         if e1.status() == GameStatus::Lost {
             break;
@@ -1,7 +1,7 @@
 use battleship::transport::in_memory::InMemoryTransport;
 use battleship::transport::Transport;
 use battleship::transport::tcp::TcpTransport;
-use battleship::{AiPlayer, GameEngine, GameStatus, Player, PlayerNode};
+use battleship::{AiPlayer, GameEngine, GameStatus, Player, PlayerNode, Role};
 use rand::rngs::SmallRng;
 use rand::SeedableRng;
 use tokio::net::TcpListener;
@@ -47,10 +47,10 @@ async fn run_game(kind: TransportKind) -> anyhow::Result<()> {
 
     tokio::join!(
         async {
-            node1.run(&mut rng1, true).await.unwrap();
+            node1.run(&mut rng1, Role::Initiator).await.unwrap();
         },
         async {
-            node2.run(&mut rng2, false).await.unwrap();
+            node2.run(&mut rng2, Role::Responder).await.unwrap();
         },
     );
 
@@ -67,6 +67,13 @@ async fn run_game(kind: TransportKind) -> anyhow::Result<()> {
 
     assert!(matches!(status1, GameStatus::Won | GameStatus::Lost));
     assert!(matches!(status2, GameStatus::Won | GameStatus::Lost));
+
+    // Each side saw the other's fleet and report once the game was over
+    for (node, peer) in [(&node1, &node2), (&node2, &node1)] {
+        let (fleet, stats) = node.opponent_fleet().expect("the peer's fleet is revealed");
+        assert_eq!(fleet.ship_map(), peer.engine().board().ship_map());
+        assert_eq!(stats.hits, peer.engine().stats().hits);
+    }
     Ok(())
 }
 
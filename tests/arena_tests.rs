@@ -0,0 +1,45 @@
+#![cfg(feature = "std")]
+
+use battleship::{run_match, AiConfig, AiPlayer, PlacementStyle, TargetingStyle};
+
+fn player(targeting: TargetingStyle) -> AiPlayer {
+    AiPlayer::with_strategies(targeting, PlacementStyle::default())
+}
+
+#[test]
+fn test_match_is_reproducible() {
+    let play = || {
+        let (mut a, mut b) = (player(TargetingStyle::ParityHunt), player(TargetingStyle::Random));
+        run_match(&mut a, &mut b, 4, 17).unwrap()
+    };
+    let report = play();
+    assert_eq!(report, play());
+    assert_eq!(report.games.len(), 4);
+    for (game, record) in report.games.iter().enumerate() {
+        assert_eq!(record.first, game % 2);
+        assert_eq!(record.sank, [true, true]);
+        assert!(record.shots.iter().all(|&s| (17..=100).contains(&s)));
+    }
+}
+
+#[test]
+fn test_density_beats_random() {
+    let mut a = AiPlayer::with_config(AiConfig { deterministic: true, ..AiConfig::default() });
+    let mut b = player(TargetingStyle::Random);
+    let report = run_match(&mut a, &mut b, 10, 3).unwrap();
+    assert_eq!(report.wins(0) + report.wins(1), 10);
+    assert!(report.average_shots(0).unwrap() < report.average_shots(1).unwrap());
+    assert!(report.win_rate(0) > 0.5);
+
+    let (low, high) = report.win_rate_interval(0);
+    assert!(low <= report.win_rate(0) && report.win_rate(0) <= high);
+    assert!(low > 0.0 && high <= 1.0);
+}
+
+#[test]
+fn test_empty_match() {
+    let (mut a, mut b) = (player(TargetingStyle::Random), player(TargetingStyle::Random));
+    let report = run_match(&mut a, &mut b, 0, 0).unwrap();
+    assert_eq!((report.win_rate(0), report.win_rate_interval(0)), (0.0, (0.0, 1.0)));
+    assert_eq!(report.average_shots(1), None);
+}
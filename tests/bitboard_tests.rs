@@ -1,4 +1,4 @@
-use battleship::{BitBoard, BitBoardError};
+use battleship::{BitBoard, BitBoardError, Orientation};
 
 #[test]
 fn test_try_new_sizes() {
@@ -27,8 +27,367 @@ fn test_get_set_toggle() {
 }
 
 #[test]
+#[allow(deprecated)]
 fn test_from_iter_and_iter() {
     let bb = BitBoard::<u16, 4>::from_iter([(0,1), (3,3)]).unwrap();
     let bits: Vec<_> = bb.iter_set_bits().collect();
     assert_eq!(bits, vec![(0,1), (3,3)]);
 }
+
+type BB = BitBoard<u128, 10>;
+
+const CORNERS: BB = BB::new().with(0, 0).with(0, 9).with(9, 0).with(9, 9);
+
+const CHECKERBOARD: BitBoard<u64, 8> = {
+    let mut board = BitBoard::<u64, 8>::new();
+    let mut idx = 0;
+    while idx < 64 {
+        if (idx / 8 + idx % 8) % 2 == 0 {
+            board = board.with(idx / 8, idx % 8);
+        }
+        idx += 1;
+    }
+    board
+};
+
+const DIAGONAL: BitBoard<u16, 4> = battleship::bitboard!(u16, 4;
+    "#...",
+    ".#..",
+    "..#.",
+    "...#",
+);
+
+#[test]
+fn test_grid_literals() {
+    assert_eq!(DIAGONAL, BitBoard::<u16, 4>::try_from_cells((0..4).map(|i| (i, i))).unwrap());
+    let checkerboard = BitBoard::<u64, 8>::from_rows([
+        "# . # . # . # .",
+        ". # . # . # . #",
+        "# . # . # . # .",
+        ". # . # . # . #",
+        "# . # . # . # .",
+        ". # . # . # . #",
+        "# . # . # . # .",
+        ". # . # . # . #",
+    ]);
+    assert_eq!(checkerboard, CHECKERBOARD);
+    assert!(battleship::bitboard!(u128, 10).is_empty());
+}
+
+#[test]
+#[should_panic(expected = "row must hold N cells")]
+fn test_grid_literal_rejects_short_rows() {
+    let _ = BitBoard::<u16, 4>::from_rows(["#...", "...", "....", "...."]);
+}
+
+#[test]
+fn test_const_constructors() {
+    assert_eq!(CORNERS.count_ones(), 4);
+    assert!(CORNERS.get(9, 9).unwrap());
+    assert!(!CORNERS.get(5, 5).unwrap());
+
+    assert_eq!(CHECKERBOARD.count_ones(), 32);
+    assert!(CHECKERBOARD.get(0, 0).unwrap());
+    assert!(!CHECKERBOARD.get(0, 1).unwrap());
+}
+
+#[test]
+fn test_const_from_raw_masks_upper_bits() {
+    const FULL: BitBoard<u32, 5> = BitBoard::<u32, 5>::from_raw(u32::MAX);
+    assert_eq!(FULL.count_ones(), 25);
+    const EXACT: BitBoard<u64, 8> = BitBoard::<u64, 8>::from_raw(u64::MAX);
+    assert_eq!(EXACT.count_ones(), 64);
+}
+
+#[test]
+#[should_panic(expected = "out of bounds")]
+fn test_with_out_of_bounds_panics() {
+    let _ = BB::new().with(10, 0);
+}
+
+#[test]
+fn test_clusters_separates_groups() {
+    // Horizontal pair, vertical triple and a lone cell
+    let board = BB::new()
+        .with(0, 0)
+        .with(0, 1)
+        .with(4, 5)
+        .with(5, 5)
+        .with(6, 5)
+        .with(9, 9);
+    let clusters: Vec<BB> = board.clusters().collect();
+    assert_eq!(clusters.len(), 3);
+    assert_eq!(clusters[0], BB::new().with(0, 0).with(0, 1));
+    assert_eq!(clusters[1], BB::new().with(4, 5).with(5, 5).with(6, 5));
+    assert_eq!(clusters[2], BB::new().with(9, 9));
+}
+
+#[test]
+fn test_clusters_follow_bent_shapes() {
+    let board = BB::new().with(2, 2).with(2, 3).with(3, 3).with(4, 3).with(4, 4);
+    let clusters: Vec<BB> = board.clusters().collect();
+    assert_eq!(clusters, vec![board]);
+}
+
+#[test]
+fn test_clusters_ignore_diagonals() {
+    let board = BB::new().with(3, 3).with(4, 4).with(5, 3);
+    assert_eq!(board.clusters().count(), 3);
+}
+
+#[test]
+fn test_clusters_do_not_wrap_rows() {
+    // End of one row is adjacent in bit order to the start of the next
+    let board = BB::new().with(0, 9).with(1, 0);
+    assert_eq!(board.clusters().count(), 2);
+    let odd = BitBoard::<u32, 5>::new().with(0, 4).with(1, 0);
+    assert_eq!(odd.clusters().count(), 2);
+}
+
+#[test]
+fn test_clusters_of_empty_and_full_boards() {
+    assert_eq!(BB::new().clusters().count(), 0);
+    let full = BitBoard::<u64, 8>::from_raw(u64::MAX);
+    let clusters: Vec<_> = full.clusters().collect();
+    assert_eq!(clusters, vec![full]);
+}
+
+#[test]
+fn test_flood_fill_picks_one_cluster() {
+    let board = BB::new().with(2, 2).with(2, 3).with(3, 3).with(7, 7);
+    assert_eq!(board.flood_fill(3, 3), BB::new().with(2, 2).with(2, 3).with(3, 3));
+    assert_eq!(board.flood_fill(7, 7), BB::new().with(7, 7));
+    assert!(board.flood_fill(0, 0).is_empty());
+    assert!(board.flood_fill(10, 0).is_empty());
+}
+
+#[test]
+fn test_is_line_accepts_only_straight_runs() {
+    assert!(BB::new().with(4, 2).with(4, 3).with(4, 4).is_line());
+    assert!(BB::new().with(1, 9).with(2, 9).is_line());
+    assert!(BB::new().with(5, 5).is_line());
+    assert!(!BB::new().is_line());
+    // Gaps, bends and runs wrapping a row edge
+    assert!(!BB::new().with(4, 2).with(4, 4).is_line());
+    assert!(!BB::new().with(2, 2).with(2, 3).with(3, 3).is_line());
+    assert!(!BB::new().with(0, 9).with(1, 0).is_line());
+    assert!(!BB::new().with(0, 0).with(1, 0).with(1, 1).is_line());
+}
+
+#[test]
+fn test_rotations_move_corner() {
+    let board = BB::new().with(0, 1);
+    assert_eq!(board.rotate90(), BB::new().with(1, 9));
+    assert_eq!(board.rotate180(), BB::new().with(9, 8));
+    assert_eq!(board.rotate270(), BB::new().with(8, 0));
+}
+
+#[test]
+fn test_rotations_compose() {
+    let board = BB::new().with(2, 3).with(2, 4).with(2, 5).with(7, 1);
+    assert_eq!(board.rotate90().rotate90(), board.rotate180());
+    assert_eq!(board.rotate90().rotate180(), board.rotate270());
+    assert_eq!(board.rotate90().rotate270(), board);
+    assert_eq!(board.rotate90().count_ones(), board.count_ones());
+}
+
+#[test]
+fn test_mirrors() {
+    let board = BB::new().with(1, 2).with(6, 0);
+    assert_eq!(board.mirror_h(), BB::new().with(1, 7).with(6, 9));
+    assert_eq!(board.mirror_v(), BB::new().with(8, 2).with(3, 0));
+    assert_eq!(board.mirror_h().mirror_h(), board);
+    assert_eq!(board.mirror_h().mirror_v(), board.rotate180());
+}
+
+#[test]
+fn test_canonical_is_shared_by_all_symmetries() {
+    let board = BB::new().with(0, 1).with(0, 2).with(0, 3).with(5, 8);
+    let symmetries = board.symmetries();
+    assert_eq!(symmetries[0], board);
+    assert_eq!(symmetries[5], board.mirror_v().rotate270());
+    for sym in symmetries {
+        assert_eq!(sym.canonical(), board.canonical());
+        assert_eq!(sym.count_ones(), 4);
+    }
+    let other = BB::new().with(0, 1).with(0, 2).with(0, 3).with(5, 7);
+    assert_ne!(other.canonical(), board.canonical());
+    // A fully symmetric pattern is its own representative
+    assert_eq!(CORNERS.canonical(), CORNERS);
+}
+
+#[test]
+fn test_symmetric_fixture_is_invariant() {
+    let board = CORNERS.with(4, 4).with(4, 5).with(5, 4).with(5, 5);
+    assert_eq!(board.rotate90(), board);
+    assert_eq!(board.mirror_h(), board);
+    assert_eq!(board.mirror_v(), board);
+}
+
+#[test]
+fn test_transforms_on_odd_sized_board() {
+    let board = BitBoard::<u32, 5>::new().with(0, 0).with(2, 2);
+    assert_eq!(board.rotate90(), BitBoard::<u32, 5>::new().with(0, 4).with(2, 2));
+    assert_eq!(board.mirror_v(), BitBoard::<u32, 5>::new().with(4, 0).with(2, 2));
+}
+
+#[test]
+fn test_display_renders_grid() {
+    let board = BitBoard::<u8, 2>::new().with(0, 1).with(1, 0);
+    assert_eq!(format!("{}", board), "□ ■ \n■ □ ");
+    assert!(format!("{:?}", board).starts_with("BitBoard<u8, 2>:\n□ ■ \n"));
+}
+
+#[test]
+fn test_hex_is_single_line_and_padded() {
+    assert_eq!(format!("{:x}", BB::new()), "0".repeat(25));
+    assert_eq!(format!("{:#x}", BB::new().with(0, 0).with(0, 4)), format!("0x{}11", "0".repeat(23)));
+    assert_eq!(format!("{:x}", BitBoard::<u32, 5>::new().with(4, 4)), "1000000");
+    assert_eq!(format!("{:x}", BitBoard::<u8, 2>::from_raw(0xff)), "f");
+}
+
+#[test]
+fn test_shifts_do_not_wrap_rows() {
+    let bb = BitBoard::<u16, 4>::try_from_cells([(0, 0), (1, 3), (3, 2)]).unwrap();
+    let cells = |b: BitBoard<u16, 4>| b.iter_set_bits().collect::<Vec<_>>();
+    assert_eq!(cells(bb.shift_right(1)), [(0, 1), (3, 3)]);
+    assert_eq!(cells(bb.shift_left(1)), [(1, 2), (3, 1)]);
+    assert_eq!(cells(bb.shift_up(1)), [(0, 3), (2, 2)]);
+    assert_eq!(cells(bb.shift_down(2)), [(2, 0), (3, 3)]);
+    assert_eq!(bb.shift_left(0), bb);
+    assert!(bb.shift_up(4).is_empty() && bb.shift_right(9).is_empty());
+
+    // A board filling its storage loses the bits shifted past the top
+    let full = BitBoard::<u64, 8>::try_from_cells([(7, 7), (0, 0)]).unwrap();
+    assert_eq!(full.shift_down(1).iter_set_bits().collect::<Vec<_>>(), [(1, 0)]);
+}
+
+#[test]
+fn test_dilate_and_neighbors() {
+    let centre = BitBoard::<u32, 5>::try_from_cells([(2, 2)]).unwrap();
+    assert_eq!(centre.dilate(false).count_ones(), 5);
+    assert_eq!(centre.dilate(true).count_ones(), 9);
+    assert_eq!(centre.neighbors(true).count_ones(), 8);
+    assert!(!centre.neighbors(false).get(2, 2).unwrap());
+
+    // Cells on an edge do not spill onto the far side of the board
+    let corner = BitBoard::<u32, 5>::try_from_cells([(0, 4)]).unwrap();
+    let ring: Vec<_> = corner.neighbors(true).iter_set_bits().collect();
+    assert_eq!(ring, [(0, 3), (1, 3), (1, 4)]);
+}
+
+#[test]
+fn test_row_and_column_masks() {
+    let row = BB::row_mask(3);
+    assert_eq!(row.count_ones(), 10);
+    assert!(row.get(3, 0).unwrap() && row.get(3, 9).unwrap() && !row.get(4, 0).unwrap());
+    let col = BB::col_mask(9);
+    assert_eq!(col.iter_set_bits().map(|(_, c)| c).collect::<Vec<_>>(), [9; 10]);
+    assert!(BB::row_mask(10).is_empty() && BB::col_mask(10).is_empty());
+    assert_eq!((BB::row_mask(3) & BB::col_mask(9)).iter_set_bits().collect::<Vec<_>>(), [(3, 9)]);
+
+    assert_eq!(CORNERS.count_row(0), 2);
+    assert_eq!(CORNERS.count_row(5), 0);
+    assert_eq!(CORNERS.count_col(9), 2);
+    assert_eq!(CORNERS.count_col(12), 0);
+    let full = !BitBoard::<u64, 8>::new();
+    assert_eq!((full.count_row(7), full.count_col(0)), (8, 8));
+}
+
+#[test]
+fn test_lattice_masks() {
+    let checker = BB::lattice(2, 0);
+    assert_eq!(checker.count_ones(), 50);
+    assert_eq!(checker | BB::lattice(2, 1), !BB::new());
+    assert_eq!(BB::lattice(2, 3), BB::lattice(2, 1));
+    assert!(BB::lattice(3, 2).iter_set_bits().all(|(r, c)| (r + c) % 3 == 2));
+    assert!(BB::lattice(0, 0).is_empty());
+    // Every line of at least `spacing` cells crosses the lattice
+    for len in 3..=5 {
+        let ship = BB::line(4, 2, len, Orientation::Vertical).unwrap();
+        assert!(!(ship & BB::lattice(3, 1)).is_empty());
+    }
+}
+
+#[test]
+fn test_run_starts_match_lines_that_fit() {
+    let mut open = !BB::new();
+    open.clear(3, 4).unwrap();
+    for len in 0..=11 {
+        for orientation in [Orientation::Horizontal, Orientation::Vertical] {
+            let starts = open.run_starts(len, orientation);
+            for r in 0..10 {
+                for c in 0..10 {
+                    let fits = len > 0
+                        && BB::line(r, c, len, orientation).is_ok_and(|line| (line & !open).is_empty());
+                    assert_eq!(starts.get(r, c).unwrap(), fits, "len {} at ({}, {})", len, r, c);
+                }
+            }
+        }
+    }
+    assert_eq!(BB::new().run_starts(1, Orientation::Vertical), BB::new());
+}
+
+#[test]
+fn test_grid_and_iterator_conversions() {
+    let mut grid = [[false; 4]; 4];
+    grid[0][3] = true;
+    grid[2][1] = true;
+    let board = BitBoard::<u16, 4>::from_grid(&grid);
+    assert_eq!(board.iter_set_bits().collect::<Vec<_>>(), [(0, 3), (2, 1)]);
+    assert_eq!(board.to_grid(), grid);
+    assert_eq!(BB::new().to_grid(), [[false; 10]; 10]);
+
+    // Collecting skips positions off the board; try_from_cells rejects them
+    let collected: BitBoard<u16, 4> = [(0, 3), (2, 1), (4, 0)].into_iter().collect();
+    assert_eq!(collected, board);
+    assert!(BitBoard::<u16, 4>::try_from_cells([(4, 0)]).is_err());
+    let mut extended = BitBoard::<u16, 4>::new();
+    extended.extend([(0, 3)]);
+    extended.extend((1..3).map(|r| (r, 1)));
+    assert_eq!(extended.count_ones(), 3);
+}
+
+#[test]
+fn test_clear_bits_and_unknown_cells() {
+    let board = BitBoard::<u16, 4>::try_from_cells([(0, 0), (1, 2), (3, 3)]).unwrap();
+    let clear: Vec<_> = board.iter_clear_bits().collect();
+    assert_eq!(clear.len(), 13);
+    assert_eq!(clear[..3], [(0, 1), (0, 2), (0, 3)]);
+    assert!(!clear.contains(&(1, 2)) && !clear.contains(&(3, 3)));
+    assert_eq!(BitBoard::<u16, 4>::new().iter_clear_bits().count(), 16);
+    // Storage bits past the board are never reported
+    let mut full = BitBoard::<u32, 5>::new();
+    full.fill();
+    assert_eq!(full.iter_clear_bits().next(), None);
+
+    let hits = BB::new().with(0, 0).with(0, 1);
+    let misses = BB::new().with(9, 9);
+    let unknown = BB::unknown(&hits, &misses);
+    assert_eq!(unknown.count_ones(), 97);
+    assert_eq!(unknown.iter_set_bits().next(), Some((0, 2)));
+    assert!(unknown.iter_set_bits().eq((hits | misses).iter_clear_bits()));
+}
+
+#[test]
+fn test_rect_and_line_masks() {
+    let rect = BB::rect(2, 7, 1, 5).unwrap();
+    assert_eq!(
+        rect.iter_set_bits().collect::<Vec<_>>(),
+        [(1, 5), (1, 6), (1, 7), (2, 5), (2, 6), (2, 7)]
+    );
+    assert_eq!(BB::rect(0, 0, 9, 9).unwrap(), !BB::new());
+    assert_eq!(BB::rect(4, 4, 4, 4).unwrap(), BB::new().with(4, 4));
+    assert_eq!(BB::rect(0, 0, 0, 10), Err(BitBoardError::IndexOutOfBounds { row: 0, col: 10 }));
+
+    let across = BB::line(3, 6, 4, Orientation::Horizontal).unwrap();
+    assert_eq!(across, BB::row_mask(3) & BB::rect(0, 6, 9, 9).unwrap());
+    let down = BB::line(6, 0, 4, Orientation::Vertical).unwrap();
+    assert_eq!(down.iter_set_bits().collect::<Vec<_>>(), [(6, 0), (7, 0), (8, 0), (9, 0)]);
+    assert!(BB::line(3, 7, 4, Orientation::Horizontal).is_err());
+    assert!(BB::line(7, 0, 4, Orientation::Vertical).is_err());
+    assert!(BB::line(5, 5, 0, Orientation::Vertical).unwrap().is_empty());
+    // A full-width row on a board that fills its storage
+    let full_row = BitBoard::<u64, 8>::line(7, 0, 8, Orientation::Horizontal).unwrap();
+    assert_eq!(full_row.count_row(7), 8);
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "std")]
+
+use battleship::core::bitboard::aliases::BB12x12;
+use battleship::{BitBoard, BitBoardError, BitBoardWide, CellGrid};
+
+type Wide10 = BitBoardWide<2, 10>;
+type Narrow10 = BitBoard<u128, 10>;
+
+fn cells(bb: BB12x12) -> Vec<(usize, usize)> {
+    bb.iter_set_bits().collect()
+}
+
+#[test]
+fn test_cells_beyond_eleven_by_eleven() {
+    assert!(BB12x12::try_new().is_ok());
+    assert!(matches!(
+        BitBoardWide::<2, 12>::try_new(),
+        Err(BitBoardError::SizeTooLarge { n: 12, capacity: 128 })
+    ));
+
+    let mut bb = BB12x12::new();
+    bb.set(11, 11).unwrap();
+    bb.set(5, 4).unwrap();
+    bb.toggle(0, 0).unwrap();
+    assert_eq!(cells(bb), [(0, 0), (5, 4), (11, 11)]);
+    assert!(bb.get(11, 11).unwrap());
+    assert_eq!(bb.get(12, 0), Err(BitBoardError::IndexOutOfBounds { row: 12, col: 0 }));
+    bb.clear(5, 4).unwrap();
+    assert_eq!(bb.count_ones(), 2);
+
+    let mut full = BB12x12::new();
+    full.fill();
+    assert_eq!(full.count_ones(), 144);
+    assert_eq!((!bb).count_ones(), 142);
+    assert_eq!(!full, BB12x12::new());
+    assert_eq!(bb & full, bb);
+    assert_eq!((bb ^ full) | bb, full);
+}
+
+#[test]
+fn test_matches_the_single_word_board() {
+    let pattern = [(0, 0), (0, 9), (3, 6), (6, 3), (6, 4), (9, 9)];
+    let wide = Wide10::try_from_cells(pattern).unwrap();
+    let narrow = Narrow10::try_from_cells(pattern).unwrap();
+    let same = |w: Wide10, n: Narrow10| w.iter_set_bits().eq(n.iter_set_bits());
+
+    for k in 0..11 {
+        assert!(same(wide.shift_up(k), narrow.shift_up(k)), "up {}", k);
+        assert!(same(wide.shift_down(k), narrow.shift_down(k)), "down {}", k);
+        assert!(same(wide.shift_left(k), narrow.shift_left(k)), "left {}", k);
+        assert!(same(wide.shift_right(k), narrow.shift_right(k)), "right {}", k);
+    }
+    assert!(same(wide.dilate(true), narrow.dilate(true)));
+    assert!(same(wide.neighbors(false), narrow.neighbors(false)));
+    assert!(same(wide.rotate90(), narrow.rotate90()));
+    assert!(same(wide.mirror_v(), narrow.mirror_v()));
+    assert!(same(wide.canonical(), narrow.canonical()));
+    assert_eq!((wide.count_row(6), wide.count_col(9)), (narrow.count_row(6), narrow.count_col(9)));
+    assert_eq!(wide.to_string(), narrow.to_string());
+}
+
+#[test]
+fn test_serde_round_trip() {
+    let bb = BB12x12::try_from_cells([(0, 1), (7, 7), (11, 0)]).unwrap();
+    let json = serde_json::to_string(&bb).unwrap();
+    assert_eq!(serde_json::from_str::<BB12x12>(&json).unwrap(), bb);
+    let bytes = bincode::serialize(&bb).unwrap();
+    assert_eq!(bytes.len(), 3 * 8);
+    assert_eq!(bincode::deserialize::<BB12x12>(&bytes).unwrap(), bb);
+    assert!(serde_json::from_str::<BB12x12>("[1, 2]").is_err());
+}
+
+#[test]
+fn test_conversions_and_cell_grid() {
+    let narrow = Narrow10::try_from_cells([(0, 0), (6, 4), (9, 9)]).unwrap();
+    let wide = Wide10::from(narrow);
+    assert!(wide.iter_set_bits().eq(narrow.iter_set_bits()));
+    assert_eq!(Narrow10::from(wide), narrow);
+
+    let mut grid = <BB12x12 as CellGrid>::empty();
+    CellGrid::set(&mut grid, 11, 10).unwrap();
+    assert_eq!(BB12x12::SIDE, 12);
+    assert!(CellGrid::get(&grid, 11, 10).unwrap());
+    assert_eq!(CellGrid::count_ones(&grid), 1);
+}
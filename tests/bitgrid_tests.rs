@@ -0,0 +1,93 @@
+use battleship::{bitgrid_bytes, BitBoard, BitBoardError, BitGrid, CellGrid};
+
+type Large = BitGrid<20, { bitgrid_bytes(20) }>;
+type Small = BitGrid<10, { bitgrid_bytes(10) }>;
+
+#[test]
+fn test_large_grid_get_set_clear() {
+    let mut grid = Large::new();
+    assert!(grid.is_empty());
+    grid.set(19, 19).unwrap();
+    grid.set(0, 7).unwrap();
+    grid.set(0, 8).unwrap();
+    assert!(grid.get(19, 19).unwrap());
+    assert!(grid.get(0, 8).unwrap());
+    assert!(!grid.get(1, 0).unwrap());
+    assert_eq!(grid.count_ones(), 3);
+
+    grid.clear(0, 7).unwrap();
+    grid.toggle(0, 8).unwrap();
+    assert_eq!(grid.iter_set_bits().collect::<Vec<_>>(), vec![(19, 19)]);
+}
+
+#[test]
+fn test_out_of_bounds() {
+    let mut grid = Large::new();
+    assert_eq!(
+        grid.set(20, 0),
+        Err(BitBoardError::IndexOutOfBounds { row: 20, col: 0 })
+    );
+    assert!(grid.get(0, 20).is_err());
+}
+
+#[test]
+fn test_fill_and_not_respect_board_size() {
+    // 9x9 = 81 bits in 11 bytes leaves 7 spare bits
+    let mut grid = BitGrid::<9, { bitgrid_bytes(9) }>::new();
+    grid.fill();
+    assert_eq!(grid.count_ones(), 81);
+    assert!((!grid).is_empty());
+    assert_eq!((!BitGrid::<9, 11>::new()).count_ones(), 81);
+    assert_eq!(BitGrid::<9, 11>::from_bytes([0xff; 11]).count_ones(), 81);
+}
+
+#[test]
+fn test_bitops() {
+    let a = Large::try_from_cells([(0, 0), (5, 5), (19, 0)]).unwrap();
+    let b = Large::try_from_cells([(5, 5), (10, 10)]).unwrap();
+    assert_eq!((a & b).iter_set_bits().collect::<Vec<_>>(), vec![(5, 5)]);
+    assert_eq!((a | b).count_ones(), 4);
+    assert_eq!((a ^ b).count_ones(), 3);
+
+    let mut c = a;
+    c &= b;
+    c |= Large::try_from_cells([(1, 1)]).unwrap();
+    c ^= Large::try_from_cells([(5, 5)]).unwrap();
+    assert_eq!(c.iter_set_bits().collect::<Vec<_>>(), vec![(1, 1)]);
+}
+
+#[test]
+fn test_round_trip_with_bitboard() {
+    let board = BitBoard::<u128, 10>::try_from_cells([(0, 0), (3, 7), (9, 9)]).unwrap();
+    let grid: Small = board.into();
+    assert_eq!(grid.iter_set_bits().collect::<Vec<_>>(), board.iter_set_bits().collect::<Vec<_>>());
+    let back: BitBoard<u128, 10> = grid.into();
+    assert_eq!(back, board);
+    assert_eq!(format!("{}", grid), format!("{}", board));
+}
+
+#[test]
+fn test_same_layout_as_bitboard() {
+    let board = BitBoard::<u16, 4>::from_raw(0b1010_0000_0000_0101);
+    let grid: BitGrid<4, 2> = board.into();
+    assert_eq!(grid.as_bytes(), &[0b0000_0101, 0b1010_0000]);
+}
+
+fn mark_diagonal<G: CellGrid>() -> G {
+    let mut grid = G::empty();
+    for i in 0..G::SIDE {
+        grid.set(i, i).unwrap();
+    }
+    grid
+}
+
+#[test]
+fn test_code_generic_over_representation() {
+    let board: BitBoard<u128, 10> = mark_diagonal();
+    let small: Small = mark_diagonal();
+    let large: Large = mark_diagonal();
+    assert_eq!(CellGrid::count_ones(&board), 10);
+    assert_eq!(Small::from(board), small);
+    assert_eq!(large.count_ones(), 20);
+    assert!(CellGrid::get(&large, 19, 19).unwrap());
+}
@@ -0,0 +1,64 @@
+use battleship::{Board, BoardState, FleetConfig, LayoutError, Orientation, ShipDef, NUM_SHIPS};
+
+/// The standard fleet stacked in rows 0..5 from column 0.
+fn stacked() -> BoardState {
+    let mut board = Board::new();
+    for i in 0..NUM_SHIPS {
+        board.place(i, i, 0, Orientation::Horizontal).unwrap();
+    }
+    BoardState::from(&board)
+}
+
+#[test]
+fn test_placed_fleet_is_valid() {
+    assert_eq!(stacked().validate(&FleetConfig::STANDARD), Ok(()));
+    assert_eq!(
+        BoardState::from(&Board::new()).validate(&FleetConfig::STANDARD),
+        Err(LayoutError::MissingShip { ship: 0 })
+    );
+}
+
+#[test]
+fn test_impossible_layouts_are_named() {
+    let fleet = FleetConfig::STANDARD;
+
+    let mut overlap = stacked();
+    overlap.ship_states[2].position = Some((1, 2, Orientation::Vertical));
+    assert_eq!(overlap.validate(&fleet), Err(LayoutError::Overlap { ship: 2 }));
+
+    let mut off_board = stacked();
+    off_board.ship_states[0].position = Some((9, 6, Orientation::Horizontal));
+    assert_eq!(off_board.validate(&fleet), Err(LayoutError::OutOfBounds { ship: 0 }));
+
+    let mut small = stacked();
+    small.size = 8;
+    small.ship_states[4].position = Some((4, 7, Orientation::Horizontal));
+    assert_eq!(small.validate(&fleet), Err(LayoutError::OutOfBounds { ship: 4 }));
+    small.size = 4;
+    assert_eq!(small.validate(&fleet), Err(LayoutError::UnsupportedSize(4)));
+
+    let mut extra_cell = stacked();
+    extra_cell.ship_map.set(9, 9).unwrap();
+    assert_eq!(
+        extra_cell.validate(&fleet),
+        Err(LayoutError::CellCount { expected: 17, found: 18 })
+    );
+
+    // Same cell count, but the Destroyer's second cell bends down a row
+    let mut bent = stacked();
+    bent.ship_map.clear(4, 1).unwrap();
+    bent.ship_map.set(5, 0).unwrap();
+    assert_eq!(bent.validate(&fleet), Err(LayoutError::ShipMapMismatch));
+}
+
+#[test]
+fn test_ships_past_a_smaller_fleet_are_rejected() {
+    let fleet = FleetConfig::from_ships(&[ShipDef::new("Carrier", 5), ShipDef::new("Battleship", 4)]).unwrap();
+    let state = stacked();
+    assert_eq!(state.validate(&fleet), Err(LayoutError::ExtraShip { slot: 2 }));
+
+    let mut board = Board::with_rules(&battleship::RuleSet::STANDARD.with_fleet(fleet).unwrap());
+    board.place(0, 0, 0, Orientation::Horizontal).unwrap();
+    board.place(1, 1, 0, Orientation::Horizontal).unwrap();
+    assert_eq!(BoardState::from(&board).validate(&fleet), Ok(()));
+}
@@ -0,0 +1,195 @@
+#![cfg(feature = "std")]
+
+use battleship::domain::GameConfig;
+use battleship::protocol::setup;
+use battleship::protocol::validation::{validate_guess, ProtocolViolation};
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::PROTOCOL_VERSION;
+use battleship::{
+    AiPlayer, BoardError, Coord, GameEngine, GameStatus, GuessResult, Orientation, Player, Role, RuleSet, BOARD_SIZE,
+    MAX_BOARD_SIZE, MIN_BOARD_SIZE,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use tokio::time::{Duration, Instant};
+
+fn rules(size: u8) -> RuleSet {
+    RuleSet::with_board_size(size).unwrap()
+}
+
+#[test]
+fn test_rule_set_accepts_sizes_that_fit_storage() {
+    assert_eq!(RuleSet::default(), RuleSet::STANDARD);
+    assert_eq!(RuleSet::STANDARD.board_size(), BOARD_SIZE);
+    assert_eq!(MIN_BOARD_SIZE, 5);
+    assert!(RuleSet::with_board_size(MIN_BOARD_SIZE).is_ok());
+    assert_eq!(
+        RuleSet::with_board_size(MIN_BOARD_SIZE - 1),
+        Err(BoardError::UnsupportedBoardSize(MIN_BOARD_SIZE - 1))
+    );
+    assert_eq!(
+        RuleSet::with_board_size(MAX_BOARD_SIZE + 1),
+        Err(BoardError::UnsupportedBoardSize(MAX_BOARD_SIZE + 1))
+    );
+    let cells = MAX_BOARD_SIZE as usize * MAX_BOARD_SIZE as usize;
+    assert_eq!(rules(8).off_board().count_ones(), cells - 64);
+    assert_eq!(RuleSet::STANDARD.off_board().count_ones(), cells - 100);
+}
+
+#[test]
+fn test_small_board_bounds_placement_and_guesses() {
+    let mut engine = GameEngine::new_with_config(rules(8));
+    assert_eq!(engine.rules().board_size(), 8);
+    assert_eq!(engine.board().size(), 8);
+    assert_eq!(
+        engine.board_mut().place(0, 0, 4, Orientation::Horizontal),
+        Err(BoardError::ShipOutOfBounds)
+    );
+    engine.board_mut().place(0, 0, 3, Orientation::Horizontal).unwrap();
+    assert!(engine.opponent_guess(8, 0).is_err());
+    assert!(engine.record_guess(0, 9, GuessResult::Miss).is_err());
+    assert_eq!(
+        validate_guess(&engine, Coord::new(2, 8)),
+        Err(ProtocolViolation::CoordinateOutOfBounds { target: Coord::new(2, 8), size: 8 })
+    );
+    assert_eq!(validate_guess(&engine, Coord::new(7, 7)), Ok(Coord::new(7, 7)));
+}
+
+#[test]
+fn test_random_fleet_stays_on_small_board() {
+    let small = rules(MIN_BOARD_SIZE + 1);
+    for seed in 0..20 {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut engine = GameEngine::new_with_config(small);
+        engine.board_mut().place_all_random(&mut rng).unwrap();
+        assert!((engine.board().ship_map() & small.off_board()).is_empty());
+
+        let mut engine = GameEngine::new_with_config(small);
+        AiPlayer::new().place_ships(&mut rng, engine.board_mut()).unwrap();
+        assert!((engine.board().ship_map() & small.off_board()).is_empty());
+    }
+}
+
+#[test]
+fn test_ai_game_on_small_board() {
+    let small = rules(7);
+    let mut rng = SmallRng::seed_from_u64(3501);
+    let mut players = [AiPlayer::new(), AiPlayer::new()];
+    let mut engines = [GameEngine::new_with_config(small), GameEngine::new_with_config(small)];
+    for (player, engine) in players.iter_mut().zip(engines.iter_mut()) {
+        player.place_ships(&mut rng, engine.board_mut()).unwrap();
+    }
+
+    let mut shooter = 0;
+    for _ in 0..2 * 49 {
+        let defender = 1 - shooter;
+        let target = players[shooter].select_target(
+            &mut rng,
+            &engines[shooter].guess_hits(),
+            &engines[shooter].blocked_cells(),
+            &engines[shooter].enemy_ship_lengths_remaining(),
+        );
+        assert!(small.contains(target.row, target.col), "aimed off the board at {:?}", target);
+        let res = engines[defender].opponent_guess(target.row, target.col).unwrap();
+        engines[shooter].record_guess(target.row, target.col, res).unwrap();
+        players[shooter].handle_guess_result(target, res);
+        if engines[defender].status() == GameStatus::Lost {
+            assert_eq!(engines[shooter].status(), GameStatus::Won);
+            return;
+        }
+        shooter = defender;
+    }
+    panic!("game on a 7x7 board did not finish");
+}
+
+#[test]
+fn test_board_size_survives_state_and_json() {
+    let mut rng = SmallRng::seed_from_u64(8);
+    let mut engine = GameEngine::new_with_config(rules(8));
+    engine.board_mut().place_all_random(&mut rng).unwrap();
+    engine.record_guess(7, 7, GuessResult::Miss).unwrap();
+
+    assert_eq!(engine.state().my_board.size, 8);
+    let restored = GameEngine::from_state(engine.state());
+    assert_eq!(restored.rules(), engine.rules());
+
+    let json = engine.to_json().unwrap();
+    assert!(json.contains("\"board_size\":8"));
+    let restored = GameEngine::from_json(&json).unwrap();
+    assert_eq!(restored.rules(), engine.rules());
+    assert_eq!(restored.state(), engine.state());
+}
+
+#[test]
+fn test_json_rejects_cells_off_a_small_board() {
+    let mut engine = GameEngine::new_with_config(rules(8));
+    engine.record_guess(7, 7, GuessResult::Miss).unwrap();
+    let json = engine.to_json().unwrap().replace("[[7,7]]", "[[9,9]]");
+    assert!(GameEngine::from_json(&json).is_err());
+
+    let unsupported = engine.to_json().unwrap().replace("\"board_size\":8", "\"board_size\":3");
+    assert!(GameEngine::from_json(&unsupported).is_err());
+}
+
+#[test]
+fn test_game_config_maps_to_rules() {
+    assert_eq!(GameConfig::local().rules(), Some(RuleSet::STANDARD));
+    let config = GameConfig::from(&rules(8));
+    assert_eq!(config.board_size, 8);
+    assert_eq!(config.rules(), Some(rules(8)));
+
+    let mut too_big = GameConfig::local();
+    too_big.board_size = MAX_BOARD_SIZE + 1;
+    assert_eq!(too_big.rules(), None);
+    let mut other_fleet = GameConfig::local();
+    other_fleet.ships.clear();
+    assert_eq!(other_fleet.rules(), None);
+}
+
+#[tokio::test]
+async fn test_handshake_agrees_on_small_board() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let config = GameConfig::from(&rules(8));
+
+    let peer_config = config.clone();
+    let responder = tokio::spawn(async move { setup::agree_config(&mut t2, PROTOCOL_VERSION, &peer_config).await });
+    let agreed = setup::agree_config(&mut t1, PROTOCOL_VERSION, &config).await.unwrap();
+    assert_eq!(agreed, config);
+    assert_eq!(responder.await.unwrap().unwrap(), config);
+
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let peer_config = config.clone();
+    let responder = tokio::spawn(async move {
+        setup::exchange_ready(&mut t2, PROTOCOL_VERSION, &peer_config, Role::Responder, false, deadline).await
+    });
+    let agreed = setup::exchange_ready(&mut t1, PROTOCOL_VERSION, &config, Role::Initiator, true, deadline).await.unwrap();
+    assert_eq!(agreed, config);
+    assert_eq!(responder.await.unwrap().unwrap(), config);
+}
+
+#[tokio::test]
+async fn test_handshake_rejects_board_size_mismatch() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+
+    let responder = tokio::spawn(async move { setup::agree_config(&mut t2, PROTOCOL_VERSION, &GameConfig::local()).await });
+    let err = setup::agree_config(&mut t1, PROTOCOL_VERSION, &GameConfig::from(&rules(8))).await.unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch"));
+    let err = responder.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch"));
+}
+
+#[tokio::test]
+async fn test_start_game_still_rejects_board_size_mismatch() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    let responder = tokio::spawn(async move {
+        setup::exchange_ready(&mut t2, PROTOCOL_VERSION, &GameConfig::local(), Role::Responder, false, deadline).await
+    });
+    setup::exchange_ready(&mut t1, PROTOCOL_VERSION, &GameConfig::from(&rules(8)), Role::Initiator, true, deadline)
+        .await
+        .unwrap();
+    let err = responder.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch"));
+}
@@ -1,5 +1,5 @@
-use battleship::{Board, BoardError, GuessResult, Orientation, BOARD_SIZE, NUM_SHIPS, SHIPS};
-use battleship::{BoardState, Ship};
+use battleship::{Board, BoardError, CellState, Coord, GuessResult, Orientation, RuleSet, BOARD_SIZE, SHIPS};
+use battleship::{BoardBits, BoardState, Ship};
 use rand::rngs::SmallRng;
 use rand::SeedableRng;
 
@@ -9,18 +9,18 @@ fn test_manual_place_and_guess_sink() {
     board.place(0, 0, 0, Orientation::Horizontal).unwrap();
 
     for c in 0..SHIPS[0].length() - 1 {
-        assert_eq!(board.guess(0, c).unwrap(), GuessResult::Hit);
+        assert_eq!(board.guess(Coord::new(0, c)).unwrap(), GuessResult::Hit);
     }
     // final hit should sink
     assert_eq!(
-        board.guess(0, SHIPS[0].length() - 1).unwrap(),
+        board.guess(Coord::new(0, SHIPS[0].length() - 1)).unwrap(),
         GuessResult::Sink("Carrier")
     );
     assert!(board.ship_states()[0].sunk);
 
     // repeated guess triggers error
     assert_eq!(
-        board.guess(0, SHIPS[0].length() - 1).unwrap_err(),
+        board.guess(Coord::new(0, SHIPS[0].length() - 1)).unwrap_err(),
         BoardError::AlreadyGuessed
     );
 }
@@ -42,10 +42,10 @@ fn test_place_random_all_ships_no_overlap() {
     let mut rng = SmallRng::seed_from_u64(42);
 
     let mut expected_bits = 0;
-    for i in 0..NUM_SHIPS as usize {
+    for (i, def) in SHIPS.iter().enumerate() {
         let (r, c, orient) = board.random_placement(&mut rng, i).unwrap();
         board.place(i, r, c, orient).unwrap();
-        expected_bits += SHIPS[i].length();
+        expected_bits += def.length();
     }
 
     assert_eq!(
@@ -59,12 +59,12 @@ fn test_place_random_all_ships_no_overlap() {
 fn test_board_state_roundtrip() {
     let mut board = Board::new();
     board.place(1, 2, 2, Orientation::Vertical).unwrap();
-    board.guess(2, 2).unwrap();
+    board.guess(Coord::new(2, 2)).unwrap();
 
     let state = BoardState::from(&board);
     let mut board2: Board = state.into();
 
-    assert_eq!(board2.guess(2, 2).unwrap_err(), BoardError::AlreadyGuessed);
+    assert_eq!(board2.guess(Coord::new(2, 2)).unwrap_err(), BoardError::AlreadyGuessed);
     assert_eq!(
         board2.ship_states()[1].position,
         Some((2, 2, Orientation::Vertical))
@@ -77,9 +77,222 @@ fn test_ship_state_conversion() {
     board.place(2, 4, 1, Orientation::Horizontal).unwrap();
     let states = board.ship_states();
     let def = SHIPS[2];
-    let ship = Ship::<u128, { BOARD_SIZE as usize }>::from_state(&states[2], def)
+    let ship = Ship::<BoardBits>::from_state(&states[2], def)
         .unwrap()
         .unwrap();
     assert_eq!(ship.origin(), (4, 1));
     assert_eq!(ship.orientation(), Orientation::Horizontal);
 }
+
+#[test]
+fn test_can_place_reports_without_mutating() {
+    let mut board = Board::new();
+    board.place(0, 0, 0, Orientation::Horizontal).unwrap();
+    let before = BoardState::from(&board);
+
+    assert_eq!(board.can_place(1, 0, 0, Orientation::Vertical), Err(BoardError::ShipOverlaps));
+    assert_eq!(board.can_place(1, 0, 8, Orientation::Horizontal), Err(BoardError::ShipOutOfBounds));
+    assert_eq!(board.can_place(0, 5, 5, Orientation::Horizontal), Err(BoardError::ShipAlreadyPlaced));
+    assert_eq!(board.can_place(SHIPS.len(), 5, 5, Orientation::Horizontal), Err(BoardError::InvalidIndex));
+    assert_eq!(board.can_place(1, 1, 0, Orientation::Vertical), Ok(()));
+    assert_eq!(BoardState::from(&board), before);
+}
+
+#[test]
+fn test_can_place_agrees_with_place() {
+    let mut board = Board::new();
+    board.place(0, 4, 2, Orientation::Vertical).unwrap();
+    let n = BOARD_SIZE as usize;
+    for r in 0..n + 1 {
+        for c in 0..n + 1 {
+            for o in [Orientation::Horizontal, Orientation::Vertical] {
+                let mut probe = Board::from(BoardState::from(&board));
+                assert_eq!(board.can_place(1, r, c, o), probe.place(1, r, c, o), "at ({}, {}) {:?}", r, c, o);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_place_all_random_places_full_fleet() {
+    for seed in 0..50 {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut board = Board::new();
+        board.place_all_random(&mut rng).unwrap();
+        assert!(board.all_placed());
+        let cells: usize = SHIPS.iter().map(|s| s.length()).sum();
+        assert_eq!(board.ship_map().count_ones(), cells);
+    }
+}
+
+#[test]
+fn test_place_all_random_keeps_placed_ships() {
+    let mut rng = SmallRng::seed_from_u64(3);
+    let mut board = Board::new();
+    board.place(2, 9, 0, Orientation::Horizontal).unwrap();
+    board.place_all_random(&mut rng).unwrap();
+    assert!(board.all_placed());
+    assert_eq!(board.ship_states()[2].position, Some((9, 0, Orientation::Horizontal)));
+}
+
+#[test]
+fn test_place_all_random_fits_around_crowded_board() {
+    // Four ships already stacked side by side in the top-left corner
+    let mut board = Board::new();
+    board.place(0, 0, 0, Orientation::Vertical).unwrap();
+    board.place(1, 0, 1, Orientation::Vertical).unwrap();
+    board.place(2, 0, 2, Orientation::Vertical).unwrap();
+    board.place(3, 0, 3, Orientation::Vertical).unwrap();
+    let mut rng = SmallRng::seed_from_u64(11);
+    board.place_all_random(&mut rng).unwrap();
+    assert!(board.all_placed());
+}
+
+#[test]
+fn test_place_all_random_on_full_fleet_is_noop() {
+    let mut rng = SmallRng::seed_from_u64(1);
+    let mut board = Board::new();
+    board.place_all_random(&mut rng).unwrap();
+    let before = BoardState::from(&board);
+    board.place_all_random(&mut rng).unwrap();
+    assert_eq!(BoardState::from(&board), before);
+}
+
+#[test]
+fn test_fleet_report_tracks_damage() {
+    let mut board = Board::new();
+    board.place(0, 0, 0, Orientation::Horizontal).unwrap();
+    board.place(4, 5, 5, Orientation::Vertical).unwrap();
+
+    board.guess(Coord::new(0, 0)).unwrap();
+    board.guess(Coord::new(0, 1)).unwrap();
+    board.guess(Coord::new(5, 5)).unwrap();
+    board.guess(Coord::new(6, 5)).unwrap();
+    board.guess(Coord::new(9, 9)).unwrap();
+
+    let report = board.fleet_report();
+    let carrier = report.ships[0];
+    assert_eq!((carrier.name, carrier.hits, carrier.remaining, carrier.sunk), ("Carrier", 2, 3, false));
+    let destroyer = report.ships[4];
+    assert_eq!((destroyer.hits, destroyer.remaining, destroyer.sunk), (2, 0, true));
+    assert!(!report.ships[1].placed);
+    assert_eq!(report.ships[1].remaining, SHIPS[1].length());
+
+    assert_eq!(report.total_cells(), 17);
+    assert_eq!(report.hit_cells(), 4);
+    assert_eq!(report.ships_afloat(), 4);
+    // 13 of 17 cells intact
+    assert_eq!(report.health_percent(), 76);
+}
+
+#[test]
+fn test_fleet_report_health_bounds() {
+    let mut rng = SmallRng::seed_from_u64(8);
+    let mut board = Board::new();
+    board.place_all_random(&mut rng).unwrap();
+    assert_eq!(board.fleet_report().health_percent(), 100);
+
+    for (r, c) in board.ship_map().iter_set_bits() {
+        board.guess(Coord::new(r, c)).unwrap();
+    }
+    let report = board.fleet_report();
+    assert_eq!(report.health_percent(), 0);
+    assert_eq!(report.ships_afloat(), 0);
+}
+
+#[test]
+fn test_remove_frees_the_ship_cells() {
+    let mut board = Board::new();
+    board.place(0, 0, 0, Orientation::Horizontal).unwrap();
+    board.place(1, 1, 0, Orientation::Horizontal).unwrap();
+
+    assert_eq!(board.remove(0), Ok((0, 0, Orientation::Horizontal)));
+    assert_eq!(board.ship_map().count_ones(), SHIPS[1].length());
+    assert!(board.ship_states()[0].position.is_none());
+    assert_eq!(board.ship_index_at(0, 0), None);
+    assert_eq!(board.remove(0), Err(BoardError::ShipNotPlaced));
+    assert_eq!(board.remove(SHIPS.len()), Err(BoardError::InvalidIndex));
+
+    // The freed cells take another ship, and the removed one goes elsewhere
+    board.place(2, 0, 0, Orientation::Horizontal).unwrap();
+    board.place(0, 5, 0, Orientation::Vertical).unwrap();
+    assert_eq!(board.ship_index_at(9, 0), Some(0));
+}
+
+#[test]
+fn test_move_ship_keeps_the_ship_on_failure() {
+    let mut board = Board::new();
+    board.place(0, 0, 0, Orientation::Horizontal).unwrap();
+    board.place(1, 2, 0, Orientation::Horizontal).unwrap();
+    let before = board.ship_map();
+
+    assert_eq!(board.move_ship(0, 2, 1, Orientation::Horizontal), Err(BoardError::ShipOverlaps));
+    assert_eq!(board.move_ship(0, 0, 8, Orientation::Horizontal), Err(BoardError::ShipOutOfBounds));
+    assert_eq!(board.ship_map(), before);
+    assert_eq!(board.ship_states()[0].position, Some((0, 0, Orientation::Horizontal)));
+
+    // Moving onto cells it already covers is fine
+    board.move_ship(0, 0, 1, Orientation::Horizontal).unwrap();
+    assert_eq!(board.ship_index_at(0, 0), None);
+    assert_eq!(board.ship_index_at(0, 5), Some(0));
+    assert_eq!(board.move_ship(3, 5, 5, Orientation::Vertical), Err(BoardError::ShipNotPlaced));
+
+    board.guess(Coord::new(9, 9)).unwrap();
+    assert_eq!(board.move_ship(0, 5, 0, Orientation::Vertical), Err(BoardError::PlacementLocked));
+    assert_eq!(board.remove(1), Err(BoardError::PlacementLocked));
+}
+
+#[test]
+fn test_cell_reports_ships_and_shots() {
+    let mut board = Board::new();
+    board.place(4, 2, 3, Orientation::Vertical).unwrap();
+    board.guess(Coord::new(2, 3)).unwrap();
+    board.guess(Coord::new(0, 0)).unwrap();
+
+    assert_eq!(board.cell(2, 3), Ok(CellState::Hit(4)));
+    assert_eq!(board.cell(3, 3), Ok(CellState::Ship(4)));
+    assert_eq!(board.cell(0, 0), Ok(CellState::Miss));
+    assert_eq!(board.cell(9, 9), Ok(CellState::Empty));
+    assert!(board.cell(0, BOARD_SIZE as usize).is_err());
+
+    let cells: Vec<_> = board.cells().collect();
+    assert_eq!(cells.len(), BOARD_SIZE as usize * BOARD_SIZE as usize);
+    assert_eq!(cells[0], ((0, 0), CellState::Miss));
+    let ship_cells: Vec<_> = cells
+        .iter()
+        .filter(|(_, state)| matches!(state, CellState::Ship(_) | CellState::Hit(_)))
+        .map(|&(cell, _)| cell)
+        .collect();
+    assert_eq!(ship_cells, [(2, 3), (3, 3)]);
+
+    let small = Board::with_rules(&RuleSet::with_board_size(6).unwrap());
+    assert_eq!(small.cells().count(), 36);
+    assert!(small.cell(6, 0).is_err());
+}
+
+#[test]
+fn test_ship_damage_follows_segments_from_origin() {
+    let mut board = Board::new();
+    board.place(1, 2, 4, Orientation::Vertical).unwrap();
+    board.guess(Coord::new(3, 4)).unwrap();
+    board.guess(Coord::new(5, 4)).unwrap();
+
+    let state = board.ship_states()[1];
+    assert_eq!(state.damage, 0b1010);
+    assert!(!state.segment_hit(0));
+    assert!(state.segment_hit(1));
+    assert!(state.segment_hit(3));
+    assert!(!state.segment_hit(40));
+
+    let mut ship = Ship::<BoardBits>::new(SHIPS[1], Orientation::Vertical, 2, 4).unwrap();
+    assert!(ship.hit_cells().is_empty());
+    ship.guess(3, 4);
+    ship.guess(5, 4);
+    assert_eq!(ship.hit_cells().iter_set_bits().collect::<Vec<_>>(), [(3, 4), (5, 4)]);
+    assert_eq!(ship.damage(), state.damage);
+    let restored = Board::from(BoardState::from(&board));
+    assert_eq!(restored.ship_states()[1], state);
+
+    let reported = battleship::domain::Ship::from(state);
+    assert_eq!(reported.hit_segments, [1, 3]);
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "std")]
+
+use battleship::domain::GameConfig;
+use battleship::protocol::setup;
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::PROTOCOL_VERSION;
+use battleship::{
+    BoardError, DuplicateGuessPolicy, FleetConfig, GameEngine, GameEngineBuilder, GuessResult, Orientation,
+    PlacementRules, Role, RuleSet, TurnPolicy,
+};
+use tokio::time::{Duration, Instant};
+
+fn custom() -> GameEngineBuilder {
+    GameEngine::builder()
+        .board_size(8)
+        .fleet(FleetConfig::empty().with_ships("Destroyer", 2, 3).unwrap())
+        .salvo(true)
+        .placement(PlacementRules::NO_TOUCHING)
+        .turn_policy(TurnPolicy::ContinueOnHit)
+}
+
+#[test]
+fn test_builder_composes_every_rule() {
+    assert_eq!(GameEngine::builder().build().unwrap().rules(), RuleSet::STANDARD);
+    assert_eq!(GameEngineBuilder::default(), GameEngine::builder());
+
+    let expected = RuleSet::new(8, FleetConfig::empty().with_ships("Destroyer", 2, 3).unwrap())
+        .unwrap()
+        .with_salvo(true)
+        .with_placement(PlacementRules::NO_TOUCHING)
+        .with_turn_policy(TurnPolicy::ContinueOnHit);
+    assert_eq!(custom().rules(), Ok(expected));
+    assert_eq!(custom().build().unwrap().rules(), expected);
+    assert_eq!(GameEngineBuilder::from(expected), custom());
+}
+
+#[test]
+fn test_builder_rejects_rules_that_cannot_be_played() {
+    assert_eq!(
+        GameEngine::builder().board_size(4).build().err(),
+        Some(BoardError::UnsupportedBoardSize(4))
+    );
+    assert_eq!(
+        GameEngine::builder().fleet(FleetConfig::empty()).build().err(),
+        Some(BoardError::InvalidFleet)
+    );
+    // The order options are set in does not matter
+    let fleet = FleetConfig::empty().with_ships("Destroyer", 2, 1).unwrap();
+    assert!(GameEngine::builder().board_size(4).fleet(fleet).build().is_ok());
+}
+
+#[test]
+fn test_builder_sets_duplicate_policy() {
+    let mut engine = GameEngine::builder()
+        .duplicate_policy(DuplicateGuessPolicy::WastedTurn)
+        .build()
+        .unwrap();
+    assert_eq!(engine.duplicate_policy(), DuplicateGuessPolicy::WastedTurn);
+    engine.board_mut().place(4, 0, 0, Orientation::Horizontal).unwrap();
+    engine.opponent_guess(0, 0).unwrap();
+    assert_eq!(engine.opponent_guess(0, 0), Ok(GuessResult::Miss));
+
+    // Part of the config the peers agree on
+    let builder = GameEngine::builder().duplicate_policy(DuplicateGuessPolicy::WastedTurn);
+    let config = GameConfig::try_from(&builder).unwrap();
+    assert_eq!(config.duplicate_policy, DuplicateGuessPolicy::WastedTurn);
+    assert_eq!(config, GameConfig::from(&engine));
+    assert_ne!(config, GameConfig::local());
+    assert_eq!(config.builder(), Some(builder));
+}
+
+#[test]
+fn test_builder_round_trips_through_game_config() {
+    let config = GameConfig::try_from(&GameEngine::builder().board_size(8).salvo(true)).unwrap();
+    assert_eq!(config.board_size, 8);
+    assert!(config.salvo);
+    assert_eq!(config.builder(), Some(GameEngine::builder().board_size(8).salvo(true)));
+
+    // Custom fleets travel with their ship names
+    let config = GameConfig::try_from(&custom()).unwrap();
+    assert_eq!(config.builder(), Some(custom()));
+    assert!(GameConfig::try_from(&GameEngine::builder().board_size(3)).is_err());
+}
+
+#[tokio::test]
+async fn test_config_agreement_compares_duplicate_policy() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let ours = GameConfig::try_from(&GameEngine::builder()).unwrap();
+    let theirs =
+        GameConfig::try_from(&GameEngine::builder().duplicate_policy(DuplicateGuessPolicy::IdempotentReturnPrior)).unwrap();
+
+    let responder = tokio::spawn(async move { setup::agree_config(&mut t2, PROTOCOL_VERSION, &theirs).await });
+    let err = setup::agree_config(&mut t1, PROTOCOL_VERSION, &ours).await.unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch"));
+    assert!(responder.await.unwrap().is_err());
+}
+
+#[tokio::test]
+async fn test_handshake_compares_builder_rules() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let deadline = Instant::now() + Duration::from_secs(5);
+    let ours = GameConfig::try_from(&GameEngine::builder().turn_policy(TurnPolicy::ContinueOnHit)).unwrap();
+    let theirs = GameConfig::try_from(&GameEngine::builder().placement(PlacementRules::NO_TOUCHING)).unwrap();
+
+    let responder =
+        tokio::spawn(async move { setup::exchange_ready(&mut t2, PROTOCOL_VERSION, &theirs, Role::Responder, false, deadline).await });
+    setup::exchange_ready(&mut t1, PROTOCOL_VERSION, &ours, Role::Initiator, true, deadline).await.unwrap();
+    let err = responder.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch"));
+}
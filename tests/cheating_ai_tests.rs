@@ -0,0 +1,56 @@
+#![cfg(feature = "debug-tools")]
+
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{
+    AiPlayer, BoardBits, CheatingAiPlayer, Coord, GameEngine, GameStatus, Player, PlayerNode, Role,
+    TOTAL_SHIP_CELLS,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+#[test]
+fn test_cheater_only_targets_ship_cells() {
+    let mut rng = SmallRng::seed_from_u64(3);
+    let mut engine = GameEngine::new();
+    AiPlayer::new()
+        .place_ships(&mut rng, engine.board_mut())
+        .unwrap();
+    let mut cheater = CheatingAiPlayer::from_board(engine.board());
+    let remaining = engine.enemy_ship_lengths_remaining();
+
+    let mut hits = BoardBits::new();
+    let misses = BoardBits::new();
+    for _ in 0..TOTAL_SHIP_CELLS {
+        let Coord { row: r, col: c } = cheater.select_target(&mut rng, &hits, &misses, &remaining);
+        assert!(engine.board().ship_map().get(r, c).unwrap());
+        assert!(!hits.get(r, c).unwrap());
+        hits.set(r, c).unwrap();
+    }
+    assert_eq!(hits, engine.board().ship_map());
+}
+
+#[tokio::test]
+async fn test_cheater_wins_in_fleet_size_shots() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut rng1 = SmallRng::seed_from_u64(10);
+    let mut rng2 = SmallRng::seed_from_u64(20);
+
+    let mut victim = AiPlayer::new();
+    let mut victim_engine = GameEngine::new();
+    victim.place_ships(&mut rng2, victim_engine.board_mut()).unwrap();
+    let cheater = CheatingAiPlayer::from_board(victim_engine.board());
+
+    let mut node1 = PlayerNode::new(Box::new(cheater), GameEngine::new(), Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(victim), victim_engine, Box::new(t2));
+
+    let (r1, r2) = tokio::join!(
+        node1.run(&mut rng1, Role::Initiator),
+        node2.run(&mut rng2, Role::Responder)
+    );
+    r1.unwrap();
+    r2.unwrap();
+
+    assert_eq!(node1.status(), GameStatus::Won);
+    assert_eq!(node2.status(), GameStatus::Lost);
+    assert_eq!(node1.guess_count(), TOTAL_SHIP_CELLS);
+}
@@ -1,7 +1,8 @@
 #[cfg(feature = "std")]
 #[cfg(test)]
 mod cli_tests {
-    use battleship::{CliPlayer, GameEngine};
+    use battleship::player::cli::{fleet_status_lines, print_player_view};
+    use battleship::{CliPlayer, GameEngine, GuessResult, Orientation};
     use rand::rngs::SmallRng;
     use rand::SeedableRng;
 
@@ -11,6 +12,14 @@ mod cli_tests {
         let _player = CliPlayer::new();
     }
 
+    #[test]
+    fn test_cli_suggestion_takes_ai_config() {
+        let config = battleship::AiConfig { deterministic: true, ..Default::default() };
+        let player = CliPlayer::text_only().with_ai_config(config);
+        assert_eq!(player.ai_config(), &config);
+        assert_eq!(CliPlayer::new().ai_config(), &battleship::AiConfig::default());
+    }
+
     #[test]
     fn test_cli_with_fixed_seed_placement() {
         // Test that CLI player can use board with fixed RNG seed
@@ -58,4 +67,28 @@ mod cli_tests {
             assert_eq!(states1[i].position, states2[i].position);
         }
     }
+
+    #[test]
+    fn test_fleet_status_sidebar() {
+        let mut engine = GameEngine::new();
+        for i in 0..5 {
+            engine.board_mut().place(i, i, 0, Orientation::Horizontal).unwrap();
+        }
+        engine.record_guess(0, 0, GuessResult::Sink("Destroyer")).unwrap();
+        engine.opponent_guess(0, 0).unwrap();
+        engine.opponent_guess(4, 0).unwrap();
+        engine.opponent_guess(4, 1).unwrap();
+        engine.opponent_guess(1, 2).unwrap();
+
+        let lines = fleet_status_lines(&engine);
+        assert_eq!(lines[0], "Enemy fleet (4/5 afloat)");
+        assert_eq!(lines[1], "  Carrier    5  afloat");
+        assert_eq!(lines[5], "  Destroyer  2  SUNK");
+        assert_eq!(lines[7], "Your fleet (76% health)");
+        assert_eq!(lines[8], "  Carrier    5  1/5 hit [X....]");
+        assert_eq!(lines[9], "  Battleship 4  1/4 hit [..X.]");
+        assert_eq!(lines[10], "  Cruiser    3  Active");
+        assert_eq!(lines[12], "  Destroyer  2  SUNK");
+        print_player_view(&engine);
+    }
 }
@@ -0,0 +1,139 @@
+use battleship::protocol::commitment::{layout_digest, reveal_due, verify_game_over, verify_reveal, LayoutCommitment};
+use battleship::protocol::CheatDetected;
+use battleship::{AiPlayer, Board, BoardState, Coord, GameEngine, GuessResult, Player, RuleSet, Stats};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+/// A fleet placed from `seed`.
+fn place_fleet(seed: u64) -> Board {
+    let mut fleet = Board::new();
+    AiPlayer::new().place_ships(&mut SmallRng::seed_from_u64(seed), &mut fleet).unwrap();
+    fleet
+}
+
+/// A placed fleet and our engine after firing at `shots` of its cells,
+/// recording the answers it gives.
+fn fleet_and_shots(shots: &[Coord]) -> (Board, GameEngine) {
+    let mut answers = place_fleet(5);
+    let mut engine = GameEngine::new();
+    for &target in shots {
+        let res = answers.guess(target).unwrap();
+        engine.record_guess(target.row, target.col, res).unwrap();
+    }
+    (place_fleet(5), engine)
+}
+
+fn shots() -> Vec<Coord> {
+    (0..10).map(|i| Coord::new(i, i)).collect()
+}
+
+#[test]
+fn test_honest_reveal_replays_our_shots() {
+    let (fleet, engine) = fleet_and_shots(&shots());
+    let layout = LayoutCommitment::new(&mut SmallRng::seed_from_u64(1), &fleet);
+    let board = verify_reveal(&layout.digest(), &layout.salt, &layout.layout, &RuleSet::STANDARD, engine.history())
+        .unwrap();
+    assert_eq!(board.hits(), engine.guess_hits());
+    assert_eq!(board.misses(), engine.guess_misses());
+}
+
+#[test]
+fn test_other_salt_does_not_match_commitment() {
+    let (fleet, engine) = fleet_and_shots(&shots());
+    let layout = LayoutCommitment::new(&mut SmallRng::seed_from_u64(1), &fleet);
+    let mut salt = layout.salt;
+    salt[0] ^= 1;
+    let err = verify_reveal(&layout.digest(), &salt, &layout.layout, &RuleSet::STANDARD, engine.history());
+    assert_eq!(err.unwrap_err(), CheatDetected::CommitmentMismatch);
+}
+
+#[test]
+fn test_moved_ship_does_not_match_commitment() {
+    let (fleet, engine) = fleet_and_shots(&shots());
+    let layout = LayoutCommitment::new(&mut SmallRng::seed_from_u64(1), &fleet);
+    let moved = place_fleet(6);
+    assert_ne!(moved.ship_map(), fleet.ship_map());
+    let revealed = LayoutCommitment { salt: layout.salt, layout: (&moved).into() };
+    let err = verify_reveal(&layout.digest(), &revealed.salt, &revealed.layout, &RuleSet::STANDARD, engine.history());
+    assert_eq!(err.unwrap_err(), CheatDetected::CommitmentMismatch);
+}
+
+#[test]
+fn test_committed_illegal_layout_is_rejected() {
+    let (fleet, engine) = fleet_and_shots(&[]);
+    let mut layout = LayoutCommitment::new(&mut SmallRng::seed_from_u64(1), &fleet).layout;
+    layout.ship_states[1].position = layout.ship_states[0].position;
+    let salt = [3; 32];
+    let err = verify_reveal(&layout_digest(&salt, &layout), &salt, &layout, &RuleSet::STANDARD, engine.history());
+    assert!(matches!(err.unwrap_err(), CheatDetected::IllegalLayout(_)));
+}
+
+#[test]
+fn test_answer_the_layout_contradicts_is_caught() {
+    let (fleet, mut engine) = fleet_and_shots(&[]);
+    let water = (0..10)
+        .flat_map(|r| (0..10).map(move |c| Coord::new(r, c)))
+        .find(|c| !fleet.ship_map().get(c.row, c.col).unwrap())
+        .unwrap();
+    engine.record_guess(water.row, water.col, GuessResult::Hit).unwrap();
+    let layout = LayoutCommitment::new(&mut SmallRng::seed_from_u64(1), &fleet);
+    let err = verify_reveal(&layout.digest(), &layout.salt, &layout.layout, &RuleSet::STANDARD, engine.history());
+    assert_eq!(
+        err.unwrap_err(),
+        CheatDetected::ResultMismatch {
+            target: water,
+            reported: GuessResult::Hit,
+            actual: GuessResult::Miss
+        }
+    );
+}
+
+#[test]
+fn test_reveal_due_once_the_game_is_over() {
+    let (_, mut engine) = fleet_and_shots(&shots());
+    assert!(!reveal_due(&engine));
+    engine.record_resignation();
+    assert!(reveal_due(&engine));
+
+    let (_, mut engine) = fleet_and_shots(&shots());
+    engine.abort();
+    assert!(!reveal_due(&engine));
+}
+
+/// The peer's board as [`verify_reveal`] rebuilt it, with our engine.
+fn revealed_after_shots() -> (Board, GameEngine) {
+    let (fleet, engine) = fleet_and_shots(&shots());
+    let layout = LayoutCommitment::new(&mut SmallRng::seed_from_u64(1), &fleet);
+    let board = verify_reveal(&layout.digest(), &layout.salt, &layout.layout, &RuleSet::STANDARD, engine.history())
+        .unwrap();
+    (board, engine)
+}
+
+#[test]
+fn test_honest_game_over_is_accepted() {
+    let (board, engine) = revealed_after_shots();
+    let final_board: BoardState = (&board).into();
+    assert_eq!(verify_game_over(&board, &final_board, &Stats::default(), &engine), Ok(()));
+}
+
+#[test]
+fn test_game_over_with_extra_shots_is_rejected() {
+    let (board, engine) = revealed_after_shots();
+    let mut final_board: BoardState = (&board).into();
+    final_board.misses.set(9, 0).unwrap();
+    let err = verify_game_over(&board, &final_board, &Stats::default(), &engine);
+    assert!(matches!(err, Err(CheatDetected::GameOverMismatch(_))));
+}
+
+#[test]
+fn test_game_over_with_inflated_stats_is_rejected() {
+    let (board, engine) = revealed_after_shots();
+    let final_board: BoardState = (&board).into();
+    let stats = Stats {
+        hits: 3,
+        ships_sunk: 1,
+        ..Stats::default()
+    };
+    let err = verify_game_over(&board, &final_board, &stats, &engine);
+    assert!(matches!(err, Err(CheatDetected::GameOverMismatch(_))));
+}
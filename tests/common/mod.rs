@@ -0,0 +1,137 @@
+//! Fixtures shared by several integration test files.
+
+#![allow(dead_code)]
+
+use battleship::{
+    calc_exact_pdf, AiPlayer, Board, BoardBits, FleetConfig, GameEngine, GameStatus, GuessResult, Orientation,
+    PlacementRules, Player, RuleSet, TargetingView, MAX_BOARD_SIZE, MAX_SHIPS, NUM_SHIPS, SHIPS,
+};
+use rand::rngs::SmallRng;
+
+/// An engine with ship `i` laid horizontally along row `i` from column 0.
+pub fn stacked_engine() -> GameEngine {
+    stack(GameEngine::new())
+}
+
+/// `engine` with ship `i` of its fleet laid horizontally along row `i`
+/// from column 0, for engines with other rules.
+pub fn stack(mut engine: GameEngine) -> GameEngine {
+    for i in 0..engine.rules().fleet().len() {
+        engine.board_mut().place(i, i, 0, Orientation::Horizontal).unwrap();
+    }
+    engine
+}
+
+/// Ships stacked in rows 0..5 from column 0, longest first.
+pub fn stacked_fleet() -> Board {
+    let mut board = Board::new();
+    for i in 0..NUM_SHIPS {
+        board.place(i, i, 0, Orientation::Horizontal).unwrap();
+    }
+    board
+}
+
+/// [`stacked_fleet`] as the placements a client submits.
+#[cfg(feature = "std")]
+pub fn stacked_placements() -> Vec<battleship::domain::Placement> {
+    battleship::domain::Placement::from_board(&stacked_fleet())
+}
+
+/// The ship lengths of the standard fleet, longest first.
+pub fn full_fleet() -> [usize; NUM_SHIPS] {
+    core::array::from_fn(|i| SHIPS[i].length())
+}
+
+/// A board with each of `list` set.
+pub fn cells(list: &[(usize, usize)]) -> BoardBits {
+    BoardBits::try_from_cells(list.iter().copied()).unwrap()
+}
+
+/// Ships sunk from column 0: the first `len` cells of each `(row, len)`.
+pub fn wrecks(runs: &[(usize, usize)]) -> BoardBits {
+    let mut bits = BoardBits::new();
+    for &(row, len) in runs {
+        for col in 0..len {
+            bits.set(row, col).unwrap();
+        }
+    }
+    bits
+}
+
+/// Misses on every cell but `shot` and `open`, as if a search had left
+/// only `open` to try.
+pub fn misses_except(shot: BoardBits, open: &[(usize, usize)]) -> BoardBits {
+    !(shot | cells(open))
+}
+
+/// A density over every cell of the board storage.
+pub type Pdf = [[f64; MAX_BOARD_SIZE as usize]; MAX_BOARD_SIZE as usize];
+
+/// [`calc_exact_pdf`] for the standard fleet and placement rules.
+pub fn exact_pdf(hits: &BoardBits, misses: &BoardBits, remaining: &[usize]) -> Option<Pdf> {
+    calc_exact_pdf(hits, misses, remaining, &FleetConfig::STANDARD, &PlacementRules::STANDARD)
+}
+
+/// A view of the board for the standard fleet and placement rules.
+pub fn standard_view<'a>(
+    hits: &'a BoardBits,
+    sunk: &'a BoardBits,
+    misses: &'a BoardBits,
+    remaining: &'a [usize],
+) -> TargetingView<'a> {
+    TargetingView {
+        hits,
+        sunk,
+        misses,
+        remaining,
+        fleet: &FleetConfig::STANDARD,
+        placement: &PlacementRules::STANDARD,
+    }
+}
+
+/// What a shooter has learned of the enemy board: hits, sunk cells, blocked
+/// cells and the lengths still afloat.
+pub type Seen = (BoardBits, BoardBits, BoardBits, [usize; MAX_SHIPS]);
+
+/// One side of a game: a fleet laid out by an [`AiPlayer`] and the engine
+/// of the player shooting at it.
+pub struct Duel {
+    pub target: GameEngine,
+    pub shooter: GameEngine,
+}
+
+impl Duel {
+    /// A standard game with the target fleet placed from `rng`.
+    pub fn new(rng: &mut SmallRng) -> Self {
+        Self::with_rules(rng, RuleSet::STANDARD)
+    }
+
+    /// [`Duel::new`] for a game played under `rules`.
+    pub fn with_rules(rng: &mut SmallRng, rules: RuleSet) -> Self {
+        let mut target = GameEngine::new_with_config(rules);
+        AiPlayer::new().place_ships(rng, target.board_mut()).unwrap();
+        Duel { target, shooter: GameEngine::new_with_config(rules) }
+    }
+
+    /// Shoot at `(row, col)` and record the result on the shooter's side.
+    pub fn fire(&mut self, row: usize, col: usize) -> GuessResult {
+        let result = self.target.opponent_guess(row, col).unwrap();
+        self.shooter.record_guess(row, col, result).unwrap();
+        result
+    }
+
+    /// Whether the whole target fleet is sunk.
+    pub fn won(&self) -> bool {
+        self.target.status() == GameStatus::Lost
+    }
+
+    /// What the shooter has learned so far.
+    pub fn seen(&self) -> Seen {
+        (
+            self.shooter.guess_hits(),
+            self.shooter.opponent_model().sunk_cells(),
+            self.shooter.blocked_cells(),
+            self.shooter.enemy_ship_lengths_remaining(),
+        )
+    }
+}
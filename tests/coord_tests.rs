@@ -0,0 +1,52 @@
+use battleship::{Coord, CoordError};
+
+#[test]
+fn test_notation_round_trips() {
+    assert_eq!("B7".parse::<Coord>(), Ok(Coord::new(6, 1)));
+    assert_eq!("j10".parse::<Coord>(), Ok(Coord::new(9, 9)));
+    for row in 0..10 {
+        for col in 0..10 {
+            let coord = Coord::new(row, col);
+            assert_eq!(coord.to_string().parse::<Coord>(), Ok(coord));
+        }
+    }
+    assert_eq!(Coord::new(0, 0).to_string(), "A1");
+    assert_eq!(<(usize, usize)>::from(Coord::from((3, 4))), (3, 4));
+}
+
+#[test]
+fn test_bad_notation_names_the_problem() {
+    let cases = [
+        ("", CoordError::Empty),
+        ("A", CoordError::TooShort),
+        ("55", CoordError::InvalidColumn('5')),
+        ("M1", CoordError::ColumnOutOfBounds('M')),
+        ("AA", CoordError::InvalidRow),
+        ("A0", CoordError::RowOutOfBounds(0)),
+        ("A13", CoordError::RowOutOfBounds(13)),
+    ];
+    for (text, error) in cases {
+        assert_eq!(text.parse::<Coord>(), Err(error), "{:?}", text);
+    }
+    #[cfg(not(feature = "large-boards"))]
+    assert_eq!(
+        CoordError::ColumnOutOfBounds('K').to_string(),
+        "Column 'K' out of bounds - must be A-J"
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_guess_keeps_its_wire_bytes() {
+    use battleship::{Message, PROTOCOL_VERSION};
+
+    let msg = Message::Guess {
+        version: PROTOCOL_VERSION,
+        seq: 3,
+        target: Coord::new(4, 7),
+    };
+    let bytes = bincode::serialize(&msg).unwrap();
+    // Variant tag, version, seq, then the row and column bytes
+    assert_eq!(&bytes[bytes.len() - 2..], &[4, 7]);
+    assert!(bincode::serialize(&Coord::new(256, 0)).is_err());
+}
@@ -1,339 +1,330 @@
+use battleship::domain::ErrorCode;
+use battleship::protocol::handshake::{exchange_handshake, negotiate, SUPPORTED_VERSIONS};
+use battleship::protocol::{Message, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
 use battleship::transport::in_memory::InMemoryTransport;
 use battleship::transport::tcp::TcpTransport;
 use battleship::transport::Transport;
-use battleship::protocol::{Message, PROTOCOL_VERSION};
+use battleship::{AiPlayer, Coord, GameEngine, GameStatus, Player, PlayerNode, Role, Skeleton};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
 use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+
+/// A node with a freshly placed fleet.
+fn node(seed: u64, transport: impl Transport + 'static) -> PlayerNode {
+    let mut player = AiPlayer::new();
+    let mut engine = GameEngine::new();
+    player.place_ships(&mut SmallRng::seed_from_u64(seed), engine.board_mut()).unwrap();
+    PlayerNode::new(Box::new(player), engine, Box::new(transport))
+}
+
+/// Play a full game between `initiator` and `responder`.
+async fn play(initiator: &mut PlayerNode, responder: &mut PlayerNode) {
+    let mut rng1 = SmallRng::seed_from_u64(1);
+    let mut rng2 = SmallRng::seed_from_u64(2);
+    let (a, b) = tokio::join!(
+        initiator.run(&mut rng1, Role::Initiator),
+        responder.run(&mut rng2, Role::Responder)
+    );
+    a.unwrap();
+    b.unwrap();
+    for status in [initiator.status(), responder.status()] {
+        assert!(matches!(status, GameStatus::Won | GameStatus::Lost));
+    }
+}
+
+#[test]
+fn test_negotiate_picks_highest_common_version() {
+    assert_eq!(negotiate(&SUPPORTED_VERSIONS, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION), Some(PROTOCOL_VERSION));
+    assert_eq!(negotiate(&SUPPORTED_VERSIONS, 0, MIN_PROTOCOL_VERSION), Some(MIN_PROTOCOL_VERSION));
+    assert_eq!(negotiate(&SUPPORTED_VERSIONS, PROTOCOL_VERSION, PROTOCOL_VERSION + 10), Some(PROTOCOL_VERSION));
+    assert_eq!(negotiate(&SUPPORTED_VERSIONS, PROTOCOL_VERSION + 1, PROTOCOL_VERSION + 10), None);
+    assert_eq!(negotiate(&SUPPORTED_VERSIONS, 0, MIN_PROTOCOL_VERSION - 1), None);
+    // An inverted range overlaps nothing
+    assert_eq!(negotiate(&SUPPORTED_VERSIONS, PROTOCOL_VERSION + 1, MIN_PROTOCOL_VERSION), None);
+}
 
 #[tokio::test]
-async fn test_version_mismatch_reject() {
+async fn test_responder_picks_highest_common_version() {
     let (mut t1, mut t2) = InMemoryTransport::pair();
-    
-    // Player 1 sends handshake with version 1
-    t1.send(Message::Handshake {
-        version: PROTOCOL_VERSION,
-    })
-    .await
-    .unwrap();
-    
-    let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { version } if version == PROTOCOL_VERSION));
-    
-    // Player 2 responds with incompatible version
-    let wrong_version = PROTOCOL_VERSION + 10;
-    t2.send(Message::HandshakeAck {
-        version: wrong_version,
-    })
-    .await
-    .unwrap();
-    
-    // Player 1 receives mismatched version
-    let reply = t1.recv().await.unwrap();
-    if let Message::HandshakeAck { version } = reply {
-        assert_eq!(version, wrong_version);
-        assert_ne!(version, PROTOCOL_VERSION);
-    } else {
-        panic!("Expected HandshakeAck");
+    let future = MIN_PROTOCOL_VERSION..=PROTOCOL_VERSION + 5;
+    let (theirs, ours) = tokio::join!(
+        exchange_handshake(&mut t1, Role::Initiator, &future),
+        exchange_handshake(&mut t2, Role::Responder, &SUPPORTED_VERSIONS)
+    );
+    assert_eq!(theirs.unwrap(), PROTOCOL_VERSION);
+    assert_eq!(ours.unwrap(), PROTOCOL_VERSION);
+}
+
+/// Messages as the version 1 release put them on the wire, as hex
+/// bincode. Its variants keep their indices, so a version 1 peer can still
+/// read our side of the handshake.
+const VERSION_1_WIRE: &[(&str, &str)] = &[
+    ("handshake", "0000000001"),
+    ("handshake_ack", "0100000001"),
+    ("guess", "020000000103000000000000000205"),
+    ("status_req", "03000000010400000000000000"),
+    ("ship_status_req", "060000000105000000000000000200000000000000"),
+    ("game_status_req", "08000000010600000000000000"),
+    ("ack", "0a000000010700000000000000"),
+    ("heartbeat", "0b00000001"),
+];
+
+/// The messages behind [`VERSION_1_WIRE`] as this build writes them.
+fn version_1_messages() -> Vec<(&'static str, Message)> {
+    let v = 1;
+    vec![
+        ("handshake", Message::Handshake { version: v }),
+        ("handshake_ack", Message::HandshakeAck { version: v }),
+        ("guess", Message::Guess { version: v, seq: 3, target: Coord::new(2, 5) }),
+        ("status_req", Message::StatusReq { version: v, seq: 4 }),
+        ("ship_status_req", Message::ShipStatusReq { version: v, seq: 5, id: 2 }),
+        ("game_status_req", Message::GameStatusReq { version: v, seq: 6 }),
+        ("ack", Message::Ack { version: v, seq: 7 }),
+        ("heartbeat", Message::Heartbeat { version: v }),
+    ]
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+}
+
+#[test]
+fn test_version_one_messages_keep_their_encoding() {
+    let messages = version_1_messages();
+    assert_eq!(messages.len(), VERSION_1_WIRE.len());
+    for ((name, msg), (wire_name, wire)) in messages.iter().zip(VERSION_1_WIRE) {
+        assert_eq!(name, wire_name);
+        assert_eq!(to_hex(&bincode::serialize(msg).unwrap()), *wire, "{} encodes differently", name);
+        let decoded: Message = bincode::deserialize(&from_hex(wire)).unwrap();
+        assert_eq!(to_hex(&bincode::serialize(&decoded).unwrap()), *wire, "{} decodes differently", name);
     }
 }
 
 #[tokio::test]
-async fn test_old_client_new_server() {
-    let (mut old_client, mut new_server) = InMemoryTransport::pair();
-    
-    let old_version = PROTOCOL_VERSION.saturating_sub(1);
-    
-    // Old client sends old version
-    old_client.send(Message::Handshake {
-        version: old_version,
-    })
-    .await
-    .unwrap();
-    
-    let msg = new_server.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { version } if version == old_version));
-    
-    // New server acknowledges with its version
-    new_server.send(Message::HandshakeAck {
-        version: PROTOCOL_VERSION,
-    })
-    .await
-    .unwrap();
-    
-    // Old client receives version it doesn't support
-    let reply = old_client.recv().await.unwrap();
-    if let Message::HandshakeAck { version } = reply {
-        assert_eq!(version, PROTOCOL_VERSION);
-        assert_ne!(version, old_version);
-    }
+async fn test_version_one_initiator_is_told_our_version() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let hello: Message = bincode::deserialize(&from_hex("0000000001")).unwrap();
+    t1.send(hello).await.unwrap();
+    let err = exchange_handshake(&mut t2, Role::Responder, &SUPPORTED_VERSIONS).await.unwrap_err();
+    assert!(err.to_string().contains("Protocol version mismatch"), "{}", err);
+
+    // `Error` is newer than version 1, so the refusal is a `HandshakeAck`
+    // it reads and rejects as a mismatch
+    let reply = bincode::serialize(&t1.recv().await.unwrap()).unwrap();
+    assert_eq!(to_hex(&reply), format!("01000000{:02x}", PROTOCOL_VERSION));
 }
 
 #[tokio::test]
-async fn test_new_client_old_server() {
-    let (mut new_client, mut old_server) = InMemoryTransport::pair();
-    
-    let old_version = PROTOCOL_VERSION.saturating_sub(1);
-    
-    // New client sends current version
-    new_client.send(Message::Handshake {
-        version: PROTOCOL_VERSION,
-    })
-    .await
-    .unwrap();
-    
-    let msg = old_server.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { version } if version == PROTOCOL_VERSION));
-    
-    // Old server responds with old version
-    old_server.send(Message::HandshakeAck {
-        version: old_version,
-    })
-    .await
-    .unwrap();
-    
-    // New client receives incompatible version
-    let reply = new_client.recv().await.unwrap();
-    if let Message::HandshakeAck { version } = reply {
-        assert_eq!(version, old_version);
-        assert_ne!(version, PROTOCOL_VERSION);
-    }
+async fn test_initiator_opens_in_a_form_version_one_reads() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let initiator = tokio::spawn(async move { exchange_handshake(&mut t1, Role::Initiator, &SUPPORTED_VERSIONS).await });
+    let hello = bincode::serialize(&t2.recv().await.unwrap()).unwrap();
+    assert_eq!(to_hex(&hello), format!("00000000{:02x}", PROTOCOL_VERSION));
+
+    // A version 1 responder never answers a version it does not speak
+    drop(t2);
+    assert!(initiator.await.unwrap().is_err());
 }
 
 #[tokio::test]
-async fn test_future_version() {
+async fn test_future_client_plays_at_our_newest_version() {
     let (mut t1, mut t2) = InMemoryTransport::pair();
-    
-    let future_version = PROTOCOL_VERSION + 100;
-    
-    // Future client
-    t1.send(Message::Handshake {
-        version: future_version,
+    t1.send(Message::HandshakeRange {
+        min_version: MIN_PROTOCOL_VERSION,
+        max_version: PROTOCOL_VERSION + 100,
     })
     .await
     .unwrap();
-    
-    let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { version } if version == future_version));
-    
-    // Current server rejects
-    t2.send(Message::HandshakeAck {
-        version: PROTOCOL_VERSION,
-    })
-    .await
-    .unwrap();
-    
-    let reply = t1.recv().await.unwrap();
-    if let Message::HandshakeAck { version } = reply {
-        assert_ne!(version, future_version);
-    }
+    let version = exchange_handshake(&mut t2, Role::Responder, &SUPPORTED_VERSIONS).await.unwrap();
+    assert_eq!(version, PROTOCOL_VERSION);
+    assert!(matches!(t1.recv().await.unwrap(), Message::HandshakeAck { version } if version == PROTOCOL_VERSION));
 }
 
 #[tokio::test]
-async fn test_version_zero() {
+async fn test_disjoint_versions_are_rejected() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let future = PROTOCOL_VERSION + 1..=PROTOCOL_VERSION + 10;
+    let (client, server) = tokio::join!(
+        exchange_handshake(&mut t1, Role::Initiator, &future),
+        exchange_handshake(&mut t2, Role::Responder, &SUPPORTED_VERSIONS)
+    );
+    assert!(server.unwrap_err().to_string().contains("Protocol version mismatch"));
+    let err = client.unwrap_err();
+    assert!(err.to_string().contains("VersionMismatch"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_ack_outside_offered_range_is_rejected() {
     let (mut t1, mut t2) = InMemoryTransport::pair();
-    
-    // Send version 0 (invalid)
-    t1.send(Message::Handshake { version: 0 })
-        .await
-        .unwrap();
-    
-    let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { version: 0 }));
-    
-    // Respond with current version
     t2.send(Message::HandshakeAck {
-        version: PROTOCOL_VERSION,
+        version: PROTOCOL_VERSION + 10,
     })
     .await
     .unwrap();
-    
-    let reply = t1.recv().await.unwrap();
-    if let Message::HandshakeAck { version } = reply {
-        assert_eq!(version, PROTOCOL_VERSION);
-        assert_ne!(version, 0);
-    }
+    let err = exchange_handshake(&mut t1, Role::Initiator, &SUPPORTED_VERSIONS).await.unwrap_err();
+    assert!(err.to_string().contains("Protocol version mismatch in HandshakeAck"));
+    assert!(matches!(t2.recv().await.unwrap(), Message::Handshake { .. } | Message::HandshakeRange { .. }));
 }
 
-#[tokio::test(flavor = "multi_thread")]
-async fn test_cross_version_tcp() -> anyhow::Result<()> {
-    let listener = TcpListener::bind("127.0.0.1:0").await?;
-    let addr = listener.local_addr()?;
-    
-    let old_version = PROTOCOL_VERSION.saturating_sub(1);
-    
-    let server_task = tokio::spawn(async move {
-        let (socket, _) = listener.accept().await.unwrap();
-        let mut transport = TcpTransport::new(socket);
-        
-        // Server with current version
-        let msg = transport.recv().await.unwrap();
-        assert!(matches!(msg, Message::Handshake { .. }));
-        
-        transport.send(Message::HandshakeAck {
-            version: PROTOCOL_VERSION,
-        })
-        .await
-        .unwrap();
-    });
-    
-    let stream = TcpTransport::connect(addr).await?;
-    let mut transport = stream;
-    
-    // Client with old version
-    transport.send(Message::Handshake {
-        version: old_version,
-    })
-    .await?;
-    
-    let reply = transport.recv().await?;
-    if let Message::HandshakeAck { version } = reply {
-        assert_eq!(version, PROTOCOL_VERSION);
-        assert_ne!(version, old_version);
+/// A transport that copies every message it sends to `sent`.
+struct Recording<T> {
+    inner: T,
+    sent: mpsc::UnboundedSender<Message>,
+}
+
+#[async_trait::async_trait]
+impl<T: Transport> Transport for Recording<T> {
+    async fn send(&mut self, msg: Message) -> anyhow::Result<()> {
+        let _ = self.sent.send(msg.clone());
+        self.inner.send(msg).await
+    }
+
+    async fn recv(&mut self) -> anyhow::Result<Message> {
+        self.inner.recv().await
     }
-    
-    server_task.await?;
-    Ok(())
 }
 
-#[tokio::test(flavor = "multi_thread")]
-async fn test_multiple_version_handshakes() -> anyhow::Result<()> {
-    let listener = TcpListener::bind("127.0.0.1:0").await?;
-    let addr = listener.local_addr()?;
-    
-    let server_task = tokio::spawn(async move {
-        let (socket, _) = listener.accept().await.unwrap();
-        let mut transport = TcpTransport::new(socket);
-        
-        // Receive multiple handshakes
-        for _ in 0..3 {
-            let msg = transport.recv().await.unwrap();
-            if let Message::Handshake { version: _ } = msg {
-                // Always respond with current version
-                transport.send(Message::HandshakeAck {
-                    version: PROTOCOL_VERSION,
-                })
-                .await
-                .unwrap();
+#[tokio::test]
+async fn test_layout_commitments_carry_the_agreed_version() {
+    let agreed = PROTOCOL_VERSION;
+    let (t1, t2) = InMemoryTransport::pair();
+    let (sent, mut log) = mpsc::unbounded_channel();
+    let mut client = node(1, Recording { inner: t1, sent });
+    let mut server = node(2, t2).with_protocol_versions(MIN_PROTOCOL_VERSION, PROTOCOL_VERSION + 5);
+    play(&mut client, &mut server).await;
+    for node in [&client, &server] {
+        assert_eq!(node.protocol_version(), agreed);
+        assert!(node.opponent_fleet().is_some());
+    }
+
+    let mut reports = 0;
+    while let Ok(msg) = log.try_recv() {
+        match msg {
+            Message::Commit { version, .. } | Message::Reveal { version, .. } | Message::GameOver { version, .. } => {
+                assert_eq!(version, agreed, "{:?}", msg);
+                reports += 1;
             }
-        }
-    });
-    
-    let stream = TcpTransport::connect(addr).await?;
-    let mut transport = stream;
-    
-    // Try handshake with different versions
-    for offset in 0..3 {
-        let version = PROTOCOL_VERSION.saturating_sub(offset);
-        transport.send(Message::Handshake { version }).await?;
-        
-        let reply = transport.recv().await?;
-        if let Message::HandshakeAck { version: ack_version } = reply {
-            assert_eq!(ack_version, PROTOCOL_VERSION);
+            _ => {}
         }
     }
-    
-    server_task.await?;
-    Ok(())
+    assert_eq!(reports, 3);
 }
 
 #[tokio::test]
-async fn test_version_in_guess_message() {
-    let (mut t1, mut t2) = InMemoryTransport::pair();
-    
-    // Complete handshake
-    t1.send(Message::Handshake {
-        version: PROTOCOL_VERSION,
-    })
-    .await
-    .unwrap();
-    t2.recv().await.unwrap();
-    
-    t2.send(Message::HandshakeAck {
-        version: PROTOCOL_VERSION,
-    })
-    .await
-    .unwrap();
-    t1.recv().await.unwrap();
-    
-    // Send guess with wrong version
-    let wrong_version = PROTOCOL_VERSION + 5;
-    t1.send(Message::Guess {
-        version: wrong_version,
-        seq: 0,
-        x: 0,
-        y: 0,
-    })
-    .await
-    .unwrap();
-    
-    let msg = t2.recv().await.unwrap();
-    if let Message::Guess { version, .. } = msg {
-        assert_eq!(version, wrong_version);
-        assert_ne!(version, PROTOCOL_VERSION);
+async fn test_compatible_versions_play_at_newest() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut client = node(1, t1);
+    let mut server = node(2, t2).with_protocol_versions(MIN_PROTOCOL_VERSION, PROTOCOL_VERSION + 5);
+    play(&mut client, &mut server).await;
+    for node in [&client, &server] {
+        assert_eq!(node.protocol_version(), PROTOCOL_VERSION);
+        assert!(node.opponent_fleet().is_some());
     }
 }
 
-#[tokio::test]
-async fn test_compatible_versions() {
-    let (mut t1, mut t2) = InMemoryTransport::pair();
-    
-    // Both use same version
-    t1.send(Message::Handshake {
-        version: PROTOCOL_VERSION,
-    })
-    .await
-    .unwrap();
-    
-    let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { version } if version == PROTOCOL_VERSION));
-    
-    t2.send(Message::HandshakeAck {
-        version: PROTOCOL_VERSION,
-    })
-    .await
-    .unwrap();
-    
-    let reply = t1.recv().await.unwrap();
-    if let Message::HandshakeAck { version } = reply {
-        assert_eq!(version, PROTOCOL_VERSION);
-    }
-    
-    // Now they should be able to exchange game messages
-    t1.send(Message::Guess {
-        version: PROTOCOL_VERSION,
-        seq: 0,
-        x: 5,
-        y: 5,
-    })
-    .await
-    .unwrap();
-    
-    let guess_msg = t2.recv().await.unwrap();
-    assert!(matches!(guess_msg, Message::Guess { version, seq: 0, x: 5, y: 5 } if version == PROTOCOL_VERSION));
+#[tokio::test(flavor = "multi_thread")]
+async fn test_cross_version_tcp() -> anyhow::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let accept = tokio::spawn(async move {
+        let (socket, _) = listener.accept().await?;
+        Ok::<TcpTransport, anyhow::Error>(TcpTransport::new(socket))
+    });
+    let client = TcpTransport::connect(addr).await?;
+    let server = accept.await??;
+
+    let mut old_client = node(1, client).with_protocol_versions(MIN_PROTOCOL_VERSION, MIN_PROTOCOL_VERSION);
+    let mut new_server = node(2, server);
+    play(&mut old_client, &mut new_server).await;
+    assert_eq!(new_server.protocol_version(), MIN_PROTOCOL_VERSION);
+    Ok(())
 }
 
 #[tokio::test]
-async fn test_version_negotiation_rejection() {
-    let (mut t1, mut t2) = InMemoryTransport::pair();
-
-    // Attempt negotiation with mismatched versions
-    let old_version = 0;
-    t1.send(Message::Handshake { version: old_version }).await.unwrap();
-    let msg = t2.recv().await.unwrap();
+async fn test_skeleton_stamps_the_agreed_version() {
+    let (mut client, server) = InMemoryTransport::pair();
+    let mut engine = GameEngine::new();
+    AiPlayer::new()
+        .place_ships(&mut SmallRng::seed_from_u64(1), engine.board_mut())
+        .unwrap();
+    let server = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(engine, server);
+        skeleton.run().await.unwrap();
+        skeleton.protocol_version()
+    });
 
-    if let Message::Handshake { version: client_version } = msg {
-        // Server sees old version, responds with current version
-        assert_eq!(client_version, old_version);
+    client
+        .send(Message::HandshakeRange {
+            min_version: 0,
+            max_version: MIN_PROTOCOL_VERSION,
+        })
+        .await
+        .unwrap();
+    assert!(matches!(client.recv().await.unwrap(), Message::HandshakeAck { version } if version == MIN_PROTOCOL_VERSION));
 
-        // Send rejection by indicating supported version
-        t2.send(Message::HandshakeAck { version: PROTOCOL_VERSION })
-            .await
-            .unwrap();
+    // A request at another version is only acknowledged
+    client
+        .send(Message::Guess {
+            version: PROTOCOL_VERSION + 1,
+            seq: 0,
+            target: Coord::new(0, 0),
+        })
+        .await
+        .unwrap();
+    assert!(matches!(client.recv().await.unwrap(), Message::Ack { seq: 0, .. }));
 
-        let reply = t1.recv().await.unwrap();
-        if let Message::HandshakeAck { version: server_version } = reply {
-            // Client sees server doesn't support its version
-            assert_ne!(client_version, server_version);
-            assert_eq!(server_version, PROTOCOL_VERSION);
+    client
+        .send(Message::Guess {
+            version: MIN_PROTOCOL_VERSION,
+            seq: 0,
+            target: Coord::new(0, 0),
+        })
+        .await
+        .unwrap();
+    match client.recv().await.unwrap() {
+        Message::StatusResp { version, seq, .. } => {
+            assert_eq!(version, MIN_PROTOCOL_VERSION);
+            assert_eq!(seq, 0);
         }
+        other => panic!("Expected StatusResp, got {:?}", other),
+    }
+    drop(client);
+    assert_eq!(server.await.unwrap(), MIN_PROTOCOL_VERSION);
+}
+
+#[tokio::test]
+async fn test_skeleton_rejects_disjoint_versions() {
+    let (mut client, server) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(GameEngine::new(), server);
+        skeleton.run().await
+    });
+    client
+        .send(Message::Handshake {
+            version: PROTOCOL_VERSION + 1,
+        })
+        .await
+        .unwrap();
+    match client.recv().await.unwrap() {
+        Message::Error { code, .. } => assert_eq!(code, ErrorCode::VersionMismatch),
+        other => panic!("Expected Error, got {:?}", other),
     }
+    assert!(server.await.unwrap().is_err());
+}
+
+#[tokio::test]
+async fn test_skeleton_tells_a_version_one_client_our_version() {
+    let (mut client, server) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(GameEngine::new(), server);
+        skeleton.run().await
+    });
+    client.send(Message::Handshake { version: 1 }).await.unwrap();
+    assert!(matches!(client.recv().await.unwrap(), Message::HandshakeAck { version } if version == PROTOCOL_VERSION));
+    assert!(server.await.unwrap().is_err());
 }
@@ -0,0 +1,261 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    daemon::{self, DaemonConfig, MatchOutcome, MatchRecord, Matchmaker},
+    domain::{GameConfig, GuessResult},
+    transport::{in_memory::InMemoryTransport, tcp::TcpTransport, Transport},
+    AiPlayer, Board, BoardState, Coord, FleetConfig, GameEngine, GameStatus, HeartbeatTransport, Message, PlayerNode,
+    Role, RuleSet, Stats, PROTOCOL_VERSION, SHIPS,
+};
+use rand::{rngs::SmallRng, SeedableRng};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout, Duration};
+
+async fn next_record(rx: &mut mpsc::UnboundedReceiver<MatchRecord>) -> MatchRecord {
+    timeout(Duration::from_secs(10), rx.recv())
+        .await
+        .expect("match result within timeout")
+        .expect("results channel open")
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn ai_game_through_daemon_records_winner() -> anyhow::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tokio::spawn(daemon::serve(listener, DaemonConfig::default(), tx));
+
+    let client = |seed: u8| async move {
+        let tcp = TcpTransport::connect(addr).await?;
+        let mut transport = HeartbeatTransport::new(tcp, Duration::from_secs(10), Duration::from_secs(45));
+        let role = daemon::wait_for_opponent(&mut transport).await?;
+        let mut rng = SmallRng::from_seed([seed; 32]);
+        let mut node = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(transport));
+        node.run(&mut rng, role).await?;
+        anyhow::Ok((role, node.status(), node.guess_count()))
+    };
+    let (a, b) = tokio::try_join!(client(1), client(2))?;
+
+    let (initiator, responder) = if a.0 == Role::Initiator { (a, b) } else { (b, a) };
+    assert_eq!(responder.0, Role::Responder);
+    let record = next_record(&mut rx).await;
+    let winner = if initiator.1 == GameStatus::Won { 0 } else { 1 };
+    assert_eq!(record.outcome, MatchOutcome::Finished { winner });
+    assert_eq!(record.shots, [initiator.2 as u32, responder.2 as u32]);
+    assert_eq!(record.id, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn matchmaker_pairs_in_arrival_order() -> anyhow::Result<()> {
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut matchmaker = Matchmaker::new(tx);
+
+    let (mut first, first_server) = InMemoryTransport::pair();
+    let (mut second, second_server) = InMemoryTransport::pair();
+    matchmaker.enqueue("first", first_server).await;
+    assert_eq!(matchmaker.waiting(), 1);
+    matchmaker.enqueue("second", second_server).await;
+    assert_eq!(matchmaker.waiting(), 0);
+
+    assert_eq!(daemon::wait_for_opponent(&mut first).await?, Role::Initiator);
+    assert_eq!(daemon::wait_for_opponent(&mut second).await?, Role::Responder);
+    Ok(())
+}
+
+#[tokio::test]
+async fn player_leaving_queue_is_skipped() -> anyhow::Result<()> {
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut matchmaker = Matchmaker::new(tx);
+
+    let (gone, gone_server) = InMemoryTransport::pair();
+    matchmaker.enqueue("gone", gone_server).await;
+    drop(gone);
+    for _ in 0..100 {
+        if matchmaker.waiting() == 0 {
+            break;
+        }
+        sleep(Duration::from_millis(10)).await;
+    }
+    assert_eq!(matchmaker.waiting(), 0);
+
+    let (mut second, second_server) = InMemoryTransport::pair();
+    let (mut third, third_server) = InMemoryTransport::pair();
+    matchmaker.enqueue("second", second_server).await;
+    matchmaker.enqueue("third", third_server).await;
+    assert_eq!(daemon::wait_for_opponent(&mut second).await?, Role::Initiator);
+    assert_eq!(daemon::wait_for_opponent(&mut third).await?, Role::Responder);
+    Ok(())
+}
+
+#[tokio::test]
+async fn heartbeats_are_not_relayed() -> anyhow::Result<()> {
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let mut matchmaker = Matchmaker::new(tx);
+    let (mut a, a_server) = InMemoryTransport::pair();
+    let (mut b, b_server) = InMemoryTransport::pair();
+    matchmaker.enqueue("a", a_server).await;
+    matchmaker.enqueue("b", b_server).await;
+    daemon::wait_for_opponent(&mut a).await?;
+    daemon::wait_for_opponent(&mut b).await?;
+
+    a.send(Message::Heartbeat { version: PROTOCOL_VERSION }).await?;
+    a.send(Message::Handshake { version: PROTOCOL_VERSION }).await?;
+    match timeout(Duration::from_secs(5), b.recv()).await?? {
+        Message::Handshake { version } => assert_eq!(version, PROTOCOL_VERSION),
+        other => panic!("Expected relayed Handshake, got {:?}", other),
+    }
+    Ok(())
+}
+
+type RelayedGame = (InMemoryTransport, InMemoryTransport, JoinHandle<(MatchOutcome, [u32; 2])>);
+
+/// Start a relayed game between `a` (the initiator) and `b` on the rules in
+/// `start`.
+async fn start_relayed_game(start: Message) -> anyhow::Result<RelayedGame> {
+    let (mut a, mut relay_a) = InMemoryTransport::pair();
+    let (mut b, mut relay_b) = InMemoryTransport::pair();
+    let relay = tokio::spawn(async move { daemon::relay(&mut relay_a, &mut relay_b).await });
+    a.send(start).await?;
+    b.recv().await?;
+    Ok((a, b, relay))
+}
+
+/// `shooter` fires at `target` and `defender` answers that it missed.
+async fn exchange_miss(
+    shooter: &mut InMemoryTransport,
+    defender: &mut InMemoryTransport,
+    seq: u64,
+    target: Coord,
+) -> anyhow::Result<()> {
+    shooter.send(Message::Guess { version: PROTOCOL_VERSION, seq, target }).await?;
+    defender.recv().await?;
+    let res = GuessResult::Miss;
+    defender.send(Message::StatusResp { version: PROTOCOL_VERSION, seq, res, digest: None }).await?;
+    shooter.recv().await?;
+    Ok(())
+}
+
+fn standard_start() -> Message {
+    let config = GameConfig::local();
+    Message::StartGame { version: PROTOCOL_VERSION, config, first_mover: Role::Initiator }
+}
+
+#[tokio::test]
+async fn test_salvo_shots_and_sinks_are_counted() -> anyhow::Result<()> {
+    let (mut a, mut b, relay) = start_relayed_game(standard_start()).await?;
+    let shots = (0..SHIPS.len() as u8).map(|row| (row, 0)).collect();
+    a.send(Message::GuessSalvo { version: PROTOCOL_VERSION, seq: 0, shots }).await?;
+    b.recv().await?;
+    let results = SHIPS.iter().map(|def| GuessResult::Sink(def.name().to_string())).collect();
+    b.send(Message::SalvoResp { version: PROTOCOL_VERSION, seq: 0, results, digest: None }).await?;
+    a.recv().await?;
+    // Leaving once the game is decided ends the relay
+    drop((a, b));
+
+    let (outcome, shots) = timeout(Duration::from_secs(5), relay).await??;
+    assert_eq!(outcome, MatchOutcome::Finished { winner: 0 });
+    assert_eq!(shots, [SHIPS.len() as u32, 0]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_relay_takes_the_fleet_size_from_start_game() -> anyhow::Result<()> {
+    let fleet = FleetConfig::empty().with_ships("Frigate", 3, 2)?;
+    let config = GameConfig::from(&RuleSet::new(8, fleet)?);
+    let start = Message::StartGame { version: PROTOCOL_VERSION, config, first_mover: Role::Initiator };
+    let (mut a, mut b, relay) = start_relayed_game(start).await?;
+    for seq in 0..2 {
+        a.send(Message::Guess { version: PROTOCOL_VERSION, seq, target: Coord::new(seq as usize, 0) }).await?;
+        b.recv().await?;
+        let res = GuessResult::Sink("Frigate".to_string());
+        b.send(Message::StatusResp { version: PROTOCOL_VERSION, seq, res, digest: None }).await?;
+        a.recv().await?;
+    }
+    // Leaving once the game is decided ends the relay
+    drop((a, b));
+
+    let (outcome, shots) = timeout(Duration::from_secs(5), relay).await??;
+    assert_eq!(outcome, MatchOutcome::Finished { winner: 0 });
+    assert_eq!(shots, [2, 0]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_resignation_hands_the_other_player_the_win() -> anyhow::Result<()> {
+    let (mut a, mut b, relay) = start_relayed_game(standard_start()).await?;
+    b.send(Message::Resign { version: PROTOCOL_VERSION, seq: 0 }).await?;
+    a.recv().await?;
+    // Leaving once the game is decided ends the relay
+    drop((a, b));
+    let (outcome, _) = timeout(Duration::from_secs(5), relay).await??;
+    assert_eq!(outcome, MatchOutcome::Finished { winner: 0 });
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_crossed_resignations_are_a_draw() -> anyhow::Result<()> {
+    // Both reports are due, so neither resignation ends the relay before
+    // the other is seen
+    let start = Message::StartGame { version: PROTOCOL_VERSION, config: GameConfig::local(), first_mover: Role::Initiator };
+    let (mut a, mut b, relay) = start_relayed_game(start).await?;
+    a.send(Message::Resign { version: PROTOCOL_VERSION, seq: 0 }).await?;
+    b.send(Message::Resign { version: PROTOCOL_VERSION, seq: 0 }).await?;
+    a.recv().await?;
+    b.recv().await?;
+    for player in [&mut a, &mut b] {
+        let final_board = BoardState::from(&Board::default());
+        player.send(Message::GameOver { version: PROTOCOL_VERSION, final_board, stats: Stats::default() }).await?;
+    }
+    let (outcome, _) = timeout(Duration::from_secs(5), relay).await??;
+    assert_eq!(outcome, MatchOutcome::Drawn);
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_move_limit_is_a_draw() -> anyhow::Result<()> {
+    let mut config = GameConfig::local();
+    config.move_limit = Some(2);
+    let start = Message::StartGame {
+        version: PROTOCOL_VERSION,
+        config,
+        first_mover: Role::Initiator,
+    };
+    let (mut a, mut b, relay) = start_relayed_game(start).await?;
+    // The same cell twice only counts once towards the limit
+    for (seq, col) in [0, 0, 1].into_iter().enumerate() {
+        exchange_miss(&mut a, &mut b, seq as u64, Coord::new(0, col)).await?;
+        exchange_miss(&mut b, &mut a, seq as u64, Coord::new(0, col)).await?;
+    }
+    // Leaving once the game is decided ends the relay
+    drop((a, b));
+    let (outcome, shots) = timeout(Duration::from_secs(5), relay).await??;
+    assert_eq!(outcome, MatchOutcome::Drawn);
+    assert_eq!(shots, [3, 3]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn disconnect_mid_game_is_recorded_as_abandoned() -> anyhow::Result<()> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut matchmaker = Matchmaker::new(tx);
+    let (mut a, a_server) = InMemoryTransport::pair();
+    let (mut b, b_server) = InMemoryTransport::pair();
+    matchmaker.enqueue("a", a_server).await;
+    matchmaker.enqueue("b", b_server).await;
+    daemon::wait_for_opponent(&mut a).await?;
+    daemon::wait_for_opponent(&mut b).await?;
+
+    a.send(Message::Guess { version: PROTOCOL_VERSION, seq: 0, target: Coord::new(1, 2) }).await?;
+    b.recv().await?;
+    drop(b);
+
+    let record = next_record(&mut rx).await;
+    assert_eq!(record.players, ["a".to_string(), "b".to_string()]);
+    assert_eq!(record.shots, [1, 0]);
+    assert!(matches!(record.outcome, MatchOutcome::Abandoned { player: 1, .. }));
+    drop(a);
+    Ok(())
+}
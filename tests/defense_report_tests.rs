@@ -0,0 +1,59 @@
+use battleship::{GameEngine, SHIPS};
+
+mod common;
+
+#[test]
+fn test_fresh_engine_reports_no_fire() {
+    let report = common::stacked_engine().defense_report();
+    assert_eq!(report.shots_received, 0);
+    assert_eq!(report.hits_received, 0);
+    assert_eq!(report.opponent_accuracy_percent(), 0);
+    assert_eq!(report.first_ship_lost(), None);
+    assert!(report.ships.iter().all(|s| s.first_hit.is_none() && s.turns_survived == 0));
+}
+
+#[test]
+fn test_tracks_hits_and_sink_turns_per_ship() {
+    let mut engine = common::stacked_engine();
+    engine.opponent_guess(9, 9).unwrap(); // turn 1: miss
+    engine.opponent_guess(2, 0).unwrap(); // turn 2: first hit on ship 2
+    let last = SHIPS[1].length();
+    for c in 0..last {
+        engine.opponent_guess(1, c).unwrap(); // turns 3.. sink ship 1
+    }
+    let sunk_turn = 2 + last;
+    engine.opponent_guess(8, 9).unwrap();
+
+    let report = engine.defense_report();
+    assert_eq!(report.shots_received, sunk_turn + 1);
+    assert_eq!(report.hits_received, last + 1);
+    assert_eq!(report.ships[2].hits, 1);
+    assert_eq!(report.ships[2].first_hit, Some(2));
+    assert_eq!(report.ships[2].sunk_at, None);
+    assert_eq!(report.ships[2].turns_survived, sunk_turn + 1);
+    assert_eq!(report.ships[1].first_hit, Some(3));
+    assert_eq!(report.ships[1].sunk_at, Some(sunk_turn));
+    assert_eq!(report.ships[1].turns_survived, sunk_turn);
+    assert_eq!(report.first_ship_lost(), Some(1));
+}
+
+#[test]
+fn test_opponent_accuracy_rounds_to_percent() {
+    let mut engine = common::stacked_engine();
+    engine.opponent_guess(0, 0).unwrap();
+    engine.opponent_guess(9, 9).unwrap();
+    engine.opponent_guess(9, 8).unwrap();
+    assert_eq!(engine.defense_report().opponent_accuracy_percent(), 33);
+}
+
+#[test]
+fn test_restored_engine_counts_earlier_shots() {
+    let mut engine = common::stacked_engine();
+    engine.opponent_guess(0, 0).unwrap();
+    engine.opponent_guess(9, 9).unwrap();
+    let restored = GameEngine::from_state(engine.state());
+    let report = restored.defense_report();
+    assert_eq!(report.shots_received, 2);
+    assert_eq!(report.hits_received, 1);
+    assert_eq!(report.ships[0].first_hit, None);
+}
@@ -0,0 +1,63 @@
+#![cfg(feature = "std")]
+
+use battleship::{AiPlayer, GameEngine, GameStatus, GuessResult, Orientation, Player};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+#[test]
+fn test_digest_is_stable_and_ignores_ship_positions() {
+    let empty = GameEngine::new().state();
+    assert_eq!(empty.digest(), 0xb9b2_3f3a_46fd_0825);
+    assert_eq!(empty.digest(), empty.mirrored_digest());
+
+    let mut a = GameEngine::new();
+    let mut b = GameEngine::new();
+    a.board_mut().place(0, 0, 0, Orientation::Horizontal).unwrap();
+    b.board_mut().place(0, 5, 5, Orientation::Vertical).unwrap();
+    assert_eq!(a.state().digest(), b.state().digest());
+
+    a.opponent_guess(9, 9).unwrap();
+    assert_ne!(a.state().digest(), b.state().digest());
+    b.opponent_guess(9, 9).unwrap();
+    assert_eq!(a.state().digest(), b.state().digest());
+
+    // A shot we fired is not the same as a shot we took
+    let mut fired = GameEngine::new();
+    fired.record_guess(9, 9, GuessResult::Miss).unwrap();
+    assert_ne!(fired.state().digest(), a.state().digest());
+    assert_eq!(fired.state().mirrored_digest(), a.state().digest());
+}
+
+#[test]
+fn test_honest_peers_agree_after_every_shot() {
+    let mut rng = SmallRng::seed_from_u64(3508);
+    let mut players = [AiPlayer::new(), AiPlayer::new()];
+    let mut engines = [GameEngine::new(), GameEngine::new()];
+    for (player, engine) in players.iter_mut().zip(engines.iter_mut()) {
+        player.place_ships(&mut rng, engine.board_mut()).unwrap();
+    }
+
+    let mut shooter = 0;
+    while engines[shooter].status() == GameStatus::InProgress {
+        let defender = 1 - shooter;
+        let target = players[shooter].select_target(
+            &mut rng,
+            &engines[shooter].guess_hits(),
+            &engines[shooter].guess_misses(),
+            &engines[shooter].enemy_ship_lengths_remaining(),
+        );
+        let res = engines[defender].opponent_guess(target.row, target.col).unwrap();
+        let sent = engines[defender].state().digest();
+        engines[shooter].record_guess(target.row, target.col, res).unwrap();
+        assert_eq!(sent, engines[shooter].state().mirrored_digest());
+        shooter = defender;
+    }
+
+    // A misreported result shows up in the digest
+    let mut liar = GameEngine::new();
+    let mut victim = GameEngine::new();
+    liar.board_mut().place(4, 0, 0, Orientation::Horizontal).unwrap();
+    assert_eq!(liar.opponent_guess(0, 0).unwrap(), GuessResult::Hit);
+    victim.record_guess(0, 0, GuessResult::Miss).unwrap();
+    assert_ne!(liar.state().digest(), victim.state().mirrored_digest());
+}
@@ -2,7 +2,7 @@ use battleship::transport::tcp::TcpTransport;
 use battleship::transport::Transport;
 use battleship::protocol::{Message, PROTOCOL_VERSION, GameApi};
 use battleship::domain::{GuessResult, GameStatus, Ship, SyncPayload};
-use battleship::{GameEngine, Skeleton, Stub};
+use battleship::{Coord, GameEngine, Skeleton, Stub};
 use tokio::net::TcpListener;
 use tokio::time::{sleep, Duration};
 use std::sync::Arc;
@@ -22,9 +22,9 @@ impl TestEngine {
 
 #[async_trait::async_trait]
 impl GameApi for TestEngine {
-    async fn make_guess(&mut self, x: u8, y: u8) -> anyhow::Result<GuessResult> {
+    async fn make_guess(&mut self, target: Coord) -> anyhow::Result<GuessResult> {
         let mut eng = self.engine.lock().await;
-        let res = eng.opponent_guess(x as usize, y as usize)
+        let res = eng.opponent_guess(target.row, target.col)
             .map_err(|e| anyhow::anyhow!("{:?}", e))?;
         Ok(GuessResult::from(res))
     }
@@ -57,7 +57,7 @@ async fn test_disconnect_reconnect_basic() -> anyhow::Result<()> {
     // Place ships
     use rand::{rngs::SmallRng, SeedableRng};
     let mut rng = SmallRng::seed_from_u64(42);
-    for i in 0..battleship::NUM_SHIPS as usize {
+    for i in 0..battleship::NUM_SHIPS {
         let (r, c, o) = engine1.board_mut().random_placement(&mut rng, i)
             .map_err(|e| anyhow::anyhow!("{:?}", e))?;
         engine1.board_mut().place(i, r, c, o)
@@ -73,11 +73,19 @@ async fn test_disconnect_reconnect_basic() -> anyhow::Result<()> {
     
     // Start server
     let server_engine = TestEngine::new(engine2);
+    let shared_engine = server_engine.engine.clone();
     let server_task = tokio::spawn(async move {
-        let (socket, _) = listener.accept().await.unwrap();
-        let transport = TcpTransport::new(socket);
-        let mut skeleton = Skeleton::new(server_engine, transport);
-        skeleton.run().await
+        // Serve the original connection and the reconnection against the same engine
+        for _ in 0..2 {
+            let (socket, _) = listener.accept().await.unwrap();
+            let transport = TcpTransport::new(socket);
+            let engine = TestEngine {
+                engine: shared_engine.clone(),
+            };
+            let mut skeleton = Skeleton::new(engine, transport);
+            skeleton.run().await?;
+        }
+        anyhow::Ok(())
     });
     
     // Connect client
@@ -85,7 +93,7 @@ async fn test_disconnect_reconnect_basic() -> anyhow::Result<()> {
     let mut stub = Stub::new(stream);
     
     // Make a guess
-    let res = stub.make_guess(0, 0).await?;
+    let res = stub.make_guess(Coord::new(0, 0)).await?;
     assert!(matches!(res, GuessResult::Hit | GuessResult::Miss | GuessResult::Sink(_)));
     
     // Disconnect (drop the stub)
@@ -101,12 +109,12 @@ async fn test_disconnect_reconnect_basic() -> anyhow::Result<()> {
     // Sync state
     let sync_payload = SyncPayload {
         game_state: state1,
-        enemy_ships_remaining: [true; 5],
+        enemy_ships_remaining: GameEngine::new().enemy_ships_remaining(),
     };
     stub2.sync_state(sync_payload).await?;
     
     // Make another guess after reconnect
-    let res2 = stub2.make_guess(1, 1).await?;
+    let res2 = stub2.make_guess(Coord::new(1, 1)).await?;
     assert!(matches!(res2, GuessResult::Hit | GuessResult::Miss | GuessResult::Sink(_)));
     
     drop(stub2);
@@ -162,7 +170,7 @@ async fn test_reconnect_with_state_sync() -> anyhow::Result<()> {
     let mut engine = GameEngine::new();
     use rand::{rngs::SmallRng, SeedableRng};
     let mut rng = SmallRng::seed_from_u64(123);
-    for i in 0..battleship::NUM_SHIPS as usize {
+    for i in 0..battleship::NUM_SHIPS {
         let (r, c, o) = engine.board_mut().random_placement(&mut rng, i)
             .map_err(|e| anyhow::anyhow!("{:?}", e))?;
         engine.board_mut().place(i, r, c, o)
@@ -188,7 +196,7 @@ async fn test_reconnect_with_state_sync() -> anyhow::Result<()> {
     let mut stub = Stub::new(stream);
     
     // Make a guess
-    let _ = stub.make_guess(2, 2).await;
+    let _ = stub.make_guess(Coord::new(2, 2)).await;
     
     // Disconnect
     drop(stub);
@@ -200,7 +208,7 @@ async fn test_reconnect_with_state_sync() -> anyhow::Result<()> {
     let addr2 = listener2.local_addr()?;
     
     let mut engine2 = GameEngine::new();
-    for i in 0..battleship::NUM_SHIPS as usize {
+    for i in 0..battleship::NUM_SHIPS {
         let (r, c, o) = engine2.board_mut().random_placement(&mut rng, i)
             .map_err(|e| anyhow::anyhow!("{:?}", e))?;
         engine2.board_mut().place(i, r, c, o)
@@ -221,12 +229,12 @@ async fn test_reconnect_with_state_sync() -> anyhow::Result<()> {
     // Sync state from before disconnect
     let sync_payload = SyncPayload {
         game_state: state_before_disconnect,
-        enemy_ships_remaining: [true; 5],
+        enemy_ships_remaining: GameEngine::new().enemy_ships_remaining(),
     };
     stub2.sync_state(sync_payload).await?;
     
     // Verify we can continue playing
-    let res = stub2.make_guess(3, 3).await?;
+    let res = stub2.make_guess(Coord::new(3, 3)).await?;
     assert!(matches!(res, GuessResult::Hit | GuessResult::Miss | GuessResult::Sink(_)));
     
     drop(stub2);
@@ -280,7 +288,7 @@ async fn test_multiple_disconnect_reconnect_cycles() -> anyhow::Result<()> {
         
         let mut rng = SmallRng::seed_from_u64(cycle);
         let mut engine = GameEngine::new();
-        for i in 0..battleship::NUM_SHIPS as usize {
+        for i in 0..battleship::NUM_SHIPS {
             let (r, c, o) = engine.board_mut().random_placement(&mut rng, i)
                 .map_err(|e| anyhow::anyhow!("{:?}", e))?;
             engine.board_mut().place(i, r, c, o)
@@ -299,7 +307,7 @@ async fn test_multiple_disconnect_reconnect_cycles() -> anyhow::Result<()> {
         let mut stub = Stub::new(stream);
         
         // Make a guess
-        let _ = stub.make_guess(cycle as u8, cycle as u8).await;
+        let _ = stub.make_guess(Coord::new(cycle as usize, cycle as usize)).await;
         
         // Disconnect
         drop(stub);
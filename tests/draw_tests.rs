@@ -0,0 +1,226 @@
+#![cfg(feature = "std")]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use battleship::domain::{self, GameConfig};
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{
+    AiPlayer, BoardBits, Board, BoardError, Coord, GameApi, GameEngine, GameEvent, GameStatus, GuessResult, Orientation, Player,
+    PlayerNode, Role, RuleSet, TimeoutAction, TurnClock, NUM_SHIPS,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+type BB = BoardBits;
+
+fn capped(limit: u16) -> GameEngine {
+    let mut engine = GameEngine::builder().move_limit(Some(limit)).build().unwrap();
+    for (i, row) in (0..NUM_SHIPS).zip([0, 2, 4, 6, 8]) {
+        engine.board_mut().place(i, row, 0, Orientation::Horizontal).unwrap();
+    }
+    engine
+}
+
+#[test]
+fn test_move_limit_draws_once_both_sides_have_fired() {
+    let mut engine = capped(3);
+    for c in 0..3 {
+        engine.record_guess(9, c, GuessResult::Miss).unwrap();
+        assert_eq!(engine.status(), GameStatus::InProgress);
+        engine.opponent_guess(9, c).unwrap();
+    }
+    assert_eq!(engine.status(), GameStatus::Draw);
+    assert!(matches!(GameApi::status(&engine), domain::GameStatus::Draw));
+
+    // Repeated shots do not count towards the limit
+    let mut engine = capped(2);
+    engine.record_guess(9, 0, GuessResult::Miss).unwrap();
+    engine.record_guess(9, 1, GuessResult::Miss).unwrap();
+    engine.opponent_guess(9, 0).unwrap();
+    assert!(engine.opponent_guess(9, 0).is_err());
+    assert_eq!(engine.status(), GameStatus::InProgress);
+    engine.opponent_guess(9, 1).unwrap();
+    assert_eq!(engine.status(), GameStatus::Draw);
+
+    // Sinking the last ship on the final shot still wins
+    let fleet = battleship::FleetConfig::empty().with_ships("Destroyer", 2, 1).unwrap();
+    let mut engine = GameEngine::new_with_config(RuleSet::new(4, fleet).unwrap().with_move_limit(Some(2)));
+    engine.board_mut().place(0, 3, 0, Orientation::Horizontal).unwrap();
+    engine.opponent_guess(0, 0).unwrap();
+    engine.opponent_guess(0, 1).unwrap();
+    engine.record_guess(1, 1, GuessResult::Hit).unwrap();
+    engine.record_guess(1, 2, GuessResult::Sink("Destroyer")).unwrap();
+    assert_eq!(engine.status(), GameStatus::Won);
+}
+
+#[test]
+fn test_move_limit_survives_config_and_json() {
+    let rules = RuleSet::STANDARD.with_move_limit(Some(60));
+    assert_eq!(RuleSet::STANDARD.move_limit(), None);
+    assert_eq!(rules.with_fleet(battleship::FleetConfig::STANDARD).unwrap(), rules);
+    assert_eq!(GameConfig::from(&rules).rules(), Some(rules));
+
+    let engine = GameEngine::builder().move_limit(Some(60)).build().unwrap();
+    assert_eq!(engine.move_limit(), Some(60));
+    let json = engine.to_json().unwrap();
+    assert!(json.contains("\"move_limit\":60"));
+    assert_eq!(GameEngine::from_json(&json).unwrap().rules(), rules);
+    assert!(!GameEngine::new().to_json().unwrap().contains("move_limit"));
+}
+
+#[test]
+fn test_crossed_resignations_draw() {
+    let mut engine = capped(10);
+    engine.record_crossed_resignation();
+    assert_eq!(engine.status(), GameStatus::InProgress);
+    engine.resign();
+    engine.record_crossed_resignation();
+    assert_eq!(engine.status(), GameStatus::Draw);
+    engine.abort();
+    assert_eq!(engine.status(), GameStatus::Aborted);
+}
+
+#[tokio::test]
+async fn test_player_nodes_agree_on_a_capped_draw() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut rng1 = SmallRng::seed_from_u64(3515);
+    let mut rng2 = SmallRng::seed_from_u64(3516);
+    let rules = RuleSet::STANDARD.with_move_limit(Some(8));
+    let mut node1 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new_with_config(rules), Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new_with_config(rules), Box::new(t2));
+    let mut rx1 = node1.subscribe();
+    let mut rx2 = node2.subscribe();
+    let (r1, r2) = tokio::join!(node1.run(&mut rng1, Role::Initiator), node2.run(&mut rng2, Role::Responder));
+    r1.unwrap();
+    r2.unwrap();
+
+    assert_eq!(node1.status(), GameStatus::Draw);
+    assert_eq!(node2.status(), GameStatus::Draw);
+    assert_eq!(node1.guess_count(), 8);
+    assert_eq!(node2.guess_count(), 8);
+    for rx in [&mut rx1, &mut rx2] {
+        let mut last = None;
+        while let Ok(event) = rx.try_recv() {
+            last = Some(event);
+        }
+        assert_eq!(last, Some(GameEvent::GameOver { status: GameStatus::Draw }));
+    }
+}
+
+/// An AI that resigns at its first target once `stalled` is set.
+struct Quitter {
+    ai: AiPlayer,
+    stalled: Arc<AtomicBool>,
+    quit: bool,
+}
+
+/// An AI that sets `stalled`, stalls past its turn clock and then resigns
+/// when asked for its second target.
+struct Staller {
+    ai: AiPlayer,
+    stalled: Arc<AtomicBool>,
+    delay: Duration,
+    picks: usize,
+}
+
+impl Player for Quitter {
+    fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError> {
+        self.ai.place_ships(rng, board)
+    }
+
+    fn select_target(
+        &mut self,
+        rng: &mut SmallRng,
+        hits: &BB,
+        misses: &BB,
+        remaining: &[usize],
+    ) -> Coord {
+        self.quit = self.stalled.load(Ordering::SeqCst);
+        self.ai.select_target(rng, hits, misses, remaining)
+    }
+
+    fn resigned(&self) -> bool {
+        self.quit
+    }
+}
+
+impl Player for Staller {
+    fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError> {
+        self.ai.place_ships(rng, board)
+    }
+
+    fn select_target(
+        &mut self,
+        rng: &mut SmallRng,
+        hits: &BB,
+        misses: &BB,
+        remaining: &[usize],
+    ) -> Coord {
+        self.picks += 1;
+        if self.picks == 2 {
+            self.stalled.store(true, Ordering::SeqCst);
+            std::thread::sleep(self.delay);
+        }
+        self.ai.select_target(rng, hits, misses, remaining)
+    }
+
+    fn resigned(&self) -> bool {
+        self.picks >= 2
+    }
+}
+
+#[test]
+fn test_resignations_crossing_a_timed_out_turn_draw() {
+    // The peer plays the staller's turn, then resigns while the staller's
+    // own resignation is on its way
+    let rules = RuleSet::STANDARD.with_turn_clock(Some(TurnClock::new(150, TimeoutAction::RandomMove)));
+    let stalled = Arc::new(AtomicBool::new(false));
+    let players: [Box<dyn Player>; 2] = [
+        Box::new(Staller {
+            ai: AiPlayer::new(),
+            stalled: stalled.clone(),
+            delay: Duration::from_millis(500),
+            picks: 0,
+        }),
+        Box::new(Quitter {
+            ai: AiPlayer::new(),
+            stalled,
+            quit: false,
+        }),
+    ];
+    let (t1, t2) = InMemoryTransport::pair();
+    let threads: Vec<_> = players
+        .into_iter()
+        .zip([t1, t2])
+        .zip([(Role::Initiator, 0), (Role::Responder, 1)])
+        .map(|((player, transport), (role, offset))| {
+            std::thread::spawn(move || {
+                let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+                let mut node = PlayerNode::new(player, GameEngine::new_with_config(rules), Box::new(transport));
+                let mut rx = node.subscribe();
+                let mut rng = SmallRng::seed_from_u64(3515 + offset);
+                let collect = async {
+                    let mut events = Vec::new();
+                    while let Ok(event) = rx.recv().await {
+                        events.push(event);
+                        if matches!(event, GameEvent::GameOver { .. }) {
+                            break;
+                        }
+                    }
+                    events
+                };
+                let (result, events) = runtime.block_on(async { tokio::join!(node.run(&mut rng, role), collect) });
+                result.unwrap();
+                (node.status(), events)
+            })
+        })
+        .collect();
+
+    for thread in threads {
+        let (status, events) = thread.join().unwrap();
+        assert_eq!(status, GameStatus::Draw);
+        assert_eq!(events.last(), Some(&GameEvent::GameOver { status: GameStatus::Draw }));
+    }
+}
@@ -0,0 +1,96 @@
+use battleship::{
+    protocol::validation, BoardError, DuplicateGuessPolicy, GameEngine, GuessResult, ProtocolViolation, SHIPS,
+};
+use battleship::domain::GuessResult as DomainGuessResult;
+
+mod common;
+
+/// Fire at every cell of ship `i`, returning the final (sinking) result.
+fn sink(engine: &mut GameEngine, i: usize) -> GuessResult {
+    let mut last = GuessResult::Miss;
+    for c in 0..SHIPS[i].length() {
+        last = engine.opponent_guess(i, c).unwrap();
+    }
+    last
+}
+
+#[test]
+fn test_error_policy_rejects_repeats() {
+    let mut engine = common::stacked_engine().with_duplicate_policy(DuplicateGuessPolicy::Error);
+    engine.opponent_guess(9, 9).unwrap();
+    assert_eq!(engine.opponent_guess(9, 9), Err(BoardError::AlreadyGuessed));
+    engine.record_guess(0, 0, GuessResult::Miss).unwrap();
+    assert_eq!(engine.record_guess(0, 0, GuessResult::Miss), Err(BoardError::AlreadyGuessed));
+}
+
+#[test]
+fn test_idempotent_policy_returns_prior_result() {
+    let mut engine = common::stacked_engine().with_duplicate_policy(DuplicateGuessPolicy::IdempotentReturnPrior);
+    assert_eq!(engine.opponent_guess(9, 9).unwrap(), GuessResult::Miss);
+    assert_eq!(engine.opponent_guess(9, 9).unwrap(), GuessResult::Miss);
+    assert_eq!(engine.opponent_guess(0, 0).unwrap(), GuessResult::Hit);
+    assert_eq!(engine.opponent_guess(0, 0).unwrap(), GuessResult::Hit);
+
+    let sunk = sink(&mut engine, 1);
+    assert_eq!(sunk, GuessResult::Sink(SHIPS[1].name()));
+    let last = SHIPS[1].length() - 1;
+    assert_eq!(engine.opponent_guess(1, last).unwrap(), sunk);
+    assert_eq!(engine.board().misses().count_ones(), 1);
+}
+
+#[test]
+fn test_wasted_turn_policy_answers_miss() {
+    let mut engine = common::stacked_engine().with_duplicate_policy(DuplicateGuessPolicy::WastedTurn);
+    assert_eq!(engine.opponent_guess(0, 0).unwrap(), GuessResult::Hit);
+    let hits = engine.board().hits().count_ones();
+    assert_eq!(engine.opponent_guess(0, 0).unwrap(), GuessResult::Miss);
+    assert_eq!(engine.board().hits().count_ones(), hits);
+    assert!(!engine.board().misses().get(0, 0).unwrap());
+}
+
+#[test]
+fn test_repeated_record_leaves_guesses_unchanged() {
+    let mut engine = common::stacked_engine().with_duplicate_policy(DuplicateGuessPolicy::WastedTurn);
+    engine.record_guess(3, 3, GuessResult::Hit).unwrap();
+    engine.record_guess(3, 3, GuessResult::Miss).unwrap();
+    assert!(engine.guess_hits().get(3, 3).unwrap());
+    assert_eq!(engine.guess_misses().count_ones(), 0);
+    assert_eq!(engine.enemy_cells_remaining(), battleship::TOTAL_SHIP_CELLS - 1);
+}
+
+#[test]
+fn test_validate_repeat_follows_policy() {
+    let policy = DuplicateGuessPolicy::IdempotentReturnPrior;
+    let mut idempotent = common::stacked_engine().with_duplicate_policy(policy);
+    idempotent.record_guess(3, 3, GuessResult::Hit).unwrap();
+    assert_eq!(
+        validation::validate_repeat(&idempotent, policy, 3, 3, &DomainGuessResult::Hit),
+        Ok(GuessResult::Hit)
+    );
+    assert_eq!(
+        validation::validate_repeat(&idempotent, policy, 3, 3, &DomainGuessResult::Miss),
+        Err(ProtocolViolation::InconsistentRepeat { row: 3, col: 3 })
+    );
+
+    let policy = DuplicateGuessPolicy::WastedTurn;
+    let mut wasted = common::stacked_engine().with_duplicate_policy(policy);
+    wasted.record_guess(3, 3, GuessResult::Hit).unwrap();
+    assert_eq!(
+        validation::validate_repeat(&wasted, policy, 3, 3, &DomainGuessResult::Miss),
+        Ok(GuessResult::Miss)
+    );
+    assert!(validation::validate_repeat(&wasted, policy, 3, 3, &DomainGuessResult::Hit).is_err());
+}
+
+#[test]
+fn test_validate_repeat_uses_the_agreed_policy() {
+    // The engine's own policy does not decide what the peer may answer
+    let mut engine = common::stacked_engine();
+    engine.record_guess(3, 3, GuessResult::Hit).unwrap();
+    assert_eq!(
+        validation::validate_repeat(&engine, DuplicateGuessPolicy::WastedTurn, 3, 3, &DomainGuessResult::Miss),
+        Ok(GuessResult::Miss)
+    );
+    let engine = engine.with_duplicate_policy(DuplicateGuessPolicy::WastedTurn);
+    assert!(validation::validate_repeat(&engine, DuplicateGuessPolicy::Error, 3, 3, &DomainGuessResult::Miss).is_err());
+}
@@ -0,0 +1,77 @@
+use battleship::{plan_endgame, AiPlayer, BoardBits, Player, RuleSet, ENDGAME_MAX_CANDIDATES};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+type BB = BoardBits;
+
+/// Every ship but the Destroyer sunk in rows 0, 2, 4 and 6, and every other
+/// cell missed except `open`.
+fn destroyer_left(open: &[(usize, usize)]) -> (BB, BB) {
+    let sunk = common::wrecks(&[(0, 5), (2, 4), (4, 3), (6, 3)]);
+    (sunk, common::misses_except(sunk, open))
+}
+
+#[test]
+fn test_endgame_splits_the_candidates() {
+    // Three ways to lie in the bottom-left corner: shooting second from
+    // the end sinks the Destroyer in 8/3 shots on average, the end in 3
+    let (sunk, misses) = destroyer_left(&[(9, 0), (9, 1), (9, 2), (9, 3)]);
+    let remaining = [0, 0, 0, 0, 2];
+    let plan = plan_endgame(&common::standard_view(&sunk, &sunk, &misses, &remaining), ENDGAME_MAX_CANDIDATES).unwrap();
+    assert_eq!(plan.candidates, 3);
+    let best = plan.best.unwrap();
+    assert_eq!((best.row, best.col), (9, 1));
+    assert!((best.expected_shots - 8.0 / 3.0).abs() < 1e-12);
+
+    // A hit narrows the candidates to the two covering it
+    let mut hits = sunk;
+    hits.set(9, 1).unwrap();
+    let best = plan_endgame(&common::standard_view(&hits, &sunk, &misses, &remaining), ENDGAME_MAX_CANDIDATES)
+        .unwrap()
+        .best
+        .unwrap();
+    assert_eq!(best.expected_shots, 1.5);
+    assert!([(9, 0), (9, 2)].contains(&(best.row, best.col)));
+}
+
+#[test]
+fn test_endgame_only_with_one_ship_left() {
+    let (sunk, misses) = destroyer_left(&[(9, 0), (9, 1), (9, 2)]);
+    assert_eq!(plan_endgame(&common::standard_view(&sunk, &sunk, &misses, &[0, 0, 0, 3, 2]), 8), None);
+
+    // Sunk ships must be told apart from the last one's hits
+    assert_eq!(plan_endgame(&common::standard_view(&sunk, &BB::new(), &misses, &[0, 0, 0, 0, 2]), 8), None);
+
+    // Too many candidates are counted, not searched
+    let open = !(sunk | BB::rect(0, 0, 7, 9).unwrap() | RuleSet::STANDARD.off_board());
+    let plan = plan_endgame(&common::standard_view(&sunk, &sunk, &!(sunk | open), &[0, 0, 0, 0, 2]), 8).unwrap();
+    assert_eq!((plan.candidates, plan.best), (28, None));
+    assert_eq!(plan_endgame(&common::standard_view(&sunk, &sunk, &misses, &[0, 0, 0, 0, 2]), 0).unwrap().best, None);
+}
+
+#[test]
+fn test_ai_player_reports_the_endgame() {
+    let mut rng = SmallRng::seed_from_u64(9);
+    let mut duel = common::Duel::new(&mut rng);
+    let mut player = AiPlayer::new();
+    let mut searched = false;
+    for _ in 0..100 {
+        let (hits, _, misses, remaining) = duel.seen();
+        let guess = player.select_target(&mut rng, &hits, &misses, &remaining);
+        let state = player.debug_state();
+        let afloat = remaining.iter().filter(|&&l| l > 0).count();
+        assert_eq!(state.endgame_candidates.is_some(), afloat == 1);
+        let small = state.endgame_candidates.is_some_and(|n| n <= ENDGAME_MAX_CANDIDATES);
+        assert_eq!(state.endgame_expected_shots.is_some(), small);
+        searched |= small;
+
+        player.handle_guess_result(guess, duel.fire(guess.row, guess.col));
+        if duel.won() {
+            assert!(searched);
+            return;
+        }
+    }
+    panic!("fleet not sunk in 100 shots");
+}
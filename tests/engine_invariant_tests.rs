@@ -0,0 +1,60 @@
+use battleship::{DuplicateGuessPolicy, GameEngine, GuessResult, SHIPS};
+
+mod common;
+
+#[test]
+fn test_invariants_hold_through_play() {
+    let mut engine = common::stacked_engine().with_duplicate_policy(DuplicateGuessPolicy::WastedTurn);
+    for c in 0..SHIPS[0].length() {
+        engine.opponent_guess(0, c).unwrap();
+    }
+    engine.opponent_guess(9, 9).unwrap();
+    engine.opponent_guess(9, 9).unwrap();
+    engine.record_guess(5, 5, GuessResult::Hit).unwrap();
+    engine.record_guess(5, 6, GuessResult::Sink(SHIPS[4].name())).unwrap();
+    engine.record_guess(7, 7, GuessResult::Miss).unwrap();
+    assert_eq!(engine.check_invariants(), Ok(()));
+    assert_eq!(GameEngine::from_state(engine.state()).check_invariants(), Ok(()));
+}
+
+#[cfg(not(feature = "debug-invariants"))]
+#[test]
+fn test_corrupted_state_is_reported() {
+    use battleship::{InvariantViolation, TOTAL_SHIP_CELLS};
+
+    let mut engine = common::stacked_engine().with_duplicate_policy(DuplicateGuessPolicy::WastedTurn);
+    engine.record_guess(0, 0, GuessResult::Hit).unwrap();
+
+    let mut state = engine.state();
+    state.enemy_remaining = TOTAL_SHIP_CELLS;
+    assert_eq!(
+        GameEngine::from_state(state).check_invariants(),
+        Err(InvariantViolation::EnemyRemainingMismatch {
+            recorded: TOTAL_SHIP_CELLS,
+            expected: TOTAL_SHIP_CELLS - 1,
+        })
+    );
+
+    let mut state = engine.state();
+    state.my_guesses.misses.set(0, 0).unwrap();
+    assert_eq!(
+        GameEngine::from_state(state).check_invariants(),
+        Err(InvariantViolation::OverlappingGuesses { cells: 1 })
+    );
+
+    let mut state = engine.state();
+    state.my_board.hits.set(9, 9).unwrap();
+    assert_eq!(
+        GameEngine::from_state(state).check_invariants(),
+        Err(InvariantViolation::HitOffShip { cells: 1 })
+    );
+}
+
+#[cfg(feature = "debug-invariants")]
+#[test]
+#[should_panic(expected = "GameEngine invariant violated")]
+fn test_corrupted_state_panics_on_restore() {
+    let mut state = common::stacked_engine().with_duplicate_policy(DuplicateGuessPolicy::WastedTurn).state();
+    state.my_guesses.hits.set(3, 3).unwrap();
+    let _ = GameEngine::from_state(state);
+}
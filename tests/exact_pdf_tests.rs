@@ -0,0 +1,60 @@
+use battleship::{calc_pdf_with_config, AiConfig, BoardBits};
+
+mod common;
+
+type BB = BoardBits;
+
+/// Hits where the Carrier, Battleship, Cruiser and Submarine were sunk in
+/// rows 2..6, misses on every other cell but `open`.
+fn endgame(open: &[(usize, usize)]) -> (BB, BB) {
+    let hits = common::wrecks(&[(2, 5), (3, 4), (4, 3), (5, 3)]);
+    (hits, common::misses_except(hits, open))
+}
+
+const DESTROYER_ONLY: [usize; 5] = [0, 0, 0, 0, 2];
+
+#[test]
+fn test_exact_density_counts_each_layout_once() {
+    let open = [(9, 0), (9, 1), (9, 2), (9, 3)];
+    let (hits, misses) = endgame(&open);
+    let pdf = common::exact_pdf(&hits, &misses, &DESTROYER_ONLY).expect("few unknown cells");
+    // The Destroyer lies at columns 0-1, 1-2 or 2-3 of the bottom row
+    let expected = [1.0 / 6.0, 2.0 / 6.0, 2.0 / 6.0, 1.0 / 6.0];
+    for (&(r, c), want) in open.iter().zip(expected) {
+        assert!((pdf[r][c] - want).abs() < 1e-12, "({}, {}): {}", r, c, pdf[r][c]);
+    }
+    assert_eq!(pdf[0][0], 0.0);
+
+    // Wrecks passed among the misses still count as hits
+    let blocked = common::exact_pdf(&hits, &(misses | hits), &DESTROYER_ONLY);
+    assert_eq!(blocked, Some(pdf));
+}
+
+#[test]
+fn test_exact_density_follows_a_wounded_ship() {
+    let (mut hits, misses) = endgame(&[(9, 0), (9, 1), (9, 2), (9, 3)]);
+    hits.set(9, 1).unwrap();
+    let pdf = common::exact_pdf(&hits, &misses, &DESTROYER_ONLY).unwrap();
+    assert_eq!((pdf[9][0], pdf[9][2], pdf[9][3]), (0.5, 0.5, 0.0));
+
+    let config = AiConfig { use_exact_solver: true, ..AiConfig::default() };
+    assert_eq!(calc_pdf_with_config(&hits, &misses, &DESTROYER_ONLY, &config), pdf);
+}
+
+#[test]
+fn test_infeasible_cases_fall_back_to_the_heuristic() {
+    let empty = BB::new();
+    let fleet = [5, 4, 3, 3, 2];
+    assert!(common::exact_pdf(&empty, &empty, &fleet).is_none());
+    let config = AiConfig { use_exact_solver: true, ..AiConfig::default() };
+    assert_eq!(
+        calc_pdf_with_config(&empty, &empty, &fleet, &config),
+        calc_pdf_with_config(&empty, &empty, &fleet, &AiConfig::default())
+    );
+
+    let (hits, misses) = endgame(&[(9, 0), (9, 1)]);
+    // No such ship in the fleet
+    assert!(common::exact_pdf(&hits, &misses, &[0, 0, 0, 0, 7]).is_none());
+    // The open cells cannot hold a Cruiser
+    assert!(common::exact_pdf(&hits, &misses, &[0, 0, 3, 0, 0]).is_none());
+}
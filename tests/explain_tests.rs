@@ -0,0 +1,86 @@
+use battleship::{
+    AiConfig, AiPlayer, BoardBits, Coord, GameEngine, GameStatus, GuessResult, Player, TargetPhase,
+    EXPLAIN_TOP,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+type BB = BoardBits;
+
+fn deterministic() -> AiPlayer {
+    AiPlayer::with_config(AiConfig { deterministic: true, ..AiConfig::default() })
+}
+
+#[test]
+fn test_explanation_ranks_the_hunt() {
+    let mut player = deterministic();
+    assert_eq!(player.last_explanation(), None);
+    let mut rng = SmallRng::seed_from_u64(1);
+    let target = player.select_target(&mut rng, &BB::new(), &BB::new(), &[5, 4, 3, 3, 2]);
+
+    let explanation = player.last_explanation().unwrap();
+    assert_eq!(explanation.phase, TargetPhase::Hunt);
+    assert_eq!((explanation.chosen, explanation.expected_shots), (target, None));
+    assert_eq!((explanation.constraints.ruled_out, explanation.constraints.follow_up), (0, 0));
+    let top = explanation.top();
+    assert_eq!(top.len(), EXPLAIN_TOP);
+    assert_eq!(top[0].coord, target);
+    assert!(top.windows(2).all(|w| w[0].probability >= w[1].probability));
+    assert!(top.iter().map(|c| c.probability).sum::<f64>() < 1.0);
+    assert!(explanation.to_string().starts_with("Hunting"));
+}
+
+#[test]
+fn test_explanation_follows_a_hit() {
+    let mut player = deterministic();
+    let mut rng = SmallRng::seed_from_u64(1);
+    let hits = BB::try_from_cells([(4, 4)]).unwrap();
+    player.handle_guess_result(Coord::new(4, 4), GuessResult::Hit);
+    let target = player.select_target(&mut rng, &hits, &BB::new(), &[5, 4, 3, 3, 2]);
+
+    let explanation = player.last_explanation().unwrap();
+    assert_eq!(explanation.phase, TargetPhase::Target);
+    assert_eq!((explanation.constraints.ruled_out, explanation.constraints.follow_up), (1, 4));
+    // Only the four neighbours of the hit are left in the running
+    let top = explanation.top();
+    assert_eq!((top.len(), top[0].coord), (4, target));
+    assert!(top.iter().all(|c| c.coord.row.abs_diff(4) + c.coord.col.abs_diff(4) == 1));
+    assert!((top.iter().map(|c| c.probability).sum::<f64>() - 1.0).abs() < 1e-9);
+    let text = explanation.to_string();
+    assert!(text.contains("probing 4 cells") && text.ends_with(&format!("Chose {}.", target)));
+}
+
+#[test]
+fn test_explanation_every_turn() {
+    let mut rng = SmallRng::seed_from_u64(9);
+    let mut target = GameEngine::new();
+    let mut shooter = GameEngine::new();
+    AiPlayer::new().place_ships(&mut rng, target.board_mut()).unwrap();
+    let mut player = AiPlayer::new();
+    let mut phases = Vec::new();
+    for _ in 0..100 {
+        let guess = player.select_target(
+            &mut rng,
+            &shooter.guess_hits(),
+            &shooter.blocked_cells(),
+            &shooter.enemy_ship_lengths_remaining(),
+        );
+        let explanation = player.last_explanation().unwrap();
+        assert_eq!(explanation.chosen, guess);
+        assert_eq!(explanation.constraints.ruled_out, (shooter.guess_hits() | shooter.blocked_cells()).count_ones());
+        assert_eq!(explanation.phase == TargetPhase::Endgame, explanation.expected_shots.is_some());
+        assert_eq!(explanation.phase == TargetPhase::Endgame, explanation.top().is_empty());
+        phases.push(explanation.phase);
+
+        let result = target.opponent_guess(guess.row, guess.col).unwrap();
+        shooter.record_guess(guess.row, guess.col, result).unwrap();
+        player.handle_guess_result(guess, result);
+        if target.status() == GameStatus::Lost {
+            for phase in [TargetPhase::Hunt, TargetPhase::Target, TargetPhase::Endgame] {
+                assert!(phases.contains(&phase), "no {:?} turn", phase);
+            }
+            return;
+        }
+    }
+    panic!("fleet not sunk in 100 shots");
+}
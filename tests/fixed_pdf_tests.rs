@@ -0,0 +1,104 @@
+use battleship::{
+    argmax_fixed, argmax_pdf, calc_pdf, calc_pdf_fixed, calc_pdf_fixed_with_sunk, calc_pdf_with_config,
+    calc_pdf_with_sunk, sample_fixed, AiConfig, AiPlayer, BoardBits, FixedConfig, FleetConfig, PlacementRules, Player,
+    BOARD_SIZE, MAX_BOARD_SIZE,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+type BB = BoardBits;
+const N: usize = BOARD_SIZE as usize;
+const GRID: usize = MAX_BOARD_SIZE as usize;
+
+/// Boards seen while an AI sinks a fleet: hits, sunk cells, misses and the
+/// ships left.
+fn positions(seed: u64) -> Vec<common::Seen> {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut duel = common::Duel::new(&mut rng);
+    let mut player = AiPlayer::new();
+    let mut seen = Vec::new();
+    while !duel.won() {
+        let (hits, sunk, misses, remaining) = duel.seen();
+        seen.push((hits, sunk, misses, remaining));
+        let guess = player.select_target(&mut rng, &hits, &misses, &remaining);
+        player.handle_guess_result(guess, duel.fire(guess.row, guess.col));
+    }
+    seen
+}
+
+fn assert_same_ranking(fixed: &[[u32; GRID]; GRID], pdf: &[[f64; GRID]; GRID]) {
+    let cells: Vec<(usize, usize)> = (0..N).flat_map(|r| (0..N).map(move |c| (r, c))).collect();
+    for &(r1, c1) in &cells {
+        for &(r2, c2) in &cells {
+            let fixed_order = fixed[r1][c1].cmp(&fixed[r2][c2]);
+            assert_eq!(Some(fixed_order), pdf[r1][c1].partial_cmp(&pdf[r2][c2]), "({}, {}) vs ({}, {})", r1, c1, r2, c2);
+        }
+    }
+}
+
+#[test]
+fn test_fixed_density_ranks_like_calc_pdf() {
+    let empty = calc_pdf_fixed(&BB::new(), &BB::new(), &[5, 4, 3, 3, 2]);
+    assert_same_ranking(&empty, &calc_pdf(&BB::new(), &BB::new(), &[5, 4, 3, 3, 2]));
+
+    let (fleet, rules) = (FleetConfig::STANDARD, PlacementRules { no_touching: true });
+    for config in [AiConfig::default(), AiConfig { parity: true, hit_bias: 7.0, ..AiConfig::default() }] {
+        for (hits, sunk, misses, remaining) in positions(3).into_iter().step_by(4) {
+            for placement in [PlacementRules::STANDARD, rules] {
+                let fixed = calc_pdf_fixed_with_sunk(&hits, &sunk, &misses, &remaining, &fleet, &placement, &(&config).into());
+                let pdf = calc_pdf_with_sunk(&hits, &sunk, &misses, &remaining, &fleet, &placement, &config);
+                if fixed.iter().flatten().any(|&s| s > 0) {
+                    assert_same_ranking(&fixed, &pdf);
+                    assert_eq!(argmax_fixed(&fixed), argmax_pdf(&pdf));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_config_selects_fixed_point() {
+    let config = AiConfig { fixed_point: true, ..AiConfig::default() };
+    for (hits, _, misses, remaining) in positions(8).into_iter().step_by(5) {
+        let fixed = calc_pdf_with_config(&hits, &misses, &remaining, &config);
+        let float = calc_pdf_with_config(&hits, &misses, &remaining, &AiConfig::default());
+        for (a, b) in fixed.iter().flatten().zip(float.iter().flatten()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+    }
+    assert_eq!(FixedConfig::from(&AiConfig { hit_bias: 2.6, ..AiConfig::default() }).hit_bias, 3);
+    assert_eq!(FixedConfig::from(&AiConfig::default()), FixedConfig::default());
+}
+
+#[test]
+fn test_sample_fixed_follows_the_scores() {
+    let mut scores = [[0u32; GRID]; GRID];
+    scores[2][3] = 1;
+    scores[7][1] = 3;
+    let mut rng = SmallRng::seed_from_u64(4);
+    let picks: Vec<_> = (0..200).map(|_| sample_fixed(&scores, &mut rng)).collect();
+    assert!(picks.iter().all(|p| [(2, 3), (7, 1)].contains(p)));
+    let heavy = picks.iter().filter(|&&p| p == (7, 1)).count();
+    assert!((120..190).contains(&heavy));
+    assert_eq!(argmax_fixed(&scores), (7, 1));
+    assert_eq!(argmax_fixed(&[[0; GRID]; GRID]), (0, 0));
+}
+
+#[test]
+fn test_huge_bias_saturates() {
+    let hits = common::cells(&[(4, 4), (4, 5), (4, 6)]);
+    let config = FixedConfig { hit_bias: u32::MAX, parity: false };
+    let fixed = calc_pdf_fixed_with_sunk(
+        &hits,
+        &BB::new(),
+        &BB::new(),
+        &[5],
+        &FleetConfig::STANDARD,
+        &PlacementRules::STANDARD,
+        &config,
+    );
+    assert_eq!(fixed[4][3], u32::MAX);
+    assert_eq!(fixed[4][4], 0);
+}
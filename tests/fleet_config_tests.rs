@@ -0,0 +1,264 @@
+#![cfg(feature = "std")]
+
+use battleship::domain::{GameConfig, GuessResult as DomainGuessResult, Placement, MAX_SHIP_NAME_LEN};
+use battleship::protocol::validation::{validate_fleet, validate_result, ProtocolViolation};
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{
+    calc_pdf, calc_pdf_for_fleet, calc_pdf_with_config, AiConfig, AiPlayer, BoardBits, BoardError, FleetConfig, GameEngine,
+    GameStatus, GuessResult, Orientation, Player, PlayerNode, Role, RuleSet, ShipDef, MAX_SHIPS, SHIPS,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+/// Two destroyers and no carrier.
+fn destroyers() -> FleetConfig {
+    FleetConfig::empty().with_ships("Destroyer", 2, 2).unwrap()
+}
+
+fn rules() -> RuleSet {
+    RuleSet::STANDARD.with_fleet(destroyers()).unwrap()
+}
+
+/// An engine with destroyer `i` along row `i` from column 0.
+fn engine() -> GameEngine {
+    let mut engine = GameEngine::new_with_config(rules());
+    engine.board_mut().place(0, 0, 0, Orientation::Horizontal).unwrap();
+    engine.board_mut().place(1, 1, 0, Orientation::Horizontal).unwrap();
+    engine
+}
+
+#[test]
+fn test_fleet_definition_limits() {
+    let fleet = destroyers();
+    assert_eq!(fleet.len(), 2);
+    assert_eq!(fleet.total_cells(), 4);
+    assert_eq!(fleet.longest(), 2);
+    assert_eq!(fleet.lengths()[..5], [2, 2, 0, 0, 0]);
+    assert_eq!(FleetConfig::STANDARD.total_cells(), battleship::TOTAL_SHIP_CELLS);
+    assert_eq!(fleet.with_ships("Submarine", 3, MAX_SHIPS - 1), Err(BoardError::InvalidFleet));
+    assert_eq!(fleet.with_ships("Submarine", 3, usize::MAX), Err(BoardError::InvalidFleet));
+    assert_eq!(FleetConfig::empty().with_ships("Raft", 0, 1), Err(BoardError::InvalidFleet));
+    assert_eq!(RuleSet::STANDARD.with_fleet(FleetConfig::empty()), Err(BoardError::InvalidFleet));
+
+    let long = FleetConfig::empty().with_ships("Carrier", 5, 1).unwrap();
+    assert_eq!(RuleSet::new(4, long), Err(BoardError::UnsupportedBoardSize(4)));
+    assert!(RuleSet::new(3, fleet).is_ok());
+}
+
+#[test]
+fn test_board_holds_only_the_custom_fleet() {
+    let mut engine = GameEngine::new_with_config(rules());
+    assert!(!engine.board().all_placed());
+    assert_eq!(engine.board_mut().place(2, 5, 5, Orientation::Horizontal), Err(BoardError::InvalidIndex));
+    engine.board_mut().place(0, 0, 0, Orientation::Horizontal).unwrap();
+    engine.board_mut().place(1, 1, 0, Orientation::Horizontal).unwrap();
+    assert!(engine.board().all_placed());
+    assert_eq!(engine.board().fleet_report().ships_afloat(), 2);
+    assert_eq!(engine.board().ship_states()[2].position, None);
+
+    let mut rng = SmallRng::seed_from_u64(3502);
+    let mut random = GameEngine::new_with_config(rules());
+    let mut player = AiPlayer::new();
+    player.place_ships(&mut rng, random.board_mut()).unwrap();
+    assert_eq!(random.board().ship_map().count_ones(), 4);
+    // The AI targets the fleet of the game it placed for
+    assert_eq!(*player.fleet(), destroyers());
+}
+
+#[test]
+fn test_sinking_ships_that_share_a_name() {
+    let mut engine = engine();
+    assert_eq!(engine.enemy_ship_lengths_remaining()[..5], [2, 2, 0, 0, 0]);
+    assert_eq!(engine.enemy_cells_remaining(), 4);
+
+    engine.record_guess(5, 5, GuessResult::Hit).unwrap();
+    engine.record_guess(5, 6, GuessResult::Sink("Destroyer")).unwrap();
+    assert_eq!(engine.enemy_ship_lengths_remaining()[..5], [0, 2, 0, 0, 0]);
+    engine.record_guess(7, 5, GuessResult::Hit).unwrap();
+    engine.record_guess(7, 6, GuessResult::Sink("Destroyer")).unwrap();
+    assert_eq!(engine.status(), GameStatus::Won);
+
+    let mut engine = self::engine();
+    assert_eq!(engine.opponent_guess(0, 0).unwrap(), GuessResult::Hit);
+    assert_eq!(engine.opponent_guess(0, 1).unwrap(), GuessResult::Sink("Destroyer"));
+    assert_eq!(engine.opponent_guess(1, 0).unwrap(), GuessResult::Hit);
+    assert_eq!(engine.opponent_guess(1, 1).unwrap(), GuessResult::Sink("Destroyer"));
+    assert_eq!(engine.status(), GameStatus::Lost);
+    assert_eq!(engine.defense_report().first_ship_lost(), Some(0));
+}
+
+#[test]
+fn test_validation_follows_the_fleet() {
+    let placement = |row| Placement {
+        ship: "Destroyer".to_string(),
+        row,
+        col: 0,
+        orientation: Orientation::Horizontal,
+    };
+    assert!(validate_fleet(&rules(), &[placement(0), placement(2)]).is_ok());
+    let reasons = validate_fleet(&rules(), &[placement(0), placement(2), placement(4)]).unwrap_err();
+    assert_eq!(reasons, vec!["Destroyer placed more than once".to_string()]);
+
+    let mut engine = engine();
+    assert_eq!(
+        validate_result(&engine, &DomainGuessResult::Sink("Carrier".to_string())),
+        Err(ProtocolViolation::UnknownShip("Carrier".to_string()))
+    );
+    engine.record_guess(5, 0, GuessResult::Hit).unwrap();
+    engine.record_guess(5, 1, GuessResult::Sink("Destroyer")).unwrap();
+    engine.record_guess(7, 0, GuessResult::Hit).unwrap();
+    assert_eq!(
+        validate_result(&engine, &DomainGuessResult::Sink("Destroyer".to_string())),
+        Ok(GuessResult::Sink("Destroyer"))
+    );
+}
+
+#[test]
+fn test_parity_uses_the_fleet_size() {
+    // One destroyer sunk: every hit is resolved, so parity applies
+    let hits = BoardBits::try_from_cells([(0, 0), (0, 1)]).unwrap();
+    let misses = BoardBits::new();
+    let remaining = [0, 2, 0, 0, 0];
+    let config = AiConfig { parity: true, ..AiConfig::default() };
+
+    let pdf = calc_pdf_for_fleet(&hits, &misses, &remaining, &destroyers(), &config);
+    assert_eq!(pdf[5][6], 0.0);
+    assert!(pdf[5][5] > 0.0);
+    let standard = calc_pdf_with_config(&hits, &misses, &remaining, &config);
+    assert!(standard[5][6] > 0.0);
+}
+
+#[test]
+fn test_fleets_and_lengths_from_slices() {
+    let ships = [ShipDef::new("Destroyer", 2), ShipDef::new("Destroyer", 2)];
+    assert_eq!(FleetConfig::from_ships(&ships), Ok(destroyers()));
+    assert_eq!(FleetConfig::from_ships(&[SHIPS[0]; MAX_SHIPS + 1]), Err(BoardError::InvalidFleet));
+    assert_eq!(FleetConfig::from_ships(&[ShipDef::new("Raft", 0)]), Err(BoardError::InvalidFleet));
+
+    // Lengths need not be padded to MAX_SHIPS
+    let hits = BoardBits::new();
+    let misses = BoardBits::new();
+    let fleet = destroyers();
+    let short = calc_pdf(&hits, &misses, &fleet.lengths()[..fleet.len()]);
+    assert_eq!(short, calc_pdf(&hits, &misses, &fleet.lengths()));
+}
+
+#[test]
+fn test_ai_game_with_custom_fleet() {
+    let mut rng = SmallRng::seed_from_u64(2);
+    let mut players = [AiPlayer::new().with_fleet(destroyers()), AiPlayer::new().with_fleet(destroyers())];
+    let mut engines = [GameEngine::new_with_config(rules()), GameEngine::new_with_config(rules())];
+    for (player, engine) in players.iter_mut().zip(engines.iter_mut()) {
+        player.place_ships(&mut rng, engine.board_mut()).unwrap();
+    }
+
+    let mut shooter = 0;
+    for _ in 0..200 {
+        let defender = 1 - shooter;
+        let target = players[shooter].select_target(
+            &mut rng,
+            &engines[shooter].guess_hits(),
+            &engines[shooter].guess_misses(),
+            &engines[shooter].enemy_ship_lengths_remaining(),
+        );
+        let res = engines[defender].opponent_guess(target.row, target.col).unwrap();
+        engines[shooter].record_guess(target.row, target.col, res).unwrap();
+        if engines[defender].status() == GameStatus::Lost {
+            assert_eq!(engines[shooter].status(), GameStatus::Won);
+            return;
+        }
+        shooter = defender;
+    }
+    panic!("game with two destroyers each did not finish");
+}
+
+/// A fleet whose ship names the standard fleet does not use.
+fn flotilla() -> RuleSet {
+    let fleet = FleetConfig::empty()
+        .with_ships("Frigate", 4, 1)
+        .and_then(|fleet| fleet.with_ships("Patrol Boat", 2, 2))
+        .unwrap();
+    RuleSet::new(8, fleet).unwrap()
+}
+
+#[test]
+fn test_game_config_carries_the_fleet() {
+    let config = GameConfig::from(&flotilla());
+    assert_eq!(config.ships[1], ("Patrol Boat".to_string(), 2));
+    assert_eq!(config.rules(), Some(flotilla()));
+    assert_eq!(GameConfig::from(&rules()).rules(), Some(rules()));
+
+    let mut long_name = config.clone();
+    long_name.ships[0].0 = "F".repeat(MAX_SHIP_NAME_LEN + 1);
+    assert_eq!(long_name.rules(), None);
+    let mut unknown = config.clone();
+    unknown.ships[0].0 = "Dinghy".to_string();
+    assert_eq!(unknown.rules(), None);
+    let mut raft = config.clone();
+    raft.ships[0].1 = 0;
+    assert_eq!(raft.rules(), None);
+    let mut armada = config;
+    armada.ships = vec![("Frigate".to_string(), 2); MAX_SHIPS + 1];
+    assert_eq!(armada.rules(), None);
+}
+
+#[cfg(feature = "large-fleets")]
+#[test]
+fn test_large_fleets_hold_more_than_the_standard_five() {
+    let fleet = FleetConfig::from_ships(&[ShipDef::new("Patrol Boat", 2); 7]).unwrap();
+    let rules = RuleSet::new(10, fleet).unwrap();
+    assert_eq!(GameConfig::from(&rules).rules(), Some(rules));
+
+    let mut engine = GameEngine::new_with_config(rules);
+    for i in 0..7 {
+        engine.board_mut().place(i, i, 0, Orientation::Horizontal).unwrap();
+    }
+    assert_eq!(engine.enemy_ship_lengths_remaining()[..8], [2, 2, 2, 2, 2, 2, 2, 0]);
+    assert_eq!(engine.opponent_guess(6, 0).unwrap(), GuessResult::Hit);
+    assert_eq!(engine.opponent_guess(6, 1).unwrap(), GuessResult::Sink("Patrol Boat"));
+    assert!(engine.defense_report().ships[6].sunk_at.is_some());
+    assert_eq!(engine.status(), GameStatus::InProgress);
+}
+
+#[tokio::test]
+async fn test_nodes_play_a_custom_fleet_sent_in_the_config() {
+    // The responder only learns the fleet from the config on the wire
+    let bytes = bincode::serialize(&GameConfig::from(&flotilla())).unwrap();
+    let received: GameConfig = bincode::deserialize(&bytes).unwrap();
+    let responder_engine = received.builder().unwrap().build().unwrap();
+    assert_eq!(responder_engine.rules(), flotilla());
+
+    let node = |seed, mut engine: GameEngine, transport: InMemoryTransport| {
+        let mut player = AiPlayer::new();
+        player.place_ships(&mut SmallRng::seed_from_u64(seed), engine.board_mut()).unwrap();
+        assert_eq!(engine.board().ship_map().count_ones(), 8);
+        PlayerNode::new(Box::new(player), engine, Box::new(transport))
+    };
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut initiator = node(1, GameEngine::new_with_config(flotilla()), t1);
+    let mut responder = node(2, responder_engine, t2);
+    let mut rng1 = SmallRng::seed_from_u64(1);
+    let mut rng2 = SmallRng::seed_from_u64(2);
+    let (a, b) = tokio::join!(
+        initiator.run(&mut rng1, Role::Initiator),
+        responder.run(&mut rng2, Role::Responder)
+    );
+    a.unwrap();
+    b.unwrap();
+    let mut statuses = [initiator.status(), responder.status()];
+    statuses.sort_by_key(|status| *status == GameStatus::Won);
+    assert_eq!(statuses, [GameStatus::Lost, GameStatus::Won]);
+}
+
+#[test]
+fn test_json_round_trip_with_custom_fleet() {
+    let mut engine = engine();
+    engine.opponent_guess(0, 0).unwrap();
+    engine.record_guess(4, 4, GuessResult::Hit).unwrap();
+    let json = engine.to_json().unwrap();
+
+    let restored = GameEngine::from_json_with_fleet(&json, &destroyers()).unwrap();
+    assert_eq!(restored.rules(), rules());
+    assert_eq!(restored.state(), engine.state());
+    assert!(GameEngine::from_json(&json).is_err());
+}
@@ -1,30 +1,31 @@
-use battleship::protocol::Message;
-use battleship::domain::{GuessResult, GameStatus, Ship, SyncPayload};
-use battleship::{GameState, GuessBoardState, BoardState, BitBoard, ShipState};
+use battleship::protocol::{lobby::LobbyError, LobbyMessage, Message, Role};
+use battleship::domain::{ErrorCode, GameConfig, GuessResult, GameStatus, Placement, Ship, SyncPayload};
+use battleship::{BoardBits, Coord, DuplicateGuessPolicy, GameState, GuessBoardState, BoardState, BitBoard, Orientation, ShipState, TimeoutAction, TurnClock, TurnPolicy, BOARD_SIZE, MAX_SHIPS};
 use proptest::prelude::*;
 
 /// Generate arbitrary messages for fuzzing
 fn arb_message() -> impl Strategy<Value = Message> {
     prop_oneof![
         (any::<u8>()).prop_map(|v| Message::Handshake { version: v }),
+        (any::<u8>(), any::<u8>()).prop_map(|(min, max)| Message::HandshakeRange { min_version: min, max_version: max }),
         (any::<u8>()).prop_map(|v| Message::HandshakeAck { version: v }),
         (any::<u8>(), any::<u64>(), any::<u8>(), any::<u8>()).prop_map(|(v, s, x, y)| {
             Message::Guess {
                 version: v,
                 seq: s,
-                x,
-                y,
+                target: Coord::new(x as usize, y as usize),
             }
         }),
         (any::<u8>(), any::<u64>()).prop_map(|(v, s)| Message::StatusReq {
             version: v,
             seq: s,
         }),
-        (any::<u8>(), any::<u64>(), arb_guess_result()).prop_map(|(v, s, res)| {
+        (any::<u8>(), any::<u64>(), arb_guess_result(), any::<Option<u64>>()).prop_map(|(v, s, res, digest)| {
             Message::StatusResp {
                 version: v,
                 seq: s,
                 res,
+                digest,
             }
         }),
         (any::<u8>(), any::<u64>(), arb_sync_payload()).prop_map(|(v, s, payload)| {
@@ -61,6 +62,121 @@ fn arb_message() -> impl Strategy<Value = Message> {
         }),
         (any::<u8>(), any::<u64>()).prop_map(|(v, s)| Message::Ack { version: v, seq: s }),
         (any::<u8>()).prop_map(|v| Message::Heartbeat { version: v }),
+        (any::<u8>(), any::<[u8; 32]>()).prop_map(|(v, c)| Message::CoinCommit {
+            version: v,
+            commitment: c,
+        }),
+        (any::<u8>(), any::<[u8; 32]>()).prop_map(|(v, n)| Message::CoinReveal {
+            version: v,
+            nonce: n,
+        }),
+        (any::<u8>(), any::<u32>()).prop_map(|(v, e)| Message::PlacementProgress {
+            version: v,
+            elapsed_secs: e,
+        }),
+        (any::<u8>()).prop_map(|v| Message::Ready { version: v }),
+        (any::<u8>(), any::<u8>(), prop::collection::vec(any::<(String, u8)>(), 0..8), any::<[bool; 4]>(), any::<Option<(u32, bool)>>(), any::<Option<u16>>(), 0..3u8)
+            .prop_map(|(v, board_size, ships, [salvo, no_touching, continue_on_hit, initiator], clock, move_limit, policy)| Message::StartGame {
+                version: v,
+                config: GameConfig {
+                    board_size,
+                    ships,
+                    salvo,
+                    no_touching,
+                    turn_policy: if continue_on_hit { TurnPolicy::ContinueOnHit } else { TurnPolicy::Alternate },
+                    turn_clock: clock.map(|(limit_ms, forfeit)| TurnClock {
+                        limit_ms,
+                        on_timeout: if forfeit { TimeoutAction::Forfeit } else { TimeoutAction::RandomMove },
+                    }),
+                    move_limit,
+                    duplicate_policy: match policy {
+                        0 => DuplicateGuessPolicy::Error,
+                        1 => DuplicateGuessPolicy::IdempotentReturnPrior,
+                        _ => DuplicateGuessPolicy::WastedTurn,
+                    },
+                },
+                first_mover: if initiator { Role::Initiator } else { Role::Responder },
+            }),
+        (any::<u8>(), any::<[u8; 32]>()).prop_map(|(v, d)| Message::Commit { version: v, digest: d }),
+        (any::<u8>(), any::<bool>()).prop_map(|(v, initiator)| Message::Paired {
+            version: v,
+            role: if initiator { Role::Initiator } else { Role::Responder },
+        }),
+        (any::<u8>(), any::<u64>(), any::<u64>()).prop_map(|(v, seq, id)| Message::Lobby {
+            version: v,
+            msg: if id % 2 == 0 {
+                LobbyMessage::JoinGame {
+                    seq,
+                    id,
+                    resume_token: (id % 4 == 0).then_some([id as u8; 16]),
+                }
+            } else {
+                LobbyMessage::Reply {
+                    seq,
+                    result: Err(LobbyError::UnknownGame(id)),
+                }
+            },
+        }),
+        (any::<u8>(), any::<u64>()).prop_map(|(v, seq)| Message::Resync {
+            version: v,
+            seq,
+            payload: None,
+        }),
+        (any::<u8>(), any::<u64>(), any::<bool>()).prop_map(|(v, seq, digest)| Message::ResyncAck {
+            version: v,
+            seq,
+            digest: digest.then_some([v; 32]),
+        }),
+        (any::<u8>(), any::<u64>(), prop::collection::vec((any::<String>(), any::<u8>(), any::<u8>(), any::<bool>()), 0..6))
+            .prop_map(|(v, seq, ships)| Message::PlaceFleet {
+                version: v,
+                seq,
+                placements: ships
+                    .into_iter()
+                    .map(|(ship, row, col, horizontal)| Placement {
+                        ship,
+                        row,
+                        col,
+                        orientation: if horizontal { Orientation::Horizontal } else { Orientation::Vertical },
+                    })
+                    .collect(),
+            }),
+        (any::<u8>(), any::<u64>()).prop_map(|(v, seq)| Message::PlacementAccepted { version: v, seq }),
+        (any::<u8>(), any::<u64>(), prop::collection::vec(any::<String>(), 0..4))
+            .prop_map(|(v, seq, reasons)| Message::PlacementRejected { version: v, seq, reasons }),
+        (any::<u8>(), any::<u64>(), arb_error_code(), any::<String>())
+            .prop_map(|(v, seq, code, detail)| Message::Error { version: v, seq, code, detail }),
+        (any::<u8>(), any::<u64>(), any::<String>())
+            .prop_map(|(v, seq, reason)| Message::Abort { version: v, seq, reason }),
+        (any::<u8>(), any::<u64>(), prop::collection::vec((any::<u8>(), any::<u8>()), 0..6))
+            .prop_map(|(v, seq, shots)| Message::GuessSalvo { version: v, seq, shots }),
+        (any::<u8>(), any::<u64>(), prop::collection::vec(arb_guess_result(), 0..6), any::<Option<u64>>())
+            .prop_map(|(v, seq, results, digest)| Message::SalvoResp { version: v, seq, results, digest }),
+        (any::<u8>(), any::<u64>()).prop_map(|(v, seq)| Message::Resign { version: v, seq }),
+        (
+            any::<u8>(),
+            any::<u64>(),
+            prop::collection::vec((any::<u8>(), any::<u8>()), 0..6),
+            prop::collection::vec(arb_guess_result(), 0..6)
+        )
+            .prop_map(|(v, seq, shots, results)| Message::TurnTimeout { version: v, seq, shots, results }),
+        (any::<u8>(), any::<u32>()).prop_map(|(v, game)| Message::RematchRequest { version: v, game }),
+        (any::<u8>(), any::<u32>()).prop_map(|(v, game)| Message::RematchAccept { version: v, game }),
+        (any::<u8>(), any::<u64>(), arb_game_state(), arb_game_state())
+            .prop_map(|(v, seq, base, state)| Message::ResyncDelta { version: v, seq, delta: state.diff(&base) }),
+    ]
+}
+
+fn arb_error_code() -> impl Strategy<Value = ErrorCode> {
+    prop_oneof![
+        Just(ErrorCode::VersionMismatch),
+        Just(ErrorCode::BadSeq),
+        Just(ErrorCode::InvalidCoordinate),
+        Just(ErrorCode::RuleViolation),
+        Just(ErrorCode::InvalidState),
+        Just(ErrorCode::Throttled),
+        Just(ErrorCode::Unexpected),
+        Just(ErrorCode::Internal),
     ]
 }
 
@@ -68,7 +184,7 @@ fn arb_guess_result() -> impl Strategy<Value = GuessResult> {
     prop_oneof![
         Just(GuessResult::Hit),
         Just(GuessResult::Miss),
-        any::<String>().prop_map(|name| GuessResult::Sink(name)),
+        any::<String>().prop_map(GuessResult::Sink),
     ]
 }
 
@@ -77,6 +193,10 @@ fn arb_game_status() -> impl Strategy<Value = GameStatus> {
         Just(GameStatus::InProgress),
         Just(GameStatus::Won),
         Just(GameStatus::Lost),
+        Just(GameStatus::Desync),
+        Just(GameStatus::Aborted),
+        Just(GameStatus::Forfeited),
+        Just(GameStatus::Draw),
     ]
 }
 
@@ -89,16 +209,18 @@ fn arb_ship() -> impl Strategy<Value = Ship> {
             (any::<u8>(), any::<u8>(), prop_oneof![Just(battleship::Orientation::Horizontal), Just(battleship::Orientation::Vertical)])
                 .prop_map(|(r, c, o)| Some((r, c, o)))
         ],
+        prop::collection::vec(any::<u8>(), 0..6),
     )
-        .prop_map(|(name, sunk, position)| Ship {
+        .prop_map(|(name, sunk, position, hit_segments)| Ship {
             name,
             sunk,
             position,
+            hit_segments,
         })
 }
 
 fn arb_sync_payload() -> impl Strategy<Value = SyncPayload> {
-    (arb_game_state(), any::<[bool; 5]>()).prop_map(|(game_state, enemy_ships_remaining)| {
+    (arb_game_state(), any::<[bool; MAX_SHIPS]>()).prop_map(|(game_state, enemy_ships_remaining)| {
         SyncPayload {
             game_state,
             enemy_ships_remaining,
@@ -110,7 +232,7 @@ fn arb_game_state() -> impl Strategy<Value = GameState> {
     (
         arb_board_state(),
         arb_guess_board_state(),
-        any::<[bool; 5]>(),
+        any::<[bool; MAX_SHIPS]>(),
         any::<usize>(),
     )
         .prop_map(|(my_board, my_guesses, enemy_ships_remaining, enemy_remaining)| GameState {
@@ -123,16 +245,14 @@ fn arb_game_state() -> impl Strategy<Value = GameState> {
 
 fn arb_board_state() -> impl Strategy<Value = BoardState> {
     (
-        prop::array::uniform5(arb_ship_state()),
-        any::<u128>(),
-        any::<u128>(),
-        any::<u128>(),
+        prop::array::uniform::<_, MAX_SHIPS>(arb_ship_state()),
+        arb_board_bits(),
+        arb_board_bits(),
+        arb_board_bits(),
     )
-        .prop_map(|(ship_states, ship_map_bits, hits_bits, misses_bits)| {
-            let ship_map = BitBoard::<u128, 10>::from_raw(ship_map_bits);
-            let hits = BitBoard::<u128, 10>::from_raw(hits_bits);
-            let misses = BitBoard::<u128, 10>::from_raw(misses_bits);
+        .prop_map(|(ship_states, ship_map, hits, misses)| {
             BoardState {
+                size: BOARD_SIZE,
                 ship_states,
                 ship_map,
                 hits,
@@ -151,20 +271,29 @@ fn arb_ship_state() -> impl Strategy<Value = ShipState> {
             (any::<usize>(), any::<usize>(), prop_oneof![Just(battleship::Orientation::Horizontal), Just(battleship::Orientation::Vertical)])
                 .prop_map(|(r, c, o)| Some((r, c, o)))
         ],
+        any::<u16>(),
     )
-        .prop_map(|(name, sunk, position)| {
+        .prop_map(|(name, sunk, position, damage)| {
             let mut state = ShipState::new(name);
             state.sunk = sunk;
             state.position = position;
+            state.damage = damage;
             state
         })
 }
 
 fn arb_guess_board_state() -> impl Strategy<Value = GuessBoardState> {
-    (any::<u128>(), any::<u128>()).prop_map(|(hits_bits, misses_bits)| {
-        let hits = BitBoard::<u128, 10>::from_raw(hits_bits);
-        let misses = BitBoard::<u128, 10>::from_raw(misses_bits);
-        GuessBoardState { hits, misses }
+    (arb_board_bits(), arb_board_bits()).prop_map(|(hits, misses)| GuessBoardState { hits, misses })
+}
+
+fn arb_board_bits() -> impl Strategy<Value = BoardBits> {
+    let size = BOARD_SIZE as usize;
+    prop::collection::vec((0..size, 0..size), 0..size * size).prop_map(|cells| {
+        let mut bits = BoardBits::new();
+        for (r, c) in cells {
+            bits.set(r, c).unwrap();
+        }
+        bits
     })
 }
 
@@ -209,7 +338,7 @@ proptest! {
             let deserialized: Result<SyncPayload, _> = bincode::deserialize(&bytes);
             if let Ok(restored) = deserialized {
                 // Basic sanity checks
-                prop_assert_eq!(restored.enemy_ships_remaining.len(), 5);
+                prop_assert_eq!(restored.enemy_ships_remaining.len(), MAX_SHIPS);
             }
         }
     }
@@ -224,7 +353,7 @@ proptest! {
             let deserialized: Result<GameState, _> = bincode::deserialize(&bytes);
             if let Ok(restored) = deserialized {
                 // Verify array sizes
-                prop_assert_eq!(restored.enemy_ships_remaining.len(), 5);
+                prop_assert_eq!(restored.enemy_ships_remaining.len(), MAX_SHIPS);
             }
         }
     }
@@ -241,6 +370,7 @@ proptest! {
             version,
             seq,
             res: GuessResult::Sink(large_name),
+            digest: None,
         };
         
         let serialized = bincode::serialize(&msg);
@@ -258,8 +388,7 @@ proptest! {
         let msg = Message::Guess {
             version,
             seq,
-            x,
-            y,
+            target: Coord::new(x as usize, y as usize),
         };
         
         let serialized = bincode::serialize(&msg);
@@ -277,8 +406,7 @@ proptest! {
         let msg = Message::Guess {
             version: 1,
             seq,
-            x: 0,
-            y: 0,
+            target: Coord::new(0, 0),
         };
         
         let serialized = bincode::serialize(&msg);
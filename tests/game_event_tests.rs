@@ -0,0 +1,105 @@
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{
+    AiPlayer, EventListener, GameEngine, GameEvent, GameStatus, GuessResult, PlayerNode, Role, NUM_SHIPS,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use tokio::sync::broadcast;
+
+fn drain(rx: &mut broadcast::Receiver<GameEvent>) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        events.push(event);
+    }
+    events
+}
+
+#[tokio::test]
+async fn test_node_publishes_game_lifecycle() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut node1 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(t2));
+    let mut rx1 = node1.subscribe();
+    let mut rx2 = node2.subscribe();
+    let mut rng1 = SmallRng::seed_from_u64(2219);
+    let mut rng2 = SmallRng::seed_from_u64(2220);
+
+    let (r1, r2) = tokio::join!(
+        node1.run(&mut rng1, Role::Initiator),
+        node2.run(&mut rng2, Role::Responder)
+    );
+    r1.unwrap();
+    r2.unwrap();
+
+    for (node, events) in [(&node1, drain(&mut rx1)), (&node2, drain(&mut rx2))] {
+        assert_eq!(events[0], GameEvent::HandshakeComplete);
+        assert_eq!(
+            events[1],
+            GameEvent::GameStarted {
+                first_move: node.first_move().unwrap()
+            }
+        );
+        assert_eq!(
+            events.last(),
+            Some(&GameEvent::GameOver { status: node.status() })
+        );
+
+        let sent = events.iter().filter(|e| matches!(e, GameEvent::GuessSent { .. })).count();
+        let received = events.iter().filter(|e| matches!(e, GameEvent::ResultReceived { .. })).count();
+        assert_eq!(sent, node.guess_count());
+        assert_eq!(received, sent);
+
+        let enemy_sinks = events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::Sink { ours: false, .. }))
+            .count();
+        let own_sinks = events
+            .iter()
+            .filter(|e| matches!(e, GameEvent::Sink { ours: true, .. }))
+            .count();
+        match node.status() {
+            GameStatus::Won => assert_eq!(enemy_sinks, NUM_SHIPS),
+            GameStatus::Lost => assert_eq!(own_sinks, NUM_SHIPS),
+            other => panic!("unexpected final status {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_sink_event_follows_its_result() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut node1 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(t2));
+    let mut rx = node1.subscribe();
+    let mut rng1 = SmallRng::seed_from_u64(7);
+    let mut rng2 = SmallRng::seed_from_u64(8);
+    let (r1, r2) = tokio::join!(
+        node1.run(&mut rng1, Role::Initiator),
+        node2.run(&mut rng2, Role::Responder)
+    );
+    r1.unwrap();
+    r2.unwrap();
+
+    let events = drain(&mut rx);
+    for (i, event) in events.iter().enumerate() {
+        if let GameEvent::Sink { ship, ours } = *event {
+            let expected = match events[i - 1] {
+                GameEvent::ResultReceived { result, .. } => (result, false),
+                GameEvent::OpponentGuess { result, .. } => (result, true),
+                other => panic!("sink after {:?}", other),
+            };
+            assert_eq!(expected, (GuessResult::Sink(ship), ours));
+        }
+    }
+}
+
+#[test]
+fn test_closure_listener() {
+    let mut seen = Vec::new();
+    {
+        let mut listener = |e: &GameEvent| seen.push(*e);
+        listener.on_event(&GameEvent::HandshakeComplete);
+        listener.on_event(&GameEvent::GuessSent { row: 1, col: 2 });
+    }
+    assert_eq!(seen, vec![GameEvent::HandshakeComplete, GameEvent::GuessSent { row: 1, col: 2 }]);
+}
@@ -0,0 +1,201 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    domain::{GameConfig, GuessResult},
+    protocol::{FileStore, GameHost, GameStore, MemoryStore, SavedGame},
+    transport::{in_memory::InMemoryTransport, Transport},
+    Coord, DuplicateGuessPolicy, FleetConfig, GameApi, GameEngine, Message, Orientation, RuleSet, Skeleton, Stub,
+    PROTOCOL_VERSION,
+};
+use std::sync::Arc;
+
+mod common;
+
+/// Connect a stub to `host` over an in-memory pair, serving it in the background.
+fn connect<S: GameStore + 'static>(
+    host: &Arc<GameHost<S>>,
+) -> (Stub<InMemoryTransport>, tokio::task::JoinHandle<anyhow::Result<()>>) {
+    let (client, server) = InMemoryTransport::pair();
+    let host = host.clone();
+    let task = tokio::spawn(async move { host.serve(server).await });
+    (Stub::new(client), task)
+}
+
+#[tokio::test]
+async fn rejoin_continues_where_it_left_off() -> anyhow::Result<()> {
+    let host = Arc::new(GameHost::new(MemoryStore::new())?);
+    let (id, token) = host.create(&common::stacked_engine())?;
+
+    let (mut stub, task) = connect(&host);
+    assert!(stub.rejoin_game(id, token).await?.is_empty());
+    stub.make_guess(Coord::new(0, 0)).await?;
+    let second = stub.make_guess(Coord::new(0, 1)).await?;
+    drop(stub);
+    task.await??;
+
+    // The reply to the last request is replayed, then numbering continues
+    let (mut stub, task) = connect(&host);
+    let replayed = stub.rejoin_game(id, token).await?;
+    match replayed.as_slice() {
+        [Message::StatusResp { seq: 1, res, .. }] => {
+            assert_eq!(format!("{:?}", res), format!("{:?}", second))
+        }
+        other => panic!("Expected replayed StatusResp for seq 1, got {:?}", other),
+    }
+    let third = stub.make_guess(Coord::new(0, 2)).await?;
+    assert!(matches!(third, GuessResult::Hit | GuessResult::Miss | GuessResult::Sink(_)));
+    drop(stub);
+    task.await??;
+
+    let saved = host.store().load(id)?.expect("game saved");
+    assert_eq!(saved.next_seq, 3);
+    let shots = saved.state.my_board.hits.count_ones() + saved.state.my_board.misses.count_ones();
+    assert_eq!(shots, 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn wrong_token_or_unknown_game_is_refused() -> anyhow::Result<()> {
+    let host = Arc::new(GameHost::new(MemoryStore::new())?);
+    let (id, token) = host.create(&common::stacked_engine())?;
+    let mut wrong = token;
+    wrong[0] ^= 1;
+
+    let (stub, task) = connect(&host);
+    assert!(stub.rejoin_game(id, wrong).await.is_err());
+    assert!(task.await?.is_err());
+
+    let (stub, task) = connect(&host);
+    assert!(stub.rejoin_game(id + 1, token).await.is_err());
+    assert!(task.await?.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn file_store_survives_host_restart() -> anyhow::Result<()> {
+    let dir = std::env::temp_dir().join(format!("battleship-host-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let host = Arc::new(GameHost::new(FileStore::new(&dir)?)?);
+    let (id, token) = host.create(&common::stacked_engine())?;
+    let (mut stub, task) = connect(&host);
+    stub.rejoin_game(id, token).await?;
+    stub.make_guess(Coord::new(4, 4)).await?;
+    drop(stub);
+    task.await??;
+    drop(host);
+
+    let host = Arc::new(GameHost::new(FileStore::new(&dir)?)?);
+    assert_eq!(host.store().ids()?, vec![id]);
+    let (mut stub, task) = connect(&host);
+    assert_eq!(stub.rejoin_game(id, token).await?.len(), 1);
+    stub.make_guess(Coord::new(5, 5)).await?;
+    drop(stub);
+    task.await??;
+
+    let (next_id, _) = host.create(&GameEngine::new())?;
+    assert_eq!(next_id, id + 1);
+    host.store().remove(id)?;
+    assert!(host.store().load(id)?.is_none());
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejoin_keeps_custom_rules_and_duplicate_policy() -> anyhow::Result<()> {
+    let fleet = FleetConfig::empty().with_ships("Frigate", 2, 1)?.with_ships("Cruiser", 3, 1)?;
+    let rules = RuleSet::new(8, fleet)?.with_salvo(true);
+    let mut engine = GameEngine::new_with_config(rules).with_duplicate_policy(DuplicateGuessPolicy::IdempotentReturnPrior);
+    engine.board_mut().place(0, 0, 0, Orientation::Horizontal)?;
+    engine.board_mut().place(1, 2, 0, Orientation::Horizontal)?;
+    let host = Arc::new(GameHost::new(MemoryStore::new())?);
+    let (id, token) = host.create(&engine)?;
+
+    let (mut stub, task) = connect(&host);
+    stub.rejoin_game(id, token).await?;
+    let first = stub.make_salvo(vec![(0, 0), (2, 0)]).await?;
+    assert!(matches!(first.as_slice(), [GuessResult::Hit, GuessResult::Hit]));
+    drop(stub);
+    task.await??;
+
+    // Restored as a two-ship salvo game that answers repeats with the prior result
+    let (mut stub, task) = connect(&host);
+    stub.rejoin_game(id, token).await?;
+    let second = stub.make_salvo(vec![(0, 1), (0, 0)]).await?;
+    match second.as_slice() {
+        [GuessResult::Sink(name), GuessResult::Hit] => assert_eq!(name, "Frigate"),
+        other => panic!("Expected the Frigate sunk and a repeated hit, got {:?}", other),
+    }
+    drop(stub);
+    task.await??;
+
+    let saved = host.store().load(id)?.expect("game saved");
+    assert_eq!(saved.config.rules(), Some(rules));
+    assert_eq!(saved.config.duplicate_policy, DuplicateGuessPolicy::IdempotentReturnPrior);
+    Ok(())
+}
+
+#[tokio::test]
+async fn skeleton_checkpoints_every_accepted_request() -> anyhow::Result<()> {
+    let store: Arc<dyn GameStore> = Arc::new(MemoryStore::new());
+    let engine = common::stacked_engine();
+    let saved = SavedGame {
+        state: engine.state(),
+        config: GameConfig::from(&engine),
+        next_seq: 0,
+        last_response: None,
+        resume_token: [9; 16],
+    };
+    store.save(3, &saved)?;
+
+    let (mut client, server) = InMemoryTransport::pair();
+    let mut skeleton = Skeleton::restore(3, store.clone(), server)?;
+    skeleton.handle(Message::Guess { version: PROTOCOL_VERSION, seq: 0, target: Coord::new(2, 3) }).await?;
+    let reply = client.recv().await?;
+    // A stale request is refused and changes nothing
+    skeleton.handle(Message::Guess { version: PROTOCOL_VERSION, seq: 0, target: Coord::new(4, 4) }).await?;
+    client.recv().await?;
+    // Crash: the skeleton goes away without any shutdown step
+    drop(skeleton);
+
+    let saved = store.load(3)?.expect("checkpoint saved");
+    assert_eq!(saved.next_seq, 1);
+    assert_eq!(saved.resume_token, [9; 16]);
+    assert_eq!(format!("{:?}", saved.last_response), format!("{:?}", Some(reply)));
+    let shots = saved.state.my_board.hits.count_ones() + saved.state.my_board.misses.count_ones();
+    assert_eq!(shots, 1);
+
+    let (mut client, server) = InMemoryTransport::pair();
+    let mut skeleton = Skeleton::restore(3, store.clone(), server)?;
+    assert_eq!(skeleton.next_seq(), 1);
+    skeleton.handle(Message::Guess { version: PROTOCOL_VERSION, seq: 1, target: Coord::new(2, 4) }).await?;
+    assert!(matches!(client.recv().await?, Message::StatusResp { seq: 1, .. }));
+    assert_eq!(store.load(3)?.expect("checkpoint saved").next_seq, 2);
+
+    assert!(Skeleton::restore(4, store, InMemoryTransport::pair().1).is_err());
+    Ok(())
+}
+
+#[test]
+fn restore_refuses_a_saved_state_that_breaks_its_rules() -> anyhow::Result<()> {
+    let store: Arc<dyn GameStore> = Arc::new(MemoryStore::new());
+    let engine = common::stacked_engine();
+    let mut saved = SavedGame {
+        state: engine.state(),
+        config: GameConfig::from(&engine),
+        next_seq: 0,
+        last_response: None,
+        resume_token: [9; 16],
+    };
+    // The carrier runs off the right edge
+    saved.state.my_board.ship_states[0].position = Some((0, 8, Orientation::Horizontal));
+    store.save(5, &saved)?;
+    assert!(Skeleton::restore(5, store.clone(), InMemoryTransport::pair().1).is_err());
+
+    // A layout for a smaller board than the saved rules
+    saved.state = common::stack(GameEngine::new_with_config(RuleSet::with_board_size(8)?)).state();
+    store.save(6, &saved)?;
+    assert!(Skeleton::restore(6, store, InMemoryTransport::pair().1).is_err());
+    Ok(())
+}
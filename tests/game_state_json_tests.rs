@@ -0,0 +1,81 @@
+use battleship::{GameEngine, GuessResult, Orientation, JSON_SCHEMA_VERSION, NUM_SHIPS};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+fn played_engine() -> GameEngine {
+    let mut rng = SmallRng::seed_from_u64(2217);
+    let mut engine = GameEngine::new();
+    engine.board_mut().place_all_random(&mut rng).unwrap();
+    let ship_cell = engine.board().ship_map().iter_set_bits().next().unwrap();
+    engine.opponent_guess(ship_cell.0, ship_cell.1).unwrap();
+    let water = (0..10)
+        .flat_map(|r| (0..10).map(move |c| (r, c)))
+        .find(|&(r, c)| !engine.board().ship_map().get(r, c).unwrap())
+        .unwrap();
+    engine.opponent_guess(water.0, water.1).unwrap();
+    engine.record_guess(4, 4, GuessResult::Hit).unwrap();
+    engine.record_guess(4, 5, GuessResult::Sink("Destroyer")).unwrap();
+    engine.record_guess(0, 9, GuessResult::Miss).unwrap();
+    engine
+}
+
+#[test]
+fn test_json_round_trip() {
+    let engine = played_engine();
+    let json = engine.to_json().unwrap();
+    let restored = GameEngine::from_json(&json).unwrap();
+    assert_eq!(restored.state(), engine.state());
+    assert_eq!(restored.to_json().unwrap(), json);
+}
+
+#[test]
+fn test_json_schema_shape() {
+    let engine = played_engine();
+    let value: serde_json::Value = serde_json::from_str(&engine.to_json().unwrap()).unwrap();
+
+    assert_eq!(value["schema_version"], JSON_SCHEMA_VERSION);
+    let ships = value["my_board"]["ships"].as_array().unwrap();
+    assert_eq!(ships.len(), NUM_SHIPS);
+    assert_eq!(ships[0]["name"], "Carrier");
+    assert!(ships[0]["position"]["orientation"].is_string());
+    assert_eq!(value["my_board"]["hits"].as_array().unwrap().len(), 1);
+    assert_eq!(value["my_guesses"]["hits"], serde_json::json!([[4, 4], [4, 5]]));
+    assert_eq!(value["my_guesses"]["misses"], serde_json::json!([[0, 9]]));
+    assert_eq!(value["enemy_ships_remaining"][4], false);
+    assert_eq!(value["enemy_remaining"], 15);
+}
+
+#[test]
+fn test_json_recomputes_sunk_flags() {
+    let mut engine = GameEngine::new();
+    engine.board_mut().place(4, 0, 0, Orientation::Horizontal).unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&engine.to_json().unwrap()).unwrap();
+    value["my_board"]["hits"] = serde_json::json!([[0, 0], [0, 1]]);
+    let restored = GameEngine::from_json(&value.to_string()).unwrap();
+    assert!(restored.board().ship_states()[4].sunk);
+}
+
+#[test]
+fn test_json_rejects_inconsistent_input() {
+    let engine = played_engine();
+    let base: serde_json::Value = serde_json::from_str(&engine.to_json().unwrap()).unwrap();
+
+    let mut wrong_version = base.clone();
+    wrong_version["schema_version"] = serde_json::json!(99);
+    let err = GameEngine::from_json(&wrong_version.to_string()).err().unwrap();
+    assert!(err.to_string().contains("schema version"), "{}", err);
+
+    let mut overlapping = base.clone();
+    overlapping["my_board"]["ships"][1]["position"] = overlapping["my_board"]["ships"][0]["position"].clone();
+    assert!(GameEngine::from_json(&overlapping.to_string()).is_err());
+
+    let mut off_board = base.clone();
+    off_board["my_guesses"]["hits"] = serde_json::json!([[10, 0]]);
+    assert!(GameEngine::from_json(&off_board.to_string()).is_err());
+
+    let mut short_fleet = base;
+    short_fleet["enemy_ships_remaining"] = serde_json::json!([true]);
+    assert!(GameEngine::from_json(&short_fleet.to_string()).is_err());
+
+    assert!(GameEngine::from_json("not json").is_err());
+}
@@ -1,4 +1,4 @@
-use battleship::{GameEngine, GameState, NUM_SHIPS};
+use battleship::{BoardBits, GameEngine, GameState, GuessBoardState, StateDelta, MAX_BOARD_SIZE, MAX_SHIPS, NUM_SHIPS};
 use proptest::prelude::*;
 use rand::rngs::SmallRng;
 use rand::SeedableRng;
@@ -8,7 +8,7 @@ proptest! {
     fn game_state_roundtrip(seed in any::<u64>()) {
         let mut rng = SmallRng::seed_from_u64(seed);
         let mut engine = GameEngine::new();
-        for i in 0..NUM_SHIPS as usize {
+        for i in 0..NUM_SHIPS {
             let (r, c, o) = engine.board().random_placement(&mut rng, i).unwrap();
             engine.board_mut().place(i, r, c, o).unwrap();
         }
@@ -19,3 +19,67 @@ proptest! {
         assert_eq!(engine.state(), restored.state());
     }
 }
+
+fn guesses(hits: &[(usize, usize)], misses: &[(usize, usize)]) -> GuessBoardState {
+    let mut state = GuessBoardState { hits: BoardBits::new(), misses: BoardBits::new() };
+    for &(r, c) in hits {
+        state.hits.set(r, c).unwrap();
+    }
+    for &(r, c) in misses {
+        state.misses.set(r, c).unwrap();
+    }
+    state
+}
+
+#[test]
+fn board_bits_encode_the_same_in_every_build() {
+    // Rows as u16 masks, column 0 lowest, trailing empty rows left off.
+    let state = guesses(&[(0, 0), (0, 9), (2, 3)], &[]);
+    let bytes = bincode::serialize(&state).unwrap();
+    let expected: Vec<u8> = [
+        &3u64.to_le_bytes()[..],
+        &0x0201u16.to_le_bytes(),
+        &0u16.to_le_bytes(),
+        &0x0008u16.to_le_bytes(),
+        &0u64.to_le_bytes(),
+    ]
+    .concat();
+    assert_eq!(bytes, expected);
+    assert_eq!(bincode::deserialize::<GuessBoardState>(&bytes).unwrap(), state);
+}
+
+#[test]
+fn board_bits_off_the_largest_board_are_rejected() {
+    let size = MAX_BOARD_SIZE as usize;
+    let too_many_rows: Vec<u8> =
+        [&(size as u64 + 1).to_le_bytes()[..], &vec![0u8; 2 * (size + 1)], &0u64.to_le_bytes()].concat();
+    assert!(bincode::deserialize::<GuessBoardState>(&too_many_rows).is_err());
+
+    let too_wide: Vec<u8> = [&1u64.to_le_bytes()[..], &(1u16 << size).to_le_bytes(), &0u64.to_le_bytes()].concat();
+    assert!(bincode::deserialize::<GuessBoardState>(&too_wide).is_err());
+}
+
+fn delta(enemy_ships_remaining: [bool; MAX_SHIPS]) -> StateDelta {
+    let empty = BoardBits::new();
+    StateDelta { base: 7, hits: empty, misses: empty, guess_hits: empty, guess_misses: empty, enemy_ships_remaining }
+}
+
+#[test]
+fn fleet_slots_encode_the_same_in_every_build() {
+    // Slots up to the last ship afloat, whatever MAX_SHIPS is.
+    let mut afloat = [false; MAX_SHIPS];
+    afloat[0] = true;
+    afloat[1] = true;
+    afloat[3] = true;
+    let bytes = bincode::serialize(&delta(afloat)).unwrap();
+    let expected: Vec<u8> = [&7u64.to_le_bytes()[..], &[0u8; 32], &4u64.to_le_bytes(), &[1, 1, 0, 1]].concat();
+    assert_eq!(bytes, expected);
+    assert_eq!(bincode::deserialize::<StateDelta>(&bytes).unwrap(), delta(afloat));
+}
+
+#[test]
+fn fleet_slots_past_the_largest_fleet_are_rejected() {
+    let too_many: Vec<u8> =
+        [&7u64.to_le_bytes()[..], &[0u8; 32], &(MAX_SHIPS as u64 + 1).to_le_bytes(), &[1u8; MAX_SHIPS + 1]].concat();
+    assert!(bincode::deserialize::<StateDelta>(&too_many).is_err());
+}
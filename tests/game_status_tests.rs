@@ -0,0 +1,69 @@
+use battleship::{GameEngine, GameStatus, GuessResult, NUM_SHIPS, SHIPS, TOTAL_SHIP_CELLS};
+
+/// Cells (row-major from the origin) used as stand-in enemy hits.
+fn cell(i: usize) -> (usize, usize) {
+    (i / 10, i % 10)
+}
+
+/// An engine whose own fleet is placed, so it can never be `Lost` here.
+fn engine() -> GameEngine {
+    let mut engine = GameEngine::new();
+    for i in 0..NUM_SHIPS {
+        engine
+            .board_mut()
+            .place(i, 9 - i, 0, battleship::Orientation::Horizontal)
+            .unwrap();
+    }
+    engine
+}
+
+#[test]
+fn test_won_when_every_ship_reported_sunk() {
+    let mut engine = engine();
+    let mut next = 0;
+    for ship in SHIPS.iter() {
+        for _ in 1..ship.length() {
+            let (r, c) = cell(next);
+            engine.record_guess(r, c, GuessResult::Hit).unwrap();
+            next += 1;
+            assert_eq!(engine.status(), GameStatus::InProgress);
+        }
+        let (r, c) = cell(next);
+        engine.record_guess(r, c, GuessResult::Sink(ship.name())).unwrap();
+        next += 1;
+    }
+    assert_eq!(next, TOTAL_SHIP_CELLS);
+    assert_eq!(engine.status(), GameStatus::Won);
+}
+
+#[test]
+fn test_sinks_before_enough_hits_is_desync() {
+    let mut engine = engine();
+    for (i, ship) in SHIPS.iter().enumerate() {
+        let (r, c) = cell(i);
+        engine.record_guess(r, c, GuessResult::Sink(ship.name())).unwrap();
+    }
+    assert_eq!(engine.status(), GameStatus::Desync);
+}
+
+#[test]
+fn test_all_cells_hit_without_sinks_is_desync() {
+    let mut engine = engine();
+    for i in 0..TOTAL_SHIP_CELLS {
+        let (r, c) = cell(i);
+        engine.record_guess(r, c, GuessResult::Hit).unwrap();
+    }
+    assert_eq!(engine.status(), GameStatus::Desync);
+}
+
+#[test]
+// The edited counter no longer matches the hits recorded
+#[cfg_attr(feature = "debug-invariants", should_panic(expected = "invariant violated"))]
+fn test_desync_survives_state_round_trip() {
+    let mut engine = engine();
+    let (r, c) = cell(0);
+    engine.record_guess(r, c, GuessResult::Hit).unwrap();
+    let mut state = engine.state();
+    state.enemy_remaining = 0;
+    assert_eq!(GameEngine::from_state(state).status(), GameStatus::Desync);
+}
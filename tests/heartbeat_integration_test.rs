@@ -1,6 +1,6 @@
 /// Integration test: Full AI vs AI game with active heartbeat monitoring
 use battleship::{
-    AiPlayer, GameEngine, PlayerNode, HeartbeatTransport, TcpTransport, Player,
+    AiPlayer, GameEngine, PlayerNode, HeartbeatTransport, TcpTransport, Player, Role,
 };
 use tokio::net::TcpListener;
 use tokio::time::Duration;
@@ -27,7 +27,7 @@ async fn test_full_game_with_heartbeat() {
         ai.place_ships(&mut rng, engine.board_mut()).unwrap();
 
         let mut node = PlayerNode::new(Box::new(ai), engine, Box::new(transport));
-        node.run(&mut rng, true).await
+        node.run(&mut rng, Role::Initiator).await
     });
 
     let client_task = tokio::spawn(async move {
@@ -44,7 +44,7 @@ async fn test_full_game_with_heartbeat() {
         ai.place_ships(&mut rng, engine.board_mut()).unwrap();
 
         let mut node = PlayerNode::new(Box::new(ai), engine, Box::new(transport));
-        node.run(&mut rng, false).await
+        node.run(&mut rng, Role::Responder).await
     });
 
     // Both should complete successfully with heartbeats running in background
@@ -73,7 +73,7 @@ async fn test_heartbeat_keeps_connection_alive() {
         ai.place_ships(&mut rng, engine.board_mut()).unwrap();
 
         let mut node = PlayerNode::new(Box::new(ai), engine, Box::new(transport));
-        node.run(&mut rng, true).await
+        node.run(&mut rng, Role::Initiator).await
     });
 
     let client_task = tokio::spawn(async move {
@@ -93,7 +93,7 @@ async fn test_heartbeat_keeps_connection_alive() {
         ai.place_ships(&mut rng, engine.board_mut()).unwrap();
 
         let mut node = PlayerNode::new(Box::new(ai), engine, Box::new(transport));
-        node.run(&mut rng, false).await
+        node.run(&mut rng, Role::Responder).await
     });
 
     // Game should complete despite aggressive idle timeout because heartbeats keep it alive
@@ -117,7 +117,7 @@ async fn test_disabled_heartbeat_with_game() {
         ai.place_ships(&mut rng, engine.board_mut()).unwrap();
 
         let mut node = PlayerNode::new(Box::new(ai), engine, Box::new(transport1));
-        node.run(&mut rng, true).await
+        node.run(&mut rng, Role::Initiator).await
     });
 
     let game2 = tokio::spawn(async move {
@@ -127,7 +127,7 @@ async fn test_disabled_heartbeat_with_game() {
         ai.place_ships(&mut rng, engine.board_mut()).unwrap();
 
         let mut node = PlayerNode::new(Box::new(ai), engine, Box::new(transport2));
-        node.run(&mut rng, false).await
+        node.run(&mut rng, Role::Responder).await
     });
 
     // Game should complete successfully with disabled heartbeat
@@ -0,0 +1,107 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    AiPlayer, Board, BoardError, BoardState, DuplicateGuessPolicy, GameEngine, GameStatus, GuessResult, Move, Orientation, Player,
+    ReplayError, RuleSet, Shooter,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+/// Two AI engines play a full game; returns the engines and their fleets
+/// as placed before the first shot.
+fn play(seed: u64) -> ([GameEngine; 2], [BoardState; 2]) {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut players = [AiPlayer::new(), AiPlayer::new()];
+    let mut engines = [GameEngine::new(), GameEngine::new()];
+    for (player, engine) in players.iter_mut().zip(engines.iter_mut()) {
+        player.place_ships(&mut rng, engine.board_mut()).unwrap();
+    }
+    let fleets = [BoardState::from(engines[0].board()), BoardState::from(engines[1].board())];
+
+    let mut shooter = 0;
+    while engines[shooter].status() == GameStatus::InProgress {
+        let defender = 1 - shooter;
+        let target = players[shooter].select_target(
+            &mut rng,
+            &engines[shooter].guess_hits(),
+            &engines[shooter].guess_misses(),
+            &engines[shooter].enemy_ship_lengths_remaining(),
+        );
+        let res = engines[defender].opponent_guess(target.row, target.col).unwrap();
+        engines[shooter].record_guess(target.row, target.col, res).unwrap();
+        players[shooter].handle_guess_result(target, res);
+        shooter = defender;
+    }
+    (engines, fleets)
+}
+
+fn fleet_board(state: BoardState) -> Board {
+    Board::from_state(state, &RuleSet::STANDARD)
+}
+
+#[test]
+fn test_history_logs_both_directions_in_order() {
+    let mut engine = GameEngine::new();
+    engine.board_mut().place(4, 0, 0, Orientation::Horizontal).unwrap();
+    assert!(engine.history().is_empty());
+
+    engine.record_guess(5, 5, GuessResult::Miss).unwrap();
+    engine.opponent_guess(0, 0).unwrap();
+    engine.opponent_guess(0, 1).unwrap();
+    let logged: Vec<_> = engine.history().iter().map(|m| (m.shooter, m.row, m.col, m.result)).collect();
+    assert_eq!(
+        logged,
+        [
+            (Shooter::Us, 5, 5, GuessResult::Miss),
+            (Shooter::Opponent, 0, 0, GuessResult::Hit),
+            (Shooter::Opponent, 0, 1, GuessResult::Sink("Destroyer")),
+        ]
+    );
+
+    // Repeats change nothing and are not logged
+    let mut engine = engine.with_duplicate_policy(DuplicateGuessPolicy::WastedTurn);
+    engine.opponent_guess(0, 0).unwrap();
+    engine.record_guess(5, 5, GuessResult::Hit).unwrap();
+    assert_eq!(engine.history().len(), 3);
+}
+
+#[test]
+fn test_replay_reconstructs_a_full_game() {
+    for seed in 0..5 {
+        let (engines, fleets) = play(seed);
+        for (engine, fleet) in engines.iter().zip(fleets) {
+            let moves = engine.history().as_slice();
+            assert!(moves.len() <= battleship::MAX_MOVES);
+            let replayed = GameEngine::replay(fleet_board(fleet), moves).unwrap();
+            assert_eq!(replayed.state(), engine.state());
+            assert_eq!(replayed.status(), engine.status());
+            assert_eq!(replayed.history(), engine.history());
+            assert_eq!(replayed.defense_report(), engine.defense_report());
+        }
+    }
+}
+
+#[test]
+fn test_replay_reports_where_the_log_diverges() {
+    let (engines, fleets) = play(7);
+    let mut moves: Vec<Move> = engines[0].history().iter().copied().collect();
+    let index = moves.iter().position(|m| m.shooter == Shooter::Opponent).unwrap();
+    let actual = moves[index].result;
+    let logged = if actual == GuessResult::Miss { GuessResult::Hit } else { GuessResult::Miss };
+    moves[index].result = logged;
+    assert_eq!(
+        GameEngine::replay(fleet_board(fleets[0]), &moves).err(),
+        Some(ReplayError::ResultMismatch { index, logged, actual })
+    );
+
+    let mut moves: Vec<Move> = engines[0].history().iter().copied().collect();
+    let first_ours = moves.iter().position(|m| m.shooter == Shooter::Us).unwrap();
+    moves.insert(first_ours + 1, moves[first_ours]);
+    assert_eq!(
+        GameEngine::replay(fleet_board(fleets[0]), &moves).err(),
+        Some(ReplayError::Rejected {
+            index: first_ours + 1,
+            error: BoardError::AlreadyGuessed
+        })
+    );
+}
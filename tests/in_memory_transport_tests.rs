@@ -1,17 +1,17 @@
 use battleship::transport::in_memory::InMemoryTransport;
 use battleship::protocol::GameApi;
 use battleship::domain::{GuessResult, GameStatus, Ship, SyncPayload};
-use battleship::{GameState, GuessBoardState, BoardState, BitBoard, Skeleton, Stub};
+use battleship::{Board, Coord, GameState, GuessBoardState, BoardState, BoardBits, Skeleton, Stub, MAX_SHIPS};
 
 struct DummyEngine;
 
 #[async_trait::async_trait]
 impl GameApi for DummyEngine {
-    async fn make_guess(&mut self, _x: u8, _y: u8) -> anyhow::Result<GuessResult> {
+    async fn make_guess(&mut self, _target: Coord) -> anyhow::Result<GuessResult> {
         Ok(GuessResult::Hit)
     }
     async fn get_ship_status(&self, _ship_id: usize) -> anyhow::Result<Ship> {
-        Ok(Ship { name: "dummy".to_string(), sunk: false, position: None })
+        Ok(Ship { name: "dummy".to_string(), sunk: false, position: None, hit_segments: Vec::new() })
     }
     async fn sync_state(&mut self, _payload: SyncPayload) -> anyhow::Result<()> {
         Ok(())
@@ -33,7 +33,7 @@ async fn test_stub_skeleton_in_memory() -> anyhow::Result<()> {
 
     let mut stub = Stub::new(client_transport);
 
-    let res = stub.make_guess(1, 2).await?;
+    let res = stub.make_guess(Coord::new(1, 2)).await?;
     assert!(matches!(res, GuessResult::Hit));
 
     let ship = stub.get_ship_status(0).await?;
@@ -43,25 +43,20 @@ async fn test_stub_skeleton_in_memory() -> anyhow::Result<()> {
     let sync_payload = SyncPayload {
         game_state: GameState {
             my_board: BoardState {
-                ship_states: [
-                    battleship::ShipState::new("Carrier"),
-                    battleship::ShipState::new("Battleship"),
-                    battleship::ShipState::new("Cruiser"),
-                    battleship::ShipState::new("Submarine"),
-                    battleship::ShipState::new("Destroyer"),
-                ],
-                ship_map: BitBoard::<u128, 10>::new(),
-                hits: BitBoard::<u128, 10>::new(),
-                misses: BitBoard::<u128, 10>::new(),
+                size: 10,
+                ship_states: Board::new().ship_states(),
+                ship_map: BoardBits::new(),
+                hits: BoardBits::new(),
+                misses: BoardBits::new(),
             },
             my_guesses: GuessBoardState {
-                hits: BitBoard::<u128, 10>::new(),
-                misses: BitBoard::<u128, 10>::new(),
+                hits: BoardBits::new(),
+                misses: BoardBits::new(),
             },
-            enemy_ships_remaining: [true; 5],
+            enemy_ships_remaining: [true; MAX_SHIPS],
             enemy_remaining: 17,
         },
-        enemy_ships_remaining: [true; 5],
+        enemy_ships_remaining: [true; MAX_SHIPS],
     };
     stub.sync_state(sync_payload).await?;
 
@@ -0,0 +1,77 @@
+use battleship::{
+    hit_probabilities, info_gain_scores, pick_target, shot_entropy, AiConfig, BoardBits, FleetConfig, InfoGain,
+    TargetingStrategy, TargetingStyle,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+type BB = BoardBits;
+
+#[test]
+fn test_shot_entropy_peaks_at_even_odds() {
+    assert_eq!(shot_entropy(0.5), 1.0);
+    assert_eq!((shot_entropy(0.0), shot_entropy(1.0)), (0.0, 0.0));
+    assert!((shot_entropy(0.25) - shot_entropy(0.75)).abs() < 1e-12);
+    assert!(shot_entropy(0.3) > shot_entropy(0.1));
+}
+
+/// Only the Destroyer is afloat, with one hit at (9, 0) and every other
+/// cell but (9, 1) and (8, 0) shot: two layouts are left.
+fn destroyer_endgame() -> (BB, BB, [usize; 5]) {
+    let hits = common::wrecks(&[(1, 5), (3, 4), (5, 3), (7, 3)]) | common::cells(&[(9, 0)]);
+    (hits, common::misses_except(hits, &[(9, 1), (8, 0)]), [0, 0, 0, 0, 2])
+}
+
+#[test]
+fn test_scores_follow_the_hit_chance() {
+    let (hits, misses, remaining) = destroyer_endgame();
+    let pdf = common::exact_pdf(&hits, &misses, &remaining).unwrap();
+    let unseen = FleetConfig::STANDARD.total_cells() - hits.count_ones();
+    assert_eq!(unseen, 1);
+
+    let probs = hit_probabilities(&pdf, unseen);
+    assert_eq!((probs[9][1], probs[8][0], probs[0][9]), (0.5, 0.5, 0.0));
+    let scores = info_gain_scores(&pdf, unseen);
+    assert_eq!((scores[9][1], scores[8][0], scores[9][0]), (1.0, 1.0, 0.0));
+
+    // With nothing left to learn the sure hit is still worth a shot
+    let mut settled = pdf;
+    settled[9][1] = 1.0;
+    settled[8][0] = 0.0;
+    assert_eq!(info_gain_scores(&settled, unseen), settled);
+}
+
+#[test]
+fn test_info_gain_prefers_uncertain_cells() {
+    // The Submarine can only lie on (0, 0)-(0, 2); the Destroyer, hit at
+    // (4, 4), runs on to (4, 5) or (5, 4)
+    let sunk = common::wrecks(&[(1, 5), (3, 4), (5, 3)]);
+    let hits = sunk | common::cells(&[(4, 4)]);
+    let misses = common::misses_except(hits, &[(0, 0), (0, 1), (0, 2), (4, 5), (5, 4)]);
+    let remaining = [0, 0, 0, 3, 2];
+    let view = common::standard_view(&hits, &sunk, &misses, &remaining);
+    let config = AiConfig { use_exact_solver: true, ..AiConfig::default() };
+    let scores = InfoGain.density(&mut SmallRng::seed_from_u64(1), &view, &config);
+    assert_eq!((scores[4][5], scores[5][4]), (1.0, 1.0));
+    assert_eq!((scores[0][0], scores[0][1], scores[4][4]), (0.0, 0.0, 0.0));
+}
+
+#[test]
+fn test_info_gain_sinks_a_fleet() {
+    let mut rng = SmallRng::seed_from_u64(5);
+    let mut duel = common::Duel::new(&mut rng);
+    let config = AiConfig { mc_samples: 20, use_exact_solver: true, ..AiConfig::default() };
+    for _ in 0..100 {
+        let (hits, sunk, misses, remaining) = duel.seen();
+        let view = common::standard_view(&hits, &sunk, &misses, &remaining);
+        let scores = TargetingStyle::InfoGain.density(&mut rng, &view, &config);
+        let (row, col) = pick_target(&scores, &config, &mut rng);
+        duel.fire(row, col);
+        if duel.won() {
+            return;
+        }
+    }
+    panic!("fleet not sunk in 100 shots");
+}
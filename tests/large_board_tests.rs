@@ -0,0 +1,130 @@
+#![cfg(all(feature = "std", feature = "large-boards"))]
+
+use battleship::domain::GameConfig;
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{
+    AiPlayer, BoardError, Coord, GameEngine, GameStatus, GuessResult, Orientation, Player, PlayerNode, Role, RuleSet,
+    BOARD_SIZE, MAX_BOARD_SIZE,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+fn rules(size: u8) -> RuleSet {
+    RuleSet::with_board_size(size).unwrap()
+}
+
+/// Play two AIs against each other under `rules`, checking every shot
+/// lands on the board. Returns whether any shot landed past the 10×10
+/// corner of the board.
+fn play_ai_game(rules: RuleSet, seed: u64) -> bool {
+    let size = rules.board_size() as usize;
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut players = [AiPlayer::new(), AiPlayer::new()];
+    let mut engines = [GameEngine::new_with_config(rules), GameEngine::new_with_config(rules)];
+    for (player, engine) in players.iter_mut().zip(engines.iter_mut()) {
+        player.place_ships(&mut rng, engine.board_mut()).unwrap();
+        assert!((engine.board().ship_map() & rules.off_board()).is_empty());
+    }
+
+    let mut beyond_standard = false;
+    let mut shooter = 0;
+    for _ in 0..2 * size * size {
+        let defender = 1 - shooter;
+        let target = players[shooter].select_target(
+            &mut rng,
+            &engines[shooter].guess_hits(),
+            &engines[shooter].blocked_cells(),
+            &engines[shooter].enemy_ship_lengths_remaining(),
+        );
+        assert!(rules.contains(target.row, target.col), "aimed off the board at {:?}", target);
+        beyond_standard |= target.row >= BOARD_SIZE as usize || target.col >= BOARD_SIZE as usize;
+        let res = engines[defender].opponent_guess(target.row, target.col).unwrap();
+        engines[shooter].record_guess(target.row, target.col, res).unwrap();
+        players[shooter].handle_guess_result(target, res);
+        if engines[defender].status() == GameStatus::Lost {
+            assert_eq!(engines[shooter].status(), GameStatus::Won);
+            return beyond_standard;
+        }
+        shooter = defender;
+    }
+    panic!("game on a {}x{} board did not finish", size, size);
+}
+
+#[test]
+fn test_rule_set_accepts_twelve_by_twelve() {
+    assert_eq!(MAX_BOARD_SIZE, 12);
+    assert_eq!(RuleSet::STANDARD.board_size(), BOARD_SIZE);
+    assert_eq!(RuleSet::STANDARD.off_board().count_ones(), 144 - 100);
+    assert!(rules(12).off_board().is_empty());
+    assert_eq!(RuleSet::with_board_size(13), Err(BoardError::UnsupportedBoardSize(13)));
+    assert_eq!("L12".parse::<Coord>(), Ok(Coord::new(11, 11)));
+}
+
+#[test]
+fn test_large_board_bounds_placement_and_guesses() {
+    let mut engine = GameEngine::new_with_config(rules(12));
+    engine.board_mut().place(0, 11, 7, Orientation::Horizontal).unwrap();
+    assert_eq!(
+        engine.board_mut().place(1, 0, 9, Orientation::Horizontal),
+        Err(BoardError::ShipOutOfBounds)
+    );
+    assert_eq!(engine.opponent_guess(11, 7).unwrap(), GuessResult::Hit);
+    assert!(engine.opponent_guess(12, 0).is_err());
+    engine.record_guess(11, 11, GuessResult::Miss).unwrap();
+    assert!(engine.guess_misses().get(11, 11).unwrap());
+}
+
+#[test]
+fn test_ai_game_on_large_board() {
+    assert!(play_ai_game(rules(12), 3501), "no shot reached past the 10x10 corner");
+}
+
+#[test]
+fn test_ai_game_on_standard_board_in_wide_storage() {
+    assert!(!play_ai_game(RuleSet::STANDARD, 3501));
+}
+
+#[test]
+fn test_large_board_survives_state_and_json() {
+    let mut rng = SmallRng::seed_from_u64(12);
+    let mut engine = GameEngine::new_with_config(rules(12));
+    engine.board_mut().place_all_random(&mut rng).unwrap();
+    engine.record_guess(11, 11, GuessResult::Miss).unwrap();
+
+    let restored = GameEngine::from_state(engine.state());
+    assert_eq!(restored.rules(), engine.rules());
+
+    let json = engine.to_json().unwrap();
+    assert!(json.contains("\"board_size\":12"));
+    let restored = GameEngine::from_json(&json).unwrap();
+    assert_eq!(restored.state(), engine.state());
+}
+
+#[tokio::test]
+async fn test_nodes_play_a_large_board_sent_in_the_config() {
+    // The responder only learns the board size from the config on the wire
+    let bytes = bincode::serialize(&GameConfig::from(&rules(12))).unwrap();
+    let received: GameConfig = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(received.rules(), Some(rules(12)));
+    let responder_engine = received.builder().unwrap().build().unwrap();
+
+    let node = |seed, mut engine: GameEngine, transport: InMemoryTransport| {
+        let mut player = AiPlayer::new();
+        player.place_ships(&mut SmallRng::seed_from_u64(seed), engine.board_mut()).unwrap();
+        PlayerNode::new(Box::new(player), engine, Box::new(transport))
+    };
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut initiator = node(1, GameEngine::new_with_config(rules(12)), t1);
+    let mut responder = node(2, responder_engine, t2);
+    let mut rng1 = SmallRng::seed_from_u64(1);
+    let mut rng2 = SmallRng::seed_from_u64(2);
+    let (a, b) = tokio::join!(
+        initiator.run(&mut rng1, Role::Initiator),
+        responder.run(&mut rng2, Role::Responder)
+    );
+    a.unwrap();
+    b.unwrap();
+    let mut statuses = [initiator.status(), responder.status()];
+    statuses.sort_by_key(|status| *status == GameStatus::Won);
+    assert_eq!(statuses, [GameStatus::Lost, GameStatus::Won]);
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    daemon::{self, DaemonConfig},
+    domain::GameConfig,
+    protocol::auth::{issue_ticket, LobbyAuth, LobbyGate, RateLimit},
+    protocol::lobby::{LobbyError, LobbyMessage, LobbyReply},
+    transport::tcp::TcpTransport,
+    HeartbeatTransport, Stub,
+};
+use std::collections::HashMap;
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::Duration;
+
+fn tokens() -> LobbyAuth {
+    LobbyAuth::Tokens(HashMap::from([("s3cret".to_string(), "alice".to_string())]))
+}
+
+fn refusal(reply: Option<LobbyMessage>) -> LobbyError {
+    match reply {
+        Some(LobbyMessage::Reply { result: Err(e), .. }) => e,
+        other => panic!("Expected refusal, got {:?}", other),
+    }
+}
+
+#[test]
+fn tokens_map_to_identities() {
+    let auth = tokens();
+    assert!(auth.required());
+    assert_eq!(auth.verify("s3cret", 0), Ok("alice".to_string()));
+    assert_eq!(auth.verify("s3cre", 0), Err(LobbyError::Unauthorized));
+    assert_eq!(auth.verify("", 0), Err(LobbyError::Unauthorized));
+    assert!(!LobbyAuth::Open.required());
+}
+
+#[test]
+fn tickets_are_signed_and_expire() {
+    let auth = LobbyAuth::Tickets { secret: b"key".to_vec() };
+    let ticket = issue_ticket(b"key", "bob", 1_000);
+    assert_eq!(auth.verify(&ticket, 999), Ok("bob".to_string()));
+    assert_eq!(auth.verify(&ticket, 1_000), Err(LobbyError::Unauthorized));
+
+    let forged = ticket.replace("bob", "eve");
+    assert_eq!(auth.verify(&forged, 0), Err(LobbyError::Unauthorized));
+    let extended = ticket.replacen("1000", "9999", 1);
+    assert_eq!(auth.verify(&extended, 0), Err(LobbyError::Unauthorized));
+    let other_key = issue_ticket(b"other", "bob", 1_000);
+    assert_eq!(auth.verify(&other_key, 0), Err(LobbyError::Unauthorized));
+    assert_eq!(auth.verify("garbage", 0), Err(LobbyError::Unauthorized));
+}
+
+#[test]
+fn gate_refuses_commands_until_authenticated() {
+    let mut gate = LobbyGate::new(tokens(), None);
+    let mut identity = None;
+    let now = Instant::now();
+
+    let list = LobbyMessage::ListGames { seq: 0 };
+    assert_eq!(refusal(gate.check("peer", &mut identity, &list, now)), LobbyError::Unauthorized);
+
+    let wrong = LobbyMessage::Authenticate { seq: 1, credential: "nope".to_string() };
+    assert_eq!(refusal(gate.check("peer", &mut identity, &wrong, now)), LobbyError::Unauthorized);
+    assert_eq!(identity, None);
+
+    let right = LobbyMessage::Authenticate { seq: 2, credential: "s3cret".to_string() };
+    assert_eq!(
+        gate.check("peer", &mut identity, &right, now),
+        Some(LobbyMessage::Reply { seq: 2, result: Ok(LobbyReply::Authenticated("alice".to_string())) })
+    );
+    assert_eq!(identity.as_deref(), Some("alice"));
+    assert_eq!(gate.check("peer", &mut identity, &list, now), None);
+}
+
+#[test]
+fn open_gate_passes_commands_through() {
+    let mut gate = LobbyGate::new(LobbyAuth::Open, None);
+    let mut identity = None;
+    let list = LobbyMessage::ListGames { seq: 0 };
+    assert_eq!(gate.check("peer", &mut identity, &list, Instant::now()), None);
+}
+
+#[test]
+fn rate_limit_is_per_identity_and_refills() {
+    let limit = RateLimit { burst: 3, per_second: 1.0 };
+    let mut gate = LobbyGate::new(LobbyAuth::Open, Some(limit));
+    let start = Instant::now();
+    let list = LobbyMessage::ListGames { seq: 0 };
+    let mut a = Some("a".to_string());
+    let mut b = Some("b".to_string());
+
+    for _ in 0..3 {
+        assert_eq!(gate.check("peer", &mut a, &list, start), None);
+    }
+    assert_eq!(refusal(gate.check("peer", &mut a, &list, start)), LobbyError::RateLimited);
+    // Another identity on the same server has its own budget
+    assert_eq!(gate.check("peer", &mut b, &list, start), None);
+
+    let later = start + Duration::from_millis(1_500);
+    assert_eq!(gate.check("peer", &mut a, &list, later), None);
+    assert_eq!(refusal(gate.check("peer", &mut a, &list, later)), LobbyError::RateLimited);
+}
+
+#[test]
+fn anonymous_rate_limit_is_per_ip_and_forgets_idle_clients() {
+    let limit = RateLimit { burst: 2, per_second: 1.0 };
+    let mut gate = LobbyGate::new(LobbyAuth::Open, Some(limit));
+    let start = Instant::now();
+    let list = LobbyMessage::ListGames { seq: 0 };
+
+    assert_eq!(gate.check("10.0.0.1:4000", &mut None, &list, start), None);
+    assert_eq!(gate.check("10.0.0.1:4001", &mut None, &list, start), None);
+    // Reconnecting from a new port shares the address's budget
+    assert_eq!(refusal(gate.check("10.0.0.1:4002", &mut None, &list, start)), LobbyError::RateLimited);
+    assert_eq!(gate.check("10.0.0.2:4000", &mut None, &list, start), None);
+
+    for i in 0..100 {
+        assert_eq!(gate.check(&format!("10.0.1.{i}:4000"), &mut None, &list, start), None);
+    }
+    assert!(gate.tracked() > 100);
+    // Once everyone has refilled, a sweep drops them all
+    let later = start + Duration::from_secs(10);
+    for i in 0..200 {
+        assert_eq!(gate.check(&format!("10.0.2.{i}:4000"), &mut None, &list, later), None);
+    }
+    assert!(gate.tracked() <= 200, "tracked {}", gate.tracked());
+}
+
+#[tokio::test]
+async fn lobby_server_requires_credential() -> anyhow::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (tx, _rx) = mpsc::unbounded_channel();
+    let config = DaemonConfig {
+        auth: tokens(),
+        ..DaemonConfig::default()
+    };
+    tokio::spawn(daemon::serve_lobby(listener, config, tx));
+
+    let tcp = TcpTransport::connect(addr).await?;
+    let stub = Stub::new(HeartbeatTransport::new(tcp, Duration::from_secs(10), Duration::from_secs(45)));
+    let refused = stub.create_game(GameConfig::local()).await.unwrap_err();
+    assert_eq!(refused.downcast_ref::<LobbyError>(), Some(&LobbyError::Unauthorized));
+    assert!(stub.authenticate("wrong").await.is_err());
+
+    assert_eq!(stub.authenticate("s3cret").await?, "alice");
+    let id = stub.create_game(GameConfig::local()).await?;
+    assert_eq!(stub.list_games().await?.iter().map(|g| g.id).collect::<Vec<_>>(), vec![id]);
+    Ok(())
+}
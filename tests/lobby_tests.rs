@@ -0,0 +1,140 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    daemon::{self, DaemonConfig, MatchOutcome},
+    domain::GameConfig,
+    protocol::lobby::{GameInfo, Lobby, LobbyError, LobbyEvent, LobbyMessage, LobbyReply},
+    transport::tcp::TcpTransport,
+    AiPlayer, GameEngine, GameStatus, HeartbeatTransport, PlayerNode, Role, Stub, MAX_BOARD_SIZE,
+};
+use rand::{rngs::SmallRng, SeedableRng};
+use std::net::SocketAddr;
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio::time::{timeout, Duration};
+
+fn reply(lobby: &mut Lobby, peer: &str, msg: LobbyMessage) -> Result<LobbyReply, LobbyError> {
+    match lobby.handle(peer, msg).expect("requests get a reply").reply {
+        LobbyMessage::Reply { result, .. } => result,
+        other => panic!("Expected Reply, got {:?}", other),
+    }
+}
+
+#[test]
+fn create_list_and_join() {
+    let mut lobby = Lobby::new();
+    let outcome = lobby
+        .handle("host", LobbyMessage::CreateGame { seq: 7, config: GameConfig::local() })
+        .unwrap();
+    let info = GameInfo {
+        id: 0,
+        host: "host".to_string(),
+        config: GameConfig::local(),
+    };
+    assert_eq!(
+        outcome.reply,
+        LobbyMessage::Reply { seq: 7, result: Ok(LobbyReply::Created(0)) }
+    );
+    assert_eq!(outcome.events, vec![LobbyEvent::GameOpened(info.clone())]);
+    assert_eq!(
+        reply(&mut lobby, "guest", LobbyMessage::ListGames { seq: 0 }),
+        Ok(LobbyReply::Games(vec![info]))
+    );
+
+    let outcome = lobby.handle("guest", LobbyMessage::JoinGame { seq: 1, id: 0, resume_token: None }).unwrap();
+    assert_eq!(outcome.matched, Some((0, "host".to_string())));
+    assert_eq!(outcome.events, vec![LobbyEvent::GameStarted(0)]);
+    assert!(lobby.open_games().is_empty());
+}
+
+#[test]
+fn refused_requests() {
+    let mut lobby = Lobby::new();
+    let mut other_rules = GameConfig::local();
+    other_rules.board_size = MAX_BOARD_SIZE + 1;
+    assert_eq!(
+        reply(&mut lobby, "host", LobbyMessage::CreateGame { seq: 0, config: other_rules }),
+        Err(LobbyError::UnsupportedConfig)
+    );
+    assert_eq!(
+        reply(&mut lobby, "host", LobbyMessage::LeaveGame { seq: 1 }),
+        Err(LobbyError::NotHosting)
+    );
+    reply(&mut lobby, "host", LobbyMessage::CreateGame { seq: 2, config: GameConfig::local() }).unwrap();
+    assert_eq!(
+        reply(&mut lobby, "host", LobbyMessage::CreateGame { seq: 3, config: GameConfig::local() }),
+        Err(LobbyError::AlreadyHosting(0))
+    );
+    assert_eq!(
+        reply(&mut lobby, "host", LobbyMessage::JoinGame { seq: 4, id: 0, resume_token: None }),
+        Err(LobbyError::OwnGame(0))
+    );
+    assert_eq!(
+        reply(&mut lobby, "guest", LobbyMessage::JoinGame { seq: 5, id: 9, resume_token: None }),
+        Err(LobbyError::UnknownGame(9))
+    );
+    assert_eq!(
+        reply(&mut lobby, "guest", LobbyMessage::JoinGame { seq: 6, id: 0, resume_token: Some([0; 16]) }),
+        Err(LobbyError::InvalidResumeToken(0))
+    );
+    assert!(lobby.handle("guest", LobbyMessage::Event(LobbyEvent::GameClosed(0))).is_none());
+}
+
+#[test]
+fn leaving_or_disconnecting_closes_the_game() {
+    let mut lobby = Lobby::new();
+    reply(&mut lobby, "a", LobbyMessage::CreateGame { seq: 0, config: GameConfig::local() }).unwrap();
+    reply(&mut lobby, "b", LobbyMessage::CreateGame { seq: 0, config: GameConfig::local() }).unwrap();
+
+    let outcome = lobby.handle("a", LobbyMessage::LeaveGame { seq: 1 }).unwrap();
+    assert_eq!(outcome.events, vec![LobbyEvent::GameClosed(0)]);
+    assert_eq!(lobby.disconnect("b"), vec![LobbyEvent::GameClosed(1)]);
+    assert!(lobby.disconnect("b").is_empty());
+    assert!(lobby.open_games().is_empty());
+}
+
+async fn connect(addr: SocketAddr) -> anyhow::Result<Stub<HeartbeatTransport<TcpTransport>>> {
+    let tcp = TcpTransport::connect(addr).await?;
+    Ok(Stub::new(HeartbeatTransport::new(tcp, Duration::from_secs(10), Duration::from_secs(45))))
+}
+
+async fn play(stub: Stub<HeartbeatTransport<TcpTransport>>, seed: u8) -> anyhow::Result<(Role, GameStatus)> {
+    let mut transport = stub.into_transport();
+    let role = daemon::wait_for_opponent(&mut transport).await?;
+    let mut rng = SmallRng::from_seed([seed; 32]);
+    let mut node = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(transport));
+    node.run(&mut rng, role).await?;
+    Ok((role, node.status()))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn host_and_join_through_lobby_server() -> anyhow::Result<()> {
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tokio::spawn(daemon::serve_lobby(listener, DaemonConfig::default(), tx));
+
+    let observer = connect(addr).await?;
+    let host = connect(addr).await?;
+    let id = host.create_game(GameConfig::local()).await?;
+
+    let event = timeout(Duration::from_secs(5), observer.next_lobby_event()).await??;
+    assert!(matches!(event, LobbyEvent::GameOpened(GameInfo { id: opened, .. }) if opened == id));
+
+    let guest = connect(addr).await?;
+    let games = guest.list_games().await?;
+    assert_eq!(games.iter().map(|g| g.id).collect::<Vec<_>>(), vec![id]);
+    assert!(guest.join_game(id + 1).await.is_err());
+    guest.join_game(id).await?;
+
+    let (host_result, guest_result) = tokio::try_join!(play(host, 1), play(guest, 2))?;
+    assert_eq!(host_result.0, Role::Initiator);
+    assert_eq!(guest_result.0, Role::Responder);
+
+    let record = timeout(Duration::from_secs(10), rx.recv()).await?.expect("match recorded");
+    assert_eq!(record.id, id);
+    let winner = if host_result.1 == GameStatus::Won { 0 } else { 1 };
+    assert_eq!(record.outcome, MatchOutcome::Finished { winner });
+    assert!(observer.list_games().await?.is_empty());
+    Ok(())
+}
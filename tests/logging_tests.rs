@@ -0,0 +1,59 @@
+use battleship::logging::{LogFile, LogRotation};
+use battleship::{init_logging, LogConfig};
+
+#[test]
+fn test_filter_directives() {
+    assert_eq!(LogConfig::default().filter_directives(), "info");
+    let config = LogConfig {
+        level: "warn".to_string(),
+        ..LogConfig::default()
+    }
+    .with_module_level("battleship::transport", "debug")
+    .with_module_level("battleship::player", "trace");
+    assert_eq!(
+        config.filter_directives(),
+        "warn,battleship::transport=debug,battleship::player=trace"
+    );
+    assert!(LogConfig::quiet().quiet);
+}
+
+// Only one global subscriber can exist per process, so the whole lifecycle
+// is exercised in a single test.
+#[test]
+fn test_init_logging_writes_json_file_and_only_once() {
+    let dir = std::env::temp_dir().join(format!("battleship-logging-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let bad = LogConfig {
+        level: "not a level=".to_string(),
+        ..LogConfig::default()
+    };
+    assert!(init_logging(bad).is_err());
+
+    let config = LogConfig {
+        level: "warn".to_string(),
+        json: true,
+        quiet: true,
+        file: Some(LogFile {
+            directory: dir.clone(),
+            prefix: "test.log".to_string(),
+            rotation: LogRotation::Never,
+        }),
+        ..LogConfig::default()
+    }
+    .with_module_level("logging_tests", "debug");
+    let guard = init_logging(config).unwrap();
+
+    tracing::debug!(target: "logging_tests", shots = 3, "kept by module override");
+    tracing::info!(target: "other", "dropped by default level");
+    drop(guard);
+
+    let contents = std::fs::read_to_string(dir.join("test.log")).unwrap();
+    let lines: Vec<serde_json::Value> = contents.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines.len(), 1, "{}", contents);
+    assert_eq!(lines[0]["fields"]["message"], "kept by module override");
+    assert_eq!(lines[0]["fields"]["shots"], 3);
+
+    assert!(init_logging(LogConfig::default()).is_err());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
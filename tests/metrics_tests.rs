@@ -0,0 +1,119 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    daemon::{self, DaemonConfig, MatchOutcome, Matchmaker},
+    metrics::{self, Metrics},
+    transport::{in_memory::InMemoryTransport, tcp::TcpTransport, Transport},
+    AiPlayer, Coord, GameEngine, HeartbeatTransport, Message, PlayerNode, PROTOCOL_VERSION,
+};
+use rand::{rngs::SmallRng, SeedableRng};
+use std::net::SocketAddr;
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio::time::{timeout, Duration};
+
+/// Value of the sample line starting with `series`.
+fn sample(text: &str, series: &str) -> Option<f64> {
+    text.lines()
+        .find_map(|line| line.strip_prefix(series)?.strip_prefix(' ')?.parse().ok())
+}
+
+async fn get(addr: SocketAddr, path: &str) -> anyhow::Result<String> {
+    let mut stream = TcpStream::connect(addr).await?;
+    stream
+        .write_all(format!("GET {} HTTP/1.1\r\nHost: test\r\n\r\n", path).as_bytes())
+        .await?;
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await?;
+    Ok(response)
+}
+
+#[test]
+fn render_tracks_live_and_ended_games() {
+    let metrics = Metrics::new();
+    metrics.game_started(1);
+    metrics.game_started(2);
+    metrics.record_move(1);
+    metrics.record_move(1);
+    metrics.record_move(2);
+    let text = metrics.render();
+    assert_eq!(sample(&text, "battleship_games_active"), Some(2.0));
+    assert_eq!(sample(&text, "battleship_moves_total"), Some(3.0));
+    assert_eq!(sample(&text, "battleship_game_moves{game=\"1\"}"), Some(2.0));
+    assert!(sample(&text, "battleship_game_moves_per_second{game=\"2\"}").is_some());
+
+    metrics.game_ended(1, true);
+    metrics.record_disconnect();
+    metrics.record_transport_error();
+    metrics.game_ended(2, false);
+    let text = metrics.render();
+    assert_eq!(metrics.active_games(), 0);
+    assert_eq!(sample(&text, "battleship_games_total{outcome=\"finished\"}"), Some(1.0));
+    assert_eq!(sample(&text, "battleship_games_total{outcome=\"abandoned\"}"), Some(1.0));
+    assert_eq!(sample(&text, "battleship_disconnects_total"), Some(1.0));
+    assert_eq!(sample(&text, "battleship_transport_errors_total"), Some(1.0));
+    assert_eq!(sample(&text, "battleship_game_duration_seconds_count"), Some(2.0));
+    assert!(sample(&text, "battleship_game_moves{game=\"1\"}").is_none());
+}
+
+#[tokio::test]
+async fn abandoned_match_counts_disconnect() -> anyhow::Result<()> {
+    let metrics = Arc::new(Metrics::new());
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut matchmaker = Matchmaker::new(tx).with_metrics(metrics.clone());
+    let (mut a, a_server) = InMemoryTransport::pair();
+    let (mut b, b_server) = InMemoryTransport::pair();
+    matchmaker.enqueue("a", a_server).await;
+    matchmaker.enqueue("b", b_server).await;
+    daemon::wait_for_opponent(&mut a).await?;
+    daemon::wait_for_opponent(&mut b).await?;
+
+    a.send(Message::Guess { version: PROTOCOL_VERSION, seq: 0, target: Coord::new(0, 0) }).await?;
+    b.recv().await?;
+    drop(b);
+    let record = timeout(Duration::from_secs(5), rx.recv()).await?.expect("match recorded");
+    assert!(matches!(record.outcome, MatchOutcome::Abandoned { .. }));
+
+    let text = metrics.render();
+    assert_eq!(sample(&text, "battleship_moves_total"), Some(1.0));
+    assert_eq!(sample(&text, "battleship_disconnects_total"), Some(1.0));
+    assert_eq!(sample(&text, "battleship_games_active"), Some(0.0));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn metrics_endpoint_reports_daemon_games() -> anyhow::Result<()> {
+    let config = DaemonConfig::default();
+    let metrics_listener = TcpListener::bind("127.0.0.1:0").await?;
+    let metrics_addr = metrics_listener.local_addr()?;
+    tokio::spawn(metrics::serve(metrics_listener, config.metrics.clone()));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let addr = listener.local_addr()?;
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tokio::spawn(daemon::serve(listener, config, tx));
+
+    let client = |seed: u8| async move {
+        let tcp = TcpTransport::connect(addr).await?;
+        let mut transport = HeartbeatTransport::new(tcp, Duration::from_secs(10), Duration::from_secs(45));
+        let role = daemon::wait_for_opponent(&mut transport).await?;
+        let mut rng = SmallRng::from_seed([seed; 32]);
+        let mut node = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(transport));
+        node.run(&mut rng, role).await?;
+        anyhow::Ok(node.guess_count())
+    };
+    let (a, b) = tokio::try_join!(client(3), client(4))?;
+    timeout(Duration::from_secs(10), rx.recv()).await?.expect("match recorded");
+
+    let response = get(metrics_addr, "/metrics").await?;
+    assert!(response.starts_with("HTTP/1.1 200 OK"));
+    assert_eq!(sample(&response, "battleship_moves_total"), Some((a + b) as f64));
+    assert_eq!(sample(&response, "battleship_games_total{outcome=\"finished\"}"), Some(1.0));
+    assert_eq!(sample(&response, "battleship_game_duration_seconds_count"), Some(1.0));
+    assert_eq!(sample(&response, "battleship_games_active"), Some(0.0));
+
+    assert!(get(metrics_addr, "/other").await?.starts_with("HTTP/1.1 404"));
+    Ok(())
+}
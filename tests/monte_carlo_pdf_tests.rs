@@ -0,0 +1,70 @@
+use battleship::{calc_pdf, calc_pdf_monte_carlo, AiConfig, AiPlayer, BoardBits, Coord, Player};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+type BB = BoardBits;
+
+/// Hits where the Carrier, Battleship and Cruiser were sunk in rows 2..5,
+/// misses on every other cell but the first `open` cells of the bottom row.
+fn endgame(open: usize) -> (BB, BB) {
+    let hits = common::wrecks(&[(2, 5), (3, 4), (4, 3)]);
+    let open: Vec<_> = (0..open).map(|col| (9, col)).collect();
+    (hits, common::misses_except(hits, &open))
+}
+
+#[test]
+fn test_sampled_ships_do_not_overlap() {
+    // The Submarine and Destroyer fill the five open cells between them
+    let (hits, misses) = endgame(5);
+    let remaining = [0, 0, 0, 3, 2];
+    let mut rng = SmallRng::seed_from_u64(3);
+    let pdf = calc_pdf_monte_carlo(&hits, &misses, &remaining, 500, &mut rng);
+    for (col, p) in pdf[9][..5].iter().enumerate() {
+        assert!((p - 0.2).abs() < 1e-12, "column {}: {}", col, p);
+    }
+
+    // Weighing each ship alone favours the middle of the row
+    let independent = calc_pdf(&hits, &misses, &remaining);
+    assert!(independent[9][2] > independent[9][0]);
+}
+
+#[test]
+fn test_samples_approach_the_placement_frequencies() {
+    let (hits, misses) = endgame(4);
+    let mut rng = SmallRng::seed_from_u64(5);
+    let pdf = calc_pdf_monte_carlo(&hits, &misses, &[0, 0, 0, 0, 2], 3000, &mut rng);
+    // The Destroyer lies at columns 0-1, 1-2 or 2-3
+    for (col, want) in [1.0 / 6.0, 2.0 / 6.0, 2.0 / 6.0, 1.0 / 6.0].into_iter().enumerate() {
+        assert!((pdf[9][col] - want).abs() < 0.03, "column {}: {}", col, pdf[9][col]);
+    }
+}
+
+#[test]
+fn test_no_fitting_sample_falls_back_to_the_heuristic() {
+    let (hits, misses) = endgame(2);
+    let remaining = [0, 0, 0, 3, 2];
+    let mut rng = SmallRng::seed_from_u64(7);
+    assert_eq!(
+        calc_pdf_monte_carlo(&hits, &misses, &remaining, 100, &mut rng),
+        calc_pdf(&hits, &misses, &remaining)
+    );
+    assert_eq!(
+        calc_pdf_monte_carlo(&hits, &misses, &remaining, 0, &mut rng),
+        calc_pdf(&hits, &misses, &remaining)
+    );
+}
+
+#[test]
+fn test_ai_player_targets_by_sampling() {
+    let config = AiConfig { mc_samples: 200, ..AiConfig::default() };
+    let mut player = AiPlayer::with_config(config);
+    let (hits, misses) = endgame(5);
+    let mut rng = SmallRng::seed_from_u64(9);
+    for _ in 0..20 {
+        let Coord { row, col } = player.select_target(&mut rng, &hits, &misses, &[0, 0, 0, 3, 2]);
+        assert_eq!(row, 9);
+        assert!(col < 5);
+    }
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "std")]
+
+use battleship::domain::{GameConfig, Placement};
+use battleship::protocol::validation::validate_fleet;
+use battleship::{
+    calc_pdf_with_rules, AiConfig, AiPlayer, BoardBits, Board, BoardError, FleetConfig, GameEngine, GuessResult, Orientation,
+    PlacementRules, PlacementStyle, Player, RuleSet,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+fn rules() -> RuleSet {
+    RuleSet::STANDARD.with_placement(PlacementRules::NO_TOUCHING)
+}
+
+/// Cells of every placed ship, one list per ship.
+fn ship_cells(board: &Board) -> Vec<Vec<(usize, usize)>> {
+    let fleet = *board.fleet();
+    board
+        .ship_states()
+        .iter()
+        .zip(fleet.ships())
+        .filter_map(|(state, def)| {
+            let (r, c, o) = state.position?;
+            Some(
+                (0..def.length())
+                    .map(|k| match o {
+                        Orientation::Horizontal => (r, c + k),
+                        Orientation::Vertical => (r + k, c),
+                    })
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn assert_no_ships_touch(board: &Board) {
+    let ships = ship_cells(board);
+    for (i, a) in ships.iter().enumerate() {
+        for b in &ships[i + 1..] {
+            for &(ar, ac) in a {
+                for &(br, bc) in b {
+                    assert!(ar.abs_diff(br).max(ac.abs_diff(bc)) > 1, "ships touch at {:?}/{:?}", (ar, ac), (br, bc));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_place_rejects_touching_ships() {
+    let mut board = Board::with_rules(&rules());
+    board.place(0, 0, 0, Orientation::Horizontal).unwrap();
+    assert_eq!(board.place(1, 1, 0, Orientation::Horizontal), Err(BoardError::ShipsTouch));
+    assert_eq!(board.place(1, 1, 5, Orientation::Vertical), Err(BoardError::ShipsTouch));
+    assert_eq!(board.place(1, 0, 5, Orientation::Horizontal), Err(BoardError::ShipsTouch));
+    assert_eq!(board.place(1, 0, 4, Orientation::Horizontal), Err(BoardError::ShipOverlaps));
+    board.place(1, 2, 0, Orientation::Horizontal).unwrap();
+    board.place(2, 0, 6, Orientation::Horizontal).unwrap();
+
+    let mut standard = Board::new();
+    standard.place(0, 0, 0, Orientation::Horizontal).unwrap();
+    standard.place(1, 1, 0, Orientation::Horizontal).unwrap();
+}
+
+#[test]
+fn test_random_layouts_keep_ships_apart() {
+    for seed in 0..20 {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut board = Board::with_rules(&rules());
+        board.place_all_random(&mut rng).unwrap();
+        assert!(board.all_placed());
+        assert_no_ships_touch(&board);
+
+        let mut engine = GameEngine::new_with_config(rules());
+        AiPlayer::with_placement(PlacementStyle::Dispersed)
+            .place_ships(&mut rng, engine.board_mut())
+            .unwrap();
+        assert_no_ships_touch(engine.board());
+    }
+}
+
+#[test]
+fn test_random_layout_fails_when_ships_cannot_be_kept_apart() {
+    let fleet = FleetConfig::empty().with_ships("Destroyer", 2, 3).unwrap();
+    let rules = RuleSet::new(3, fleet).unwrap();
+    let mut rng = SmallRng::seed_from_u64(1);
+    Board::with_rules(&rules).place_all_random(&mut rng).unwrap();
+
+    let mut board = Board::with_rules(&rules.with_placement(PlacementRules::NO_TOUCHING));
+    assert_eq!(board.place_all_random(&mut rng), Err(BoardError::UnableToPlaceShip));
+    assert!(board.ship_map().is_empty());
+}
+
+#[test]
+fn test_pdf_excludes_cells_next_to_other_ships() {
+    // Two hits in the corner: a ship covering either one lies along row 0,
+    // and no other ship may touch them
+    let hits = BoardBits::try_from_cells([(0, 0), (0, 1)]).unwrap();
+    let misses = BoardBits::new();
+    let remaining = [5, 4, 3, 3, 0];
+    let config = AiConfig::default();
+
+    let apart = calc_pdf_with_rules(
+        &hits,
+        &misses,
+        &remaining,
+        &FleetConfig::STANDARD,
+        &PlacementRules::NO_TOUCHING,
+        &config,
+    );
+    for (r, c) in [(1, 0), (1, 1), (1, 2)] {
+        assert_eq!(apart[r][c], 0.0, "cell ({}, {})", r, c);
+    }
+    assert!(apart[0][2] > 0.0);
+    assert!(apart[2][2] > 0.0);
+
+    let touching = calc_pdf_with_rules(
+        &hits,
+        &misses,
+        &remaining,
+        &FleetConfig::STANDARD,
+        &PlacementRules::STANDARD,
+        &config,
+    );
+    assert!(touching[1][0] > 0.0);
+}
+
+#[test]
+fn test_engine_blocks_water_around_placed_wrecks() {
+    let mut engine = GameEngine::new_with_config(rules());
+    engine.record_guess(4, 4, GuessResult::Hit).unwrap();
+    engine.record_guess(4, 5, GuessResult::Sink("Destroyer")).unwrap();
+    let blocked = engine.blocked_cells();
+    assert_eq!(blocked.count_ones(), 3 * 4 + rules().off_board().count_ones());
+    assert!(blocked.get(3, 3).unwrap() && blocked.get(5, 6).unwrap());
+
+    let mut touching = GameEngine::new();
+    touching.record_guess(4, 4, GuessResult::Hit).unwrap();
+    touching.record_guess(4, 5, GuessResult::Sink("Destroyer")).unwrap();
+    assert_eq!(touching.blocked_cells().count_ones(), 2 + touching.rules().off_board().count_ones());
+}
+
+#[test]
+fn test_rule_reaches_validation_config_and_json() {
+    let placement = |ship: &str, row| Placement {
+        ship: ship.to_string(),
+        row,
+        col: 0,
+        orientation: Orientation::Horizontal,
+    };
+    let layout = [
+        placement("Carrier", 0),
+        placement("Battleship", 1),
+        placement("Cruiser", 4),
+        placement("Submarine", 6),
+        placement("Destroyer", 8),
+    ];
+    assert!(validate_fleet(&RuleSet::STANDARD, &layout).is_ok());
+    let reasons = validate_fleet(&rules(), &layout).unwrap_err();
+    assert!(reasons.iter().any(|r| r.contains("touches")), "{:?}", reasons);
+
+    let config = GameConfig::from(&rules());
+    assert!(config.no_touching);
+    assert_eq!(config.rules(), Some(rules()));
+
+    let mut rng = SmallRng::seed_from_u64(3504);
+    let mut engine = GameEngine::new_with_config(rules());
+    engine.board_mut().place_all_random(&mut rng).unwrap();
+    let json = engine.to_json().unwrap();
+    assert!(json.contains("\"no_touching\":true"));
+    assert_eq!(GameEngine::from_json(&json).unwrap().rules(), rules());
+}
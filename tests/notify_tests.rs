@@ -0,0 +1,109 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    notify::{self, Notifications, Notifier},
+    transport::in_memory::InMemoryTransport,
+    AiPlayer, EventListener, GameEngine, GameEvent, GameStatus, GuessResult, HeartbeatTransport, PlayerNode, Role,
+};
+use rand::{rngs::SmallRng, SeedableRng};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Note {
+    Turn,
+    Sunk(&'static str, bool),
+    Over(GameStatus),
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Mutex<Vec<Note>>>);
+
+impl Recorder {
+    fn notes(&self) -> Vec<Note> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+impl Notifier for Recorder {
+    fn on_your_turn(&mut self) {
+        self.0.lock().unwrap().push(Note::Turn);
+    }
+
+    fn on_ship_sunk(&mut self, ship: &'static str, ours: bool) {
+        self.0.lock().unwrap().push(Note::Sunk(ship, ours));
+    }
+
+    fn on_game_over(&mut self, status: GameStatus) {
+        self.0.lock().unwrap().push(Note::Over(status));
+    }
+}
+
+fn opponent_guess() -> GameEvent {
+    GameEvent::OpponentGuess { row: 0, col: 0, result: GuessResult::Miss }
+}
+
+#[test]
+fn maps_events_to_notifications() {
+    let recorder = Recorder::default();
+    let mut hook = Notifications::with_min_interval(recorder.clone(), Duration::ZERO);
+    hook.on_event(&GameEvent::GameStarted { first_move: false });
+    hook.on_event(&GameEvent::GuessSent { row: 0, col: 0 });
+    hook.on_event(&opponent_guess());
+    hook.on_event(&GameEvent::Sink { ship: "Destroyer", ours: true });
+    hook.on_event(&GameEvent::GameOver { status: GameStatus::Lost });
+    hook.on_event(&opponent_guess());
+    hook.on_event(&GameEvent::GameOver { status: GameStatus::Lost });
+    assert_eq!(
+        recorder.notes(),
+        vec![Note::Turn, Note::Sunk("Destroyer", true), Note::Over(GameStatus::Lost)]
+    );
+}
+
+#[test]
+fn rate_limit_drops_bursts_but_not_game_over() {
+    let recorder = Recorder::default();
+    let mut hook = Notifications::with_min_interval(recorder.clone(), Duration::from_secs(60));
+    hook.on_event(&GameEvent::GameStarted { first_move: true });
+    hook.on_event(&opponent_guess());
+    hook.on_event(&GameEvent::Sink { ship: "Submarine", ours: false });
+    hook.on_event(&GameEvent::Sink { ship: "Cruiser", ours: false });
+    hook.on_event(&GameEvent::GameOver { status: GameStatus::Won });
+    assert_eq!(
+        recorder.notes(),
+        vec![Note::Turn, Note::Sunk("Submarine", false), Note::Over(GameStatus::Won)]
+    );
+}
+
+#[tokio::test]
+async fn forwards_a_node_game_to_the_notifier() -> anyhow::Result<()> {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut node1 = PlayerNode::new(
+        Box::new(AiPlayer::new()),
+        GameEngine::new(),
+        Box::new(HeartbeatTransport::disabled(t1)),
+    );
+    let mut node2 = PlayerNode::new(
+        Box::new(AiPlayer::new()),
+        GameEngine::new(),
+        Box::new(HeartbeatTransport::disabled(t2)),
+    );
+    let recorder = Recorder::default();
+    let hook = Notifications::with_min_interval(recorder.clone(), Duration::ZERO);
+    let forwarding = tokio::spawn(notify::forward_events(node1.subscribe(), hook));
+    let mut rng1 = SmallRng::seed_from_u64(21);
+    let mut rng2 = SmallRng::seed_from_u64(22);
+    tokio::try_join!(node1.run(&mut rng1, Role::Initiator), node2.run(&mut rng2, Role::Responder))?;
+    let status = node1.status();
+    drop((node1, node2));
+    forwarding.await?;
+
+    let notes = recorder.notes();
+    assert_eq!(notes.last(), Some(&Note::Over(status)));
+    let sunk = notes.iter().filter(|n| matches!(n, Note::Sunk(..))).count();
+    let enemy_sunk = notes.iter().filter(|n| matches!(n, Note::Sunk(_, false))).count();
+    assert!(sunk >= 5);
+    assert_eq!(enemy_sunk == 5, status == GameStatus::Won);
+    assert!(notes.contains(&Note::Turn));
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use battleship::{
+    calc_pdf_with_rules, calc_pdf_with_sunk, AiConfig, BoardBits, FleetConfig, GameEngine, GuessResult, OpponentModel,
+    PlacementRules,
+};
+
+mod common;
+
+type BB = BoardBits;
+
+#[test]
+fn test_lone_run_places_the_sunk_ship() {
+    let mut engine = GameEngine::new();
+    engine.record_guess(5, 0, GuessResult::Hit).unwrap();
+    engine.record_guess(7, 7, GuessResult::Hit).unwrap();
+    engine.record_guess(5, 1, GuessResult::Sink("Destroyer")).unwrap();
+
+    let model = engine.opponent_model();
+    assert_eq!(model.ship_cells(4), Some(common::cells(&[(5, 0), (5, 1)])));
+    assert_eq!(model.unresolved_hits(), common::cells(&[(7, 7)]));
+    assert!(!model.has_pending());
+    assert!(engine.blocked_cells().get(5, 0).unwrap());
+    assert!(!engine.blocked_cells().get(7, 7).unwrap());
+}
+
+#[test]
+fn test_ambiguous_sink_waits_for_a_neighbour() {
+    let mut engine = GameEngine::new();
+    engine.record_guess(2, 2, GuessResult::Hit).unwrap();
+    engine.record_guess(3, 3, GuessResult::Hit).unwrap();
+    // The Destroyer could lie along row 2 or down column 3
+    engine.record_guess(2, 3, GuessResult::Sink("Destroyer")).unwrap();
+    assert!(engine.opponent_model().has_pending());
+    assert_eq!(engine.opponent_model().ship_cells(4), None);
+    assert!(engine.opponent_model().sunk_cells().is_empty());
+
+    // The Cruiser can only run (3,3)..(3,5), which leaves the Destroyer
+    // on row 2
+    engine.record_guess(3, 4, GuessResult::Hit).unwrap();
+    engine.record_guess(3, 5, GuessResult::Sink("Cruiser")).unwrap();
+    let model = engine.opponent_model();
+    assert_eq!(model.ship_cells(2), Some(common::cells(&[(3, 3), (3, 4), (3, 5)])));
+    assert_eq!(model.ship_cells(4), Some(common::cells(&[(2, 2), (2, 3)])));
+    assert!(model.unresolved_hits().is_empty());
+    assert!(!model.has_pending());
+}
+
+#[test]
+fn test_sunk_cells_stop_attracting_shots() {
+    let mut engine = GameEngine::new();
+    engine.record_guess(5, 0, GuessResult::Hit).unwrap();
+    engine.record_guess(7, 7, GuessResult::Hit).unwrap();
+    engine.record_guess(5, 1, GuessResult::Sink("Destroyer")).unwrap();
+    let hits = engine.guess_hits();
+    let misses = engine.guess_misses() | engine.rules().off_board();
+    let sunk = engine.opponent_model().sunk_cells();
+    let remaining = engine.enemy_ship_lengths_remaining();
+    let (fleet, rules, config) = (FleetConfig::STANDARD, PlacementRules::STANDARD, AiConfig::default());
+
+    let naive = calc_pdf_with_rules(&hits, &misses, &remaining, &fleet, &rules, &config);
+    let aware = calc_pdf_with_sunk(&hits, &sunk, &misses, &remaining, &fleet, &rules, &config);
+    // The wreck no longer pulls shots along row 5; the live hit still does
+    assert!(aware[5][2] * 10.0 < naive[5][2]);
+    assert!(aware[7][8] > naive[7][8]);
+    assert_eq!((aware[5][0], aware[5][1]), (0.0, 0.0));
+
+    let blocked = calc_pdf_with_rules(&hits, &engine.blocked_cells(), &remaining, &fleet, &rules, &config);
+    assert_eq!(aware, blocked);
+}
+
+#[test]
+fn test_restored_engine_leaves_hits_unresolved() {
+    let mut engine = GameEngine::new();
+    engine.record_guess(0, 0, GuessResult::Hit).unwrap();
+    engine.record_guess(0, 1, GuessResult::Sink("Destroyer")).unwrap();
+    let restored = GameEngine::from_state(engine.state());
+    assert_eq!(restored.opponent_model().unresolved_hits(), engine.guess_hits());
+    assert!(restored.opponent_model().sunk_cells().is_empty());
+    assert_eq!(OpponentModel::with_hits(BB::new()), OpponentModel::default());
+}
@@ -0,0 +1,122 @@
+use battleship::{
+    calc_pdf, AiPlayer, BoardBits, Board, Coord, GuessResult, OpponentProfile, Player, BOARD_SIZE,
+    EARLY_SHOT_WINDOW, MIN_GAMES_FOR_ADAPTATION,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+const N: usize = BOARD_SIZE as usize;
+
+#[test]
+fn test_profile_matures_after_enough_games() {
+    let mut profile = OpponentProfile::new();
+    for _ in 0..MIN_GAMES_FOR_ADAPTATION {
+        profile.begin_game();
+        assert!(!profile.is_mature());
+    }
+    profile.begin_game();
+    assert!(profile.is_mature());
+    assert_eq!(profile.games(), MIN_GAMES_FOR_ADAPTATION + 1);
+}
+
+#[test]
+fn test_early_shots_weigh_more() {
+    let mut profile = OpponentProfile::new();
+    profile.begin_game();
+    profile.record_opponent_shot(0, 0);
+    for _ in 0..EARLY_SHOT_WINDOW {
+        profile.record_opponent_shot(5, 5);
+    }
+    profile.record_opponent_shot(9, 9);
+
+    let heat = profile.shot_heat();
+    assert_eq!(heat[0][0], 3.0);
+    assert_eq!(heat[9][9], 1.0);
+    assert_eq!(heat[1][1], 0.0);
+}
+
+#[test]
+fn test_hunting_prior_favours_past_ship_cells() {
+    let mut profile = OpponentProfile::new();
+    profile.begin_game();
+    profile.record_enemy_ship_cell(4, 4);
+
+    let lengths = common::full_fleet();
+    let base = calc_pdf(&BoardBits::new(), &BoardBits::new(), &lengths);
+    let mut adapted = base;
+    profile.apply_hunting_prior(&mut adapted);
+
+    assert!(adapted[4][4] > base[4][4]);
+    assert!(adapted[4][5] < base[4][5]);
+    let total: f64 = adapted.iter().flatten().sum();
+    assert!((total - 1.0).abs() < 1e-9);
+}
+
+#[test]
+fn test_mature_ai_places_away_from_opponent_targets() {
+    let mut ai = AiPlayer::new();
+    let mut rng = SmallRng::seed_from_u64(5);
+
+    // An opponent that only ever shoots at the left half of the board
+    for _ in 0..=MIN_GAMES_FOR_ADAPTATION {
+        let mut board = Board::new();
+        ai.place_ships(&mut rng, &mut board).unwrap();
+        for r in 0..N {
+            for c in 0..N / 2 {
+                ai.handle_opponent_guess(Coord::new(r, c), GuessResult::Miss);
+            }
+        }
+    }
+    assert!(ai.profile().is_mature());
+
+    let mut left = 0;
+    for _ in 0..10 {
+        let mut board = Board::new();
+        ai.place_ships(&mut rng, &mut board).unwrap();
+        left += board
+            .ship_map()
+            .iter_set_bits()
+            .filter(|&(_, c)| c < N / 2)
+            .count();
+    }
+    // Uniform placement would put about half of the 170 cells on the left
+    assert!(left < 60, "{} ship cells placed on the targeted half", left);
+}
+
+#[test]
+fn test_profile_round_trips_through_serde() {
+    let mut ai = AiPlayer::new();
+    let mut rng = SmallRng::seed_from_u64(11);
+    let mut board = Board::new();
+    ai.place_ships(&mut rng, &mut board).unwrap();
+    ai.handle_opponent_guess(Coord::new(2, 3), GuessResult::Hit);
+    ai.handle_guess_result(Coord::new(7, 1), GuessResult::Hit);
+    ai.handle_guess_result(Coord::new(7, 2), GuessResult::Miss);
+
+    let bytes = bincode::serialize(ai.profile()).unwrap();
+    let restored: OpponentProfile = bincode::deserialize(&bytes).unwrap();
+    assert_eq!(&restored, ai.profile());
+
+    let resumed = AiPlayer::new().with_profile(restored);
+    assert_eq!(resumed.profile().games(), 1);
+    assert_eq!(resumed.profile().shot_heat()[2][3], 3.0);
+}
+
+#[test]
+fn test_profile_persists_between_sessions() {
+    let mut profile = OpponentProfile::new();
+    profile.begin_game();
+    profile.record_opponent_shot(4, 6);
+    profile.record_enemy_ship_cell(1, 1);
+
+    let path = std::env::temp_dir().join(format!("battleship-profile-{}.json", std::process::id()));
+    profile.save(&path).unwrap();
+    assert_eq!(OpponentProfile::load(&path).unwrap(), profile);
+
+    std::fs::write(&path, "{}").unwrap();
+    assert!(OpponentProfile::load(&path).is_err());
+    std::fs::remove_file(&path).unwrap();
+    assert!(OpponentProfile::load(&path).is_err());
+}
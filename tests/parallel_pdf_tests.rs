@@ -0,0 +1,45 @@
+#![cfg(all(feature = "parallel", not(feature = "large-boards")))]
+
+use battleship::{
+    calc_pdf, calc_pdf_monte_carlo, calc_pdf_with_sunk, calc_placement_counts, AiConfig, BitBoard, FleetConfig,
+    PlacementRules, BOARD_SIZE,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+type BB = BitBoard<u128, { BOARD_SIZE as usize }>;
+
+const FLEET: [usize; 5] = [5, 4, 3, 3, 2];
+
+#[test]
+fn test_parallel_density_matches_the_placement_counts() {
+    let misses = BB::try_from_cells([(0, 0), (4, 4), (4, 5), (9, 2)]).unwrap();
+    let pdf = calc_pdf(&BB::new(), &misses, &FLEET);
+    let counts = calc_placement_counts(&misses, &FLEET);
+    let total: u32 = counts.iter().flatten().sum();
+    for (row, counts) in pdf.iter().zip(counts.iter()) {
+        for (&p, &n) in row.iter().zip(counts.iter()) {
+            assert!((p - f64::from(n) / f64::from(total)).abs() < 1e-12);
+        }
+    }
+
+    // Ships are weighed on separate threads but summed in fleet order
+    let hits = BB::try_from_cells([(6, 6), (6, 7)]).unwrap();
+    let config = AiConfig { hit_bias: 7.3, ..AiConfig::default() };
+    let density = || {
+        calc_pdf_with_sunk(&hits, &BB::new(), &misses, &FLEET, &FleetConfig::STANDARD, &PlacementRules::STANDARD, &config)
+    };
+    assert_eq!(density(), density());
+}
+
+#[test]
+fn test_parallel_sampling_is_reproducible() {
+    let hits = BB::try_from_cells([(3, 3)]).unwrap();
+    let misses = BB::try_from_cells([(2, 3), (3, 2)]).unwrap();
+    let sample = |seed| calc_pdf_monte_carlo(&hits, &misses, &FLEET, 300, &mut SmallRng::seed_from_u64(seed));
+    let pdf = sample(7);
+    assert_eq!(pdf, sample(7));
+    assert!((pdf.iter().flatten().sum::<f64>() - 1.0).abs() < 1e-9);
+    assert_eq!((pdf[3][3], pdf[2][3]), (0.0, 0.0));
+    assert!(pdf[3][4] > 0.0 && pdf[4][3] > 0.0);
+}
@@ -0,0 +1,70 @@
+use battleship::{
+    calc_pdf_with_sunk, AiConfig, AiPlayer, BoardBits, PdfCache, PlacementRules, Player, RuleSet, TargetingView,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+fn assert_close(cached: &common::Pdf, fresh: &common::Pdf, shot: usize) {
+    for (r, (a, b)) in cached.iter().zip(fresh.iter()).enumerate() {
+        for (c, (x, y)) in a.iter().zip(b.iter()).enumerate() {
+            assert!((x - y).abs() < 1e-9, "shot {} ({}, {}): {} vs {}", shot, r, c, x, y);
+        }
+    }
+}
+
+/// Play one side of a game, checking the cache against a fresh density
+/// before every shot.
+fn follow_game(cache: &mut PdfCache, rules: RuleSet, config: &AiConfig, seed: u64) {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut duel = common::Duel::with_rules(&mut rng, rules);
+    let mut player = AiPlayer::new().with_placement_rules(rules.placement());
+    let placement = rules.placement();
+    for shot in 0..100 {
+        let (hits, sunk, blocked, remaining) = duel.seen();
+        let misses = duel.shooter.guess_misses();
+        let view = TargetingView {
+            placement: &placement,
+            ..common::standard_view(&hits, &sunk, &misses, &remaining)
+        };
+        let fresh = calc_pdf_with_sunk(&hits, &sunk, &misses, &remaining, view.fleet, &placement, config);
+        assert_close(&cache.density(&view, config), &fresh, shot);
+
+        let guess = player.select_target(&mut rng, &hits, &blocked, &remaining);
+        player.handle_guess_result(guess, duel.fire(guess.row, guess.col));
+        if duel.won() {
+            return;
+        }
+    }
+    panic!("fleet not sunk in 100 shots");
+}
+
+#[test]
+fn test_cache_tracks_the_fresh_density_through_a_game() {
+    let mut cache = PdfCache::new();
+    follow_game(&mut cache, RuleSet::STANDARD, &AiConfig::default(), 1);
+    // A new game leaves shots behind, so the cache starts over
+    follow_game(&mut cache, RuleSet::STANDARD, &AiConfig::default(), 2);
+
+    let config = AiConfig { hit_bias: 7.3, parity: true, ..AiConfig::default() };
+    follow_game(&mut cache, RuleSet::STANDARD, &config, 3);
+}
+
+#[test]
+fn test_cache_follows_the_no_touching_rule() {
+    let rules = RuleSet::STANDARD.with_placement(PlacementRules { no_touching: true });
+    follow_game(&mut PdfCache::new(), rules, &AiConfig::default(), 4);
+}
+
+#[test]
+fn test_unknown_lengths_fall_back_to_a_fresh_density() {
+    let hits = BoardBits::new();
+    let misses = BoardBits::new();
+    let view = common::standard_view(&hits, &hits, &misses, &[7]);
+    let config = AiConfig::default();
+    assert_eq!(
+        PdfCache::new().density(&view, &config),
+        calc_pdf_with_sunk(&hits, &hits, &misses, &[7], view.fleet, view.placement, &config)
+    );
+}
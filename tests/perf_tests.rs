@@ -0,0 +1,29 @@
+use battleship::perf::{self, Counter};
+use battleship::{calc_pdf, calc_pdf_fixed, sample_pdf, BoardBits, Board, Coord};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+type BB = BoardBits;
+
+#[test]
+fn test_counters_follow_the_work() {
+    let before = perf::snapshot();
+    let pdf = calc_pdf(&BB::new(), &BB::new(), &[5, 4, 3, 3, 2]);
+    calc_pdf_fixed(&BB::new(), &BB::new(), &[2]);
+    sample_pdf(&pdf, 0.5, &mut SmallRng::seed_from_u64(1));
+    let mut board = Board::new();
+    board.guess(Coord::new(0, 0)).unwrap();
+    board.guess(Coord::new(0, 0)).unwrap_err();
+    let work = perf::snapshot().since(&before);
+
+    // Other tests may count alongside, so only a floor holds
+    let expected = [(Counter::Densities, 2), (Counter::Placements, 760 + 180), (Counter::Samples, 1), (Counter::Guesses, 2)];
+    for (counter, floor) in expected {
+        if cfg!(feature = "perf") {
+            assert!(work.get(counter) >= floor, "{:?}: {}", counter, work.get(counter));
+        } else {
+            assert_eq!(work.get(counter), 0);
+        }
+    }
+}
+
@@ -0,0 +1,76 @@
+use battleship::{calc_pdf, calc_placement_counts, enumerate_placements, BoardBits, Orientation, MAX_BOARD_SIZE, NUM_SHIPS};
+
+mod common;
+
+#[test]
+fn test_empty_board_counts() {
+    let counts = calc_placement_counts(&BoardBits::new(), &common::full_fleet());
+    // One horizontal and one vertical placement per ship reach a corner
+    assert_eq!(counts[0][0], 2 * NUM_SHIPS as u32);
+    // Interior cells are covered by `len` offsets in each orientation
+    let fleet_cells: u32 = common::full_fleet().iter().map(|&l| l as u32).sum();
+    assert_eq!(counts[4][4], 2 * fleet_cells);
+    // Symmetric under transposition
+    for (r, row) in counts.iter().enumerate() {
+        for (c, &count) in row.iter().enumerate() {
+            assert_eq!(count, counts[c][r]);
+        }
+    }
+}
+
+#[test]
+fn test_miss_blocks_covering_placements() {
+    let misses = common::cells(&[(0, 1)]);
+    let counts = calc_placement_counts(&misses, &common::full_fleet());
+    assert_eq!(counts[0][1], 0);
+    // Every horizontal placement through the corner also covers (0, 1)
+    assert_eq!(counts[0][0], NUM_SHIPS as u32);
+}
+
+#[test]
+fn test_counts_match_unbiased_pdf() {
+    let misses = common::cells(&[(2, 7)]);
+    let lengths = common::full_fleet();
+    let counts = calc_placement_counts(&misses, &lengths);
+
+    // With no hits the heuristic PDF is exactly the normalised counts
+    let pdf = calc_pdf(&BoardBits::new(), &misses, &lengths);
+    let total: u32 = counts.iter().flatten().sum();
+    for (pdf_row, count_row) in pdf.iter().zip(counts.iter()) {
+        for (&p, &count) in pdf_row.iter().zip(count_row.iter()) {
+            let expected = count as f64 / total as f64;
+            assert!((p - expected).abs() < 1e-12);
+        }
+    }
+}
+
+#[test]
+fn test_enumerated_placements_avoid_misses_and_count_hits() {
+    let empty = BoardBits::new();
+    // A Destroyer fits n - 1 ways along each of n rows, and as many down columns
+    let n = MAX_BOARD_SIZE as usize;
+    assert_eq!(enumerate_placements(2, &empty, &empty).count(), 2 * n * (n - 1));
+    assert_eq!(enumerate_placements(0, &empty, &empty).count(), 0);
+    assert_eq!(enumerate_placements(n + 1, &empty, &empty).count(), 0);
+
+    let hits = common::cells(&[(0, 2)]);
+    let misses = common::cells(&[(0, 0)]);
+    let first = enumerate_placements(3, &hits, &misses).next().unwrap();
+    assert_eq!((first.row, first.col, first.orientation), (0, 1, Orientation::Horizontal));
+    assert_eq!(first.hits, 1);
+    assert!(enumerate_placements(3, &hits, &misses).all(|p| (p.mask & misses).is_empty()));
+
+    // The per-cell counts are the enumerated placements stacked up
+    let lengths = common::full_fleet();
+    let counts = calc_placement_counts(&misses, &lengths);
+    let mut stacked = [[0u32; MAX_BOARD_SIZE as usize]; MAX_BOARD_SIZE as usize];
+    for &len in &lengths {
+        for p in enumerate_placements(len, &hits, &misses) {
+            assert_eq!(p.mask.count_ones(), len);
+            for (r, c) in p.mask.iter_set_bits() {
+                stacked[r][c] += 1;
+            }
+        }
+    }
+    assert_eq!(stacked, counts);
+}
@@ -0,0 +1,164 @@
+/// Tests for the placement phase: progress notifications while a fleet is
+/// being placed and the setup timeout while waiting for the peer.
+use battleship::protocol::setup::{self, SetupConfig};
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::transport::Transport;
+use battleship::domain::GameConfig;
+use battleship::{
+    AiPlayer, Board, BoardError, Coord, GameEngine, Message, Player, PlayerNode, Role, RuleSet, PROTOCOL_VERSION,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tokio::time::{Duration, Instant};
+
+/// An AI player that records whether it was ever asked to place its fleet.
+struct WatchedPlacer {
+    inner: AiPlayer,
+    placed: Arc<AtomicBool>,
+}
+
+impl Player for WatchedPlacer {
+    fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError> {
+        self.placed.store(true, Ordering::SeqCst);
+        self.inner.place_ships(rng, board)
+    }
+
+    fn select_target(
+        &mut self,
+        rng: &mut SmallRng,
+        hits: &battleship::BoardBits,
+        misses: &battleship::BoardBits,
+        remaining: &[usize],
+    ) -> Coord {
+        self.inner.select_target(rng, hits, misses, remaining)
+    }
+}
+
+#[tokio::test]
+async fn test_slow_placement_sends_progress() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let config = SetupConfig {
+        progress_interval: Duration::from_millis(50),
+        timeout: Duration::from_secs(5),
+    };
+    let deadline = Instant::now() + config.timeout;
+
+    let placed = setup::run_placement(&mut t1, PROTOCOL_VERSION, &config, deadline, |_| {
+        std::thread::sleep(Duration::from_millis(230));
+        42
+    })
+    .await
+    .unwrap();
+    assert_eq!(placed, 42);
+
+    let mut progress = 0;
+    while let Ok(Ok(msg)) = tokio::time::timeout(Duration::from_millis(20), t2.recv()).await {
+        assert!(matches!(
+            msg,
+            Message::PlacementProgress { version: PROTOCOL_VERSION, .. }
+        ));
+        progress += 1;
+    }
+    assert!(progress >= 2, "expected periodic progress, got {}", progress);
+}
+
+#[tokio::test]
+async fn test_placement_past_deadline_aborts() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let config = SetupConfig {
+        progress_interval: Duration::from_secs(5),
+        timeout: Duration::from_millis(100),
+    };
+    let deadline = Instant::now() + config.timeout;
+    let (stopped_tx, stopped_rx) = std::sync::mpsc::channel();
+
+    let result = setup::run_placement(&mut t1, PROTOCOL_VERSION, &config, deadline, move |cancel| {
+        // Stands in for a player placing ships until told to stop
+        while !cancel.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        stopped_tx.send(()).unwrap();
+    })
+    .await;
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("Setup timed out"));
+    stopped_rx.recv_timeout(Duration::from_secs(5)).expect("placement was not cancelled");
+
+    // The peer hears why, and gives up waiting for Ready
+    let abort = t2.recv().await.unwrap();
+    assert!(matches!(&abort, Message::Abort { version: PROTOCOL_VERSION, reason, .. } if reason.contains("placement")));
+    t1.send(abort).await.unwrap();
+    let err = setup::exchange_ready(&mut t2, PROTOCOL_VERSION, &GameConfig::local(), Role::Responder, false, deadline)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Peer aborted setup"));
+}
+
+#[tokio::test]
+async fn test_progress_is_skipped_while_waiting_for_ready() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    let responder = tokio::spawn(async move {
+        setup::exchange_ready(&mut t2, PROTOCOL_VERSION, &GameConfig::local(), Role::Responder, false, deadline).await
+    });
+
+    for elapsed_secs in 1..=3 {
+        t1.send(Message::PlacementProgress {
+            version: PROTOCOL_VERSION,
+            elapsed_secs,
+        })
+        .await
+        .unwrap();
+    }
+    setup::exchange_ready(&mut t1, PROTOCOL_VERSION, &GameConfig::local(), Role::Initiator, true, deadline)
+        .await
+        .unwrap();
+
+    assert!(responder.await.unwrap().is_ok());
+}
+
+#[tokio::test]
+async fn test_peer_never_ready_times_out() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let deadline = Instant::now() + Duration::from_millis(100);
+
+    let result = setup::exchange_ready(&mut t1, PROTOCOL_VERSION, &GameConfig::local(), Role::Initiator, true, deadline).await;
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("Setup timed out waiting for peer"));
+    assert!(matches!(t2.recv().await.unwrap(), Message::Ready { .. }));
+    assert!(matches!(t2.recv().await.unwrap(), Message::Abort { .. }));
+}
+
+#[test]
+fn test_quick_players_ignore_a_cancelled_placement() {
+    let cancel = AtomicBool::new(true);
+    let mut board = Board::new();
+    let mut rng = SmallRng::seed_from_u64(3);
+    // Only players that wait on someone check the flag
+    assert!(AiPlayer::new().place_ships_cancellable(&mut rng, &mut board, &cancel).is_ok());
+    assert!(board.all_placed());
+    assert_eq!(BoardError::PlacementCancelled.to_string(), "Ship placement was cancelled");
+}
+
+#[tokio::test]
+async fn test_config_mismatch_parts_before_placement() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let placed = Arc::new(AtomicBool::new(false));
+    let watched = |placed: &Arc<AtomicBool>| WatchedPlacer {
+        inner: AiPlayer::new(),
+        placed: placed.clone(),
+    };
+    let mut a = PlayerNode::new(Box::new(watched(&placed)), GameEngine::new(), Box::new(t1));
+    let small = GameEngine::new_with_config(RuleSet::with_board_size(8).unwrap());
+    let mut b = PlayerNode::new(Box::new(watched(&placed)), small, Box::new(t2));
+
+    let responder = tokio::spawn(async move { b.run(&mut SmallRng::seed_from_u64(2), Role::Responder).await });
+    let err = a.run(&mut SmallRng::seed_from_u64(1), Role::Initiator).await.unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch in Rules"), "{}", err);
+    let err = responder.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch in Rules"), "{}", err);
+    assert!(!placed.load(Ordering::SeqCst), "a fleet was placed before the configs were compared");
+}
@@ -0,0 +1,206 @@
+use battleship::{
+    calc_pdf, AvoidHeat, BoardError, AiPlayer, BoardBits, Board, Clustered, CounterMeta, Dispersed, EdgeHugging, FleetConfig,
+    PlacementRules, PlacementStrategy, RuleSet, PlacementStyle, Player, UniformRandom, BOARD_SIZE, MAX_BOARD_SIZE,
+    TOTAL_SHIP_CELLS, WeightedRandom,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+const N: usize = BOARD_SIZE as usize;
+const GRID: usize = MAX_BOARD_SIZE as usize;
+const SAMPLES: u64 = 40;
+
+fn edge_cells(map: &BoardBits) -> usize {
+    map.iter_set_bits()
+        .filter(|&(r, c)| r == 0 || c == 0 || r == N - 1 || c == N - 1)
+        .count()
+}
+
+/// Number of orthogonally adjacent occupied cell pairs.
+fn adjacent_pairs(map: &BoardBits) -> usize {
+    map.iter_set_bits()
+        .map(|(r, c)| {
+            let right = c + 1 < N && map.get(r, c + 1).unwrap();
+            let down = r + 1 < N && map.get(r + 1, c).unwrap();
+            right as usize + down as usize
+        })
+        .sum()
+}
+
+/// Total of `metric` over boards placed by `strategy` with fixed seeds.
+fn total_over_seeds<S: PlacementStrategy>(strategy: S, metric: fn(&BoardBits) -> usize) -> usize {
+    (0..SAMPLES)
+        .map(|seed| {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let mut board = Board::new();
+            strategy.place_fleet(&mut rng, &mut board).unwrap();
+            metric(&board.ship_map())
+        })
+        .sum()
+}
+
+#[test]
+fn test_every_style_places_full_fleet() {
+    for style in [
+        PlacementStyle::UniformRandom,
+        PlacementStyle::EdgeHugging,
+        PlacementStyle::Dispersed,
+        PlacementStyle::Clustered,
+        PlacementStyle::CounterMeta,
+    ] {
+        for seed in 0..10 {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let mut board = Board::new();
+            style.place_fleet(&mut rng, &mut board).unwrap();
+            assert!(board.all_placed(), "{:?} left ships unplaced", style);
+            assert_eq!(board.ship_map().count_ones(), TOTAL_SHIP_CELLS);
+        }
+    }
+}
+
+#[test]
+fn test_strategy_keeps_already_placed_ships() {
+    let mut rng = SmallRng::seed_from_u64(7);
+    let mut board = Board::new();
+    board
+        .place(0, 0, 0, battleship::Orientation::Horizontal)
+        .unwrap();
+    Clustered.place_fleet(&mut rng, &mut board).unwrap();
+    assert!(board.all_placed());
+    assert_eq!(board.ship_states()[0].position, Some((0, 0, battleship::Orientation::Horizontal)));
+}
+
+#[test]
+fn test_edge_hugging_prefers_edges() {
+    let uniform = total_over_seeds(UniformRandom, edge_cells);
+    let edge = total_over_seeds(EdgeHugging, edge_cells);
+    assert!(edge > uniform, "edge hugging {} vs uniform {}", edge, uniform);
+}
+
+#[test]
+fn test_clustered_touches_more_than_dispersed() {
+    let clustered = total_over_seeds(Clustered, adjacent_pairs);
+    let dispersed = total_over_seeds(Dispersed, adjacent_pairs);
+    assert!(
+        clustered > dispersed,
+        "clustered {} vs dispersed {}",
+        clustered,
+        dispersed
+    );
+}
+
+/// Opening-PDF heat of the occupied cells, scaled to an integer.
+fn opening_heat(map: &BoardBits) -> usize {
+    let lengths = common::full_fleet();
+    let pdf = calc_pdf(&BoardBits::new(), &BoardBits::new(), &lengths);
+    map.iter_set_bits().map(|(r, c)| (pdf[r][c] * 1e6) as usize).sum()
+}
+
+#[test]
+fn test_counter_meta_avoids_opening_pdf_hot_cells() {
+    let uniform = total_over_seeds(UniformRandom, opening_heat);
+    let counter = total_over_seeds(CounterMeta, opening_heat);
+    assert!(counter < uniform, "counter-meta {} vs uniform {}", counter, uniform);
+}
+
+#[test]
+fn test_counter_meta_reads_heat_off_the_game_board() {
+    // On an 8x8 board the last row and column are edges, as cool as the
+    // first ones, not the hot middle of the largest board
+    let rules = RuleSet::with_board_size(8).unwrap();
+    let (mut first, mut last) = (0, 0);
+    for seed in 0..SAMPLES {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut board = Board::with_rules(&rules);
+        CounterMeta.place_fleet(&mut rng, &mut board).unwrap();
+        for (r, c) in board.ship_map().iter_set_bits() {
+            first += usize::from(r == 0 || c == 0);
+            last += usize::from(r == 7 || c == 7);
+        }
+    }
+    assert!(3 * last > 2 * first && 3 * first > 2 * last, "first row and column {} vs last {}", first, last);
+}
+
+#[test]
+fn test_ai_player_uses_configured_placement() {
+    let mut player = AiPlayer::with_placement(PlacementStyle::EdgeHugging);
+    assert_eq!(player.placement(), PlacementStyle::EdgeHugging);
+    assert_eq!(AiPlayer::new().placement(), PlacementStyle::UniformRandom);
+
+    let mut rng = SmallRng::seed_from_u64(99);
+    let mut board = Board::new();
+    player.place_ships(&mut rng, &mut board).unwrap();
+    assert!(board.all_placed());
+}
+
+#[test]
+fn test_ai_player_places_dense_fleet_every_time() {
+    // Five cruisers fill 15 of 16 cells; placing them one at a time often
+    // paints itself into a corner
+    let fleet = FleetConfig::empty().with_ships("Cruiser", 3, 5).unwrap();
+    let rules = RuleSet::new(4, fleet).unwrap();
+    for seed in 0..SAMPLES {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut board = Board::with_rules(&rules);
+        AiPlayer::new().place_ships(&mut rng, &mut board).unwrap();
+        assert!(board.all_placed(), "seed {}", seed);
+        assert_eq!(board.ship_map().count_ones(), 15);
+    }
+}
+
+#[test]
+fn test_weighted_styles_place_dense_no_touching_fleet() {
+    // Three carriers fit a 5x5 no-touching board only on alternate rows or
+    // columns; a carrier dropped on row 1 first leaves no room for the third
+    let fleet = FleetConfig::empty().with_ships("Carrier", 5, 3).unwrap();
+    let rules = RuleSet::new(5, fleet).unwrap().with_placement(PlacementRules::NO_TOUCHING);
+    let cool_centre: [[f64; GRID]; GRID] = core::array::from_fn(|r| core::array::from_fn(|c| (r.abs_diff(2) + c.abs_diff(2)) as f64));
+    for seed in 0..SAMPLES {
+        let place = |strategy: &dyn Fn(&mut SmallRng, &mut Board) -> Result<(), BoardError>, name: &str| {
+            let mut rng = SmallRng::seed_from_u64(seed);
+            let mut board = Board::with_rules(&rules);
+            strategy(&mut rng, &mut board).unwrap_or_else(|e| panic!("{} with seed {}: {}", name, seed, e));
+            assert!(board.all_placed(), "{} with seed {}", name, seed);
+            assert_eq!(board.ship_map().count_ones(), 15);
+        };
+        place(&|rng, board| EdgeHugging.place_fleet(rng, board), "EdgeHugging");
+        place(&|rng, board| Dispersed.place_fleet(rng, board), "Dispersed");
+        place(&|rng, board| Clustered.place_fleet(rng, board), "Clustered");
+        place(&|rng, board| CounterMeta.place_fleet(rng, board), "CounterMeta");
+        place(&|rng, board| AvoidHeat::new(cool_centre).place_fleet(rng, board), "AvoidHeat");
+        place(&|rng, board| WeightedRandom::new([[1.0; GRID]; GRID]).place_fleet(rng, board), "WeightedRandom");
+    }
+}
+
+#[test]
+fn test_weighted_random_never_uses_zero_weight_cells() {
+    // Forbid the left three columns entirely
+    let weights: [[f64; GRID]; GRID] = core::array::from_fn(|_| core::array::from_fn(|c| if c < 3 { 0.0 } else { 1.0 }));
+    for seed in 0..SAMPLES {
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut board = Board::new();
+        WeightedRandom::new(weights).place_fleet(&mut rng, &mut board).unwrap();
+        assert!(board.all_placed());
+        assert!(board.ship_map().iter_set_bits().all(|(_, c)| c >= 3));
+    }
+}
+
+#[test]
+fn test_weighted_random_favours_heavy_cells() {
+    let weights: [[f64; GRID]; GRID] = core::array::from_fn(|r| core::array::from_fn(|_| if r < N / 2 { 4.0 } else { 1.0 }));
+    let top = |map: &BoardBits| map.iter_set_bits().filter(|&(r, _)| r < N / 2).count();
+    let weighted = total_over_seeds(WeightedRandom::new(weights), top);
+    let uniform = total_over_seeds(UniformRandom, top);
+    assert!(weighted > uniform, "weighted {} vs uniform {}", weighted, uniform);
+}
+
+#[test]
+fn test_weighted_random_fails_without_any_weight() {
+    let mut rng = SmallRng::seed_from_u64(0);
+    let mut board = Board::new();
+    let err = WeightedRandom::new([[0.0; GRID]; GRID]).place_fleet(&mut rng, &mut board);
+    assert_eq!(err, Err(BoardError::UnableToPlaceShip));
+    assert!(board.ship_map().is_empty());
+}
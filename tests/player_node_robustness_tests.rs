@@ -4,10 +4,122 @@
 /// - Session closure on errors instead of continuing
 use battleship::transport::in_memory::InMemoryTransport;
 use battleship::transport::Transport;
-use battleship::{AiPlayer, GameEngine, Message, Player, PlayerNode, PROTOCOL_VERSION};
-use battleship::domain::GuessResult;
+use battleship::{AiPlayer, Coord, GameEngine, Message, Player, PlayerNode, Role, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION};
+use battleship::domain::{ErrorCode, GameConfig, GuessResult, RemoteError};
+use battleship::protocol::coin_flip;
+use battleship::protocol::commitment::LayoutCommitment;
+use battleship::protocol::CheatDetected;
+use battleship::{Board, RuleSet, Stats};
 use rand::rngs::SmallRng;
-use rand::SeedableRng;
+use rand::{Rng, SeedableRng};
+
+/// Play the peer side of the `Rules` exchange that follows the handshake,
+/// agreeing to `config`.
+async fn peer_rules(peer: &mut InMemoryTransport, config: &GameConfig) {
+    peer.send(Message::Rules {
+        version: PROTOCOL_VERSION,
+        config: config.clone(),
+    })
+    .await
+    .unwrap();
+    match peer.recv().await.unwrap() {
+        Message::Rules { config: theirs, .. } => assert_eq!(theirs, *config),
+        other => panic!("Expected Rules, got {:?}", other),
+    }
+}
+
+/// Play the peer side of the coin flip so that the node moves first iff `node_first`.
+///
+/// The node draws its nonce first thing after the handshake, so a clone of its
+/// RNG taken before `run` predicts that nonce and lets the peer pick its own.
+async fn peer_coin_flip(
+    peer: &mut InMemoryTransport,
+    mut node_rng: SmallRng,
+    peer_role: Role,
+    node_first: bool,
+) {
+    let mut node_nonce = [0u8; coin_flip::NONCE_LEN];
+    node_rng.fill(&mut node_nonce);
+    let mut nonce = [0u8; coin_flip::NONCE_LEN];
+    let initiator_first = match peer_role {
+        Role::Initiator => coin_flip::initiator_moves_first(&nonce, &node_nonce),
+        Role::Responder => coin_flip::initiator_moves_first(&node_nonce, &nonce),
+    };
+    if initiator_first != (node_first == (peer_role == Role::Responder)) {
+        nonce[coin_flip::NONCE_LEN - 1] ^= 1;
+    }
+
+    let commit = Message::CoinCommit {
+        version: PROTOCOL_VERSION,
+        commitment: coin_flip::commitment(&nonce),
+    };
+    let reveal = Message::CoinReveal {
+        version: PROTOCOL_VERSION,
+        nonce,
+    };
+    match peer_role {
+        Role::Initiator => {
+            peer.send(commit).await.unwrap();
+            assert!(matches!(peer.recv().await.unwrap(), Message::CoinCommit { .. }));
+            peer.send(reveal).await.unwrap();
+            assert!(matches!(peer.recv().await.unwrap(), Message::CoinReveal { .. }));
+        }
+        Role::Responder => {
+            assert!(matches!(peer.recv().await.unwrap(), Message::CoinCommit { .. }));
+            peer.send(commit).await.unwrap();
+            assert!(matches!(peer.recv().await.unwrap(), Message::CoinReveal { .. }));
+            peer.send(reveal).await.unwrap();
+        }
+    }
+}
+
+/// Play the peer side of the Ready/StartGame exchange that follows the coin
+/// flip, and of the layout commitments after it.
+async fn peer_ready(peer: &mut InMemoryTransport, peer_role: Role, node_first: bool) {
+    peer.send(Message::Ready { version: PROTOCOL_VERSION }).await.unwrap();
+    assert!(matches!(peer.recv().await.unwrap(), Message::Ready { .. }));
+    let node_role = match peer_role {
+        Role::Initiator => Role::Responder,
+        Role::Responder => Role::Initiator,
+    };
+    let first_mover = if node_first { node_role } else { peer_role };
+    match peer_role {
+        Role::Initiator => {
+            peer.send(Message::StartGame {
+                version: PROTOCOL_VERSION,
+                config: GameConfig::local(),
+                first_mover,
+            })
+            .await
+            .unwrap();
+        }
+        Role::Responder => match peer.recv().await.unwrap() {
+            Message::StartGame {
+                config,
+                first_mover: announced,
+                ..
+            } => {
+                assert_eq!(config, GameConfig::local());
+                assert_eq!(announced, first_mover);
+            }
+            other => panic!("Expected StartGame, got {:?}", other),
+        },
+    }
+    let commit = Message::Commit {
+        version: PROTOCOL_VERSION,
+        digest: [0; 32],
+    };
+    match peer_role {
+        Role::Initiator => {
+            peer.send(commit).await.unwrap();
+            assert!(matches!(peer.recv().await.unwrap(), Message::Commit { .. }));
+        }
+        Role::Responder => {
+            assert!(matches!(peer.recv().await.unwrap(), Message::Commit { .. }));
+            peer.send(commit).await.unwrap();
+        }
+    }
+}
 
 #[tokio::test]
 async fn test_handshake_rejects_wrong_version() {
@@ -22,14 +134,21 @@ async fn test_handshake_rejects_wrong_version() {
 
     // Start node as initiator in background
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng, true).await
+        node.run(&mut rng, Role::Initiator).await
     });
 
     // Receive handshake
     let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { version: PROTOCOL_VERSION }));
-
-    // Send back wrong version
+    assert!(matches!(
+        msg,
+        Message::Handshake { version: PROTOCOL_VERSION }
+            | Message::HandshakeRange {
+                min_version: MIN_PROTOCOL_VERSION,
+                max_version: PROTOCOL_VERSION
+            }
+    ));
+
+    // Send back a version the node did not offer
     t2.send(Message::HandshakeAck {
         version: PROTOCOL_VERSION + 1,
     })
@@ -54,19 +173,18 @@ async fn test_handshake_rejects_unexpected_message() {
 
     // Start node as initiator
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng, true).await
+        node.run(&mut rng, Role::Initiator).await
     });
 
     // Receive handshake
     let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { .. }));
+    assert!(matches!(msg, Message::Handshake { .. } | Message::HandshakeRange { .. }));
 
     // Send unexpected message instead of HandshakeAck
     t2.send(Message::Guess {
         version: PROTOCOL_VERSION,
         seq: 0,
-        x: 0,
-        y: 0,
+        target: Coord::new(0, 0),
     })
     .await
     .unwrap();
@@ -89,21 +207,28 @@ async fn test_responder_handshake_rejects_wrong_version() {
 
     // Start node as responder
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng, false).await
+        node.run(&mut rng, Role::Responder).await
     });
 
-    // Send handshake with wrong version
+    // Send handshake offering only newer versions
     t1.send(Message::Handshake {
         version: PROTOCOL_VERSION + 1,
     })
     .await
     .unwrap();
 
-    // Node should reject
+    // Node should reject, telling the peer why
     let result = node_handle.await.unwrap();
     assert!(result.is_err());
     let err = result.unwrap_err();
     assert!(err.to_string().contains("version mismatch"));
+    assert!(matches!(
+        t1.recv().await.unwrap(),
+        Message::Error {
+            code: ErrorCode::VersionMismatch,
+            ..
+        }
+    ));
 }
 
 #[tokio::test]
@@ -117,7 +242,7 @@ async fn test_responder_handshake_rejects_unexpected_message() {
 
     // Start node as responder
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng, false).await
+        node.run(&mut rng, Role::Responder).await
     });
 
     // Send unexpected message instead of Handshake
@@ -125,6 +250,7 @@ async fn test_responder_handshake_rejects_unexpected_message() {
         version: PROTOCOL_VERSION,
         seq: 0,
         res: GuessResult::Miss,
+        digest: None,
     })
     .await
     .unwrap();
@@ -144,15 +270,19 @@ async fn test_sequence_mismatch_in_status_resp() {
     let mut engine = GameEngine::new();
     player.place_ships(&mut rng1, engine.board_mut()).unwrap();
     let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t1));
+    let node_rng = rng1.clone();
 
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng1, true).await
+        node.run(&mut rng1, Role::Initiator).await
     });
 
     // Complete handshake
     let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { .. }));
+    assert!(matches!(msg, Message::Handshake { .. } | Message::HandshakeRange { .. }));
     t2.send(Message::HandshakeAck { version: PROTOCOL_VERSION }).await.unwrap();
+    peer_rules(&mut t2, &GameConfig::local()).await;
+    peer_coin_flip(&mut t2, node_rng, Role::Responder, true).await;
+    peer_ready(&mut t2, Role::Responder, true).await;
 
     // Receive first guess
     let msg = t2.recv().await.unwrap();
@@ -163,6 +293,7 @@ async fn test_sequence_mismatch_in_status_resp() {
         version: PROTOCOL_VERSION,
         seq: 999, // Wrong seq, should be 0
         res: GuessResult::Miss,
+        digest: None,
     })
     .await
     .unwrap();
@@ -182,15 +313,19 @@ async fn test_version_mismatch_in_status_resp() {
     let mut engine = GameEngine::new();
     player.place_ships(&mut rng1, engine.board_mut()).unwrap();
     let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t1));
+    let node_rng = rng1.clone();
 
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng1, true).await
+        node.run(&mut rng1, Role::Initiator).await
     });
 
     // Complete handshake
     let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { .. }));
+    assert!(matches!(msg, Message::Handshake { .. } | Message::HandshakeRange { .. }));
     t2.send(Message::HandshakeAck { version: PROTOCOL_VERSION }).await.unwrap();
+    peer_rules(&mut t2, &GameConfig::local()).await;
+    peer_coin_flip(&mut t2, node_rng, Role::Responder, true).await;
+    peer_ready(&mut t2, Role::Responder, true).await;
 
     // Receive first guess
     let msg = t2.recv().await.unwrap();
@@ -201,6 +336,7 @@ async fn test_version_mismatch_in_status_resp() {
         version: PROTOCOL_VERSION + 1,
         seq: 0,
         res: GuessResult::Miss,
+        digest: None,
     })
     .await
     .unwrap();
@@ -220,15 +356,19 @@ async fn test_unexpected_message_instead_of_status_resp() {
     let mut engine = GameEngine::new();
     player.place_ships(&mut rng1, engine.board_mut()).unwrap();
     let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t1));
+    let node_rng = rng1.clone();
 
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng1, true).await
+        node.run(&mut rng1, Role::Initiator).await
     });
 
     // Complete handshake
     let msg = t2.recv().await.unwrap();
-    assert!(matches!(msg, Message::Handshake { .. }));
+    assert!(matches!(msg, Message::Handshake { .. } | Message::HandshakeRange { .. }));
     t2.send(Message::HandshakeAck { version: PROTOCOL_VERSION }).await.unwrap();
+    peer_rules(&mut t2, &GameConfig::local()).await;
+    peer_coin_flip(&mut t2, node_rng, Role::Responder, true).await;
+    peer_ready(&mut t2, Role::Responder, true).await;
 
     // Receive first guess
     let msg = t2.recv().await.unwrap();
@@ -254,22 +394,25 @@ async fn test_sequence_mismatch_in_guess() {
     let mut engine = GameEngine::new();
     player.place_ships(&mut rng2, engine.board_mut()).unwrap();
     let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t2));
+    let node_rng = rng2.clone();
 
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng2, false).await
+        node.run(&mut rng2, Role::Responder).await
     });
 
     // Complete handshake
     t1.send(Message::Handshake { version: PROTOCOL_VERSION }).await.unwrap();
     let msg = t1.recv().await.unwrap();
     assert!(matches!(msg, Message::HandshakeAck { .. }));
+    peer_rules(&mut t1, &GameConfig::local()).await;
+    peer_coin_flip(&mut t1, node_rng, Role::Initiator, false).await;
+    peer_ready(&mut t1, Role::Initiator, false).await;
 
     // Send guess with wrong sequence number
     t1.send(Message::Guess {
         version: PROTOCOL_VERSION,
         seq: 5, // Wrong, should be 0
-        x: 0,
-        y: 0,
+        target: Coord::new(0, 0),
     })
     .await
     .unwrap();
@@ -289,22 +432,25 @@ async fn test_version_mismatch_in_guess() {
     let mut engine = GameEngine::new();
     player.place_ships(&mut rng2, engine.board_mut()).unwrap();
     let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t2));
+    let node_rng = rng2.clone();
 
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng2, false).await
+        node.run(&mut rng2, Role::Responder).await
     });
 
     // Complete handshake
     t1.send(Message::Handshake { version: PROTOCOL_VERSION }).await.unwrap();
     let msg = t1.recv().await.unwrap();
     assert!(matches!(msg, Message::HandshakeAck { .. }));
+    peer_rules(&mut t1, &GameConfig::local()).await;
+    peer_coin_flip(&mut t1, node_rng, Role::Initiator, false).await;
+    peer_ready(&mut t1, Role::Initiator, false).await;
 
     // Send guess with wrong version
     t1.send(Message::Guess {
         version: PROTOCOL_VERSION + 1,
         seq: 0,
-        x: 0,
-        y: 0,
+        target: Coord::new(0, 0),
     })
     .await
     .unwrap();
@@ -324,21 +470,26 @@ async fn test_unexpected_message_instead_of_guess() {
     let mut engine = GameEngine::new();
     player.place_ships(&mut rng2, engine.board_mut()).unwrap();
     let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t2));
+    let node_rng = rng2.clone();
 
     let node_handle = tokio::spawn(async move {
-        node.run(&mut rng2, false).await
+        node.run(&mut rng2, Role::Responder).await
     });
 
     // Complete handshake
     t1.send(Message::Handshake { version: PROTOCOL_VERSION }).await.unwrap();
     let msg = t1.recv().await.unwrap();
     assert!(matches!(msg, Message::HandshakeAck { .. }));
+    peer_rules(&mut t1, &GameConfig::local()).await;
+    peer_coin_flip(&mut t1, node_rng, Role::Initiator, false).await;
+    peer_ready(&mut t1, Role::Initiator, false).await;
 
     // Send unexpected message type instead of Guess
     t1.send(Message::StatusResp {
         version: PROTOCOL_VERSION,
         seq: 0,
         res: GuessResult::Miss,
+        digest: None,
     })
     .await
     .unwrap();
@@ -369,8 +520,8 @@ async fn test_successful_game_with_strict_validation() {
     let mut node2 = PlayerNode::new(Box::new(p2), e2, Box::new(t2));
 
     let result = tokio::join!(
-        async { node1.run(&mut rng1, true).await },
-        async { node2.run(&mut rng2, false).await },
+        async { node1.run(&mut rng1, Role::Initiator).await },
+        async { node2.run(&mut rng2, Role::Responder).await },
     );
 
     // Both should complete successfully
@@ -381,4 +532,460 @@ async fn test_successful_game_with_strict_validation() {
     let status1 = node1.status();
     let status2 = node2.status();
     assert_ne!(status1, status2);
+
+    // Both sides agree on the coin flip outcome
+    let first1 = node1.first_move().expect("coin flip completed");
+    let first2 = node2.first_move().expect("coin flip completed");
+    assert_ne!(first1, first2);
+}
+
+#[tokio::test]
+async fn test_coin_flip_rejects_mismatched_reveal() {
+    let (t1, mut t2) = InMemoryTransport::pair();
+    let mut rng = SmallRng::seed_from_u64(777);
+    let mut player = AiPlayer::new();
+    let mut engine = GameEngine::new();
+    player.place_ships(&mut rng, engine.board_mut()).unwrap();
+    let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t1));
+
+    let node_handle = tokio::spawn(async move {
+        node.run(&mut rng, Role::Initiator).await
+    });
+
+    // Complete handshake
+    let msg = t2.recv().await.unwrap();
+    assert!(matches!(msg, Message::Handshake { .. } | Message::HandshakeRange { .. }));
+    t2.send(Message::HandshakeAck { version: PROTOCOL_VERSION }).await.unwrap();
+    peer_rules(&mut t2, &GameConfig::local()).await;
+
+    // Commit to one nonce, then reveal a different one
+    let msg = t2.recv().await.unwrap();
+    assert!(matches!(msg, Message::CoinCommit { .. }));
+    t2.send(Message::CoinCommit {
+        version: PROTOCOL_VERSION,
+        commitment: coin_flip::commitment(&[1u8; coin_flip::NONCE_LEN]),
+    })
+    .await
+    .unwrap();
+    let msg = t2.recv().await.unwrap();
+    assert!(matches!(msg, Message::CoinReveal { .. }));
+    t2.send(Message::CoinReveal {
+        version: PROTOCOL_VERSION,
+        nonce: [2u8; coin_flip::NONCE_LEN],
+    })
+    .await
+    .unwrap();
+
+    let result = node_handle.await.unwrap();
+    assert!(result.is_err());
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("does not match commitment"));
+}
+
+#[tokio::test]
+async fn test_guess_before_ready_rejected() {
+    let (mut t1, t2) = InMemoryTransport::pair();
+    let mut rng2 = SmallRng::seed_from_u64(888);
+    let mut player = AiPlayer::new();
+    let mut engine = GameEngine::new();
+    player.place_ships(&mut rng2, engine.board_mut()).unwrap();
+    let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t2));
+    let node_rng = rng2.clone();
+
+    let node_handle = tokio::spawn(async move {
+        node.run(&mut rng2, Role::Responder).await
+    });
+
+    t1.send(Message::Handshake { version: PROTOCOL_VERSION }).await.unwrap();
+    let msg = t1.recv().await.unwrap();
+    assert!(matches!(msg, Message::HandshakeAck { .. }));
+    peer_rules(&mut t1, &GameConfig::local()).await;
+    peer_coin_flip(&mut t1, node_rng, Role::Initiator, false).await;
+
+    // Skip Ready/StartGame and fire straight away
+    t1.send(Message::Guess {
+        version: PROTOCOL_VERSION,
+        seq: 0,
+        target: Coord::new(0, 0),
+    })
+    .await
+    .unwrap();
+
+    let result = node_handle.await.unwrap();
+    assert!(result.is_err());
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("Expected Ready"));
+}
+
+#[tokio::test]
+async fn test_start_game_rejects_wrong_first_mover() {
+    let (mut t1, t2) = InMemoryTransport::pair();
+    let mut rng2 = SmallRng::seed_from_u64(999);
+    let mut player = AiPlayer::new();
+    let mut engine = GameEngine::new();
+    player.place_ships(&mut rng2, engine.board_mut()).unwrap();
+    let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t2));
+    let node_rng = rng2.clone();
+
+    let node_handle = tokio::spawn(async move {
+        node.run(&mut rng2, Role::Responder).await
+    });
+
+    t1.send(Message::Handshake { version: PROTOCOL_VERSION }).await.unwrap();
+    let msg = t1.recv().await.unwrap();
+    assert!(matches!(msg, Message::HandshakeAck { .. }));
+    peer_rules(&mut t1, &GameConfig::local()).await;
+    peer_coin_flip(&mut t1, node_rng, Role::Initiator, false).await;
+
+    t1.send(Message::Ready { version: PROTOCOL_VERSION }).await.unwrap();
+    assert!(matches!(t1.recv().await.unwrap(), Message::Ready { .. }));
+    // The coin flip made the initiator first; claim the opposite
+    t1.send(Message::StartGame {
+        version: PROTOCOL_VERSION,
+        config: GameConfig::local(),
+        first_mover: Role::Responder,
+    })
+    .await
+    .unwrap();
+
+    let result = node_handle.await.unwrap();
+    assert!(result.is_err());
+    let err = result.unwrap_err();
+    assert!(err.to_string().contains("First mover mismatch"));
+}
+
+#[tokio::test]
+async fn test_nodes_place_unplaced_fleets_before_ready() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut rng1 = SmallRng::seed_from_u64(2001);
+    let mut rng2 = SmallRng::seed_from_u64(2002);
+
+    let mut node1 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(t2));
+
+    let (r1, r2) = tokio::join!(
+        node1.run(&mut rng1, Role::Initiator),
+        node2.run(&mut rng2, Role::Responder)
+    );
+    assert!(r1.is_ok());
+    assert!(r2.is_ok());
+    assert_ne!(node1.status(), battleship::GameStatus::InProgress);
+}
+
+/// Run a node that moves first until its first guess, returning the guess seq.
+async fn node_at_first_guess(
+    seed: u64,
+) -> (
+    InMemoryTransport,
+    u64,
+    tokio::task::JoinHandle<(anyhow::Result<()>, battleship::GameStatus)>,
+) {
+    let (t1, mut t2) = InMemoryTransport::pair();
+    let mut rng1 = SmallRng::seed_from_u64(seed);
+    let mut player = AiPlayer::new();
+    let mut engine = GameEngine::new();
+    player.place_ships(&mut rng1, engine.board_mut()).unwrap();
+    let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t1));
+    let node_rng = rng1.clone();
+
+    let node_handle = tokio::spawn(async move {
+        let result = node.run(&mut rng1, Role::Initiator).await;
+        (result, node.status())
+    });
+
+    let msg = t2.recv().await.unwrap();
+    assert!(matches!(msg, Message::Handshake { .. } | Message::HandshakeRange { .. }));
+    t2.send(Message::HandshakeAck { version: PROTOCOL_VERSION }).await.unwrap();
+    peer_rules(&mut t2, &GameConfig::local()).await;
+    peer_coin_flip(&mut t2, node_rng, Role::Responder, true).await;
+    peer_ready(&mut t2, Role::Responder, true).await;
+
+    let seq = match t2.recv().await.unwrap() {
+        Message::Guess { seq, .. } => seq,
+        other => panic!("Expected Guess, got {:?}", other),
+    };
+    (t2, seq, node_handle)
+}
+
+#[tokio::test]
+async fn test_premature_sink_is_protocol_violation() {
+    let (mut t2, seq, node_handle) = node_at_first_guess(2215).await;
+
+    // A five-cell carrier cannot sink on the first hit
+    t2.send(Message::StatusResp {
+        version: PROTOCOL_VERSION,
+        seq,
+        res: GuessResult::Sink("Carrier".to_string()),
+        digest: None,
+    })
+    .await
+    .unwrap();
+
+    let (result, status) = node_handle.await.unwrap();
+    assert_eq!(status, battleship::GameStatus::InProgress);
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("Protocol violation"), "{}", err);
+    assert!(err.contains("Carrier"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_state_digest_mismatch_closes_session() {
+    let (mut t2, seq, node_handle) = node_at_first_guess(3508).await;
+    t2.send(Message::StatusResp {
+        version: PROTOCOL_VERSION,
+        seq,
+        res: GuessResult::Miss,
+        digest: Some(0),
+    })
+    .await
+    .unwrap();
+
+    assert!(matches!(
+        t2.recv().await.unwrap(),
+        Message::Error { code: ErrorCode::InvalidState, seq: s, .. } if s == seq
+    ));
+    let (result, status) = node_handle.await.unwrap();
+    assert_eq!(status, battleship::GameStatus::InProgress);
+    let err = result.unwrap_err().to_string();
+    assert!(err.contains("desync"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_unknown_ship_sink_is_protocol_violation() {
+    let (mut t2, seq, node_handle) = node_at_first_guess(2216).await;
+    t2.send(Message::StatusResp {
+        version: PROTOCOL_VERSION,
+        seq,
+        res: GuessResult::Sink("Dinghy".to_string()),
+        digest: None,
+    })
+    .await
+    .unwrap();
+
+    let err = node_handle.await.unwrap().0.unwrap_err().to_string();
+    assert!(err.contains("unknown ship"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_out_of_bounds_guess_is_protocol_violation() {
+    let (mut t2, seq, node_handle) = node_at_first_guess(2217).await;
+    t2.send(Message::StatusResp {
+        version: PROTOCOL_VERSION,
+        seq,
+        res: GuessResult::Miss,
+        digest: None,
+    })
+    .await
+    .unwrap();
+    t2.send(Message::Guess {
+        version: PROTOCOL_VERSION,
+        seq: 0,
+        target: Coord::new(200, 3),
+    })
+    .await
+    .unwrap();
+
+    let err = node_handle.await.unwrap().0.unwrap_err().to_string();
+    assert!(err.contains("Protocol violation"), "{}", err);
+    assert!(err.contains("outside"), "{}", err);
+}
+
+#[tokio::test]
+async fn test_illegal_guess_is_reported_with_error_code() {
+    let (mut t2, seq, node_handle) = node_at_first_guess(2237).await;
+    t2.send(Message::StatusResp {
+        version: PROTOCOL_VERSION,
+        seq,
+        res: GuessResult::Miss,
+        digest: None,
+    })
+    .await
+    .unwrap();
+    t2.send(Message::Guess {
+        version: PROTOCOL_VERSION,
+        seq: 0,
+        target: Coord::new(0, 10),
+    })
+    .await
+    .unwrap();
+
+    match t2.recv().await.unwrap() {
+        Message::Error { seq, code, .. } => {
+            assert_eq!(seq, 0);
+            assert_eq!(code, ErrorCode::InvalidCoordinate);
+            assert!(code.is_fatal());
+        }
+        other => panic!("Expected Error, got {:?}", other),
+    }
+    assert!(node_handle.await.unwrap().0.is_err());
+}
+
+#[tokio::test]
+async fn test_peer_error_surfaces_as_remote_error() {
+    let (mut t2, seq, node_handle) = node_at_first_guess(2238).await;
+    t2.send(Message::Error {
+        version: PROTOCOL_VERSION,
+        seq,
+        code: ErrorCode::RuleViolation,
+        detail: "cell already targeted".to_string(),
+    })
+    .await
+    .unwrap();
+
+    let err = node_handle.await.unwrap().0.unwrap_err();
+    let remote = err.downcast_ref::<RemoteError>().expect("RemoteError");
+    assert_eq!(remote.code, ErrorCode::RuleViolation);
+    assert_eq!(remote.detail, "cell already targeted");
+}
+
+#[tokio::test]
+async fn test_abort_instead_of_guess_ends_game() {
+    let (mut t1, t2) = InMemoryTransport::pair();
+    let mut rng2 = SmallRng::seed_from_u64(667);
+    let mut player = AiPlayer::new();
+    let mut engine = GameEngine::new();
+    player.place_ships(&mut rng2, engine.board_mut()).unwrap();
+    let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t2));
+    let mut events = node.subscribe();
+    let node_rng = rng2.clone();
+
+    let node_handle = tokio::spawn(async move {
+        let result = node.run(&mut rng2, Role::Responder).await;
+        (result, node.status())
+    });
+
+    t1.send(Message::Handshake { version: PROTOCOL_VERSION }).await.unwrap();
+    assert!(matches!(t1.recv().await.unwrap(), Message::HandshakeAck { .. }));
+    peer_rules(&mut t1, &GameConfig::local()).await;
+    peer_coin_flip(&mut t1, node_rng, Role::Initiator, false).await;
+    peer_ready(&mut t1, Role::Initiator, false).await;
+
+    t1.send(Message::Abort {
+        version: PROTOCOL_VERSION,
+        seq: 0,
+        reason: "operator shutdown".to_string(),
+    })
+    .await
+    .unwrap();
+    assert!(matches!(t1.recv().await.unwrap(), Message::Ack { seq: 0, .. }));
+
+    let (result, status) = node_handle.await.unwrap();
+    result.unwrap();
+    assert_eq!(status, battleship::GameStatus::Aborted);
+    let mut last = None;
+    while let Ok(event) = events.try_recv() {
+        last = Some(event);
+    }
+    assert!(matches!(
+        last,
+        Some(battleship::GameEvent::GameOver {
+            status: battleship::GameStatus::Aborted
+        })
+    ));
+}
+
+/// Play a one-move game against a node that moves second, answering its
+/// shot truthfully or not, then reveal the layout committed to and report
+/// the game over. Returns the node's result.
+async fn play_one_move_and_reveal(honest: bool) -> anyhow::Result<()> {
+    let (mut t1, t2) = InMemoryTransport::pair();
+    let mut rng = SmallRng::seed_from_u64(4242);
+    let rules = RuleSet::STANDARD.with_move_limit(Some(1));
+    let mut player = AiPlayer::new();
+    let mut engine = GameEngine::new_with_config(rules);
+    player.place_ships(&mut rng, engine.board_mut()).unwrap();
+    let mut node = PlayerNode::new(Box::new(player), engine, Box::new(t2));
+    let node_rng = rng.clone();
+    let node_handle = tokio::spawn(async move { node.run(&mut rng, Role::Responder).await });
+
+    t1.send(Message::Handshake { version: PROTOCOL_VERSION }).await.unwrap();
+    assert!(matches!(t1.recv().await.unwrap(), Message::HandshakeAck { .. }));
+    peer_rules(&mut t1, &GameConfig::from(&rules)).await;
+    peer_coin_flip(&mut t1, node_rng, Role::Initiator, false).await;
+    t1.send(Message::Ready { version: PROTOCOL_VERSION }).await.unwrap();
+    assert!(matches!(t1.recv().await.unwrap(), Message::Ready { .. }));
+    t1.send(Message::StartGame {
+        version: PROTOCOL_VERSION,
+        config: GameConfig::from(&rules),
+        first_mover: Role::Initiator,
+    })
+    .await
+    .unwrap();
+
+    let mut peer_rng = SmallRng::seed_from_u64(7);
+    let mut fleet = Board::new();
+    AiPlayer::new().place_ships(&mut peer_rng, &mut fleet).unwrap();
+    let layout = LayoutCommitment::new(&mut peer_rng, &fleet);
+    t1.send(Message::Commit {
+        version: PROTOCOL_VERSION,
+        digest: layout.digest(),
+    })
+    .await
+    .unwrap();
+    assert!(matches!(t1.recv().await.unwrap(), Message::Commit { .. }));
+
+    t1.send(Message::Guess {
+        version: PROTOCOL_VERSION,
+        seq: 0,
+        target: Coord::new(0, 0),
+    })
+    .await
+    .unwrap();
+    let Message::StatusResp { res: answer, .. } = t1.recv().await.unwrap() else {
+        panic!("Expected the node's StatusResp");
+    };
+    let Message::Guess { target, .. } = t1.recv().await.unwrap() else {
+        panic!("Expected the node's Guess");
+    };
+    let on_ship = fleet.ship_map().get(target.row, target.col).unwrap();
+    let res = if on_ship == honest { GuessResult::Hit } else { GuessResult::Miss };
+    t1.send(Message::StatusResp {
+        version: PROTOCOL_VERSION,
+        seq: 0,
+        res,
+        digest: None,
+    })
+    .await
+    .unwrap();
+
+    // The move limit draws the game, so both layouts are revealed
+    t1.send(Message::Reveal {
+        version: PROTOCOL_VERSION,
+        salt: layout.salt,
+        layout: layout.layout,
+    })
+    .await
+    .unwrap();
+    if honest {
+        assert!(matches!(t1.recv().await.unwrap(), Message::Reveal { .. }));
+        fleet.guess(target).unwrap();
+        let stats = Stats {
+            hits: usize::from(!matches!(answer, GuessResult::Miss)),
+            misses: usize::from(matches!(answer, GuessResult::Miss)),
+            ..Stats::default()
+        };
+        t1.send(Message::GameOver {
+            version: PROTOCOL_VERSION,
+            final_board: (&fleet).into(),
+            stats,
+        })
+        .await
+        .unwrap();
+        assert!(matches!(t1.recv().await.unwrap(), Message::GameOver { .. }));
+    }
+    node_handle.await.unwrap()
+}
+
+#[tokio::test]
+async fn test_honest_reveal_is_accepted() {
+    play_one_move_and_reveal(true).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_reveal_contradicting_a_result_is_cheat_detected() {
+    let err = play_one_move_and_reveal(false).await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<CheatDetected>(),
+        Some(CheatDetected::ResultMismatch { .. })
+    ));
 }
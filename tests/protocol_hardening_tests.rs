@@ -1,7 +1,7 @@
 use battleship::transport::in_memory::InMemoryTransport;
 use battleship::transport::tcp::TcpTransport;
 use battleship::transport::Transport;
-use battleship::{AiPlayer, GameEngine, Message, Player, PlayerNode, PROTOCOL_VERSION};
+use battleship::{AiPlayer, Coord, GameEngine, Message, Player, PlayerNode, Role, PROTOCOL_VERSION};
 use rand::rngs::SmallRng;
 use rand::SeedableRng;
 use tokio::net::TcpListener;
@@ -58,8 +58,7 @@ async fn test_sequence_validation() {
     t1.send(Message::Guess {
         version: PROTOCOL_VERSION,
         seq: 0,
-        x: 0,
-        y: 0,
+        target: Coord::new(0, 0),
     })
     .await
     .unwrap();
@@ -72,6 +71,7 @@ async fn test_sequence_validation() {
         version: PROTOCOL_VERSION,
         seq: 0,
         res: battleship::domain::GuessResult::Miss,
+        digest: None,
     })
     .await
     .unwrap();
@@ -123,8 +123,8 @@ async fn test_successful_handshake_and_game() {
     let mut node2 = PlayerNode::new(Box::new(p2), e2, Box::new(t2));
 
     let result = tokio::join!(
-        async { node1.run(&mut rng1, true).await },
-        async { node2.run(&mut rng2, false).await },
+        async { node1.run(&mut rng1, Role::Initiator).await },
+        async { node2.run(&mut rng2, Role::Responder).await },
     );
 
     // Both should complete successfully with handshake and strict sequence validation
@@ -0,0 +1,105 @@
+use battleship::domain::{ErrorCode, GuessResult as DomainGuessResult};
+use battleship::protocol::validation::{validate_guess, validate_result};
+use battleship::{Coord, GameEngine, GuessResult, Orientation, ProtocolViolation, NUM_SHIPS, SHIPS};
+
+fn engine() -> GameEngine {
+    let mut engine = GameEngine::new();
+    for i in 0..NUM_SHIPS {
+        engine.board_mut().place(i, i, 0, Orientation::Horizontal).unwrap();
+    }
+    engine
+}
+
+fn sink(name: &str) -> DomainGuessResult {
+    DomainGuessResult::Sink(name.to_string())
+}
+
+#[test]
+fn test_guess_bounds() {
+    let engine = engine();
+    assert_eq!(validate_guess(&engine, Coord::new(9, 9)), Ok(Coord::new(9, 9)));
+    assert_eq!(
+        validate_guess(&engine, Coord::new(10, 0)),
+        Err(ProtocolViolation::CoordinateOutOfBounds { target: Coord::new(10, 0), size: 10 })
+    );
+    assert!(validate_guess(&engine, Coord::new(0, u8::MAX as usize)).is_err());
+}
+
+#[test]
+fn test_guess_after_game_lost() {
+    let mut engine = engine();
+    for (r, c) in engine.board().ship_map().iter_set_bits() {
+        engine.opponent_guess(r, c).unwrap();
+    }
+    assert_eq!(validate_guess(&engine, Coord::new(9, 9)), Err(ProtocolViolation::GameFinished));
+}
+
+#[test]
+fn test_sink_needs_enough_hits() {
+    let mut engine = engine();
+    // Destroyer (length 2) cannot sink on the first hit
+    assert_eq!(
+        validate_result(&engine, &sink("Destroyer")),
+        Err(ProtocolViolation::ImpossibleSink {
+            ship: "Destroyer",
+            hits: 1,
+            required: 2
+        })
+    );
+    engine.record_guess(0, 0, GuessResult::Hit).unwrap();
+    assert_eq!(validate_result(&engine, &sink("Destroyer")), Ok(GuessResult::Sink("Destroyer")));
+    engine.record_guess(0, 1, GuessResult::Sink("Destroyer")).unwrap();
+
+    // A second sink must also account for the destroyer's cells
+    engine.record_guess(0, 2, GuessResult::Hit).unwrap();
+    assert!(matches!(
+        validate_result(&engine, &sink("Submarine")),
+        Err(ProtocolViolation::ImpossibleSink { required: 5, hits: 4, .. })
+    ));
+    assert_eq!(
+        validate_result(&engine, &sink("Destroyer")),
+        Err(ProtocolViolation::ShipAlreadySunk("Destroyer"))
+    );
+}
+
+#[test]
+fn test_unknown_ship() {
+    assert_eq!(
+        validate_result(&engine(), &sink("Rowboat")),
+        Err(ProtocolViolation::UnknownShip("Rowboat".to_string()))
+    );
+}
+
+#[test]
+fn test_hit_cannot_cover_whole_fleet() {
+    let mut engine = engine();
+    let mut n = 0;
+    for ship in SHIPS.iter() {
+        for _ in 1..ship.length() {
+            engine.record_guess(n / 10, n % 10, GuessResult::Hit).unwrap();
+            n += 1;
+        }
+    }
+    // 12 hits, one short per ship: 4 more plain hits fit, the 17th does not
+    for _ in 0..4 {
+        assert_eq!(validate_result(&engine, &DomainGuessResult::Hit), Ok(GuessResult::Hit));
+        engine.record_guess(n / 10, n % 10, GuessResult::Hit).unwrap();
+        n += 1;
+    }
+    assert_eq!(
+        validate_result(&engine, &DomainGuessResult::Hit),
+        Err(ProtocolViolation::ImpossibleHit { hits: 17 })
+    );
+    assert_eq!(validate_result(&engine, &DomainGuessResult::Miss), Ok(GuessResult::Miss));
+}
+
+#[test]
+fn test_violation_error_codes() {
+    let engine = engine();
+    assert_eq!(validate_guess(&engine, Coord::new(10, 0)).unwrap_err().code(), ErrorCode::InvalidCoordinate);
+    assert_eq!(validate_result(&engine, &sink("Dinghy")).unwrap_err().code(), ErrorCode::RuleViolation);
+    assert_eq!(ProtocolViolation::InvalidSync("test").code(), ErrorCode::InvalidState);
+    assert!(!ErrorCode::BadSeq.is_fatal());
+    assert!(!ErrorCode::Throttled.is_fatal());
+    assert!(ErrorCode::VersionMismatch.is_fatal());
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    domain::GuessResult,
+    protocol::{GameApi, RefereeNode, RefereeOutcome},
+    transport::in_memory::InMemoryTransport,
+    Coord, Role, Stub,
+};
+use std::time::Duration;
+
+mod common;
+
+fn referee() -> (RefereeNode, Stub<InMemoryTransport>, Stub<InMemoryTransport>) {
+    let (a_server, a_client) = InMemoryTransport::pair();
+    let (b_server, b_client) = InMemoryTransport::pair();
+    let node = RefereeNode::new(Box::new(a_server), Box::new(b_server)).with_first_mover(Role::Initiator);
+    (node, Stub::new(a_client), Stub::new(b_client))
+}
+
+/// Guess every cell row by row until the enemy fleet is sunk, returning the
+/// number of shots, or the error that ended the game for this player.
+async fn sweep(stub: &mut Stub<InMemoryTransport>) -> anyhow::Result<usize> {
+    let mut sunk = 0;
+    for shot in 0..100 {
+        if let GuessResult::Sink(_) = stub.make_guess(Coord::new(shot / 10, shot % 10)).await? {
+            sunk += 1;
+            if sunk == 5 {
+                return Ok(shot + 1);
+            }
+        }
+    }
+    Err(anyhow::anyhow!("fleet not sunk after 100 shots"))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn referee_adjudicates_a_full_game() -> anyhow::Result<()> {
+    let (mut node, mut a, mut b) = referee();
+    let referee = tokio::spawn(async move { node.run().await });
+
+    a.place_fleet(common::stacked_placements()).await?.unwrap();
+    b.place_fleet(common::stacked_placements()).await?.unwrap();
+    let (a_shots, b_result) = tokio::join!(sweep(&mut a), sweep(&mut b));
+    // The last ship cell is row 4, column 1: 42 shots in row-major order
+    assert_eq!(a_shots?, 42);
+    let err = b_result.unwrap_err().to_string();
+    assert!(err.contains("Lost"), "{}", err);
+
+    drop((a, b));
+    let outcome = referee.await??;
+    assert_eq!(
+        outcome,
+        RefereeOutcome::Finished {
+            winner: Role::Initiator,
+            shots: [42, 41],
+        }
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn early_guess_waits_for_both_fleets_and_its_turn() -> anyhow::Result<()> {
+    let (mut node, mut a, mut b) = referee();
+    let referee = tokio::spawn(async move { node.run().await });
+
+    b.place_fleet(common::stacked_placements()).await?.unwrap();
+    let waiting = tokio::spawn(async move {
+        let res = b.make_guess(Coord::new(9, 9)).await;
+        (b, res)
+    });
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiting.is_finished(), "guess answered before the game started");
+
+    a.place_fleet(common::stacked_placements()).await?.unwrap();
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert!(!waiting.is_finished(), "guess answered before the first mover shot");
+
+    assert!(matches!(a.make_guess(Coord::new(0, 0)).await?, GuessResult::Hit));
+    let (b, res) = waiting.await?;
+    assert!(matches!(res?, GuessResult::Miss));
+
+    drop(a);
+    assert_eq!(referee.await??, RefereeOutcome::Abandoned { by: Role::Initiator });
+    drop(b);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn player_leaving_early_abandons_the_game() -> anyhow::Result<()> {
+    let (mut node, mut a, b) = referee();
+    let referee = tokio::spawn(async move { node.run().await });
+
+    a.place_fleet(common::stacked_placements()).await?.unwrap();
+    drop(b);
+    assert_eq!(referee.await??, RefereeOutcome::Abandoned { by: Role::Responder });
+    drop(a);
+    Ok(())
+}
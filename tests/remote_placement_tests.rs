@@ -0,0 +1,61 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    domain::Placement,
+    protocol::{validation::validate_fleet, GameApi},
+    transport::in_memory::InMemoryTransport,
+    GameEngine, Orientation, RuleSet, Skeleton, Stub,
+};
+
+mod common;
+
+fn placement(ship: &str, row: u8, col: u8) -> Placement {
+    Placement {
+        ship: ship.to_string(),
+        row,
+        col,
+        orientation: Orientation::Horizontal,
+    }
+}
+
+#[test]
+fn validate_fleet_lists_every_problem() {
+    let board = validate_fleet(&RuleSet::STANDARD, &common::stacked_placements()).unwrap();
+    assert!(board.all_placed());
+
+    let mut bad = common::stacked_placements();
+    bad[1] = placement("Battleship", 0, 3); // overlaps the Carrier
+    bad[2] = placement("Cruiser", 9, 8); // runs off the board
+    bad[3] = placement("Carrier", 7, 0); // second Carrier, Submarine missing
+    bad.push(placement("Dinghy", 8, 0));
+    let reasons = validate_fleet(&RuleSet::STANDARD, &bad).unwrap_err();
+    assert_eq!(reasons.len(), 5, "{:?}", reasons);
+    for expected in ["Battleship", "Cruiser", "more than once", "Submarine is missing", "Dinghy"] {
+        assert!(reasons.iter().any(|r| r.contains(expected)), "no reason mentions {}: {:?}", expected, reasons);
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn thin_client_places_fleet_on_server_engine() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(GameEngine::new(), server_transport);
+        skeleton.run().await?;
+        anyhow::Ok(skeleton.engine().board().ship_map())
+    });
+
+    let mut stub = Stub::new(client_transport);
+    let mut overlapping = common::stacked_placements();
+    overlapping[4] = placement("Destroyer", 0, 0);
+    let reasons = stub.place_fleet(overlapping).await?.unwrap_err();
+    assert_eq!(reasons.len(), 1);
+
+    stub.place_fleet(common::stacked_placements()).await?.unwrap();
+    let again = stub.place_fleet(common::stacked_placements()).await?.unwrap_err();
+    assert_eq!(again, vec!["fleet is already placed".to_string()]);
+
+    drop(stub);
+    let ship_map = server.await??;
+    assert_eq!(ship_map, validate_fleet(&RuleSet::STANDARD, &common::stacked_placements()).unwrap().ship_map());
+    Ok(())
+}
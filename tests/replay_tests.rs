@@ -0,0 +1,102 @@
+#![cfg(feature = "std")]
+
+use battleship::{
+    core::replay::{self, notation, parse_notation},
+    transport::in_memory::InMemoryTransport,
+    AiPlayer, GameEngine, GameStatus, GuessResult, HeartbeatTransport, PlayerNode, Replay, Role,
+};
+use rand::{rngs::SmallRng, SeedableRng};
+
+mod common;
+
+#[test]
+fn notation_matches_cli_coordinates() {
+    assert_eq!(notation(0, 0), "A1");
+    assert_eq!(notation(9, 9), "J10");
+    assert_eq!(parse_notation("b5").unwrap(), (4, 1));
+    assert_eq!(parse_notation("J10").unwrap(), (9, 9));
+    for bad in ["", "5", "A0", "A13", "M1", "AA"] {
+        assert!(parse_notation(bad).is_err(), "{:?} should be rejected", bad);
+    }
+}
+
+#[test]
+fn frames_recompute_results() {
+    let fleet = common::stacked_fleet();
+    let replay = Replay::new([&fleet, &fleet]).with_shots(0, [vec![(4, 0), (4, 1)], vec![(9, 9)]]);
+    let targets: Vec<_> = replay.moves.iter().map(|m| (m.player, m.target.as_str())).collect();
+    assert_eq!(targets, vec![(0, "A5"), (1, "J10"), (0, "B5")]);
+
+    let start = replay.frame(0).unwrap();
+    assert!(start.last.is_none());
+    assert_eq!(start.next, Some((0, (4, 0))));
+
+    let after_hit = replay.frame(1).unwrap();
+    assert_eq!(after_hit.last, Some((0, (4, 0), GuessResult::Hit)));
+    assert_eq!(after_hit.engines[1].board().hits().count_ones(), 1);
+    assert_eq!(after_hit.engines[0].guess_hits().count_ones(), 1);
+
+    let end = replay.frame(3).unwrap();
+    assert_eq!(end.last, Some((0, (4, 1), GuessResult::Sink("Destroyer"))));
+    assert_eq!(end.engines[0].guess_misses().count_ones(), 0);
+    assert_eq!(end.engines[1].guess_misses().count_ones(), 1);
+    assert!(end.next.is_none());
+    assert!(replay.frame(4).is_err());
+}
+
+#[test]
+fn json_round_trip_and_validation() {
+    let fleet = common::stacked_fleet();
+    let replay = Replay::new([&fleet, &fleet]).with_shots(1, [vec![(0, 0)], vec![(0, 0)]]);
+    let json = replay.to_json().unwrap();
+    assert_eq!(Replay::from_json(&json).unwrap(), replay);
+
+    let mut repeated = replay.clone();
+    repeated.push(1, 0, 0);
+    assert!(Replay::from_json(&repeated.to_json().unwrap()).is_err());
+
+    let mut bad_version = replay.clone();
+    bad_version.version += 1;
+    assert!(Replay::from_json(&bad_version.to_json().unwrap()).is_err());
+
+    let mut overlapping = replay.clone();
+    overlapping.fleets[0][1].row = 0;
+    assert!(Replay::from_json(&overlapping.to_json().unwrap()).is_err());
+
+    let mut bad_player = replay;
+    bad_player.moves[0].player = 2;
+    assert!(Replay::from_json(&bad_player.to_json().unwrap()).is_err());
+}
+
+#[tokio::test]
+async fn recorded_ai_game_replays_to_same_result() -> anyhow::Result<()> {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut node1 = PlayerNode::new(
+        Box::new(AiPlayer::new()),
+        GameEngine::new(),
+        Box::new(HeartbeatTransport::disabled(t1)),
+    );
+    let mut node2 = PlayerNode::new(
+        Box::new(AiPlayer::new()),
+        GameEngine::new(),
+        Box::new(HeartbeatTransport::disabled(t2)),
+    );
+    let shots1 = tokio::spawn(replay::shots_fired(node1.subscribe()));
+    let shots2 = tokio::spawn(replay::shots_fired(node2.subscribe()));
+    let mut rng1 = SmallRng::seed_from_u64(5);
+    let mut rng2 = SmallRng::seed_from_u64(6);
+    tokio::try_join!(node1.run(&mut rng1, Role::Initiator), node2.run(&mut rng2, Role::Responder))?;
+
+    let first = if node1.first_move() == Some(true) { 0 } else { 1 };
+    let statuses = [node1.status(), node2.status()];
+    let counts = [node1.guess_count(), node2.guess_count()];
+    let recording = Replay::new([node1.engine().board(), node2.engine().board()]);
+    drop((node1, node2));
+    let recording = recording.with_shots(first, [shots1.await??, shots2.await??]);
+
+    assert_eq!(recording.len(), counts[0] + counts[1]);
+    let end = recording.frame(recording.len())?;
+    assert_eq!([end.engines[0].status(), end.engines[1].status()], statuses);
+    assert!(statuses.contains(&GameStatus::Won));
+    Ok(())
+}
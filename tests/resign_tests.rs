@@ -0,0 +1,121 @@
+#![cfg(feature = "std")]
+
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{
+    domain, AiPlayer, BoardBits, Board, BoardError, Coord, GameApi, GameEngine, GameEvent, GameStatus, Player, PlayerNode, Role,
+    Skeleton, Stub, BOARD_SIZE,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+type BB = BoardBits;
+
+/// An AI that concedes when asked for its `after`-th target.
+struct Quitter {
+    ai: AiPlayer,
+    after: usize,
+    targets: usize,
+}
+
+impl Player for Quitter {
+    fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError> {
+        self.ai.place_ships(rng, board)
+    }
+
+    fn select_target(
+        &mut self,
+        rng: &mut SmallRng,
+        hits: &BB,
+        misses: &BB,
+        remaining: &[usize],
+    ) -> Coord {
+        self.targets += 1;
+        self.ai.select_target(rng, hits, misses, remaining)
+    }
+
+    fn resigned(&self) -> bool {
+        self.targets >= self.after
+    }
+}
+
+#[test]
+fn test_resignation_decides_the_game_once() {
+    let mut engine = common::stacked_engine();
+    engine.resign();
+    assert_eq!(engine.status(), GameStatus::Forfeited);
+    engine.record_resignation();
+    assert_eq!(engine.status(), GameStatus::Forfeited);
+    engine.abort();
+    assert_eq!(engine.status(), GameStatus::Aborted);
+
+    let mut engine = common::stacked_engine();
+    engine.record_resignation();
+    assert_eq!(engine.status(), GameStatus::Won);
+
+    // A lost game stays lost
+    let mut engine = GameEngine::new();
+    let mut rng = SmallRng::seed_from_u64(3);
+    engine.board_mut().place_all_random(&mut rng).unwrap();
+    for r in 0..BOARD_SIZE as usize {
+        for c in 0..BOARD_SIZE as usize {
+            if engine.status() == GameStatus::InProgress {
+                engine.opponent_guess(r, c).unwrap();
+            }
+        }
+    }
+    engine.resign();
+    assert_eq!(engine.status(), GameStatus::Lost);
+}
+
+#[tokio::test]
+async fn test_player_node_resignation_ends_both_games() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut rng1 = SmallRng::seed_from_u64(3506);
+    let mut rng2 = SmallRng::seed_from_u64(3507);
+    let quitter = Quitter {
+        ai: AiPlayer::new(),
+        after: 4,
+        targets: 0,
+    };
+    let mut node1 = PlayerNode::new(Box::new(quitter), GameEngine::new(), Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(t2));
+    let mut rx1 = node1.subscribe();
+    let mut rx2 = node2.subscribe();
+    let (r1, r2) = tokio::join!(node1.run(&mut rng1, Role::Initiator), node2.run(&mut rng2, Role::Responder));
+    r1.unwrap();
+    r2.unwrap();
+
+    assert_eq!(node1.status(), GameStatus::Forfeited);
+    assert_eq!(node2.status(), GameStatus::Won);
+    assert_eq!(node1.guess_count(), 3);
+
+    let last = |rx: &mut tokio::sync::broadcast::Receiver<GameEvent>| {
+        let mut last = None;
+        while let Ok(event) = rx.try_recv() {
+            last = Some(event);
+        }
+        last
+    };
+    assert!(matches!(last(&mut rx1), Some(GameEvent::GameOver { status: GameStatus::Forfeited })));
+    assert!(matches!(last(&mut rx2), Some(GameEvent::GameOver { status: GameStatus::Won })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stub_concedes_to_skeleton() -> anyhow::Result<()> {
+    let (client, server) = InMemoryTransport::pair();
+    let task = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(common::stacked_engine(), server);
+        skeleton.run().await.map(|_| skeleton.engine().status())
+    });
+
+    let mut stub = Stub::new(client);
+    stub.make_guess(Coord::new(0, 0)).await?;
+    stub.concede().await?;
+    assert!(matches!(stub.status(), domain::GameStatus::Won));
+    drop(stub);
+
+    assert_eq!(task.await??, GameStatus::Won);
+    Ok(())
+}
@@ -0,0 +1,139 @@
+#![cfg(feature = "std")]
+
+use battleship::domain::{GameConfig, GuessResult as DomainGuessResult};
+use battleship::protocol::validation::{validate_salvo, ProtocolViolation};
+use battleship::protocol::{setup, GameApi};
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::PROTOCOL_VERSION;
+use battleship::{
+    AiPlayer, FleetConfig, GameEngine, GameStatus, GuessResult, Orientation, Player, PlayerNode, Role, RuleSet,
+    NUM_SHIPS, SHIPS,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use tokio::time::{Duration, Instant};
+
+mod common;
+
+fn salvo() -> RuleSet {
+    RuleSet::STANDARD.with_salvo(true)
+}
+
+#[test]
+fn test_shots_follow_ships_afloat() {
+    let mut engine = common::stack(GameEngine::new_with_config(salvo()));
+    assert_eq!(engine.shots_per_turn(), NUM_SHIPS);
+    assert_eq!(engine.opponent_shots_per_turn(), NUM_SHIPS);
+    assert_eq!(GameEngine::new().shots_per_turn(), 1);
+
+    let last = SHIPS[4].length();
+    for c in 0..last {
+        engine.opponent_guess(4, c).unwrap();
+    }
+    assert_eq!(engine.shots_per_turn(), NUM_SHIPS - 1);
+
+    engine.record_guess(9, 0, GuessResult::Hit).unwrap();
+    engine.record_guess(9, 1, GuessResult::Sink("Destroyer")).unwrap();
+    assert_eq!(engine.opponent_shots_per_turn(), NUM_SHIPS - 1);
+}
+
+#[test]
+fn test_shots_capped_by_open_cells() {
+    let rules = RuleSet::new(2, FleetConfig::empty().with_ships("Destroyer", 2, 2).unwrap())
+        .unwrap()
+        .with_salvo(true);
+    let mut engine = GameEngine::new_with_config(rules);
+    engine.board_mut().place(0, 0, 0, Orientation::Horizontal).unwrap();
+    engine.board_mut().place(1, 1, 0, Orientation::Horizontal).unwrap();
+    engine.record_guess(0, 0, GuessResult::Hit).unwrap();
+    engine.record_guess(0, 1, GuessResult::Sink("Destroyer")).unwrap();
+    engine.record_guess(1, 0, GuessResult::Hit).unwrap();
+    assert_eq!(engine.shots_per_turn(), 1);
+}
+
+#[test]
+fn test_validate_salvo_rejects_bad_volleys() {
+    let engine = common::stack(GameEngine::new_with_config(salvo()));
+    let volley = [(9, 0), (9, 1), (9, 2), (9, 3), (9, 4)];
+    assert_eq!(validate_salvo(&engine, &volley).unwrap().len(), NUM_SHIPS);
+    assert_eq!(
+        validate_salvo(&engine, &volley[..4]),
+        Err(ProtocolViolation::SalvoSize { expected: 5, got: 4 })
+    );
+    assert_eq!(
+        validate_salvo(&engine, &[(9, 0), (9, 1), (9, 2), (9, 3), (9, 1)]),
+        Err(ProtocolViolation::DuplicateShot { x: 9, y: 1 })
+    );
+    assert!(matches!(
+        validate_salvo(&engine, &[(9, 0), (9, 1), (9, 2), (9, 3), (10, 0)]),
+        Err(ProtocolViolation::CoordinateOutOfBounds { .. })
+    ));
+}
+
+#[tokio::test]
+async fn test_engine_stops_resolving_once_the_game_is_won() {
+    let rules = RuleSet::STANDARD
+        .with_fleet(FleetConfig::empty().with_ships("Destroyer", 2, 1).unwrap())
+        .unwrap()
+        .with_salvo(true);
+    let mut engine = GameEngine::new_with_config(rules);
+    engine.board_mut().place(0, 0, 0, Orientation::Horizontal).unwrap();
+
+    let results = engine.make_salvo(vec![(5, 5)]).await.unwrap();
+    assert!(matches!(results[..], [DomainGuessResult::Miss]));
+    engine.make_salvo(vec![(0, 0)]).await.unwrap();
+    let results = engine.make_salvo(vec![(0, 1)]).await.unwrap();
+    assert!(matches!(&results[..], [DomainGuessResult::Sink(name)] if name == "Destroyer"));
+    assert_eq!(GameEngine::status(&engine), GameStatus::Lost);
+    assert!(engine.make_salvo(vec![(9, 9)]).await.is_err());
+}
+
+#[test]
+fn test_salvo_survives_config_and_json() {
+    let config = GameConfig::from(&salvo());
+    assert!(config.salvo);
+    assert_eq!(config.rules(), Some(salvo()));
+    assert_ne!(config, GameConfig::local());
+
+    let json = common::stack(GameEngine::new_with_config(salvo())).to_json().unwrap();
+    assert!(json.contains("\"salvo\":true"));
+    assert_eq!(GameEngine::from_json(&json).unwrap().rules(), salvo());
+    assert!(!GameEngine::new().to_json().unwrap().contains("salvo"));
+}
+
+#[tokio::test]
+async fn test_player_nodes_play_salvo_game() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut rng1 = SmallRng::seed_from_u64(3503);
+    let mut rng2 = SmallRng::seed_from_u64(3504);
+    let mut e1 = GameEngine::new_with_config(salvo());
+    let mut e2 = GameEngine::new_with_config(salvo());
+    AiPlayer::new().place_ships(&mut rng1, e1.board_mut()).unwrap();
+    AiPlayer::new().place_ships(&mut rng2, e2.board_mut()).unwrap();
+
+    let mut node1 = PlayerNode::new(Box::new(AiPlayer::new()), e1, Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(AiPlayer::new()), e2, Box::new(t2));
+    let (r1, r2) = tokio::join!(node1.run(&mut rng1, Role::Initiator), node2.run(&mut rng2, Role::Responder));
+    r1.unwrap();
+    r2.unwrap();
+
+    let statuses = [node1.status(), node2.status()];
+    assert!(statuses.contains(&GameStatus::Won) && statuses.contains(&GameStatus::Lost));
+    let winner = if statuses[0] == GameStatus::Won { &node1 } else { &node2 };
+    assert!(winner.guess_count() >= battleship::TOTAL_SHIP_CELLS);
+}
+
+#[tokio::test]
+async fn test_handshake_rejects_salvo_mismatch() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+    let deadline = Instant::now() + Duration::from_secs(5);
+
+    let responder = tokio::spawn(async move {
+        setup::exchange_ready(&mut t2, PROTOCOL_VERSION, &GameConfig::local(), Role::Responder, false, deadline).await
+    });
+    setup::exchange_ready(&mut t1, PROTOCOL_VERSION, &GameConfig::from(&salvo()), Role::Initiator, true, deadline)
+        .await
+        .unwrap();
+    let err = responder.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch"));
+}
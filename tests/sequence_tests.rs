@@ -1,14 +1,14 @@
-use battleship::domain::{GameStatus, GuessResult, Ship, SyncPayload};
+use battleship::domain::{ErrorCode, GameStatus, GuessResult, RemoteError, Ship, SyncPayload};
 use battleship::protocol::GameApi;
 use battleship::transport::in_memory::InMemoryTransport;
 use battleship::transport::Transport;
-use battleship::{Message, Skeleton, PROTOCOL_VERSION};
+use battleship::{Coord, GameEngine, Message, Skeleton, Stub, PROTOCOL_VERSION};
 
 struct DummyEngine;
 
 #[async_trait::async_trait]
 impl GameApi for DummyEngine {
-    async fn make_guess(&mut self, _x: u8, _y: u8) -> anyhow::Result<GuessResult> {
+    async fn make_guess(&mut self, _target: Coord) -> anyhow::Result<GuessResult> {
         Ok(GuessResult::Hit)
     }
     async fn get_ship_status(&self, _ship_id: usize) -> anyhow::Result<Ship> {
@@ -16,6 +16,7 @@ impl GameApi for DummyEngine {
             name: "dummy".to_string(),
             sunk: false,
             position: None,
+            hit_segments: Vec::new(),
         })
     }
     async fn sync_state(&mut self, _payload: SyncPayload) -> anyhow::Result<()> {
@@ -40,8 +41,7 @@ async fn test_out_of_order_message() -> anyhow::Result<()> {
         .send(Message::Guess {
             version: PROTOCOL_VERSION,
             seq: 1,
-            x: 0,
-            y: 0,
+            target: Coord::new(0, 0),
         })
         .await?;
     match client_transport.recv().await? {
@@ -54,8 +54,7 @@ async fn test_out_of_order_message() -> anyhow::Result<()> {
         .send(Message::Guess {
             version: PROTOCOL_VERSION,
             seq: 0,
-            x: 0,
-            y: 0,
+            target: Coord::new(0, 0),
         })
         .await?;
     match client_transport.recv().await? {
@@ -84,8 +83,7 @@ async fn test_duplicate_message() -> anyhow::Result<()> {
         .send(Message::Guess {
             version: PROTOCOL_VERSION,
             seq: 0,
-            x: 0,
-            y: 0,
+            target: Coord::new(0, 0),
         })
         .await?;
     match client_transport.recv().await? {
@@ -98,8 +96,7 @@ async fn test_duplicate_message() -> anyhow::Result<()> {
         .send(Message::Guess {
             version: PROTOCOL_VERSION,
             seq: 0,
-            x: 1,
-            y: 1,
+            target: Coord::new(1, 1),
         })
         .await?;
     match client_transport.recv().await? {
@@ -111,3 +108,131 @@ async fn test_duplicate_message() -> anyhow::Result<()> {
     server.await.unwrap();
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stub_resyncs_after_refused_sequence() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move {
+        // Server already expects seq 5, e.g. after requests the client lost track of
+        let mut skeleton = Skeleton::new(DummyEngine, server_transport).resumed(5, None);
+        skeleton.run().await.unwrap();
+        skeleton.next_seq()
+    });
+
+    let mut stub = Stub::new(client_transport);
+    assert!(matches!(stub.make_guess(Coord::new(0, 0)).await?, GuessResult::Hit));
+    assert!(matches!(stub.make_guess(Coord::new(1, 1)).await?, GuessResult::Hit));
+
+    drop(stub);
+    // Resync took seq 1 (after refused 0), then two guesses at 2 and 3
+    assert_eq!(server.await.unwrap(), 4);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stub_uses_late_reply_found_while_resyncing() -> anyhow::Result<()> {
+    let (mut server, client_transport) = InMemoryTransport::pair();
+    let fake_server = tokio::spawn(async move {
+        assert!(matches!(server.recv().await?, Message::Guess { seq: 0, .. }));
+        // A reply from some later request overtakes the one for seq 0
+        server
+            .send(Message::StatusResp { version: PROTOCOL_VERSION, seq: 7, res: GuessResult::Miss, digest: None })
+            .await?;
+        let Message::Resync { seq, .. } = server.recv().await? else {
+            panic!("expected Resync");
+        };
+        server
+            .send(Message::StatusResp { version: PROTOCOL_VERSION, seq: 0, res: GuessResult::Hit, digest: None })
+            .await?;
+        server
+            .send(Message::ResyncAck { version: PROTOCOL_VERSION, seq, digest: None })
+            .await?;
+        // The guess must not be sent again
+        assert!(server.recv().await.is_err());
+        anyhow::Ok(())
+    });
+
+    let mut stub = Stub::new(client_transport);
+    assert!(matches!(stub.make_guess(Coord::new(0, 0)).await?, GuessResult::Hit));
+    drop(stub);
+    fake_server.await??;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_explicit_resync_realigns_numbering() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(DummyEngine, server_transport).resumed(9, None);
+        skeleton.run().await.unwrap();
+    });
+
+    let stub = Stub::new(client_transport);
+    stub.resync().await?;
+    let ship = stub.get_ship_status(0).await?;
+    assert_eq!(ship.name, "dummy");
+
+    let mut transport = stub.into_transport();
+    // The skeleton now expects seq 2: resync was 0, the ship request 1
+    transport
+        .send(Message::GameStatusReq { version: PROTOCOL_VERSION, seq: 2 })
+        .await?;
+    assert!(matches!(transport.recv().await?, Message::GameStatusResp { seq: 2, .. }));
+
+    drop(transport);
+    server.await.unwrap();
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stub_surfaces_skeleton_error_code() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(GameEngine::new(), server_transport);
+        skeleton.run().await
+    });
+
+    let mut stub = Stub::new(client_transport);
+    let err = stub.make_guess(Coord::new(12, 0)).await.unwrap_err();
+    let remote = err.downcast_ref::<RemoteError>().expect("RemoteError");
+    assert_eq!(remote.code, ErrorCode::InvalidCoordinate);
+    assert!(err.to_string().contains("closing session"), "{}", err);
+    assert!(server.await?.is_err());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stub_resyncs_on_recoverable_error() -> anyhow::Result<()> {
+    let (mut server, client_transport) = InMemoryTransport::pair();
+    let fake_server = tokio::spawn(async move {
+        let Message::Guess { seq, .. } = server.recv().await? else {
+            panic!("expected Guess");
+        };
+        server
+            .send(Message::Error {
+                version: PROTOCOL_VERSION,
+                seq,
+                code: ErrorCode::BadSeq,
+                detail: "expected seq 3".to_string(),
+            })
+            .await?;
+        let Message::Resync { seq, .. } = server.recv().await? else {
+            panic!("expected Resync");
+        };
+        server
+            .send(Message::ResyncAck { version: PROTOCOL_VERSION, seq, digest: None })
+            .await?;
+        let Message::Guess { seq, .. } = server.recv().await? else {
+            panic!("expected retried Guess");
+        };
+        server
+            .send(Message::StatusResp { version: PROTOCOL_VERSION, seq, res: GuessResult::Miss, digest: None })
+            .await?;
+        anyhow::Ok(())
+    });
+
+    let mut stub = Stub::new(client_transport);
+    assert!(matches!(stub.make_guess(Coord::new(0, 0)).await?, GuessResult::Miss));
+    fake_server.await??;
+    Ok(())
+}
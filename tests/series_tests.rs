@@ -0,0 +1,66 @@
+#![cfg(feature = "std")]
+
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{AiPlayer, GameEngine, GameStatus, GuessResult, PlayerNode, Role, RuleSet, SeriesResult};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+/// Play a best-of-`games` series between two AIs and return each side's
+/// result.
+async fn series(rules: RuleSet, games: u32, seed: u64) -> (SeriesResult, SeriesResult) {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut rng1 = SmallRng::seed_from_u64(seed);
+    let mut rng2 = SmallRng::seed_from_u64(seed + 1);
+    let mut node1 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new_with_config(rules), Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new_with_config(rules), Box::new(t2));
+    let (r1, r2) = tokio::join!(
+        node1.run_series(&mut rng1, Role::Initiator, games),
+        node2.run_series(&mut rng2, Role::Responder, games)
+    );
+    (r1.unwrap(), r2.unwrap())
+}
+
+#[test]
+fn test_reset_for_rematch_keeps_only_the_rules() {
+    let rules = RuleSet::STANDARD.with_salvo(true).with_move_limit(Some(40));
+    let mut engine = GameEngine::new_with_config(rules);
+    let mut rng = SmallRng::seed_from_u64(3517);
+    engine.board_mut().place_all_random(&mut rng).unwrap();
+    engine.record_guess(0, 0, GuessResult::Hit).unwrap();
+    engine.opponent_guess(9, 9).unwrap();
+    engine.resign();
+
+    engine.reset_for_rematch();
+    assert_eq!(engine.rules(), rules);
+    assert_eq!(engine.status(), GameStatus::InProgress);
+    assert!(!engine.board().all_placed());
+    assert!(engine.history().is_empty());
+    assert_eq!(engine.stats().shots(), 0);
+    assert_eq!(engine.guess_hits().count_ones(), 0);
+}
+
+#[tokio::test]
+async fn test_best_of_three_ends_at_a_majority() {
+    for seed in [1, 2] {
+        let (ours, theirs) = series(RuleSet::STANDARD, 3, seed).await;
+        assert!(ours.games.len() == 2 || ours.games.len() == 3, "{:?}", ours);
+        assert_eq!(ours.games.len(), theirs.games.len());
+        assert_eq!((ours.wins(), ours.losses()), (theirs.losses(), theirs.wins()));
+        assert_eq!(ours.wins().max(ours.losses()), 2);
+        let outcomes = [ours.status(), theirs.status()];
+        assert!(
+            outcomes == [GameStatus::Won, GameStatus::Lost] || outcomes == [GameStatus::Lost, GameStatus::Won],
+            "{:?}",
+            outcomes
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_drawn_games_play_the_series_out() {
+    let rules = RuleSet::STANDARD.with_move_limit(Some(5));
+    let (ours, theirs) = series(rules, 3, 3517).await;
+    assert_eq!(ours.games, [GameStatus::Draw; 3]);
+    assert_eq!(theirs.games, [GameStatus::Draw; 3]);
+    assert_eq!((ours.draws(), ours.status()), (3, GameStatus::Draw));
+}
@@ -0,0 +1,131 @@
+#![cfg(feature = "std")]
+
+use battleship::domain::state_digest;
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{BoardBits, DeltaError, GameEngine, GuessResult, Skeleton, Stub};
+
+mod common;
+
+type BB = BoardBits;
+
+fn cell(row: usize, col: usize) -> BB {
+    let mut bb = BB::new();
+    bb.set(row, col).unwrap();
+    bb
+}
+
+#[test]
+fn test_delta_brings_an_earlier_state_forward() {
+    let mut engine = common::stacked_engine();
+    engine.opponent_guess(9, 9).unwrap();
+    let base = engine.state();
+
+    engine.opponent_guess(4, 0).unwrap();
+    engine.opponent_guess(4, 1).unwrap();
+    engine.record_guess(7, 7, GuessResult::Hit).unwrap();
+    engine.record_guess(7, 8, GuessResult::Sink("Destroyer")).unwrap();
+    engine.record_guess(0, 0, GuessResult::Miss).unwrap();
+    let delta = engine.state().diff(&base);
+    assert_eq!(delta.base, base.digest());
+    assert_eq!(delta.shots(), 5);
+    assert!(engine.state().diff(&engine.state()).is_empty());
+
+    let mut behind = GameEngine::from_state(base);
+    behind.apply_delta(&delta).unwrap();
+    assert_eq!(behind.state(), engine.state());
+    assert_eq!(behind.enemy_cells_remaining(), engine.enemy_cells_remaining());
+    assert!(behind.board().ship_states()[4].sunk);
+}
+
+#[test]
+fn test_delta_keeps_history_and_stats() {
+    let opening = || {
+        let mut engine = common::stacked_engine();
+        engine.opponent_guess(9, 9).unwrap();
+        engine.record_guess(5, 5, GuessResult::Hit).unwrap();
+        engine.record_guess(5, 6, GuessResult::Hit).unwrap();
+        engine
+    };
+    let mut engine = opening();
+    let mut behind = opening();
+    let base = engine.state();
+
+    engine.opponent_guess(4, 0).unwrap();
+    engine.opponent_guess(4, 1).unwrap();
+    engine.record_guess(5, 7, GuessResult::Sink("Cruiser")).unwrap();
+    engine.record_guess(0, 0, GuessResult::Miss).unwrap();
+    let delta = engine.state().diff(&base);
+
+    behind.apply_delta(&delta).unwrap();
+    assert_eq!(behind.state(), engine.state());
+    assert_eq!(behind.history().len(), 3);
+    let stats = behind.stats();
+    assert_eq!((stats.hits, stats.misses, stats.ships_sunk), (3, 1, 1));
+    assert_eq!(stats.longest_hit_streak, 2);
+    assert_eq!(stats.turns_survived, 3);
+    let report = behind.defense_report();
+    assert_eq!(report.shots_received, 3);
+    assert!(report.ships[4].sunk_at.is_none());
+}
+
+#[test]
+fn test_delta_that_does_not_fit_leaves_the_engine_alone() {
+    let mut engine = common::stacked_engine();
+    engine.opponent_guess(0, 0).unwrap();
+    let base = engine.state();
+    let fresh = base.diff(&base);
+
+    let mut other = common::stacked_engine();
+    assert!(matches!(other.apply_delta(&fresh), Err(DeltaError::BaseMismatch { .. })));
+
+    let cases = [
+        (battleship::StateDelta { hits: cell(0, 0), ..fresh }, DeltaError::ConflictingShots),
+        (
+            battleship::StateDelta {
+                guess_hits: cell(3, 3),
+                guess_misses: cell(3, 3),
+                ..fresh
+            },
+            DeltaError::ConflictingShots,
+        ),
+        (battleship::StateDelta { hits: cell(9, 9), ..fresh }, DeltaError::WrongResult),
+        (battleship::StateDelta { misses: cell(1, 0), ..fresh }, DeltaError::WrongResult),
+    ];
+    for (delta, error) in cases {
+        assert_eq!(engine.apply_delta(&delta), Err(error));
+        assert_eq!(engine.state(), base);
+    }
+
+    let mut sunk = base;
+    sunk.enemy_ships_remaining[4] = false;
+    let mut behind = GameEngine::from_state(sunk);
+    let refloat = base.diff(&sunk);
+    assert_eq!(behind.apply_delta(&refloat), Err(DeltaError::InvalidSinks));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stub_resyncs_skeleton_by_delta() -> anyhow::Result<()> {
+    let mut source = common::stacked_engine();
+    source.opponent_guess(9, 9).unwrap();
+    let base = source.state();
+    let (server_transport, client_transport) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(GameEngine::from_state(base), server_transport);
+        skeleton.run().await?;
+        anyhow::Ok(skeleton.engine().state())
+    });
+
+    source.opponent_guess(4, 0).unwrap();
+    source.record_guess(6, 6, GuessResult::Miss).unwrap();
+    let stub = Stub::new(client_transport);
+    stub.resync_delta(&base, &source.state()).await?;
+    // A delta against a state the server no longer holds is refused
+    assert!(stub.resync_delta(&base, &source.state()).await.is_err());
+
+    let err = server.await?.unwrap_err();
+    assert!(err.to_string().contains("closing session"), "{}", err);
+    let mut expected = GameEngine::from_state(base);
+    expected.apply_delta(&source.state().diff(&base)).unwrap();
+    assert_eq!(state_digest(&expected.state()), state_digest(&source.state()));
+    Ok(())
+}
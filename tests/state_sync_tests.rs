@@ -1,9 +1,14 @@
 use battleship::protocol::GameApi;
 use battleship::{
-    BitBoard, BoardState, GameEngine, GameState, GuessBoardState, ShipState,
-    BOARD_SIZE, NUM_SHIPS, TOTAL_SHIP_CELLS,
+    Board, BoardBits, BoardState, Coord, GameEngine, LayoutError, RuleSet, GameState, GuessBoardState,
+    MAX_SHIPS, NUM_SHIPS, TOTAL_SHIP_CELLS,
 };
-use battleship::domain::SyncPayload;
+use battleship::domain::{state_digest, GuessResult, SyncPayload};
+use battleship::protocol::validation::{self, ProtocolViolation};
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{Skeleton, Stub};
+
+mod common;
 
 #[tokio::test]
 async fn test_sync_state_restores_enemy_ships_remaining() {
@@ -48,26 +53,23 @@ async fn test_sync_state_restores_enemy_ships_remaining() {
 #[tokio::test]
 async fn test_from_state_preserves_enemy_ships_remaining() {
     // Create a game state with mixed ship status
-    let mut enemy_ships = [true; NUM_SHIPS as usize];
+    let mut enemy_ships = [false; MAX_SHIPS];
+    enemy_ships[..NUM_SHIPS].fill(true);
     enemy_ships[1] = false; // Battleship sunk
     enemy_ships[3] = false; // Submarine sunk
     
     let state = GameState {
         my_board: BoardState {
-            ship_states: [
-                ShipState::new("Carrier"),
-                ShipState::new("Battleship"),
-                ShipState::new("Cruiser"),
-                ShipState::new("Submarine"),
-                ShipState::new("Destroyer"),
-            ],
-            ship_map: BitBoard::<u128, { BOARD_SIZE as usize }>::new(),
-            hits: BitBoard::<u128, { BOARD_SIZE as usize }>::new(),
-            misses: BitBoard::<u128, { BOARD_SIZE as usize }>::new(),
+            size: 10,
+            ship_states: Board::new().ship_states(),
+            ship_map: BoardBits::new(),
+            hits: BoardBits::new(),
+            misses: BoardBits::new(),
         },
         my_guesses: GuessBoardState {
-            hits: BitBoard::<u128, { BOARD_SIZE as usize }>::new(),
-            misses: BitBoard::<u128, { BOARD_SIZE as usize }>::new(),
+            // The seven hits that sank them
+            hits: BoardBits::try_from_cells((0..7).map(|c| (0, c))).unwrap(),
+            misses: BoardBits::new(),
         },
         enemy_ships_remaining: enemy_ships,
         enemy_remaining: TOTAL_SHIP_CELLS - 7, // Battleship (4) + Submarine (3) = 7 cells
@@ -127,3 +129,84 @@ async fn test_sync_state_full_roundtrip() {
     let lengths2 = engine2.enemy_ship_lengths_remaining();
     assert_eq!(lengths1, lengths2);
 }
+
+fn payload_of(engine: &GameEngine) -> SyncPayload {
+    let state = engine.state();
+    SyncPayload {
+        game_state: state,
+        enemy_ships_remaining: state.enemy_ships_remaining,
+    }
+}
+
+#[tokio::test]
+async fn test_sync_state_rejects_impossible_states() {
+    let mut source = common::stacked_engine();
+    source.opponent_guess(0, 0).unwrap();
+    source.record_guess(5, 5, battleship::GuessResult::Hit).unwrap();
+    assert!(validation::validate_sync(&RuleSet::STANDARD, &payload_of(&source)).is_ok());
+
+    let mut summary = payload_of(&source);
+    summary.enemy_ships_remaining[0] = false;
+    let mut overlap = payload_of(&source);
+    overlap.game_state.my_board.ship_states[1].position = Some((0, 0, battleship::Orientation::Horizontal));
+    let mut counter = payload_of(&source);
+    counter.game_state.enemy_remaining += 1;
+    let mut both = payload_of(&source);
+    both.game_state.my_guesses.misses.set(5, 5).unwrap();
+    let mut stray_hit = payload_of(&source);
+    stray_hit.game_state.my_board.hits.set(9, 9).unwrap();
+
+    assert_eq!(
+        validation::validate_sync(&RuleSet::STANDARD, &overlap),
+        Err(ProtocolViolation::InvalidLayout(LayoutError::Overlap { ship: 1 }))
+    );
+    for bad in [summary, overlap, counter, both, stray_hit] {
+        assert!(matches!(
+            validation::validate_sync(&RuleSet::STANDARD, &bad),
+            Err(ProtocolViolation::InvalidSync(_) | ProtocolViolation::InvalidLayout(_))
+        ));
+        let mut engine = GameEngine::new();
+        assert!(engine.sync_state(bad).await.is_err());
+        assert_eq!(engine.state(), GameEngine::new().state());
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resync_rebuilds_server_engine_and_reports_digest() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move {
+        let mut skeleton = Skeleton::new(GameEngine::new(), server_transport).resumed(4, None);
+        skeleton.run().await?;
+        anyhow::Ok((skeleton.engine().state(), skeleton.next_seq()))
+    });
+
+    let mut source = common::stacked_engine();
+    source.opponent_guess(4, 0).unwrap();
+    let payload = payload_of(&source);
+    let mut stub = Stub::new(client_transport);
+    stub.resync_state(payload.clone()).await?;
+    // The rebuilt engine already has the Destroyer hit once; finish it off
+    assert!(matches!(stub.make_guess(Coord::new(4, 1)).await?, GuessResult::Sink(ref s) if s == "Destroyer"));
+
+    drop(stub);
+    let (state, next_seq) = server.await??;
+    assert_eq!(next_seq, 2);
+    assert_eq!(state.my_board.hits.count_ones(), 2);
+    assert_eq!(state.my_board.ship_map, payload.game_state.my_board.ship_map);
+    assert_ne!(state_digest(&state), state_digest(&payload.game_state));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_resync_with_invalid_payload_closes_session() -> anyhow::Result<()> {
+    let (server_transport, client_transport) = InMemoryTransport::pair();
+    let server = tokio::spawn(async move { Skeleton::new(GameEngine::new(), server_transport).run().await });
+
+    let mut payload = payload_of(&common::stacked_engine());
+    payload.game_state.enemy_remaining = 0;
+    let stub = Stub::new(client_transport);
+    assert!(stub.resync_state(payload).await.is_err());
+    let err = server.await?.unwrap_err();
+    assert!(err.to_string().contains("closing session"), "{}", err);
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use battleship::{GameEngine, GuessResult};
+
+mod common;
+
+#[test]
+fn test_stats_track_our_fire() {
+    let mut engine = common::stacked_engine();
+    assert_eq!(engine.stats().accuracy_percent(), 0);
+    assert_eq!(engine.stats().shots_per_sink(), None);
+
+    engine.record_guess(0, 0, GuessResult::Hit).unwrap();
+    engine.record_guess(0, 1, GuessResult::Hit).unwrap();
+    engine.record_guess(0, 2, GuessResult::Miss).unwrap();
+    engine.record_guess(5, 0, GuessResult::Hit).unwrap();
+    engine.record_guess(5, 1, GuessResult::Sink("Destroyer")).unwrap();
+    engine.record_guess(9, 9, GuessResult::Miss).unwrap();
+    assert!(engine.record_guess(9, 9, GuessResult::Miss).is_err());
+
+    let stats = engine.stats();
+    assert_eq!((stats.hits, stats.misses, stats.shots()), (4, 2, 6));
+    assert_eq!(stats.accuracy_percent(), 67);
+    assert_eq!(stats.hit_streak, 0);
+    assert_eq!(stats.longest_hit_streak, 2);
+    assert_eq!(stats.ships_sunk, 1);
+    assert_eq!(stats.shots_per_sink(), Some(6.0));
+}
+
+#[test]
+fn test_turns_survived_stop_when_the_fleet_sinks() {
+    let mut engine = common::stacked_engine();
+    engine.opponent_guess(9, 9).unwrap();
+    assert_eq!(engine.stats().turns_survived, 1);
+    for (i, ship) in engine.rules().fleet().ships().iter().enumerate() {
+        for c in 0..ship.length() {
+            engine.opponent_guess(i, c).unwrap();
+        }
+    }
+    let cells = engine.rules().fleet().total_cells();
+    assert_eq!(engine.stats().turns_survived, cells);
+
+    // A restored engine recounts its shots but not the streaks
+    engine.record_guess(3, 3, GuessResult::Hit).unwrap();
+    let restored = GameEngine::from_state(engine.state()).stats();
+    assert_eq!(restored.turns_survived, cells);
+    assert_eq!((restored.hits, restored.misses), (1, 0));
+    assert_eq!(restored.longest_hit_streak, 0);
+}
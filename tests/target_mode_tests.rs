@@ -0,0 +1,73 @@
+use battleship::{follow_up_targets, AiPlayer, BoardBits, Coord, GuessResult, Player};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+type BB = BoardBits;
+
+#[test]
+fn test_lone_hit_probes_its_neighbours() {
+    let hits = common::cells(&[(5, 5)]);
+    let misses = common::cells(&[(4, 5)]);
+    assert_eq!(follow_up_targets(&hits, &hits, &misses), common::cells(&[(5, 4), (5, 6), (6, 5)]));
+
+    let corner = common::cells(&[(0, 0)]);
+    assert_eq!(follow_up_targets(&corner, &corner, &BB::new()), common::cells(&[(0, 1), (1, 0)]));
+    assert!(follow_up_targets(&BB::new(), &hits, &misses).is_empty());
+}
+
+#[test]
+fn test_aligned_hits_lock_the_direction() {
+    let hits = common::cells(&[(5, 4), (5, 5)]);
+    assert_eq!(follow_up_targets(&hits, &hits, &BB::new()), common::cells(&[(5, 3), (5, 6)]));
+
+    let vertical = common::cells(&[(2, 7), (3, 7), (4, 7)]);
+    let misses = common::cells(&[(1, 7)]);
+    assert_eq!(follow_up_targets(&vertical, &vertical, &misses), common::cells(&[(5, 7)]));
+
+    // Boxed in at both ends: the hits belong to ships lying across the line
+    let misses = common::cells(&[(5, 3), (5, 6)]);
+    assert_eq!(
+        follow_up_targets(&hits, &hits, &misses),
+        common::cells(&[(4, 4), (4, 5), (6, 4), (6, 5)])
+    );
+}
+
+#[test]
+fn test_ai_player_finishes_what_it_hits() {
+    let mut player = AiPlayer::new();
+    let mut rng = SmallRng::seed_from_u64(4);
+    let lengths = [5, 4, 3, 3, 2];
+    let mut hits = BB::new();
+    let misses = BB::new();
+
+    hits.set(5, 5).unwrap();
+    player.handle_guess_result(Coord::new(5, 5), GuessResult::Hit);
+    for _ in 0..20 {
+        let target = player.select_target(&mut rng, &hits, &misses, &lengths);
+        assert!([(4, 5), (6, 5), (5, 4), (5, 6)].contains(&target.into()), "{}", target);
+    }
+
+    hits.set(5, 6).unwrap();
+    player.handle_guess_result(Coord::new(5, 6), GuessResult::Hit);
+    for _ in 0..20 {
+        let target = player.select_target(&mut rng, &hits, &misses, &lengths);
+        assert!([(5, 4), (5, 7)].contains(&target.into()), "{}", target);
+    }
+
+    hits.set(5, 7).unwrap();
+    player.handle_guess_result(Coord::new(5, 7), GuessResult::Sink("Cruiser"));
+    assert!(player.opponent_model().unresolved_hits().is_empty());
+    assert_eq!(player.opponent_model().ship_cells(2), Some(common::cells(&[(5, 5), (5, 6), (5, 7)])));
+}
+
+#[test]
+fn test_ai_player_forgets_hits_from_the_last_game() {
+    let mut player = AiPlayer::new();
+    let mut rng = SmallRng::seed_from_u64(6);
+    player.handle_guess_result(Coord::new(5, 5), GuessResult::Hit);
+    // A fresh board: hunting, not chasing the old hit
+    let target = player.select_target(&mut rng, &BB::new(), &BB::new(), &[5, 4, 3, 3, 2]);
+    assert!(player.opponent_model().unresolved_hits().is_empty(), "{}", target);
+}
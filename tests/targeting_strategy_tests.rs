@@ -0,0 +1,90 @@
+use battleship::{
+    calc_pdf_with_sunk, AiConfig, AiPlayer, BoardBits, ParityHunt, PlacementStyle, Player, RandomTargeting,
+    TargetingStrategy, TargetingStyle, MAX_BOARD_SIZE,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+mod common;
+
+type BB = BoardBits;
+
+const FLEET: [usize; 5] = [5, 4, 3, 3, 2];
+
+#[test]
+fn test_random_and_parity_spread_evenly() {
+    let mut rng = SmallRng::seed_from_u64(1);
+    let config = AiConfig::default();
+    let (hits, sunk) = (BB::new(), BB::new());
+    let misses = common::cells(&[(0, 0), (0, 2)]);
+
+    let random = RandomTargeting.density(&mut rng, &common::standard_view(&hits, &sunk, &misses, &FLEET), &config);
+    assert_eq!((random[0][0], random[0][1], random[9][9]), (0.0, 1.0, 1.0));
+    let cells = MAX_BOARD_SIZE as usize * MAX_BOARD_SIZE as usize;
+    assert_eq!(random.iter().flatten().sum::<f64>(), (cells - 2) as f64);
+
+    let parity = ParityHunt.density(&mut rng, &common::standard_view(&hits, &sunk, &misses, &FLEET), &config);
+    for (r, row) in parity.iter().enumerate() {
+        for (c, &p) in row.iter().enumerate() {
+            assert_eq!(p > 0.0, (r + c) % 2 == 0 && !misses.get(r, c).unwrap(), "({}, {})", r, c);
+        }
+    }
+
+    // A wounded ship could lie off the lattice
+    let wounded = common::cells(&[(4, 4)]);
+    let chasing = ParityHunt.density(&mut rng, &common::standard_view(&wounded, &sunk, &misses, &FLEET), &config);
+    assert_eq!((chasing[4][4], chasing[4][5]), (0.0, 1.0));
+}
+
+#[test]
+fn test_density_style_follows_the_config() {
+    let hits = common::cells(&[(5, 0), (5, 1), (7, 7)]);
+    let sunk = common::cells(&[(5, 0), (5, 1)]);
+    let misses = BB::new();
+    let remaining = [5, 4, 3, 3, 0];
+    let view = common::standard_view(&hits, &sunk, &misses, &remaining);
+
+    let config = AiConfig::default();
+    let mut rng = SmallRng::seed_from_u64(2);
+    assert_eq!(
+        TargetingStyle::Density.density(&mut rng, &view, &config),
+        calc_pdf_with_sunk(&hits, &sunk, &misses, &remaining, view.fleet, view.placement, &config)
+    );
+
+    let sampling = AiConfig { mc_samples: 50, ..AiConfig::default() };
+    let mut rng1 = SmallRng::seed_from_u64(3);
+    let mut rng2 = SmallRng::seed_from_u64(3);
+    let sampled = TargetingStyle::MonteCarlo.density(&mut rng1, &view, &sampling);
+    assert_eq!(TargetingStyle::Density.density(&mut rng2, &view, &sampling), sampled);
+    assert_eq!((sampled[5][0], sampled[5][2] > 0.0), (0.0, true));
+}
+
+/// Shots `player` needs to sink a fleet laid out with seed `seed`.
+fn shots_to_win(mut player: AiPlayer, seed: u64) -> usize {
+    let mut rng = SmallRng::seed_from_u64(seed);
+    let mut duel = common::Duel::new(&mut rng);
+    for shots in 1..=100 {
+        let (hits, _, misses, remaining) = duel.seen();
+        let guess = player.select_target(&mut rng, &hits, &misses, &remaining);
+        player.handle_guess_result(guess, duel.fire(guess.row, guess.col));
+        if duel.won() {
+            return shots;
+        }
+    }
+    panic!("fleet not sunk in 100 shots");
+}
+
+#[test]
+fn test_strategies_can_be_pitted_against_each_other() {
+    let player = AiPlayer::with_strategies(TargetingStyle::Random, PlacementStyle::Clustered);
+    assert_eq!((player.targeting(), player.placement()), (TargetingStyle::Random, PlacementStyle::Clustered));
+    assert_eq!(AiPlayer::new().targeting(), TargetingStyle::Density);
+
+    let total = |style| -> usize {
+        (0..5)
+            .map(|seed| shots_to_win(AiPlayer::with_strategies(style, PlacementStyle::default()), seed))
+            .sum()
+    };
+    assert!(total(TargetingStyle::Density) < total(TargetingStyle::Random));
+    total(TargetingStyle::ParityHunt);
+}
@@ -1,7 +1,7 @@
 #![cfg(feature = "std")]
 
 use battleship::{
-    transport::tcp::TcpTransport, AiPlayer, GameEngine, GameStatus, Player, PlayerNode,
+    transport::tcp::TcpTransport, AiPlayer, GameEngine, GameStatus, Player, PlayerNode, Role,
 };
 use rand::{rngs::SmallRng, SeedableRng};
 use tokio::net::TcpListener;
@@ -22,7 +22,7 @@ async fn test_ai_vs_ai_tcp_game() -> anyhow::Result<()> {
         player.place_ships(&mut rng, engine.board_mut()).unwrap();
 
         let mut server_node = PlayerNode::new(Box::new(player), engine, Box::new(transport));
-        server_node.run(&mut rng, true).await.unwrap();
+        server_node.run(&mut rng, Role::Initiator).await.unwrap();
         server_node.status()
     });
 
@@ -36,7 +36,7 @@ async fn test_ai_vs_ai_tcp_game() -> anyhow::Result<()> {
         player.place_ships(&mut rng, engine.board_mut()).unwrap();
 
         let mut client_node = PlayerNode::new(Box::new(player), engine, Box::new(transport));
-        client_node.run(&mut rng, false).await.unwrap();
+        client_node.run(&mut rng, Role::Responder).await.unwrap();
         client_node.status()
     });
 
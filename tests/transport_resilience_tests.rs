@@ -1,7 +1,7 @@
 /// Tests for transport resilience features.
 use battleship::{
     transport::{in_memory::InMemoryTransport, Transport},
-    TcpTransport, HeartbeatTransport,
+    Coord, TcpTransport, HeartbeatTransport,
     protocol::{Message, PROTOCOL_VERSION},
 };
 use tokio::net::TcpListener;
@@ -111,8 +111,7 @@ async fn test_tcp_bounded_message_size() {
         transport.send(Message::Guess {
             version: PROTOCOL_VERSION,
             seq: 12345,
-            x: 5,
-            y: 5,
+            target: Coord::new(5, 5),
         }).await.unwrap();
         
         // Wait for server to process
@@ -0,0 +1,179 @@
+#![cfg(feature = "std")]
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use battleship::domain::GameConfig;
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::{
+    AiPlayer, BoardBits, Board, BoardError, Coord, FleetConfig, GameEngine, GameEvent, GameStatus, Orientation, Player,
+    PlayerNode, Role, RuleSet, TimeoutAction, TurnClock,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+
+type BB = BoardBits;
+
+fn rules(limit_ms: u32, on_timeout: TimeoutAction) -> RuleSet {
+    RuleSet::STANDARD.with_turn_clock(Some(TurnClock::new(limit_ms, on_timeout)))
+}
+
+/// An AI that stalls before picking its `slow_pick`-th target, or every
+/// target when `slow_pick` is `None`.
+struct Sluggish {
+    ai: AiPlayer,
+    delay: Duration,
+    slow_pick: Option<usize>,
+    picks: usize,
+}
+
+impl Sluggish {
+    fn new(delay: Duration, slow_pick: Option<usize>) -> Self {
+        Sluggish {
+            ai: AiPlayer::new(),
+            delay,
+            slow_pick,
+            picks: 0,
+        }
+    }
+}
+
+impl Player for Sluggish {
+    fn place_ships(&mut self, rng: &mut SmallRng, board: &mut Board) -> Result<(), BoardError> {
+        self.ai.place_ships(rng, board)
+    }
+
+    fn select_target(
+        &mut self,
+        rng: &mut SmallRng,
+        hits: &BB,
+        misses: &BB,
+        remaining: &[usize],
+    ) -> Coord {
+        self.picks += 1;
+        if self.slow_pick.is_none_or(|n| n == self.picks) {
+            std::thread::sleep(self.delay);
+        }
+        self.ai.select_target(rng, hits, misses, remaining)
+    }
+}
+
+/// Play `slow` against a prompt AI and return both nodes with the events
+/// each published. Each node gets a thread and runtime of its own, so a
+/// stalling player cannot hold up the peer's clock.
+fn play(rules: RuleSet, slow: Sluggish, seed: u64) -> [(PlayerNode, Vec<GameEvent>); 2] {
+    let (t1, t2) = InMemoryTransport::pair();
+    let players: [Box<dyn Player>; 2] = [Box::new(slow), Box::new(AiPlayer::new())];
+    let threads: Vec<_> = players
+        .into_iter()
+        .zip([t1, t2])
+        .zip([(Role::Initiator, 0), (Role::Responder, 1)])
+        .map(|((player, transport), (role, offset))| {
+            std::thread::spawn(move || {
+                let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+                let mut node = PlayerNode::new(player, GameEngine::new_with_config(rules), Box::new(transport));
+                let mut rx = node.subscribe();
+                let mut rng = SmallRng::seed_from_u64(seed + offset);
+                let collect = async {
+                    let mut events = Vec::new();
+                    while let Ok(event) = rx.recv().await {
+                        events.push(event);
+                        if matches!(event, GameEvent::GameOver { .. }) {
+                            break;
+                        }
+                    }
+                    events
+                };
+                let (result, events) = runtime.block_on(async { tokio::join!(node.run(&mut rng, role), collect) });
+                result.unwrap();
+                (node, events)
+            })
+        })
+        .collect();
+    let mut nodes = threads.into_iter().map(|t| t.join().unwrap());
+    [nodes.next().unwrap(), nodes.next().unwrap()]
+}
+
+fn timeouts(events: &[GameEvent], ours: bool) -> usize {
+    events.iter().filter(|e| **e == GameEvent::TurnTimedOut { ours }).count()
+}
+
+#[test]
+fn test_turn_clock_survives_config_builder_and_json() {
+    let rules = rules(30_000, TimeoutAction::RandomMove);
+    assert_eq!(RuleSet::STANDARD.turn_clock(), None);
+    assert_eq!(rules.turn_clock().unwrap().limit(), Duration::from_secs(30));
+    assert_eq!(rules.with_fleet(FleetConfig::STANDARD).unwrap(), rules);
+
+    let config = GameConfig::from(&rules);
+    assert_eq!(config.rules(), Some(rules));
+    assert_ne!(config, GameConfig::local());
+
+    let engine = GameEngine::builder().turn_clock(rules.turn_clock()).build().unwrap();
+    assert_eq!(engine.turn_clock(), rules.turn_clock());
+
+    let json = engine.to_json().unwrap();
+    assert!(json.contains("\"turn_clock\":{\"limit_ms\":30000,\"on_timeout\":\"RandomMove\"}"));
+    assert_eq!(GameEngine::from_json(&json).unwrap().rules(), rules);
+    assert!(!GameEngine::new().to_json().unwrap().contains("turn_clock"));
+}
+
+#[test]
+fn test_play_for_opponent_fires_at_each_open_cell_once() {
+    let fleet = FleetConfig::empty().with_ships("Destroyer", 2, 1).unwrap();
+    let mut engine = GameEngine::new_with_config(RuleSet::new(4, fleet).unwrap());
+    engine.board_mut().place(0, 3, 2, Orientation::Horizontal).unwrap();
+    engine.opponent_guess(0, 0).unwrap();
+
+    let mut rng = SmallRng::seed_from_u64(3510);
+    let mut cells = HashSet::from([(0, 0)]);
+    while engine.status() == GameStatus::InProgress {
+        let (r, c, result) = engine.play_for_opponent(&mut rng).unwrap();
+        assert!(r < 4 && c < 4, "({}, {}) is off the board", r, c);
+        assert!(cells.insert((r, c)), "({}, {}) was fired at twice", r, c);
+        assert_eq!(engine.history().last().unwrap().result, result);
+    }
+    assert_eq!(engine.status(), GameStatus::Lost);
+    while cells.len() < 16 {
+        let (r, c, _) = engine.play_for_opponent(&mut rng).unwrap();
+        assert!(cells.insert((r, c)));
+    }
+    assert_eq!(engine.play_for_opponent(&mut rng), Err(BoardError::AlreadyGuessed));
+}
+
+#[test]
+fn test_slow_player_forfeits_on_time() {
+    let slow = Sluggish::new(Duration::from_millis(400), None);
+    let [(slow, slow_events), (prompt, prompt_events)] = play(rules(100, TimeoutAction::Forfeit), slow, 3510);
+
+    assert_eq!(slow.status(), GameStatus::Forfeited);
+    assert_eq!(prompt.status(), GameStatus::Won);
+    assert_eq!(timeouts(&slow_events, true), 1);
+    assert_eq!(timeouts(&prompt_events, false), 1);
+    assert_eq!(slow.guess_count(), 0);
+    assert_eq!(
+        slow_events.last(),
+        Some(&GameEvent::GameOver {
+            status: GameStatus::Forfeited
+        })
+    );
+    assert_eq!(prompt_events.last(), Some(&GameEvent::GameOver { status: GameStatus::Won }));
+}
+
+#[test]
+fn test_timed_out_turn_is_played_at_random() {
+    // The late shot reaches the peer after it played the turn and is dropped
+    let slow = Sluggish::new(Duration::from_millis(600), Some(2));
+    let [(slow, slow_events), (prompt, prompt_events)] =
+        play(rules(200, TimeoutAction::RandomMove), slow, 3511);
+
+    assert_eq!(timeouts(&slow_events, true), 1);
+    assert_eq!(timeouts(&prompt_events, false), 1);
+    let statuses = [slow.status(), prompt.status()];
+    assert!(
+        statuses == [GameStatus::Won, GameStatus::Lost] || statuses == [GameStatus::Lost, GameStatus::Won],
+        "{:?}",
+        statuses
+    );
+    assert_eq!(slow.engine().state().digest(), prompt.engine().state().mirrored_digest());
+}
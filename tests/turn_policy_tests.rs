@@ -0,0 +1,123 @@
+#![cfg(feature = "std")]
+
+use battleship::domain::GameConfig;
+use battleship::protocol::setup;
+use battleship::transport::in_memory::InMemoryTransport;
+use battleship::PROTOCOL_VERSION;
+use battleship::{
+    AiPlayer, GameEngine, GameEvent, GameStatus, GuessResult, Player, PlayerNode, Role, RuleSet, TimeoutAction,
+    TurnClock, TurnPolicy,
+};
+use rand::rngs::SmallRng;
+use rand::SeedableRng;
+use tokio::sync::broadcast;
+
+fn rules() -> RuleSet {
+    RuleSet::STANDARD.with_turn_policy(TurnPolicy::ContinueOnHit)
+}
+
+fn drain(rx: &mut broadcast::Receiver<GameEvent>) -> Vec<GameEvent> {
+    let mut events = Vec::new();
+    while let Ok(event) = rx.try_recv() {
+        events.push(event);
+    }
+    events
+}
+
+#[test]
+fn test_only_hits_keep_the_turn() {
+    assert_eq!(TurnPolicy::default(), TurnPolicy::Alternate);
+    assert!(!TurnPolicy::Alternate.keeps_turn(GuessResult::Hit));
+    assert!(TurnPolicy::ContinueOnHit.keeps_turn(GuessResult::Hit));
+    assert!(TurnPolicy::ContinueOnHit.keeps_turn(GuessResult::Sink("Carrier")));
+    assert!(!TurnPolicy::ContinueOnHit.keeps_turn(GuessResult::Miss));
+    assert_eq!(GameEngine::new_with_config(rules()).turn_policy(), TurnPolicy::ContinueOnHit);
+}
+
+#[tokio::test]
+async fn test_player_nodes_fire_again_after_a_hit() {
+    let (t1, t2) = InMemoryTransport::pair();
+    let mut rng1 = SmallRng::seed_from_u64(3505);
+    let mut rng2 = SmallRng::seed_from_u64(3506);
+    let mut e1 = GameEngine::new_with_config(rules());
+    let mut e2 = GameEngine::new_with_config(rules());
+    AiPlayer::new().place_ships(&mut rng1, e1.board_mut()).unwrap();
+    AiPlayer::new().place_ships(&mut rng2, e2.board_mut()).unwrap();
+
+    let mut node1 = PlayerNode::new(Box::new(AiPlayer::new()), e1, Box::new(t1));
+    let mut node2 = PlayerNode::new(Box::new(AiPlayer::new()), e2, Box::new(t2));
+    let mut rx = node1.subscribe();
+    let (r1, r2) = tokio::join!(node1.run(&mut rng1, Role::Initiator), node2.run(&mut rng2, Role::Responder));
+    r1.unwrap();
+    r2.unwrap();
+    assert!(matches!(node1.status(), GameStatus::Won | GameStatus::Lost));
+
+    // Each answered shot is followed by the next shot of whoever keeps the turn
+    let turns: Vec<_> = drain(&mut rx)
+        .into_iter()
+        .filter(|e| matches!(e, GameEvent::ResultReceived { .. } | GameEvent::OpponentGuess { .. }))
+        .collect();
+    let mut kept = 0;
+    for pair in turns.windows(2) {
+        let (ours, result) = match pair[0] {
+            GameEvent::ResultReceived { result, .. } => (true, result),
+            GameEvent::OpponentGuess { result, .. } => (false, result),
+            _ => unreachable!(),
+        };
+        let next_ours = matches!(pair[1], GameEvent::ResultReceived { .. });
+        let keeps = result != GuessResult::Miss;
+        assert_eq!(next_ours, ours == keeps, "turn after {:?}", pair[0]);
+        kept += usize::from(keeps);
+    }
+    assert!(kept > 0);
+}
+
+#[tokio::test]
+async fn test_handshake_rejects_turn_policy_mismatch() {
+    let (mut t1, mut t2) = InMemoryTransport::pair();
+
+    let responder = tokio::spawn(async move { setup::agree_config(&mut t2, PROTOCOL_VERSION, &GameConfig::local()).await });
+    let err = setup::agree_config(&mut t1, PROTOCOL_VERSION, &GameConfig::from(&rules())).await.unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch"));
+    let err = responder.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("Game config mismatch"));
+}
+
+#[tokio::test]
+async fn test_turn_rules_mismatch_parts_before_placement() {
+    let variants = [
+        rules(),
+        RuleSet::STANDARD.with_turn_clock(Some(TurnClock::new(5_000, TimeoutAction::Forfeit))),
+        RuleSet::STANDARD.with_move_limit(Some(40)),
+    ];
+    for theirs in variants {
+        let (t1, t2) = InMemoryTransport::pair();
+        let mut a = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new(), Box::new(t1));
+        let mut b = PlayerNode::new(Box::new(AiPlayer::new()), GameEngine::new_with_config(theirs), Box::new(t2));
+
+        let responder = tokio::spawn(async move {
+            let res = b.run(&mut SmallRng::seed_from_u64(2), Role::Responder).await;
+            (b, res)
+        });
+        let err = a.run(&mut SmallRng::seed_from_u64(1), Role::Initiator).await.unwrap_err();
+        assert!(err.to_string().contains("Game config mismatch in Rules"), "{:?}: {}", theirs, err);
+        let (b, res) = responder.await.unwrap();
+        assert!(res.unwrap_err().to_string().contains("Game config mismatch in Rules"));
+        for node in [&a, &b] {
+            assert_eq!(node.first_move(), None, "{:?}", theirs);
+            assert!(!node.engine().board().all_placed(), "{:?}", theirs);
+        }
+    }
+}
+
+#[test]
+fn test_turn_policy_survives_config_and_json() {
+    let config = GameConfig::from(&rules());
+    assert_eq!(config.turn_policy, TurnPolicy::ContinueOnHit);
+    assert_eq!(config.rules(), Some(rules()));
+
+    let json = GameEngine::new_with_config(rules()).to_json().unwrap();
+    assert!(json.contains("\"turn_policy\":\"ContinueOnHit\""));
+    assert_eq!(GameEngine::from_json(&json).unwrap().rules(), rules());
+    assert!(!GameEngine::new().to_json().unwrap().contains("turn_policy"));
+}